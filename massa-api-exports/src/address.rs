@@ -177,3 +177,37 @@ impl std::fmt::Display for AddressFilter {
         }
     }
 }
+
+/// Staking history of an address, as recorded by the node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressStakingHistory {
+    /// the address
+    pub address: Address,
+    /// staking activity per cycle, oldest first
+    pub cycles: Vec<StakingCycleHistory>,
+}
+
+/// Staking activity of an address during a cycle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StakingCycleHistory {
+    /// the cycle
+    pub cycle: u64,
+    /// number of blocks produced
+    pub block_success_count: u64,
+    /// number of blocks missed
+    pub block_failure_count: u64,
+    /// number of endorsements included in blocks
+    pub endorsement_success_count: u64,
+    /// number of endorsements missing from produced blocks
+    pub endorsement_failure_count: u64,
+    /// coins earned by producing blocks and endorsements
+    pub rewards: Amount,
+    /// number of rolls bought
+    pub rolls_bought: u64,
+    /// number of rolls sold, including the implicit sells caused by too many misses
+    pub rolls_sold: u64,
+    /// number of rolls slashed by denunciations
+    pub rolls_slashed: u64,
+    /// deferred credits paid to the address
+    pub deferred_credits_released: Amount,
+}
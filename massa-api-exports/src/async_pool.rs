@@ -0,0 +1,28 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::page::PageRequest;
+use massa_async_pool::{AsyncMessage, AsyncMessageStatus};
+use massa_models::address::Address;
+use serde::{Deserialize, Serialize};
+
+/// Asynchronous messages request, messages matching all the set filters are returned
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct AsyncMessagesRequest {
+    /// only the messages sent by this address
+    pub sender: Option<Address>,
+    /// only the messages sent to this address
+    pub destination: Option<Address>,
+    /// page of the matching messages to return, at most `max_arguments` messages per page
+    pub page_request: Option<PageRequest>,
+}
+
+/// Asynchronous message waiting in the candidate pool
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AsyncPoolMessage {
+    /// the message
+    pub message: AsyncMessage,
+    /// whether the message can be executed at the next slot, or why it can not
+    pub status: AsyncMessageStatus,
+    /// whether the message is also in the final pool
+    pub is_final: bool,
+}
@@ -1,7 +1,7 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_signature::KeyPair;
-use massa_time::MassaTime;
+use massa_time::{MassaTime, SharedClock};
 use std::net::SocketAddr;
 use std::path::PathBuf;
 
@@ -21,6 +21,8 @@ pub struct APIConfig {
     pub bind_api: SocketAddr,
     /// max argument count
     pub max_arguments: u64,
+    /// maximum number of slots returned by a draws lookahead request
+    pub max_draws_lookahead_count: u64,
     /// openrpc specification path
     pub openrpc_spec_path: PathBuf,
     /// bootstrap whitelist path
@@ -77,4 +79,7 @@ pub struct APIConfig {
     pub keypair: KeyPair,
     /// last_start_period value, used to know if we are during a restart or not
     pub last_start_period: u64,
+    /// clock giving the current slot, fast-forwarded on a devnet
+    #[serde(skip)]
+    pub clock: SharedClock,
 }
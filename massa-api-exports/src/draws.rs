@@ -0,0 +1,78 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::{address::Address, slot::Slot};
+use serde::{Deserialize, Serialize};
+
+/// Draws lookahead request
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DrawsLookaheadRequest {
+    /// addresses whose draws are looked for
+    pub addresses: Vec<Address>,
+    /// first slot to look at, the current slot if not set
+    pub start: Option<Slot>,
+    /// maximum number of slots returned, capped by the node `max_draws_lookahead_count` setting
+    pub count: Option<u64>,
+}
+
+/// Upcoming draws of a list of addresses, and their draw statistics
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DrawsLookahead {
+    /// cycles whose draws are available on the node
+    pub available_cycles: Vec<u64>,
+    /// next slots where at least one of the addresses is drawn
+    pub draws: Vec<SlotDraws>,
+    /// draw statistics of each address for each available cycle
+    pub stats: Vec<AddressDrawStats>,
+}
+
+/// Draws of the requested addresses at a slot
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SlotDraws {
+    /// the slot
+    pub slot: Slot,
+    /// block producer, if it is one of the requested addresses
+    pub block_producer: Option<Address>,
+    /// endorsements to be produced by the requested addresses
+    pub endorsements: Vec<EndorserDraw>,
+}
+
+/// An endorsement draw of one of the requested addresses
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EndorserDraw {
+    /// endorsement index in the block
+    pub index: usize,
+    /// endorsement producer
+    pub address: Address,
+}
+
+/// Expected and actual draws of an address during a cycle
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AddressDrawStats {
+    /// the address
+    pub address: Address,
+    /// the cycle
+    pub cycle: u64,
+    /// active rolls of the address used for the draws
+    pub rolls: u64,
+    /// total active rolls used for the draws
+    pub total_rolls: u64,
+    /// number of blocks the address was drawn to produce
+    pub block_draws: u64,
+    /// number of block draws expected from the active rolls
+    pub expected_block_draws: f64,
+    /// number of endorsements the address was drawn to produce
+    pub endorsement_draws: u64,
+    /// number of endorsement draws expected from the active rolls
+    pub expected_endorsement_draws: f64,
+}
+
+/// Notification of the availability of the draws of a new cycle
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NewCycleDraws {
+    /// cycle whose draws were computed
+    pub cycle: u64,
+    /// first slot of the cycle
+    pub first_slot: Slot,
+    /// last slot of the cycle
+    pub last_slot: Slot,
+}
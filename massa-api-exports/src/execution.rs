@@ -1,9 +1,16 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_final_state::StateChanges;
+use massa_hash::Hash;
+use massa_ledger_exports::{SetOrDelete, SetOrKeep, SetUpdateOrDelete};
 use massa_models::{address::Address, amount::Amount, output_event::SCOutputEvent, slot::Slot};
 use serde::{Deserialize, Serialize};
-use std::{collections::VecDeque, fmt::Display};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Display,
+};
+
+use crate::slot::SlotAmount;
 
 /// The result of the read-only execution.
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -27,6 +34,8 @@ pub struct ExecuteReadOnlyResponse {
     pub gas_cost: u64,
     /// state changes caused by the execution step
     pub state_changes: StateChanges,
+    /// readable view of the state changes, indexed by address
+    pub state_diff: ReadOnlyStateDiff,
 }
 
 impl Display for ExecuteReadOnlyResponse {
@@ -48,6 +57,421 @@ impl Display for ExecuteReadOnlyResponse {
                 writeln!(f, "{}", event)?; // id already displayed in event
             }
         }
+        if !self.state_diff.addresses.is_empty() {
+            writeln!(f, "State changes:")?;
+            write!(f, "{}", self.state_diff)?;
+        }
+        Ok(())
+    }
+}
+
+/// state changes of a read-only execution, indexed by the impacted addresses
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ReadOnlyStateDiff {
+    /// changes of each impacted address
+    pub addresses: BTreeMap<Address, AddressStateDiff>,
+}
+
+/// state changes impacting an address
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct AddressStateDiff {
+    /// the ledger entry of the address was created or entirely replaced
+    pub created: bool,
+    /// the ledger entry of the address was deleted
+    pub deleted: bool,
+    /// new balance, if changed
+    pub balance: Option<Amount>,
+    /// balance before the execution, if the ledger entry existed
+    pub previous_balance: Option<Amount>,
+    /// new bytecode, if changed
+    pub bytecode: Option<Vec<u8>>,
+    /// bytecode before the execution, if changed and if the ledger entry existed
+    pub previous_bytecode: Option<Vec<u8>>,
+    /// datastore entries written or deleted
+    pub datastore: Vec<DatastoreEntryDiff>,
+    /// new roll count, if changed
+    pub rolls: Option<u64>,
+    /// roll count before the execution, if changed
+    pub previous_rolls: Option<u64>,
+    /// deferred credits set for this address
+    pub deferred_credits: Vec<SlotAmount>,
+    /// asynchronous messages emitted by this address
+    pub emitted_async_messages: Vec<EmittedAsyncMessage>,
+}
+
+/// change of a datastore entry
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DatastoreEntryDiff {
+    /// datastore key
+    pub key: Vec<u8>,
+    /// new value, the entry was deleted if not set
+    pub value: Option<Vec<u8>>,
+    /// value before the execution, the entry did not exist if not set
+    pub previous_value: Option<Vec<u8>>,
+}
+
+/// asynchronous message emitted during a read-only execution
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EmittedAsyncMessage {
+    /// slot at which the message was emitted
+    pub emission_slot: Slot,
+    /// destination address
+    pub destination: Address,
+    /// function called on the destination
+    pub function: String,
+    /// parameters of the called function
+    pub function_params: Vec<u8>,
+    /// maximum gas of the execution
+    pub max_gas: u64,
+    /// fee paid for the execution
+    pub fee: Amount,
+    /// coins sent to the destination
+    pub coins: Amount,
+    /// first slot at which the message can be executed
+    pub validity_start: Slot,
+    /// last slot at which the message can be executed
+    pub validity_end: Slot,
+}
+
+/// The values before the execution are not part of the state changes and are left unset
+impl From<&StateChanges> for ReadOnlyStateDiff {
+    fn from(changes: &StateChanges) -> Self {
+        let mut addresses: BTreeMap<Address, AddressStateDiff> = BTreeMap::new();
+        for (address, change) in changes.ledger_changes.0.iter() {
+            let diff = addresses.entry(*address).or_default();
+            match change {
+                SetUpdateOrDelete::Set(entry) => {
+                    diff.created = true;
+                    diff.balance = Some(entry.balance);
+                    diff.bytecode = Some(entry.bytecode.0.clone());
+                    diff.datastore = entry
+                        .datastore
+                        .iter()
+                        .map(|(key, value)| DatastoreEntryDiff {
+                            key: key.clone(),
+                            value: Some(value.clone()),
+                            previous_value: None,
+                        })
+                        .collect();
+                }
+                SetUpdateOrDelete::Update(update) => {
+                    if let SetOrKeep::Set(balance) = update.balance {
+                        diff.balance = Some(balance);
+                    }
+                    if let SetOrKeep::Set(bytecode) = &update.bytecode {
+                        diff.bytecode = Some(bytecode.0.clone());
+                    }
+                    diff.datastore = update
+                        .datastore
+                        .iter()
+                        .map(|(key, value)| DatastoreEntryDiff {
+                            key: key.clone(),
+                            value: match value {
+                                SetOrDelete::Set(value) => Some(value.clone()),
+                                SetOrDelete::Delete => None,
+                            },
+                            previous_value: None,
+                        })
+                        .collect();
+                }
+                SetUpdateOrDelete::Delete => diff.deleted = true,
+            }
+        }
+        for (address, rolls) in changes.pos_changes.roll_changes.iter() {
+            addresses.entry(*address).or_default().rolls = Some(*rolls);
+        }
+        for (slot, credits) in changes.pos_changes.deferred_credits.credits.iter() {
+            for (address, amount) in credits.iter() {
+                addresses
+                    .entry(*address)
+                    .or_default()
+                    .deferred_credits
+                    .push(SlotAmount {
+                        slot: *slot,
+                        amount: *amount,
+                    });
+            }
+        }
+        for change in changes.async_pool_changes.0.values() {
+            if let SetUpdateOrDelete::Set(message) = change {
+                addresses
+                    .entry(message.sender)
+                    .or_default()
+                    .emitted_async_messages
+                    .push(EmittedAsyncMessage {
+                        emission_slot: message.emission_slot,
+                        destination: message.destination,
+                        function: message.function.clone(),
+                        function_params: message.function_params.clone(),
+                        max_gas: message.max_gas,
+                        fee: message.fee,
+                        coins: message.coins,
+                        validity_start: message.validity_start,
+                        validity_end: message.validity_end,
+                    });
+            }
+        }
+        ReadOnlyStateDiff { addresses }
+    }
+}
+
+/// Renders datastore bytes as text when they are printable UTF-8, as hex otherwise
+fn display_datastore_bytes(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) if !text.chars().any(char::is_control) => format!("\"{}\"", text),
+        _ => format!(
+            "0x{}",
+            bytes
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>()
+        ),
+    }
+}
+
+impl Display for ReadOnlyStateDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (address, diff) in self.addresses.iter() {
+            writeln!(f, "\tAddress {}:", address)?;
+            if diff.deleted {
+                writeln!(f, "\t\tLedger entry deleted")?;
+            } else if diff.created {
+                writeln!(f, "\t\tLedger entry created")?;
+            }
+            if let Some(balance) = diff.balance {
+                match diff.previous_balance {
+                    Some(previous_balance) => writeln!(
+                        f,
+                        "\t\tBalance set to {} (was {})",
+                        balance, previous_balance
+                    )?,
+                    None => writeln!(f, "\t\tBalance set to {}", balance)?,
+                }
+            }
+            if let Some(bytecode) = &diff.bytecode {
+                match &diff.previous_bytecode {
+                    Some(previous_bytecode) => writeln!(
+                        f,
+                        "\t\tBytecode set ({} bytes, was {} bytes)",
+                        bytecode.len(),
+                        previous_bytecode.len()
+                    )?,
+                    None => writeln!(f, "\t\tBytecode set ({} bytes)", bytecode.len())?,
+                }
+            }
+            for entry in diff.datastore.iter() {
+                let key = display_datastore_bytes(&entry.key);
+                let previous = match &entry.previous_value {
+                    Some(previous_value) => {
+                        format!(" (was {})", display_datastore_bytes(previous_value))
+                    }
+                    None => String::new(),
+                };
+                match &entry.value {
+                    Some(value) => writeln!(
+                        f,
+                        "\t\tDatastore entry {} set to {}{}",
+                        key,
+                        display_datastore_bytes(value),
+                        previous
+                    )?,
+                    None => writeln!(f, "\t\tDatastore entry {} deleted{}", key, previous)?,
+                }
+            }
+            if let Some(rolls) = diff.rolls {
+                match diff.previous_rolls {
+                    Some(previous_rolls) => writeln!(
+                        f,
+                        "\t\tRoll count set to {} (was {})",
+                        rolls, previous_rolls
+                    )?,
+                    None => writeln!(f, "\t\tRoll count set to {}", rolls)?,
+                }
+            }
+            for credit in diff.deferred_credits.iter() {
+                writeln!(
+                    f,
+                    "\t\t{} locked coins will be unlocked at slot {}",
+                    credit.amount, credit.slot
+                )?;
+            }
+            for message in diff.emitted_async_messages.iter() {
+                writeln!(
+                    f,
+                    "\t\tAsync message to {} calling {} with {} coins, valid from slot {} to {}",
+                    message.destination,
+                    message.function,
+                    message.coins,
+                    message.validity_start,
+                    message.validity_end
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// module cache statistics, cumulated since the node started
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ModuleCacheInfo {
+    /// number of modules found in RAM, either pinned or in the LRU cache
+    pub lru_hits: u64,
+    /// number of modules missing from RAM
+    pub lru_misses: u64,
+    /// number of modules missing from RAM but found in the HD cache
+    pub hd_hits: u64,
+    /// number of modules missing from both caches
+    pub hd_misses: u64,
+    /// number of module compilations
+    pub compilations: u64,
+    /// total time spent compiling modules, in microseconds
+    pub compilation_time_micros: u64,
+    /// number of modules in the LRU cache
+    pub lru_entry_count: usize,
+    /// number of entries in the HD cache
+    pub hd_entry_count: usize,
+    /// number of modules pinned in RAM
+    pub pinned_count: usize,
+    /// number of modules whose call statistics are tracked
+    pub tracked_count: usize,
+    /// number of modules loaded in RAM by the startup warm-up
+    pub warmed_up_count: usize,
+}
+
+impl Display for ModuleCacheInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Module cache:")?;
+        writeln!(
+            f,
+            "\tLRU: {} entries, {} hits, {} misses",
+            self.lru_entry_count, self.lru_hits, self.lru_misses
+        )?;
+        writeln!(
+            f,
+            "\tHD: {} entries, {} hits, {} misses",
+            self.hd_entry_count, self.hd_hits, self.hd_misses
+        )?;
+        writeln!(
+            f,
+            "\tCompilations: {} in {} µs",
+            self.compilations, self.compilation_time_micros
+        )?;
+        writeln!(
+            f,
+            "\tPinned: {}, tracked: {}, warmed up at startup: {}",
+            self.pinned_count, self.tracked_count, self.warmed_up_count
+        )
+    }
+}
+
+/// module cache status of a module
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ModuleCacheEntry {
+    /// hash of the module bytecode
+    pub hash: Hash,
+    /// the module is in the LRU cache
+    pub in_lru: bool,
+    /// the module is in the HD cache
+    pub in_hd: bool,
+    /// the module is pinned in RAM
+    pub pinned: bool,
+    /// number of times the module was loaded for execution, if tracked
+    pub call_count: Option<u64>,
+    /// instance initialization cost, if known
+    pub init_cost: Option<u64>,
+    /// invalidity reason, if the module is invalid
+    pub invalid: Option<String>,
+}
+
+impl Display for ModuleCacheEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Module {}:", self.hash)?;
+        writeln!(
+            f,
+            "\tIn LRU: {}, in HD: {}, pinned: {}",
+            self.in_lru, self.in_hd, self.pinned
+        )?;
+        if let Some(call_count) = self.call_count {
+            writeln!(f, "\tCalls: {}", call_count)?;
+        }
+        if let Some(init_cost) = self.init_cost {
+            writeln!(f, "\tInit cost: {}", init_cost)?;
+        }
+        if let Some(invalid) = &self.invalid {
+            writeln!(f, "\tInvalid: {}", invalid)?;
+        }
+        Ok(())
+    }
+}
+
+/// number of executions, wall time and gas spent in an execution category
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct ExecutionProfileStats {
+    /// number of executions
+    pub count: u64,
+    /// total wall time of the executions, in microseconds
+    pub time_micros: u64,
+    /// total gas spent by the executions
+    pub gas: u64,
+}
+
+impl Display for ExecutionProfileStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} executions in {} µs, {} gas",
+            self.count, self.time_micros, self.gas
+        )
+    }
+}
+
+/// profile of the calls to a smart contract function
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ContractCallStats {
+    /// address of the called smart contract
+    pub address: Address,
+    /// called function
+    pub function: String,
+    /// calls made by operations and asynchronous messages
+    pub calls: ExecutionProfileStats,
+}
+
+/// execution profile of the slots executed during the recent profiling window
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ExecutionProfileInfo {
+    /// duration of the profiling window, in milliseconds
+    pub window_millis: u64,
+    /// executed slots
+    pub slots: ExecutionProfileStats,
+    /// executed operations, per operation type
+    pub operations: BTreeMap<String, ExecutionProfileStats>,
+    /// executed asynchronous messages
+    pub async_messages: ExecutionProfileStats,
+    /// most expensive smart contract functions, by decreasing gas then wall time
+    pub top_contracts: Vec<ContractCallStats>,
+}
+
+impl Display for ExecutionProfileInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Execution profile over the last {} ms:",
+            self.window_millis
+        )?;
+        writeln!(f, "\tSlots: {}", self.slots)?;
+        writeln!(f, "\tAsync messages: {}", self.async_messages)?;
+        writeln!(f, "\tOperations:")?;
+        for (op_type, stats) in &self.operations {
+            writeln!(f, "\t\t{}: {}", op_type, stats)?;
+        }
+        writeln!(f, "\tTop contracts:")?;
+        for contract in &self.top_contracts {
+            writeln!(
+                f,
+                "\t\t{} {}: {}",
+                contract.address, contract.function, contract.calls
+            )?;
+        }
         Ok(())
     }
 }
@@ -85,3 +509,158 @@ pub struct ReadOnlyCall {
     /// fee
     pub fee: Option<Amount>,
 }
+
+/// read-only execution of a bundle
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub enum ReadOnlyExecution {
+    /// bytecode execution
+    Bytecode(ReadOnlyBytecodeExecution),
+    /// SC call
+    Call(ReadOnlyCall),
+}
+
+/// ordered bundle of read-only executions, simulated on top of an overridden state
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct ReadOnlyBundle {
+    /// executions, run in order, each one seeing the effects of the previous successful ones
+    pub executions: Vec<ReadOnlyExecution>,
+    /// overrides applied to the state before the first execution
+    #[serde(default)]
+    pub state_overrides: Vec<StateOverride>,
+    /// slot at which the executions are simulated, optional,
+    /// at most `max_read_only_lookahead_periods` periods after the next candidate slot
+    pub slot: Option<Slot>,
+}
+
+/// override of the ledger entry of an address
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct StateOverride {
+    /// overridden address
+    pub address: Address,
+    /// balance, optional
+    pub balance: Option<Amount>,
+    /// bytecode, optional
+    pub bytecode: Option<Vec<u8>>,
+    /// datastore entries to set or delete
+    #[serde(default)]
+    pub datastore: Vec<DatastoreEntryOverride>,
+}
+
+/// override of a datastore entry
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct DatastoreEntryOverride {
+    /// datastore key
+    pub key: Vec<u8>,
+    /// value to set, the entry is deleted if not set
+    pub value: Option<Vec<u8>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AddressStateDiff, DatastoreEntryDiff, ReadOnlyStateDiff};
+    use massa_async_pool::AsyncMessage;
+    use massa_final_state::StateChanges;
+    use massa_ledger_exports::{LedgerEntryUpdate, SetOrDelete, SetOrKeep, SetUpdateOrDelete};
+    use massa_models::{address::Address, amount::Amount, prehash::PreHashMap, slot::Slot};
+    use massa_signature::KeyPair;
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_state_diff_from_state_changes() {
+        let caller = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let target = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let mut changes = StateChanges::default();
+        changes.ledger_changes.0.insert(
+            caller,
+            SetUpdateOrDelete::Update(LedgerEntryUpdate {
+                balance: SetOrKeep::Set(Amount::from_str("90").unwrap()),
+                bytecode: SetOrKeep::Keep,
+                datastore: BTreeMap::from([
+                    (b"set".to_vec(), SetOrDelete::Set(b"value".to_vec())),
+                    (b"deleted".to_vec(), SetOrDelete::Delete),
+                ]),
+            }),
+        );
+        changes
+            .ledger_changes
+            .0
+            .insert(target, SetUpdateOrDelete::Delete);
+        changes.pos_changes.roll_changes.insert(caller, 3);
+        changes.pos_changes.deferred_credits.credits.insert(
+            Slot::new(4, 0),
+            PreHashMap::from_iter([(caller, Amount::from_str("10").unwrap())]),
+        );
+        let message = AsyncMessage::new(
+            Slot::new(1, 0),
+            0,
+            caller,
+            target,
+            "receive".to_string(),
+            1000,
+            Amount::zero(),
+            Amount::from_str("1").unwrap(),
+            Slot::new(2, 0),
+            Slot::new(3, 0),
+            Vec::new(),
+            None,
+            None,
+        );
+        changes
+            .async_pool_changes
+            .0
+            .insert(message.compute_id(), SetUpdateOrDelete::Set(message));
+
+        let diff = ReadOnlyStateDiff::from(&changes);
+        assert_eq!(diff.addresses.len(), 2);
+        let caller_diff = diff.addresses.get(&caller).unwrap();
+        assert!(!caller_diff.created && !caller_diff.deleted);
+        assert_eq!(caller_diff.balance, Some(Amount::from_str("90").unwrap()));
+        assert!(caller_diff.bytecode.is_none());
+        assert_eq!(caller_diff.datastore.len(), 2);
+        assert!(caller_diff
+            .datastore
+            .iter()
+            .any(|entry| entry.key == b"deleted".to_vec() && entry.value.is_none()));
+        assert_eq!(caller_diff.rolls, Some(3));
+        assert_eq!(caller_diff.deferred_credits.len(), 1);
+        assert_eq!(caller_diff.emitted_async_messages.len(), 1);
+        assert_eq!(caller_diff.emitted_async_messages[0].destination, target);
+        assert!(caller_diff.previous_balance.is_none());
+        assert!(diff.addresses.get(&target).unwrap().deleted);
+    }
+
+    #[test]
+    fn test_state_diff_display() {
+        let address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let diff = ReadOnlyStateDiff {
+            addresses: BTreeMap::from([(
+                address,
+                AddressStateDiff {
+                    balance: Some(Amount::from_str("90").unwrap()),
+                    previous_balance: Some(Amount::from_str("100").unwrap()),
+                    datastore: vec![
+                        DatastoreEntryDiff {
+                            key: b"name".to_vec(),
+                            value: Some(b"new".to_vec()),
+                            previous_value: Some(b"old".to_vec()),
+                        },
+                        DatastoreEntryDiff {
+                            key: vec![0, 159],
+                            value: None,
+                            previous_value: Some(vec![1]),
+                        },
+                    ],
+                    rolls: Some(3),
+                    previous_rolls: Some(5),
+                    ..Default::default()
+                },
+            )]),
+        };
+        let display = diff.to_string();
+        assert!(display.contains("Balance set to 90 (was 100)"));
+        assert!(display.contains("Datastore entry \"name\" set to \"new\" (was \"old\")"));
+        assert!(display.contains("Datastore entry 0x009f deleted (was 0x01)"));
+        assert!(display.contains("Roll count set to 3 (was 5)"));
+    }
+}
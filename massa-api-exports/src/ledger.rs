@@ -1,7 +1,11 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_hash::Hash;
+use massa_models::address::Address;
 use massa_models::amount::Amount;
 use massa_models::ledger::LedgerData;
+use massa_models::ledger_tree::{matches_local_root, LedgerTreeItem, SparseMerklePath};
+use massa_models::slot::Slot;
 
 use serde::{Deserialize, Serialize};
 
@@ -28,3 +32,175 @@ impl std::fmt::Display for LedgerInfo {
         Ok(())
     }
 }
+
+/// Request of aggregated views over the final ledger
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct LedgerAnalyticsRequest {
+    /// number of addresses with the highest balances to return
+    pub rich_list_length: usize,
+    /// addresses whose datastore accounting is returned
+    #[serde(default)]
+    pub addresses: Vec<Address>,
+}
+
+/// Aggregated views over the final ledger
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LedgerAnalytics {
+    /// final slot the analytics are attached to
+    pub slot: Slot,
+    /// breakdown of the circulating supply
+    pub supply: SupplyBreakdown,
+    /// number of addresses in the ledger
+    pub address_count: u64,
+    /// number of datastore entries in the ledger
+    pub datastore_entry_count: u64,
+    /// cumulated size in bytes of the datastore keys and values of the ledger
+    pub datastore_size: u64,
+    /// addresses with the highest balances, by decreasing balance
+    pub rich_list: Vec<AddressBalance>,
+    /// datastore accounting of the requested addresses, in the order of the request,
+    /// null if the address is not in the ledger
+    pub entries: Vec<Option<LedgerEntryStorage>>,
+}
+
+/// Breakdown of the circulating supply
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+pub struct SupplyBreakdown {
+    /// sum of the balances
+    pub balances: Amount,
+    /// value of the rolls at the roll price
+    pub rolls: Amount,
+    /// sum of the pending deferred credits
+    pub deferred_credits: Amount,
+    /// sum of the above
+    pub total: Amount,
+}
+
+/// Balance of an address
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+pub struct AddressBalance {
+    /// the address
+    pub address: Address,
+    /// its final balance
+    pub balance: Amount,
+}
+
+/// Datastore accounting of a ledger entry
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+pub struct LedgerEntryStorage {
+    /// the address
+    pub address: Address,
+    /// its final balance
+    pub balance: Amount,
+    /// number of entries in its datastore
+    pub datastore_entry_count: u64,
+    /// cumulated size in bytes of the keys and values of its datastore
+    pub datastore_size: u64,
+    /// storage cost locked by its datastore
+    pub datastore_storage_cost: Amount,
+}
+
+impl std::fmt::Display for LedgerAnalytics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Final slot: {}", self.slot)?;
+        writeln!(f, "Supply: {}", self.supply.total)?;
+        writeln!(f, "\tBalances: {}", self.supply.balances)?;
+        writeln!(f, "\tRolls: {}", self.supply.rolls)?;
+        writeln!(f, "\tDeferred credits: {}", self.supply.deferred_credits)?;
+        writeln!(f, "Addresses: {}", self.address_count)?;
+        writeln!(
+            f,
+            "Datastore: {} entries, {} bytes",
+            self.datastore_entry_count, self.datastore_size
+        )?;
+        writeln!(f, "Rich list:")?;
+        for entry in &self.rich_list {
+            writeln!(f, "\t{}: {}", entry.address, entry.balance)?;
+        }
+        for entry in self.entries.iter().flatten() {
+            writeln!(
+                f,
+                "Address {}: balance {}, {} datastore entries, {} bytes, storage cost {}",
+                entry.address,
+                entry.balance,
+                entry.datastore_entry_count,
+                entry.datastore_size,
+                entry.datastore_storage_cost
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Item of a ledger entry whose path in the ledger Merkle tree is requested
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LedgerTreePathInput {
+    /// address of the ledger entry
+    pub address: Address,
+    /// requested item of the entry
+    pub item: LedgerTreeItem,
+}
+
+/// Final values of ledger items with their paths in the ledger Merkle tree of the node.
+///
+/// The tree is maintained locally by the node: its root is not committed by the consensus,
+/// so the paths only show that the values are consistent with the tree of the node that returned them.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LedgerTreeItems {
+    /// final slot the tree matches
+    pub slot: Slot,
+    /// root of the ledger Merkle tree computed by the node, not committed by the consensus
+    pub local_root: Hash,
+    /// requested items, in the order of the request
+    pub items: Vec<LedgerTreeItemPath>,
+}
+
+/// Final value of a ledger item with its path in the tree
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LedgerTreeItemPath {
+    /// address of the ledger entry
+    pub address: Address,
+    /// item of the entry
+    pub item: LedgerTreeItem,
+    /// serialized value of the item, null if it is absent from the ledger
+    pub value: Option<Vec<u8>>,
+    /// path of the item, leading to its value or showing its absence
+    pub path: SparseMerklePath,
+}
+
+impl std::fmt::Display for LedgerTreeItems {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Final slot: {}", self.slot)?;
+        writeln!(
+            f,
+            "Ledger Merkle root (node local, not committed by the consensus): {}",
+            self.local_root
+        )?;
+        for item_path in &self.items {
+            let consistent = matches_local_root(
+                &self.local_root,
+                &item_path.address,
+                &item_path.item,
+                item_path.value.as_deref(),
+                &item_path.path,
+            );
+            writeln!(
+                f,
+                "Address {}, {:?}: {}, path of depth {} {}",
+                item_path.address,
+                item_path.item,
+                match &item_path.value {
+                    Some(value) => format!("value of {} bytes", value.len()),
+                    None => "absent".to_string(),
+                },
+                item_path.path.siblings.len(),
+                if consistent {
+                    "consistent with the local root"
+                } else {
+                    "INCONSISTENT with the local root"
+                }
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -11,12 +11,16 @@ use serde::{Deserialize, Serialize};
 
 /// address related structures
 pub mod address;
+/// asynchronous pool related structures
+pub mod async_pool;
 /// block-related structures
 pub mod block;
 /// node configuration
 pub mod config;
 /// datastore serialization / deserialization
 pub mod datastore;
+/// selector draws
+pub mod draws;
 /// endorsements
 pub mod endorsement;
 /// models error
@@ -25,16 +29,22 @@ pub mod error;
 pub mod execution;
 /// ledger structures
 pub mod ledger;
+/// light node related structures
+pub mod light;
 /// node related structure
 pub mod node;
 /// operations
 pub mod operation;
 /// page
 pub mod page;
+/// roll manager events
+pub mod roll_manager;
 /// rolls
 pub mod rolls;
 /// slots
 pub mod slot;
+/// versioning (MIP deployments)
+pub mod versioning;
 
 /// Dumb utils function to display nicely boolean value
 fn display_if_true(value: bool, text: &str) -> String {
@@ -0,0 +1,46 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::{block_header::SecuredHeader, block_id::BlockId, slot::Slot, version::Version};
+use serde::{Deserialize, Serialize};
+
+/// Sync status of a light node
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LightStatus {
+    /// node version
+    pub version: Version,
+    /// latest final block id and period of each thread
+    pub latest_final_blocks: Vec<(BlockId, u64)>,
+    /// blocks of the clique of higher fitness
+    pub blockclique: Vec<BlockId>,
+    /// number of headers waiting for their parents or for the draws of their slot
+    pub waiting_headers: usize,
+    /// first slot whose draws are not known yet
+    pub next_missing_draws_slot: Option<Slot>,
+}
+
+impl std::fmt::Display for LightStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Version: {}", self.version)?;
+        writeln!(f, "Latest final blocks:")?;
+        for (thread, (block_id, period)) in self.latest_final_blocks.iter().enumerate() {
+            writeln!(f, "\tThread {}: {} at period {}", thread, block_id, period)?;
+        }
+        writeln!(f, "Blockclique size: {}", self.blockclique.len())?;
+        writeln!(f, "Waiting headers: {}", self.waiting_headers)?;
+        if let Some(slot) = self.next_missing_draws_slot {
+            writeln!(f, "First slot with missing draws: {}", slot)?;
+        }
+        Ok(())
+    }
+}
+
+/// Header of a block followed by a light node
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LightHeaderInfo {
+    /// block id
+    pub id: BlockId,
+    /// header of the block, if the block is in the graph of the light node
+    pub header: Option<SecuredHeader>,
+    /// whether the block is final, if the block is in the graph of the light node
+    pub is_final: Option<bool>,
+}
@@ -1,5 +1,7 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::versioning::MipStatus;
+use massa_models::ban::BanInfo;
 use massa_models::node::NodeId;
 use massa_models::stats::{ConsensusStats, ExecutionStats, NetworkStats};
 use massa_models::{config::CompactConfig, slot::Slot, version::Version};
@@ -41,6 +43,9 @@ pub struct NodeStatus {
     pub execution_stats: ExecutionStats,
     /// compact configuration
     pub config: CompactConfig,
+    /// deployment status of the known MIPs
+    #[serde(default)]
+    pub mip_statuses: Vec<MipStatus>,
 }
 
 impl std::fmt::Display for NodeStatus {
@@ -76,6 +81,14 @@ impl std::fmt::Display for NodeStatus {
 
         writeln!(f, "{}", self.execution_stats)?;
 
+        if !self.mip_statuses.is_empty() {
+            writeln!(f, "Versioning:")?;
+            for mip_status in &self.mip_statuses {
+                write!(f, "{}", mip_status)?;
+            }
+            writeln!(f)?;
+        }
+
         writeln!(f, "Connected nodes:")?;
         for (node_id, (ip_addr, is_outgoing)) in &self.connected_nodes {
             writeln!(
@@ -89,3 +102,19 @@ impl std::fmt::Display for NodeStatus {
         Ok(())
     }
 }
+
+/// ban of a node, as reported by the node's protocol
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NodeBanInfo {
+    /// banned node id
+    pub node_id: NodeId,
+    /// cause, date and expiry of the ban
+    pub ban: BanInfo,
+}
+
+impl std::fmt::Display for NodeBanInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Node's ID: {}", self.node_id)?;
+        write!(f, "{}", self.ban)
+    }
+}
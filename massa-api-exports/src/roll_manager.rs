@@ -0,0 +1,30 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::{address::Address, amount::Amount, operation::OperationId};
+use serde::{Deserialize, Serialize};
+
+/// Roll operation type sent by the roll manager
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum RollManagerAction {
+    /// rolls are bought
+    Buy,
+    /// rolls are sold
+    Sell,
+}
+
+/// Roll operation sent by the roll manager of the node for one of its staking addresses
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RollManagerEvent {
+    /// staking address
+    pub address: Address,
+    /// id of the roll operation
+    pub operation_id: OperationId,
+    /// whether rolls are bought or sold
+    pub action: RollManagerAction,
+    /// number of rolls bought or sold
+    pub roll_count: u64,
+    /// candidate balance of the address when the operation was sent
+    pub candidate_balance: Amount,
+    /// candidate roll count of the address when the operation was sent
+    pub candidate_roll_count: u64,
+}
@@ -0,0 +1,131 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+use massa_time::MassaTime;
+use massa_versioning::versioning::{ComponentStateTypeId, MipVoteStatus};
+use serde::{Deserialize, Serialize};
+
+/// Deployment state of a MIP
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, strum::Display)]
+pub enum MipDeploymentState {
+    /// inconsistent state
+    Error,
+    /// the version can not be announced yet
+    Defined,
+    /// the version is announced, waiting for the lock-in threshold
+    Started,
+    /// the threshold was reached, waiting for the activation delay
+    LockedIn,
+    /// the MIP is active
+    Active,
+    /// the deployment timed out before locking in
+    Failed,
+}
+
+impl From<ComponentStateTypeId> for MipDeploymentState {
+    fn from(value: ComponentStateTypeId) -> Self {
+        match value {
+            ComponentStateTypeId::Error => MipDeploymentState::Error,
+            ComponentStateTypeId::Defined => MipDeploymentState::Defined,
+            ComponentStateTypeId::Started => MipDeploymentState::Started,
+            ComponentStateTypeId::LockedIn => MipDeploymentState::LockedIn,
+            ComponentStateTypeId::Active => MipDeploymentState::Active,
+            ComponentStateTypeId::Failed => MipDeploymentState::Failed,
+        }
+    }
+}
+
+/// Deployment status of a MIP, with the announcements of its network version
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MipStatus {
+    /// MIP name
+    pub name: String,
+    /// network version announced in block headers
+    pub version: u32,
+    /// deployment state
+    pub state: MipDeploymentState,
+    /// timestamp from which the version can be announced
+    pub start: MassaTime,
+    /// timestamp at which the deployment fails if not locked in
+    pub timeout: MassaTime,
+    /// number of blocks announcing the version in the stats window
+    pub announced_count: u64,
+    /// number of announcements required in the stats window to lock in
+    pub required_count: u64,
+    /// number of blocks currently in the stats window
+    pub window_length: usize,
+    /// size of the stats window once full
+    pub block_count_considered: usize,
+    /// true if our node announces the version in the blocks it produces
+    pub announced_by_node: bool,
+    /// lock-in timestamp, projected if all the next blocks announce the version (from its start time on) when not locked in yet
+    pub lock_in_at: Option<MassaTime>,
+    /// activation timestamp, projected when not locked in yet
+    pub activation_at: Option<MassaTime>,
+}
+
+impl From<MipVoteStatus> for MipStatus {
+    fn from(value: MipVoteStatus) -> Self {
+        MipStatus {
+            name: value.mip_info.name,
+            version: value.mip_info.version,
+            state: value.state.into(),
+            start: value.mip_info.start,
+            timeout: value.mip_info.timeout,
+            announced_count: value.announced_count,
+            required_count: value.required_count,
+            window_length: value.window_length,
+            block_count_considered: value.block_count_considered,
+            announced_by_node: value.announced_by_node,
+            lock_in_at: value.lock_in_at,
+            activation_at: value.activation_at,
+        }
+    }
+}
+
+impl std::fmt::Display for MipStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} (version {}): {}{}",
+            self.name,
+            self.version,
+            self.state,
+            crate::display_if_true(self.announced_by_node, "announced by this node")
+        )?;
+        writeln!(
+            f,
+            "\tAnnouncements: {}/{} required, {} blocks remaining to fill the stats window of {} blocks",
+            self.announced_count,
+            self.required_count,
+            self.block_count_considered.saturating_sub(self.window_length),
+            self.block_count_considered
+        )?;
+        writeln!(
+            f,
+            "\tStart: {} / Timeout: {}",
+            self.start.format_instant(),
+            self.timeout.format_instant()
+        )?;
+        let projected = matches!(
+            self.state,
+            MipDeploymentState::Defined | MipDeploymentState::Started
+        );
+        if let Some(lock_in_at) = self.lock_in_at {
+            writeln!(
+                f,
+                "\tLock-in{}: {}",
+                if projected { " (earliest)" } else { "" },
+                lock_in_at.format_instant()
+            )?;
+        }
+        if let Some(activation_at) = self.activation_at {
+            writeln!(
+                f,
+                "\tActivation{}: {}",
+                if projected { " (earliest)" } else { "" },
+                activation_at.format_instant()
+            )?;
+        }
+        Ok(())
+    }
+}
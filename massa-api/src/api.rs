@@ -12,16 +12,17 @@ use jsonrpsee::{PendingSubscriptionSink, SubscriptionMessage};
 use massa_api_exports::config::APIConfig;
 use massa_api_exports::error::ApiError;
 use massa_api_exports::page::{PageRequest, PagedVec, PagedVecV2};
+use massa_api_exports::roll_manager::RollManagerEvent;
 use massa_api_exports::ApiRequest;
 use massa_consensus_exports::{ConsensusBroadcasts, ConsensusController};
-use massa_execution_exports::ExecutionController;
+use massa_execution_exports::{ExecutionChannels, ExecutionController};
 use massa_models::address::Address;
 use massa_models::block_id::BlockId;
 use massa_models::slot::Slot;
 use massa_models::timeslots::get_latest_block_slot_at_timestamp;
 use massa_models::version::Version;
 use massa_pool_exports::PoolBroadcasts;
-use massa_time::MassaTime;
+use massa_pos_exports::SelectorChannels;
 use serde::Serialize;
 use tokio_stream::wrappers::BroadcastStream;
 
@@ -31,7 +32,10 @@ impl API<ApiV2> {
         consensus_controller: Box<dyn ConsensusController>,
         consensus_broadcasts: ConsensusBroadcasts,
         execution_controller: Box<dyn ExecutionController>,
+        execution_channels: ExecutionChannels,
         pool_broadcasts: PoolBroadcasts,
+        selector_channels: SelectorChannels,
+        roll_manager_event_sender: tokio::sync::broadcast::Sender<RollManagerEvent>,
         api_settings: APIConfig,
         version: Version,
     ) -> Self {
@@ -39,7 +43,10 @@ impl API<ApiV2> {
             consensus_controller,
             consensus_broadcasts,
             execution_controller,
+            execution_channels,
             pool_broadcasts,
+            selector_channels,
+            roll_manager_event_sender,
             api_settings,
             version,
         })
@@ -66,7 +73,7 @@ impl MassaApiServer for API<ApiV2> {
     ) -> RpcResult<PagedVecV2<(Address, u64)>> {
         let cfg = self.0.api_settings.clone();
 
-        let now = MassaTime::now();
+        let now = cfg.clock.now();
 
         let latest_block_slot_at_timestamp_result = get_latest_block_slot_at_timestamp(
             cfg.thread_count,
@@ -149,6 +156,31 @@ impl MassaApiServer for API<ApiV2> {
     ) -> SubscriptionResult {
         broadcast_via_ws(self.0.pool_broadcasts.operation_sender.clone(), pending).await
     }
+
+    async fn subscribe_new_cycle_draws(
+        &self,
+        pending: PendingSubscriptionSink,
+    ) -> SubscriptionResult {
+        broadcast_via_ws(self.0.selector_channels.cycle_draws_sender.clone(), pending).await
+    }
+
+    async fn subscribe_async_message_events(
+        &self,
+        pending: PendingSubscriptionSink,
+    ) -> SubscriptionResult {
+        broadcast_via_ws(
+            self.0.execution_channels.async_message_event_sender.clone(),
+            pending,
+        )
+        .await
+    }
+
+    async fn subscribe_roll_manager_events(
+        &self,
+        pending: PendingSubscriptionSink,
+    ) -> SubscriptionResult {
+        broadcast_via_ws(self.0.roll_manager_event_sender.clone(), pending).await
+    }
 }
 
 // Brodcast the stream(sender) content via a WebSocket
@@ -57,4 +57,28 @@ pub trait MassaApi {
 		item = Operation
 	)]
     async fn subscribe_new_operations(&self) -> SubscriptionResult;
+
+    /// New cycles whose selector draws are available.
+    #[subscription(
+		name = "subscribe_new_cycle_draws" => "new_cycle_draws",
+		unsubscribe = "unsubscribe_new_cycle_draws",
+		item = NewCycleDraws
+	)]
+    async fn subscribe_new_cycle_draws(&self) -> SubscriptionResult;
+
+    /// Asynchronous messages executed, expired or evicted from the final pool.
+    #[subscription(
+		name = "subscribe_async_message_events" => "async_message_events",
+		unsubscribe = "unsubscribe_async_message_events",
+		item = AsyncMessageEvent
+	)]
+    async fn subscribe_async_message_events(&self) -> SubscriptionResult;
+
+    /// Roll operations sent by the roll manager of the node for its staking addresses.
+    #[subscription(
+		name = "subscribe_roll_manager_events" => "roll_manager_events",
+		unsubscribe = "unsubscribe_roll_manager_events",
+		item = RollManagerEvent
+	)]
+    async fn subscribe_roll_manager_events(&self) -> SubscriptionResult;
 }
@@ -11,23 +11,35 @@ use jsonrpsee::proc_macros::rpc;
 use jsonrpsee::server::middleware::HostFilterLayer;
 use jsonrpsee::server::{BatchRequestConfig, ServerBuilder, ServerHandle};
 use jsonrpsee::RpcModule;
+pub use light::MassaLightRpcServer;
 use massa_api_exports::{
-    address::{AddressFilter, AddressInfo},
+    address::{AddressFilter, AddressInfo, AddressStakingHistory},
+    async_pool::{AsyncMessagesRequest, AsyncPoolMessage},
     block::{BlockInfo, BlockSummary},
     config::APIConfig,
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
+    draws::{DrawsLookahead, DrawsLookaheadRequest},
     endorsement::EndorsementInfo,
     error::ApiError::WrongAPI,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
-    node::NodeStatus,
+    execution::{
+        ExecuteReadOnlyResponse, ExecutionProfileInfo, ModuleCacheEntry, ModuleCacheInfo,
+        ReadOnlyBundle, ReadOnlyBytecodeExecution, ReadOnlyCall,
+    },
+    ledger::{LedgerAnalytics, LedgerAnalyticsRequest, LedgerTreeItems, LedgerTreePathInput},
+    node::{NodeBanInfo, NodeStatus},
     operation::{OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
+    roll_manager::RollManagerEvent,
     TimeInterval,
 };
 use massa_consensus_exports::{ConsensusBroadcasts, ConsensusController};
-use massa_execution_exports::ExecutionController;
+use massa_execution_exports::{ExecutionChannels, ExecutionController};
+use massa_factory_exports::FactoryController;
+use massa_hash::Hash;
+use massa_light_client::LightClient;
 use massa_models::clique::Clique;
 use massa_models::composite::PubkeySig;
+use massa_models::connection::ConnectionInfo;
 use massa_models::node::NodeId;
 use massa_models::operation::OperationId;
 use massa_models::output_event::SCOutputEvent;
@@ -37,7 +49,7 @@ use massa_models::{
     execution::EventFilter, slot::Slot, version::Version,
 };
 use massa_pool_exports::{PoolBroadcasts, PoolController};
-use massa_pos_exports::SelectorController;
+use massa_pos_exports::{SelectorChannels, SelectorController};
 use massa_protocol_exports::{ProtocolConfig, ProtocolController};
 use massa_storage::Storage;
 use massa_versioning::keypair_factory::KeyPairFactory;
@@ -51,6 +63,7 @@ use tracing::{info, warn};
 
 mod api;
 mod api_trait;
+mod light;
 mod private;
 mod public;
 
@@ -96,6 +109,8 @@ pub struct Private {
     pub stop_cv: Arc<(Mutex<bool>, Condvar)>,
     /// User wallet
     pub node_wallet: Arc<RwLock<Wallet>>,
+    /// link to the block factory, only available on a local development network
+    pub factory_controller: Option<Box<dyn FactoryController>>,
 }
 
 /// API v2 content
@@ -106,8 +121,24 @@ pub struct ApiV2 {
     pub consensus_broadcasts: ConsensusBroadcasts,
     /// link to the execution component
     pub execution_controller: Box<dyn ExecutionController>,
+    /// channels with informations broadcasted by the execution
+    pub execution_channels: ExecutionChannels,
     /// channels with informations broadcasted by the pool
     pub pool_broadcasts: PoolBroadcasts,
+    /// channels with informations broadcasted by the selector
+    pub selector_channels: SelectorChannels,
+    /// roll operations sent by the roll manager of the node
+    pub roll_manager_event_sender: tokio::sync::broadcast::Sender<RollManagerEvent>,
+    /// API settings
+    pub api_settings: APIConfig,
+    /// node version
+    pub version: Version,
+}
+
+/// Light node API content
+pub struct Light {
+    /// light client following the headers
+    pub light_client: Arc<RwLock<LightClient>>,
     /// API settings
     pub api_settings: APIConfig,
     /// node version
@@ -139,6 +170,17 @@ pub trait ApiServer: MassaApiServer {
     ) -> Result<StopHandle, JsonRpseeError>;
 }
 
+/// Used to manage the light node API
+#[async_trait::async_trait]
+pub trait LightServer: MassaLightRpcServer {
+    /// Start the API
+    async fn serve(
+        self,
+        url: &SocketAddr,
+        api_config: &APIConfig,
+    ) -> Result<StopHandle, JsonRpseeError>;
+}
+
 async fn serve<T>(
     api: RpcModule<T>,
     url: &SocketAddr,
@@ -247,6 +289,15 @@ pub trait MassaRpc {
         arg: Vec<ReadOnlyCall>,
     ) -> RpcResult<Vec<ExecuteReadOnlyResponse>>;
 
+    /// Execute an ordered bundle of bytecode executions and SC calls in read-only mode,
+    /// on top of state overrides and at an optional slot.
+    /// Each execution sees the effects of the previous successful ones.
+    #[method(name = "execute_read_only_bundle")]
+    async fn execute_read_only_bundle(
+        &self,
+        arg: ReadOnlyBundle,
+    ) -> RpcResult<Vec<ExecuteReadOnlyResponse>>;
+
     /// Remove a vector of addresses used to stake.
     /// No confirmation to expect.
     #[method(name = "remove_staking_addresses")]
@@ -321,6 +372,54 @@ pub trait MassaRpc {
     #[method(name = "node_unban_by_id")]
     async fn node_unban_by_id(&self, arg: Vec<NodeId>) -> RpcResult<()>;
 
+    /// Returns the currently banned node ids along with the cause and expiry of their ban.
+    #[method(name = "node_bans")]
+    async fn node_bans(&self) -> RpcResult<Vec<NodeBanInfo>>;
+
+    /// Returns the module cache statistics.
+    #[method(name = "node_module_cache_stats")]
+    async fn node_module_cache_stats(&self) -> RpcResult<ModuleCacheInfo>;
+
+    /// Returns the module cache status of the modules with the given bytecode hashes.
+    #[method(name = "node_module_cache_entries")]
+    async fn node_module_cache_entries(&self, arg: Vec<Hash>) -> RpcResult<Vec<ModuleCacheEntry>>;
+
+    /// Removes the modules with the given bytecode hashes from the module cache.
+    /// They will be compiled again the next time they are executed.
+    #[method(name = "node_evict_modules")]
+    async fn node_evict_modules(&self, arg: Vec<Hash>) -> RpcResult<()>;
+
+    /// Pins the modules with the given bytecode hashes in RAM, they are never evicted by the LRU cache.
+    /// The modules must be cached or have already been executed.
+    #[method(name = "node_pin_modules")]
+    async fn node_pin_modules(&self, arg: Vec<Hash>) -> RpcResult<()>;
+
+    /// Unpins the modules with the given bytecode hashes.
+    #[method(name = "node_unpin_modules")]
+    async fn node_unpin_modules(&self, arg: Vec<Hash>) -> RpcResult<()>;
+
+    /// Returns the wall time and gas spent executing the slots of the recent profiling window,
+    /// per operation type and for asynchronous messages,
+    /// with the given number of most expensive smart contract functions.
+    /// Execution profiling must be enabled on the node.
+    #[method(name = "node_execution_profile")]
+    async fn node_execution_profile(&self, arg: usize) -> RpcResult<ExecutionProfileInfo>;
+
+    /// Devnet only: fast-forward the clock to the next slot and produce its block immediately.
+    /// Returns the produced slot.
+    #[method(name = "node_devnet_next_slot")]
+    async fn node_devnet_next_slot(&self) -> RpcResult<Slot>;
+
+    /// Devnet only: fast-forward the clock by the given number of periods,
+    /// producing the blocks of all the slots on the way. Returns the last produced slot.
+    #[method(name = "node_devnet_fast_forward")]
+    async fn node_devnet_fast_forward(&self, arg: u64) -> RpcResult<Slot>;
+
+    /// Returns the details of every active peer connection: direction, transport, category,
+    /// connection age, last announcement, traffic counters and known objects counts.
+    #[method(name = "get_peers")]
+    async fn get_peers(&self) -> RpcResult<Vec<ConnectionInfo>>;
+
     /// Summary of the current state: time, last final blocks (hash, thread, slot, timestamp), clique count, connected nodes count.
     #[method(name = "get_status")]
     async fn get_status(&self) -> RpcResult<NodeStatus>;
@@ -369,6 +468,43 @@ pub trait MassaRpc {
     #[method(name = "get_addresses")]
     async fn get_addresses(&self, arg: Vec<Address>) -> RpcResult<Vec<AddressInfo>>;
 
+    /// Get the staking history of addresses, per cycle.
+    /// Only available if the node records the staking history.
+    #[method(name = "get_staking_history")]
+    async fn get_staking_history(&self, arg: Vec<Address>)
+        -> RpcResult<Vec<AddressStakingHistory>>;
+
+    /// Get aggregated views over the final ledger: supply breakdown, richest addresses
+    /// and datastore accounting of addresses.
+    /// Only available if the node maintains the ledger analytics.
+    #[method(name = "get_ledger_analytics")]
+    async fn get_ledger_analytics(&self, arg: LedgerAnalyticsRequest)
+        -> RpcResult<LedgerAnalytics>;
+
+    /// Get the final values of ledger items (balances, bytecodes, datastore entries)
+    /// with their paths in the ledger Merkle tree of the node.
+    /// The tree is local to the node and its root is not committed by the consensus:
+    /// the paths only show that the values are consistent with the tree of this node.
+    /// Only available if the node maintains the ledger Merkle tree.
+    #[method(name = "get_ledger_tree_paths")]
+    async fn get_ledger_tree_paths(
+        &self,
+        arg: Vec<LedgerTreePathInput>,
+    ) -> RpcResult<LedgerTreeItems>;
+
+    /// Get the next block and endorsement draws of addresses through all the cycles available
+    /// in the selector, with their expected and actual draw counts per cycle.
+    #[method(name = "get_draws_lookahead")]
+    async fn get_draws_lookahead(&self, arg: DrawsLookaheadRequest) -> RpcResult<DrawsLookahead>;
+
+    /// Get the asynchronous messages of the candidate pool sent by or to an address,
+    /// with the reason why they are not executed yet.
+    #[method(name = "get_async_messages")]
+    async fn get_async_messages(
+        &self,
+        arg: AsyncMessagesRequest,
+    ) -> RpcResult<Vec<AsyncPoolMessage>>;
+
     /// Get addresses bytecode.
     #[method(name = "get_addresses_bytecode")]
     async fn get_addresses_bytecode(&self, args: Vec<AddressFilter>) -> RpcResult<Vec<Vec<u8>>>;
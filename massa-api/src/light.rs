@@ -0,0 +1,122 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Json RPC API of a light node
+use std::net::SocketAddr;
+
+use crate::{Light, LightServer, StopHandle, API};
+use async_trait::async_trait;
+use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
+use jsonrpsee::proc_macros::rpc;
+use massa_api_exports::config::APIConfig;
+use massa_api_exports::error::ApiError;
+use massa_api_exports::ledger::LedgerTreeItems;
+use massa_api_exports::light::{LightHeaderInfo, LightStatus};
+use massa_light_client::LightClient;
+use massa_models::block_id::BlockId;
+use massa_models::ledger_tree::LedgerTreePaths;
+use massa_models::version::Version;
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+/// Exposed light node API methods
+#[rpc(server)]
+pub trait MassaLightRpc {
+    /// Sync status of the light node.
+    #[method(name = "get_status")]
+    async fn get_status(&self) -> RpcResult<LightStatus>;
+
+    /// Headers of the blocks followed by the light node, and whether they are final.
+    #[method(name = "get_headers")]
+    async fn get_headers(&self, arg: Vec<BlockId>) -> RpcResult<Vec<LightHeaderInfo>>;
+
+    /// Checks that ledger items returned by a full node at a slot final for the light node
+    /// are consistent with the local root of the ledger Merkle tree of that full node.
+    /// The root is not committed in the headers, so this gives no guarantee beyond the trust in the full node.
+    #[method(name = "check_ledger_tree_paths")]
+    async fn check_ledger_tree_paths(&self, arg: LedgerTreeItems) -> RpcResult<bool>;
+}
+
+impl API<Light> {
+    /// generate a new light node API
+    pub fn new(
+        light_client: Arc<RwLock<LightClient>>,
+        api_settings: APIConfig,
+        version: Version,
+    ) -> Self {
+        API(Light {
+            light_client,
+            api_settings,
+            version,
+        })
+    }
+}
+
+#[async_trait]
+impl LightServer for API<Light> {
+    async fn serve(
+        self,
+        url: &SocketAddr,
+        api_config: &APIConfig,
+    ) -> Result<StopHandle, JsonRpseeError> {
+        crate::serve(self.into_rpc(), url, api_config).await
+    }
+}
+
+#[doc(hidden)]
+#[async_trait]
+impl MassaLightRpcServer for API<Light> {
+    async fn get_status(&self) -> RpcResult<LightStatus> {
+        let light_client = self.0.light_client.read();
+        Ok(LightStatus {
+            version: self.0.version,
+            latest_final_blocks: light_client.get_latest_final_blocks_periods().to_vec(),
+            blockclique: light_client.get_blockclique().into_iter().collect(),
+            waiting_headers: light_client.get_waiting_headers_count(),
+            next_missing_draws_slot: light_client.next_missing_draws_slot().ok(),
+        })
+    }
+
+    async fn get_headers(&self, arg: Vec<BlockId>) -> RpcResult<Vec<LightHeaderInfo>> {
+        if arg.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+
+        let light_client = self.0.light_client.read();
+        Ok(arg
+            .into_iter()
+            .map(|id| LightHeaderInfo {
+                id,
+                header: light_client.get_header(&id).cloned(),
+                is_final: light_client.is_final(&id),
+            })
+            .collect())
+    }
+
+    async fn check_ledger_tree_paths(&self, arg: LedgerTreeItems) -> RpcResult<bool> {
+        if arg.items.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+
+        let (items, paths) = arg
+            .items
+            .into_iter()
+            .map(|item_path| {
+                (
+                    (item_path.address, item_path.item),
+                    (item_path.value, item_path.path),
+                )
+            })
+            .unzip::<_, _, Vec<_>, Vec<_>>();
+        self.0
+            .light_client
+            .read()
+            .check_ledger_tree_paths(
+                &LedgerTreePaths {
+                    slot: arg.slot,
+                    local_root: arg.local_root,
+                    paths,
+                },
+                &items,
+            )
+            .map_err(|err| ApiError::BadRequest(err.to_string()).into())
+    }
+}
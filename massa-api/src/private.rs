@@ -5,24 +5,31 @@ use crate::{MassaRpcServer, Private, RpcServer, StopHandle, Value, API};
 use async_trait::async_trait;
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
 use massa_api_exports::{
-    address::{AddressFilter, AddressInfo},
+    address::{AddressFilter, AddressInfo, AddressStakingHistory},
+    async_pool::{AsyncMessagesRequest, AsyncPoolMessage},
     block::{BlockInfo, BlockSummary},
     config::APIConfig,
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
+    draws::{DrawsLookahead, DrawsLookaheadRequest},
     endorsement::EndorsementInfo,
     error::ApiError,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
-    node::NodeStatus,
+    execution::{
+        ContractCallStats, ExecuteReadOnlyResponse, ExecutionProfileInfo, ExecutionProfileStats,
+        ModuleCacheEntry, ModuleCacheInfo, ReadOnlyBundle, ReadOnlyBytecodeExecution, ReadOnlyCall,
+    },
+    ledger::{LedgerAnalytics, LedgerAnalyticsRequest, LedgerTreeItems, LedgerTreePathInput},
+    node::{NodeBanInfo, NodeStatus},
     operation::{OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
     ListType, ScrudOperation, TimeInterval,
 };
 use massa_execution_exports::ExecutionController;
+use massa_factory_exports::FactoryController;
 use massa_hash::Hash;
 use massa_models::{
     address::Address, block::Block, block_id::BlockId, clique::Clique, composite::PubkeySig,
-    endorsement::EndorsementId, execution::EventFilter, node::NodeId, operation::OperationId,
-    output_event::SCOutputEvent, prehash::PreHashSet, slot::Slot,
+    connection::ConnectionInfo, endorsement::EndorsementId, execution::EventFilter, node::NodeId,
+    operation::OperationId, output_event::SCOutputEvent, prehash::PreHashSet, slot::Slot,
 };
 use massa_protocol_exports::{PeerId, ProtocolController};
 use massa_signature::KeyPair;
@@ -46,6 +53,7 @@ impl API<Private> {
         api_settings: APIConfig,
         stop_cv: Arc<(Mutex<bool>, Condvar)>,
         node_wallet: Arc<RwLock<Wallet>>,
+        factory_controller: Option<Box<dyn FactoryController>>,
     ) -> Self {
         API(Private {
             protocol_controller,
@@ -53,8 +61,25 @@ impl API<Private> {
             api_settings,
             stop_cv,
             node_wallet,
+            factory_controller,
         })
     }
+
+    /// fast-forward the devnet clock by the given number of slots, producing their blocks
+    async fn devnet_fast_forward(&self, slot_count: u64) -> RpcResult<Slot> {
+        let factory_controller = match &self.0.factory_controller {
+            Some(factory_controller) => factory_controller.clone(),
+            None => {
+                return Err(ApiError::BadRequest("the node is not running a devnet".into()).into())
+            }
+        };
+        // block production can take a while: do not block the async runtime
+        let slot = tokio::task::spawn_blocking(move || factory_controller.fast_forward(slot_count))
+            .await
+            .map_err(|e| ApiError::InternalServerError(e.to_string()))?
+            .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+        Ok(slot)
+    }
 }
 
 #[async_trait]
@@ -125,6 +150,13 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<_>()
     }
 
+    async fn execute_read_only_bundle(
+        &self,
+        _bundle: ReadOnlyBundle,
+    ) -> RpcResult<Vec<ExecuteReadOnlyResponse>> {
+        crate::wrong_api::<_>()
+    }
+
     async fn remove_staking_addresses(&self, addresses: Vec<Address>) -> RpcResult<()> {
         let node_wallet = self.0.node_wallet.clone();
 
@@ -183,6 +215,143 @@ impl MassaRpcServer for API<Private> {
             .map_err(|e| ApiError::ProtocolError(e.to_string()).into())
     }
 
+    async fn node_bans(&self) -> RpcResult<Vec<NodeBanInfo>> {
+        let bans = self
+            .0
+            .protocol_controller
+            .get_bans()
+            .map_err(|e| ApiError::ProtocolError(e.to_string()))?;
+        let mut bans: Vec<NodeBanInfo> = bans
+            .into_iter()
+            .map(|(peer_id, ban)| NodeBanInfo {
+                node_id: NodeId::new(peer_id.get_public_key()),
+                ban,
+            })
+            .collect();
+        bans.sort_unstable_by_key(|ban_info| ban_info.ban.banned_at);
+        Ok(bans)
+    }
+
+    async fn node_module_cache_stats(&self) -> RpcResult<ModuleCacheInfo> {
+        let stats = self.0.execution_controller.get_module_cache_stats();
+        Ok(ModuleCacheInfo {
+            lru_hits: stats.lru_hits,
+            lru_misses: stats.lru_misses,
+            hd_hits: stats.hd_hits,
+            hd_misses: stats.hd_misses,
+            compilations: stats.compilations,
+            compilation_time_micros: stats.compilation_time_micros,
+            lru_entry_count: stats.lru_entry_count,
+            hd_entry_count: stats.hd_entry_count,
+            pinned_count: stats.pinned_count,
+            tracked_count: stats.tracked_count,
+            warmed_up_count: stats.warmed_up_count,
+        })
+    }
+
+    async fn node_module_cache_entries(
+        &self,
+        hashes: Vec<Hash>,
+    ) -> RpcResult<Vec<ModuleCacheEntry>> {
+        if hashes.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+        Ok(self
+            .0
+            .execution_controller
+            .get_module_cache_entries(&hashes)
+            .into_iter()
+            .map(|entry| ModuleCacheEntry {
+                hash: entry.hash,
+                in_lru: entry.in_lru,
+                in_hd: entry.in_hd,
+                pinned: entry.pinned,
+                call_count: entry.call_count,
+                init_cost: entry.init_cost,
+                invalid: entry.invalid,
+            })
+            .collect())
+    }
+
+    async fn node_evict_modules(&self, hashes: Vec<Hash>) -> RpcResult<()> {
+        self.0.execution_controller.evict_modules(&hashes);
+        Ok(())
+    }
+
+    async fn node_pin_modules(&self, hashes: Vec<Hash>) -> RpcResult<()> {
+        self.0
+            .execution_controller
+            .pin_modules(&hashes)
+            .map_err(|e| ApiError::ExecutionError(e.to_string()).into())
+    }
+
+    async fn node_unpin_modules(&self, hashes: Vec<Hash>) -> RpcResult<()> {
+        self.0.execution_controller.unpin_modules(&hashes);
+        Ok(())
+    }
+
+    async fn node_execution_profile(&self, top_n: usize) -> RpcResult<ExecutionProfileInfo> {
+        if top_n as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+        let profile = match self.0.execution_controller.get_execution_profile(top_n) {
+            Some(profile) => profile,
+            None => {
+                return Err(ApiError::BadRequest(
+                    "execution profiling is disabled on this node".into(),
+                )
+                .into())
+            }
+        };
+        let to_stats =
+            |entry: &massa_execution_exports::ExecutionProfileEntry| ExecutionProfileStats {
+                count: entry.count,
+                time_micros: entry.time_micros,
+                gas: entry.gas,
+            };
+        Ok(ExecutionProfileInfo {
+            window_millis: profile.window_millis,
+            slots: to_stats(&profile.slots),
+            operations: profile
+                .operations
+                .iter()
+                .map(|(op_type, entry)| (op_type.clone(), to_stats(entry)))
+                .collect(),
+            async_messages: to_stats(&profile.async_messages),
+            top_contracts: profile
+                .top_contracts
+                .iter()
+                .map(|contract| ContractCallStats {
+                    address: contract.address,
+                    function: contract.function.clone(),
+                    calls: to_stats(&contract.calls),
+                })
+                .collect(),
+        })
+    }
+
+    async fn node_devnet_next_slot(&self) -> RpcResult<Slot> {
+        self.devnet_fast_forward(1).await
+    }
+
+    async fn node_devnet_fast_forward(&self, periods: u64) -> RpcResult<Slot> {
+        if periods > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many periods".into()).into());
+        }
+        self.devnet_fast_forward(periods.saturating_mul(self.0.api_settings.thread_count as u64))
+            .await
+    }
+
+    async fn get_peers(&self) -> RpcResult<Vec<ConnectionInfo>> {
+        let mut peers = self
+            .0
+            .protocol_controller
+            .get_peers()
+            .map_err(|e| ApiError::ProtocolError(e.to_string()))?;
+        peers.sort_unstable_by_key(|peer| peer.node_id);
+        Ok(peers)
+    }
+
     async fn node_unban_by_ip(&self, _ips: Vec<IpAddr>) -> RpcResult<()> {
         //TODO: Reinvoke
         // let network_command_sender = self.0.network_command_sender.clone();
@@ -238,6 +407,32 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<AddressInfo>>()
     }
 
+    async fn get_staking_history(&self, _: Vec<Address>) -> RpcResult<Vec<AddressStakingHistory>> {
+        crate::wrong_api::<Vec<AddressStakingHistory>>()
+    }
+
+    async fn get_ledger_analytics(&self, _: LedgerAnalyticsRequest) -> RpcResult<LedgerAnalytics> {
+        crate::wrong_api::<LedgerAnalytics>()
+    }
+
+    async fn get_ledger_tree_paths(
+        &self,
+        _: Vec<LedgerTreePathInput>,
+    ) -> RpcResult<LedgerTreeItems> {
+        crate::wrong_api::<LedgerTreeItems>()
+    }
+
+    async fn get_draws_lookahead(&self, _: DrawsLookaheadRequest) -> RpcResult<DrawsLookahead> {
+        crate::wrong_api::<DrawsLookahead>()
+    }
+
+    async fn get_async_messages(
+        &self,
+        _: AsyncMessagesRequest,
+    ) -> RpcResult<Vec<AsyncPoolMessage>> {
+        crate::wrong_api::<Vec<AsyncPoolMessage>>()
+    }
+
     async fn get_addresses_bytecode(&self, _: Vec<AddressFilter>) -> RpcResult<Vec<Vec<u8>>> {
         crate::wrong_api::<Vec<Vec<u8>>>()
     }
@@ -6,34 +6,51 @@ use async_trait::async_trait;
 use itertools::{izip, Itertools};
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
 use massa_api_exports::{
-    address::{AddressFilter, AddressInfo},
+    address::{AddressFilter, AddressInfo, AddressStakingHistory, StakingCycleHistory},
+    async_pool::{AsyncMessagesRequest, AsyncPoolMessage},
     block::{BlockInfo, BlockInfoContent, BlockSummary},
     config::APIConfig,
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
+    draws::{AddressDrawStats, DrawsLookahead, DrawsLookaheadRequest, EndorserDraw, SlotDraws},
     endorsement::EndorsementInfo,
     error::ApiError,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyResult},
-    node::NodeStatus,
+    execution::{
+        DatastoreEntryOverride, ExecuteReadOnlyResponse, ExecutionProfileInfo, ModuleCacheEntry,
+        ModuleCacheInfo, ReadOnlyBundle, ReadOnlyBytecodeExecution, ReadOnlyCall,
+        ReadOnlyExecution, ReadOnlyResult, ReadOnlyStateDiff, StateOverride,
+    },
+    ledger::{
+        AddressBalance, LedgerAnalytics, LedgerAnalyticsRequest, LedgerEntryStorage,
+        LedgerTreeItemPath, LedgerTreeItems, LedgerTreePathInput, SupplyBreakdown,
+    },
+    node::{NodeBanInfo, NodeStatus},
     operation::{OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
     slot::SlotAmount,
+    versioning::MipStatus,
     TimeInterval,
 };
 use massa_consensus_exports::block_status::DiscardReason;
 use massa_consensus_exports::ConsensusController;
 use massa_execution_exports::{
-    ExecutionController, ExecutionQueryRequest, ExecutionQueryRequestItem,
-    ExecutionQueryResponseItem, ExecutionStackElement, ReadOnlyExecutionRequest,
+    AsyncMessageFilter, ExecutionController, ExecutionError, ExecutionQueryRequest,
+    ExecutionQueryRequestItem, ExecutionQueryResponseItem, ExecutionStackElement,
+    ReadOnlyExecutionBundle, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
     ReadOnlyExecutionTarget,
 };
+use massa_ledger_exports::{
+    LedgerChanges, LedgerEntryUpdate, SetOrDelete, SetOrKeep, SetUpdateOrDelete,
+};
 use massa_models::{
     address::Address,
     amount::Amount,
     block::{Block, BlockGraphStatus},
     block_id::BlockId,
+    bytecode::Bytecode,
     clique::Clique,
     composite::PubkeySig,
     config::CompactConfig,
+    connection::ConnectionInfo,
     datastore::DatastoreDeserializer,
     endorsement::EndorsementId,
     endorsement::SecureShareEndorsement,
@@ -56,7 +73,6 @@ use massa_pos_exports::SelectorController;
 use massa_protocol_exports::{PeerConnectionType, ProtocolConfig, ProtocolController};
 use massa_serialization::{DeserializeError, Deserializer};
 use massa_storage::Storage;
-use massa_time::MassaTime;
 use massa_versioning::versioning_factory::FactoryStrategy;
 use massa_versioning::{
     keypair_factory::KeyPairFactory, versioning::MipStore, versioning_factory::VersioningFactory,
@@ -93,6 +109,161 @@ impl API<Public> {
             keypair_factory: KeyPairFactory { mip_store },
         })
     }
+
+    /// translate a read-only bytecode execution into an execution request
+    fn readonly_bytecode_request(
+        &self,
+        execution: ReadOnlyBytecodeExecution,
+    ) -> RpcResult<ReadOnlyExecutionRequest> {
+        let ReadOnlyBytecodeExecution {
+            max_gas,
+            address,
+            bytecode,
+            operation_datastore,
+            fee,
+        } = execution;
+        let address = if let Some(addr) = address {
+            addr
+        } else {
+            let now = self.0.api_settings.clock.now();
+            let keypair = self
+                .0
+                .keypair_factory
+                .create(&(), FactoryStrategy::At(now))
+                .map_err(ApiError::from)?;
+            Address::from_public_key(&keypair.get_public_key())
+        };
+
+        let op_datastore = match operation_datastore {
+            Some(v) => {
+                let deserializer = DatastoreDeserializer::new(
+                    self.0.api_settings.max_op_datastore_entry_count,
+                    self.0.api_settings.max_op_datastore_key_length,
+                    self.0.api_settings.max_op_datastore_value_length,
+                );
+                match deserializer.deserialize::<DeserializeError>(&v) {
+                    Ok((_, deserialized)) => Some(deserialized),
+                    Err(e) => {
+                        return Err(ApiError::InconsistencyError(format!(
+                            "Operation datastore error: {}",
+                            e
+                        ))
+                        .into())
+                    }
+                }
+            }
+            None => None,
+        };
+
+        Ok(ReadOnlyExecutionRequest {
+            max_gas,
+            target: ReadOnlyExecutionTarget::BytecodeExecution(bytecode),
+            call_stack: vec![ExecutionStackElement {
+                address,
+                coins: Default::default(),
+                owned_addresses: vec![address],
+                operation_datastore: op_datastore,
+            }],
+            coins: None,
+            fee,
+        })
+    }
+
+    /// translate a read-only call into an execution request
+    fn readonly_call_request(&self, call: ReadOnlyCall) -> RpcResult<ReadOnlyExecutionRequest> {
+        let ReadOnlyCall {
+            max_gas,
+            target_address,
+            target_function,
+            parameter,
+            caller_address,
+            coins,
+            fee,
+        } = call;
+        let caller_address = if let Some(addr) = caller_address {
+            addr
+        } else {
+            let now = self.0.api_settings.clock.now();
+            let keypair = self
+                .0
+                .keypair_factory
+                .create(&(), FactoryStrategy::At(now))
+                .map_err(ApiError::from)?;
+            Address::from_public_key(&keypair.get_public_key())
+        };
+
+        Ok(ReadOnlyExecutionRequest {
+            max_gas,
+            target: ReadOnlyExecutionTarget::FunctionCall {
+                target_func: target_function,
+                target_addr: target_address,
+                parameter,
+            },
+            call_stack: vec![
+                ExecutionStackElement {
+                    address: caller_address,
+                    coins: Default::default(),
+                    owned_addresses: vec![caller_address],
+                    operation_datastore: None, // should always be None
+                },
+                ExecutionStackElement {
+                    address: target_address,
+                    coins: coins.unwrap_or(Amount::default()),
+                    owned_addresses: vec![target_address],
+                    operation_datastore: None, // should always be None
+                },
+            ],
+            coins,
+            fee,
+        })
+    }
+}
+
+/// build the state diff of a read-only execution, with the values the changed entries had before it
+fn readonly_state_diff(output: &ReadOnlyExecutionOutput) -> ReadOnlyStateDiff {
+    let mut state_diff = ReadOnlyStateDiff::from(&output.out.state_changes);
+    let previous_values = &output.previous_values;
+    for (address, diff) in state_diff.addresses.iter_mut() {
+        diff.previous_balance = previous_values.balances.get(address).copied().flatten();
+        diff.previous_bytecode = previous_values
+            .bytecodes
+            .get(address)
+            .cloned()
+            .flatten()
+            .map(|bytecode| bytecode.0);
+        diff.previous_rolls = previous_values.roll_counts.get(address).copied();
+        for entry in diff.datastore.iter_mut() {
+            entry.previous_value = previous_values
+                .datastore_entries
+                .get(&(*address, entry.key.clone()))
+                .cloned()
+                .flatten();
+        }
+    }
+    state_diff
+}
+
+/// map the result of a read-only execution to its API response
+fn readonly_response(
+    result: Result<ReadOnlyExecutionOutput, ExecutionError>,
+) -> ExecuteReadOnlyResponse {
+    ExecuteReadOnlyResponse {
+        executed_at: result
+            .as_ref()
+            .map_or_else(|_| Slot::new(0, 0), |v| v.out.slot),
+        result: result.as_ref().map_or_else(
+            |err| ReadOnlyResult::Error(format!("readonly call failed: {}", err)),
+            |res| ReadOnlyResult::Ok(res.call_result.clone()),
+        ),
+        gas_cost: result.as_ref().map_or_else(|_| 0, |v| v.gas_cost),
+        output_events: result
+            .as_ref()
+            .map_or_else(|_| Default::default(), |v| v.out.events.clone().0),
+        state_diff: result
+            .as_ref()
+            .map_or_else(|_| Default::default(), readonly_state_diff),
+        state_changes: result.map_or_else(|_| Default::default(), |v| v.out.state_changes),
+    }
 }
 
 #[async_trait]
@@ -130,81 +301,14 @@ impl MassaRpcServer for API<Public> {
         }
 
         let mut res: Vec<ExecuteReadOnlyResponse> = Vec::with_capacity(reqs.len());
-        for ReadOnlyBytecodeExecution {
-            max_gas,
-            address,
-            bytecode,
-            operation_datastore,
-            fee,
-        } in reqs
-        {
-            let address = if let Some(addr) = address {
-                addr
-            } else {
-                let now = MassaTime::now();
-                let keypair = self
-                    .0
-                    .keypair_factory
-                    .create(&(), FactoryStrategy::At(now))
-                    .map_err(ApiError::from)?;
-                Address::from_public_key(&keypair.get_public_key())
-            };
-
-            let op_datastore = match operation_datastore {
-                Some(v) => {
-                    let deserializer = DatastoreDeserializer::new(
-                        self.0.api_settings.max_op_datastore_entry_count,
-                        self.0.api_settings.max_op_datastore_key_length,
-                        self.0.api_settings.max_op_datastore_value_length,
-                    );
-                    match deserializer.deserialize::<DeserializeError>(&v) {
-                        Ok((_, deserialized)) => Some(deserialized),
-                        Err(e) => {
-                            return Err(ApiError::InconsistencyError(format!(
-                                "Operation datastore error: {}",
-                                e
-                            ))
-                            .into())
-                        }
-                    }
-                }
-                None => None,
-            };
-
+        for execution in reqs {
             // translate request
-            let req = ReadOnlyExecutionRequest {
-                max_gas,
-                target: ReadOnlyExecutionTarget::BytecodeExecution(bytecode),
-                call_stack: vec![ExecutionStackElement {
-                    address,
-                    coins: Default::default(),
-                    owned_addresses: vec![address],
-                    operation_datastore: op_datastore,
-                }],
-                coins: None,
-                fee,
-            };
+            let req = self.readonly_bytecode_request(execution)?;
 
             // run
             let result = self.0.execution_controller.execute_readonly_request(req);
 
-            // map result
-            let result = ExecuteReadOnlyResponse {
-                executed_at: result
-                    .as_ref()
-                    .map_or_else(|_| Slot::new(0, 0), |v| v.out.slot),
-                result: result.as_ref().map_or_else(
-                    |err| ReadOnlyResult::Error(format!("readonly call failed: {}", err)),
-                    |res| ReadOnlyResult::Ok(res.call_result.clone()),
-                ),
-                gas_cost: result.as_ref().map_or_else(|_| 0, |v| v.gas_cost),
-                output_events: result
-                    .as_ref()
-                    .map_or_else(|_| Default::default(), |v| v.out.events.clone().0),
-                state_changes: result.map_or_else(|_| Default::default(), |v| v.out.state_changes),
-            };
-
-            res.push(result);
+            res.push(readonly_response(result));
         }
 
         // return result
@@ -221,80 +325,90 @@ impl MassaRpcServer for API<Public> {
         }
 
         let mut res: Vec<ExecuteReadOnlyResponse> = Vec::with_capacity(reqs.len());
-        for ReadOnlyCall {
-            max_gas,
-            target_address,
-            target_function,
-            parameter,
-            caller_address,
-            coins,
-            fee,
-        } in reqs
-        {
-            let caller_address = if let Some(addr) = caller_address {
-                addr
-            } else {
-                let now = MassaTime::now();
-                let keypair = self
-                    .0
-                    .keypair_factory
-                    .create(&(), FactoryStrategy::At(now))
-                    .map_err(ApiError::from)?;
-                Address::from_public_key(&keypair.get_public_key())
-            };
-
+        for call in reqs {
             // translate request
-            let req = ReadOnlyExecutionRequest {
-                max_gas,
-                target: ReadOnlyExecutionTarget::FunctionCall {
-                    target_func: target_function,
-                    target_addr: target_address,
-                    parameter,
-                },
-                call_stack: vec![
-                    ExecutionStackElement {
-                        address: caller_address,
-                        coins: Default::default(),
-                        owned_addresses: vec![caller_address],
-                        operation_datastore: None, // should always be None
-                    },
-                    ExecutionStackElement {
-                        address: target_address,
-                        coins: coins.unwrap_or(Amount::default()),
-                        owned_addresses: vec![target_address],
-                        operation_datastore: None, // should always be None
-                    },
-                ],
-                coins,
-                fee,
-            };
+            let req = self.readonly_call_request(call)?;
 
             // run
             let result = self.0.execution_controller.execute_readonly_request(req);
 
-            // map result
-            let result = ExecuteReadOnlyResponse {
-                executed_at: result
-                    .as_ref()
-                    .map_or_else(|_| Slot::new(0, 0), |v| v.out.slot),
-                result: result.as_ref().map_or_else(
-                    |err| ReadOnlyResult::Error(format!("readonly call failed: {}", err)),
-                    |res| ReadOnlyResult::Ok(res.call_result.clone()),
-                ),
-                gas_cost: result.as_ref().map_or_else(|_| 0, |v| v.gas_cost),
-                output_events: result
-                    .as_ref()
-                    .map_or_else(|_| Default::default(), |v| v.out.events.clone().0),
-                state_changes: result.map_or_else(|_| Default::default(), |v| v.out.state_changes),
-            };
-
-            res.push(result);
+            res.push(readonly_response(result));
         }
 
         // return result
         Ok(res)
     }
 
+    /// execute a bundle of read-only executions
+    async fn execute_read_only_bundle(
+        &self,
+        bundle: ReadOnlyBundle,
+    ) -> RpcResult<Vec<ExecuteReadOnlyResponse>> {
+        if bundle.executions.len() as u64 > self.0.api_settings.max_arguments
+            || bundle.state_overrides.len() as u64 > self.0.api_settings.max_arguments
+        {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+
+        // translate the executions
+        let mut requests = Vec::with_capacity(bundle.executions.len());
+        for execution in bundle.executions {
+            requests.push(match execution {
+                ReadOnlyExecution::Bytecode(execution) => {
+                    self.readonly_bytecode_request(execution)?
+                }
+                ReadOnlyExecution::Call(call) => self.readonly_call_request(call)?,
+            });
+        }
+
+        // translate the state overrides
+        let mut state_overrides = LedgerChanges::default();
+        for StateOverride {
+            address,
+            balance,
+            bytecode,
+            datastore,
+        } in bundle.state_overrides
+        {
+            let update = LedgerEntryUpdate {
+                balance: balance.map_or(SetOrKeep::Keep, SetOrKeep::Set),
+                bytecode: bytecode.map_or(SetOrKeep::Keep, |bytecode| {
+                    SetOrKeep::Set(Bytecode(bytecode))
+                }),
+                datastore: datastore
+                    .into_iter()
+                    .map(|DatastoreEntryOverride { key, value }| {
+                        (key, value.map_or(SetOrDelete::Delete, SetOrDelete::Set))
+                    })
+                    .collect(),
+            };
+            if state_overrides
+                .0
+                .insert(address, SetUpdateOrDelete::Update(update))
+                .is_some()
+            {
+                return Err(ApiError::BadRequest(format!(
+                    "duplicate state override for address {}",
+                    address
+                ))
+                .into());
+            }
+        }
+
+        // run
+        let outputs = self
+            .0
+            .execution_controller
+            .execute_readonly_bundle(ReadOnlyExecutionBundle {
+                requests,
+                state_overrides,
+                slot: bundle.slot,
+            })
+            .map_err(|err| ApiError::ExecutionError(err.to_string()))?;
+
+        Ok(outputs.into_iter().map(readonly_response).collect())
+    }
+
     async fn remove_staking_addresses(&self, _: Vec<Address>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
@@ -311,6 +425,46 @@ impl MassaRpcServer for API<Public> {
         crate::wrong_api::<()>()
     }
 
+    async fn node_bans(&self) -> RpcResult<Vec<NodeBanInfo>> {
+        crate::wrong_api::<Vec<NodeBanInfo>>()
+    }
+
+    async fn node_module_cache_stats(&self) -> RpcResult<ModuleCacheInfo> {
+        crate::wrong_api::<ModuleCacheInfo>()
+    }
+
+    async fn node_module_cache_entries(&self, _: Vec<Hash>) -> RpcResult<Vec<ModuleCacheEntry>> {
+        crate::wrong_api::<Vec<ModuleCacheEntry>>()
+    }
+
+    async fn node_evict_modules(&self, _: Vec<Hash>) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_pin_modules(&self, _: Vec<Hash>) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_unpin_modules(&self, _: Vec<Hash>) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_execution_profile(&self, _: usize) -> RpcResult<ExecutionProfileInfo> {
+        crate::wrong_api::<ExecutionProfileInfo>()
+    }
+
+    async fn node_devnet_next_slot(&self) -> RpcResult<Slot> {
+        crate::wrong_api::<Slot>()
+    }
+
+    async fn node_devnet_fast_forward(&self, _: u64) -> RpcResult<Slot> {
+        crate::wrong_api::<Slot>()
+    }
+
+    async fn get_peers(&self) -> RpcResult<Vec<ConnectionInfo>> {
+        crate::wrong_api::<Vec<ConnectionInfo>>()
+    }
+
     async fn node_unban_by_ip(&self, _: Vec<IpAddr>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
@@ -325,8 +479,12 @@ impl MassaRpcServer for API<Public> {
         let api_settings = self.0.api_settings.clone();
         let protocol_config = self.0.protocol_config.clone();
         let node_id = self.0.node_id;
-        let config = CompactConfig::default();
-        let now = MassaTime::now();
+        let config = CompactConfig {
+            genesis_timestamp: api_settings.genesis_timestamp,
+            t0: api_settings.t0,
+            ..Default::default()
+        };
+        let now = api_settings.clock.now();
 
         let last_slot_result = get_latest_block_slot_at_timestamp(
             api_settings.thread_count,
@@ -407,6 +565,22 @@ impl MassaRpcServer for API<Public> {
             Err(e) => return Err(ApiError::TimeError(e).into()),
         };
 
+        let slot_duration = match api_settings
+            .t0
+            .checked_div_u64(api_settings.thread_count as u64)
+        {
+            Ok(slot_duration) => slot_duration,
+            Err(e) => return Err(ApiError::TimeError(e).into()),
+        };
+        let mip_statuses = self
+            .0
+            .keypair_factory
+            .mip_store
+            .get_mip_vote_status(now, slot_duration)
+            .into_iter()
+            .map(MipStatus::from)
+            .collect();
+
         Ok(NodeStatus {
             node_id,
             node_ip: protocol_config.routable_ip,
@@ -423,6 +597,7 @@ impl MassaRpcServer for API<Public> {
             pool_stats,
             config,
             current_cycle,
+            mip_statuses,
         })
     }
 
@@ -438,7 +613,7 @@ impl MassaRpcServer for API<Public> {
     ) -> RpcResult<PagedVec<(Address, u64)>> {
         let cfg = self.0.api_settings.clone();
 
-        let now = MassaTime::now();
+        let now = cfg.clock.now();
 
         let latest_block_slot_at_timestamp_result = get_latest_block_slot_at_timestamp(
             cfg.thread_count,
@@ -945,6 +1120,273 @@ impl MassaRpcServer for API<Public> {
         Ok(res)
     }
 
+    /// get the staking history of addresses
+    async fn get_staking_history(
+        &self,
+        addresses: Vec<Address>,
+    ) -> RpcResult<Vec<AddressStakingHistory>> {
+        if addresses.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+
+        let mut res = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            let Some(history) = self.0.execution_controller.get_staking_history(&address) else {
+                return Err(ApiError::BadRequest(
+                    "the staking history is not recorded by this node".into(),
+                )
+                .into());
+            };
+            res.push(AddressStakingHistory {
+                address,
+                cycles: history
+                    .into_iter()
+                    .map(|(cycle, record)| StakingCycleHistory {
+                        cycle,
+                        block_success_count: record.block_success_count,
+                        block_failure_count: record.block_failure_count,
+                        endorsement_success_count: record.endorsement_success_count,
+                        endorsement_failure_count: record.endorsement_failure_count,
+                        rewards: record.rewards,
+                        rolls_bought: record.rolls_bought,
+                        rolls_sold: record.rolls_sold,
+                        rolls_slashed: record.rolls_slashed,
+                        deferred_credits_released: record.deferred_credits_released,
+                    })
+                    .collect(),
+            });
+        }
+        Ok(res)
+    }
+
+    async fn get_ledger_analytics(
+        &self,
+        arg: LedgerAnalyticsRequest,
+    ) -> RpcResult<LedgerAnalytics> {
+        if arg.addresses.len() as u64 > self.0.api_settings.max_arguments
+            || arg.rich_list_length as u64 > self.0.api_settings.max_arguments
+        {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+
+        let Some(analytics) = self
+            .0
+            .execution_controller
+            .get_ledger_analytics(arg.rich_list_length, &arg.addresses)
+        else {
+            return Err(ApiError::BadRequest(
+                "the ledger analytics are not maintained by this node".into(),
+            )
+            .into());
+        };
+
+        let balances = analytics.totals.total_balance;
+        Ok(LedgerAnalytics {
+            slot: analytics.slot,
+            supply: SupplyBreakdown {
+                balances,
+                rolls: analytics.rolls_value,
+                deferred_credits: analytics.deferred_credits,
+                total: balances
+                    .saturating_add(analytics.rolls_value)
+                    .saturating_add(analytics.deferred_credits),
+            },
+            address_count: analytics.totals.address_count,
+            datastore_entry_count: analytics.totals.datastore_entry_count,
+            datastore_size: analytics.totals.datastore_size,
+            rich_list: analytics
+                .rich_list
+                .into_iter()
+                .map(|(address, balance)| AddressBalance { address, balance })
+                .collect(),
+            entries: arg
+                .addresses
+                .into_iter()
+                .zip(analytics.entries)
+                .map(|(address, entry)| {
+                    entry.map(|(entry, datastore_storage_cost)| LedgerEntryStorage {
+                        address,
+                        balance: entry.balance,
+                        datastore_entry_count: entry.datastore_entry_count,
+                        datastore_size: entry.datastore_size,
+                        datastore_storage_cost,
+                    })
+                })
+                .collect(),
+        })
+    }
+
+    async fn get_ledger_tree_paths(
+        &self,
+        arg: Vec<LedgerTreePathInput>,
+    ) -> RpcResult<LedgerTreeItems> {
+        if arg.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+
+        let items: Vec<_> = arg
+            .into_iter()
+            .map(|input| (input.address, input.item))
+            .collect();
+        let Some(paths) = self.0.execution_controller.get_ledger_tree_paths(&items) else {
+            return Err(ApiError::BadRequest(
+                "the ledger Merkle tree is not maintained by this node, or is being updated".into(),
+            )
+            .into());
+        };
+
+        Ok(LedgerTreeItems {
+            slot: paths.slot,
+            local_root: paths.local_root,
+            items: items
+                .into_iter()
+                .zip(paths.paths)
+                .map(|((address, item), (value, path))| LedgerTreeItemPath {
+                    address,
+                    item,
+                    value,
+                    path,
+                })
+                .collect(),
+        })
+    }
+
+    /// get the next draws of addresses and their draw statistics
+    async fn get_draws_lookahead(&self, arg: DrawsLookaheadRequest) -> RpcResult<DrawsLookahead> {
+        if arg.addresses.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+        let addresses: PreHashSet<Address> = arg.addresses.iter().copied().collect();
+
+        let start = match arg.start {
+            Some(slot) => slot,
+            None => timeslots::get_current_latest_block_slot(
+                self.0.api_settings.thread_count,
+                self.0.api_settings.t0,
+                self.0.api_settings.genesis_timestamp,
+            )
+            .map_err(ApiError::ModelsError)?
+            .unwrap_or_else(|| Slot::new(0, 0)),
+        };
+        let max_count = arg
+            .count
+            .map_or(self.0.api_settings.max_draws_lookahead_count, |count| {
+                count.min(self.0.api_settings.max_draws_lookahead_count)
+            })
+            .try_into()
+            .unwrap_or(usize::MAX);
+
+        let selections = self
+            .0
+            .selector_controller
+            .get_next_selections(start, &addresses, max_count)
+            .map_err(|err| ApiError::InternalServerError(err.to_string()))?;
+        let draw_stats = self
+            .0
+            .selector_controller
+            .get_draw_stats(&addresses)
+            .map_err(|err| ApiError::InternalServerError(err.to_string()))?;
+
+        let draws = selections
+            .into_iter()
+            .map(|(slot, selection)| SlotDraws {
+                slot,
+                block_producer: Some(selection.producer)
+                    .filter(|producer| addresses.contains(producer)),
+                endorsements: selection
+                    .endorsements
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(_, address)| addresses.contains(address))
+                    .map(|(index, address)| EndorserDraw { index, address })
+                    .collect(),
+            })
+            .collect();
+        let available_cycles = draw_stats.keys().copied().collect();
+        let stats = draw_stats
+            .into_iter()
+            .flat_map(|(cycle, cycle_stats)| {
+                cycle_stats
+                    .into_iter()
+                    .map(move |(address, stats)| AddressDrawStats {
+                        address,
+                        cycle,
+                        rolls: stats.rolls,
+                        total_rolls: stats.total_rolls,
+                        block_draws: stats.block_draws,
+                        expected_block_draws: stats.expected_block_draws(),
+                        endorsement_draws: stats.endorsement_draws,
+                        expected_endorsement_draws: stats.expected_endorsement_draws(),
+                    })
+            })
+            .collect();
+
+        Ok(DrawsLookahead {
+            available_cycles,
+            draws,
+            stats,
+        })
+    }
+
+    /// get the asynchronous messages of the candidate pool
+    async fn get_async_messages(
+        &self,
+        arg: AsyncMessagesRequest,
+    ) -> RpcResult<Vec<AsyncPoolMessage>> {
+        if arg.sender.is_none() && arg.destination.is_none() {
+            return Err(
+                ApiError::BadRequest("no sender nor destination specified".to_string()).into(),
+            );
+        }
+        let max_results = self.0.api_settings.max_arguments as usize;
+        let (limit, offset) = match arg.page_request {
+            Some(PageRequest { limit, offset }) => (limit, offset),
+            None => (max_results, 0),
+        };
+        if limit > max_results {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+        let filter = AsyncMessageFilter {
+            sender: arg.sender,
+            destination: arg.destination,
+            offset: offset.saturating_mul(limit),
+            limit,
+        };
+
+        let messages = match self
+            .0
+            .execution_controller
+            .query_state(ExecutionQueryRequest {
+                requests: vec![ExecutionQueryRequestItem::AsyncMessagesCandidate(filter)],
+            })
+            .responses
+            .pop()
+        {
+            Some(Ok(ExecutionQueryResponseItem::AsyncMessages(messages))) => messages,
+            Some(Ok(_)) => {
+                return Err(
+                    ApiError::InternalServerError("unexpected response type".to_string()).into(),
+                )
+            }
+            Some(Err(err)) => return Err(ApiError::InternalServerError(err.to_string()).into()),
+            None => {
+                return Err(ApiError::InternalServerError(
+                    "missing execution query response".to_string(),
+                )
+                .into())
+            }
+        };
+
+        Ok(messages
+            .into_iter()
+            .map(|message| AsyncPoolMessage {
+                is_final: message.is_final,
+                message: message.message,
+                status: message.status,
+            })
+            .collect())
+    }
+
     /// get addresses bytecode
     async fn get_addresses_bytecode(&self, args: Vec<AddressFilter>) -> RpcResult<Vec<Vec<u8>>> {
         let queries = args
@@ -998,7 +1440,7 @@ impl MassaRpcServer for API<Public> {
         if ops.len() as u64 > api_cfg.max_arguments {
             return Err(ApiError::BadRequest("too many arguments".into()).into());
         }
-        let now = MassaTime::now();
+        let now = api_cfg.clock.now();
         let last_slot = get_latest_block_slot_at_timestamp(
             api_cfg.thread_count,
             api_cfg.t0,
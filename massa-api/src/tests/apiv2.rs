@@ -7,17 +7,22 @@ use jsonrpsee::{
     rpc_params,
     ws_client::WsClientBuilder,
 };
+use massa_api_exports::roll_manager::{RollManagerAction, RollManagerEvent};
+use massa_async_pool::{AsyncMessageEvent, AsyncMessageOutcome};
 use massa_consensus_exports::MockConsensusController;
 use massa_execution_exports::MockExecutionController;
 use massa_models::{
     address::Address,
+    amount::Amount,
     block::{FilledBlock, SecureShareBlock},
     block_header::BlockHeader,
     block_id::BlockId,
     config::VERSION,
     operation::SecureShareOperation,
     secure_share::SecureShare,
+    slot::Slot,
 };
+use massa_pos_exports::CycleDrawsNotification;
 use massa_protocol_exports::test_exports::tools::{
     create_block, create_operation_with_expire_period,
 };
@@ -333,3 +338,173 @@ async fn subscribe_new_operations() {
 
     api_handle.stop().await;
 }
+
+#[tokio::test]
+async fn subscribe_new_cycle_draws() {
+    let addr: SocketAddr = "[::]:5044".parse().unwrap();
+    let (mut api_server, api_config) = get_apiv2_server(&addr);
+
+    let uri = Url::parse(&format!(
+        "ws://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .unwrap();
+    let (tx, _rx) = tokio::sync::broadcast::channel::<CycleDrawsNotification>(10);
+
+    api_server.0.selector_channels.cycle_draws_sender = tx.clone();
+
+    let api_handle = api_server
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start MASSA API V2");
+
+    let client1 = WsClientBuilder::default().build(&uri).await.unwrap();
+    let mut sub1: Subscription<Value> = client1
+        .subscribe(
+            "subscribe_new_cycle_draws",
+            rpc_params![],
+            "unsubscribe_new_cycle_draws",
+        )
+        .await
+        .unwrap();
+
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let _ = tx
+            .send(CycleDrawsNotification {
+                cycle: 3,
+                first_slot: Slot::new(384, 0),
+                last_slot: Slot::new(511, 31),
+            })
+            .unwrap();
+    });
+
+    let result = tokio::time::timeout(Duration::from_secs(4), sub1.next())
+        .await
+        .unwrap();
+
+    let obj = result.unwrap().unwrap();
+    assert_eq!(obj["cycle"].as_u64().unwrap(), 3);
+    assert_eq!(obj["first_slot"]["period"].as_u64().unwrap(), 384);
+
+    api_handle.stop().await;
+}
+
+#[tokio::test]
+async fn subscribe_async_message_events() {
+    let addr: SocketAddr = "[::]:5047".parse().unwrap();
+    let (mut api_server, api_config) = get_apiv2_server(&addr);
+
+    let uri = Url::parse(&format!(
+        "ws://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .unwrap();
+    let (tx, _rx) = tokio::sync::broadcast::channel::<AsyncMessageEvent>(10);
+
+    api_server.0.execution_channels.async_message_event_sender = tx.clone();
+
+    let api_handle = api_server
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start MASSA API V2");
+
+    let client1 = WsClientBuilder::default().build(&uri).await.unwrap();
+    let mut sub1: Subscription<Value> = client1
+        .subscribe(
+            "subscribe_async_message_events",
+            rpc_params![],
+            "unsubscribe_async_message_events",
+        )
+        .await
+        .unwrap();
+
+    let address =
+        Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let _ = tx
+            .send(AsyncMessageEvent {
+                slot: Slot::new(12, 3),
+                emission_slot: Slot::new(10, 0),
+                emission_index: 2,
+                sender: address,
+                destination: address,
+                function: "receive".to_string(),
+                outcome: AsyncMessageOutcome::Expired,
+            })
+            .unwrap();
+    });
+
+    let result = tokio::time::timeout(Duration::from_secs(4), sub1.next())
+        .await
+        .unwrap();
+
+    let obj = result.unwrap().unwrap();
+    assert_eq!(obj["emission_index"].as_u64().unwrap(), 2);
+    assert_eq!(obj["outcome"].as_str().unwrap(), "Expired");
+
+    api_handle.stop().await;
+}
+
+#[tokio::test]
+async fn subscribe_roll_manager_events() {
+    let addr: SocketAddr = "[::]:5051".parse().unwrap();
+    let (mut api_server, api_config) = get_apiv2_server(&addr);
+
+    let uri = Url::parse(&format!(
+        "ws://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .unwrap();
+    let (tx, _rx) = tokio::sync::broadcast::channel::<RollManagerEvent>(10);
+
+    api_server.0.roll_manager_event_sender = tx.clone();
+
+    let api_handle = api_server
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start MASSA API V2");
+
+    let client1 = WsClientBuilder::default().build(&uri).await.unwrap();
+    let mut sub1: Subscription<Value> = client1
+        .subscribe(
+            "subscribe_roll_manager_events",
+            rpc_params![],
+            "unsubscribe_roll_manager_events",
+        )
+        .await
+        .unwrap();
+
+    let keypair = KeyPair::generate(0).unwrap();
+    let operation = create_operation_with_expire_period(&keypair, 10);
+    let address = Address::from_public_key(&keypair.get_public_key());
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let _ = tx
+            .send(RollManagerEvent {
+                address,
+                operation_id: operation.id,
+                action: RollManagerAction::Buy,
+                roll_count: 2,
+                candidate_balance: Amount::from_str("250").unwrap(),
+                candidate_roll_count: 1,
+            })
+            .unwrap();
+    });
+
+    let result = tokio::time::timeout(Duration::from_secs(4), sub1.next())
+        .await
+        .unwrap();
+
+    let obj = result.unwrap().unwrap();
+    assert_eq!(obj["address"].as_str().unwrap(), address.to_string());
+    assert_eq!(
+        obj["operation_id"].as_str().unwrap(),
+        operation.id.to_string()
+    );
+    assert_eq!(obj["action"].as_str().unwrap(), "Buy");
+    assert_eq!(obj["roll_count"].as_u64().unwrap(), 2);
+
+    api_handle.stop().await;
+}
@@ -6,22 +6,23 @@ use std::{collections::HashMap, net::SocketAddr};
 
 use massa_api_exports::config::APIConfig;
 use massa_consensus_exports::{ConsensusBroadcasts, MockConsensusController};
-use massa_execution_exports::{GasCosts, MockExecutionController};
+use massa_execution_exports::{ExecutionChannels, GasCosts, MockExecutionController};
 use massa_models::{
     config::{
-        BASE_OPERATION_GAS_COST, ENDORSEMENT_COUNT, GENESIS_TIMESTAMP, MAX_DATASTORE_VALUE_LENGTH,
-        MAX_FUNCTION_NAME_LENGTH, MAX_GAS_PER_BLOCK, MAX_MESSAGE_SIZE,
-        MAX_OPERATION_DATASTORE_ENTRY_COUNT, MAX_OPERATION_DATASTORE_KEY_LENGTH,
+        BASE_OPERATION_GAS_COST, COMPACT_BLOCK_RELAY_MIN_VERSION, ENDORSEMENT_COUNT,
+        GENESIS_TIMESTAMP, MAX_DATASTORE_VALUE_LENGTH, MAX_FUNCTION_NAME_LENGTH, MAX_GAS_PER_BLOCK,
+        MAX_MESSAGE_SIZE, MAX_OPERATION_DATASTORE_ENTRY_COUNT, MAX_OPERATION_DATASTORE_KEY_LENGTH,
         MAX_OPERATION_DATASTORE_VALUE_LENGTH, MAX_PARAMETERS_SIZE,
-        MIP_STORE_STATS_BLOCK_CONSIDERED, PERIODS_PER_CYCLE, T0, THREAD_COUNT, VERSION,
+        MIP_STORE_STATS_BLOCK_CONSIDERED, OPERATION_RECONCILIATION_MIN_VERSION, PERIODS_PER_CYCLE,
+        T0, THREAD_COUNT, VERSION,
     },
     node::NodeId,
 };
 use massa_pool_exports::{MockPoolController, PoolBroadcasts};
-use massa_pos_exports::MockSelectorController;
+use massa_pos_exports::{MockSelectorController, SelectorChannels};
 use massa_protocol_exports::{MockProtocolController, PeerCategoryInfo, ProtocolConfig};
 use massa_signature::KeyPair;
-use massa_time::MassaTime;
+use massa_time::{MassaTime, SharedClock};
 use massa_versioning::versioning::{MipStatsConfig, MipStore};
 use num::rational::Ratio;
 use tempfile::NamedTempFile;
@@ -37,6 +38,7 @@ pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
         bind_api: *addr,
         draw_lookahead_period_count: 10,
         max_arguments: 128,
+        max_draws_lookahead_count: 1000,
         openrpc_spec_path: "base_config/openrpc.json".parse().unwrap(),
         bootstrap_whitelist_path: "base_config/bootstrap_whitelist.json".parse().unwrap(),
         bootstrap_blacklist_path: "base_config/bootstrap_blacklist.json".parse().unwrap(),
@@ -65,6 +67,7 @@ pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
         t0: T0,
         periods_per_cycle: PERIODS_PER_CYCLE,
         last_start_period: 0,
+        clock: SharedClock::default(),
     };
 
     // let shared_storage: massa_storage::Storage = massa_storage::Storage::create_root();
@@ -90,11 +93,23 @@ pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
         filled_block_sender: broadcast::channel(100).0,
     };
 
+    let selector_channels = SelectorChannels {
+        cycle_draws_sender: broadcast::channel(100).0,
+    };
+
+    let execution_channels = ExecutionChannels {
+        slot_execution_output_sender: broadcast::channel(100).0,
+        async_message_event_sender: broadcast::channel(100).0,
+    };
+
     let api = API::<ApiV2>::new(
         Box::new(consensus_ctrl),
         consensus_broadcasts,
         Box::new(exec_ctrl),
+        execution_channels,
         pool_broadcasts,
+        selector_channels,
+        broadcast::channel(100).0,
         api_config.clone(),
         *VERSION,
     );
@@ -110,6 +125,7 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
         bind_api: "[::]:0".parse().unwrap(),
         draw_lookahead_period_count: 10,
         max_arguments: 128,
+        max_draws_lookahead_count: 1000,
         openrpc_spec_path: "base_config/openrpc.json".parse().unwrap(),
         bootstrap_whitelist_path: "base_config/bootstrap_whitelist.json".parse().unwrap(),
         bootstrap_blacklist_path: "base_config/bootstrap_blacklist.json".parse().unwrap(),
@@ -138,6 +154,7 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
         t0: T0,
         periods_per_cycle: PERIODS_PER_CYCLE,
         last_start_period: 0,
+        clock: SharedClock::default(),
     };
 
     let shared_storage: massa_storage::Storage = massa_storage::Storage::create_root();
@@ -226,6 +243,8 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
             max_op_datastore_key_length: u8::MAX,
             max_op_datastore_value_length: 1000000,
             max_endorsements_per_message: 1000,
+            max_draws_per_message: 512,
+            headers_only: false,
             max_size_listeners_per_peer: 100,
             max_size_peers_announcement: 100,
             message_timeout: MassaTime::from_millis(10000),
@@ -234,7 +253,8 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
             read_write_limit_bytes_per_second: 1024 * 1000,
             timeout_connection: MassaTime::from_millis(1000),
             try_connection_timer: MassaTime::from_millis(5000),
-            unban_everyone_timer: MassaTime::from_millis(3600000),
+            ban_duration: MassaTime::from_millis(3600000),
+            max_ban_duration: MassaTime::from_millis(3600000),
             routable_ip: None,
             max_in_connections: 10,
             debug: true,
@@ -249,6 +269,16 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
             try_connection_timer_same_peer: MassaTime::from_millis(1000),
             test_oldest_peer_cooldown: MassaTime::from_millis(720000),
             rate_limit: 1024 * 1024 * 2,
+            rate_limit_blocks: 0,
+            rate_limit_endorsements: 0,
+            rate_limit_operations: 0,
+            rate_limit_ban_threshold: 0,
+            compact_block_relay: false,
+            compact_block_relay_min_version: *COMPACT_BLOCK_RELAY_MIN_VERSION,
+            operation_reconciliation: false,
+            operation_reconciliation_interval: MassaTime::from_millis(1000),
+            operation_reconciliation_min_version: *OPERATION_RECONCILIATION_MIN_VERSION,
+            clock: SharedClock::default(),
         },
         *VERSION,
         NodeId::new(keypair.get_public_key()),
@@ -13,25 +13,39 @@ use jsonrpsee::{
     rpc_params,
 };
 use massa_api_exports::{
-    address::{AddressFilter, AddressInfo},
+    address::{AddressFilter, AddressInfo, AddressStakingHistory},
+    async_pool::{AsyncMessagesRequest, AsyncPoolMessage},
     block::{BlockInfo, BlockSummary},
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
+    draws::{DrawsLookahead, DrawsLookaheadRequest},
     endorsement::EndorsementInfo,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
+    execution::{
+        DatastoreEntryOverride, ExecuteReadOnlyResponse, ReadOnlyBundle, ReadOnlyBytecodeExecution,
+        ReadOnlyCall, ReadOnlyExecution, ReadOnlyResult, StateOverride,
+    },
+    ledger::{LedgerAnalytics, LedgerAnalyticsRequest, LedgerTreeItems, LedgerTreePathInput},
     operation::{OperationInfo, OperationInput},
+    page::PageRequest,
     TimeInterval,
 };
 use massa_consensus_exports::{
     block_graph_export::BlockGraphExport, block_status::ExportCompiledBlock,
     MockConsensusController,
 };
+use massa_hash::Hash;
+use massa_ledger_exports::{
+    LedgerAnalyticsTotals, LedgerEntryAnalytics, LedgerEntryUpdate, SetOrDelete, SetOrKeep,
+    SetUpdateOrDelete,
+};
 use massa_pool_exports::MockPoolController;
-use massa_pos_exports::MockSelectorController;
+use massa_pos_exports::{CycleDrawStats, MockSelectorController, Selection, StakingCycleRecord};
 
 use crate::{tests::mock::start_public_api, RpcServer};
+use massa_async_pool::{AsyncMessage, AsyncMessageStatus};
 use massa_execution_exports::{
-    ExecutionAddressInfo, ExecutionQueryResponse, ExecutionQueryResponseItem,
-    MockExecutionController, ReadOnlyExecutionOutput,
+    ExecutionAddressInfo, ExecutionLedgerAnalytics, ExecutionQueryAsyncMessage,
+    ExecutionQueryRequestItem, ExecutionQueryResponse, ExecutionQueryResponseItem,
+    MockExecutionController, ReadOnlyExecutionOutput, ReadOnlyPreviousValues,
 };
 use massa_models::{
     address::Address,
@@ -41,6 +55,9 @@ use massa_models::{
     clique::Clique,
     endorsement::EndorsementId,
     execution::EventFilter,
+    ledger_tree::{
+        matches_local_root, LedgerTreeItem, LedgerTreePaths, SparseMerkleNode, SparseMerklePath,
+    },
     node::NodeId,
     operation::OperationId,
     output_event::SCOutputEvent,
@@ -96,6 +113,7 @@ async fn get_status() {
                 known_peer_count: 6,
                 banned_peer_count: 0,
                 active_node_count: 15,
+                traffic: Default::default(),
             },
             HashMap::new(),
         ))
@@ -128,6 +146,7 @@ async fn get_status() {
     assert_eq!(response.network_stats.in_connection_count, 10);
     assert_eq!(response.network_stats.out_connection_count, 5);
     assert_eq!(response.config.thread_count, 32);
+    assert!(response.mip_statuses.is_empty());
 
     api_public_handle.stop().await;
 }
@@ -629,9 +648,12 @@ async fn execute_read_only_bytecode() {
                     block_info: None,
                     state_changes: massa_final_state::StateChanges::default(),
                     events: massa_execution_exports::EventStore::default(),
+                    staking_history_changes: Default::default(),
+                    async_message_events: Default::default(),
                 },
                 gas_cost: 100,
                 call_result: "toto".as_bytes().to_vec(),
+                previous_values: Default::default(),
             })
         });
 
@@ -709,9 +731,12 @@ async fn execute_read_only_call() {
                     block_info: None,
                     state_changes: massa_final_state::StateChanges::default(),
                     events: massa_execution_exports::EventStore::default(),
+                    staking_history_changes: Default::default(),
+                    async_message_events: Default::default(),
                 },
                 gas_cost: 100,
                 call_result: "toto".as_bytes().to_vec(),
+                previous_values: Default::default(),
             })
         });
 
@@ -754,6 +779,140 @@ async fn execute_read_only_call() {
     api_public_handle.stop().await;
 }
 
+#[tokio::test]
+async fn execute_read_only_bundle() {
+    let addr: SocketAddr = "[::]:5050".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let overridden_addr =
+        Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_execute_readonly_bundle()
+        .returning(move |bundle| {
+            assert_eq!(bundle.requests.len(), 2);
+            assert_eq!(bundle.slot, Some(Slot::new(3, 0)));
+            let update = match bundle.state_overrides.0.get(&overridden_addr) {
+                Some(SetUpdateOrDelete::Update(update)) => update,
+                _ => panic!("missing state override"),
+            };
+            assert_eq!(
+                update.balance,
+                SetOrKeep::Set(Amount::from_str("1000").unwrap())
+            );
+            assert_eq!(
+                update.datastore.get(&b"key".to_vec()),
+                Some(&SetOrDelete::Delete)
+            );
+            let mut state_changes = massa_final_state::StateChanges::default();
+            state_changes.ledger_changes.0.insert(
+                overridden_addr,
+                SetUpdateOrDelete::Update(LedgerEntryUpdate {
+                    balance: SetOrKeep::Set(Amount::from_str("990").unwrap()),
+                    bytecode: SetOrKeep::Keep,
+                    datastore: BTreeMap::from([(b"key".to_vec(), SetOrDelete::Delete)]),
+                }),
+            );
+            let mut previous_values = ReadOnlyPreviousValues::default();
+            previous_values
+                .balances
+                .insert(overridden_addr, Some(Amount::from_str("1000").unwrap()));
+            previous_values
+                .datastore_entries
+                .insert((overridden_addr, b"key".to_vec()), Some(b"value".to_vec()));
+            Ok(vec![
+                Ok(ReadOnlyExecutionOutput {
+                    out: massa_execution_exports::ExecutionOutput {
+                        slot: Slot::new(3, 0),
+                        block_info: None,
+                        state_changes,
+                        events: massa_execution_exports::EventStore::default(),
+                        staking_history_changes: Default::default(),
+                        async_message_events: Default::default(),
+                    },
+                    gas_cost: 100,
+                    call_result: Vec::new(),
+                    previous_values,
+                }),
+                Err(massa_execution_exports::ExecutionError::RuntimeError(
+                    "failed".to_string(),
+                )),
+            ])
+        });
+
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let call = ReadOnlyCall {
+        max_gas: 1000000,
+        target_address: Address::from_str("AS12mzL2UWroPV7zzHpwHnnF74op9Gtw7H55fAmXMnCuVZTFSjZCA")
+            .unwrap(),
+        target_function: "hello".to_string(),
+        parameter: vec![],
+        caller_address: Some(overridden_addr),
+        fee: None,
+        coins: None,
+    };
+    let state_override = StateOverride {
+        address: overridden_addr,
+        balance: Some(Amount::from_str("1000").unwrap()),
+        bytecode: None,
+        datastore: vec![DatastoreEntryOverride {
+            key: b"key".to_vec(),
+            value: None,
+        }],
+    };
+    let bundle = ReadOnlyBundle {
+        executions: vec![
+            ReadOnlyExecution::Call(call.clone()),
+            ReadOnlyExecution::Call(call),
+        ],
+        state_overrides: vec![state_override.clone()],
+        slot: Some(Slot::new(3, 0)),
+    };
+
+    let response: Vec<ExecuteReadOnlyResponse> = client
+        .request("execute_read_only_bundle", rpc_params![bundle.clone()])
+        .await
+        .unwrap();
+    assert_eq!(response.len(), 2);
+    assert!(matches!(response[0].result, ReadOnlyResult::Ok(_)));
+    assert!(matches!(response[1].result, ReadOnlyResult::Error(_)));
+    let diff = response[0]
+        .state_diff
+        .addresses
+        .get(&overridden_addr)
+        .expect("missing state diff");
+    assert_eq!(
+        diff.previous_balance,
+        Some(Amount::from_str("1000").unwrap())
+    );
+    assert_eq!(diff.datastore[0].previous_value, Some(b"value".to_vec()));
+
+    // an address cannot be overridden twice
+    let duplicated = ReadOnlyBundle {
+        state_overrides: vec![state_override.clone(), state_override],
+        ..bundle
+    };
+    let response: Result<Vec<ExecuteReadOnlyResponse>, Error> = client
+        .request("execute_read_only_bundle", rpc_params![duplicated])
+        .await;
+    assert!(response.is_err());
+
+    api_public_handle.stop().await;
+}
+
 #[tokio::test]
 async fn get_addresses() {
     let addr: SocketAddr = "[::]:5010".parse().unwrap();
@@ -814,6 +973,481 @@ async fn get_addresses() {
     api_public_handle.stop().await;
 }
 
+#[tokio::test]
+async fn get_staking_history() {
+    let addr: SocketAddr = "[::]:5043".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl.expect_get_staking_history().returning(|_addr| {
+        Some(BTreeMap::from([
+            (
+                3,
+                StakingCycleRecord {
+                    block_success_count: 2,
+                    rewards: Amount::from_str("1.5").unwrap(),
+                    ..Default::default()
+                },
+            ),
+            (
+                4,
+                StakingCycleRecord {
+                    block_failure_count: 1,
+                    rolls_slashed: 1,
+                    ..Default::default()
+                },
+            ),
+        ]))
+    });
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let params = rpc_params![vec![Address::from_str(
+        "AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x"
+    )
+    .unwrap()]];
+    let response: Vec<AddressStakingHistory> = client
+        .request("get_staking_history", params.clone())
+        .await
+        .unwrap();
+
+    assert_eq!(response.len(), 1);
+    let cycles: Vec<u64> = response[0].cycles.iter().map(|c| c.cycle).collect();
+    assert_eq!(cycles, vec![3, 4]);
+    assert_eq!(
+        response[0].cycles[0].rewards,
+        Amount::from_str("1.5").unwrap()
+    );
+    assert_eq!(response[0].cycles[1].rolls_slashed, 1);
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_ledger_analytics() {
+    let addr: SocketAddr = "[::]:5048".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let address =
+        Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let unknown_address =
+        Address::from_str("AU12htxRWiEm8jDJpJptr6cwEhWNcCSFWstN1MLSa96DDkVM9Y42G").unwrap();
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_get_ledger_analytics()
+        .returning(move |rich_list_length, addresses| {
+            assert_eq!(rich_list_length, 1);
+            assert_eq!(addresses.len(), 2);
+            Some(ExecutionLedgerAnalytics {
+                slot: Slot::new(10, 3),
+                totals: LedgerAnalyticsTotals {
+                    address_count: 2,
+                    total_balance: Amount::from_str("1000").unwrap(),
+                    datastore_entry_count: 4,
+                    datastore_size: 100,
+                },
+                rolls_value: Amount::from_str("300").unwrap(),
+                deferred_credits: Amount::from_str("50").unwrap(),
+                rich_list: vec![(address, Amount::from_str("900").unwrap())],
+                entries: vec![
+                    Some((
+                        LedgerEntryAnalytics {
+                            balance: Amount::from_str("900").unwrap(),
+                            datastore_entry_count: 4,
+                            datastore_size: 100,
+                        },
+                        Amount::from_str("0.1").unwrap(),
+                    )),
+                    None,
+                ],
+            })
+        });
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let params = rpc_params![LedgerAnalyticsRequest {
+        rich_list_length: 1,
+        addresses: vec![address, unknown_address],
+    }];
+    let response: LedgerAnalytics = client
+        .request("get_ledger_analytics", params)
+        .await
+        .unwrap();
+
+    assert_eq!(response.supply.total, Amount::from_str("1350").unwrap());
+    assert_eq!(response.address_count, 2);
+    assert_eq!(response.rich_list.len(), 1);
+    assert_eq!(response.rich_list[0].address, address);
+    assert_eq!(response.entries.len(), 2);
+    let entry = response.entries[0].unwrap();
+    assert_eq!(entry.address, address);
+    assert_eq!(entry.datastore_size, 100);
+    assert_eq!(
+        entry.datastore_storage_cost,
+        Amount::from_str("0.1").unwrap()
+    );
+    assert!(response.entries[1].is_none());
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_ledger_tree_paths() {
+    let addr: SocketAddr = "[::]:5049".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let address =
+        Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let balance_key = LedgerTreeItem::Balance.tree_key(&address);
+    let leaf = SparseMerkleNode::new_leaf(balance_key, b"balance");
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_get_ledger_tree_paths()
+        .returning(move |items| {
+            assert_eq!(items.len(), 2);
+            // a ledger holding the balance only: its leaf is the root
+            Some(LedgerTreePaths {
+                slot: Slot::new(10, 3),
+                local_root: leaf.hash(),
+                paths: vec![
+                    (
+                        Some(b"balance".to_vec()),
+                        SparseMerklePath {
+                            siblings: vec![],
+                            leaf: Some((balance_key, Hash::compute_from(b"balance"))),
+                        },
+                    ),
+                    (
+                        None,
+                        SparseMerklePath {
+                            siblings: vec![],
+                            leaf: Some((balance_key, Hash::compute_from(b"balance"))),
+                        },
+                    ),
+                ],
+            })
+        });
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let params = rpc_params![vec![
+        LedgerTreePathInput {
+            address,
+            item: LedgerTreeItem::Balance,
+        },
+        LedgerTreePathInput {
+            address,
+            item: LedgerTreeItem::Datastore(b"key".to_vec()),
+        },
+    ]];
+    let response: LedgerTreeItems = client
+        .request("get_ledger_tree_paths", params)
+        .await
+        .unwrap();
+
+    assert_eq!(response.slot, Slot::new(10, 3));
+    assert_eq!(response.items.len(), 2);
+    for item_path in response.items.iter() {
+        assert!(matches_local_root(
+            &response.local_root,
+            &item_path.address,
+            &item_path.item,
+            item_path.value.as_deref(),
+            &item_path.path
+        ));
+    }
+    assert_eq!(
+        response.items[1].item,
+        LedgerTreeItem::Datastore(b"key".to_vec())
+    );
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_draws_lookahead() {
+    let addr: SocketAddr = "[::]:5045".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let address =
+        Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let other_address =
+        Address::from_str("AU12htxRWiEm8jDJpJptr6cwEhWNcCSFWstN1MLSa96DDkVM9Y42G").unwrap();
+
+    let mut selector_ctrl = MockSelectorController::new();
+    selector_ctrl
+        .expect_get_next_selections()
+        .withf(|_, _, max_count| *max_count == 2)
+        .times(1)
+        .returning(move |from_slot, _addrs, _max_count| {
+            assert_eq!(from_slot, Slot::new(130, 0));
+            Ok(BTreeMap::from([
+                (
+                    Slot::new(130, 4),
+                    Selection {
+                        endorsements: vec![other_address, address, address],
+                        producer: other_address,
+                    },
+                ),
+                (
+                    Slot::new(131, 2),
+                    Selection {
+                        endorsements: vec![other_address; 3],
+                        producer: address,
+                    },
+                ),
+            ]))
+        });
+    // the count is capped by the node settings
+    selector_ctrl
+        .expect_get_next_selections()
+        .withf(|_, _, max_count| *max_count == 1000)
+        .times(2)
+        .returning(|_, _, _| Ok(BTreeMap::new()));
+    selector_ctrl
+        .expect_get_draw_stats()
+        .returning(move |_addrs| {
+            Ok(BTreeMap::from([(
+                1,
+                BTreeMap::from([(
+                    address,
+                    CycleDrawStats {
+                        rolls: 10,
+                        total_rolls: 40,
+                        block_slots: 4096,
+                        endorsement_slots: 65536,
+                        block_draws: 1000,
+                        endorsement_draws: 16500,
+                    },
+                )]),
+            )]))
+        });
+    api_public.0.selector_controller = Box::new(selector_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let params = rpc_params![DrawsLookaheadRequest {
+        addresses: vec![address],
+        start: Some(Slot::new(130, 0)),
+        count: Some(2),
+    }];
+    let response: DrawsLookahead = client
+        .request("get_draws_lookahead", params.clone())
+        .await
+        .unwrap();
+
+    assert_eq!(response.available_cycles, vec![1]);
+    assert_eq!(response.draws.len(), 2);
+    assert_eq!(response.draws[0].block_producer, None);
+    let indexes: Vec<usize> = response.draws[0]
+        .endorsements
+        .iter()
+        .map(|draw| draw.index)
+        .collect();
+    assert_eq!(indexes, vec![1, 2]);
+    assert_eq!(response.draws[1].block_producer, Some(address));
+    assert!(response.draws[1].endorsements.is_empty());
+    assert_eq!(response.stats.len(), 1);
+    assert_eq!(response.stats[0].expected_block_draws, 1024.0);
+    assert_eq!(response.stats[0].expected_endorsement_draws, 16384.0);
+
+    for count in [None, Some(u64::MAX)] {
+        let params = rpc_params![DrawsLookaheadRequest {
+            addresses: vec![address],
+            start: Some(Slot::new(130, 0)),
+            count,
+        }];
+        let response: DrawsLookahead = client.request("get_draws_lookahead", params).await.unwrap();
+        assert!(response.draws.is_empty());
+    }
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_async_messages() {
+    let addr: SocketAddr = "[::]:5046".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let sender =
+        Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let destination =
+        Address::from_str("AU12htxRWiEm8jDJpJptr6cwEhWNcCSFWstN1MLSa96DDkVM9Y42G").unwrap();
+    let message = |emission_index: u64, can_be_executed: bool| {
+        AsyncMessage::new(
+            Slot::new(1, 0),
+            emission_index,
+            sender,
+            destination,
+            "receive".to_string(),
+            100_000,
+            Amount::from_str("0.1").unwrap(),
+            Amount::zero(),
+            Slot::new(2, 0),
+            Slot::new(10, 0),
+            vec![],
+            None,
+            Some(can_be_executed),
+        )
+    };
+    let executable = message(0, true);
+    let triggered = message(1, false);
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_query_state()
+        .times(2)
+        .returning(move |request| {
+            assert_eq!(request.requests.len(), 1);
+            let ExecutionQueryRequestItem::AsyncMessagesCandidate(filter) = &request.requests[0]
+            else {
+                panic!("unexpected request");
+            };
+            assert_eq!(filter.sender, Some(sender));
+            assert!(filter.destination.is_none());
+            let messages = vec![
+                ExecutionQueryAsyncMessage {
+                    id: executable.compute_id(),
+                    message: executable.clone(),
+                    status: AsyncMessageStatus::Executable,
+                    is_final: true,
+                },
+                ExecutionQueryAsyncMessage {
+                    id: triggered.compute_id(),
+                    message: triggered.clone(),
+                    status: AsyncMessageStatus::WaitingForTrigger,
+                    is_final: false,
+                },
+            ];
+            ExecutionQueryResponse {
+                responses: vec![Ok(ExecutionQueryResponseItem::AsyncMessages(
+                    messages
+                        .into_iter()
+                        .skip(filter.offset)
+                        .take(filter.limit)
+                        .collect(),
+                ))],
+                candidate_cursor: Slot::new(3, 0),
+                final_cursor: Slot::new(1, 31),
+                final_state_fingerprint: massa_hash::Hash::compute_from(&Vec::new()),
+            }
+        });
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    // a sender or a destination is required
+    let response: Result<Vec<AsyncPoolMessage>, Error> = client
+        .request(
+            "get_async_messages",
+            rpc_params![AsyncMessagesRequest::default()],
+        )
+        .await;
+    assert!(response.is_err());
+
+    // pages can not be larger than max_arguments
+    let response: Result<Vec<AsyncPoolMessage>, Error> = client
+        .request(
+            "get_async_messages",
+            rpc_params![AsyncMessagesRequest {
+                sender: Some(sender),
+                destination: None,
+                page_request: Some(PageRequest {
+                    limit: 129,
+                    offset: 0,
+                }),
+            }],
+        )
+        .await;
+    assert!(response.is_err());
+
+    let params = rpc_params![AsyncMessagesRequest {
+        sender: Some(sender),
+        destination: None,
+        page_request: None,
+    }];
+    let response: Vec<AsyncPoolMessage> =
+        client.request("get_async_messages", params).await.unwrap();
+
+    assert_eq!(response.len(), 2);
+    assert_eq!(response[0].status, AsyncMessageStatus::Executable);
+    assert!(response[0].is_final);
+    assert_eq!(response[1].message.emission_index, 1);
+    assert_eq!(response[1].status, AsyncMessageStatus::WaitingForTrigger);
+    assert!(!response[1].is_final);
+
+    // the second page of one message
+    let params = rpc_params![AsyncMessagesRequest {
+        sender: Some(sender),
+        destination: None,
+        page_request: Some(PageRequest {
+            limit: 1,
+            offset: 1,
+        }),
+    }];
+    let response: Vec<AsyncPoolMessage> =
+        client.request("get_async_messages", params).await.unwrap();
+    assert_eq!(response.len(), 1);
+    assert_eq!(response[0].message.emission_index, 1);
+
+    api_public_handle.stop().await;
+}
+
 #[tokio::test]
 async fn get_addresses_bytecode() {
     let addr: SocketAddr = "[::]:5019".parse().unwrap();
@@ -1001,6 +1635,18 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
+    let response: Result<(), Error> = client.request("node_bans", params.clone()).await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("The wrong API (either Public or Private) was called"));
+
+    let response: Result<(), Error> = client.request("get_peers", params.clone()).await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("The wrong API (either Public or Private) was called"));
+
     let response: Result<(), Error> = client.request("node_peers_whitelist", params.clone()).await;
     assert!(response
         .unwrap_err()
@@ -260,6 +260,7 @@ mod tests {
                 address: Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x")
                     .unwrap(),
                 datastore_key: Some(vec![1, 2, 3, 4]),
+                condition: None,
             }),
             None,
         )
@@ -39,6 +39,11 @@
 //!
 //! Note that `fee + coins` coins are burned when sending the message.
 //!
+//! Once the `AsyncMessageTrigger` MIP component reaches version 1, a message triggered by an `AsyncMessageTriggerCondition`
+//! is sent using a dedicated ABI: `send_message_with_condition(..., trigger_address, condition)`,
+//! where `condition` is serialized with `AsyncMessageTriggerSerializer::serialize_condition`.
+//! A periodic message pays its fee again for each occurrence and is dropped once its sender can no longer pay it.
+//!
 //! ## How is the `AsyncPool` handled
 //! ```md
 //! * In the AsyncPool, Messages are kept sorted by `priority = AsyncMessageId(rev(Ratio(msg.fee, max(msg.max_gas,1))), rev(msg.slot), rev(msg.emission_index))`
@@ -94,9 +99,10 @@ mod pool;
 pub use changes::{AsyncPoolChanges, AsyncPoolChangesDeserializer, AsyncPoolChangesSerializer};
 pub use config::AsyncPoolConfig;
 pub use message::{
-    AsyncMessage, AsyncMessageDeserializer, AsyncMessageId, AsyncMessageIdDeserializer,
-    AsyncMessageIdSerializer, AsyncMessageInfo, AsyncMessageSerializer, AsyncMessageTrigger,
-    AsyncMessageTriggerSerializer, AsyncMessageUpdate,
+    AsyncMessage, AsyncMessageDeserializer, AsyncMessageEvent, AsyncMessageId,
+    AsyncMessageIdDeserializer, AsyncMessageIdSerializer, AsyncMessageInfo, AsyncMessageOutcome,
+    AsyncMessageSerializer, AsyncMessageStatus, AsyncMessageTrigger, AsyncMessageTriggerCondition,
+    AsyncMessageTriggerDeserializer, AsyncMessageTriggerSerializer, AsyncMessageUpdate,
 };
 pub use pool::{AsyncPool, AsyncPoolDeserializer, AsyncPoolSerializer};
 
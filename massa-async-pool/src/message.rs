@@ -16,11 +16,13 @@ use massa_models::{
 };
 use massa_serialization::{
     BoolDeserializer, BoolSerializer, Deserializer, OptionDeserializer, OptionSerializer,
-    SerializeError, Serializer, U16VarIntDeserializer, U16VarIntSerializer, U64VarIntDeserializer,
-    U64VarIntSerializer,
+    SerializeError, Serializer, U16VarIntDeserializer, U16VarIntSerializer, U32VarIntDeserializer,
+    U32VarIntSerializer, U64VarIntDeserializer, U64VarIntSerializer,
 };
-use nom::error::{context, ContextError, ParseError};
-use nom::sequence::tuple;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::error::{context, ContextError, ErrorKind, ParseError};
+use nom::sequence::{preceded, tuple};
 use nom::{IResult, Parser};
 use num::rational::Ratio;
 use serde::{Deserialize, Serialize};
@@ -189,13 +191,51 @@ pub struct AsyncMessageTrigger {
 
     /// Filter on the datastore key
     pub datastore_key: Option<Vec<u8>>,
+
+    /// Extended condition on the address, evaluated instead of `datastore_key` when set.
+    /// Only evaluated once the `AsyncMessageTrigger` MIP component reaches version 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<AsyncMessageTriggerCondition>,
+}
+
+/// Extended condition of an asynchronous message trigger
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AsyncMessageTriggerCondition {
+    /// A datastore entry of the address whose key starts with the given prefix is written or deleted
+    DatastoreKeyPrefix(Vec<u8>),
+    /// The balance of the address is set strictly below the given amount
+    BalanceBelow(Amount),
+    /// The balance of the address is set strictly above the given amount
+    BalanceAbove(Amount),
+    /// A period multiple of `interval` is reached,
+    /// the message is executed again at every occurrence until its validity end,
+    /// as long as its sender pays the message fee again for each occurrence
+    Periodic {
+        /// Number of periods between two occurrences
+        interval: u64,
+    },
 }
 
+impl AsyncMessageTriggerCondition {
+    const DATASTORE_KEY_PREFIX_ID: u32 = 0;
+    const BALANCE_BELOW_ID: u32 = 1;
+    const BALANCE_ABOVE_ID: u32 = 2;
+    const PERIODIC_ID: u32 = 3;
+}
+
+/// Tag introducing an extended condition in place of the optional datastore key of a trigger.
+/// `OptionSerializer` uses `b'0'` and `b'1'`, so triggers without condition keep their encoding.
+const TRIGGER_CONDITION_TAG: &[u8] = b"2";
+
 #[derive(Clone)]
 /// Serializer for a trigger for an asynchronous message
 pub struct AsyncMessageTriggerSerializer {
     address_serializer: AddressSerializer,
     key_serializer: OptionSerializer<Vec<u8>, VecU8Serializer>,
+    condition_id_serializer: U32VarIntSerializer,
+    prefix_serializer: VecU8Serializer,
+    amount_serializer: AmountSerializer,
+    interval_serializer: U64VarIntSerializer,
 }
 
 impl AsyncMessageTriggerSerializer {
@@ -203,6 +243,10 @@ impl AsyncMessageTriggerSerializer {
         Self {
             address_serializer: AddressSerializer::new(),
             key_serializer: OptionSerializer::new(VecU8Serializer::new()),
+            condition_id_serializer: U32VarIntSerializer::new(),
+            prefix_serializer: VecU8Serializer::new(),
+            amount_serializer: AmountSerializer::new(),
+            interval_serializer: U64VarIntSerializer::new(),
         }
     }
 }
@@ -213,6 +257,46 @@ impl Default for AsyncMessageTriggerSerializer {
     }
 }
 
+impl AsyncMessageTriggerSerializer {
+    /// Serializes an extended trigger condition, without the tag introducing it in a trigger
+    pub fn serialize_condition(
+        &self,
+        condition: &AsyncMessageTriggerCondition,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        match condition {
+            AsyncMessageTriggerCondition::DatastoreKeyPrefix(prefix) => {
+                self.condition_id_serializer.serialize(
+                    &AsyncMessageTriggerCondition::DATASTORE_KEY_PREFIX_ID,
+                    buffer,
+                )?;
+                self.prefix_serializer.serialize(prefix, buffer)?;
+            }
+            AsyncMessageTriggerCondition::BalanceBelow(amount) => {
+                self.condition_id_serializer
+                    .serialize(&AsyncMessageTriggerCondition::BALANCE_BELOW_ID, buffer)?;
+                self.amount_serializer.serialize(amount, buffer)?;
+            }
+            AsyncMessageTriggerCondition::BalanceAbove(amount) => {
+                self.condition_id_serializer
+                    .serialize(&AsyncMessageTriggerCondition::BALANCE_ABOVE_ID, buffer)?;
+                self.amount_serializer.serialize(amount, buffer)?;
+            }
+            AsyncMessageTriggerCondition::Periodic { interval } => {
+                if *interval == 0 {
+                    return Err(SerializeError::GeneralError(
+                        "a periodic trigger needs a non-zero interval".to_string(),
+                    ));
+                }
+                self.condition_id_serializer
+                    .serialize(&AsyncMessageTriggerCondition::PERIODIC_ID, buffer)?;
+                self.interval_serializer.serialize(interval, buffer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 impl Serializer<AsyncMessageTrigger> for AsyncMessageTriggerSerializer {
     fn serialize(
         &self,
@@ -220,9 +304,16 @@ impl Serializer<AsyncMessageTrigger> for AsyncMessageTriggerSerializer {
         buffer: &mut Vec<u8>,
     ) -> Result<(), SerializeError> {
         self.address_serializer.serialize(&value.address, buffer)?;
-        self.key_serializer
-            .serialize(&value.datastore_key, buffer)?;
-        Ok(())
+        let Some(condition) = &value.condition else {
+            return self.key_serializer.serialize(&value.datastore_key, buffer);
+        };
+        if value.datastore_key.is_some() {
+            return Err(SerializeError::GeneralError(
+                "a trigger cannot have both a datastore key and a condition".to_string(),
+            ));
+        }
+        buffer.extend_from_slice(TRIGGER_CONDITION_TAG);
+        self.serialize_condition(condition, buffer)
     }
 }
 
@@ -231,6 +322,10 @@ impl Serializer<AsyncMessageTrigger> for AsyncMessageTriggerSerializer {
 pub struct AsyncMessageTriggerDeserializer {
     address_deserializer: AddressDeserializer,
     key_serializer: OptionDeserializer<Vec<u8>, VecU8Deserializer>,
+    condition_id_deserializer: U32VarIntDeserializer,
+    prefix_deserializer: VecU8Deserializer,
+    amount_deserializer: AmountDeserializer,
+    interval_deserializer: U64VarIntDeserializer,
 }
 
 impl AsyncMessageTriggerDeserializer {
@@ -241,6 +336,61 @@ impl AsyncMessageTriggerDeserializer {
                 Included(0),
                 Included(max_key_length as u64),
             )),
+            condition_id_deserializer: U32VarIntDeserializer::new(
+                Included(AsyncMessageTriggerCondition::DATASTORE_KEY_PREFIX_ID),
+                Included(AsyncMessageTriggerCondition::PERIODIC_ID),
+            ),
+            prefix_deserializer: VecU8Deserializer::new(
+                Included(0),
+                Included(max_key_length as u64),
+            ),
+            amount_deserializer: AmountDeserializer::new(
+                Included(Amount::MIN),
+                Included(Amount::MAX),
+            ),
+            interval_deserializer: U64VarIntDeserializer::new(Included(1), Included(u64::MAX)),
+        }
+    }
+
+    /// Deserializes an extended trigger condition, without the tag introducing it in a trigger
+    pub fn deserialize_condition<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], AsyncMessageTriggerCondition, E> {
+        let (rest, id) = context("Failed condition id deserialization", |input| {
+            self.condition_id_deserializer.deserialize(input)
+        })
+        .parse(buffer)?;
+        match id {
+            AsyncMessageTriggerCondition::DATASTORE_KEY_PREFIX_ID => {
+                context("Failed key prefix deserialization", |input| {
+                    self.prefix_deserializer.deserialize(input)
+                })
+                .map(AsyncMessageTriggerCondition::DatastoreKeyPrefix)
+                .parse(rest)
+            }
+            AsyncMessageTriggerCondition::BALANCE_BELOW_ID => {
+                context("Failed balance threshold deserialization", |input| {
+                    self.amount_deserializer.deserialize(input)
+                })
+                .map(AsyncMessageTriggerCondition::BalanceBelow)
+                .parse(rest)
+            }
+            AsyncMessageTriggerCondition::BALANCE_ABOVE_ID => {
+                context("Failed balance threshold deserialization", |input| {
+                    self.amount_deserializer.deserialize(input)
+                })
+                .map(AsyncMessageTriggerCondition::BalanceAbove)
+                .parse(rest)
+            }
+            AsyncMessageTriggerCondition::PERIODIC_ID => {
+                context("Failed interval deserialization", |input| {
+                    self.interval_deserializer.deserialize(input)
+                })
+                .map(|interval| AsyncMessageTriggerCondition::Periodic { interval })
+                .parse(rest)
+            }
+            _ => Err(nom::Err::Error(E::from_error_kind(buffer, ErrorKind::Fail))),
         }
     }
 }
@@ -256,15 +406,28 @@ impl Deserializer<AsyncMessageTrigger> for AsyncMessageTriggerDeserializer {
                 context("Failed address deserialization", |input| {
                     self.address_deserializer.deserialize(input)
                 }),
-                context("Failed datastore_key deserialization", |input| {
-                    self.key_serializer.deserialize(input)
-                }),
+                alt((
+                    context("Failed datastore_key deserialization", |input| {
+                        self.key_serializer.deserialize(input)
+                    })
+                    .map(|datastore_key| (datastore_key, None)),
+                    context(
+                        "Failed condition deserialization",
+                        preceded(tag(TRIGGER_CONDITION_TAG), |input| {
+                            self.deserialize_condition(input)
+                        }),
+                    )
+                    .map(|condition| (None, Some(condition))),
+                )),
             )),
         )
-        .map(|(address, datastore_key)| AsyncMessageTrigger {
-            address,
-            datastore_key,
-        })
+        .map(
+            |(address, (datastore_key, condition))| AsyncMessageTrigger {
+                address,
+                datastore_key,
+                condition,
+            },
+        )
         .parse(buffer)
     }
 }
@@ -377,6 +540,82 @@ impl AsyncMessage {
             self.emission_index,
         )
     }
+
+    /// Tells whether the message can be executed at `slot`, or why it can not.
+    /// An executable message is still only taken if enough asynchronous gas is left in the slot.
+    pub fn status_at(&self, slot: Slot) -> AsyncMessageStatus {
+        if slot >= self.validity_end {
+            AsyncMessageStatus::Expired
+        } else if slot < self.validity_start {
+            AsyncMessageStatus::NotYetValid
+        } else if !self.can_be_executed {
+            AsyncMessageStatus::WaitingForTrigger
+        } else {
+            AsyncMessageStatus::Executable
+        }
+    }
+}
+
+/// Executability of an asynchronous message of the pool at a given slot
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum AsyncMessageStatus {
+    /// The message can be executed
+    Executable,
+    /// The validity window of the message starts after the slot
+    NotYetValid,
+    /// The trigger of the message was not satisfied since the start of its validity window
+    WaitingForTrigger,
+    /// The validity window of the message ended, it is removed from the pool when the slot is settled
+    Expired,
+}
+
+/// Reason why an asynchronous message left the pool
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum AsyncMessageOutcome {
+    /// The message was executed successfully
+    ExecutedWithSuccess,
+    /// The message execution failed and the sender was reimbursed
+    ExecutedWithFailure,
+    /// The validity window of the message ended before its execution and the sender was reimbursed
+    Expired,
+    /// The message was dropped to keep the pool within its maximum length and the sender was reimbursed
+    Evicted,
+    /// The message was cancelled by its sender, who was reimbursed
+    Cancelled,
+}
+
+/// Asynchronous message that left the pool during the execution of a slot
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct AsyncMessageEvent {
+    /// Slot whose execution removed the message from the pool
+    pub slot: Slot,
+    /// Slot at which the message was emitted
+    pub emission_slot: Slot,
+    /// Index of the emitted message within the `emission_slot`
+    pub emission_index: u64,
+    /// The address that sent the message
+    pub sender: Address,
+    /// The address towards which the message was sent
+    pub destination: Address,
+    /// The function called on the destination
+    pub function: String,
+    /// Why the message left the pool
+    pub outcome: AsyncMessageOutcome,
+}
+
+impl AsyncMessageEvent {
+    /// Creates the event of a message leaving the pool at `slot`
+    pub fn new(slot: Slot, message: &AsyncMessage, outcome: AsyncMessageOutcome) -> Self {
+        AsyncMessageEvent {
+            slot,
+            emission_slot: message.emission_slot,
+            emission_index: message.emission_index,
+            sender: message.sender,
+            destination: message.destination,
+            function: message.function.clone(),
+            outcome,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -436,7 +675,8 @@ impl Serializer<AsyncMessage> for AsyncMessageSerializer {
     ///     vec![1, 2, 3, 4],
     ///     Some(AsyncMessageTrigger {
     ///         address: Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap(),
-    ///         datastore_key: Some(vec![1, 2, 3, 4])
+    ///         datastore_key: Some(vec![1, 2, 3, 4]),
+    ///         condition: None,
     ///     }),
     ///     None,
     /// );
@@ -554,6 +794,7 @@ impl Deserializer<AsyncMessage> for AsyncMessageDeserializer {
     ///     Some(AsyncMessageTrigger {
     ///        address: Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap(),
     ///        datastore_key: Some(vec![1, 2, 3, 4]),
+    ///        condition: None,
     ///     }),
     ///     None,
     /// );
@@ -661,6 +902,8 @@ impl Deserializer<AsyncMessage> for AsyncMessageDeserializer {
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct AsyncMessageInfo {
+    pub sender: Address,
+    pub destination: Address,
     pub validity_start: Slot,
     pub validity_end: Slot,
     pub max_gas: u64,
@@ -671,6 +914,8 @@ pub struct AsyncMessageInfo {
 impl From<AsyncMessage> for AsyncMessageInfo {
     fn from(value: AsyncMessage) -> Self {
         Self {
+            sender: value.sender,
+            destination: value.destination,
             validity_start: value.validity_start,
             validity_end: value.validity_end,
             max_gas: value.max_gas,
@@ -996,6 +1241,8 @@ impl Applicable<AsyncMessageUpdate> for AsyncMessage {
 impl Applicable<AsyncMessageUpdate> for AsyncMessageInfo {
     /// extends the `AsyncMessage` with a `AsyncMessageUpdate`
     fn apply(&mut self, update: AsyncMessageUpdate) {
+        update.sender.apply_to(&mut self.sender);
+        update.destination.apply_to(&mut self.destination);
         update.max_gas.apply_to(&mut self.max_gas);
         update.validity_start.apply_to(&mut self.validity_start);
         update.validity_end.apply_to(&mut self.validity_end);
@@ -1007,20 +1254,25 @@ impl Applicable<AsyncMessageUpdate> for AsyncMessageInfo {
 #[cfg(test)]
 mod tests {
     use massa_ledger_exports::{Applicable, SetOrKeep};
-    use massa_serialization::{DeserializeError, Deserializer, Serializer};
+    use massa_serialization::{DeserializeError, Deserializer, OptionSerializer, Serializer};
     use num::rational::Ratio;
 
     use crate::{
-        message::{AsyncMessageUpdateDeserializer, AsyncMessageUpdateSerializer},
+        message::{
+            AsyncMessageTriggerDeserializer, AsyncMessageUpdateDeserializer,
+            AsyncMessageUpdateSerializer,
+        },
         AsyncMessage, AsyncMessageDeserializer, AsyncMessageId, AsyncMessageIdDeserializer,
-        AsyncMessageIdSerializer, AsyncMessageSerializer, AsyncMessageTrigger, AsyncMessageUpdate,
+        AsyncMessageIdSerializer, AsyncMessageSerializer, AsyncMessageStatus, AsyncMessageTrigger,
+        AsyncMessageTriggerCondition, AsyncMessageTriggerSerializer, AsyncMessageUpdate,
     };
     use massa_models::{
-        address::Address,
+        address::{Address, AddressSerializer},
         amount::Amount,
         config::{
             MAX_DATASTORE_KEY_LENGTH, MAX_FUNCTION_NAME_LENGTH, MAX_PARAMETERS_SIZE, THREAD_COUNT,
         },
+        serialization::VecU8Serializer,
         slot::Slot,
     };
     use std::str::FromStr;
@@ -1116,6 +1368,7 @@ mod tests {
                 address: Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x")
                     .unwrap(),
                 datastore_key: Some(vec![0; MAX_DATASTORE_KEY_LENGTH as usize]),
+                condition: None,
             })),
             can_be_executed: SetOrKeep::Set(true),
         };
@@ -1143,6 +1396,7 @@ mod tests {
                 address: Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x")
                     .unwrap(),
                 datastore_key: Some(vec![0; MAX_DATASTORE_KEY_LENGTH as usize]),
+                condition: None,
             }),
             can_be_executed: true,
         };
@@ -1211,6 +1465,7 @@ mod tests {
                 address: Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x")
                     .unwrap(),
                 datastore_key: Some(vec![0; MAX_DATASTORE_KEY_LENGTH as usize]),
+                condition: None,
             })),
             can_be_executed: SetOrKeep::Set(true),
         };
@@ -1239,6 +1494,7 @@ mod tests {
                 address: Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x")
                     .unwrap(),
                 datastore_key: Some(vec![0; MAX_DATASTORE_KEY_LENGTH as usize]),
+                condition: None,
             }),
             can_be_executed: true,
         };
@@ -1365,6 +1621,7 @@ mod tests {
                 address: Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x")
                     .unwrap(),
                 datastore_key: Some(vec![0; MAX_DATASTORE_KEY_LENGTH as usize]),
+                condition: None,
             }),
             can_be_executed: true,
         };
@@ -1447,6 +1704,7 @@ mod tests {
                 address: Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x")
                     .unwrap(),
                 datastore_key: Some(vec![0; MAX_DATASTORE_KEY_LENGTH as usize]),
+                condition: None,
             })),
             can_be_executed: SetOrKeep::Set(true),
         };
@@ -1488,6 +1746,7 @@ mod tests {
                 address: Address::from_str("AU12htxRWiEm8jDJpJptr6cwEhWNcCSFWstN1MLSa96DDkVM9Y42G")
                     .unwrap(),
                 datastore_key: None,
+                condition: None,
             }),
             None,
         );
@@ -1509,4 +1768,108 @@ mod tests {
             .deserialize::<DeserializeError>(&serialized)
             .unwrap_err();
     }
+
+    #[test]
+    fn message_status_at() {
+        let mut message = AsyncMessage::new(
+            Slot::new(1, 0),
+            0,
+            Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap(),
+            Address::from_str("AU12htxRWiEm8jDJpJptr6cwEhWNcCSFWstN1MLSa96DDkVM9Y42G").unwrap(),
+            String::from("test"),
+            10_000_000,
+            Amount::from_str("1").unwrap(),
+            Amount::from_str("1").unwrap(),
+            Slot::new(2, 0),
+            Slot::new(3, 0),
+            vec![],
+            Some(AsyncMessageTrigger {
+                address: Address::from_str("AU12htxRWiEm8jDJpJptr6cwEhWNcCSFWstN1MLSa96DDkVM9Y42G")
+                    .unwrap(),
+                datastore_key: None,
+                condition: None,
+            }),
+            None,
+        );
+        assert_eq!(
+            message.status_at(Slot::new(1, 5)),
+            AsyncMessageStatus::NotYetValid
+        );
+        assert_eq!(
+            message.status_at(Slot::new(2, 0)),
+            AsyncMessageStatus::WaitingForTrigger
+        );
+        message.can_be_executed = true;
+        assert_eq!(
+            message.status_at(Slot::new(2, 31)),
+            AsyncMessageStatus::Executable
+        );
+        // the end of the validity window is excluded
+        assert_eq!(
+            message.status_at(Slot::new(3, 0)),
+            AsyncMessageStatus::Expired
+        );
+    }
+
+    #[test]
+    fn trigger_condition_ser_der() {
+        let address =
+            Address::from_str("AU12htxRWiEm8jDJpJptr6cwEhWNcCSFWstN1MLSa96DDkVM9Y42G").unwrap();
+        let serializer = AsyncMessageTriggerSerializer::new();
+        let deserializer = AsyncMessageTriggerDeserializer::new(MAX_DATASTORE_KEY_LENGTH as u32);
+
+        // triggers without condition keep the encoding of an address followed by an optional key
+        let legacy = AsyncMessageTrigger {
+            address,
+            datastore_key: Some(vec![1, 2, 3]),
+            condition: None,
+        };
+        let mut serialized = Vec::new();
+        serializer.serialize(&legacy, &mut serialized).unwrap();
+        let mut expected = Vec::new();
+        AddressSerializer::new()
+            .serialize(&address, &mut expected)
+            .unwrap();
+        OptionSerializer::new(VecU8Serializer::new())
+            .serialize(&legacy.datastore_key, &mut expected)
+            .unwrap();
+        assert_eq!(serialized, expected);
+
+        for condition in [
+            AsyncMessageTriggerCondition::DatastoreKeyPrefix(vec![1, 2]),
+            AsyncMessageTriggerCondition::BalanceBelow(Amount::from_str("10").unwrap()),
+            AsyncMessageTriggerCondition::BalanceAbove(Amount::from_str("100").unwrap()),
+            AsyncMessageTriggerCondition::Periodic { interval: 8 },
+        ] {
+            let trigger = AsyncMessageTrigger {
+                address,
+                datastore_key: None,
+                condition: Some(condition),
+            };
+            let mut serialized = Vec::new();
+            serializer.serialize(&trigger, &mut serialized).unwrap();
+            let (rest, deserialized) = deserializer
+                .deserialize::<DeserializeError>(&serialized)
+                .unwrap();
+            assert!(rest.is_empty());
+            assert_eq!(trigger, deserialized);
+        }
+
+        // a periodic trigger needs a non-zero interval
+        let trigger = AsyncMessageTrigger {
+            address,
+            datastore_key: None,
+            condition: Some(AsyncMessageTriggerCondition::Periodic { interval: 0 }),
+        };
+        serializer.serialize(&trigger, &mut Vec::new()).unwrap_err();
+        let mut serialized = Vec::new();
+        AddressSerializer::new()
+            .serialize(&address, &mut serialized)
+            .unwrap();
+        serialized.extend_from_slice(b"2");
+        serialized.extend_from_slice(&[3, 0]);
+        deserializer
+            .deserialize::<DeserializeError>(&serialized)
+            .unwrap_err();
+    }
 }
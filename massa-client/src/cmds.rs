@@ -8,8 +8,11 @@ use massa_api_exports::{
     address::{AddressInfo, CompactAddressInfo},
     datastore::DatastoreEntryInput,
     execution::{ReadOnlyBytecodeExecution, ReadOnlyCall},
+    ledger::{LedgerAnalyticsRequest, LedgerTreePathInput},
     operation::OperationInput,
 };
+use massa_hash::Hash;
+use massa_models::ledger_tree::LedgerTreeItem;
 use massa_models::node::NodeId;
 use massa_models::prehash::PreHashMap;
 use massa_models::timeslots::get_current_latest_block_slot;
@@ -86,6 +89,76 @@ pub enum Command {
     )]
     node_ban_by_id,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show banned ids with the reason and expiry of their ban"
+    )]
+    node_bans,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the module cache statistics"
+    )]
+    node_module_cache_stats,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "BytecodeHash1 BytecodeHash2 ...", pwd_not_needed = "true"),
+        message = "show the module cache status of given bytecode hash(es)"
+    )]
+    node_module_cache_entries,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "BytecodeHash1 BytecodeHash2 ...", pwd_not_needed = "true"),
+        message = "evict given bytecode hash(es) from the module cache"
+    )]
+    node_evict_modules,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "BytecodeHash1 BytecodeHash2 ...", pwd_not_needed = "true"),
+        message = "pin given bytecode hash(es) in the module cache"
+    )]
+    node_pin_modules,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "BytecodeHash1 BytecodeHash2 ...", pwd_not_needed = "true"),
+        message = "unpin given bytecode hash(es) in the module cache"
+    )]
+    node_unpin_modules,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "[TopCount]", pwd_not_needed = "true"),
+        message = "show the execution profile of the recent slots, with the most expensive contracts (10 by default)"
+    )]
+    node_execution_profile,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "devnet only: produce the block of the next slot immediately"
+    )]
+    node_devnet_next_slot,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "PeriodCount", pwd_not_needed = "true"),
+        message = "devnet only: fast-forward the clock by the given number of periods, producing all their blocks"
+    )]
+    node_devnet_fast_forward,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the details of every active peer connection"
+    )]
+    get_peers,
+
     #[strum(
         ascii_case_insensitive,
         props(pwd_not_needed = "true"),
@@ -149,6 +222,13 @@ pub enum Command {
     )]
     get_status,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(pwd_not_needed = "true"),
+        message = "show the deployment status of each MIP: announcements versus lock-in threshold, lock-in and activation times"
+    )]
+    get_mip_status,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "Address1 Address2 ...", pwd_not_needed = "true"),
@@ -156,6 +236,27 @@ pub enum Command {
     )]
     get_addresses,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Address1 Address2 ...", pwd_not_needed = "true"),
+        message = "output as CSV the staking history of a list of addresses per cycle (blocks, endorsements, rewards, rolls), if recorded by the node"
+    )]
+    staking_report,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "RichListLength Address1 Address2 ...", pwd_not_needed = "true"),
+        message = "show the supply breakdown, the richest addresses and the datastore size of a list of addresses, if the node maintains the ledger analytics"
+    )]
+    get_ledger_analytics,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Address [Key]", pwd_not_needed = "true"),
+        message = "get the final balance of an address, or its datastore entry of the given key (must be UTF-8), with its path in the ledger Merkle tree of the node (local to the node, not committed by the consensus), if the node maintains that tree"
+    )]
+    get_ledger_tree_path,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "Address Key", pwd_not_needed = "true"),
@@ -277,6 +378,13 @@ pub enum Command {
     )]
     call_smart_contract,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "SenderAddress EmissionSlot EmissionIndex Fee"),
+        message = "cancel an asynchronous message sent by a wallet address, the emission slot is given as period,thread"
+    )]
+    cancel_async_message,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "PathToBytecode MaxGas Address Fee", pwd_not_needed = "true"),
@@ -506,6 +614,100 @@ impl Command {
                 Ok(Box::new(()))
             }
 
+            Command::node_bans => match client.private.node_bans().await {
+                Ok(bans) => Ok(Box::new(bans)),
+                Err(e) => rpc_error!(e),
+            },
+
+            Command::node_module_cache_stats => {
+                match client.private.node_module_cache_stats().await {
+                    Ok(stats) => Ok(Box::new(stats)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::node_module_cache_entries => {
+                let hashes = parse_vec::<Hash>(parameters)?;
+                match client.private.node_module_cache_entries(hashes).await {
+                    Ok(entries) => Ok(Box::new(entries)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::node_evict_modules => {
+                let hashes = parse_vec::<Hash>(parameters)?;
+                match client.private.node_evict_modules(hashes).await {
+                    Ok(()) => {
+                        if !json {
+                            println!("Modules successfully evicted!")
+                        }
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::node_pin_modules => {
+                let hashes = parse_vec::<Hash>(parameters)?;
+                match client.private.node_pin_modules(hashes).await {
+                    Ok(()) => {
+                        if !json {
+                            println!("Modules successfully pinned!")
+                        }
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::node_unpin_modules => {
+                let hashes = parse_vec::<Hash>(parameters)?;
+                match client.private.node_unpin_modules(hashes).await {
+                    Ok(()) => {
+                        if !json {
+                            println!("Modules successfully unpinned!")
+                        }
+                    }
+                    Err(e) => rpc_error!(e),
+                }
+                Ok(Box::new(()))
+            }
+
+            Command::node_execution_profile => {
+                if parameters.len() > 1 {
+                    bail!("wrong number of parameters");
+                }
+                let top_n = match parameters.first() {
+                    Some(top_n) => top_n.parse::<usize>()?,
+                    None => 10,
+                };
+                match client.private.node_execution_profile(top_n).await {
+                    Ok(profile) => Ok(Box::new(profile)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::node_devnet_next_slot => match client.private.node_devnet_next_slot().await {
+                Ok(slot) => Ok(Box::new(slot)),
+                Err(e) => rpc_error!(e),
+            },
+
+            Command::node_devnet_fast_forward => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let periods = parameters[0].parse::<u64>()?;
+                match client.private.node_devnet_fast_forward(periods).await {
+                    Ok(slot) => Ok(Box::new(slot)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::get_peers => match client.private.get_peers().await {
+                Ok(peers) => Ok(Box::new(peers)),
+                Err(e) => rpc_error!(e),
+            },
+
             Command::node_stop => {
                 match client.private.stop_node().await {
                     Ok(()) => {
@@ -558,6 +760,11 @@ impl Command {
                 }
             }
 
+            Command::get_mip_status => match client.public.get_status().await {
+                Ok(node_status) => Ok(Box::new(node_status.mip_statuses)),
+                Err(e) => rpc_error!(e),
+            },
+
             Command::get_status => match client.public.get_status().await {
                 Ok(node_status) => Ok(Box::new(node_status)),
                 Err(e) => rpc_error!(e),
@@ -571,6 +778,47 @@ impl Command {
                 }
             }
 
+            Command::staking_report => {
+                let addresses = parse_vec::<Address>(parameters)?;
+                match client.public.get_staking_history(addresses).await {
+                    Ok(staking_history) => Ok(Box::new(staking_history)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::get_ledger_analytics => {
+                if parameters.is_empty() {
+                    bail!("invalid number of parameters");
+                }
+                let request = LedgerAnalyticsRequest {
+                    rich_list_length: parameters[0].parse::<usize>()?,
+                    addresses: parse_vec::<Address>(&parameters[1..])?,
+                };
+                match client.public.get_ledger_analytics(request).await {
+                    Ok(analytics) => Ok(Box::new(analytics)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::get_ledger_tree_path => {
+                if parameters.is_empty() || parameters.len() > 2 {
+                    bail!("invalid number of parameters");
+                }
+                let address = parameters[0].parse::<Address>()?;
+                let item = match parameters.get(1) {
+                    Some(key) => LedgerTreeItem::Datastore(key.as_bytes().to_vec()),
+                    None => LedgerTreeItem::Balance,
+                };
+                match client
+                    .public
+                    .get_ledger_tree_paths(vec![LedgerTreePathInput { address, item }])
+                    .await
+                {
+                    Ok(items) => Ok(Box::new(items)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
             Command::get_datastore_entry => {
                 if parameters.len() != 2 {
                     bail!("invalid number of parameters");
@@ -1082,6 +1330,30 @@ impl Command {
                 )
                 .await
             }
+            Command::cancel_async_message => {
+                let wallet = wallet_opt.as_mut().unwrap();
+
+                if parameters.len() != 4 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parameters[0].parse::<Address>()?;
+                let emission_slot = parameters[1].parse::<Slot>()?;
+                let emission_index = parameters[2].parse::<u64>()?;
+                let fee = parameters[3].parse::<Amount>()?;
+
+                send_operation(
+                    client,
+                    wallet,
+                    OperationType::CancelAsyncMessage {
+                        emission_slot,
+                        emission_index,
+                    },
+                    fee,
+                    addr,
+                    json,
+                )
+                .await
+            }
             Command::wallet_sign => {
                 let wallet = wallet_opt.as_mut().unwrap();
 
@@ -4,13 +4,22 @@ use crate::cmds::ExtendedWallet;
 use console::style;
 use erased_serde::{Serialize, Serializer};
 use massa_api_exports::{
-    address::AddressInfo, block::BlockInfo, datastore::DatastoreEntryOutput,
-    endorsement::EndorsementInfo, execution::ExecuteReadOnlyResponse, node::NodeStatus,
+    address::{AddressInfo, AddressStakingHistory},
+    block::BlockInfo,
+    datastore::DatastoreEntryOutput,
+    endorsement::EndorsementInfo,
+    execution::{ExecuteReadOnlyResponse, ExecutionProfileInfo, ModuleCacheEntry, ModuleCacheInfo},
+    ledger::{LedgerAnalytics, LedgerTreeItems},
+    node::NodeBanInfo,
+    node::NodeStatus,
     operation::OperationInfo,
+    versioning::MipStatus,
 };
 use massa_models::composite::PubkeySig;
+use massa_models::connection::ConnectionInfo;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
+use massa_models::slot::Slot;
 use massa_models::stats::{ConsensusStats, ExecutionStats, NetworkStats};
 use massa_models::{address::Address, config::CompactConfig, operation::OperationId};
 use massa_signature::{KeyPair, PublicKey};
@@ -214,6 +223,15 @@ impl Output for &str {
     }
 }
 
+impl Output for Slot {
+    fn pretty_print(&self) {
+        println!(
+            "Clock fast-forwarded up to slot {}",
+            Style::Protocol.style(self)
+        );
+    }
+}
+
 impl Output for NodeStatus {
     fn pretty_print(&self) {
         println!("Node's ID: {}", Style::Id.style(self.node_id));
@@ -261,6 +279,11 @@ impl Output for NodeStatus {
         self.network_stats.pretty_print();
         self.execution_stats.pretty_print();
 
+        if !self.mip_statuses.is_empty() {
+            println!("Versioning:");
+            self.mip_statuses.pretty_print();
+        }
+
         if !self.connected_nodes.is_empty() {
             println!("Connected nodes:");
             for (node_id, (ip_addr, is_outgoing)) in &self.connected_nodes {
@@ -328,6 +351,10 @@ impl Output for NetworkStats {
             "\tActive nodes: {}",
             Style::Good.style(self.active_node_count)
         );
+        println!(
+            "\tRate limited messages: {}",
+            Style::Bad.style(self.traffic.total().messages_rate_limited)
+        );
     }
 }
 
@@ -467,6 +494,44 @@ impl Output for Vec<AddressInfo> {
     }
 }
 
+impl Output for LedgerAnalytics {
+    fn pretty_print(&self) {
+        println!("{}", self);
+    }
+}
+
+impl Output for LedgerTreeItems {
+    fn pretty_print(&self) {
+        println!("{}", self);
+    }
+}
+
+impl Output for Vec<AddressStakingHistory> {
+    fn pretty_print(&self) {
+        println!(
+            "address,cycle,blocks_produced,blocks_missed,endorsements_produced,endorsements_missed,rewards,rolls_bought,rolls_sold,rolls_slashed,deferred_credits_released"
+        );
+        for history in self {
+            for cycle in &history.cycles {
+                println!(
+                    "{},{},{},{},{},{},{},{},{},{},{}",
+                    history.address,
+                    cycle.cycle,
+                    cycle.block_success_count,
+                    cycle.block_failure_count,
+                    cycle.endorsement_success_count,
+                    cycle.endorsement_failure_count,
+                    cycle.rewards,
+                    cycle.rolls_bought,
+                    cycle.rolls_sold,
+                    cycle.rolls_slashed,
+                    cycle.deferred_credits_released
+                );
+            }
+        }
+    }
+}
+
 impl Output for Vec<DatastoreEntryOutput> {
     fn pretty_print(&self) {
         for data_entry in self {
@@ -483,6 +548,59 @@ impl Output for Vec<EndorsementInfo> {
     }
 }
 
+impl Output for Vec<NodeBanInfo> {
+    fn pretty_print(&self) {
+        if self.is_empty() {
+            println!("No banned node");
+        }
+        for ban_info in self {
+            println!("{}", ban_info);
+        }
+    }
+}
+
+impl Output for ModuleCacheInfo {
+    fn pretty_print(&self) {
+        println!("{}", self);
+    }
+}
+
+impl Output for ExecutionProfileInfo {
+    fn pretty_print(&self) {
+        println!("{}", self);
+    }
+}
+
+impl Output for Vec<ModuleCacheEntry> {
+    fn pretty_print(&self) {
+        for entry in self {
+            println!("{}", entry);
+        }
+    }
+}
+
+impl Output for Vec<MipStatus> {
+    fn pretty_print(&self) {
+        if self.is_empty() {
+            println!("No MIP known by the node");
+        }
+        for mip_status in self {
+            println!("{}", mip_status);
+        }
+    }
+}
+
+impl Output for Vec<ConnectionInfo> {
+    fn pretty_print(&self) {
+        if self.is_empty() {
+            println!("No active connection");
+        }
+        for connection in self {
+            println!("{}", connection);
+        }
+    }
+}
+
 impl Output for Vec<IpAddr> {
     fn pretty_print(&self) {
         for ips in self {
@@ -1,5 +1,5 @@
 use massa_signature::KeyPair;
-use massa_time::MassaTime;
+use massa_time::{MassaTime, SharedClock};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -52,4 +52,7 @@ pub struct ConsensusConfig {
     pub broadcast_filled_blocks_channel_capacity: usize,
     /// last start period
     pub last_start_period: u64,
+    /// source of the current time, the system clock outside of tests
+    #[serde(skip)]
+    pub clock: SharedClock,
 }
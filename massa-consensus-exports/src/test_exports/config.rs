@@ -5,7 +5,7 @@ use massa_models::config::{
     },
     CONSENSUS_BOOTSTRAP_PART_SIZE,
 };
-use massa_time::MassaTime;
+use massa_time::{MassaTime, SharedClock};
 
 use crate::ConsensusConfig;
 
@@ -36,6 +36,7 @@ impl Default for ConsensusConfig {
             broadcast_blocks_channel_capacity: 128,
             broadcast_filled_blocks_channel_capacity: 128,
             last_start_period: 0,
+            clock: SharedClock::default(),
         }
     }
 }
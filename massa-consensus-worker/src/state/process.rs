@@ -21,7 +21,6 @@ use massa_models::{
 };
 use massa_signature::PublicKey;
 use massa_storage::Storage;
-use massa_time::MassaTime;
 use tracing::{debug, info};
 
 use crate::state::{
@@ -570,7 +569,7 @@ impl ConsensusState {
                 self.config.genesis_timestamp,
                 add_block_slot,
             )?;
-            let diff = MassaTime::now().saturating_sub(add_slot_timestamp);
+            let diff = self.config.clock.now().saturating_sub(add_slot_timestamp);
             self.massa_metrics
                 .set_block_slot_delay(diff.to_duration().as_secs_f64());
         }
@@ -717,7 +716,7 @@ impl ConsensusState {
             }
 
             // manage finalized blocks
-            let timestamp = MassaTime::now();
+            let timestamp = self.config.clock.now();
             let finalized_blocks = mem::take(&mut self.new_final_blocks);
             let mut final_block_slots = HashMap::with_capacity(finalized_blocks.len());
             let mut final_block_stats = VecDeque::with_capacity(finalized_blocks.len());
@@ -742,7 +741,7 @@ impl ConsensusState {
 
             // add stale blocks to stats
             let new_stale_block_ids_creators_slots = mem::take(&mut self.new_stale_blocks);
-            let timestamp = MassaTime::now();
+            let timestamp = self.config.clock.now();
             for (_b_id, (_b_creator, _b_slot)) in new_stale_block_ids_creators_slots.into_iter() {
                 self.stale_block_stats.push_back(timestamp);
             }
@@ -9,7 +9,6 @@ use massa_models::{
     block_header::SecuredHeader, block_id::BlockId, denunciation::DenunciationPrecursor, slot::Slot,
 };
 use massa_storage::Storage;
-use massa_time::MassaTime;
 use tracing::debug;
 
 use super::ConsensusState;
@@ -93,7 +92,7 @@ impl ConsensusState {
 
         // Block is coming from protocol mark it for desync calculation
         if !created {
-            let now = MassaTime::now();
+            let now = self.config.clock.now();
             self.protocol_blocks.push_back((now, block_id));
         }
 
@@ -18,7 +18,7 @@ use massa_pool_exports::MockPoolController;
 use massa_pos_exports::{MockSelectorController, Selection};
 use massa_signature::KeyPair;
 use massa_storage::Storage;
-use massa_test_framework::TestUniverse;
+use massa_test_framework::{genesis_blocks, BlockGraph, TestUniverse};
 use massa_time::MassaTime;
 use mockall::Sequence;
 
@@ -57,6 +57,17 @@ fn test_genesis_block_creation() {
         .expect("could not get block graph status")
         .genesis_blocks;
     assert_eq!(genesis_hashes.len() as u8, thread_count);
+
+    // the test framework builds the same genesis blocks
+    let built_genesis_ids: Vec<BlockId> = genesis_blocks(&staking_key, thread_count, 0)
+        .iter()
+        .map(|block| block.id)
+        .collect();
+    assert_eq!(built_genesis_ids, genesis_hashes);
+    assert_eq!(
+        BlockGraph::new(&staking_key, thread_count).genesis_ids(),
+        genesis_hashes
+    );
 }
 
 /// This test tests that the blocks are well processed by consensus even if they are not sent in a sorted way.
@@ -104,7 +115,7 @@ fn test_unsorted_block() {
         .expect_add_denunciation_precursor()
         .returning(|_| {});
     let mut sequence = Sequence::new();
-    for slot in sent_slot_order.into_iter() {
+    for slot in sent_slot_order.iter().copied() {
         foreign_controllers
             .selector_controller
             .expect_get_producer()
@@ -122,62 +133,14 @@ fn test_unsorted_block() {
         .expect("could not get block graph status")
         .genesis_blocks;
     // create test blocks
+    let graph = BlockGraph::new(&staking_key, 2).fill_periods(1 + start_period, 4 + start_period);
+    assert_eq!(graph.genesis_ids(), genesis_hashes);
 
-    let t0s1 = create_block(
-        Slot::new(1 + start_period, 0),
-        genesis_hashes.clone(),
-        &staking_key,
-    );
-
-    let t1s1 = create_block(
-        Slot::new(1 + start_period, 1),
-        genesis_hashes.clone(),
-        &staking_key,
-    );
-
-    let t0s2 = create_block(
-        Slot::new(2 + start_period, 0),
-        vec![t0s1.id, t1s1.id],
-        &staking_key,
-    );
-    let t1s2 = create_block(
-        Slot::new(2 + start_period, 1),
-        vec![t0s1.id, t1s1.id],
-        &staking_key,
-    );
-
-    let t0s3 = create_block(
-        Slot::new(3 + start_period, 0),
-        vec![t0s2.id, t1s2.id],
-        &staking_key,
-    );
-    let t1s3 = create_block(
-        Slot::new(3 + start_period, 1),
-        vec![t0s2.id, t1s2.id],
-        &staking_key,
-    );
-
-    let t0s4 = create_block(
-        Slot::new(4 + start_period, 0),
-        vec![t0s3.id, t1s3.id],
-        &staking_key,
-    );
-    let t1s4 = create_block(
-        Slot::new(4 + start_period, 1),
-        vec![t0s3.id, t1s3.id],
-        &staking_key,
-    );
-
-    // send blocks  t0s1, t1s1,
-    register_block(&universe.module_controller, t0s1.clone(), storage.clone());
-    register_block(&universe.module_controller, t1s1.clone(), storage.clone());
-    //register blocks t0s3, t1s4, t0s4, t0s2, t1s3, t1s2
-    register_block(&universe.module_controller, t0s3.clone(), storage.clone());
-    register_block(&universe.module_controller, t1s4.clone(), storage.clone());
-    register_block(&universe.module_controller, t0s4.clone(), storage.clone());
-    register_block(&universe.module_controller, t0s2.clone(), storage.clone());
-    register_block(&universe.module_controller, t1s3.clone(), storage.clone());
-    register_block(&universe.module_controller, t1s2.clone(), storage.clone());
+    // send the blocks unsorted, in the order of their producer draws
+    let sent_names: Vec<String> = sent_slot_order.iter().map(BlockGraph::slot_name).collect();
+    for block in graph.select(sent_names.iter().map(String::as_str)) {
+        register_block(&universe.module_controller, block, storage.clone());
+    }
 }
 
 #[test]
@@ -19,7 +19,6 @@ use massa_models::{
     timeslots::{get_block_slot_timestamp, get_latest_block_slot_at_timestamp},
 };
 use massa_storage::Storage;
-use massa_time::MassaTime;
 use parking_lot::RwLock;
 use std::{
     collections::{HashMap, VecDeque},
@@ -88,7 +87,7 @@ impl ConsensusWorker {
         init_graph: Option<BootstrapableGraph>,
         storage: Storage,
     ) -> Result<Self, ConsensusError> {
-        let now = MassaTime::now();
+        let now = config.clock.now();
         let previous_slot = get_latest_block_slot_at_timestamp(
             config.thread_count,
             config.t0,
@@ -129,13 +128,12 @@ impl ConsensusWorker {
         let next_slot = previous_slot.map_or(Ok(Slot::new(0u64, 0u8)), |s| {
             s.get_next_slot(config.thread_count)
         })?;
-        let next_instant = get_block_slot_timestamp(
+        let next_timestamp = get_block_slot_timestamp(
             config.thread_count,
             config.t0,
             config.genesis_timestamp,
             next_slot,
-        )?
-        .estimate_instant()?;
+        )?;
 
         info!(
             "Started node at time {}, cycle {}, period {}, thread {}",
@@ -195,7 +193,7 @@ impl ConsensusWorker {
             shared_state,
             previous_slot,
             next_slot,
-            next_instant,
+            next_timestamp,
         };
 
         // If the node starts after the genesis timestamp then it has to initialize its graph
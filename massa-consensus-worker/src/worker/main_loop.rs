@@ -1,5 +1,4 @@
-use std::time::Instant;
-
+use crossbeam::select;
 use massa_consensus_exports::{error::ConsensusError, events::ConsensusEvent};
 use massa_models::{
     slot::Slot,
@@ -50,34 +49,38 @@ impl ConsensusWorker {
         }
     }
 
-    /// Wait and interrupt if we receive a command, a stop signal or we reach the `instant`
+    /// Wait and interrupt if we receive a command, a stop signal or the clock reaches the `deadline`
     ///
     /// # Return:
     /// WaitingStatus::Interrupted => if a command has been executed
-    /// WaitingStatus::Ended => if we reached the `instant`
+    /// WaitingStatus::Ended => if we reached the `deadline`
     /// WaitingStatus::Disconnected => if we received a stop signal
-    fn wait_slot_or_command(&mut self, deadline: Instant) -> WaitingStatus {
-        match self.command_receiver.recv_deadline(deadline) {
-            // message received => manage it
-            Ok(command) => {
-                if let Err(err) = self.manage_command(command) {
-                    warn!("Error in consensus: {}", err);
+    fn wait_slot_or_command(&mut self, deadline: MassaTime) -> WaitingStatus {
+        let slot_timer = self.config.clock.after(deadline);
+        select! {
+            recv(self.command_receiver) -> command => match command {
+                // message received => manage it
+                Ok(command) => {
+                    self.command_receiver.update_metrics();
+                    if let Err(err) = self.manage_command(command) {
+                        warn!("Error in consensus: {}", err);
+                    }
+                    WaitingStatus::Interrupted
                 }
-                WaitingStatus::Interrupted
-            }
-            // timeout => continue main loop
-            Err(crossbeam::channel::RecvTimeoutError::Timeout) => WaitingStatus::Ended,
-            // channel disconnected (sender dropped) => quit main loop
-            Err(crossbeam::channel::RecvTimeoutError::Disconnected) => WaitingStatus::Disconnected,
+                // channel disconnected (sender dropped) => quit main loop
+                Err(_) => WaitingStatus::Disconnected,
+            },
+            // deadline reached => continue main loop
+            recv(slot_timer) -> _ => WaitingStatus::Ended,
         }
     }
 
-    /// Gets the next slot and the instant when it will happen.
+    /// Gets the next slot and the timestamp when it will happen.
     /// Slots can be skipped if we waited too much in-between.
     /// Extra safety against double-production caused by clock adjustments (this is the role of the `previous_slot` parameter).
-    fn get_next_slot(&self, previous_slot: Option<Slot>) -> (Slot, Instant) {
+    fn get_next_slot(&self, previous_slot: Option<Slot>) -> (Slot, MassaTime) {
         // get current absolute time
-        let now = MassaTime::now();
+        let now = self.config.clock.now();
 
         // get closest slot according to the current absolute time
         let mut next_slot = get_closest_slot_to_timestamp(
@@ -97,30 +100,28 @@ impl ConsensusWorker {
         }
 
         // get the timestamp of the target slot
-        let next_instant = get_block_slot_timestamp(
+        let next_timestamp = get_block_slot_timestamp(
             self.config.thread_count,
             self.config.t0,
             self.config.genesis_timestamp,
             next_slot,
         )
-        .expect("could not get block slot timestamp")
-        .estimate_instant()
-        .expect("could not estimate block slot instant");
+        .expect("could not get block slot timestamp");
 
-        (next_slot, next_instant)
+        (next_slot, next_timestamp)
     }
 
     /// Runs in loop forever. This loop must stop every slot to perform operations on stats and graph
     /// but can be stopped anytime by a command received.
     pub fn run(&mut self) {
-        let mut last_prune = Instant::now();
+        let mut last_prune = self.config.clock.now();
         loop {
-            match self.wait_slot_or_command(self.next_instant) {
+            match self.wait_slot_or_command(self.next_timestamp) {
                 // When we reached the instant of the next slot
                 WaitingStatus::Ended => {
                     if let Some(end) = self.config.end_timestamp {
                         // The testnet has ended. Will be removed for mainnet.
-                        if self.next_instant > end.estimate_instant().unwrap() {
+                        if self.next_timestamp > end {
                             info!("This episode has come to an end, please get the latest testnet node version to continue");
                             let _ = self
                                 .shared_state
@@ -149,17 +150,17 @@ impl ConsensusWorker {
                             warn!("Error while processing block tick: {}", err);
                         }
                     };
-                    if last_prune.elapsed().as_millis()
-                        > self.config.block_db_prune_interval.as_millis() as u128
-                    {
+                    let now = self.config.clock.now();
+                    if now.saturating_sub(last_prune) > self.config.block_db_prune_interval {
                         self.shared_state
                             .write()
                             .prune()
                             .expect("Error while pruning");
-                        last_prune = Instant::now();
+                        last_prune = now;
                     }
                     self.previous_slot = Some(self.next_slot);
-                    (self.next_slot, self.next_instant) = self.get_next_slot(Some(self.next_slot));
+                    (self.next_slot, self.next_timestamp) =
+                        self.get_next_slot(Some(self.next_slot));
                 }
                 WaitingStatus::Disconnected => {
                     break;
@@ -14,7 +14,6 @@ use massa_time::MassaTime;
 use parking_lot::RwLock;
 use std::sync::Arc;
 use std::thread;
-use std::time::Instant;
 
 use crate::commands::ConsensusCommand;
 use crate::controller::ConsensusControllerImpl;
@@ -33,8 +32,8 @@ pub struct ConsensusWorker {
     previous_slot: Option<Slot>,
     /// Next slot
     next_slot: Slot,
-    /// Next slot instant
-    next_instant: Instant,
+    /// Next slot timestamp
+    next_timestamp: MassaTime,
 }
 
 mod init;
@@ -88,7 +87,7 @@ pub fn start_consensus_worker(
         stale_block_stats: Default::default(),
         protocol_blocks: Default::default(),
         wishlist: Default::default(),
-        launch_time: MassaTime::now(),
+        launch_time: config.clock.now(),
         stats_desync_detection_timespan,
         stats_history_timespan: std::cmp::max(
             stats_desync_detection_timespan,
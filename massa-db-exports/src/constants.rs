@@ -2,6 +2,8 @@
 pub const METADATA_CF: &str = "metadata";
 pub const STATE_CF: &str = "state";
 pub const VERSIONING_CF: &str = "versioning";
+/// Node local indexes, neither hashed nor streamed during bootstrap
+pub const INDEXES_CF: &str = "indexes";
 
 // Hash
 pub const STATE_HASH_BYTES_LEN: usize = 512;
@@ -29,6 +31,9 @@ pub const LEDGER_PREFIX: &str = "ledger/";
 pub const MIP_STORE_PREFIX: &str = "versioning/";
 pub const MIP_STORE_STATS_PREFIX: &str = "versioning_stats/";
 pub const EXECUTION_TRAIL_HASH_PREFIX: &str = "execution_trail_hash/";
+pub const STAKING_HISTORY_PREFIX: &str = "staking_history/";
+pub const LEDGER_ANALYTICS_PREFIX: &str = "ledger_analytics/";
+pub const LEDGER_MERKLE_PREFIX: &str = "ledger_merkle/";
 
 // Async Pool
 pub const MESSAGE_DESER_ERROR: &str = "critical: message deserialization failed";
@@ -41,6 +46,8 @@ pub const CYCLE_HISTORY_DESER_ERROR: &str = "critical: cycle_history deserializa
 pub const CYCLE_HISTORY_SER_ERROR: &str = "critical: cycle_history serialization failed";
 pub const DEFERRED_CREDITS_DESER_ERROR: &str = "critical: deferred_credits deserialization failed";
 pub const DEFERRED_CREDITS_SER_ERROR: &str = "critical: deferred_credits serialization failed";
+pub const STAKING_HISTORY_DESER_ERROR: &str = "critical: staking_history deserialization failed";
+pub const STAKING_HISTORY_SER_ERROR: &str = "critical: staking_history serialization failed";
 
 // Executed Ops
 pub const EXECUTED_OPS_ID_DESER_ERROR: &str = "critical: executed_ops_id deserialization failed";
@@ -56,3 +63,7 @@ pub const EXECUTED_DENUNCIATIONS_INDEX_SER_ERROR: &str =
 pub const KEY_DESER_ERROR: &str = "critical: key deserialization failed";
 pub const KEY_SER_ERROR: &str = "critical: key serialization failed";
 pub const KEY_LEN_SER_ERROR: &str = "critical: key length serialization failed";
+pub const LEDGER_ANALYTICS_DESER_ERROR: &str = "critical: ledger_analytics deserialization failed";
+pub const LEDGER_ANALYTICS_SER_ERROR: &str = "critical: ledger_analytics serialization failed";
+pub const LEDGER_MERKLE_DESER_ERROR: &str = "critical: ledger_merkle deserialization failed";
+pub const LEDGER_MERKLE_SER_ERROR: &str = "critical: ledger_merkle serialization failed";
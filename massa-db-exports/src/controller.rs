@@ -25,6 +25,20 @@ pub trait MassaDBController: Send + Sync + Debug {
     /// Writes the batch to the DB
     fn write_batch(&mut self, batch: DBBatch, versioning_batch: DBBatch, change_id: Option<Slot>);
 
+    /// Writes the batch to the DB, along with a batch of node local indexes (INDEXES_CF)
+    /// written atomically with it
+    fn write_batch_with_indexes(
+        &mut self,
+        batch: DBBatch,
+        versioning_batch: DBBatch,
+        indexes_batch: DBBatch,
+        change_id: Option<Slot>,
+    );
+
+    /// Writes a batch to the node local indexes (INDEXES_CF).
+    /// These entries are not part of the state hash, have no change history and are not bootstrapped.
+    fn write_index_batch(&mut self, batch: DBBatch);
+
     /// Utility function to put / update a key & value in the batch
     fn put_or_update_entry_value(&self, batch: &mut DBBatch, key: Vec<u8>, value: &[u8]);
 
@@ -1,8 +1,8 @@
 use massa_db_exports::{
     DBBatch, Key, MassaDBConfig, MassaDBController, MassaDBError, MassaDirection,
     MassaIteratorMode, StreamBatch, Value, CF_ERROR, CHANGE_ID_DESER_ERROR, CHANGE_ID_KEY,
-    CHANGE_ID_SER_ERROR, CRUD_ERROR, METADATA_CF, OPEN_ERROR, STATE_CF, STATE_HASH_ERROR,
-    STATE_HASH_INITIAL_BYTES, STATE_HASH_KEY, VERSIONING_CF,
+    CHANGE_ID_SER_ERROR, CRUD_ERROR, INDEXES_CF, METADATA_CF, OPEN_ERROR, STATE_CF,
+    STATE_HASH_ERROR, STATE_HASH_INITIAL_BYTES, STATE_HASH_KEY, VERSIONING_CF,
 };
 use massa_hash::{HashXof, HASH_XOF_SIZE_BYTES};
 use massa_models::{
@@ -347,6 +347,7 @@ where
         &mut self,
         changes: BTreeMap<Key, Option<Value>>,
         versioning_changes: BTreeMap<Key, Option<Value>>,
+        indexes_changes: BTreeMap<Key, Option<Value>>,
         change_id: Option<ChangeID>,
         reset_history: bool,
     ) -> Result<(), MassaDBError> {
@@ -361,6 +362,7 @@ where
         let handle_state = self.db.cf_handle(STATE_CF).expect(CF_ERROR);
         let handle_metadata = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
         let handle_versioning = self.db.cf_handle(VERSIONING_CF).expect(CF_ERROR);
+        let handle_indexes = self.db.cf_handle(INDEXES_CF).expect(CF_ERROR);
 
         let mut current_xor_hash = self.get_xof_db_hash();
 
@@ -402,6 +404,16 @@ where
             }
         }
 
+        // in indexes_changes, we have the node local indexes: they are neither hashed,
+        // nor kept in the change history
+        for (key, value) in indexes_changes.iter() {
+            if let Some(value) = value {
+                self.current_batch.lock().put_cf(handle_indexes, key, value);
+            } else {
+                self.current_batch.lock().delete_cf(handle_indexes, key);
+            }
+        }
+
         if let Some(change_id) = change_id {
             self.set_change_id_to_batch(change_id);
         }
@@ -549,6 +561,7 @@ where
         self.write_changes(
             changes,
             versioning_changes,
+            BTreeMap::new(),
             Some(stream_changes.change_id),
             true,
         )?;
@@ -598,6 +611,7 @@ impl RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
                 ColumnFamilyDescriptor::new(STATE_CF, Options::default()),
                 ColumnFamilyDescriptor::new(METADATA_CF, Options::default()),
                 ColumnFamilyDescriptor::new(VERSIONING_CF, Options::default()),
+                ColumnFamilyDescriptor::new(INDEXES_CF, Options::default()),
             ],
         )?;
 
@@ -683,10 +697,36 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
 
     /// Writes the batch to the DB
     fn write_batch(&mut self, batch: DBBatch, versioning_batch: DBBatch, change_id: Option<Slot>) {
-        self.write_changes(batch, versioning_batch, change_id, false)
+        self.write_changes(batch, versioning_batch, DBBatch::new(), change_id, false)
             .expect(CRUD_ERROR);
     }
 
+    /// Writes the batch to the DB, along with a batch of node local indexes
+    fn write_batch_with_indexes(
+        &mut self,
+        batch: DBBatch,
+        versioning_batch: DBBatch,
+        indexes_batch: DBBatch,
+        change_id: Option<Slot>,
+    ) {
+        self.write_changes(batch, versioning_batch, indexes_batch, change_id, false)
+            .expect(CRUD_ERROR);
+    }
+
+    /// Writes a batch to the node local indexes (INDEXES_CF)
+    fn write_index_batch(&mut self, batch: DBBatch) {
+        let handle = self.db.cf_handle(INDEXES_CF).expect(CF_ERROR);
+        let mut write_batch = WriteBatch::default();
+        for (key, value) in batch.iter() {
+            if let Some(value) = value {
+                write_batch.put_cf(handle, key, value);
+            } else {
+                write_batch.delete_cf(handle, key);
+            }
+        }
+        self.db.write(write_batch).expect(CRUD_ERROR);
+    }
+
     /// Utility function to put / update a key & value in the batch
     fn put_or_update_entry_value(&self, batch: &mut DBBatch, key: Vec<u8>, value: &[u8]) {
         batch.insert(key, Some(value.to_vec()));
@@ -718,6 +758,9 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
             VERSIONING_CF => {
                 self.write_batch(DBBatch::new(), batch, change_id);
             }
+            INDEXES_CF => {
+                self.write_index_batch(batch);
+            }
             _ => {}
         }
     }
@@ -1024,6 +1067,55 @@ mod test {
         assert!(dump_column(db.clone(), "versioning").is_empty());
     }
 
+    #[test]
+    fn test_index_batch() {
+        // Entries of the indexes column family are not part of the state hash
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir_db.path().to_path_buf(),
+            max_history_length: 100,
+            max_final_state_elements_size: 100,
+            max_versioning_elements_size: 100,
+            thread_count: THREAD_COUNT,
+        };
+        let db = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+        ));
+        let change_id = db.read().get_change_id().unwrap();
+
+        let mut batch = DBBatch::new();
+        batch.insert(vec![1, 2, 3], Some(vec![4, 5, 6]));
+        db.write().write_index_batch(batch.clone());
+
+        assert_eq!(dump_column_opt(db.clone(), INDEXES_CF), batch);
+        assert!(dump_column(db.clone(), STATE_CF).is_empty());
+        assert_eq!(
+            Hash::compute_from(db.read().get_xof_db_hash().to_bytes()),
+            initial_hash()
+        );
+        assert_eq!(db.read().get_change_id().unwrap(), change_id);
+
+        let mut batch = DBBatch::new();
+        batch.insert(vec![1, 2, 3], None);
+        db.write().write_index_batch(batch);
+        assert!(dump_column(db.clone(), INDEXES_CF).is_empty());
+
+        // Indexes written along with a state batch only change the state
+        let mut batch = DBBatch::new();
+        batch.insert(vec![7, 8], Some(vec![9]));
+        let mut indexes_batch = DBBatch::new();
+        indexes_batch.insert(vec![1, 2, 3], Some(vec![4, 5, 6]));
+        db.write().write_batch_with_indexes(
+            batch.clone(),
+            DBBatch::new(),
+            indexes_batch.clone(),
+            Some(Slot::new(1, 0)),
+        );
+        assert_eq!(dump_column_opt(db.clone(), INDEXES_CF), indexes_batch);
+        assert_eq!(dump_column_opt(db.clone(), STATE_CF), batch);
+        assert_eq!(db.read().get_change_id().unwrap(), Slot::new(1, 0));
+    }
+
     #[test]
     fn test_basics_2() {
         // 1- Init a db + check initial hash
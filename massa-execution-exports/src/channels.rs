@@ -1,10 +1,13 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
 use crate::types::SlotExecutionOutput;
+use massa_async_pool::AsyncMessageEvent;
 
 /// channels used by the execution worker
 #[derive(Clone)]
 pub struct ExecutionChannels {
     /// Broadcast channel for new slot execution outputs
     pub slot_execution_output_sender: tokio::sync::broadcast::Sender<SlotExecutionOutput>,
+    /// Broadcast channel for asynchronous messages leaving the final pool
+    pub async_message_event_sender: tokio::sync::broadcast::Sender<AsyncMessageEvent>,
 }
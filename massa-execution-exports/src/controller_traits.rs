@@ -3,20 +3,27 @@
 //! This module exports generic traits representing interfaces for interacting with the Execution worker
 
 use crate::types::{
-    ExecutionBlockMetadata, ExecutionQueryRequest, ExecutionQueryResponse, ReadOnlyExecutionRequest,
+    ExecutionBlockMetadata, ExecutionQueryRequest, ExecutionQueryResponse, ReadOnlyExecutionBundle,
+    ReadOnlyExecutionRequest,
 };
 use crate::ExecutionError;
-use crate::{ExecutionAddressInfo, ReadOnlyExecutionOutput};
+use crate::{
+    ExecutionAddressInfo, ExecutionLedgerAnalytics, ExecutionProfile, ModuleCacheEntryInfo,
+    ModuleCacheStats, ReadOnlyExecutionOutput,
+};
+use massa_hash::Hash;
 use massa_models::address::Address;
 use massa_models::amount::Amount;
 use massa_models::block_id::BlockId;
 use massa_models::denunciation::DenunciationIndex;
 use massa_models::execution::EventFilter;
+use massa_models::ledger_tree::{LedgerTreeItem, LedgerTreePaths};
 use massa_models::operation::OperationId;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashMap;
 use massa_models::slot::Slot;
 use massa_models::stats::ExecutionStats;
+use massa_pos_exports::StakingCycleRecord;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 
@@ -94,6 +101,20 @@ pub trait ExecutionController: Send + Sync {
         req: ReadOnlyExecutionRequest,
     ) -> Result<ReadOnlyExecutionOutput, ExecutionError>;
 
+    /// Execute an ordered bundle of read-only requests without causing modifications to the consensus state.
+    /// Each request sees the state overrides of the bundle and the effects of the previous successful requests.
+    ///
+    /// # arguments
+    /// * `bundle`: an instance of `ReadOnlyExecutionBundle` describing the requests and the simulated state
+    ///
+    /// # returns
+    /// The output (or error) of each request, in order,
+    /// or an error if the bundle itself is invalid.
+    fn execute_readonly_bundle(
+        &self,
+        bundle: ReadOnlyExecutionBundle,
+    ) -> Result<Vec<Result<ReadOnlyExecutionOutput, ExecutionError>>, ExecutionError>;
+
     /// Check if a denunciation has been executed given a `DenunciationIndex`
     /// (speculative, final)
     fn get_denunciation_execution_status(
@@ -104,9 +125,48 @@ pub trait ExecutionController: Send + Sync {
     /// Gets information about a batch of addresses
     fn get_addresses_infos(&self, addresses: &[Address]) -> Vec<ExecutionAddressInfo>;
 
+    /// Gets the staking history of an address, per cycle.
+    /// Returns None if the staking history is disabled on this node.
+    fn get_staking_history(&self, address: &Address) -> Option<BTreeMap<u64, StakingCycleRecord>>;
+
+    /// Gets aggregated views over the final ledger: the `rich_list_length` richest addresses
+    /// and the datastore accounting of `addresses`.
+    /// Returns None if the ledger analytics are disabled on this node.
+    fn get_ledger_analytics(
+        &self,
+        rich_list_length: usize,
+        addresses: &[Address],
+    ) -> Option<ExecutionLedgerAnalytics>;
+
+    /// Gets the final values of ledger items with their paths in the node local ledger Merkle tree.
+    /// Returns None if the ledger Merkle tree is disabled on this node, or while it is being updated.
+    fn get_ledger_tree_paths(&self, items: &[(Address, LedgerTreeItem)])
+        -> Option<LedgerTreePaths>;
+
     /// Get execution statistics
     fn get_stats(&self) -> ExecutionStats;
 
+    /// Get the module cache statistics
+    fn get_module_cache_stats(&self) -> ModuleCacheStats;
+
+    /// Get the module cache status of the modules with the given bytecode hashes
+    fn get_module_cache_entries(&self, hashes: &[Hash]) -> Vec<ModuleCacheEntryInfo>;
+
+    /// Remove the modules with the given bytecode hashes from the module cache
+    fn evict_modules(&self, hashes: &[Hash]);
+
+    /// Pin the modules with the given bytecode hashes in RAM.
+    /// Fails if one of the modules is neither cached nor tracked by the call statistics.
+    fn pin_modules(&self, hashes: &[Hash]) -> Result<(), ExecutionError>;
+
+    /// Unpin the modules with the given bytecode hashes
+    fn unpin_modules(&self, hashes: &[Hash]);
+
+    /// Get the execution profile of the recently executed slots,
+    /// with the `top_n` most expensive smart contract functions.
+    /// Returns None if execution profiling is disabled on this node.
+    fn get_execution_profile(&self, top_n: usize) -> Option<ExecutionProfile>;
+
     /// Returns a boxed clone of self.
     /// Useful to allow cloning `Box<dyn ExecutionController>`.
     fn clone_box(&self) -> Box<dyn ExecutionController>;
@@ -48,6 +48,9 @@ pub enum ExecutionError {
     /// Given gas is above the threshold: {0}
     TooMuchGas(String),
 
+    /// Invalid read-only execution request: {0}
+    InvalidReadOnlyRequest(String),
+
     /// Include operation error: {0}
     IncludeOperationError(String),
 
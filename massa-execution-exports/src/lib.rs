@@ -59,14 +59,17 @@ pub use controller_traits::MockExecutionController;
 pub use controller_traits::{ExecutionController, ExecutionManager};
 pub use error::{ExecutionError, ExecutionQueryError};
 pub use event_store::EventStore;
+pub use massa_module_cache::types::{ModuleCacheEntryInfo, ModuleCacheStats};
 pub use massa_sc_runtime::GasCosts;
 pub use settings::{ExecutionConfig, StorageCostsConstants};
 pub use types::{
-    ExecutedBlockInfo, ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionOutput,
-    ExecutionQueryCycleInfos, ExecutionQueryExecutionStatus, ExecutionQueryRequest,
-    ExecutionQueryRequestItem, ExecutionQueryResponse, ExecutionQueryResponseItem,
-    ExecutionQueryStakerInfo, ExecutionStackElement, ReadOnlyCallRequest, ReadOnlyExecutionOutput,
-    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget, SlotExecutionOutput,
+    AsyncMessageFilter, ContractCallProfile, ExecutedBlockInfo, ExecutionAddressInfo,
+    ExecutionBlockMetadata, ExecutionLedgerAnalytics, ExecutionOutput, ExecutionProfile,
+    ExecutionProfileEntry, ExecutionQueryAsyncMessage, ExecutionQueryCycleInfos,
+    ExecutionQueryExecutionStatus, ExecutionQueryRequest, ExecutionQueryRequestItem,
+    ExecutionQueryResponse, ExecutionQueryResponseItem, ExecutionQueryStakerInfo,
+    ExecutionStackElement, ReadOnlyCallRequest, ReadOnlyExecutionBundle, ReadOnlyExecutionOutput,
+    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget, ReadOnlyPreviousValues, SlotExecutionOutput,
 };
 
 #[cfg(any(feature = "test-exports", feature = "gas_calibration"))]
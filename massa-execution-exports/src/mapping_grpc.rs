@@ -185,16 +185,15 @@ pub fn to_event_filter(
 pub fn to_execution_query_response(
     value: Result<ExecutionQueryResponseItem, ExecutionQueryError>,
 ) -> grpc_api::ExecutionQueryResponse {
-    match value {
+    match value
+        .map_err(Into::into)
+        .and_then(to_execution_query_result)
+    {
         Ok(item) => grpc_api::ExecutionQueryResponse {
-            response: Some(grpc_api::execution_query_response::Response::Result(
-                to_execution_query_result(item),
-            )),
+            response: Some(grpc_api::execution_query_response::Response::Result(item)),
         },
         Err(err) => grpc_api::ExecutionQueryResponse {
-            response: Some(grpc_api::execution_query_response::Response::Error(
-                err.into(),
-            )),
+            response: Some(grpc_api::execution_query_response::Response::Error(err)),
         },
     }
 }
@@ -202,7 +201,7 @@ pub fn to_execution_query_response(
 // Convertss a `ExecutionQueryResponseItem` to a `grpc_api::ExecutionQueryResponseItem`
 fn to_execution_query_result(
     value: ExecutionQueryResponseItem,
-) -> grpc_api::ExecutionQueryResponseItem {
+) -> Result<grpc_api::ExecutionQueryResponseItem, grpc_model::Error> {
     let response_item = match value {
         ExecutionQueryResponseItem::Boolean(result) => {
             grpc_api::execution_query_response_item::ResponseItem::Boolean(result)
@@ -266,11 +265,18 @@ fn to_execution_query_result(
                 },
             )
         }
+        // the asynchronous messages are not part of the gRPC API yet
+        ExecutionQueryResponseItem::AsyncMessages(_) => {
+            return Err(grpc_model::Error {
+                code: 501,
+                message: "asynchronous messages are not available through gRPC".to_string(),
+            })
+        }
     };
 
-    grpc_api::ExecutionQueryResponseItem {
+    Ok(grpc_api::ExecutionQueryResponseItem {
         response_item: Some(response_item),
-    }
+    })
 }
 
 // Convertss a `ExecutionQueryCycleInfos` to a `grpc_api::CycleInfos`
@@ -4,7 +4,7 @@
 
 use massa_models::amount::Amount;
 use massa_sc_runtime::GasCosts;
-use massa_time::MassaTime;
+use massa_time::{MassaTime, SharedClock};
 use num::rational::Ratio;
 use std::path::PathBuf;
 
@@ -68,6 +68,8 @@ pub struct ExecutionConfig {
     pub storage_costs_constants: StorageCostsConstants,
     /// Max gas for read only executions
     pub max_read_only_gas: u64,
+    /// Max number of periods after the next candidate slot at which read only bundles can be executed
+    pub max_read_only_lookahead_periods: u64,
     /// Gas costs
     pub gas_costs: GasCosts,
     /// Gas used by a transaction, a roll buy or a roll sell)
@@ -82,6 +84,10 @@ pub struct ExecutionConfig {
     pub hd_cache_size: usize,
     /// Amount of entries removed when `hd_cache_size` is reached
     pub snip_amount: usize,
+    /// Path to the module call statistics storage, used to warm the module cache up at startup
+    pub module_call_stats_path: PathBuf,
+    /// Number of most called modules compiled and loaded in RAM at startup
+    pub module_cache_warm_up_count: usize,
     /// Number of roll to remove per denunciation
     pub roll_count_to_slash_on_denunciation: u64,
     /// Denunciation expire delta
@@ -90,6 +96,22 @@ pub struct ExecutionConfig {
     pub broadcast_enabled: bool,
     /// slot execution outputs channel capacity
     pub broadcast_slot_execution_output_channel_capacity: usize,
+    /// asynchronous message events channel capacity
+    pub broadcast_async_message_event_channel_capacity: usize,
     /// max size of event data, in bytes
     pub max_event_size: usize,
+    /// whether the per-address staking history is recorded
+    pub staking_history: bool,
+    /// whether the ledger analytics index is maintained
+    pub ledger_analytics: bool,
+    /// whether the ledger Merkle tree is maintained
+    pub ledger_merkle_tree: bool,
+    /// whether the transactions and roll operations of a block are executed optimistically in parallel
+    pub parallel_operation_execution: bool,
+    /// whether the wall time and gas of the executions are profiled
+    pub execution_profiling: bool,
+    /// duration of the window over which the execution profile is kept
+    pub execution_profiling_window: MassaTime,
+    /// source of the current time, the system clock outside of tests
+    pub clock: SharedClock,
 }
@@ -5,7 +5,7 @@
 use crate::{ExecutionConfig, StorageCostsConstants};
 use massa_models::config::*;
 use massa_sc_runtime::GasCosts;
-use massa_time::MassaTime;
+use massa_time::{MassaTime, SharedClock};
 use tempfile::TempDir;
 
 impl Default for ExecutionConfig {
@@ -42,6 +42,7 @@ impl Default for ExecutionConfig {
             max_datastore_value_size: MAX_DATASTORE_VALUE_LENGTH,
             storage_costs_constants,
             max_read_only_gas: 1_000_000_000,
+            max_read_only_lookahead_periods: 100,
             gas_costs: GasCosts::new(
                 concat!(
                     env!("CARGO_MANIFEST_DIR"),
@@ -61,13 +62,23 @@ impl Default for ExecutionConfig {
             lru_cache_size: 1000,
             hd_cache_size: 10_000,
             snip_amount: 10,
+            module_call_stats_path: TempDir::new().unwrap().path().to_path_buf(),
+            module_cache_warm_up_count: 10,
             roll_count_to_slash_on_denunciation: 1,
             denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
             broadcast_enabled: true,
             broadcast_slot_execution_output_channel_capacity: 5000,
+            broadcast_async_message_event_channel_capacity: 5000,
             max_event_size: 50_000,
+            staking_history: false,
+            ledger_analytics: false,
+            ledger_merkle_tree: false,
+            parallel_operation_execution: false,
+            execution_profiling: false,
+            execution_profiling_window: MassaTime::from_millis(60000),
             max_function_length: 1000,
             max_parameter_length: 1000,
+            clock: SharedClock::default(),
         }
     }
 }
@@ -4,8 +4,10 @@
 
 use crate::error::ExecutionQueryError;
 use crate::event_store::EventStore;
+use massa_async_pool::{AsyncMessage, AsyncMessageEvent, AsyncMessageId, AsyncMessageStatus};
 use massa_final_state::StateChanges;
 use massa_hash::Hash;
+use massa_ledger_exports::{LedgerAnalyticsTotals, LedgerChanges, LedgerEntryAnalytics};
 use massa_models::block_id::BlockId;
 use massa_models::bytecode::Bytecode;
 use massa_models::datastore::Datastore;
@@ -17,7 +19,7 @@ use massa_models::prehash::PreHashSet;
 use massa_models::{
     address::Address, address::ExecutionAddressCycleInfo, amount::Amount, slot::Slot,
 };
-use massa_pos_exports::ProductionStats;
+use massa_pos_exports::{ProductionStats, StakingHistoryChanges};
 use massa_storage::Storage;
 use std::collections::{BTreeMap, BTreeSet};
 
@@ -119,6 +121,24 @@ pub enum ExecutionQueryRequestItem {
 
     /// get filtered events. Returns ExecutionQueryResponseItem::Events
     Events(EventFilter),
+
+    /// gets the asynchronous messages (candidate) of the pool matching a filter, returns ExecutionQueryResponseItem::AsyncMessages(messages)
+    AsyncMessagesCandidate(AsyncMessageFilter),
+    /// gets the asynchronous messages (final) of the pool matching a filter, returns ExecutionQueryResponseItem::AsyncMessages(messages)
+    AsyncMessagesFinal(AsyncMessageFilter),
+}
+
+/// Filter on the asynchronous messages of the pool
+#[derive(Clone, Debug)]
+pub struct AsyncMessageFilter {
+    /// only the messages sent by this address
+    pub sender: Option<Address>,
+    /// only the messages sent to this address
+    pub destination: Option<Address>,
+    /// number of matching messages skipped, in the priority order of the pool
+    pub offset: usize,
+    /// maximum number of messages returned
+    pub limit: usize,
 }
 
 /// Execution state query response item
@@ -143,6 +163,8 @@ pub enum ExecutionQueryResponseItem {
     CycleInfos(ExecutionQueryCycleInfos),
     /// Events
     Events(Vec<SCOutputEvent>),
+    /// asynchronous messages of the pool
+    AsyncMessages(Vec<ExecutionQueryAsyncMessage>),
 }
 
 /// Execution status of an operation or denunciation
@@ -160,6 +182,18 @@ pub enum ExecutionQueryExecutionStatus {
     ExecutableOrExpired,
 }
 
+/// Asynchronous message of the pool
+pub struct ExecutionQueryAsyncMessage {
+    /// message ID
+    pub id: AsyncMessageId,
+    /// message content
+    pub message: AsyncMessage,
+    /// whether the message can be executed at the slot following the queried state, or why it can not
+    pub status: AsyncMessageStatus,
+    /// whether the message is in the final pool
+    pub is_final: bool,
+}
+
 /// Information about cycles
 pub struct ExecutionQueryCycleInfos {
     /// cycle number
@@ -203,6 +237,71 @@ pub struct ExecutionAddressInfo {
     pub cycle_infos: Vec<ExecutionAddressCycleInfo>,
 }
 
+/// Aggregated views over the final ledger, read from the node local ledger analytics index
+#[derive(Clone, Debug)]
+pub struct ExecutionLedgerAnalytics {
+    /// final slot the analytics are attached to
+    pub slot: Slot,
+    /// totals of the final ledger
+    pub totals: LedgerAnalyticsTotals,
+    /// value of the final rolls at `roll_price`
+    pub rolls_value: Amount,
+    /// sum of the pending deferred credits
+    pub deferred_credits: Amount,
+    /// addresses with the highest final balances, by decreasing balance
+    pub rich_list: Vec<(Address, Amount)>,
+    /// analytics of the requested ledger entries with the storage cost of their datastore,
+    /// None if the entry does not exist
+    pub entries: Vec<Option<(LedgerEntryAnalytics, Amount)>>,
+}
+
+/// Number of executions, wall time and gas spent in an execution category
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExecutionProfileEntry {
+    /// number of executions
+    pub count: u64,
+    /// total wall time of the executions, in microseconds
+    pub time_micros: u64,
+    /// total gas spent by the executions
+    pub gas: u64,
+}
+
+impl ExecutionProfileEntry {
+    /// Adds an execution, or the executions of another entry, to the entry
+    pub fn add(&mut self, other: &ExecutionProfileEntry) {
+        self.count = self.count.saturating_add(other.count);
+        self.time_micros = self.time_micros.saturating_add(other.time_micros);
+        self.gas = self.gas.saturating_add(other.gas);
+    }
+}
+
+/// Profile of the calls to a smart contract function
+#[derive(Clone, Debug)]
+pub struct ContractCallProfile {
+    /// address of the called smart contract
+    pub address: Address,
+    /// called function
+    pub function: String,
+    /// calls made by operations and asynchronous messages
+    pub calls: ExecutionProfileEntry,
+}
+
+/// Execution profile of the slots executed during the recent profiling window
+#[derive(Clone, Debug, Default)]
+pub struct ExecutionProfile {
+    /// duration of the profiling window, in milliseconds
+    pub window_millis: u64,
+    /// executed slots
+    pub slots: ExecutionProfileEntry,
+    /// executed operations, per operation type
+    pub operations: BTreeMap<String, ExecutionProfileEntry>,
+    /// executed asynchronous message batches, the count being the number of executed messages
+    pub async_messages: ExecutionProfileEntry,
+    /// most expensive smart contract functions called by operations and asynchronous messages,
+    /// by decreasing gas then wall time
+    pub top_contracts: Vec<ContractCallProfile>,
+}
+
 /// structure describing the output of the execution of a slot
 #[derive(Debug, Clone)]
 pub enum SlotExecutionOutput {
@@ -235,6 +334,10 @@ pub struct ExecutionOutput {
     pub state_changes: StateChanges,
     /// events emitted by the execution step
     pub events: EventStore,
+    /// staking activity caused by the execution step (empty if the staking history is disabled)
+    pub staking_history_changes: StakingHistoryChanges,
+    /// asynchronous messages that left the pool during the execution step
+    pub async_message_events: Vec<AsyncMessageEvent>,
 }
 
 /// structure describing the output of a read only execution
@@ -246,6 +349,21 @@ pub struct ReadOnlyExecutionOutput {
     pub gas_cost: u64,
     /// Returned value from the module call
     pub call_result: Vec<u8>,
+    /// Values of the state entries changed by the execution, before it
+    pub previous_values: ReadOnlyPreviousValues,
+}
+
+/// Values of the state entries changed by a read only execution, before the execution
+#[derive(Debug, Clone, Default)]
+pub struct ReadOnlyPreviousValues {
+    /// previous balance of the addresses whose ledger entry changed, None if the entry did not exist
+    pub balances: BTreeMap<Address, Option<Amount>>,
+    /// previous bytecode of the addresses whose bytecode changed, None if the entry did not exist
+    pub bytecodes: BTreeMap<Address, Option<Bytecode>>,
+    /// previous value of the changed datastore entries, None if the entry did not exist
+    pub datastore_entries: BTreeMap<(Address, Vec<u8>), Option<Vec<u8>>>,
+    /// previous roll count of the addresses whose roll count changed
+    pub roll_counts: BTreeMap<Address, u64>,
 }
 
 /// structure describing different types of read-only execution request
@@ -263,6 +381,20 @@ pub struct ReadOnlyExecutionRequest {
     pub fee: Option<Amount>,
 }
 
+/// structure describing an ordered bundle of read-only execution requests
+#[derive(Debug, Clone)]
+pub struct ReadOnlyExecutionBundle {
+    /// Requests executed in order, each one seeing the effects of the previous successful ones
+    pub requests: Vec<ReadOnlyExecutionRequest>,
+    /// Ledger changes applied to the candidate state before the first request.
+    /// They are visible to the executions but are not part of their outputs.
+    pub state_overrides: LedgerChanges,
+    /// Slot at which the requests are executed, which also sets the execution timestamp.
+    /// Defaults to the slot following the latest executed candidate slot,
+    /// and cannot be more than `max_read_only_lookahead_periods` periods after it.
+    pub slot: Option<Slot>,
+}
+
 /// structure describing different possible targets of a read-only execution request
 #[derive(Debug, Clone)]
 pub enum ReadOnlyExecutionTarget {
@@ -8,12 +8,13 @@
 //! and does not write anything persistent to the consensus state.
 
 use crate::active_history::HistorySearchResult;
-use crate::speculative_async_pool::SpeculativeAsyncPool;
+use crate::parallel_execution::{AccessRecorder, StateKey};
+use crate::speculative_async_pool::{has_next_occurrence, SpeculativeAsyncPool};
 use crate::speculative_executed_denunciations::SpeculativeExecutedDenunciations;
 use crate::speculative_executed_ops::SpeculativeExecutedOps;
 use crate::speculative_ledger::SpeculativeLedger;
 use crate::{active_history::ActiveHistory, speculative_roll_state::SpeculativeRollState};
-use massa_async_pool::{AsyncMessage, AsyncPoolChanges};
+use massa_async_pool::{AsyncMessage, AsyncMessageEvent, AsyncMessageOutcome, AsyncPoolChanges};
 use massa_async_pool::{AsyncMessageId, AsyncMessageInfo};
 use massa_executed_ops::{ExecutedDenunciationsChanges, ExecutedOpsChanges};
 use massa_execution_exports::{
@@ -22,7 +23,9 @@ use massa_execution_exports::{
 };
 use massa_final_state::{FinalStateController, StateChanges};
 use massa_hash::Hash;
-use massa_ledger_exports::{LedgerChanges, SetOrKeep};
+use massa_ledger_exports::{
+    LedgerChanges, LedgerEntry, LedgerEntryUpdate, SetOrKeep, SetUpdateOrDelete,
+};
 use massa_models::address::ExecutionAddressCycleInfo;
 use massa_models::block_id::BlockIdSerializer;
 use massa_models::bytecode::Bytecode;
@@ -37,15 +40,15 @@ use massa_models::{
     slot::Slot,
 };
 use massa_module_cache::controller::ModuleCache;
-use massa_pos_exports::PoSChanges;
+use massa_pos_exports::{PoSChanges, StakingCycleRecord, StakingHistoryChanges};
 use massa_serialization::Serializer;
 use massa_versioning::address_factory::{AddressArgs, AddressFactory};
-use massa_versioning::versioning::MipStore;
+use massa_versioning::versioning::{MipComponent, MipStore};
 use massa_versioning::versioning_factory::{FactoryStrategy, VersioningFactory};
 use parking_lot::RwLock;
 use rand::SeedableRng;
 use rand_xoshiro::Xoshiro256PlusPlus;
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::sync::Arc;
 use tracing::{debug, warn};
 
@@ -70,6 +73,9 @@ pub struct ExecutionContextSnapshot {
     /// speculative roll state changes caused so far in the context
     pub pos_changes: PoSChanges,
 
+    /// staking activity recorded so far in the context
+    pub staking_history_changes: StakingHistoryChanges,
+
     /// counter of newly created addresses so far at this slot during this execution
     pub created_addr_index: u64,
 
@@ -85,10 +91,44 @@ pub struct ExecutionContextSnapshot {
     /// keep the count of event emitted in the context
     pub event_count: usize,
 
+    /// count of the asynchronous messages recorded as having left the pool so far in the context
+    pub async_message_event_count: usize,
+
     /// Unsafe random state
     pub unsafe_rng: Xoshiro256PlusPlus,
 }
 
+/// State entries written by an operation executed in an isolated context,
+/// to be merged into the context of its slot (see `parallel_execution.rs`).
+pub(crate) struct IsolatedWrites {
+    /// changes of the written ledger entries
+    ledger_changes: Vec<(Address, SetUpdateOrDelete<LedgerEntry, LedgerEntryUpdate>)>,
+
+    /// roll counts and deferred credits of the written roll entries
+    roll_changes: Vec<(Address, Option<u64>, Vec<(Slot, Amount)>)>,
+
+    /// staking activity of the written roll entries
+    staking_history_changes: Vec<(Address, StakingCycleRecord)>,
+
+    /// written executed operations
+    executed_ops: Vec<(OperationId, (bool, Slot))>,
+
+    /// events emitted by the operation
+    events: Vec<SCOutputEvent>,
+
+    /// call stack left by the operation
+    stack: Vec<ExecutionStackElement>,
+
+    /// minimal balance of the operation creator
+    creator_min_balance: Option<Amount>,
+
+    /// creator of the operation
+    creator_address: Option<Address>,
+
+    /// the operation ID
+    origin_operation_id: Option<OperationId>,
+}
+
 /// An execution context that needs to be initialized before executing bytecode,
 /// passed to the VM to interact with during bytecode execution (through ABIs),
 /// and read after execution to gather results.
@@ -115,7 +155,20 @@ pub struct ExecutionContext {
 
     /// speculative asynchronous pool state,
     /// as seen after everything that happened so far in the context
+    #[cfg(all(
+        not(feature = "gas_calibration"),
+        not(feature = "benchmarking"),
+        not(feature = "test-exports"),
+        not(test)
+    ))]
     speculative_async_pool: SpeculativeAsyncPool,
+    #[cfg(any(
+        feature = "gas_calibration",
+        feature = "benchmarking",
+        feature = "test-exports",
+        test
+    ))]
+    pub(crate) speculative_async_pool: SpeculativeAsyncPool,
 
     /// speculative roll state,
     /// as seen after everything that happened so far in the context
@@ -127,6 +180,12 @@ pub struct ExecutionContext {
     /// speculative list of executed denunciations
     speculative_executed_denunciations: SpeculativeExecutedDenunciations,
 
+    /// staking activity recorded so far, if the staking history is enabled
+    staking_history_changes: StakingHistoryChanges,
+
+    /// asynchronous messages that left the pool so far during the slot
+    async_message_events: Vec<AsyncMessageEvent>,
+
     /// minimal balance allowed for the creator of the operation after its execution
     pub creator_min_balance: Option<Amount>,
 
@@ -171,6 +230,9 @@ pub struct ExecutionContext {
 
     /// Address factory
     pub address_factory: AddressFactory,
+
+    /// recorder of the state entries accessed
+    accesses: AccessRecorder,
 }
 
 impl ExecutionContext {
@@ -217,6 +279,8 @@ impl ExecutionContext {
                 final_state,
                 active_history,
             ),
+            staking_history_changes: Default::default(),
+            async_message_events: Default::default(),
             creator_min_balance: Default::default(),
             slot: Slot::new(0, 0),
             created_addr_index: Default::default(),
@@ -233,9 +297,124 @@ impl ExecutionContext {
             config,
             address_factory: AddressFactory { mip_store },
             execution_trail_hash,
+            accesses: Default::default(),
         }
     }
 
+    /// Sets the recorder of the state entries accessed in the speculative states of the context
+    pub(crate) fn set_access_recorder(&mut self, accesses: AccessRecorder) {
+        self.speculative_ledger
+            .set_access_recorder(accesses.clone());
+        self.speculative_async_pool
+            .set_access_recorder(accesses.clone());
+        self.speculative_roll_state
+            .set_access_recorder(accesses.clone());
+        self.speculative_executed_ops
+            .set_access_recorder(accesses.clone());
+        self.accesses = accesses;
+    }
+
+    /// Resets the context to a snapshot, except for its asynchronous pool, and clears its events,
+    /// so that an operation can be executed in isolation on top of it (see `parallel_execution.rs`).
+    /// The results of the operations accessing the asynchronous pool are never merged,
+    /// so the asynchronous pool is not restored.
+    pub(crate) fn reset_to_isolation_base(&mut self, base: &ExecutionContextSnapshot) {
+        self.speculative_ledger
+            .reset_to_snapshot(base.ledger_changes.clone());
+        self.speculative_roll_state
+            .reset_to_snapshot(base.pos_changes.clone());
+        self.speculative_executed_ops
+            .reset_to_snapshot(base.executed_ops.clone());
+        self.speculative_executed_denunciations
+            .reset_to_snapshot(base.executed_denunciations.clone());
+        self.staking_history_changes = base.staking_history_changes.clone();
+        self.created_addr_index = base.created_addr_index;
+        self.created_event_index = base.created_event_index;
+        self.created_message_index = base.created_message_index;
+        self.stack = base.stack.clone();
+        self.unsafe_rng = base.unsafe_rng.clone();
+        self.events.clear();
+        self.creator_min_balance = None;
+        self.creator_address = None;
+        self.origin_operation_id = None;
+        self.accesses.take();
+    }
+
+    /// Gathers the state entries written by the operation executed in isolation since the last
+    /// `reset_to_isolation_base`, as well as its events and the operation-level context fields.
+    ///
+    /// # Arguments
+    /// * `writes`: the state entries written by the operation
+    pub(crate) fn get_isolated_writes(&self, writes: &HashSet<StateKey>) -> IsolatedWrites {
+        let mut isolated_writes = IsolatedWrites {
+            ledger_changes: Vec::new(),
+            roll_changes: Vec::new(),
+            staking_history_changes: Vec::new(),
+            executed_ops: Vec::new(),
+            events: self.events.0.iter().cloned().collect(),
+            stack: self.stack.clone(),
+            creator_min_balance: self.creator_min_balance,
+            creator_address: self.creator_address,
+            origin_operation_id: self.origin_operation_id,
+        };
+        for key in writes {
+            match key {
+                StateKey::LedgerEntry(addr) => {
+                    if let Some(change) = self.speculative_ledger.get_entry_change(addr) {
+                        isolated_writes.ledger_changes.push((*addr, change));
+                    }
+                }
+                StateKey::RollEntry(addr) => {
+                    let (roll_count, credits) = self.speculative_roll_state.get_entry_changes(addr);
+                    isolated_writes
+                        .roll_changes
+                        .push((*addr, roll_count, credits));
+                    if let Some(record) = self.staking_history_changes.get(addr) {
+                        isolated_writes
+                            .staking_history_changes
+                            .push((*addr, *record));
+                    }
+                }
+                StateKey::ExecutedOp(op_id) => {
+                    if let Some(status) = self.speculative_executed_ops.get_added_op(op_id) {
+                        isolated_writes.executed_ops.push((*op_id, status));
+                    }
+                }
+                StateKey::AsyncPool => {
+                    panic!(
+                        "writes to the asynchronous pool cannot be merged from an isolated context"
+                    )
+                }
+            }
+        }
+        isolated_writes
+    }
+
+    /// Merges the state entries written by an operation executed in isolation (see `get_isolated_writes`).
+    /// The merged entries must not have been written in this context since the isolation base was taken.
+    pub(crate) fn apply_isolated_writes(&mut self, isolated_writes: IsolatedWrites) {
+        for (addr, change) in isolated_writes.ledger_changes {
+            self.speculative_ledger.set_entry_change(addr, change);
+        }
+        for (addr, roll_count, credits) in isolated_writes.roll_changes {
+            self.speculative_roll_state
+                .set_entry_changes(addr, roll_count, credits);
+        }
+        for (addr, record) in isolated_writes.staking_history_changes {
+            self.staking_history_changes.insert(addr, record);
+        }
+        for (op_id, (op_exec_status, op_valid_until_slot)) in isolated_writes.executed_ops {
+            self.insert_executed_op(op_id, op_exec_status, op_valid_until_slot);
+        }
+        for event in isolated_writes.events {
+            self.event_emit(event);
+        }
+        self.stack = isolated_writes.stack;
+        self.creator_min_balance = isolated_writes.creator_min_balance;
+        self.creator_address = isolated_writes.creator_address;
+        self.origin_operation_id = isolated_writes.origin_operation_id;
+    }
+
     /// Returns a snapshot containing the clone of the current execution state.
     /// Note that the snapshot does not include slot-level information such as the slot number or block ID.
     pub(crate) fn get_snapshot(&self) -> ExecutionContextSnapshot {
@@ -247,11 +426,13 @@ impl ExecutionContext {
             pos_changes: self.speculative_roll_state.get_snapshot(),
             executed_ops: self.speculative_executed_ops.get_snapshot(),
             executed_denunciations: self.speculative_executed_denunciations.get_snapshot(),
+            staking_history_changes: self.staking_history_changes.clone(),
             created_addr_index: self.created_addr_index,
             created_event_index: self.created_event_index,
             created_message_index: self.created_message_index,
             stack: self.stack.clone(),
             event_count: self.events.0.len(),
+            async_message_event_count: self.async_message_events.len(),
             unsafe_rng: self.unsafe_rng.clone(),
         }
     }
@@ -275,11 +456,14 @@ impl ExecutionContext {
             .reset_to_snapshot(snapshot.executed_ops);
         self.speculative_executed_denunciations
             .reset_to_snapshot(snapshot.executed_denunciations);
+        self.staking_history_changes = snapshot.staking_history_changes;
         self.created_addr_index = snapshot.created_addr_index;
         self.created_event_index = snapshot.created_event_index;
         self.created_message_index = snapshot.created_message_index;
         self.stack = snapshot.stack;
         self.unsafe_rng = snapshot.unsafe_rng;
+        self.async_message_events
+            .truncate(snapshot.async_message_event_count);
 
         // For events, set snapshot delta to error events.
         for event in self.events.0.range_mut(snapshot.event_count..) {
@@ -353,11 +537,29 @@ impl ExecutionContext {
         max_gas: u64,
         async_msg_cst_gas_cost: u64,
     ) -> Vec<(Option<Bytecode>, AsyncMessage)> {
-        self.speculative_async_pool
-            .take_batch_to_execute(self.slot, max_gas, async_msg_cst_gas_cost)
-            .into_iter()
-            .map(|(_id, msg)| (self.get_bytecode(&msg.destination), msg))
-            .collect()
+        let taken = self.speculative_async_pool.take_batch_to_execute(
+            self.slot,
+            max_gas,
+            async_msg_cst_gas_cost,
+        );
+        let mut batch = Vec::with_capacity(taken.len());
+        for (message_id, message) in taken {
+            // the fee only pays for one occurrence: a periodic message is kept in the pool
+            // only if its sender pays the fee of the next one, and is dropped otherwise
+            if has_next_occurrence(&message, &self.slot) {
+                match self.transfer_coins(Some(message.sender), None, message.fee, false) {
+                    Ok(()) => self
+                        .speculative_async_pool
+                        .rearm_periodic_message(message_id, &message),
+                    Err(err) => debug!(
+                        "periodic async message {:?} dropped: could not charge its fee to {}: {}",
+                        message_id, message.sender, err
+                    ),
+                }
+            }
+            batch.push((self.get_bytecode(&message.destination), message));
+        }
+        batch
     }
 
     /// Create a new `ExecutionContext` for executing an active slot.
@@ -714,6 +916,9 @@ impl ExecutionContext {
     pub fn add_rolls(&mut self, buyer_addr: &Address, roll_count: u64) {
         self.speculative_roll_state
             .add_rolls(buyer_addr, roll_count);
+        self.record_staking_activity(buyer_addr, |record| {
+            record.rolls_bought = record.rolls_bought.saturating_add(roll_count);
+        });
     }
 
     /// Try to sell `roll_count` rolls from the seller address.
@@ -733,7 +938,11 @@ impl ExecutionContext {
             self.config.periods_per_cycle,
             self.config.thread_count,
             self.config.roll_price,
-        )
+        )?;
+        self.record_staking_activity(seller_addr, |record| {
+            record.rolls_sold = record.rolls_sold.saturating_add(roll_count);
+        });
+        Ok(())
     }
 
     /// Try to slash `roll_count` rolls from the denounced address. If not enough rolls,
@@ -750,13 +959,17 @@ impl ExecutionContext {
         // try to slash as many roll as available
         let slashed_rolls = self
             .speculative_roll_state
-            .try_slash_rolls(denounced_addr, roll_count);
+            .try_slash_rolls(denounced_addr, roll_count)
+            .unwrap_or_default();
+        self.record_staking_activity(denounced_addr, |record| {
+            record.rolls_slashed = record.rolls_slashed.saturating_add(slashed_rolls);
+        });
 
         // convert slashed rolls to coins (as deferred credits => coins)
         let mut slashed_coins = self
             .config
             .roll_price
-            .checked_mul_u64(slashed_rolls.unwrap_or_default())
+            .checked_mul_u64(slashed_rolls)
             .ok_or_else(|| {
                 ExecutionError::RuntimeError(format!(
                     "Cannot multiply roll price by {}",
@@ -813,6 +1026,46 @@ impl ExecutionContext {
     ) {
         self.speculative_roll_state
             .update_production_stats(creator, slot, block_id);
+        self.record_staking_activity(creator, |record| {
+            if block_id.is_some() {
+                record.block_success_count = record.block_success_count.saturating_add(1);
+            } else {
+                record.block_failure_count = record.block_failure_count.saturating_add(1);
+            }
+        });
+    }
+
+    /// Records staking activity of an address, if the staking history is enabled.
+    /// The recorded activity is part of the execution output of the slot.
+    ///
+    /// # Arguments
+    /// * `address`: the address concerned by the activity
+    /// * `update`: function updating the staking record of the address for the current slot
+    pub fn record_staking_activity<F>(&mut self, address: &Address, update: F)
+    where
+        F: FnOnce(&mut StakingCycleRecord),
+    {
+        if self.config.staking_history && !self.read_only {
+            self.accesses.write(StateKey::RollEntry(*address));
+            update(self.staking_history_changes.entry(*address).or_default());
+        }
+    }
+
+    /// Records that an asynchronous message left the pool during the slot.
+    /// The recorded events are part of the execution output of the slot.
+    ///
+    /// # Arguments
+    /// * `message`: the message that left the pool
+    /// * `outcome`: why it left the pool
+    pub fn record_async_message_event(
+        &mut self,
+        message: &AsyncMessage,
+        outcome: AsyncMessageOutcome,
+    ) {
+        if !self.read_only {
+            self.async_message_events
+                .push(AsyncMessageEvent::new(self.slot, message, outcome));
+        }
     }
 
     /// Execute the deferred credits of `slot`.
@@ -826,17 +1079,72 @@ impl ExecutionContext {
             .credits
         {
             for (address, amount) in map {
-                if let Err(e) = self.transfer_coins(None, Some(address), amount, false) {
-                    debug!(
+                match self.transfer_coins(None, Some(address), amount, false) {
+                    Ok(()) => self.record_staking_activity(&address, |record| {
+                        record.deferred_credits_released =
+                            record.deferred_credits_released.saturating_add(amount);
+                    }),
+                    Err(e) => debug!(
                         "could not credit {} deferred coins to {} at slot {}: {}",
                         amount, address, slot, e
-                    );
+                    ),
                 }
             }
         }
     }
 
     /// Finishes a slot and generates the execution output.
+    /// Returns the active version of the `AsyncMessageTrigger` MIP component at the current slot
+    pub fn get_async_message_trigger_version(&self) -> u32 {
+        self.get_component_version(MipComponent::AsyncMessageTrigger)
+    }
+
+    /// Returns the active version of a MIP component at the current slot
+    fn get_component_version(&self, component: MipComponent) -> u32 {
+        let slot_timestamp = get_block_slot_timestamp(
+            self.config.thread_count,
+            self.config.t0,
+            self.config.genesis_timestamp,
+            self.slot,
+        )
+        .expect("could not compute current slot timestamp");
+        self.address_factory
+            .mip_store
+            .get_latest_component_version_at(&component, slot_timestamp)
+    }
+
+    /// Cancels an asynchronous message of the pool sent by `sender`, and reimburses its coins.
+    /// Only available once the `AsyncMessageCancellation` MIP component reaches version 1.
+    ///
+    /// # Arguments
+    /// * `sender`: address cancelling the message, must be its sender
+    /// * `emission_slot`: slot at which the message was emitted
+    /// * `emission_index`: index of the message among the ones emitted at `emission_slot`
+    pub fn cancel_sent_async_message(
+        &mut self,
+        sender: &Address,
+        emission_slot: Slot,
+        emission_index: u64,
+    ) -> Result<(), ExecutionError> {
+        if self.get_component_version(MipComponent::AsyncMessageCancellation) == 0 {
+            return Err(ExecutionError::RuntimeError(
+                "the cancellation of asynchronous messages is not active yet".to_string(),
+            ));
+        }
+        let message = self
+            .speculative_async_pool
+            .take_sent_message(sender, emission_slot, emission_index)
+            .ok_or_else(|| {
+                ExecutionError::RuntimeError(format!(
+                    "no asynchronous message emitted at slot {} with index {} by {} in the pool",
+                    emission_slot, emission_index, sender
+                ))
+            })?;
+        self.cancel_async_message(&message);
+        self.record_async_message_event(&message, AsyncMessageOutcome::Cancelled);
+        Ok(())
+    }
+
     /// Settles emitted asynchronous messages, reimburse the senders of deleted messages.
     /// Moves the output of the execution out of the context,
     /// resetting some context fields in the process.
@@ -853,11 +1161,19 @@ impl ExecutionContext {
         let ledger_changes = self.speculative_ledger.take();
 
         // settle emitted async messages and reimburse the senders of deleted messages
-        let deleted_messages = self
-            .speculative_async_pool
-            .settle_slot(&slot, &ledger_changes);
+        let trigger_version = self.get_async_message_trigger_version();
+        let deleted_messages =
+            self.speculative_async_pool
+                .settle_slot(&slot, &ledger_changes, trigger_version);
         for (_msg_id, msg) in deleted_messages {
             self.cancel_async_message(&msg);
+            // messages are only deleted before the end of their validity window on pool overflow
+            let outcome = if slot >= msg.validity_end {
+                AsyncMessageOutcome::Expired
+            } else {
+                AsyncMessageOutcome::Evicted
+            };
+            self.record_async_message_event(&msg, outcome);
         }
 
         // update module cache
@@ -874,13 +1190,18 @@ impl ExecutionContext {
             .slot
             .is_last_of_cycle(self.config.periods_per_cycle, self.config.thread_count)
         {
-            self.speculative_roll_state.settle_production_stats(
+            let sold_rolls = self.speculative_roll_state.settle_production_stats(
                 &slot,
                 self.config.periods_per_cycle,
                 self.config.thread_count,
                 self.config.roll_price,
                 self.config.max_miss_ratio,
             );
+            for (address, roll_count) in sold_rolls {
+                self.record_staking_activity(&address, |record| {
+                    record.rolls_sold = record.rolls_sold.saturating_add(roll_count);
+                });
+            }
         }
 
         // generate the execution output
@@ -899,6 +1220,8 @@ impl ExecutionContext {
             block_info,
             state_changes,
             events: std::mem::take(&mut self.events),
+            staking_history_changes: std::mem::take(&mut self.staking_history_changes),
+            async_message_events: std::mem::take(&mut self.async_message_events),
         }
     }
 
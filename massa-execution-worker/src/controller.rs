@@ -8,17 +8,22 @@ use crate::request_queue::{RequestQueue, RequestWithResponseSender};
 use massa_channel::MassaChannel;
 use massa_execution_exports::{
     ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionConfig, ExecutionController,
-    ExecutionError, ExecutionManager, ExecutionQueryError, ExecutionQueryExecutionStatus,
-    ExecutionQueryRequest, ExecutionQueryRequestItem, ExecutionQueryResponse,
-    ExecutionQueryResponseItem, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ExecutionError, ExecutionLedgerAnalytics, ExecutionManager, ExecutionProfile,
+    ExecutionQueryError, ExecutionQueryExecutionStatus, ExecutionQueryRequest,
+    ExecutionQueryRequestItem, ExecutionQueryResponse, ExecutionQueryResponseItem,
+    ModuleCacheEntryInfo, ModuleCacheStats, ReadOnlyExecutionBundle, ReadOnlyExecutionOutput,
+    ReadOnlyExecutionRequest,
 };
+use massa_hash::Hash;
 use massa_models::denunciation::DenunciationIndex;
 use massa_models::execution::EventFilter;
+use massa_models::ledger_tree::{LedgerTreeItem, LedgerTreePaths};
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashMap;
 use massa_models::stats::ExecutionStats;
 use massa_models::{address::Address, amount::Amount, operation::OperationId};
 use massa_models::{block_id::BlockId, slot::Slot};
+use massa_pos_exports::StakingCycleRecord;
 use parking_lot::{Condvar, Mutex, RwLock};
 use std::collections::{BTreeMap, HashMap};
 use std::fmt::Display;
@@ -35,8 +40,9 @@ pub(crate) struct ExecutionInputData {
     pub new_blockclique: Option<HashMap<Slot, BlockId>>,
     /// storage instances for previously unprocessed blocks
     pub block_metadata: PreHashMap<BlockId, ExecutionBlockMetadata>,
-    /// queue for read-only execution requests and response MPSCs to send back their outputs
-    pub readonly_requests: RequestQueue<ReadOnlyExecutionRequest, ReadOnlyExecutionOutput>,
+    /// queue for read-only execution bundles and response MPSCs to send back their outputs
+    pub readonly_requests:
+        RequestQueue<ReadOnlyExecutionBundle, Vec<Result<ReadOnlyExecutionOutput, ExecutionError>>>,
 }
 
 impl Display for ExecutionInputData {
@@ -324,6 +330,16 @@ impl ExecutionController for ExecutionControllerImpl {
                         execution_lock.get_filtered_sc_output_event(filter),
                     ))
                 }
+                ExecutionQueryRequestItem::AsyncMessagesCandidate(filter) => {
+                    Ok(ExecutionQueryResponseItem::AsyncMessages(
+                        execution_lock.get_async_messages(&filter, true),
+                    ))
+                }
+                ExecutionQueryRequestItem::AsyncMessagesFinal(filter) => {
+                    Ok(ExecutionQueryResponseItem::AsyncMessages(
+                        execution_lock.get_async_messages(&filter, false),
+                    ))
+                }
             };
             resp.responses.push(resp_item);
         }
@@ -385,6 +401,25 @@ impl ExecutionController for ExecutionControllerImpl {
         &self,
         req: ReadOnlyExecutionRequest,
     ) -> Result<ReadOnlyExecutionOutput, ExecutionError> {
+        self.execute_readonly_bundle(ReadOnlyExecutionBundle {
+            requests: vec![req],
+            state_overrides: Default::default(),
+            slot: None,
+        })?
+        .pop()
+        .unwrap_or_else(|| {
+            Err(ExecutionError::ChannelError(
+                "readonly execution returned no output".into(),
+            ))
+        })
+    }
+
+    /// Executes a bundle of read-only requests
+    /// Read-only requests do not modify consensus state
+    fn execute_readonly_bundle(
+        &self,
+        bundle: ReadOnlyExecutionBundle,
+    ) -> Result<Vec<Result<ReadOnlyExecutionOutput, ExecutionError>>, ExecutionError> {
         let resp_rx = {
             let mut input_data = self.input_data.1.lock();
 
@@ -401,7 +436,7 @@ impl ExecutionController for ExecutionControllerImpl {
             // append the request to the queue of input read-only requests
             input_data
                 .readonly_requests
-                .push(RequestWithResponseSender::new(req, resp_tx));
+                .push(RequestWithResponseSender::new(bundle, resp_tx));
 
             // wake up the execution main loop
             self.input_data.0.notify_one();
@@ -455,11 +490,64 @@ impl ExecutionController for ExecutionControllerImpl {
         res
     }
 
+    /// Gets the staking history of an address, per cycle
+    fn get_staking_history(&self, address: &Address) -> Option<BTreeMap<u64, StakingCycleRecord>> {
+        self.execution_state.read().get_staking_history(address)
+    }
+
+    fn get_ledger_analytics(
+        &self,
+        rich_list_length: usize,
+        addresses: &[Address],
+    ) -> Option<ExecutionLedgerAnalytics> {
+        self.execution_state
+            .read()
+            .get_ledger_analytics(rich_list_length, addresses)
+    }
+
+    /// Gets the final values of ledger items with their paths in the node local ledger Merkle tree
+    fn get_ledger_tree_paths(
+        &self,
+        items: &[(Address, LedgerTreeItem)],
+    ) -> Option<LedgerTreePaths> {
+        self.execution_state.read().get_ledger_tree_paths(items)
+    }
+
     /// Get execution statistics
     fn get_stats(&self) -> ExecutionStats {
         self.execution_state.read().get_stats()
     }
 
+    /// See trait definition
+    fn get_module_cache_stats(&self) -> ModuleCacheStats {
+        self.execution_state.read().get_module_cache_stats()
+    }
+
+    /// See trait definition
+    fn get_module_cache_entries(&self, hashes: &[Hash]) -> Vec<ModuleCacheEntryInfo> {
+        self.execution_state.read().get_module_cache_entries(hashes)
+    }
+
+    /// See trait definition
+    fn evict_modules(&self, hashes: &[Hash]) {
+        self.execution_state.read().evict_modules(hashes)
+    }
+
+    /// See trait definition
+    fn pin_modules(&self, hashes: &[Hash]) -> Result<(), ExecutionError> {
+        self.execution_state.read().pin_modules(hashes)
+    }
+
+    /// See trait definition
+    fn unpin_modules(&self, hashes: &[Hash]) {
+        self.execution_state.read().unpin_modules(hashes)
+    }
+
+    /// See trait definition
+    fn get_execution_profile(&self, top_n: usize) -> Option<ExecutionProfile> {
+        self.execution_state.read().get_execution_profile(top_n)
+    }
+
     /// Returns a boxed clone of self.
     /// Allows cloning `Box<dyn ExecutionController>`,
     /// see `massa-execution-exports/controller_traits.rs`
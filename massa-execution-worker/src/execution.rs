@@ -9,24 +9,31 @@
 //! * the output of the execution is extracted from the context
 
 use crate::active_history::{ActiveHistory, HistorySearchResult};
-use crate::context::{ExecutionContext, ExecutionContextSnapshot};
+use crate::context::{ExecutionContext, ExecutionContextSnapshot, IsolatedWrites};
 use crate::interface_impl::InterfaceImpl;
+use crate::parallel_execution::{AccessRecorder, AccessSets, StateKey};
+use crate::profiling::{operation_type_name, ExecutionProfiler};
+use crate::speculative_async_pool::SpeculativeAsyncPool;
 use crate::stats::ExecutionStatsCounter;
-use massa_async_pool::AsyncMessage;
+use massa_async_pool::{AsyncMessage, AsyncMessageId, AsyncMessageInfo, AsyncMessageOutcome};
 use massa_execution_exports::{
-    EventStore, ExecutedBlockInfo, ExecutionBlockMetadata, ExecutionChannels, ExecutionConfig,
-    ExecutionError, ExecutionOutput, ExecutionQueryCycleInfos, ExecutionQueryStakerInfo,
-    ExecutionStackElement, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
-    ReadOnlyExecutionTarget, SlotExecutionOutput,
+    AsyncMessageFilter, EventStore, ExecutedBlockInfo, ExecutionBlockMetadata, ExecutionChannels,
+    ExecutionConfig, ExecutionError, ExecutionLedgerAnalytics, ExecutionOutput, ExecutionProfile,
+    ExecutionQueryAsyncMessage, ExecutionQueryCycleInfos, ExecutionQueryStakerInfo,
+    ExecutionStackElement, ModuleCacheEntryInfo, ModuleCacheStats, ReadOnlyExecutionBundle,
+    ReadOnlyExecutionOutput, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+    ReadOnlyPreviousValues, SlotExecutionOutput,
 };
-use massa_final_state::FinalStateController;
-use massa_ledger_exports::{SetOrDelete, SetUpdateOrDelete};
+use massa_final_state::{FinalStateController, StateChanges};
+use massa_hash::Hash;
+use massa_ledger_exports::{Applicable, LedgerEntry, SetOrDelete, SetOrKeep, SetUpdateOrDelete};
 use massa_metrics::MassaMetrics;
 use massa_models::address::ExecutionAddressCycleInfo;
 use massa_models::bytecode::Bytecode;
 use massa_models::datastore::get_prefix_bounds;
 use massa_models::denunciation::{Denunciation, DenunciationIndex};
 use massa_models::execution::EventFilter;
+use massa_models::ledger_tree::{LedgerTreeItem, LedgerTreePaths};
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
 use massa_models::stats::ExecutionStats;
@@ -34,18 +41,21 @@ use massa_models::timeslots::get_block_slot_timestamp;
 use massa_models::{
     address::Address,
     block_id::BlockId,
+    endorsement::SecureShareEndorsement,
     operation::{OperationId, OperationType, SecureShareOperation},
 };
 use massa_models::{amount::Amount, slot::Slot};
 use massa_module_cache::config::ModuleCacheConfig;
 use massa_module_cache::controller::ModuleCache;
-use massa_pos_exports::SelectorController;
+use massa_pos_exports::{SelectorController, StakingCycleRecord};
 use massa_sc_runtime::{Interface, Response, VMError};
 use massa_versioning::versioning::MipStore;
 use massa_wallet::Wallet;
 use parking_lot::{Mutex, RwLock};
-use std::collections::{BTreeMap, BTreeSet};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, trace, warn};
 
 /// Used to acquire a lock on the execution context
@@ -80,6 +90,8 @@ pub(crate) struct ExecutionState {
     execution_interface: Box<dyn Interface>,
     // execution statistics
     stats_counter: ExecutionStatsCounter,
+    // execution profiler, if profiling is enabled
+    profiler: Option<Mutex<ExecutionProfiler>>,
     // cache of pre compiled sc modules
     module_cache: Arc<RwLock<ModuleCache>>,
     // MipStore (Versioning)
@@ -120,6 +132,14 @@ impl ExecutionState {
             let final_state_read = final_state.read();
             last_final_slot = final_state_read.get_slot();
             execution_trail_hash = final_state_read.get_execution_trail_hash();
+            // the ledger analytics index is node local: it is only rebuilt if it lags behind the final ledger
+            if config.ledger_analytics {
+                final_state_read.get_ledger().rebuild_analytics();
+            }
+            // and so is the ledger Merkle tree
+            if config.ledger_merkle_tree {
+                final_state_read.get_ledger().rebuild_merkle_tree();
+            }
         }
 
         // Create default active history
@@ -133,6 +153,8 @@ impl ExecutionState {
             hd_cache_size: config.hd_cache_size,
             snip_amount: config.snip_amount,
             max_module_length: config.max_bytecode_size,
+            call_stats_path: config.module_call_stats_path.clone(),
+            warm_up_module_count: config.module_cache_warm_up_count,
         })));
 
         // Create an empty placeholder execution context, with shared atomic access
@@ -163,7 +185,19 @@ impl ExecutionState {
             // no active slots executed yet: set active_cursor to the last final block
             active_cursor: last_final_slot,
             final_cursor: last_final_slot,
-            stats_counter: ExecutionStatsCounter::new(config.stats_time_window_duration),
+            stats_counter: ExecutionStatsCounter::new(
+                config.stats_time_window_duration,
+                config.clock.clone(),
+            ),
+            profiler: if config.execution_profiling {
+                Some(Mutex::new(ExecutionProfiler::new(
+                    config.execution_profiling_window,
+                    config.clock.clone(),
+                    massa_metrics.clone(),
+                )))
+            } else {
+                None
+            },
             module_cache,
             config,
             mip_store,
@@ -185,6 +219,53 @@ impl ExecutionState {
             .get_stats(self.active_cursor, self.final_cursor)
     }
 
+    /// Get the execution profile of the slots executed during the profiling window,
+    /// or None if execution profiling is disabled
+    pub fn get_execution_profile(&self, top_n: usize) -> Option<ExecutionProfile> {
+        self.profiler
+            .as_ref()
+            .map(|profiler| profiler.lock().get_profile(top_n))
+    }
+
+    /// Get the module cache statistics
+    pub fn get_module_cache_stats(&self) -> ModuleCacheStats {
+        self.module_cache.read().get_stats()
+    }
+
+    /// Get the module cache status of the modules with the given bytecode hashes
+    pub fn get_module_cache_entries(&self, hashes: &[Hash]) -> Vec<ModuleCacheEntryInfo> {
+        let module_cache = self.module_cache.read();
+        hashes
+            .iter()
+            .map(|hash| module_cache.get_entry_info(*hash))
+            .collect()
+    }
+
+    /// Remove the modules with the given bytecode hashes from the module cache
+    pub fn evict_modules(&self, hashes: &[Hash]) {
+        let mut module_cache = self.module_cache.write();
+        for hash in hashes {
+            module_cache.evict(*hash);
+        }
+    }
+
+    /// Pin the modules with the given bytecode hashes in RAM
+    pub fn pin_modules(&self, hashes: &[Hash]) -> Result<(), ExecutionError> {
+        let mut module_cache = self.module_cache.write();
+        for hash in hashes {
+            module_cache.pin(*hash)?;
+        }
+        Ok(())
+    }
+
+    /// Unpin the modules with the given bytecode hashes
+    pub fn unpin_modules(&self, hashes: &[Hash]) {
+        let mut module_cache = self.module_cache.write();
+        for hash in hashes {
+            module_cache.unpin(*hash);
+        }
+    }
+
     /// Applies the output of an execution to the final execution state.
     /// The newly applied final output should be from the slot just after the last executed final slot
     ///
@@ -211,11 +292,33 @@ impl ExecutionState {
         // as it will also write the MIP store on disk
         self.update_versioning_stats(&exec_out.block_info, &exec_out.slot);
 
+        // observe the executions of the slot, now final, in the profiling histograms
+        if let Some(profiler) = &self.profiler {
+            profiler.lock().finalize_slot(&exec_out.slot);
+        }
+
+        // update the node local ledger analytics, before the previous values are overwritten
+        if self.config.ledger_analytics {
+            self.final_state
+                .read()
+                .get_ledger()
+                .apply_analytics_changes(&exec_out.state_changes.ledger_changes, &exec_out.slot);
+        }
+        if self.config.ledger_merkle_tree {
+            self.final_state
+                .read()
+                .get_ledger()
+                .apply_merkle_changes(&exec_out.state_changes.ledger_changes, &exec_out.slot);
+        }
+
         let exec_out_2 = exec_out.clone();
-        // apply state changes to the final ledger
-        self.final_state
-            .write()
-            .finalize(exec_out.slot, exec_out.state_changes);
+        // apply state changes to the final ledger, and the staking activity of the slot
+        // to the node local staking history, in the same write
+        self.final_state.write().finalize(
+            exec_out.slot,
+            exec_out.state_changes,
+            exec_out.staking_history_changes,
+        );
 
         // update the final ledger's slot
         self.final_cursor = exec_out.slot;
@@ -253,6 +356,16 @@ impl ExecutionState {
                 .len(),
         );
 
+        let module_cache_stats = self.module_cache.read().get_stats();
+        self.massa_metrics.set_module_cache_stats(
+            module_cache_stats.lru_hits,
+            module_cache_stats.lru_misses,
+            module_cache_stats.hd_hits,
+            module_cache_stats.hd_misses,
+            module_cache_stats.compilations,
+            module_cache_stats.compilation_time_micros,
+        );
+
         self.massa_metrics.inc_executed_final_slot();
         if exec_out.block_info.is_some() {
             self.massa_metrics.inc_executed_final_slot_with_block();
@@ -272,6 +385,15 @@ impl ExecutionState {
                     err
                 );
             }
+            for event in exec_out.async_message_events {
+                if let Err(err) = self.channels.async_message_event_sender.send(event) {
+                    trace!(
+                        "error, failed to broadcast async message event for slot {} due to: {}",
+                        exec_out.slot,
+                        err
+                    );
+                }
+            }
         }
     }
 
@@ -300,22 +422,21 @@ impl ExecutionState {
     }
 
     /// Helper function.
-    /// Within a locked execution context (lock is taken at the beginning of the function then released at the end):
+    /// Within an execution context:
     /// - if not yet executed then transfer fee and add the operation to the context then return a context snapshot
     ///
     /// # Arguments
+    /// * `context`: the execution context, locked by the caller
     /// * `operation`: operation to be schedule
     /// * `sender_addr`: sender address for the operation (for fee transfer)
     fn prepare_operation_for_execution(
-        &self,
+        config: &ExecutionConfig,
+        context: &mut ExecutionContext,
         operation: &SecureShareOperation,
         sender_addr: Address,
     ) -> Result<ExecutionContextSnapshot, ExecutionError> {
         let operation_id = operation.id;
 
-        // lock execution context
-        let mut context = context_guard!(self);
-
         // ignore the operation if it was already executed
         if context.is_op_executed(&operation_id) {
             return Err(ExecutionError::IncludeOperationError(
@@ -329,8 +450,7 @@ impl ExecutionState {
             .get_balance(&sender_addr)
             .unwrap_or_else(Amount::zero);
         context.creator_min_balance = Some(
-            creator_initial_balance
-                .saturating_sub(operation.get_max_spending(self.config.roll_price)),
+            creator_initial_balance.saturating_sub(operation.get_max_spending(config.roll_price)),
         );
 
         // debit the fee from the operation sender
@@ -405,13 +525,15 @@ impl ExecutionState {
             ));
         }
 
-        // get operation ID
-        let operation_id = operation.id;
-
         // Add fee from operation.
         let new_block_credits = block_credits.saturating_add(operation.content.fee);
 
-        let context_snapshot = self.prepare_operation_for_execution(operation, sender_addr)?;
+        let context_snapshot = Self::prepare_operation_for_execution(
+            &self.config,
+            &mut context_guard!(self),
+            operation,
+            sender_addr,
+        )?;
 
         // update block gas
         *remaining_block_gas = new_remaining_block_gas;
@@ -420,72 +542,355 @@ impl ExecutionState {
         *block_credits = new_block_credits;
 
         // Call the execution process specific to the operation type.
-        let mut execution_result = match &operation.content.op {
+        // Smart contract executions return the gas they left unused.
+        let start_time = Instant::now();
+        let execution_result = match &operation.content.op {
             OperationType::ExecuteSC { .. } => {
                 self.execute_executesc_op(&operation.content.op, sender_addr)
             }
             OperationType::CallSC { .. } => {
                 self.execute_callsc_op(&operation.content.op, sender_addr)
             }
-            OperationType::RollBuy { .. } => {
-                self.execute_roll_buy_op(&operation.content.op, sender_addr)
+            OperationType::RollBuy { .. } => Self::execute_roll_buy_op(
+                &self.config,
+                &mut context_guard!(self),
+                &operation.content.op,
+                sender_addr,
+            )
+            .map(|_| 0),
+            OperationType::RollSell { .. } => Self::execute_roll_sell_op(
+                &mut context_guard!(self),
+                &operation.content.op,
+                sender_addr,
+            )
+            .map(|_| 0),
+            OperationType::Transaction { .. } => Self::execute_transaction_op(
+                &mut context_guard!(self),
+                &operation.content.op,
+                sender_addr,
+            )
+            .map(|_| 0),
+            OperationType::CancelAsyncMessage { .. } => Self::execute_cancel_async_message_op(
+                &mut context_guard!(self),
+                &operation.content.op,
+                sender_addr,
+            )
+            .map(|_| 0),
+        };
+        let used_gas = match &execution_result {
+            Ok(unused_gas) => op_gas.saturating_sub(*unused_gas),
+            Err(_) => op_gas,
+        };
+
+        Self::settle_operation_execution(
+            &mut context_guard!(self),
+            operation,
+            op_thread,
+            context_snapshot,
+            execution_result.map(|_| ()),
+        );
+
+        self.profile_operation(operation, start_time.elapsed(), used_gas);
+
+        Ok(())
+    }
+
+    /// Registers the execution of an operation in the profiler, if profiling is enabled
+    fn profile_operation(
+        &self,
+        operation: &SecureShareOperation,
+        elapsed: Duration,
+        used_gas: u64,
+    ) {
+        if let Some(profiler) = &self.profiler {
+            let contract_call = match &operation.content.op {
+                OperationType::CallSC {
+                    target_addr,
+                    target_func,
+                    ..
+                } if !target_func.is_empty() => Some((*target_addr, target_func.as_str())),
+                _ => None,
+            };
+            profiler.lock().register_operation(
+                operation_type_name(&operation.content.op),
+                contract_call,
+                elapsed,
+                used_gas,
+            );
+        }
+    }
+
+    /// Helper function.
+    /// Within an execution context, after the execution of an operation prepared by `prepare_operation_for_execution`:
+    /// checks the spending limit of the sender, reverts the context to the snapshot on failure,
+    /// and records the operation as executed.
+    ///
+    /// # Arguments
+    /// * `context`: the execution context, locked by the caller
+    /// * `operation`: the executed operation
+    /// * `op_thread`: thread of the operation
+    /// * `context_snapshot`: snapshot returned by `prepare_operation_for_execution`
+    /// * `execution_result`: result of the execution specific to the operation type
+    ///
+    /// # Returns
+    /// true if the operation was executed successfully
+    fn settle_operation_execution(
+        context: &mut ExecutionContext,
+        operation: &SecureShareOperation,
+        op_thread: u8,
+        context_snapshot: ExecutionContextSnapshot,
+        mut execution_result: Result<(), ExecutionError>,
+    ) -> bool {
+        let operation_id = operation.id;
+        let sender_addr = operation.content_creator_address;
+
+        if execution_result.is_ok() {
+            // check that the `max_coins` spending limit was respected by the sender
+            if let Some(creator_min_balance) = &context.creator_min_balance {
+                let creator_balance = context
+                    .get_balance(&sender_addr)
+                    .unwrap_or_else(Amount::zero);
+                if &creator_balance < creator_min_balance {
+                    execution_result = Err(ExecutionError::RuntimeError(format!(
+                        "at the end of the execution of the operation, the sender {} was expected to have at least {} coins according to the operation's max spending, but has only {}.",
+                        sender_addr, creator_min_balance, creator_balance
+                    )));
+                }
             }
-            OperationType::RollSell { .. } => {
-                self.execute_roll_sell_op(&operation.content.op, sender_addr)
+        }
+
+        // check execution results
+        match execution_result {
+            Ok(_) => {
+                context.insert_executed_op(
+                    operation_id,
+                    true,
+                    Slot::new(operation.content.expire_period, op_thread),
+                );
+                true
             }
-            OperationType::Transaction { .. } => {
-                self.execute_transaction_op(&operation.content.op, sender_addr)
+            Err(err) => {
+                // an error occurred: emit error event and reset context to snapshot
+                let err = ExecutionError::RuntimeError(format!(
+                    "runtime error when executing operation {}: {}",
+                    operation_id, &err
+                ));
+                debug!("{}", &err);
+                context.reset_to_snapshot(context_snapshot, err);
+
+                // Insert op AFTER the context has been restored (otherwise it would be overwritten)
+                context.insert_executed_op(
+                    operation_id,
+                    false,
+                    Slot::new(operation.content.expire_period, op_thread),
+                );
+                false
             }
-        };
+        }
+    }
 
+    /// Execute an operation in the context of a block, logging the reason why it could not be executed if any.
+    /// See `execute_operation`.
+    fn execute_block_operation(
+        &self,
+        operation: &SecureShareOperation,
+        block_slot: Slot,
+        block_id: &BlockId,
+        remaining_block_gas: &mut u64,
+        block_credits: &mut Amount,
+    ) {
+        if let Err(err) =
+            self.execute_operation(operation, block_slot, remaining_block_gas, block_credits)
         {
-            // lock execution context
-            let mut context = context_guard!(self);
+            debug!(
+                "failed executing operation {} in block {}: {}",
+                operation.id, block_id, err
+            );
+        }
+    }
 
-            if execution_result.is_ok() {
-                // check that the `max_coins` spending limit was respected by the sender
-                if let Some(creator_min_balance) = &context.creator_min_balance {
-                    let creator_balance = context
-                        .get_balance(&sender_addr)
-                        .unwrap_or_else(Amount::zero);
-                    if &creator_balance < creator_min_balance {
-                        execution_result = Err(ExecutionError::RuntimeError(format!(
-                            "at the end of the execution of the operation, the sender {} was expected to have at least {} coins according to the operation's max spending, but has only {}.",
-                            sender_addr, creator_min_balance, creator_balance
-                        )));
-                    }
-                }
+    /// Execute the operations of a block, in the order in which they appear in the block,
+    /// executing the transactions and roll operations optimistically in parallel (see `parallel_execution.rs`).
+    /// The resulting context is the same as if `execute_operation` had been called on each operation in order.
+    ///
+    /// # Arguments
+    /// * `operations`: operations of the block
+    /// * `block_slot`: slot of the block
+    /// * `block_id`: ID of the block
+    /// * `remaining_block_gas`: mutable reference towards the remaining gas in the block
+    /// * `block_credits`: mutable reference towards the total block reward/fee credits
+    fn execute_operations_in_parallel(
+        &self,
+        operations: &[SecureShareOperation],
+        block_slot: Slot,
+        block_id: &BlockId,
+        remaining_block_gas: &mut u64,
+        block_credits: &mut Amount,
+    ) {
+        // The block gas check of an operation depends on the operations executed before it.
+        // Execute the operations sequentially unless they all fit in the block gas.
+        let op_gas = |operation: &SecureShareOperation| {
+            operation.get_gas_usage(
+                self.config.base_operation_gas_cost,
+                self.config.gas_costs.sp_compilation_cost,
+            )
+        };
+        let total_gas = operations.iter().fold(0u64, |total, operation| {
+            total.saturating_add(op_gas(operation))
+        });
+        if total_gas > *remaining_block_gas {
+            for operation in operations {
+                self.execute_block_operation(
+                    operation,
+                    block_slot,
+                    block_id,
+                    remaining_block_gas,
+                    block_credits,
+                );
             }
+            return;
+        }
 
-            // check execution results
-            match execution_result {
-                Ok(_) => {
-                    context.insert_executed_op(
-                        operation_id,
-                        true,
-                        Slot::new(operation.content.expire_period, op_thread),
-                    );
-                }
-                Err(err) => {
-                    // an error occurred: emit error event and reset context to snapshot
-                    let err = ExecutionError::RuntimeError(format!(
-                        "runtime error when executing operation {}: {}",
-                        operation_id, &err
-                    ));
-                    debug!("{}", &err);
-                    context.reset_to_snapshot(context_snapshot, err);
-
-                    // Insert op AFTER the context has been restored (otherwise it would be overwritten)
-                    context.insert_executed_op(
-                        operation_id,
-                        false,
-                        Slot::new(operation.content.expire_period, op_thread),
-                    )
+        // Execute the candidate operations in isolated contexts on top of the current state.
+        // Only transactions and roll operations passing the slot and thread checks are candidates.
+        let config = &self.config;
+        let is_candidate = |operation: &SecureShareOperation| {
+            matches!(
+                operation.content.op,
+                OperationType::Transaction { .. }
+                    | OperationType::RollBuy { .. }
+                    | OperationType::RollSell { .. }
+            ) && operation
+                .get_validity_range(config.operation_validity_period)
+                .contains(&block_slot.period)
+                && operation
+                    .content_creator_address
+                    .get_thread(config.thread_count)
+                    == block_slot.thread
+        };
+        let (base, execution_trail_hash) = {
+            let context = context_guard!(self);
+            (context.get_snapshot(), context.execution_trail_hash)
+        };
+        let (final_state, active_history, module_cache, mip_store) = (
+            &self.final_state,
+            &self.active_history,
+            &self.module_cache,
+            &self.mip_store,
+        );
+        let chunk_size = std::cmp::max(1, operations.len() / rayon::current_num_threads());
+        let isolated_results: Vec<Option<(AccessSets, IsolatedWrites, Duration)>> = operations
+            .par_chunks(chunk_size)
+            .map(|chunk| {
+                let mut context = ExecutionContext::new(
+                    config.clone(),
+                    final_state.clone(),
+                    active_history.clone(),
+                    module_cache.clone(),
+                    mip_store.clone(),
+                    execution_trail_hash,
+                );
+                context.slot = block_slot;
+                context.opt_block_id = Some(*block_id);
+                let accesses = AccessRecorder::enabled();
+                context.set_access_recorder(accesses.clone());
+                chunk
+                    .iter()
+                    .map(|operation| {
+                        if !is_candidate(operation) {
+                            return None;
+                        }
+                        context.reset_to_isolation_base(&base);
+                        let start_time = Instant::now();
+                        let succeeded =
+                            Self::execute_isolated_operation(config, &mut context, operation);
+                        let elapsed = start_time.elapsed();
+                        let op_accesses = accesses.take();
+                        if !succeeded || op_accesses.writes.contains(&StateKey::AsyncPool) {
+                            return None;
+                        }
+                        let writes = context.get_isolated_writes(&op_accesses.writes);
+                        Some((op_accesses, writes, elapsed))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .flatten()
+            .collect();
+
+        // Merge the results in block order.
+        // An operation that accessed no entry written by the operations before it behaves as if executed sequentially:
+        // its writes are merged. The other operations are executed again, sequentially.
+        let accesses = AccessRecorder::enabled();
+        context_guard!(self).set_access_recorder(accesses.clone());
+        let mut written = HashSet::new();
+        let mut merged_count: usize = 0;
+        for (operation, isolated_result) in operations.iter().zip(isolated_results) {
+            match isolated_result {
+                Some((op_accesses, writes, elapsed)) if !op_accesses.conflicts_with(&written) => {
+                    *remaining_block_gas = remaining_block_gas.saturating_sub(op_gas(operation));
+                    *block_credits = block_credits.saturating_add(operation.content.fee);
+                    context_guard!(self).apply_isolated_writes(writes);
+                    self.profile_operation(operation, elapsed, op_gas(operation));
+                    merged_count += 1;
                 }
+                _ => self.execute_block_operation(
+                    operation,
+                    block_slot,
+                    block_id,
+                    remaining_block_gas,
+                    block_credits,
+                ),
             }
+            written.extend(accesses.take().writes);
         }
+        context_guard!(self).set_access_recorder(Default::default());
+        debug!(
+            "merged {} of {} operations executed in parallel in block {}",
+            merged_count,
+            operations.len(),
+            block_id
+        );
+    }
 
-        Ok(())
+    /// Execute a transaction or roll operation in an isolated execution context
+    /// (see `execute_operations_in_parallel`).
+    /// The slot, thread and block gas checks must be performed _outside_ of this function.
+    ///
+    /// # Returns
+    /// true if the operation was executed successfully
+    fn execute_isolated_operation(
+        config: &ExecutionConfig,
+        context: &mut ExecutionContext,
+        operation: &SecureShareOperation,
+    ) -> bool {
+        let sender_addr = operation.content_creator_address;
+        let op_thread = sender_addr.get_thread(config.thread_count);
+        let context_snapshot =
+            match Self::prepare_operation_for_execution(config, context, operation, sender_addr) {
+                Ok(snapshot) => snapshot,
+                Err(_) => return false,
+            };
+        let execution_result = match &operation.content.op {
+            OperationType::RollBuy { .. } => {
+                Self::execute_roll_buy_op(config, context, &operation.content.op, sender_addr)
+            }
+            OperationType::RollSell { .. } => {
+                Self::execute_roll_sell_op(context, &operation.content.op, sender_addr)
+            }
+            OperationType::Transaction { .. } => {
+                Self::execute_transaction_op(context, &operation.content.op, sender_addr)
+            }
+            _ => panic!("unexpected operation type"),
+        };
+        Self::settle_operation_execution(
+            context,
+            operation,
+            op_thread,
+            context_snapshot,
+            execution_result,
+        )
     }
 
     /// Execute a denunciation in the context of a block.
@@ -637,10 +1042,11 @@ impl ExecutionState {
     /// Will panic if called with another operation type
     ///
     /// # Arguments
+    /// * `context`: the execution context, locked by the caller
     /// * `operation`: the `WrappedOperation` to process, must be an `RollSell`
     /// * `sender_addr`: address of the sender
     pub fn execute_roll_sell_op(
-        &self,
+        context: &mut ExecutionContext,
         operation: &OperationType,
         seller_addr: Address,
     ) -> Result<(), ExecutionError> {
@@ -650,9 +1056,6 @@ impl ExecutionState {
             _ => panic!("unexpected operation type"),
         };
 
-        // acquire write access to the context
-        let mut context = context_guard!(self);
-
         // Set call stack
         // This needs to be defined before anything can fail, so that the emitted event contains the right stack
         context.stack = vec![ExecutionStackElement {
@@ -676,10 +1079,12 @@ impl ExecutionState {
     /// Will panic if called with another operation type
     ///
     /// # Arguments
+    /// * `context`: the execution context, locked by the caller
     /// * `operation`: the `WrappedOperation` to process, must be an `RollBuy`
     /// * `buyer_addr`: address of the buyer
     pub fn execute_roll_buy_op(
-        &self,
+        config: &ExecutionConfig,
+        context: &mut ExecutionContext,
         operation: &OperationType,
         buyer_addr: Address,
     ) -> Result<(), ExecutionError> {
@@ -689,9 +1094,6 @@ impl ExecutionState {
             _ => panic!("unexpected operation type"),
         };
 
-        // acquire write access to the context
-        let mut context = context_guard!(self);
-
         // Set call stack
         // This needs to be defined before anything can fail, so that the emitted event contains the right stack
         context.stack = vec![ExecutionStackElement {
@@ -702,7 +1104,7 @@ impl ExecutionState {
         }];
 
         // compute the amount of coins to spend
-        let spend_coins = match self.config.roll_price.checked_mul_u64(*roll_count) {
+        let spend_coins = match config.roll_price.checked_mul_u64(*roll_count) {
             Some(v) => v,
             None => {
                 return Err(ExecutionError::RollBuyError(format!(
@@ -731,10 +1133,11 @@ impl ExecutionState {
     ///
     /// # Arguments
     /// * `operation`: the `WrappedOperation` to process, must be a `Transaction`
-    /// * `operation_id`: ID of the operation
+    /// * `context`: the execution context, locked by the caller
+    /// * `operation`: the `WrappedOperation` to process, must be a `Transaction`
     /// * `sender_addr`: address of the sender
     pub fn execute_transaction_op(
-        &self,
+        context: &mut ExecutionContext,
         operation: &OperationType,
         sender_addr: Address,
     ) -> Result<(), ExecutionError> {
@@ -747,9 +1150,6 @@ impl ExecutionState {
             _ => panic!("unexpected operation type"),
         };
 
-        // acquire write access to the context
-        let mut context = context_guard!(self);
-
         // Set call stack
         // This needs to be defined before anything can fail, so that the emitted event contains the right stack
         context.stack = vec![ExecutionStackElement {
@@ -772,17 +1172,53 @@ impl ExecutionState {
         Ok(())
     }
 
+    /// Execute an operation of type `CancelAsyncMessage`
+    /// Will panic if called with another operation type
+    ///
+    /// # Arguments
+    /// * `context`: the execution context, locked by the caller
+    /// * `operation`: the `WrappedOperation` to process, must be a `CancelAsyncMessage`
+    /// * `sender_addr`: address of the sender
+    pub fn execute_cancel_async_message_op(
+        context: &mut ExecutionContext,
+        operation: &OperationType,
+        sender_addr: Address,
+    ) -> Result<(), ExecutionError> {
+        // process async message cancellations only
+        let (emission_slot, emission_index) = match operation {
+            OperationType::CancelAsyncMessage {
+                emission_slot,
+                emission_index,
+            } => (*emission_slot, *emission_index),
+            _ => panic!("unexpected operation type"),
+        };
+
+        // Set call stack
+        // This needs to be defined before anything can fail, so that the emitted event contains the right stack
+        context.stack = vec![ExecutionStackElement {
+            address: sender_addr,
+            coins: Amount::default(),
+            owned_addresses: vec![sender_addr],
+            operation_datastore: None,
+        }];
+
+        context.cancel_sent_async_message(&sender_addr, emission_slot, emission_index)
+    }
+
     /// Execute an operation of type `ExecuteSC`
     /// Will panic if called with another operation type
     ///
     /// # Arguments
     /// * `operation`: the `WrappedOperation` to process, must be an `ExecuteSC`
     /// * `sender_addr`: address of the sender
+    ///
+    /// # Returns
+    /// The gas left unused by the execution
     pub fn execute_executesc_op(
         &self,
         operation: &OperationType,
         sender_addr: Address,
-    ) -> Result<(), ExecutionError> {
+    ) -> Result<u64, ExecutionError> {
         // process ExecuteSC operations only
         let (bytecode, max_gas, datastore) = match &operation {
             OperationType::ExecuteSC {
@@ -816,7 +1252,7 @@ impl ExecutionState {
             .read()
             .load_tmp_module(bytecode, *max_gas)?;
         // run the VM
-        massa_sc_runtime::run_main(
+        let response = massa_sc_runtime::run_main(
             &*self.execution_interface,
             module,
             remaining_gas,
@@ -827,7 +1263,7 @@ impl ExecutionState {
             error,
         })?;
 
-        Ok(())
+        Ok(response.remaining_gas)
     }
 
     /// Execute an operation of type `CallSC`
@@ -838,11 +1274,14 @@ impl ExecutionState {
     /// * `block_creator_addr`: address of the block creator
     /// * `operation_id`: ID of the operation
     /// * `sender_addr`: address of the sender
+    ///
+    /// # Returns
+    /// The gas left unused by the execution
     pub fn execute_callsc_op(
         &self,
         operation: &OperationType,
         sender_addr: Address,
-    ) -> Result<(), ExecutionError> {
+    ) -> Result<u64, ExecutionError> {
         // process CallSC operations only
         let (max_gas, target_addr, target_func, param, coins) = match &operation {
             OperationType::CallSC {
@@ -895,7 +1334,7 @@ impl ExecutionState {
 
             // quit if there is no function to be called
             if target_func.is_empty() {
-                return Ok(());
+                return Ok(max_gas);
             }
 
             // Load bytecode. Assume empty bytecode if not found.
@@ -904,7 +1343,10 @@ impl ExecutionState {
 
         // load and execute the compiled module
         // IMPORTANT: do not keep a lock here as `run_function` uses the `get_module` interface
-        let (module, remaining_gas) = self.module_cache.write().load_module(&bytecode, max_gas)?;
+        let (module, remaining_gas) = self
+            .module_cache
+            .write()
+            .load_module(&bytecode, max_gas, false)?;
         let response = massa_sc_runtime::run_function(
             &*self.execution_interface,
             module,
@@ -922,11 +1364,11 @@ impl ExecutionState {
             }
             _ => (),
         }
-        response.map_err(|error| ExecutionError::VMError {
+        let response = response.map_err(|error| ExecutionError::VMError {
             context: "CallSC".to_string(),
             error,
         })?;
-        Ok(())
+        Ok(response.remaining_gas)
     }
 
     /// Tries to execute an asynchronous message
@@ -935,11 +1377,14 @@ impl ExecutionState {
     /// # Arguments
     /// * message: message information
     /// * bytecode: executable target bytecode, or None if unavailable
+    ///
+    /// # Returns
+    /// The gas left unused by the execution
     pub fn execute_async_message(
         &self,
-        message: AsyncMessage,
+        message: &AsyncMessage,
         bytecode: Option<Bytecode>,
-    ) -> Result<(), ExecutionError> {
+    ) -> Result<u64, ExecutionError> {
         // prepare execution context
         let context_snapshot;
         let bytecode = {
@@ -965,7 +1410,7 @@ impl ExecutionState {
             // check the target address
             if let Err(err) = context.check_target_sc_address(message.destination) {
                 context.reset_to_snapshot(context_snapshot, err.clone());
-                context.cancel_async_message(&message);
+                context.cancel_async_message(message);
                 return Err(err);
             }
 
@@ -975,7 +1420,7 @@ impl ExecutionState {
                 None => {
                     let err = ExecutionError::RuntimeError("no target bytecode found".into());
                     context.reset_to_snapshot(context_snapshot, err.clone());
-                    context.cancel_async_message(&message);
+                    context.cancel_async_message(message);
                     return Err(err);
                 }
             };
@@ -990,7 +1435,7 @@ impl ExecutionState {
                     err
                 ));
                 context.reset_to_snapshot(context_snapshot, err.clone());
-                context.cancel_async_message(&message);
+                context.cancel_async_message(message);
                 return Err(err);
             }
 
@@ -999,10 +1444,10 @@ impl ExecutionState {
 
         // load and execute the compiled module
         // IMPORTANT: do not keep a lock here as `run_function` uses the `get_module` interface
-        let (module, remaining_gas) = self
-            .module_cache
-            .write()
-            .load_module(&bytecode, message.max_gas)?;
+        let (module, remaining_gas) =
+            self.module_cache
+                .write()
+                .load_module(&bytecode, message.max_gas, false)?;
         let response = massa_sc_runtime::run_function(
             &*self.execution_interface,
             module,
@@ -1012,11 +1457,15 @@ impl ExecutionState {
             self.config.gas_costs.clone(),
         );
         match response {
-            Ok(Response { init_gas_cost, .. }) => {
+            Ok(Response {
+                init_gas_cost,
+                remaining_gas,
+                ..
+            }) => {
                 self.module_cache
                     .write()
                     .set_init_cost(&bytecode, init_gas_cost);
-                Ok(())
+                Ok(remaining_gas)
             }
             Err(error) => {
                 if let VMError::ExecutionError { init_gas_cost, .. } = error {
@@ -1031,12 +1480,57 @@ impl ExecutionState {
                 };
                 let mut context = context_guard!(self);
                 context.reset_to_snapshot(context_snapshot, err.clone());
-                context.cancel_async_message(&message);
+                context.cancel_async_message(message);
                 Err(err)
             }
         }
     }
 
+    /// Records in the staking history the endorsements included in a block,
+    /// and the ones that were drawn for its slot but are missing from it.
+    ///
+    /// # Arguments
+    /// * `context`: execution context of the slot
+    /// * `selector`: Reference to the selector
+    /// * `slot`: slot of the block
+    /// * `endorsements`: endorsements included in the block
+    fn record_endorsement_stats(
+        &self,
+        context: &mut ExecutionContext,
+        selector: &dyn SelectorController,
+        slot: Slot,
+        endorsements: &[SecureShareEndorsement],
+    ) {
+        for endorsement in endorsements {
+            context.record_staking_activity(&endorsement.content_creator_address, |record| {
+                record.endorsement_success_count =
+                    record.endorsement_success_count.saturating_add(1);
+            });
+        }
+        let selection = match selector.get_selection(slot) {
+            Ok(selection) => selection,
+            Err(err) => {
+                warn!(
+                    "could not get the endorsement draws of slot {} for the staking history: {}",
+                    slot, err
+                );
+                return;
+            }
+        };
+        let included_indices: BTreeSet<u32> = endorsements
+            .iter()
+            .map(|endorsement| endorsement.content.index)
+            .collect();
+        for (index, endorser) in selection.endorsements.iter().enumerate() {
+            if !included_indices.contains(&(index as u32)) {
+                context.record_staking_activity(endorser, |record| {
+                    record.endorsement_failure_count =
+                        record.endorsement_failure_count.saturating_add(1);
+                });
+            }
+        }
+    }
+
     /// Executes a full slot (with or without a block inside) without causing any changes to the state,
     /// just yielding the execution output.
     ///
@@ -1053,6 +1547,11 @@ impl ExecutionState {
         exec_target: Option<&(BlockId, ExecutionBlockMetadata)>,
         selector: Box<dyn SelectorController>,
     ) -> ExecutionOutput {
+        let slot_start_time = Instant::now();
+        if let Some(profiler) = &self.profiler {
+            profiler.lock().start_slot();
+        }
+
         // Create a new execution context for the whole active slot
         let mut execution_context = ExecutionContext::active_slot(
             self.config.clone(),
@@ -1076,9 +1575,26 @@ impl ExecutionState {
         // Try executing asynchronous messages.
         // Effects are cancelled on failure and the sender is reimbursed.
         for (opt_bytecode, message) in messages {
-            if let Err(err) = self.execute_async_message(message, opt_bytecode) {
-                debug!("failed executing async message: {}", err);
+            let start_time = Instant::now();
+            let (outcome, used_gas) = match self.execute_async_message(&message, opt_bytecode) {
+                Ok(unused_gas) => (
+                    AsyncMessageOutcome::ExecutedWithSuccess,
+                    message.max_gas.saturating_sub(unused_gas),
+                ),
+                Err(err) => {
+                    debug!("failed executing async message: {}", err);
+                    (AsyncMessageOutcome::ExecutedWithFailure, message.max_gas)
+                }
+            };
+            if let Some(profiler) = &self.profiler {
+                profiler.lock().register_async_message(
+                    message.destination,
+                    &message.function,
+                    start_time.elapsed(),
+                    used_gas,
+                );
             }
+            context_guard!(self).record_async_message_event(&message, outcome);
         }
 
         let mut block_info: Option<ExecutedBlockInfo> = None;
@@ -1141,16 +1657,22 @@ impl ExecutionState {
 
             // Try executing the operations of this block in the order in which they appear in the block.
             // Errors are logged but do not interrupt the execution of the slot.
-            for operation in operations.into_iter() {
-                if let Err(err) = self.execute_operation(
-                    &operation,
+            if self.config.parallel_operation_execution {
+                self.execute_operations_in_parallel(
+                    &operations,
                     stored_block.content.header.content.slot,
+                    block_id,
                     &mut remaining_block_gas,
                     &mut block_credits,
-                ) {
-                    debug!(
-                        "failed executing operation {} in block {}: {}",
-                        operation.id, block_id, err
+                );
+            } else {
+                for operation in operations.iter() {
+                    self.execute_block_operation(
+                        operation,
+                        stored_block.content.header.content.slot,
+                        block_id,
+                        &mut remaining_block_gas,
+                        &mut block_credits,
                     );
                 }
             }
@@ -1178,6 +1700,16 @@ impl ExecutionState {
             // Update speculative rolls state production stats
             context.update_production_stats(&block_creator_addr, *slot, Some(*block_id));
 
+            // Record the endorsements included and missed in the staking history
+            if self.config.staking_history {
+                self.record_endorsement_stats(
+                    &mut context,
+                    selector.as_ref(),
+                    *slot,
+                    &stored_block.content.header.content.endorsements,
+                );
+            }
+
             // Credit endorsement producers and endorsed block producers
             let mut remaining_credit = block_credits;
             let block_credit_part = block_credits
@@ -1193,6 +1725,9 @@ impl ExecutionState {
                 ) {
                     Ok(_) => {
                         remaining_credit = remaining_credit.saturating_sub(block_credit_part);
+                        context.record_staking_activity(&endorsement_creator, |record| {
+                            record.rewards = record.rewards.saturating_add(block_credit_part);
+                        });
                     }
                     Err(err) => {
                         debug!(
@@ -1211,6 +1746,9 @@ impl ExecutionState {
                 ) {
                     Ok(_) => {
                         remaining_credit = remaining_credit.saturating_sub(block_credit_part);
+                        context.record_staking_activity(&endorsement_target_creator, |record| {
+                            record.rewards = record.rewards.saturating_add(block_credit_part);
+                        });
                     }
                     Err(err) => {
                         debug!(
@@ -1222,13 +1760,18 @@ impl ExecutionState {
             }
 
             // Credit block creator with remaining_credit
-            if let Err(err) =
-                context.transfer_coins(None, Some(block_creator_addr), remaining_credit, false)
-            {
-                debug!(
-                    "failed to credit {} coins to block creator {} on block execution: {}",
-                    remaining_credit, block_creator_addr, err
-                )
+            match context.transfer_coins(None, Some(block_creator_addr), remaining_credit, false) {
+                Ok(_) => {
+                    context.record_staking_activity(&block_creator_addr, |record| {
+                        record.rewards = record.rewards.saturating_add(remaining_credit);
+                    });
+                }
+                Err(err) => {
+                    debug!(
+                        "failed to credit {} coins to block creator {} on block execution: {}",
+                        remaining_credit, block_creator_addr, err
+                    )
+                }
             }
         } else {
             // the slot is a miss, check who was supposed to be the creator and update production stats
@@ -1241,6 +1784,10 @@ impl ExecutionState {
         // Finish slot
         let exec_out = context_guard!(self).settle_slot(block_info);
 
+        if let Some(profiler) = &self.profiler {
+            profiler.lock().end_slot(*slot, slot_start_time.elapsed());
+        }
+
         // Broadcast a slot execution output to active channel subscribers.
         if self.config.broadcast_enabled {
             let slot_exec_out = SlotExecutionOutput::ExecutedSlot(exec_out.clone());
@@ -1363,6 +1910,106 @@ impl ExecutionState {
         );
     }
 
+    /// Runs an ordered bundle of read-only execution requests.
+    /// The state overrides of the bundle are pushed on top of a private copy of the active history,
+    /// and the output of each successful request is pushed after them,
+    /// so that every request sees the effects of the previous ones.
+    ///
+    /// # Arguments
+    /// * `bundle`: a read-only execution bundle
+    ///
+    /// # Returns
+    /// The output (or error) of each request of the bundle, or an error if the bundle is invalid
+    pub(crate) fn execute_readonly_bundle(
+        &self,
+        bundle: ReadOnlyExecutionBundle,
+    ) -> Result<Vec<Result<ReadOnlyExecutionOutput, ExecutionError>>, ExecutionError> {
+        // check that the whole bundle stays below the read-only gas threshold
+        let total_gas = bundle
+            .requests
+            .iter()
+            .fold(0u64, |acc, req| acc.saturating_add(req.max_gas));
+        if total_gas > self.config.max_read_only_gas {
+            return Err(ExecutionError::TooMuchGas(format!(
+                "execution gas for read-only bundle is {} which is above the maximum allowed {}",
+                total_gas, self.config.max_read_only_gas
+            )));
+        }
+
+        // by default, execute at the slot after the latest executed active slot
+        let next_slot = self
+            .active_cursor
+            .get_next_slot(self.config.thread_count)
+            .expect("slot overflow in readonly execution from active slot");
+        let slot = match bundle.slot {
+            Some(slot) if slot < next_slot => {
+                return Err(ExecutionError::InvalidReadOnlyRequest(format!(
+                    "cannot execute at slot {} which is before the next candidate slot {}",
+                    slot, next_slot
+                )))
+            }
+            Some(slot)
+                if slot.period.saturating_sub(next_slot.period)
+                    > self.config.max_read_only_lookahead_periods =>
+            {
+                return Err(ExecutionError::InvalidReadOnlyRequest(format!(
+                    "cannot execute at slot {} which is more than {} periods after the next candidate slot {}",
+                    slot, self.config.max_read_only_lookahead_periods, next_slot
+                )))
+            }
+            Some(slot) => slot,
+            None => next_slot,
+        };
+
+        // a single request without overrides reads the shared active history
+        let chained = bundle.requests.len() > 1;
+        if !chained && bundle.state_overrides.0.is_empty() {
+            return Ok(bundle
+                .requests
+                .into_iter()
+                .map(|req| self.execute_readonly_request(req, slot, self.active_history.clone()))
+                .collect());
+        }
+
+        // otherwise, stack the overrides and the outputs on a copy of the active history
+        let mut history = ActiveHistory(self.active_history.read().0.clone());
+        if !bundle.state_overrides.0.is_empty() {
+            let mut ledger_changes = bundle.state_overrides;
+            for (addr, change) in ledger_changes.0.iter_mut() {
+                // updates of addresses absent from the candidate state create them
+                if let SetUpdateOrDelete::Update(update) = change {
+                    if self.get_final_and_candidate_balance(addr).1.is_none() {
+                        let mut entry = LedgerEntry::default();
+                        entry.apply(update.clone());
+                        *change = SetUpdateOrDelete::Set(entry);
+                    }
+                }
+            }
+            history.0.push_back(ExecutionOutput {
+                slot,
+                block_info: None,
+                state_changes: StateChanges {
+                    ledger_changes,
+                    ..Default::default()
+                },
+                events: Default::default(),
+                staking_history_changes: Default::default(),
+                async_message_events: Vec::new(),
+            });
+        }
+        let history = Arc::new(RwLock::new(history));
+
+        let mut outputs = Vec::with_capacity(bundle.requests.len());
+        for req in bundle.requests {
+            let output = self.execute_readonly_request(req, slot, history.clone());
+            if let (true, Ok(output)) = (chained, &output) {
+                history.write().0.push_back(output.out.clone());
+            }
+            outputs.push(output);
+        }
+        Ok(outputs)
+    }
+
     /// Runs a read-only execution request.
     /// The executed bytecode appears to be able to read and write the consensus state,
     /// but all accumulated changes are simply returned as an `ExecutionOutput` object,
@@ -1370,12 +2017,16 @@ impl ExecutionState {
     ///
     /// # Arguments
     /// * `req`: a read-only execution request
+    /// * `slot`: the slot at which the request is executed
+    /// * `active_history`: the active history the execution reads from
     ///
     /// # Returns
     ///  `ExecutionOutput` describing the output of the execution, or an error
-    pub(crate) fn execute_readonly_request(
+    fn execute_readonly_request(
         &self,
         req: ReadOnlyExecutionRequest,
+        slot: Slot,
+        active_history: Arc<RwLock<ActiveHistory>>,
     ) -> Result<ReadOnlyExecutionOutput, ExecutionError> {
         // TODO ensure that speculative things are reset after every execution ends (incl. on error and readonly)
         // otherwise, on prod stats accumulation etc... from the API we might be counting the remainder of this speculative execution
@@ -1388,19 +2039,13 @@ impl ExecutionState {
             )));
         }
 
-        // set the execution slot to be the one after the latest executed active slot
-        let slot = self
-            .active_cursor
-            .get_next_slot(self.config.thread_count)
-            .expect("slot overflow in readonly execution from active slot");
-
         // create a readonly execution context
         let execution_context = ExecutionContext::readonly(
             self.config.clone(),
             slot,
             req.call_stack,
             self.final_state.clone(),
-            self.active_history.clone(),
+            active_history.clone(),
             self.module_cache.clone(),
             self.mip_store.clone(),
         );
@@ -1474,10 +2119,10 @@ impl ExecutionState {
 
                 // load and execute the compiled module
                 // IMPORTANT: do not keep a lock here as `run_function` uses the `get_module` interface
-                let (module, remaining_gas) = self
-                    .module_cache
-                    .write()
-                    .load_module(&bytecode, req.max_gas)?;
+                let (module, remaining_gas) =
+                    self.module_cache
+                        .write()
+                        .load_module(&bytecode, req.max_gas, true)?;
 
                 let response = massa_sc_runtime::run_function(
                     &*self.execution_interface,
@@ -1507,9 +2152,12 @@ impl ExecutionState {
 
         // return the execution output
         let execution_output = context_guard!(self).settle_slot(None);
+        let previous_values = self
+            .get_readonly_previous_values(&execution_output.state_changes, &active_history.read());
         let exact_cost = req.max_gas.saturating_sub(exec_response.remaining_gas);
         Ok(ReadOnlyExecutionOutput {
             out: execution_output,
+            previous_values,
             // return max_instance_cost if the exact cost is below
             // users can paste the estimated amount into a real call
             // without having to worry about underlying limits
@@ -1522,6 +2170,65 @@ impl ExecutionState {
         })
     }
 
+    /// Gets the values, before a read-only execution, of the state entries changed by it.
+    /// They are read in the final state and in the active history the execution read from.
+    fn get_readonly_previous_values(
+        &self,
+        changes: &StateChanges,
+        active_history: &ActiveHistory,
+    ) -> ReadOnlyPreviousValues {
+        fn resolve<T>(search_result: HistorySearchResult<T>, final_value: Option<T>) -> Option<T> {
+            match search_result {
+                HistorySearchResult::Present(active_value) => Some(active_value),
+                HistorySearchResult::NoInfo => final_value,
+                HistorySearchResult::Absent => None,
+            }
+        }
+
+        let final_state = self.final_state.read();
+        let ledger = final_state.get_ledger();
+        let mut previous_values = ReadOnlyPreviousValues::default();
+        for (addr, change) in changes.ledger_changes.0.iter() {
+            previous_values.balances.insert(
+                *addr,
+                resolve(active_history.fetch_balance(addr), ledger.get_balance(addr)),
+            );
+            let (bytecode_changed, datastore_keys): (bool, Vec<&Vec<u8>>) = match change {
+                SetUpdateOrDelete::Set(entry) => (true, entry.datastore.keys().collect()),
+                SetUpdateOrDelete::Update(update) => (
+                    matches!(update.bytecode, SetOrKeep::Set(_)),
+                    update.datastore.keys().collect(),
+                ),
+                SetUpdateOrDelete::Delete => (false, Vec::new()),
+            };
+            if bytecode_changed {
+                previous_values.bytecodes.insert(
+                    *addr,
+                    resolve(
+                        active_history.fetch_bytecode(addr),
+                        ledger.get_bytecode(addr),
+                    ),
+                );
+            }
+            for key in datastore_keys {
+                previous_values.datastore_entries.insert(
+                    (*addr, key.clone()),
+                    resolve(
+                        active_history.fetch_active_history_data_entry(addr, key),
+                        ledger.get_data_entry(addr, key),
+                    ),
+                );
+            }
+        }
+        for addr in changes.pos_changes.roll_changes.keys() {
+            let rolls = active_history
+                .fetch_roll_count(addr)
+                .unwrap_or_else(|| final_state.get_pos_state().get_rolls_for(addr));
+            previous_values.roll_counts.insert(*addr, rolls);
+        }
+        previous_values
+    }
+
     /// Gets a balance both at the latest final and candidate executed slots
     pub fn get_final_and_candidate_balance(
         &self,
@@ -1670,6 +2377,91 @@ impl ExecutionState {
             .get_all_active_rolls(cycle)
     }
 
+    /// Gets the staking history of an address, per cycle.
+    /// Returns None if the staking history is disabled.
+    pub fn get_staking_history(
+        &self,
+        address: &Address,
+    ) -> Option<BTreeMap<u64, StakingCycleRecord>> {
+        if !self.config.staking_history {
+            return None;
+        }
+        Some(
+            self.final_state
+                .read()
+                .get_pos_state()
+                .get_staking_history(address),
+        )
+    }
+
+    /// Gets aggregated views over the final ledger.
+    /// Returns None if the ledger analytics are disabled.
+    pub fn get_ledger_analytics(
+        &self,
+        rich_list_length: usize,
+        addresses: &[Address],
+    ) -> Option<ExecutionLedgerAnalytics> {
+        if !self.config.ledger_analytics {
+            return None;
+        }
+        let final_state = self.final_state.read();
+        let slot = final_state.get_slot();
+        let ledger = final_state.get_ledger();
+        let pos_state = final_state.get_pos_state();
+
+        let roll_count: u64 = pos_state
+            .get_all_roll_counts(slot.get_cycle(self.config.periods_per_cycle))
+            .values()
+            .sum();
+        let deferred_credits = pos_state
+            .get_deferred_credits_range(..)
+            .credits
+            .values()
+            .flat_map(|credits| credits.values())
+            .fold(Amount::zero(), |total, amount| {
+                total.saturating_add(*amount)
+            });
+
+        let storage_costs = &self.config.storage_costs_constants;
+        let entries = addresses
+            .iter()
+            .map(|address| {
+                ledger.get_entry_analytics(address).map(|analytics| {
+                    let storage_cost = storage_costs
+                        .ledger_entry_datastore_base_cost
+                        .saturating_mul_u64(analytics.datastore_entry_count)
+                        .saturating_add(
+                            storage_costs
+                                .ledger_cost_per_byte
+                                .saturating_mul_u64(analytics.datastore_size),
+                        );
+                    (analytics, storage_cost)
+                })
+            })
+            .collect();
+
+        Some(ExecutionLedgerAnalytics {
+            slot,
+            totals: ledger.get_analytics_totals(),
+            rolls_value: self.config.roll_price.saturating_mul_u64(roll_count),
+            deferred_credits,
+            rich_list: ledger.get_rich_list(rich_list_length),
+            entries,
+        })
+    }
+
+    /// Gets the final values of ledger items with their paths in the node local ledger Merkle tree.
+    /// Returns None if the ledger Merkle tree is disabled, or while it is being updated.
+    pub fn get_ledger_tree_paths(
+        &self,
+        items: &[(Address, LedgerTreeItem)],
+    ) -> Option<LedgerTreePaths> {
+        if !self.config.ledger_merkle_tree {
+            return None;
+        }
+        self.final_state.read().get_ledger().get_tree_paths(items)
+    }
+
     /// Gets execution events optionally filtered by:
     /// * start slot
     /// * end slot
@@ -1706,6 +2498,71 @@ impl ExecutionState {
         }
     }
 
+    /// Gets a page of the asynchronous messages of the final pool, or of the candidate pool if `candidate` is true,
+    /// that match a filter, along with their status at the slot following the queried state.
+    /// Messages are matched on the in-memory message infos, only the messages of the page are fetched.
+    pub fn get_async_messages(
+        &self,
+        filter: &AsyncMessageFilter,
+        candidate: bool,
+    ) -> Vec<ExecutionQueryAsyncMessage> {
+        let matches = |info: &AsyncMessageInfo| {
+            filter.sender.map_or(true, |sender| info.sender == sender)
+                && filter
+                    .destination
+                    .map_or(true, |destination| info.destination == destination)
+        };
+        let (mut messages, cursor) = if candidate {
+            let mut pool =
+                SpeculativeAsyncPool::new(self.final_state.clone(), self.active_history.clone());
+            let page_ids: Vec<AsyncMessageId> = pool
+                .get_message_infos()
+                .iter()
+                .filter(|(_, info)| matches(info))
+                .skip(filter.offset)
+                .take(filter.limit)
+                .map(|(id, _)| *id)
+                .collect();
+            (
+                pool.fetch_messages(page_ids.iter().collect()),
+                self.active_cursor,
+            )
+        } else {
+            let final_state_lock = self.final_state.read();
+            let async_pool = final_state_lock.get_async_pool();
+            let page_ids: Vec<&AsyncMessageId> = async_pool
+                .message_info_cache
+                .iter()
+                .filter(|(_, info)| matches(info))
+                .skip(filter.offset)
+                .take(filter.limit)
+                .map(|(id, _)| id)
+                .collect();
+            let messages = async_pool
+                .fetch_messages(page_ids)
+                .into_iter()
+                .filter_map(|(id, message)| message.map(|message| (*id, message)))
+                .collect();
+            (messages, self.final_cursor)
+        };
+        messages.sort_unstable_by_key(|(id, _)| *id);
+
+        let next_slot = cursor
+            .get_next_slot(self.config.thread_count)
+            .unwrap_or(cursor);
+        let final_state_lock = self.final_state.read();
+        let final_infos = &final_state_lock.get_async_pool().message_info_cache;
+        messages
+            .into_iter()
+            .map(|(id, message)| ExecutionQueryAsyncMessage {
+                id,
+                status: message.status_at(next_slot),
+                is_final: final_infos.contains_key(&id),
+                message,
+            })
+            .collect()
+    }
+
     /// Check if a denunciation has been executed given a `DenunciationIndex`
     /// Returns a tuple of booleans:
     /// * first boolean is true if the denunciation has been executed speculatively
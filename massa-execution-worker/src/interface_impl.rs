@@ -7,7 +7,10 @@
 
 use crate::context::ExecutionContext;
 use anyhow::{anyhow, bail, Result};
-use massa_async_pool::{AsyncMessage, AsyncMessageTrigger};
+use massa_async_pool::{
+    AsyncMessage, AsyncMessageTrigger, AsyncMessageTriggerCondition,
+    AsyncMessageTriggerDeserializer,
+};
 use massa_execution_exports::ExecutionConfig;
 use massa_execution_exports::ExecutionStackElement;
 use massa_models::bytecode::Bytecode;
@@ -24,6 +27,7 @@ use massa_proto_rs::massa::model::v1::{
 };
 use massa_sc_runtime::RuntimeModule;
 use massa_sc_runtime::{Interface, InterfaceClone};
+use massa_serialization::DeserializeError;
 use massa_signature::PublicKey;
 use massa_signature::Signature;
 use massa_time::MassaTime;
@@ -77,6 +81,136 @@ impl InterfaceImpl {
         InterfaceImpl { config, context }
     }
 
+    /// Adds an asynchronous message triggered by an extended condition to the context speculative asynchronous pool.
+    /// Only available once the `AsyncMessageTrigger` MIP component reaches version 1.
+    ///
+    /// # Arguments
+    /// * `target_address`: Destination address hash in format string
+    /// * `target_function`: Name of the message handling function
+    /// * `validity_start`: Tuple containing the period and thread of the validity start slot
+    /// * `validity_end`: Tuple containing the period and thread of the validity end slot
+    /// * `max_gas`: Maximum gas for the message execution
+    /// * `raw_fee`: Fee to pay
+    /// * `raw_coins`: Coins given by the sender
+    /// * `data`: Message data
+    /// * `trigger_address`: Address watched by the condition
+    /// * `condition`: Condition serialized with `AsyncMessageTriggerSerializer::serialize_condition`
+    #[allow(clippy::too_many_arguments)]
+    pub fn send_message_with_condition(
+        &self,
+        target_address: &str,
+        target_function: &str,
+        validity_start: (u64, u8),
+        validity_end: (u64, u8),
+        max_gas: u64,
+        raw_fee: u64,
+        raw_coins: u64,
+        data: &[u8],
+        trigger_address: &str,
+        condition: &[u8],
+    ) -> Result<()> {
+        if context_guard!(self).get_async_message_trigger_version() == 0 {
+            bail!("trigger conditions of asynchronous messages are not active yet")
+        }
+        let (rest, condition) =
+            AsyncMessageTriggerDeserializer::new(MAX_DATASTORE_KEY_LENGTH as u32)
+                .deserialize_condition::<DeserializeError>(condition)
+                .map_err(|err| anyhow!("invalid trigger condition: {}", err))?;
+        if !rest.is_empty() {
+            bail!("invalid trigger condition: trailing bytes")
+        }
+        self.push_async_message(
+            target_address,
+            target_function,
+            validity_start,
+            validity_end,
+            max_gas,
+            raw_fee,
+            raw_coins,
+            data,
+            Some((trigger_address, None, Some(condition))),
+        )
+    }
+
+    /// Checks an asynchronous message emitted by the current address and adds it to the speculative asynchronous pool
+    ///
+    /// # Arguments
+    /// * `trigger`: Optional trigger address, datastore key and extended condition
+    #[allow(clippy::too_many_arguments)]
+    fn push_async_message(
+        &self,
+        target_address: &str,
+        target_function: &str,
+        validity_start: (u64, u8),
+        validity_end: (u64, u8),
+        max_gas: u64,
+        raw_fee: u64,
+        raw_coins: u64,
+        data: &[u8],
+        trigger: Option<(&str, Option<&[u8]>, Option<AsyncMessageTriggerCondition>)>,
+    ) -> Result<()> {
+        if validity_start.1 >= self.config.thread_count {
+            bail!("validity start thread exceeds the configuration thread count")
+        }
+        if validity_end.1 >= self.config.thread_count {
+            bail!("validity end thread exceeds the configuration thread count")
+        }
+        let target_addr = Address::from_str(target_address)?;
+
+        // check that the target address is an SC address
+        if !matches!(target_addr, Address::SC(..)) {
+            bail!("target address is not a smart contract address")
+        }
+
+        // Length verifications
+        if target_function.len() > self.config.max_function_length as usize {
+            bail!("Function name is too large");
+        }
+        if data.len() > self.config.max_parameter_length as usize {
+            bail!("Parameter size is too large");
+        }
+
+        let trigger = trigger
+            .map(|(addr, key, condition)| {
+                if let Some(k) = key {
+                    if k.len() > MAX_DATASTORE_KEY_LENGTH as usize {
+                        bail!("datastore key is too long")
+                    }
+                }
+                Ok::<AsyncMessageTrigger, _>(AsyncMessageTrigger {
+                    address: Address::from_str(addr)?,
+                    datastore_key: key.map(|k| k.to_vec()),
+                    condition,
+                })
+            })
+            .transpose()?;
+        let mut execution_context = context_guard!(self);
+        let emission_slot = execution_context.slot;
+        let emission_index = execution_context.created_message_index;
+        let sender = execution_context.get_current_address()?;
+        let coins = Amount::from_raw(raw_coins);
+        execution_context.transfer_coins(Some(sender), None, coins, true)?;
+        let fee = Amount::from_raw(raw_fee);
+        execution_context.transfer_coins(Some(sender), None, fee, true)?;
+        execution_context.push_new_message(AsyncMessage::new(
+            emission_slot,
+            emission_index,
+            sender,
+            target_addr,
+            target_function.to_string(),
+            max_gas,
+            fee,
+            coins,
+            Slot::new(validity_start.0, validity_start.1),
+            Slot::new(validity_end.0, validity_end.1),
+            data.to_vec(),
+            trigger,
+            None,
+        ));
+        execution_context.created_message_index += 1;
+        Ok(())
+    }
+
     #[cfg(any(
         feature = "gas_calibration",
         feature = "benchmarking",
@@ -94,7 +228,7 @@ impl InterfaceImpl {
         use massa_ledger_exports::{LedgerEntry, SetUpdateOrDelete};
         use massa_models::config::{MIP_STORE_STATS_BLOCK_CONSIDERED, THREAD_COUNT};
         use massa_module_cache::{config::ModuleCacheConfig, controller::ModuleCache};
-        use massa_pos_exports::SelectorConfig;
+        use massa_pos_exports::{SelectorChannels, SelectorConfig};
         use massa_pos_worker::start_selector_worker;
         use massa_versioning::versioning::{MipStatsConfig, MipStore};
         use parking_lot::RwLock;
@@ -106,8 +240,13 @@ impl InterfaceImpl {
             warn_announced_version_ratio: Ratio::new_raw(30, 100),
         };
         let mip_store = MipStore::try_from(([], mip_stats_config)).unwrap();
-        let (_, selector_controller) = start_selector_worker(SelectorConfig::default())
-            .expect("could not start selector controller");
+        let (_, selector_controller) = start_selector_worker(
+            SelectorConfig::default(),
+            SelectorChannels {
+                cycle_draws_sender: tokio::sync::broadcast::channel(10).0,
+            },
+        )
+        .expect("could not start selector controller");
         let disk_ledger = TempDir::new().expect("cannot create temp directory");
         let db_config = MassaDBConfig {
             path: disk_ledger.path().to_path_buf(),
@@ -129,6 +268,8 @@ impl InterfaceImpl {
             hd_cache_size: config.hd_cache_size,
             snip_amount: config.snip_amount,
             max_module_length: config.max_bytecode_size,
+            call_stats_path: config.module_call_stats_path.clone(),
+            warm_up_module_count: config.module_cache_warm_up_count,
         })));
 
         // create an empty default store
@@ -303,10 +444,11 @@ impl Interface for InterfaceImpl {
     /// A `massa-sc-runtime` CL compiled module & the remaining gas after loading the module
     fn get_module(&self, bytecode: &[u8], gas_limit: u64) -> Result<(RuntimeModule, u64)> {
         let context = context_guard!(self);
-        let (module, remaining_gas) = context
-            .module_cache
-            .write()
-            .load_module(bytecode, gas_limit)?;
+        let (module, remaining_gas) =
+            context
+                .module_cache
+                .write()
+                .load_module(bytecode, gas_limit, context.read_only)?;
         Ok((module, remaining_gas))
     }
 
@@ -1168,6 +1310,7 @@ impl Interface for InterfaceImpl {
     /// * `fee`: Fee to pay
     /// * `raw_coins`: Coins given by the sender
     /// * `data`: Message data
+    /// * `filter`: Optional trigger address and datastore key
     fn send_message(
         &self,
         target_address: &str,
@@ -1180,65 +1323,17 @@ impl Interface for InterfaceImpl {
         data: &[u8],
         filter: Option<(&str, Option<&[u8]>)>,
     ) -> Result<()> {
-        if validity_start.1 >= self.config.thread_count {
-            bail!("validity start thread exceeds the configuration thread count")
-        }
-        if validity_end.1 >= self.config.thread_count {
-            bail!("validity end thread exceeds the configuration thread count")
-        }
-        let target_addr = Address::from_str(target_address)?;
-
-        // check that the target address is an SC address
-        if !matches!(target_addr, Address::SC(..)) {
-            bail!("target address is not a smart contract address")
-        }
-
-        // Length verifications
-        if target_function.len() > self.config.max_function_length as usize {
-            bail!("Function name is too large");
-        }
-        if data.len() > self.config.max_parameter_length as usize {
-            bail!("Parameter size is too large");
-        }
-
-        let mut execution_context = context_guard!(self);
-        let emission_slot = execution_context.slot;
-        let emission_index = execution_context.created_message_index;
-        let sender = execution_context.get_current_address()?;
-        let coins = Amount::from_raw(raw_coins);
-        execution_context.transfer_coins(Some(sender), None, coins, true)?;
-        let fee = Amount::from_raw(raw_fee);
-        execution_context.transfer_coins(Some(sender), None, fee, true)?;
-        execution_context.push_new_message(AsyncMessage::new(
-            emission_slot,
-            emission_index,
-            sender,
-            target_addr,
-            target_function.to_string(),
+        self.push_async_message(
+            target_address,
+            target_function,
+            validity_start,
+            validity_end,
             max_gas,
-            fee,
-            coins,
-            Slot::new(validity_start.0, validity_start.1),
-            Slot::new(validity_end.0, validity_end.1),
-            data.to_vec(),
-            filter
-                .map(|(addr, key)| {
-                    let datastore_key = key.map(|k| k.to_vec());
-                    if let Some(ref k) = datastore_key {
-                        if k.len() > MAX_DATASTORE_KEY_LENGTH as usize {
-                            bail!("datastore key is too long")
-                        }
-                    }
-                    Ok::<AsyncMessageTrigger, _>(AsyncMessageTrigger {
-                        address: Address::from_str(addr)?,
-                        datastore_key,
-                    })
-                })
-                .transpose()?,
-            None,
-        ));
-        execution_context.created_message_index += 1;
-        Ok(())
+            raw_fee,
+            raw_coins,
+            data,
+            filter.map(|(addr, key)| (addr, key, None)),
+        )
     }
 
     // Returns the operation id that originated the current execution if there is one
@@ -2005,6 +2100,190 @@ mod tests {
             );
         }
     }
+
+    // The filter key of send_message is always a datastore key, conditions have their own entry point
+    // which is refused before the activation of the trigger component.
+    #[test]
+    fn test_send_message_with_condition_before_activation() {
+        use massa_async_pool::{AsyncMessageTriggerCondition, AsyncMessageTriggerSerializer};
+        use massa_ledger_exports::SetUpdateOrDelete;
+
+        let sender_addr = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let interface = InterfaceImpl::new_default(sender_addr, None);
+
+        let mut condition = Vec::new();
+        AsyncMessageTriggerSerializer::new()
+            .serialize_condition(
+                &AsyncMessageTriggerCondition::Periodic { interval: 4 },
+                &mut condition,
+            )
+            .unwrap();
+        assert!(interface
+            .send_message_with_condition(
+                "AS12mzL2UWroPV7zzHpwHnnF74op9Gtw7H55fAmXMnCuVZTFSjZCA",
+                "receive",
+                (1, 0),
+                (10, 0),
+                100_000,
+                0,
+                0,
+                b"",
+                &sender_addr.to_string(),
+                &condition,
+            )
+            .is_err());
+        assert!(interface
+            .context
+            .lock()
+            .get_snapshot()
+            .async_pool_changes
+            .0
+            .is_empty());
+
+        interface
+            .send_message(
+                "AS12mzL2UWroPV7zzHpwHnnF74op9Gtw7H55fAmXMnCuVZTFSjZCA",
+                "receive",
+                (1, 0),
+                (10, 0),
+                100_000,
+                0,
+                0,
+                b"",
+                Some((&sender_addr.to_string(), Some(&condition))),
+            )
+            .unwrap();
+
+        let changes = interface.context.lock().get_snapshot().async_pool_changes;
+        assert_eq!(changes.0.len(), 1);
+        let Some(SetUpdateOrDelete::Set(message)) = changes.0.values().next() else {
+            panic!("expected an emitted message");
+        };
+        let trigger = message.trigger.as_ref().unwrap();
+        assert_eq!(trigger.datastore_key, Some(condition));
+        assert_eq!(trigger.condition, None);
+    }
+
+    // A periodic message stays in the pool once executed and waits for its next occurrence.
+    #[test]
+    fn test_periodic_message_recurs() {
+        use massa_async_pool::{AsyncMessageTrigger, AsyncMessageTriggerCondition};
+        use massa_ledger_exports::SetUpdateOrDelete;
+
+        let sender_addr = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let interface = InterfaceImpl::new_default(sender_addr, None);
+        let mut context = interface.context.lock();
+        let slot = context.slot;
+
+        let mut message = AsyncMessage::new(
+            slot,
+            0,
+            sender_addr,
+            Address::from_str("AS12mzL2UWroPV7zzHpwHnnF74op9Gtw7H55fAmXMnCuVZTFSjZCA").unwrap(),
+            "receive".to_string(),
+            100_000,
+            Amount::zero(),
+            Amount::from_str("1").unwrap(),
+            slot,
+            Slot::new(slot.period + 10, 0),
+            Vec::new(),
+            Some(AsyncMessageTrigger {
+                address: sender_addr,
+                datastore_key: None,
+                condition: Some(AsyncMessageTriggerCondition::Periodic { interval: 4 }),
+            }),
+            None,
+        );
+        message.can_be_executed = true;
+        let message_id = message.compute_id();
+        context.push_new_message(message);
+
+        let batch = context.take_async_batch(1_000_000, 0);
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].1.coins, Amount::from_str("1").unwrap());
+
+        // the message is not executable until the next occurrence, and its coins were already sent
+        assert!(context.take_async_batch(1_000_000, 0).is_empty());
+        let changes = context.get_snapshot().async_pool_changes;
+        let Some(SetUpdateOrDelete::Set(rearmed)) = changes.0.get(&message_id) else {
+            panic!("expected the periodic message to stay in the pool");
+        };
+        assert!(!rearmed.can_be_executed);
+        assert_eq!(rearmed.coins, Amount::zero());
+    }
+
+    // Every occurrence of a periodic message charges its fee to the sender,
+    // and the message is dropped once the sender can no longer pay it.
+    #[test]
+    fn test_periodic_message_fee() {
+        use massa_async_pool::{AsyncMessageTrigger, AsyncMessageTriggerCondition};
+        use massa_ledger_exports::{LedgerChanges, LedgerEntry, SetUpdateOrDelete};
+
+        let sender_addr = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let interface = InterfaceImpl::new_default(sender_addr, None);
+        let mut context = interface.context.lock();
+        let fee = Amount::from_str("0.1").unwrap();
+        let initial_balance = context.get_balance(&sender_addr).unwrap();
+
+        let mut message = AsyncMessage::new(
+            Slot::new(0, 0),
+            0,
+            sender_addr,
+            Address::from_str("AS12mzL2UWroPV7zzHpwHnnF74op9Gtw7H55fAmXMnCuVZTFSjZCA").unwrap(),
+            "receive".to_string(),
+            100_000,
+            fee,
+            Amount::zero(),
+            Slot::new(0, 0),
+            Slot::new(20, 0),
+            Vec::new(),
+            Some(AsyncMessageTrigger {
+                address: sender_addr,
+                datastore_key: None,
+                condition: Some(AsyncMessageTriggerCondition::Periodic { interval: 4 }),
+            }),
+            None,
+        );
+        message.can_be_executed = true;
+        let message_id = message.compute_id();
+        context.push_new_message(message);
+
+        let mut expected_balance = initial_balance;
+        for period in [0, 4, 8] {
+            let slot = Slot::new(period, 0);
+            context.slot = slot;
+            context
+                .speculative_async_pool
+                .settle_slot(&slot, &LedgerChanges::default(), 1);
+            assert_eq!(context.take_async_batch(1_000_000, 0).len(), 1);
+            expected_balance = expected_balance.saturating_sub(fee);
+            assert_eq!(context.get_balance(&sender_addr), Some(expected_balance));
+        }
+
+        // the sender cannot pay the fee of the next occurrence anymore
+        context.speculative_ledger.added_changes.0.insert(
+            sender_addr,
+            SetUpdateOrDelete::Set(LedgerEntry {
+                balance: Amount::from_str("0.05").unwrap(),
+                ..Default::default()
+            }),
+        );
+        let slot = Slot::new(12, 0);
+        context.slot = slot;
+        context
+            .speculative_async_pool
+            .settle_slot(&slot, &LedgerChanges::default(), 1);
+        assert_eq!(context.take_async_batch(1_000_000, 0).len(), 1);
+        assert_eq!(
+            context.get_balance(&sender_addr),
+            Some(Amount::from_str("0.05").unwrap())
+        );
+        let changes = context.get_snapshot().async_pool_changes;
+        assert!(matches!(
+            changes.0.get(&message_id),
+            Some(SetUpdateOrDelete::Delete)
+        ));
+    }
 }
 
 #[test]
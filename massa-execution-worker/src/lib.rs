@@ -69,12 +69,19 @@
 //! ## `speculative_executed_ops.rs`
 //! A speculative (non-final) list of previously executed operations to prevent reuse.
 //!
+//! ## `parallel_execution.rs`
+//! Records the state entries read and written by an execution,
+//! used to execute the transactions and roll operations of a block optimistically in parallel.
+//!
 //! ## `request_queue.rs`
 //! This module contains the implementation of a generic finite-size execution request queue.
 //! It handles requests that come with an MPSC to send back the result of their execution once it's done.
 //!
 //! ## `stats.rs`
 //! Defines a structure that gathers execution statistics.
+//!
+//! ## `profiling.rs`
+//! Defines an opt-in profiler of the wall time and gas spent by the executions.
 
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
@@ -84,6 +91,8 @@ mod context;
 mod controller;
 mod execution;
 mod interface_impl;
+mod parallel_execution;
+mod profiling;
 mod request_queue;
 mod slot_sequencer;
 mod speculative_async_pool;
@@ -0,0 +1,83 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Tools used to execute the operations of a block optimistically in parallel.
+//!
+//! Each candidate operation is executed in its own isolated `ExecutionContext`,
+//! built on top of the state reached before the execution of the block operations.
+//! The speculative states of the context record the state entries read and written by the operation.
+//! The results are then merged in block order:
+//! an operation that accessed no entry written by a previously merged operation
+//! behaves exactly as it would have sequentially, so its writes can be merged as is.
+//! Other operations are executed again, sequentially, on top of the merged state.
+
+use massa_models::{address::Address, operation::OperationId};
+use parking_lot::Mutex;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Entry of the speculative state that can be read or written by an operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum StateKey {
+    /// ledger entry (balance, bytecode and datastore) of an address
+    LedgerEntry(Address),
+    /// roll count, deferred credits and staking activity of an address
+    RollEntry(Address),
+    /// asynchronous message pool
+    AsyncPool,
+    /// executed status of an operation
+    ExecutedOp(OperationId),
+}
+
+/// Sets of the state entries accessed during an execution
+#[derive(Debug, Default)]
+pub(crate) struct AccessSets {
+    /// entries read
+    pub reads: HashSet<StateKey>,
+    /// entries written
+    pub writes: HashSet<StateKey>,
+}
+
+impl AccessSets {
+    /// Returns true if any accessed entry was written by the executions that produced `writes`
+    pub fn conflicts_with(&self, writes: &HashSet<StateKey>) -> bool {
+        self.reads
+            .iter()
+            .chain(self.writes.iter())
+            .any(|key| writes.contains(key))
+    }
+}
+
+/// Records the state entries accessed by an execution.
+/// A default recorder is disabled and records nothing.
+/// Clones of an enabled recorder share the same access sets.
+#[derive(Clone, Default)]
+pub(crate) struct AccessRecorder(Option<Arc<Mutex<AccessSets>>>);
+
+impl AccessRecorder {
+    /// Creates an enabled recorder
+    pub fn enabled() -> Self {
+        AccessRecorder(Some(Default::default()))
+    }
+
+    /// Records the read of a state entry
+    pub fn read(&self, key: StateKey) {
+        if let Some(sets) = &self.0 {
+            sets.lock().reads.insert(key);
+        }
+    }
+
+    /// Records the write of a state entry
+    pub fn write(&self, key: StateKey) {
+        if let Some(sets) = &self.0 {
+            sets.lock().writes.insert(key);
+        }
+    }
+
+    /// Returns the accesses recorded so far and resets them
+    pub fn take(&self) -> AccessSets {
+        match &self.0 {
+            Some(sets) => std::mem::take(&mut *sets.lock()),
+            None => Default::default(),
+        }
+    }
+}
@@ -0,0 +1,236 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Opt-in profiling of the wall time and gas spent by the executions.
+//!
+//! The profile of each executed slot is kept over a time window.
+//! A candidate slot can be executed several times before becoming final:
+//! each execution replaces the previous profile of the slot, and the executions
+//! of a slot are only observed in the Prometheus histograms once it is final.
+
+use massa_execution_exports::{ContractCallProfile, ExecutionProfile, ExecutionProfileEntry};
+use massa_metrics::MassaMetrics;
+use massa_models::address::Address;
+use massa_models::operation::OperationType;
+use massa_models::slot::Slot;
+use massa_time::{MassaTime, SharedClock};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+/// Returns the name of the type of an operation, used to label its profile
+pub fn operation_type_name(operation: &OperationType) -> &'static str {
+    match operation {
+        OperationType::Transaction { .. } => "transaction",
+        OperationType::RollBuy { .. } => "roll_buy",
+        OperationType::RollSell { .. } => "roll_sell",
+        OperationType::ExecuteSC { .. } => "execute_sc",
+        OperationType::CallSC { .. } => "call_sc",
+        OperationType::CancelAsyncMessage { .. } => "cancel_async_message",
+    }
+}
+
+/// Builds the profile entry of a single execution
+fn single_entry(elapsed: Duration, gas: u64) -> ExecutionProfileEntry {
+    ExecutionProfileEntry {
+        count: 1,
+        time_micros: elapsed.as_micros() as u64,
+        gas,
+    }
+}
+
+/// Profile of the executions of a slot
+#[derive(Default)]
+struct SlotProfile {
+    /// executed operations, per operation type
+    operations: BTreeMap<&'static str, ExecutionProfileEntry>,
+    /// single operation executions, observed in the histograms once the slot is final
+    operation_executions: Vec<(&'static str, Duration, u64)>,
+    /// executed asynchronous messages
+    async_messages: ExecutionProfileEntry,
+    /// calls to smart contract functions, per (address, function)
+    contract_calls: HashMap<(Address, String), ExecutionProfileEntry>,
+    /// whole slot execution
+    slot: ExecutionProfileEntry,
+}
+
+/// Execution profiler
+pub struct ExecutionProfiler {
+    /// duration of the time window
+    time_window_duration: MassaTime,
+    /// clock dating the records
+    clock: SharedClock,
+    /// profiles of the slots executed in the time window (instant, profile),
+    /// a re-execution of a slot replacing its previous profile
+    slots: BTreeMap<Slot, (MassaTime, SlotProfile)>,
+    /// profile of the slot being executed
+    current_slot: SlotProfile,
+    /// prometheus metrics
+    massa_metrics: MassaMetrics,
+}
+
+impl ExecutionProfiler {
+    /// create a new `ExecutionProfiler`
+    pub fn new(
+        time_window_duration: MassaTime,
+        clock: SharedClock,
+        massa_metrics: MassaMetrics,
+    ) -> Self {
+        ExecutionProfiler {
+            time_window_duration,
+            clock,
+            slots: Default::default(),
+            current_slot: Default::default(),
+            massa_metrics,
+        }
+    }
+
+    /// delete the profiles of the slots executed before the time window
+    fn refresh(&mut self, current_time: MassaTime) {
+        let start_time = current_time.saturating_sub(self.time_window_duration);
+        self.slots.retain(|_, (t, _)| *t >= start_time);
+    }
+
+    /// start profiling the execution of a new slot, discarding any unfinished one
+    pub fn start_slot(&mut self) {
+        self.current_slot = Default::default();
+    }
+
+    /// register the execution of an operation
+    ///
+    /// # Arguments
+    /// * `op_type`: name of the type of the operation
+    /// * `contract_call`: called smart contract address and function, if any
+    /// * `elapsed`: wall time of the execution
+    /// * `gas`: gas spent by the execution
+    pub fn register_operation(
+        &mut self,
+        op_type: &'static str,
+        contract_call: Option<(Address, &str)>,
+        elapsed: Duration,
+        gas: u64,
+    ) {
+        let entry = single_entry(elapsed, gas);
+        self.current_slot
+            .operations
+            .entry(op_type)
+            .or_default()
+            .add(&entry);
+        self.current_slot
+            .operation_executions
+            .push((op_type, elapsed, gas));
+        if let Some((address, function)) = contract_call {
+            self.current_slot
+                .contract_calls
+                .entry((address, function.to_string()))
+                .or_default()
+                .add(&entry);
+        }
+    }
+
+    /// register the execution of an asynchronous message of the slot batch
+    ///
+    /// # Arguments
+    /// * `destination`: address of the called smart contract
+    /// * `function`: called function
+    /// * `elapsed`: wall time of the execution
+    /// * `gas`: gas spent by the execution
+    pub fn register_async_message(
+        &mut self,
+        destination: Address,
+        function: &str,
+        elapsed: Duration,
+        gas: u64,
+    ) {
+        let entry = single_entry(elapsed, gas);
+        self.current_slot.async_messages.add(&entry);
+        self.current_slot
+            .contract_calls
+            .entry((destination, function.to_string()))
+            .or_default()
+            .add(&entry);
+    }
+
+    /// finish profiling the execution of the current slot,
+    /// replacing the profile of any previous execution of the slot
+    ///
+    /// # Arguments
+    /// * `slot`: executed slot
+    /// * `elapsed`: wall time of the whole slot execution
+    pub fn end_slot(&mut self, slot: Slot, elapsed: Duration) {
+        let mut slot_profile = std::mem::take(&mut self.current_slot);
+        let gas = slot_profile
+            .operations
+            .values()
+            .fold(slot_profile.async_messages.gas, |gas, entry| {
+                gas.saturating_add(entry.gas)
+            });
+        slot_profile.slot = single_entry(elapsed, gas);
+
+        let current_time = self.clock.now();
+        self.slots.insert(slot, (current_time, slot_profile));
+        self.refresh(current_time);
+    }
+
+    /// observe the executions of a slot that became final in the Prometheus histograms
+    pub fn finalize_slot(&self, slot: &Slot) {
+        let Some((_, slot_profile)) = self.slots.get(slot) else {
+            return;
+        };
+        for (op_type, elapsed, gas) in &slot_profile.operation_executions {
+            self.massa_metrics
+                .observe_execution_operation(op_type, elapsed.as_secs_f64(), *gas);
+        }
+        let async_messages = slot_profile.async_messages;
+        if async_messages.count > 0 {
+            self.massa_metrics.observe_execution_async_messages(
+                Duration::from_micros(async_messages.time_micros).as_secs_f64(),
+                async_messages.gas,
+            );
+        }
+        self.massa_metrics.observe_execution_slot(
+            Duration::from_micros(slot_profile.slot.time_micros).as_secs_f64(),
+            slot_profile.slot.gas,
+        );
+    }
+
+    /// get the profile of the slots executed in the time window,
+    /// with the `top_n` smart contract functions that spent the most gas
+    pub fn get_profile(&self, top_n: usize) -> ExecutionProfile {
+        let start_time = self.clock.now().saturating_sub(self.time_window_duration);
+        let mut profile = ExecutionProfile {
+            window_millis: self.time_window_duration.as_millis(),
+            ..Default::default()
+        };
+        let mut contract_calls: HashMap<&(Address, String), ExecutionProfileEntry> = HashMap::new();
+        for (_, slot_profile) in self.slots.values().filter(|(t, _)| t >= &start_time) {
+            profile.slots.add(&slot_profile.slot);
+            profile.async_messages.add(&slot_profile.async_messages);
+            for (op_type, entry) in &slot_profile.operations {
+                profile
+                    .operations
+                    .entry(op_type.to_string())
+                    .or_default()
+                    .add(entry);
+            }
+            for (call, entry) in &slot_profile.contract_calls {
+                contract_calls.entry(call).or_default().add(entry);
+            }
+        }
+        let mut top_contracts: Vec<ContractCallProfile> = contract_calls
+            .into_iter()
+            .map(|((address, function), calls)| ContractCallProfile {
+                address: *address,
+                function: function.clone(),
+                calls,
+            })
+            .collect();
+        top_contracts.sort_unstable_by(|a, b| {
+            b.calls
+                .gas
+                .cmp(&a.calls.gas)
+                .then_with(|| b.calls.time_micros.cmp(&a.calls.time_micros))
+        });
+        top_contracts.truncate(top_n);
+        profile.top_contracts = top_contracts;
+        profile
+    }
+}
@@ -4,13 +4,14 @@
 //! the pool at an arbitrary execution slot.
 
 use crate::active_history::{ActiveHistory, HistorySearchResult::Present};
+use crate::parallel_execution::{AccessRecorder, StateKey};
 use massa_async_pool::{
-    AsyncMessage, AsyncMessageId, AsyncMessageInfo, AsyncMessageTrigger, AsyncMessageUpdate,
-    AsyncPoolChanges,
+    AsyncMessage, AsyncMessageId, AsyncMessageInfo, AsyncMessageTrigger,
+    AsyncMessageTriggerCondition, AsyncMessageUpdate, AsyncPoolChanges,
 };
 use massa_final_state::FinalStateController;
 use massa_ledger_exports::{Applicable, LedgerChanges, SetUpdateOrDelete};
-use massa_models::slot::Slot;
+use massa_models::{address::Address, amount::Amount, slot::Slot};
 use parking_lot::RwLock;
 use std::{
     collections::{BTreeMap, HashMap},
@@ -24,6 +25,8 @@ pub(crate) struct SpeculativeAsyncPool {
     pool_changes: AsyncPoolChanges,
     // Used to know which messages we want to take (contains active and final messages)
     message_infos: BTreeMap<AsyncMessageId, AsyncMessageInfo>,
+    // recorder of the accesses to the pool
+    accesses: AccessRecorder,
 }
 
 impl SpeculativeAsyncPool {
@@ -65,9 +68,15 @@ impl SpeculativeAsyncPool {
             active_history,
             pool_changes: Default::default(),
             message_infos,
+            accesses: Default::default(),
         }
     }
 
+    /// Sets the recorder of the accesses to the pool
+    pub fn set_access_recorder(&mut self, accesses: AccessRecorder) {
+        self.accesses = accesses;
+    }
+
     /// Returns the changes caused to the `SpeculativeAsyncPool` since its creation,
     /// and resets their local value to nothing.
     /// This must be called after `settle_emitted_messages()`
@@ -90,8 +99,22 @@ impl SpeculativeAsyncPool {
         self.message_infos = snapshot.1;
     }
 
+    /// Returns the infos of the messages of the pool, indexed by message id
+    pub fn get_message_infos(&self) -> &BTreeMap<AsyncMessageId, AsyncMessageInfo> {
+        &self.message_infos
+    }
+
+    /// Fetches messages of the pool without removing them
+    pub fn fetch_messages(
+        &mut self,
+        wanted_ids: Vec<&AsyncMessageId>,
+    ) -> Vec<(AsyncMessageId, AsyncMessage)> {
+        self.fetch_msgs(wanted_ids, false)
+    }
+
     /// Add a new message to the list of changes of this `SpeculativeAsyncPool`
     pub fn push_new_message(&mut self, msg: AsyncMessage) {
+        self.accesses.write(StateKey::AsyncPool);
         self.pool_changes.push_add(msg.compute_id(), msg.clone());
         self.message_infos.insert(msg.compute_id(), msg.into());
     }
@@ -111,6 +134,7 @@ impl SpeculativeAsyncPool {
         max_gas: u64,
         async_msg_cst_gas_cost: u64,
     ) -> Vec<(AsyncMessageId, AsyncMessage)> {
+        self.accesses.write(StateKey::AsyncPool);
         let mut available_gas = max_gas;
 
         // Choose which messages to take based on self.message_infos
@@ -135,19 +159,55 @@ impl SpeculativeAsyncPool {
 
         let taken = self.fetch_msgs(wanted_messages, true);
 
-        for (message_id, _) in taken.iter() {
+        for (message_id, _message) in taken.iter() {
             self.message_infos.remove(message_id);
         }
 
         taken
     }
 
+    /// Puts a periodic message taken for execution back in the pool, where it waits for its next occurrence.
+    /// Its coins are only credited to the destination on the first execution.
+    pub fn rearm_periodic_message(&mut self, message_id: AsyncMessageId, message: &AsyncMessage) {
+        self.accesses.write(StateKey::AsyncPool);
+        let mut rearmed = message.clone();
+        rearmed.coins = Amount::zero();
+        rearmed.can_be_executed = false;
+        self.pool_changes.push_add(message_id, rearmed.clone());
+        self.message_infos.insert(message_id, rearmed.into());
+    }
+
+    /// Removes a message sent by `sender` from the pool, identified by its emission slot and index
+    ///
+    /// # Returns
+    /// the removed message, or None if the pool holds no such message sent by `sender`
+    pub fn take_sent_message(
+        &mut self,
+        sender: &Address,
+        emission_slot: Slot,
+        emission_index: u64,
+    ) -> Option<AsyncMessage> {
+        self.accesses.write(StateKey::AsyncPool);
+        let message_id = self
+            .message_infos
+            .iter()
+            .find(|(id, info)| {
+                id.1 == emission_slot && id.2 == emission_index && info.sender == *sender
+            })
+            .map(|(id, _)| *id)?;
+        self.message_infos.remove(&message_id);
+        self.fetch_msgs(vec![&message_id], true)
+            .pop()
+            .map(|(_, message)| message)
+    }
+
     /// Settle a slot.
     /// Consume newly emitted messages into `self.async_pool`, recording changes into `self.settled_changes`.
     ///
     /// # Arguments
     /// * slot: slot that is being settled
     /// * ledger_changes: ledger changes for that slot, used to see if we can activate some messages
+    /// * trigger_version: active version of the `AsyncMessageTrigger` MIP component at that slot
     ///
     /// # Returns
     /// the list of deleted `(message_id, message)`, used for reimbursement
@@ -155,7 +215,10 @@ impl SpeculativeAsyncPool {
         &mut self,
         slot: &Slot,
         ledger_changes: &LedgerChanges,
+        trigger_version: u32,
     ) -> Vec<(AsyncMessageId, AsyncMessage)> {
+        self.accesses.write(StateKey::AsyncPool);
+
         // Update the messages_info: remove messages that should be removed
         // Filter out all messages for which the validity end is expired.
         // Note that the validity_end bound is NOT included in the validity interval of the message.
@@ -205,7 +268,7 @@ impl SpeculativeAsyncPool {
         let mut triggered_info = Vec::new();
         for (id, message_info) in self.message_infos.iter_mut() {
             if let Some(filter) = &message_info.trigger {
-                if is_triggered(filter, ledger_changes) {
+                if is_triggered(filter, slot, ledger_changes, trigger_version) {
                     message_info.can_be_executed = true;
                     triggered_info.push((*id, message_info.clone()));
                 }
@@ -308,6 +371,246 @@ impl SpeculativeAsyncPool {
 }
 
 /// Check in the ledger changes if a message trigger has been triggered
-fn is_triggered(filter: &AsyncMessageTrigger, ledger_changes: &LedgerChanges) -> bool {
-    ledger_changes.has_changes(&filter.address, filter.datastore_key.clone())
+fn is_triggered(
+    filter: &AsyncMessageTrigger,
+    slot: &Slot,
+    ledger_changes: &LedgerChanges,
+    trigger_version: u32,
+) -> bool {
+    let Some(condition) = &filter.condition else {
+        return ledger_changes.has_changes(&filter.address, filter.datastore_key.clone());
+    };
+    // extended conditions are only evaluated from version 1 of the trigger component,
+    // before that the message waits for its validity end
+    if trigger_version == 0 {
+        return false;
+    }
+    match condition {
+        AsyncMessageTriggerCondition::DatastoreKeyPrefix(prefix) => {
+            ledger_changes.has_prefix_changes(&filter.address, prefix)
+        }
+        // balances are only checked when they are set by the ledger changes of the slot,
+        // so that evaluating a trigger never requires reading the ledger
+        AsyncMessageTriggerCondition::BalanceBelow(amount) => ledger_changes
+            .get_balance_or_else(&filter.address, || None)
+            .map_or(false, |balance| balance < *amount),
+        AsyncMessageTriggerCondition::BalanceAbove(amount) => ledger_changes
+            .get_balance_or_else(&filter.address, || None)
+            .map_or(false, |balance| balance > *amount),
+        // a period is reached at its first slot, a zero interval never triggers
+        AsyncMessageTriggerCondition::Periodic { interval } => {
+            slot.thread == 0 && slot.period.checked_rem(*interval) == Some(0)
+        }
+    }
+}
+
+/// Check if a message executed at `slot` is periodic and can still occur before its validity end
+pub(crate) fn has_next_occurrence(message: &AsyncMessage, slot: &Slot) -> bool {
+    matches!(
+        message.trigger,
+        Some(AsyncMessageTrigger {
+            condition: Some(AsyncMessageTriggerCondition::Periodic { .. }),
+            ..
+        })
+    ) && Slot::new(slot.period.saturating_add(1), 0) < message.validity_end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_ledger_exports::{LedgerEntry, LedgerEntryUpdate, SetOrDelete, SetOrKeep};
+    use std::str::FromStr;
+
+    fn trigger(condition: AsyncMessageTriggerCondition) -> AsyncMessageTrigger {
+        AsyncMessageTrigger {
+            address: Address::from_str("AU12htxRWiEm8jDJpJptr6cwEhWNcCSFWstN1MLSa96DDkVM9Y42G")
+                .unwrap(),
+            datastore_key: None,
+            condition: Some(condition),
+        }
+    }
+
+    fn datastore_update(address: Address, key: &[u8], delete: bool) -> LedgerChanges {
+        let value = if delete {
+            SetOrDelete::Delete
+        } else {
+            SetOrDelete::Set(vec![1])
+        };
+        let mut changes = LedgerChanges::default();
+        changes.0.insert(
+            address,
+            SetUpdateOrDelete::Update(LedgerEntryUpdate {
+                datastore: [(key.to_vec(), value)].into_iter().collect(),
+                ..Default::default()
+            }),
+        );
+        changes
+    }
+
+    fn balance_update(address: Address, balance: &str) -> LedgerChanges {
+        let mut changes = LedgerChanges::default();
+        changes.0.insert(
+            address,
+            SetUpdateOrDelete::Update(LedgerEntryUpdate {
+                balance: SetOrKeep::Set(Amount::from_str(balance).unwrap()),
+                ..Default::default()
+            }),
+        );
+        changes
+    }
+
+    #[test]
+    fn test_has_prefix_changes() {
+        let address =
+            Address::from_str("AU12htxRWiEm8jDJpJptr6cwEhWNcCSFWstN1MLSa96DDkVM9Y42G").unwrap();
+
+        assert!(
+            datastore_update(address, b"price_mas", false).has_prefix_changes(&address, b"price_")
+        );
+        assert!(
+            datastore_update(address, b"price_mas", true).has_prefix_changes(&address, b"price_")
+        );
+        assert!(
+            !datastore_update(address, b"volume", false).has_prefix_changes(&address, b"price_")
+        );
+        assert!(!LedgerChanges::default().has_prefix_changes(&address, b"price_"));
+
+        let mut changes = LedgerChanges::default();
+        changes.0.insert(
+            address,
+            SetUpdateOrDelete::Set(LedgerEntry {
+                datastore: [(b"price_mas".to_vec(), vec![1])].into_iter().collect(),
+                ..Default::default()
+            }),
+        );
+        assert!(changes.has_prefix_changes(&address, b"price_"));
+        assert!(!changes.has_prefix_changes(&address, b"volume"));
+
+        let mut changes = LedgerChanges::default();
+        changes.0.insert(address, SetUpdateOrDelete::Delete);
+        assert!(changes.has_prefix_changes(&address, b"price_"));
+    }
+
+    #[test]
+    fn test_is_triggered() {
+        let slot = Slot::new(8, 0);
+        let prefix = trigger(AsyncMessageTriggerCondition::DatastoreKeyPrefix(
+            b"price_".to_vec(),
+        ));
+        let address = prefix.address;
+        assert!(is_triggered(
+            &prefix,
+            &slot,
+            &datastore_update(address, b"price_mas", false),
+            1
+        ));
+        assert!(!is_triggered(
+            &prefix,
+            &slot,
+            &datastore_update(address, b"volume", false),
+            1
+        ));
+
+        let below = trigger(AsyncMessageTriggerCondition::BalanceBelow(
+            Amount::from_str("10").unwrap(),
+        ));
+        let above = trigger(AsyncMessageTriggerCondition::BalanceAbove(
+            Amount::from_str("10").unwrap(),
+        ));
+        assert!(is_triggered(
+            &below,
+            &slot,
+            &balance_update(address, "5"),
+            1
+        ));
+        assert!(!is_triggered(
+            &above,
+            &slot,
+            &balance_update(address, "5"),
+            1
+        ));
+        assert!(!is_triggered(
+            &below,
+            &slot,
+            &balance_update(address, "10"),
+            1
+        ));
+        assert!(!is_triggered(
+            &above,
+            &slot,
+            &balance_update(address, "10"),
+            1
+        ));
+        assert!(is_triggered(
+            &above,
+            &slot,
+            &balance_update(address, "15"),
+            1
+        ));
+        // the balance is not set by the changes
+        assert!(!is_triggered(
+            &below,
+            &slot,
+            &datastore_update(address, b"price_mas", false),
+            1
+        ));
+
+        let periodic = trigger(AsyncMessageTriggerCondition::Periodic { interval: 4 });
+        let no_changes = LedgerChanges::default();
+        assert!(is_triggered(&periodic, &Slot::new(8, 0), &no_changes, 1));
+        assert!(!is_triggered(&periodic, &Slot::new(8, 1), &no_changes, 1));
+        assert!(!is_triggered(&periodic, &Slot::new(9, 0), &no_changes, 1));
+        let zero = trigger(AsyncMessageTriggerCondition::Periodic { interval: 0 });
+        assert!(!is_triggered(&zero, &Slot::new(8, 0), &no_changes, 1));
+
+        // triggers without condition only look at the datastore key
+        let legacy = AsyncMessageTrigger {
+            address,
+            datastore_key: Some(b"price_mas".to_vec()),
+            condition: None,
+        };
+        assert!(is_triggered(
+            &legacy,
+            &slot,
+            &datastore_update(address, b"price_mas", false),
+            0
+        ));
+        assert!(!is_triggered(
+            &legacy,
+            &slot,
+            &datastore_update(address, b"price_eth", false),
+            0
+        ));
+    }
+
+    #[test]
+    fn test_is_triggered_version_gate() {
+        let slot = Slot::new(8, 0);
+        let prefix = trigger(AsyncMessageTriggerCondition::DatastoreKeyPrefix(
+            b"price_".to_vec(),
+        ));
+        let address = prefix.address;
+        let changes = datastore_update(address, b"price_mas", false);
+        assert!(!is_triggered(&prefix, &slot, &changes, 0));
+        assert!(is_triggered(&prefix, &slot, &changes, 1));
+
+        let periodic = trigger(AsyncMessageTriggerCondition::Periodic { interval: 4 });
+        assert!(!is_triggered(
+            &periodic,
+            &slot,
+            &LedgerChanges::default(),
+            0
+        ));
+        assert!(is_triggered(&periodic, &slot, &LedgerChanges::default(), 1));
+
+        let below = trigger(AsyncMessageTriggerCondition::BalanceBelow(
+            Amount::from_str("10").unwrap(),
+        ));
+        assert!(!is_triggered(
+            &below,
+            &slot,
+            &balance_update(address, "5"),
+            0
+        ));
+    }
 }
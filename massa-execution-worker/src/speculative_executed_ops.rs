@@ -3,6 +3,7 @@
 //! Speculative list of previously executed operations, to prevent reuse.
 
 use crate::active_history::{ActiveHistory, HistorySearchResult};
+use crate::parallel_execution::{AccessRecorder, StateKey};
 use massa_executed_ops::ExecutedOpsChanges;
 use massa_final_state::FinalStateController;
 use massa_models::{operation::OperationId, slot::Slot};
@@ -20,6 +21,9 @@ pub(crate) struct SpeculativeExecutedOps {
 
     /// executed operations: maps the operation ID to its validity slot end - included
     executed_ops: ExecutedOpsChanges,
+
+    /// recorder of the operations accessed
+    accesses: AccessRecorder,
 }
 
 impl SpeculativeExecutedOps {
@@ -36,9 +40,15 @@ impl SpeculativeExecutedOps {
             final_state,
             active_history,
             executed_ops: Default::default(),
+            accesses: Default::default(),
         }
     }
 
+    /// Sets the recorder of the operations accessed
+    pub fn set_access_recorder(&mut self, accesses: AccessRecorder) {
+        self.accesses = accesses;
+    }
+
     /// Returns the set of operation IDs caused to the `SpeculativeExecutedOps` since its creation,
     /// and resets their local value to nothing
     pub fn take(&mut self) -> ExecutedOpsChanges {
@@ -55,8 +65,15 @@ impl SpeculativeExecutedOps {
         self.executed_ops = snapshot;
     }
 
+    /// Gets the execution status and validity end of an operation executed since the creation of the `SpeculativeExecutedOps`
+    pub fn get_added_op(&self, op_id: &OperationId) -> Option<(bool, Slot)> {
+        self.executed_ops.get(op_id).copied()
+    }
+
     /// Checks if an operation was executed previously
     pub fn is_op_executed(&self, op_id: &OperationId) -> bool {
+        self.accesses.read(StateKey::ExecutedOp(*op_id));
+
         // check in the curent changes
         if self.executed_ops.contains_key(op_id) {
             return true;
@@ -90,6 +107,7 @@ impl SpeculativeExecutedOps {
         op_exec_status: bool,
         op_valid_until_slot: Slot,
     ) {
+        self.accesses.write(StateKey::ExecutedOp(op_id));
         self.executed_ops
             .insert(op_id, (op_exec_status, op_valid_until_slot));
     }
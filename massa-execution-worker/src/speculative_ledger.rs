@@ -6,10 +6,13 @@
 //! but keeps track of the changes that were applied to it since its creation.
 
 use crate::active_history::{ActiveHistory, HistorySearchResult};
+use crate::parallel_execution::{AccessRecorder, StateKey};
 use massa_execution_exports::ExecutionError;
 use massa_execution_exports::StorageCostsConstants;
 use massa_final_state::FinalStateController;
-use massa_ledger_exports::{Applicable, LedgerChanges, SetOrDelete, SetUpdateOrDelete};
+use massa_ledger_exports::{
+    Applicable, LedgerChanges, LedgerEntry, LedgerEntryUpdate, SetOrDelete, SetUpdateOrDelete,
+};
 use massa_models::bytecode::Bytecode;
 use massa_models::datastore::get_prefix_bounds;
 use massa_models::{address::Address, amount::Amount};
@@ -60,6 +63,9 @@ pub(crate) struct SpeculativeLedger {
 
     /// storage cost constants
     storage_costs_constants: StorageCostsConstants,
+
+    /// recorder of the ledger entries accessed
+    accesses: AccessRecorder,
 }
 
 impl SpeculativeLedger {
@@ -84,9 +90,15 @@ impl SpeculativeLedger {
             max_datastore_value_size,
             max_bytecode_size,
             storage_costs_constants,
+            accesses: Default::default(),
         }
     }
 
+    /// Sets the recorder of the ledger entries accessed
+    pub fn set_access_recorder(&mut self, accesses: AccessRecorder) {
+        self.accesses = accesses;
+    }
+
     /// Returns the changes caused to the `SpeculativeLedger` since its creation,
     /// and resets their local value to nothing.
     pub fn take(&mut self) -> LedgerChanges {
@@ -103,6 +115,24 @@ impl SpeculativeLedger {
         self.added_changes = snapshot;
     }
 
+    /// Gets the change caused to the ledger entry of an address since the creation of the `SpeculativeLedger`
+    pub fn get_entry_change(
+        &self,
+        addr: &Address,
+    ) -> Option<SetUpdateOrDelete<LedgerEntry, LedgerEntryUpdate>> {
+        self.added_changes.0.get(addr).cloned()
+    }
+
+    /// Overwrites the change caused to the ledger entry of an address (see `get_entry_change` method)
+    pub fn set_entry_change(
+        &mut self,
+        addr: Address,
+        change: SetUpdateOrDelete<LedgerEntry, LedgerEntryUpdate>,
+    ) {
+        self.accesses.write(StateKey::LedgerEntry(addr));
+        self.added_changes.0.insert(addr, change);
+    }
+
     /// Gets the effective balance of an address
     ///
     /// # Arguments:
@@ -111,6 +141,7 @@ impl SpeculativeLedger {
     /// # Returns
     /// Some(Amount) if the address was found, otherwise None
     pub fn get_balance(&self, addr: &Address) -> Option<Amount> {
+        self.accesses.read(StateKey::LedgerEntry(*addr));
         // try to read from added changes > history > final_state
         self.added_changes.get_balance_or_else(addr, || {
             match self.active_history.read().fetch_balance(addr) {
@@ -131,6 +162,7 @@ impl SpeculativeLedger {
     /// # Returns
     /// `Some(Bytecode)` if the address was found, otherwise None
     pub fn get_bytecode(&self, addr: &Address) -> Option<Bytecode> {
+        self.accesses.read(StateKey::LedgerEntry(*addr));
         // try to read from added changes > history > final_state
         self.added_changes.get_bytecode_or_else(addr, || {
             match self.active_history.read().fetch_bytecode(addr) {
@@ -207,6 +239,9 @@ impl SpeculativeLedger {
         }
 
         // apply the simulated changes to the speculative ledger
+        for addr in from_addr.iter().chain(to_addr.iter()) {
+            self.accesses.write(StateKey::LedgerEntry(*addr));
+        }
         self.added_changes.apply(changes);
 
         Ok(())
@@ -220,6 +255,7 @@ impl SpeculativeLedger {
     /// # Returns
     /// true if the address was found, otherwise false
     pub fn entry_exists(&self, addr: &Address) -> bool {
+        self.accesses.read(StateKey::LedgerEntry(*addr));
         // try to read from added changes > history > final_state
         self.added_changes.entry_exists_or_else(addr, || {
             match self.active_history.read().fetch_balance(addr) {
@@ -291,6 +327,7 @@ impl SpeculativeLedger {
             })?;
 
         self.transfer_coins(Some(creator_address), None, address_storage_cost)?;
+        self.accesses.write(StateKey::LedgerEntry(addr));
         self.added_changes.create_address(&addr);
         self.added_changes.set_bytecode(addr, bytecode);
         Ok(())
@@ -354,6 +391,7 @@ impl SpeculativeLedger {
             self.transfer_coins(Some(*caller_addr), None, bytecode_storage_cost)?;
         }
         // set the bytecode of that address
+        self.accesses.write(StateKey::LedgerEntry(*addr));
         self.added_changes.set_bytecode(*addr, bytecode);
 
         Ok(())
@@ -368,6 +406,8 @@ impl SpeculativeLedger {
     /// # Returns
     /// `Some(Vec<Vec<u8>>)` for found keys, `None` if the address does not exist.
     pub fn get_keys(&self, addr: &Address, prefix: &[u8]) -> Option<BTreeSet<Vec<u8>>> {
+        self.accesses.read(StateKey::LedgerEntry(*addr));
+
         // compute prefix range
         let prefix_range = get_prefix_bounds(prefix);
         let range_ref = (prefix_range.0.as_ref(), prefix_range.1.as_ref());
@@ -434,6 +474,7 @@ impl SpeculativeLedger {
     /// # Returns
     /// `Some(Vec<u8>)` if the value was found, `None` if the address does not exist or if the key is not in its datastore.
     pub fn get_data_entry(&self, addr: &Address, key: &[u8]) -> Option<Vec<u8>> {
+        self.accesses.read(StateKey::LedgerEntry(*addr));
         // try to read from added changes > history > final_state
         self.added_changes.get_data_entry_or_else(addr, key, || {
             match self
@@ -461,6 +502,7 @@ impl SpeculativeLedger {
     /// # Returns
     /// true if the key exists in the address datastore, false otherwise
     pub fn has_data_entry(&self, addr: &Address, key: &[u8]) -> bool {
+        self.accesses.read(StateKey::LedgerEntry(*addr));
         // try to read from added changes > history > final_state
         self.added_changes.has_data_entry_or_else(addr, key, || {
             match self
@@ -631,6 +673,7 @@ impl SpeculativeLedger {
         }
 
         // set data
+        self.accesses.write(StateKey::LedgerEntry(*addr));
         self.added_changes.set_data_entry(*addr, key, value);
 
         Ok(())
@@ -661,6 +704,7 @@ impl SpeculativeLedger {
         }
 
         // delete entry
+        self.accesses.write(StateKey::LedgerEntry(*addr));
         self.added_changes.delete_data_entry(*addr, key.to_owned());
 
         Ok(())
@@ -1,6 +1,7 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use crate::active_history::ActiveHistory;
+use crate::parallel_execution::{AccessRecorder, StateKey};
 use massa_execution_exports::ExecutionError;
 use massa_final_state::FinalStateController;
 use massa_models::address::ExecutionAddressCycleInfo;
@@ -26,6 +27,9 @@ pub(crate) struct SpeculativeRollState {
 
     /// List of changes to the state after settling roll sell/buy
     pub(crate) added_changes: PoSChanges,
+
+    /// recorder of the roll entries accessed
+    accesses: AccessRecorder,
 }
 
 impl SpeculativeRollState {
@@ -41,9 +45,15 @@ impl SpeculativeRollState {
             final_state,
             active_history,
             added_changes: PoSChanges::default(),
+            accesses: Default::default(),
         }
     }
 
+    /// Sets the recorder of the roll entries accessed
+    pub fn set_access_recorder(&mut self, accesses: AccessRecorder) {
+        self.accesses = accesses;
+    }
+
     /// Returns the changes caused to the `SpeculativeRollState` since its creation,
     /// and resets their local value to nothing.
     pub fn take(&mut self) -> PoSChanges {
@@ -60,8 +70,39 @@ impl SpeculativeRollState {
         self.added_changes = snapshot;
     }
 
+    /// Gets the roll count and the deferred credits of an address changed since the creation of the `SpeculativeRollState`
+    pub fn get_entry_changes(&self, addr: &Address) -> (Option<u64>, Vec<(Slot, Amount)>) {
+        let credits = self
+            .added_changes
+            .deferred_credits
+            .credits
+            .iter()
+            .filter_map(|(slot, credits)| credits.get(addr).map(|amount| (*slot, *amount)))
+            .collect();
+        (self.added_changes.roll_changes.get(addr).copied(), credits)
+    }
+
+    /// Overwrites the roll count and the deferred credits of an address changed (see `get_entry_changes` method)
+    pub fn set_entry_changes(
+        &mut self,
+        addr: Address,
+        roll_count: Option<u64>,
+        credits: Vec<(Slot, Amount)>,
+    ) {
+        self.accesses.write(StateKey::RollEntry(addr));
+        if let Some(roll_count) = roll_count {
+            self.added_changes.roll_changes.insert(addr, roll_count);
+        }
+        for (slot, amount) in credits {
+            self.added_changes
+                .deferred_credits
+                .insert(slot, addr, amount);
+        }
+    }
+
     /// Internal function to retrieve the rolls of a given address
     fn get_rolls(&self, addr: &Address) -> u64 {
+        self.accesses.read(StateKey::RollEntry(*addr));
         self.added_changes
             .roll_changes
             .get(addr)
@@ -81,6 +122,7 @@ impl SpeculativeRollState {
     /// * `buyer_addr`: address that will receive the rolls
     /// * `roll_count`: number of rolls it will receive
     pub fn add_rolls(&mut self, buyer_addr: &Address, roll_count: u64) {
+        self.accesses.write(StateKey::RollEntry(*buyer_addr));
         let count = self
             .added_changes
             .roll_changes
@@ -142,6 +184,7 @@ impl SpeculativeRollState {
             .saturating_add(roll_price.saturating_mul_u64(roll_count));
 
         // Remove the rolls
+        self.accesses.write(StateKey::RollEntry(*seller_addr));
         self.added_changes
             .roll_changes
             .insert(*seller_addr, owned_count.saturating_sub(roll_count));
@@ -168,6 +211,7 @@ impl SpeculativeRollState {
         // fetch the roll count
         let owned_count = self.get_rolls(addr);
         let roll_to_slash = min(roll_count, owned_count);
+        self.accesses.write(StateKey::RollEntry(*addr));
 
         match self.added_changes.roll_changes.get_mut(addr) {
             None => {
@@ -198,6 +242,7 @@ impl SpeculativeRollState {
         amount: &Amount,
     ) -> Amount {
         let credits = self.get_address_deferred_credits(addr, *slot);
+        self.accesses.write(StateKey::RollEntry(*addr));
 
         let mut remaining_to_slash = *amount;
         for (credit_slot, credit_amount) in credits.iter() {
@@ -247,6 +292,9 @@ impl SpeculativeRollState {
     ///
     /// # Arguments:
     /// `slot`: the final slot of the cycle to compute
+    ///
+    /// # Returns
+    /// The number of rolls implicitly sold by each address because of its misses
     pub fn settle_production_stats(
         &mut self,
         slot: &Slot,
@@ -254,7 +302,7 @@ impl SpeculativeRollState {
         thread_count: u8,
         roll_price: Amount,
         max_miss_ratio: Ratio<u64>,
-    ) {
+    ) -> PreHashMap<Address, u64> {
         let cycle = slot.get_cycle(periods_per_cycle);
 
         let (production_stats, full) =
@@ -275,12 +323,14 @@ impl SpeculativeRollState {
         .expect("unexpected slot overflow in settle_production_stats");
 
         let mut target_credits = PreHashMap::default();
+        let mut sold_rolls = PreHashMap::default();
         for (addr, stats) in production_stats {
             if !stats.is_satisfying(&max_miss_ratio) {
                 let owned_count = self.get_rolls(&addr);
                 if owned_count != 0 {
                     if let Some(amount) = roll_price.checked_mul_u64(owned_count) {
                         target_credits.insert(addr, amount);
+                        sold_rolls.insert(addr, owned_count);
                         self.added_changes.roll_changes.insert(addr, 0);
                     }
                 }
@@ -291,6 +341,7 @@ impl SpeculativeRollState {
             credits.credits.insert(target_slot, target_credits);
             self.added_changes.deferred_credits.extend(credits);
         }
+        sold_rolls
     }
 
     /// Get deferred credits of an address starting from a given slot
@@ -299,6 +350,7 @@ impl SpeculativeRollState {
         address: &Address,
         min_slot: Slot,
     ) -> BTreeMap<Slot, Amount> {
+        self.accesses.read(StateKey::RollEntry(*address));
         let mut res: HashMap<Slot, Amount> = HashMap::default();
 
         // get added values
@@ -350,6 +402,8 @@ impl SpeculativeRollState {
 
     /// Gets the deferred credits for a given address that will be credited at a given slot
     fn get_address_deferred_credit_for_slot(&self, addr: &Address, slot: &Slot) -> Option<Amount> {
+        self.accesses.read(StateKey::RollEntry(*addr));
+
         // search in the added changes
         if let Some(v) = self
             .added_changes
@@ -388,6 +442,7 @@ impl SpeculativeRollState {
         periods_per_cycle: u64,
         cur_slot: Slot,
     ) -> Vec<ExecutionAddressCycleInfo> {
+        self.accesses.read(StateKey::RollEntry(*address));
         let mut res: Vec<ExecutionAddressCycleInfo> = Vec::new();
 
         // lock final state
@@ -2,13 +2,15 @@
 
 use massa_models::slot::Slot;
 use massa_models::stats::ExecutionStats;
-use massa_time::MassaTime;
+use massa_time::{MassaTime, SharedClock};
 use std::collections::VecDeque;
 
 /// Execution statistics counter
 pub struct ExecutionStatsCounter {
     /// duration of the time window
     time_window_duration: MassaTime,
+    /// clock dating the records
+    clock: SharedClock,
     /// final blocks in the time window (count, instant)
     final_blocks: VecDeque<(usize, MassaTime)>,
     /// final operations executed in the time window (count, instant)
@@ -19,9 +21,10 @@ pub struct ExecutionStatsCounter {
 
 impl ExecutionStatsCounter {
     /// create a new `ExecutionStatsCounter`
-    pub fn new(time_window_duration: MassaTime) -> Self {
+    pub fn new(time_window_duration: MassaTime, clock: SharedClock) -> Self {
         ExecutionStatsCounter {
             time_window_duration,
+            clock,
             final_blocks: Default::default(),
             final_executed_ops: Default::default(),
             final_executed_denunciations: Default::default(),
@@ -53,21 +56,21 @@ impl ExecutionStatsCounter {
 
     /// register final blocks
     pub fn register_final_blocks(&mut self, count: usize) {
-        let current_time = MassaTime::now();
+        let current_time = self.clock.now();
         self.final_blocks.push_back((count, current_time));
         self.refresh(current_time);
     }
 
     /// register final executed operations
     pub fn register_final_executed_operations(&mut self, count: usize) {
-        let current_time = MassaTime::now();
+        let current_time = self.clock.now();
         self.final_executed_ops.push_back((count, current_time));
         self.refresh(current_time);
     }
 
     /// register final executed denunciations
     pub fn register_final_executed_denunciations(&mut self, count: usize) {
-        let current_time = MassaTime::now();
+        let current_time = self.clock.now();
         self.final_executed_denunciations
             .push_back((count, current_time));
         self.refresh(current_time);
@@ -75,7 +78,7 @@ impl ExecutionStatsCounter {
 
     /// get statistics
     pub fn get_stats(&self, active_cursor: Slot, final_cursor: Slot) -> ExecutionStats {
-        let current_time = MassaTime::now();
+        let current_time = self.clock.now();
         let start_time = current_time.saturating_sub(self.time_window_duration);
         let map_func = |pair: &(usize, MassaTime)| -> usize {
             let (cnt, t) = pair;
@@ -10,3 +10,6 @@ mod universe;
 mod tests_active_history;
 
 mod interface;
+
+#[cfg(test)]
+mod tests_stats;
@@ -5,13 +5,13 @@ use massa_db_exports::{DBBatch, ShareableMassaDBController};
 use massa_executed_ops::{ExecutedDenunciations, ExecutedDenunciationsConfig};
 use massa_execution_exports::{
     ExecutionConfig, ExecutionQueryRequest, ExecutionQueryRequestItem, ExecutionStackElement,
-    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+    ReadOnlyExecutionBundle, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
 };
 use massa_final_state::test_exports::get_initials;
-use massa_final_state::MockFinalStateController;
+use massa_final_state::{MockFinalStateController, StateChanges};
 use massa_hash::Hash;
 use massa_ledger_exports::{
-    LedgerEntryUpdate, MockLedgerControllerWrapper, SetOrKeep, SetUpdateOrDelete,
+    LedgerChanges, LedgerEntryUpdate, MockLedgerControllerWrapper, SetOrKeep, SetUpdateOrDelete,
 };
 use massa_models::bytecode::Bytecode;
 use massa_models::config::{ENDORSEMENT_COUNT, LEDGER_ENTRY_DATASTORE_BASE_SIZE, THREAD_COUNT};
@@ -20,7 +20,7 @@ use massa_models::{address::Address, amount::Amount, slot::Slot};
 use massa_models::{
     denunciation::Denunciation,
     execution::EventFilter,
-    operation::{Operation, OperationSerializer, OperationType},
+    operation::{Operation, OperationSerializer, OperationType, SecureShareOperation},
     secure_share::SecureShareContent,
 };
 use massa_pos_exports::{MockSelectorControllerWrapper, PoSConfig, PoSFinalState, Selection};
@@ -135,8 +135,12 @@ fn expect_finalize_deploy_and_call_blocks(
         .write()
         .expect_finalize()
         .times(1)
-        .with(predicate::eq(deploy_sc_slot), predicate::always())
-        .returning(move |_, changes| {
+        .with(
+            predicate::eq(deploy_sc_slot),
+            predicate::always(),
+            predicate::always(),
+        )
+        .returning(move |_, changes, _| {
             let mut saved_bytecode = saved_bytecode_edit.write();
             if !changes.ledger_changes.get_bytecode_updates().is_empty() {
                 *saved_bytecode = Some(changes.ledger_changes.get_bytecode_updates()[0].clone());
@@ -148,8 +152,12 @@ fn expect_finalize_deploy_and_call_blocks(
             .write()
             .expect_finalize()
             .times(1)
-            .with(predicate::eq(call_sc_slot), predicate::always())
-            .returning(move |_, _| {
+            .with(
+                predicate::eq(call_sc_slot),
+                predicate::always(),
+                predicate::always(),
+            )
+            .returning(move |_, _, _| {
                 finalized_waitpoint_trigger_handle_2.trigger();
             });
     }
@@ -328,6 +336,115 @@ fn test_readonly_execution() {
     );
 }
 
+#[test]
+fn test_readonly_execution_bundle() {
+    let exec_cfg = ExecutionConfig::default();
+    let mut foreign_controllers = ExecutionForeignControllers::new_with_mocks();
+    selector_boilerplate(&mut foreign_controllers.selector_controller);
+
+    foreign_controllers
+        .ledger_controller
+        .set_expectations(|ledger_controller| {
+            ledger_controller
+                .expect_get_balance()
+                .returning(move |_| Some(Amount::from_str("100").unwrap()));
+            ledger_controller
+                .expect_entry_exists()
+                .returning(move |_| true);
+        });
+    final_state_boilerplate(
+        &mut foreign_controllers.final_state,
+        foreign_controllers.db.clone(),
+        &foreign_controllers.selector_controller,
+        &mut foreign_controllers.ledger_controller,
+        None,
+        None,
+        None,
+    );
+    let universe = ExecutionTestUniverse::new(foreign_controllers, exec_cfg.clone());
+
+    let addr = Address::from_str("AU1LQrXPJ3DVL8SFRqACk31E9MVxBcmCATFiRdpEmgztGxWAx48D").unwrap();
+    let request = ReadOnlyExecutionRequest {
+        max_gas: 100_000_000,
+        call_stack: vec![ExecutionStackElement {
+            address: addr,
+            coins: Amount::zero(),
+            owned_addresses: vec![],
+            operation_datastore: None,
+        }],
+        target: ReadOnlyExecutionTarget::BytecodeExecution(
+            include_bytes!("./wasm/event_test.wasm").to_vec(),
+        ),
+        coins: None,
+        fee: Some(Amount::from_str("40").unwrap()),
+    };
+
+    // override the balance of the caller, and chain two executions paying a fee
+    let mut state_overrides = LedgerChanges::default();
+    state_overrides.0.insert(
+        addr,
+        SetUpdateOrDelete::Update(LedgerEntryUpdate {
+            balance: SetOrKeep::Set(Amount::from_str("1000").unwrap()),
+            ..Default::default()
+        }),
+    );
+    let outputs = universe
+        .module_controller
+        .execute_readonly_bundle(ReadOnlyExecutionBundle {
+            requests: vec![request.clone(), request],
+            state_overrides,
+            slot: Some(Slot::new(10, 0)),
+        })
+        .expect("readonly bundle execution failed");
+
+    assert_eq!(outputs.len(), 2);
+    for (output, (expected_balance, previous_balance)) in
+        outputs.into_iter().zip([("960", "1000"), ("920", "960")])
+    {
+        let output = output.expect("readonly execution failed");
+        assert_eq!(output.out.slot, Slot::new(10, 0));
+        // the previous balance includes the overrides and the previous executions
+        assert_eq!(
+            output.previous_values.balances.get(&addr),
+            Some(&Some(Amount::from_str(previous_balance).unwrap()))
+        );
+        assert_eq!(
+            output
+                .out
+                .state_changes
+                .ledger_changes
+                .0
+                .get(&addr)
+                .unwrap(),
+            &SetUpdateOrDelete::Update(LedgerEntryUpdate {
+                balance: SetOrKeep::Set(Amount::from_str(expected_balance).unwrap()),
+                bytecode: SetOrKeep::Keep,
+                datastore: BTreeMap::new()
+            })
+        );
+    }
+
+    // executions cannot be simulated in the past
+    assert!(universe
+        .module_controller
+        .execute_readonly_bundle(ReadOnlyExecutionBundle {
+            requests: Vec::new(),
+            state_overrides: Default::default(),
+            slot: Some(Slot::new(0, 0)),
+        })
+        .is_err());
+
+    // nor too far in the future
+    assert!(universe
+        .module_controller
+        .execute_readonly_bundle(ReadOnlyExecutionBundle {
+            requests: Vec::new(),
+            state_overrides: Default::default(),
+            slot: Some(Slot::new(exec_cfg.max_read_only_lookahead_periods + 10, 0)),
+        })
+        .is_err());
+}
+
 /// Test the gas usage in nested calls using call SC operation
 ///
 /// Create a smart contract and send it in the blockclique.
@@ -615,8 +732,12 @@ fn send_and_receive_async_message() {
         .write()
         .expect_finalize()
         .times(1)
-        .with(predicate::eq(Slot::new(1, 0)), predicate::always())
-        .returning(move |_, changes| {
+        .with(
+            predicate::eq(Slot::new(1, 0)),
+            predicate::always(),
+            predicate::always(),
+        )
+        .returning(move |_, changes, _| {
             {
                 let mut saved_bytecode = saved_bytecode_edit.write();
                 *saved_bytecode = Some(changes.ledger_changes.get_bytecode_updates()[0].clone());
@@ -639,8 +760,12 @@ fn send_and_receive_async_message() {
         .write()
         .expect_finalize()
         .times(1)
-        .with(predicate::eq(Slot::new(1, 1)), predicate::always())
-        .returning(move |_, changes| {
+        .with(
+            predicate::eq(Slot::new(1, 1)),
+            predicate::always(),
+            predicate::always(),
+        )
+        .returning(move |_, changes, _| {
             match changes
                 .ledger_changes
                 .0
@@ -780,8 +905,12 @@ fn cancel_async_message() {
         .write()
         .expect_finalize()
         .times(1)
-        .with(predicate::eq(Slot::new(1, 0)), predicate::always())
-        .returning(move |_, changes| {
+        .with(
+            predicate::eq(Slot::new(1, 0)),
+            predicate::always(),
+            predicate::always(),
+        )
+        .returning(move |_, changes, _| {
             {
                 let mut saved_bytecode = saved_bytecode_edit.write();
                 *saved_bytecode = Some(changes.ledger_changes.get_bytecode_updates()[0].clone());
@@ -806,8 +935,12 @@ fn cancel_async_message() {
         .write()
         .expect_finalize()
         .times(1)
-        .with(predicate::eq(Slot::new(1, 1)), predicate::always())
-        .returning(move |_, changes| {
+        .with(
+            predicate::eq(Slot::new(1, 1)),
+            predicate::always(),
+            predicate::always(),
+        )
+        .returning(move |_, changes, _| {
             match changes.ledger_changes.0.get(&sender_addr).unwrap() {
                 // at slot (1,1) msg was canceled so sender has received the coins (0.0000001)
                 // sender has received the coins (0.0000001)
@@ -1210,8 +1343,12 @@ fn send_and_receive_transaction() {
         .write()
         .expect_finalize()
         .times(1)
-        .with(predicate::eq(Slot::new(1, 0)), predicate::always())
-        .returning(move |_, changes| {
+        .with(
+            predicate::eq(Slot::new(1, 0)),
+            predicate::always(),
+            predicate::always(),
+        )
+        .returning(move |_, changes, _| {
             // 190 because 100 in the get_balance in the `final_state_boilerplate` and 90 from the transfer.
             assert_eq!(
                 changes
@@ -1288,8 +1425,12 @@ fn roll_buy() {
         .write()
         .expect_finalize()
         .times(1)
-        .with(predicate::eq(Slot::new(1, 0)), predicate::always())
-        .returning(move |_, changes| {
+        .with(
+            predicate::eq(Slot::new(1, 0)),
+            predicate::always(),
+            predicate::always(),
+        )
+        .returning(move |_, changes, _| {
             assert_eq!(changes.pos_changes.roll_changes.len(), 1);
             // 100 base + 1 bought
             assert_eq!(changes.pos_changes.roll_changes.get(&address), Some(&101));
@@ -1387,8 +1528,12 @@ fn roll_sell() {
         .write()
         .expect_finalize()
         .times(1)
-        .with(predicate::eq(Slot::new(3, 0)), predicate::always())
-        .returning(move |_, changes| {
+        .with(
+            predicate::eq(Slot::new(3, 0)),
+            predicate::always(),
+            predicate::always(),
+        )
+        .returning(move |_, changes, _| {
             let amount = changes
                 .ledger_changes
                 .get_balance_or_else(&address, || None)
@@ -1500,7 +1645,7 @@ fn roll_slash() {
         .final_state
         .write()
         .expect_finalize()
-        .returning(move |_, changes| {
+        .returning(move |_, changes, _| {
             let rolls = changes.pos_changes.roll_changes.get(&address).unwrap();
             // 97 sold and 3 slashed
             assert_eq!(rolls, &0);
@@ -1617,7 +1762,7 @@ fn roll_slash_2() {
         .final_state
         .write()
         .expect_finalize()
-        .returning(move |_, changes| {
+        .returning(move |_, changes, _| {
             let rolls = changes.pos_changes.roll_changes.get(&address).unwrap();
             // 100 sold
             assert_eq!(rolls, &0);
@@ -1917,8 +2062,12 @@ fn datastore_manipulations() {
         .write()
         .expect_finalize()
         .times(1)
-        .with(predicate::eq(Slot::new(1, 0)), predicate::always())
-        .returning(move |_, changes| {
+        .with(
+            predicate::eq(Slot::new(1, 0)),
+            predicate::always(),
+            predicate::always(),
+        )
+        .returning(move |_, changes, _| {
             let key_len = (key_a.len() + key_b.len()) as u64;
             let value_len = ([21, 0, 49].len() + [5, 12, 241].len()) as u64;
             let amount = changes
@@ -2303,8 +2452,12 @@ fn test_rewards() {
         .write()
         .expect_finalize()
         .times(1)
-        .with(predicate::eq(Slot::new(1, 0)), predicate::always())
-        .returning(move |_, changes| {
+        .with(
+            predicate::eq(Slot::new(1, 0)),
+            predicate::always(),
+            predicate::always(),
+        )
+        .returning(move |_, changes, _| {
             let block_credit_part = exec_cfg
                 .block_reward
                 .checked_div_u64(3 * (1 + (ENDORSEMENT_COUNT as u64)))
@@ -2338,8 +2491,12 @@ fn test_rewards() {
         .write()
         .expect_finalize()
         .times(1)
-        .with(predicate::eq(Slot::new(1, 1)), predicate::always())
-        .returning(move |_, changes| {
+        .with(
+            predicate::eq(Slot::new(1, 1)),
+            predicate::always(),
+            predicate::always(),
+        )
+        .returning(move |_, changes, _| {
             let block_credit_part_parent_in_thread = exec_cfg
                 .block_reward
                 .checked_div_u64(3 * (1 + (ENDORSEMENT_COUNT as u64)))
@@ -2429,3 +2586,230 @@ fn test_rewards() {
     universe.send_and_finalize(&keypair, block);
     finalized_waitpoint.wait();
 }
+
+/// Execute a block containing `operations` at slot (1, 0) and return the finalized state changes
+fn execute_block_operations(
+    exec_cfg: ExecutionConfig,
+    operations: Vec<SecureShareOperation>,
+) -> StateChanges {
+    let mut foreign_controllers = ExecutionForeignControllers::new_with_mocks();
+    let finalized_waitpoint = WaitPoint::new();
+    let finalized_waitpoint_trigger_handle = finalized_waitpoint.get_trigger_handle();
+    let finalized_changes = Arc::new(RwLock::new(None));
+    let finalized_changes_edit = finalized_changes.clone();
+    selector_boilerplate(&mut foreign_controllers.selector_controller);
+    final_state_boilerplate(
+        &mut foreign_controllers.final_state,
+        foreign_controllers.db.clone(),
+        &foreign_controllers.selector_controller,
+        &mut foreign_controllers.ledger_controller,
+        None,
+        None,
+        None,
+    );
+    foreign_controllers
+        .final_state
+        .write()
+        .expect_finalize()
+        .times(1)
+        .with(
+            predicate::eq(Slot::new(1, 0)),
+            predicate::always(),
+            predicate::always(),
+        )
+        .returning(move |_, changes, _| {
+            *finalized_changes_edit.write() = Some(changes);
+            finalized_waitpoint_trigger_handle.trigger();
+        });
+    let mut universe = ExecutionTestUniverse::new(foreign_controllers, exec_cfg);
+    universe.storage.store_operations(operations.clone());
+    let block = ExecutionTestUniverse::create_block(
+        &KeyPair::from_str(TEST_SK_1).unwrap(),
+        Slot::new(1, 0),
+        operations,
+        vec![],
+        vec![],
+    );
+    universe.send_and_finalize(&KeyPair::from_str(TEST_SK_1).unwrap(), block);
+    finalized_waitpoint.wait();
+    let changes = finalized_changes.write().take();
+    changes.unwrap()
+}
+
+#[test]
+fn parallel_operation_execution() {
+    // senders of the block thread, all having 100 coins
+    let senders: Vec<KeyPair> = std::iter::once(KeyPair::from_str(TEST_SK_1).unwrap())
+        .chain(std::iter::repeat_with(|| KeyPair::generate(0).unwrap()))
+        .filter(|keypair| {
+            Address::from_public_key(&keypair.get_public_key()).get_thread(THREAD_COUNT) == 0
+        })
+        .take(5)
+        .collect();
+    let recipients: Vec<Address> = (0..3)
+        .map(|_| Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key()))
+        .collect();
+    let operation = |sender: &KeyPair, op: OperationType| {
+        Operation::new_verifiable(
+            Operation {
+                fee: Amount::from_str("1").unwrap(),
+                expire_period: 10,
+                op,
+            },
+            OperationSerializer::new(),
+            sender,
+        )
+        .unwrap()
+    };
+    let transfer = |sender: &KeyPair, recipient: Address, amount: &str| {
+        operation(
+            sender,
+            OperationType::Transaction {
+                recipient_address: recipient,
+                amount: Amount::from_str(amount).unwrap(),
+            },
+        )
+    };
+    let operations = vec![
+        // independent operations
+        transfer(&senders[1], recipients[0], "10"),
+        transfer(&senders[2], recipients[1], "20"),
+        operation(&senders[0], OperationType::RollSell { roll_count: 1 }),
+        // same sender as a previous operation
+        transfer(&senders[1], recipients[2], "50"),
+        // same recipient as a previous operation
+        transfer(&senders[3], recipients[0], "5"),
+        // not enough coins
+        transfer(&senders[2], recipients[2], "500"),
+        // only possible with the coins received from the previous operation
+        transfer(
+            &senders[4],
+            Address::from_public_key(&senders[0].get_public_key()),
+            "30",
+        ),
+        operation(&senders[0], OperationType::RollBuy { roll_count: 1 }),
+    ];
+
+    let sequential_changes =
+        execute_block_operations(ExecutionConfig::default(), operations.clone());
+    let parallel_changes = execute_block_operations(
+        ExecutionConfig {
+            parallel_operation_execution: true,
+            ..Default::default()
+        },
+        operations,
+    );
+
+    assert_eq!(
+        parallel_changes.ledger_changes,
+        sequential_changes.ledger_changes
+    );
+    assert_eq!(
+        parallel_changes.pos_changes.roll_changes,
+        sequential_changes.pos_changes.roll_changes
+    );
+    assert_eq!(
+        parallel_changes.pos_changes.deferred_credits.credits,
+        sequential_changes.pos_changes.deferred_credits.credits
+    );
+    assert_eq!(
+        parallel_changes.executed_ops_changes,
+        sequential_changes.executed_ops_changes
+    );
+    assert_eq!(
+        parallel_changes.async_pool_changes,
+        sequential_changes.async_pool_changes
+    );
+    assert_eq!(
+        parallel_changes.execution_trail_hash_change,
+        sequential_changes.execution_trail_hash_change
+    );
+}
+
+#[test]
+fn execution_profiling() {
+    // setup
+    let exec_cfg = ExecutionConfig {
+        execution_profiling: true,
+        ..Default::default()
+    };
+    let mut foreign_controllers = ExecutionForeignControllers::new_with_mocks();
+    let finalized_waitpoint = WaitPoint::new();
+    let finalized_waitpoint_trigger_handle = finalized_waitpoint.get_trigger_handle();
+    selector_boilerplate(&mut foreign_controllers.selector_controller);
+    final_state_boilerplate(
+        &mut foreign_controllers.final_state,
+        foreign_controllers.db.clone(),
+        &foreign_controllers.selector_controller,
+        &mut foreign_controllers.ledger_controller,
+        None,
+        None,
+        None,
+    );
+    foreign_controllers
+        .final_state
+        .write()
+        .expect_finalize()
+        .times(1)
+        .with(
+            predicate::eq(Slot::new(1, 0)),
+            predicate::always(),
+            predicate::always(),
+        )
+        .returning(move |_, _, _| {
+            finalized_waitpoint_trigger_handle.trigger();
+        });
+    let mut universe = ExecutionTestUniverse::new(foreign_controllers, exec_cfg.clone());
+
+    // execute a block containing a transaction
+    let keypair = KeyPair::from_str(TEST_SK_1).unwrap();
+    let operation = Operation::new_verifiable(
+        Operation {
+            fee: Amount::from_str("1").unwrap(),
+            expire_period: 10,
+            op: OperationType::Transaction {
+                recipient_address: Address::from_public_key(
+                    &KeyPair::generate(0).unwrap().get_public_key(),
+                ),
+                amount: Amount::from_str("10").unwrap(),
+            },
+        },
+        OperationSerializer::new(),
+        &keypair,
+    )
+    .unwrap();
+    universe.storage.store_operations(vec![operation.clone()]);
+    let block = ExecutionTestUniverse::create_block(
+        &keypair,
+        Slot::new(1, 0),
+        vec![operation],
+        vec![],
+        vec![],
+    );
+    universe.send_and_finalize(&keypair, block);
+    finalized_waitpoint.wait();
+
+    // check the profile
+    let profile = universe
+        .module_controller
+        .get_execution_profile(10)
+        .expect("execution profiling should be enabled");
+    assert_eq!(
+        profile.window_millis,
+        exec_cfg.execution_profiling_window.as_millis()
+    );
+    assert!(profile.slots.count >= 1);
+    let transactions = profile
+        .operations
+        .get("transaction")
+        .expect("missing transaction profile");
+    // the block slot is executed as a candidate then finalized, but profiled once
+    assert_eq!(transactions.count, 1);
+    assert_eq!(
+        transactions.gas,
+        transactions.count * exec_cfg.base_operation_gas_cost
+    );
+    assert!(profile.slots.gas >= transactions.gas);
+    assert_eq!(profile.async_messages.count, 0);
+    assert!(profile.top_contracts.is_empty());
+}
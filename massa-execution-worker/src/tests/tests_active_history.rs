@@ -54,6 +54,8 @@ fn test_active_history_deferred_credits() {
             execution_trail_hash_change: Default::default(),
         },
         events: Default::default(),
+        staking_history_changes: Default::default(),
+        async_message_events: Default::default(),
     };
 
     let active_history = ActiveHistory(VecDeque::from([exec_output_1]));
@@ -0,0 +1,37 @@
+use crate::stats::ExecutionStatsCounter;
+use massa_models::slot::Slot;
+use massa_time::{ManualClock, MassaTime, SharedClock};
+use std::sync::Arc;
+
+#[test]
+fn test_stats_time_window() {
+    let clock = Arc::new(ManualClock::new(MassaTime::from_millis(10_000)));
+    let mut counter = ExecutionStatsCounter::new(
+        MassaTime::from_millis(1_000),
+        SharedClock::new(clock.clone()),
+    );
+    let cursor = Slot::new(1, 0);
+
+    counter.register_final_blocks(2);
+    counter.register_final_executed_operations(5);
+    clock.advance(MassaTime::from_millis(600));
+    counter.register_final_blocks(3);
+
+    let stats = counter.get_stats(cursor, cursor);
+    assert_eq!(stats.final_block_count, 5);
+    assert_eq!(stats.final_executed_operations_count, 5);
+    assert_eq!(stats.time_window_start, MassaTime::from_millis(9_600));
+    assert_eq!(stats.time_window_end, MassaTime::from_millis(10_600));
+
+    // the first records leave the window
+    clock.advance(MassaTime::from_millis(600));
+    let stats = counter.get_stats(cursor, cursor);
+    assert_eq!(stats.final_block_count, 3);
+    assert_eq!(stats.final_executed_operations_count, 0);
+
+    // and are pruned at the next registration
+    counter.register_final_executed_operations(1);
+    let stats = counter.get_stats(cursor, cursor);
+    assert_eq!(stats.final_block_count, 3);
+    assert_eq!(stats.final_executed_operations_count, 1);
+}
@@ -95,6 +95,7 @@ impl TestUniverse for ExecutionTestUniverse {
             mip_store,
             ExecutionChannels {
                 slot_execution_output_sender: tx,
+                async_message_event_sender: broadcast::channel(16).0,
             },
             Arc::new(RwLock::new(create_test_wallet(Some(PreHashMap::default())))),
             MassaMetrics::new(
@@ -11,14 +11,14 @@ use crate::request_queue::RequestQueue;
 use crate::slot_sequencer::SlotSequencer;
 use massa_execution_exports::{
     ExecutionBlockMetadata, ExecutionChannels, ExecutionConfig, ExecutionController,
-    ExecutionError, ExecutionManager, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ExecutionError, ExecutionManager, ReadOnlyExecutionBundle, ReadOnlyExecutionOutput,
 };
 use massa_final_state::FinalStateController;
 use massa_metrics::MassaMetrics;
 use massa_models::block_id::BlockId;
 use massa_models::slot::Slot;
 use massa_pos_exports::SelectorController;
-use massa_time::MassaTime;
+use massa_time::SharedClock;
 use massa_versioning::versioning::MipStore;
 use massa_wallet::Wallet;
 use parking_lot::{Condvar, Mutex, RwLock};
@@ -35,9 +35,12 @@ pub(crate) struct ExecutionThread {
     // Execution state (see execution.rs) to which execution requests are sent
     execution_state: Arc<RwLock<ExecutionState>>,
     /// queue for read-only requests and response MPSCs to send back their outputs
-    readonly_requests: RequestQueue<ReadOnlyExecutionRequest, ReadOnlyExecutionOutput>,
+    readonly_requests:
+        RequestQueue<ReadOnlyExecutionBundle, Vec<Result<ReadOnlyExecutionOutput, ExecutionError>>>,
     /// Selector controller
     selector: Box<dyn SelectorController>,
+    /// Clock telling whether the next slot is due
+    clock: SharedClock,
 }
 
 impl ExecutionThread {
@@ -70,6 +73,7 @@ impl ExecutionThread {
             input_data,
             readonly_requests: RequestQueue::new(config.readonly_queue_length),
             execution_state,
+            clock: config.clock.clone(),
             slot_sequencer: SlotSequencer::new(config, final_cursor),
             selector,
         }
@@ -79,7 +83,10 @@ impl ExecutionThread {
     /// Cancel those that are in excess if there are too many.
     fn update_readonly_requests(
         &mut self,
-        new_requests: RequestQueue<ReadOnlyExecutionRequest, ReadOnlyExecutionOutput>,
+        new_requests: RequestQueue<
+            ReadOnlyExecutionBundle,
+            Vec<Result<ReadOnlyExecutionOutput, ExecutionError>>,
+        >,
     ) {
         // Append incoming readonly requests to our readonly request queue
         // Excess requests are cancelled
@@ -95,8 +102,8 @@ impl ExecutionThread {
         if let Some(req_resp) = self.readonly_requests.pop() {
             let (req, resp_tx) = req_resp.into_request_sender_pair();
 
-            // Acquire write access to the execution state (for cache updates) and execute the read-only request
-            let outcome = self.execution_state.write().execute_readonly_request(req);
+            // Acquire write access to the execution state (for cache updates) and execute the read-only bundle
+            let outcome = self.execution_state.write().execute_readonly_bundle(req);
 
             // Send the execution output through resp_tx.
             // Ignore errors because they just mean that the request emitter dropped the received
@@ -148,7 +155,7 @@ impl ExecutionThread {
             // Compute when the next slot will be
             // This is useful to wait for the next speculative miss to append to active slots.
             let wakeup_deadline = self.slot_sequencer.get_next_slot_deadline();
-            let now = MassaTime::now();
+            let now = self.clock.now();
             if wakeup_deadline <= now {
                 // next slot is right now: the loop needs to iterate
                 return (input_data, false);
@@ -158,9 +165,7 @@ impl ExecutionThread {
             // The return value is ignored because we don't care what woke up the condition variable.
             let _ = self.input_data.0.wait_until(
                 &mut input_data_lock,
-                wakeup_deadline
-                    .estimate_instant()
-                    .expect("could not estimate instant"),
+                self.clock.estimate_instant(wakeup_deadline),
             );
         }
     }
@@ -2,7 +2,7 @@
 
 //! This file defines the factory settings
 
-use massa_time::MassaTime;
+use massa_time::{MassaTime, SharedClock};
 
 /// Structure defining the settings of the factory
 #[derive(Debug, Clone)]
@@ -29,4 +29,6 @@ pub struct FactoryConfig {
     pub denunciation_expire_periods: u64,
     /// choose whether to stop production when zero connections on protocol
     pub stop_production_when_zero_connections: bool,
+    /// source of the current time, the system clock outside of tests
+    pub clock: SharedClock,
 }
@@ -3,6 +3,9 @@
 //! This module exports generic traits representing interfaces for interacting
 //! with the factory worker.
 
+use crate::FactoryResult;
+use massa_models::slot::Slot;
+
 /// Factory manager used to stop the factory thread
 pub trait FactoryManager {
     /// Stop the factory thread
@@ -11,3 +14,30 @@ pub trait FactoryManager {
     /// This will improve if the `unsized_fn_params` feature stabilizes enough to be safely usable.
     fn stop(&mut self);
 }
+
+/// Factory controller used to drive block production on a local development network
+pub trait FactoryController: Send + Sync {
+    /// Fast-forward the node clock slot by slot (see `Clock::fast_forward`),
+    /// producing the block of each slot as soon as its time is reached,
+    /// and waiting for consensus to integrate it before moving on to the next slot.
+    /// Only meant for a node that produces all the blocks of its network.
+    ///
+    /// # Arguments
+    /// * `slot_count`: number of slots to fast-forward
+    ///
+    /// # Returns
+    /// The last slot processed by the block factory
+    fn fast_forward(&self, slot_count: u64) -> FactoryResult<Slot>;
+
+    /// Returns a boxed clone of self.
+    /// Useful to allow cloning `Box<dyn FactoryController>`.
+    fn clone_box(&self) -> Box<dyn FactoryController>;
+}
+
+/// Allow cloning `Box<dyn FactoryController>`
+/// Uses `FactoryController::clone_box` internally
+impl Clone for Box<dyn FactoryController> {
+    fn clone(&self) -> Box<dyn FactoryController> {
+        self.clone_box()
+    }
+}
@@ -12,7 +12,7 @@ mod error;
 mod types;
 
 pub use config::FactoryConfig;
-pub use controller_traits::FactoryManager;
+pub use controller_traits::{FactoryController, FactoryManager};
 pub use error::*;
 pub use types::*;
 
@@ -1,7 +1,7 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use crate::FactoryConfig;
-use massa_time::MassaTime;
+use massa_time::{MassaTime, SharedClock};
 
 impl Default for FactoryConfig {
     fn default() -> Self {
@@ -18,6 +18,7 @@ impl Default for FactoryConfig {
             periods_per_cycle: PERIODS_PER_CYCLE,
             denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
             stop_production_when_zero_connections: false,
+            clock: SharedClock::default(),
         }
     }
 }
@@ -1,5 +1,7 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::controller::BlockFactoryCommand;
+use crossbeam_channel::select;
 use massa_channel::receiver::MassaReceiver;
 use massa_factory_exports::{FactoryChannels, FactoryConfig};
 use massa_models::{
@@ -16,8 +18,12 @@ use massa_time::MassaTime;
 use massa_versioning::versioning::MipStore;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
-use std::{sync::Arc, thread, time::Instant};
-use tracing::{info, warn};
+use std::{
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+use tracing::{debug, info, warn};
 
 /// Structure gathering all elements needed by the factory thread
 pub(crate) struct BlockFactoryWorker {
@@ -25,6 +31,7 @@ pub(crate) struct BlockFactoryWorker {
     wallet: Arc<RwLock<Wallet>>,
     channels: FactoryChannels,
     factory_receiver: MassaReceiver<()>,
+    command_receiver: MassaReceiver<BlockFactoryCommand>,
     mip_store: MipStore,
     op_id_serializer: OperationIdSerializer,
 }
@@ -37,6 +44,7 @@ impl BlockFactoryWorker {
         wallet: Arc<RwLock<Wallet>>,
         channels: FactoryChannels,
         factory_receiver: MassaReceiver<()>,
+        command_receiver: MassaReceiver<BlockFactoryCommand>,
         mip_store: MipStore,
     ) -> thread::JoinHandle<()> {
         thread::Builder::new()
@@ -47,6 +55,7 @@ impl BlockFactoryWorker {
                     wallet,
                     channels,
                     factory_receiver,
+                    command_receiver,
                     mip_store,
                     op_id_serializer: OperationIdSerializer::new(),
                 };
@@ -55,12 +64,12 @@ impl BlockFactoryWorker {
             .expect("failed to spawn thread : block-factory")
     }
 
-    /// Gets the next slot and the instant when it will happen.
+    /// Gets the next slot and the timestamp when it will happen.
     /// Slots can be skipped if we waited too much in-between.
     /// Extra safety against double-production caused by clock adjustments (this is the role of the `previous_slot` parameter).
-    fn get_next_slot(&self, previous_slot: Option<Slot>) -> (Slot, Instant) {
+    fn get_next_slot(&self, previous_slot: Option<Slot>) -> (Slot, MassaTime) {
         // get current absolute time
-        let now = MassaTime::now();
+        let now = self.cfg.clock.now();
 
         // if it's the first computed slot, add a time shift to prevent double-production on node restart with clock skew
         let base_time = if previous_slot.is_none() {
@@ -92,32 +101,66 @@ impl BlockFactoryWorker {
         }
 
         // get the timestamp of the target slot
-        let next_instant = get_block_slot_timestamp(
+        let next_timestamp = get_block_slot_timestamp(
             self.cfg.thread_count,
             self.cfg.t0,
             self.cfg.genesis_timestamp,
             next_slot,
         )
-        .expect("could not get block slot timestamp")
-        .estimate_instant()
-        .expect("could not estimate block slot instant");
+        .expect("could not get block slot timestamp");
 
-        (next_slot, next_instant)
+        (next_slot, next_timestamp)
     }
 
-    /// Wait and interrupt or wait until an instant or a stop signal
+    /// Fast-forward the clock slot by slot, producing the block of each slot as soon as its time is reached.
+    /// See `FactoryController::fast_forward`.
     ///
     /// # Return value
-    /// Returns `true` if the instant was reached, otherwise `false` if there was an interruption.
-    fn interruptible_wait_until(&self, deadline: Instant) -> bool {
-        match self.factory_receiver.recv_deadline(deadline) {
-            // message received => quit main loop
-            Ok(()) => false,
-            // timeout => continue main loop
-            Err(crossbeam_channel::RecvTimeoutError::Timeout) => true,
-            // channel disconnected (sender dropped) => quit main loop
-            Err(crossbeam_channel::RecvTimeoutError::Disconnected) => false,
+    /// Returns the last processed slot.
+    fn fast_forward(&mut self, previous_slot: Option<Slot>, slot_count: u64) -> Option<Slot> {
+        let mut prev_slot = previous_slot;
+        for _ in 0..slot_count {
+            let (slot, _) = self.get_next_slot(prev_slot);
+
+            // shift the clock to the timestamp of the slot
+            let slot_timestamp = get_block_slot_timestamp(
+                self.cfg.thread_count,
+                self.cfg.t0,
+                self.cfg.genesis_timestamp,
+                slot,
+            )
+            .expect("could not get block slot timestamp");
+            let now = self.cfg.clock.now();
+            if slot_timestamp > now
+                && !self
+                    .cfg
+                    .clock
+                    .fast_forward(slot_timestamp.saturating_sub(now))
+            {
+                warn!("the clock of the node cannot be fast-forwarded");
+                break;
+            }
+
+            // the draws of the slot might still be computed by the selector
+            let deadline = Instant::now() + self.cfg.t0.to_duration();
+            while self.channels.selector.get_producer(slot).is_err() && Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(1));
+            }
+
+            self.process_slot(slot);
+
+            // wait for consensus to integrate the block, so that it is the parent of the next one
+            while self.channels.consensus.get_best_parents()[slot.thread as usize].1 < slot.period {
+                if Instant::now() >= deadline {
+                    debug!("no block integrated at slot {} while fast-forwarding", slot);
+                    break;
+                }
+                thread::sleep(Duration::from_millis(1));
+            }
+
+            prev_slot = Some(slot);
         }
+        prev_slot
     }
 
     /// Process a slot: produce a block at that slot if one of the managed keys is drawn.
@@ -258,18 +301,31 @@ impl BlockFactoryWorker {
         let mut prev_slot = None;
         loop {
             // get next slot
-            let (slot, block_instant) = self.get_next_slot(prev_slot);
-
-            // wait until slot
-            if !self.interruptible_wait_until(block_instant) {
-                break;
-            }
+            let (slot, block_timestamp) = self.get_next_slot(prev_slot);
+            let slot_timer = self.cfg.clock.after(block_timestamp);
 
-            // process slot
-            self.process_slot(slot);
+            // wait until slot, a command or a stop signal
+            select! {
+                // message received or channel disconnected (sender dropped) => quit main loop
+                recv(self.factory_receiver) -> _ => break,
+                recv(self.command_receiver) -> command => {
+                    self.command_receiver.update_metrics();
+                    match command {
+                        Ok(BlockFactoryCommand::FastForward { slot_count, response_tx }) => {
+                            prev_slot = self.fast_forward(prev_slot, slot_count);
+                            let _ = response_tx.send(prev_slot.unwrap_or(slot));
+                        }
+                        Err(_) => break,
+                    }
+                },
+                recv(slot_timer) -> _ => {
+                    // process slot
+                    self.process_slot(slot);
 
-            // update previous slot
-            prev_slot = Some(slot);
+                    // update previous slot
+                    prev_slot = Some(slot);
+                }
+            }
         }
     }
 }
@@ -0,0 +1,49 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This module implements a factory controller.
+//! See `massa-factory-exports/controller_traits.rs` for functional details.
+
+use massa_channel::sender::MassaSender;
+use massa_factory_exports::{FactoryController, FactoryError, FactoryResult};
+use massa_models::slot::Slot;
+
+/// Commands that can be sent to the block factory worker
+pub(crate) enum BlockFactoryCommand {
+    /// fast-forward the clock slot by slot, producing the block of each slot
+    FastForward {
+        /// number of slots to fast-forward
+        slot_count: u64,
+        /// channel through which the last processed slot is sent back
+        response_tx: crossbeam_channel::Sender<Slot>,
+    },
+}
+
+/// Implementation of the factory controller
+#[derive(Clone)]
+pub struct FactoryControllerImpl {
+    /// block factory command sender
+    pub(crate) block_command_sender: MassaSender<BlockFactoryCommand>,
+}
+
+impl FactoryController for FactoryControllerImpl {
+    /// See trait definition
+    fn fast_forward(&self, slot_count: u64) -> FactoryResult<Slot> {
+        let (response_tx, response_rx) = crossbeam_channel::bounded(1);
+        self.block_command_sender
+            .send(BlockFactoryCommand::FastForward {
+                slot_count,
+                response_tx,
+            })
+            .map_err(|_| FactoryError::GenericError("block factory is stopped".to_string()))?;
+        response_rx.recv().map_err(|_| {
+            FactoryError::GenericError("block factory stopped while fast-forwarding".to_string())
+        })
+    }
+
+    /// Returns a boxed clone of self.
+    /// Allows cloning `Box<dyn FactoryController>`,
+    /// see `massa-factory-exports/controller_traits.rs`
+    fn clone_box(&self) -> Box<dyn FactoryController> {
+        Box::new(self.clone())
+    }
+}
@@ -1,5 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crossbeam_channel::select;
 use massa_channel::receiver::MassaReceiver;
 use massa_factory_exports::{FactoryChannels, FactoryConfig};
 use massa_models::{
@@ -13,7 +14,7 @@ use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
-use std::{sync::Arc, thread, time::Instant};
+use std::{sync::Arc, thread};
 use tracing::{debug, warn};
 
 /// Structure gathering all elements needed by the factory thread
@@ -54,12 +55,12 @@ impl EndorsementFactoryWorker {
             .expect("failed to spawn thread : endorsement-factory")
     }
 
-    /// Gets the next slot and the instant when the corresponding endorsements should be made.
+    /// Gets the next slot and the timestamp at which the corresponding endorsements should be made.
     /// Slots can be skipped if we waited too much in-between.
     /// Extra safety against double-production caused by clock adjustments (this is the role of the `previous_slot` parameter).
-    fn get_next_slot(&self, previous_slot: Option<Slot>) -> (Slot, Instant) {
+    fn get_next_slot(&self, previous_slot: Option<Slot>) -> (Slot, MassaTime) {
         // get delayed time
-        let now = MassaTime::now();
+        let now = self.cfg.clock.now();
 
         // if it's the first computed slot, add a time shift to prevent double-production on node restart with clock skew
         let base_time = if previous_slot.is_none() {
@@ -91,32 +92,29 @@ impl EndorsementFactoryWorker {
         }
 
         // get the timestamp of the target slot
-        let next_instant = get_block_slot_timestamp(
+        let next_timestamp = get_block_slot_timestamp(
             self.cfg.thread_count,
             self.cfg.t0,
             self.cfg.genesis_timestamp,
             next_slot,
         )
         .expect("could not get block slot timestamp")
-        .saturating_sub(self.half_t0)
-        .estimate_instant()
-        .expect("could not estimate block slot instant");
+        .saturating_sub(self.half_t0);
 
-        (next_slot, next_instant)
+        (next_slot, next_timestamp)
     }
 
-    /// Wait and interrupt or wait until an instant or a stop signal
+    /// Wait until the clock reaches `deadline` or a stop signal
     ///
     /// # Return value
-    /// Returns `true` if the instant was reached, otherwise `false` if there was an interruption.
-    fn interruptible_wait_until(&self, deadline: Instant) -> bool {
-        match self.factory_receiver.recv_deadline(deadline) {
-            // message received => quit main loop
-            Ok(()) => false,
-            // timeout => continue main loop
-            Err(crossbeam_channel::RecvTimeoutError::Timeout) => true,
-            // channel disconnected (sender dropped) => quit main loop
-            Err(crossbeam_channel::RecvTimeoutError::Disconnected) => false,
+    /// Returns `true` if the deadline was reached, otherwise `false` if there was an interruption.
+    fn interruptible_wait_until(&self, deadline: MassaTime) -> bool {
+        let timer = self.cfg.clock.after(deadline);
+        select! {
+            // message received or channel disconnected (sender dropped) => quit main loop
+            recv(self.factory_receiver) -> _ => false,
+            // deadline reached => continue main loop
+            recv(timer) -> _ => true,
         }
     }
 
@@ -215,10 +213,10 @@ impl EndorsementFactoryWorker {
         let mut prev_slot = None;
         loop {
             // get next slot
-            let (slot, endorsement_instant) = self.get_next_slot(prev_slot);
+            let (slot, endorsement_timestamp) = self.get_next_slot(prev_slot);
 
             // wait until slot
-            if !self.interruptible_wait_until(endorsement_instant) {
+            if !self.interruptible_wait_until(endorsement_timestamp) {
                 break;
             }
 
@@ -1,6 +1,7 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 mod block_factory;
+mod controller;
 mod endorsement_factory;
 mod manager;
 mod run;
@@ -5,6 +5,7 @@
 
 use std::thread::JoinHandle;
 
+use crate::controller::BlockFactoryCommand;
 use massa_channel::sender::MassaSender;
 use massa_factory_exports::FactoryManager;
 use tracing::{info, warn};
@@ -17,6 +18,10 @@ pub struct FactoryManagerImpl {
 
     /// endorsement worker message sender and join handle
     pub(crate) endorsement_worker: Option<(MassaSender<()>, JoinHandle<()>)>,
+
+    /// block worker command sender, kept so that the command channel stays connected
+    /// even if all the factory controllers are dropped
+    pub(crate) _block_command_sender: MassaSender<BlockFactoryCommand>,
 }
 
 impl FactoryManager for FactoryManagerImpl {
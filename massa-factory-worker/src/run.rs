@@ -6,10 +6,10 @@ use parking_lot::RwLock;
 use std::sync::Arc;
 
 use crate::{
-    block_factory::BlockFactoryWorker, endorsement_factory::EndorsementFactoryWorker,
-    manager::FactoryManagerImpl,
+    block_factory::BlockFactoryWorker, controller::FactoryControllerImpl,
+    endorsement_factory::EndorsementFactoryWorker, manager::FactoryManagerImpl,
 };
-use massa_factory_exports::{FactoryChannels, FactoryConfig, FactoryManager};
+use massa_factory_exports::{FactoryChannels, FactoryConfig, FactoryController, FactoryManager};
 use massa_wallet::Wallet;
 
 /// Start factory
@@ -20,16 +20,19 @@ use massa_wallet::Wallet;
 /// * `channels`: channels to communicate with other modules
 ///
 /// # Return value
-/// Returns a factory manager allowing to stop the workers cleanly.
+/// Returns a factory manager allowing to stop the workers cleanly,
+/// and a factory controller allowing to drive block production.
 pub fn start_factory(
     cfg: FactoryConfig,
     wallet: Arc<RwLock<Wallet>>,
     channels: FactoryChannels,
     mip_store: MipStore,
-) -> Box<dyn FactoryManager> {
-    // create block factory channel
+) -> (Box<dyn FactoryManager>, Box<dyn FactoryController>) {
+    // create block factory channels
     let (block_worker_tx, block_worker_rx) =
         MassaChannel::new("factory_block_worker".to_string(), None);
+    let (block_command_tx, block_command_rx) =
+        MassaChannel::new("factory_block_worker_command".to_string(), None);
 
     // create endorsement factory channel
     let (endorsement_worker_tx, endorsement_worker_rx) =
@@ -41,6 +44,7 @@ pub fn start_factory(
         wallet.clone(),
         channels.clone(),
         block_worker_rx,
+        block_command_rx,
         mip_store,
     );
 
@@ -52,7 +56,13 @@ pub fn start_factory(
     let manager = FactoryManagerImpl {
         block_worker: Some((block_worker_tx, block_worker_handle)),
         endorsement_worker: Some((endorsement_worker_tx, endorsement_worker_handle)),
+        _block_command_sender: block_command_tx.clone(),
     };
 
-    Box::new(manager)
+    // create factory controller
+    let controller = FactoryControllerImpl {
+        block_command_sender: block_command_tx,
+    };
+
+    (Box::new(manager), Box::new(controller))
 }
@@ -1,7 +1,9 @@
-use std::{str::FromStr, sync::Arc};
+use std::{str::FromStr, sync::Arc, time::Duration};
 
 use super::BlockTestFactory;
+use crate::controller::FactoryControllerImpl;
 use massa_consensus_exports::MockConsensusController;
+use massa_factory_exports::{FactoryConfig, FactoryController};
 use massa_hash::Hash;
 use massa_models::{
     address::Address,
@@ -11,11 +13,13 @@ use massa_models::{
     operation::{Operation, OperationSerializer, OperationType},
     secure_share::SecureShareContent,
     slot::Slot,
+    timeslots::get_block_slot_timestamp,
 };
 use massa_pool_exports::MockPoolController;
 use massa_pos_exports::MockSelectorController;
 use massa_signature::KeyPair;
 use massa_storage::Storage;
+use massa_time::{Clock, ManualClock, MassaTime, SharedClock};
 use parking_lot::{Condvar, Mutex};
 use serial_test::serial;
 
@@ -182,3 +186,176 @@ fn basic_creation_with_operation() {
     }
     test_factory.stop();
 }
+
+/// Produces blocks only when a manual clock reaches their slots.
+#[test]
+#[serial]
+fn creation_follows_manual_clock() {
+    let default_panic = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_panic(info);
+        std::process::exit(1);
+    }));
+    let keypair = KeyPair::generate(0).unwrap();
+    let storage = Storage::create_root();
+    let staking_address = Address::from_public_key(&keypair.get_public_key());
+    let parent = BlockId::generate_from_hash(Hash::compute_from("test".as_bytes()));
+    let parents: Vec<(BlockId, u64)> = (0..THREAD_COUNT as u64).map(|i| (parent, i)).collect();
+
+    let genesis_timestamp = MassaTime::from_millis(1_000_000);
+    let clock = Arc::new(ManualClock::new(genesis_timestamp));
+    let factory_config = FactoryConfig {
+        genesis_timestamp,
+        clock: SharedClock::new(clock.clone()),
+        ..Default::default()
+    };
+    let slot_timestamp = |slot| {
+        get_block_slot_timestamp(
+            factory_config.thread_count,
+            factory_config.t0,
+            genesis_timestamp,
+            slot,
+        )
+        .unwrap()
+    };
+
+    let (produced_tx, produced_rx) = std::sync::mpsc::channel();
+    let produced_tx = std::sync::Mutex::new(produced_tx);
+    let mut consensus_controller = Box::new(MockConsensusController::new());
+    consensus_controller
+        .expect_get_best_parents()
+        .returning(move || parents.clone());
+    consensus_controller
+        .expect_register_block()
+        .returning(move |_, slot, _, created| {
+            assert!(created);
+            produced_tx.lock().unwrap().send(slot).unwrap();
+        });
+    let mut selector_controller = Box::new(MockSelectorController::new());
+    selector_controller
+        .expect_get_producer()
+        .returning(move |_| Ok(staking_address));
+    let mut pool_controller = Box::new(MockPoolController::new());
+    pool_controller
+        .expect_get_block_denunciations()
+        .returning(|_| vec![]);
+    pool_controller
+        .expect_get_block_operations()
+        .returning(|_| (vec![], Storage::create_root()));
+    pool_controller
+        .expect_get_block_endorsements()
+        .returning(|_, _| (vec![], Storage::create_root()));
+    let mut test_factory = BlockTestFactory::with_config(
+        factory_config.clone(),
+        &keypair,
+        storage,
+        consensus_controller,
+        selector_controller,
+        pool_controller,
+    );
+
+    // the clock stands still at genesis: nothing is produced
+    assert!(produced_rx
+        .recv_timeout(Duration::from_millis(300))
+        .is_err());
+
+    // each move of the clock to a slot produces the block of that slot
+    clock.set(slot_timestamp(Slot::new(1, 0)));
+    assert_eq!(
+        produced_rx.recv_timeout(Duration::from_secs(10)).unwrap(),
+        Slot::new(1, 0)
+    );
+    clock.set(slot_timestamp(Slot::new(1, 1)));
+    assert_eq!(
+        produced_rx.recv_timeout(Duration::from_secs(10)).unwrap(),
+        Slot::new(1, 1)
+    );
+    assert!(produced_rx
+        .recv_timeout(Duration::from_millis(300))
+        .is_err());
+
+    test_factory.stop();
+}
+
+/// Fast-forwarding moves the clock of the factory to each slot and produces its block.
+#[test]
+#[serial]
+fn fast_forward_moves_clock() {
+    let default_panic = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_panic(info);
+        std::process::exit(1);
+    }));
+    let keypair = KeyPair::generate(0).unwrap();
+    let storage = Storage::create_root();
+    let staking_address = Address::from_public_key(&keypair.get_public_key());
+    let parent = BlockId::generate_from_hash(Hash::compute_from("test".as_bytes()));
+    let parents: Arc<std::sync::Mutex<Vec<(BlockId, u64)>>> = Arc::new(std::sync::Mutex::new(
+        (0..THREAD_COUNT as u64).map(|_| (parent, 0)).collect(),
+    ));
+
+    let genesis_timestamp = MassaTime::from_millis(1_000_000);
+    let clock = Arc::new(ManualClock::new(genesis_timestamp));
+    let factory_config = FactoryConfig {
+        genesis_timestamp,
+        clock: SharedClock::new(clock.clone()),
+        ..Default::default()
+    };
+
+    let (produced_tx, produced_rx) = std::sync::mpsc::channel();
+    let produced_tx = std::sync::Mutex::new(produced_tx);
+    let mut consensus_controller = Box::new(MockConsensusController::new());
+    consensus_controller.expect_get_best_parents().returning({
+        let parents = parents.clone();
+        move || parents.lock().unwrap().clone()
+    });
+    // the produced block becomes the best parent of its thread
+    consensus_controller
+        .expect_register_block()
+        .returning(move |block_id, slot, _, created| {
+            assert!(created);
+            parents.lock().unwrap()[slot.thread as usize] = (block_id, slot.period);
+            produced_tx.lock().unwrap().send(slot).unwrap();
+        });
+    let mut selector_controller = Box::new(MockSelectorController::new());
+    selector_controller
+        .expect_get_producer()
+        .returning(move |_| Ok(staking_address));
+    let mut pool_controller = Box::new(MockPoolController::new());
+    pool_controller
+        .expect_get_block_denunciations()
+        .returning(|_| vec![]);
+    pool_controller
+        .expect_get_block_operations()
+        .returning(|_| (vec![], Storage::create_root()));
+    pool_controller
+        .expect_get_block_endorsements()
+        .returning(|_, _| (vec![], Storage::create_root()));
+    let mut test_factory = BlockTestFactory::with_config(
+        factory_config.clone(),
+        &keypair,
+        storage,
+        consensus_controller,
+        selector_controller,
+        pool_controller,
+    );
+
+    let factory_controller = FactoryControllerImpl {
+        block_command_sender: test_factory.command_sender.clone(),
+    };
+    assert_eq!(factory_controller.fast_forward(2).unwrap(), Slot::new(1, 1));
+    assert_eq!(produced_rx.try_recv().unwrap(), Slot::new(1, 0));
+    assert_eq!(produced_rx.try_recv().unwrap(), Slot::new(1, 1));
+    assert_eq!(
+        clock.now(),
+        get_block_slot_timestamp(
+            factory_config.thread_count,
+            factory_config.t0,
+            genesis_timestamp,
+            Slot::new(1, 1)
+        )
+        .unwrap()
+    );
+
+    test_factory.stop();
+}
@@ -18,6 +18,7 @@ use massa_signature::KeyPair;
 use massa_storage::Storage;
 
 use crate::block_factory::BlockFactoryWorker;
+use crate::controller::BlockFactoryCommand;
 use crate::endorsement_factory::EndorsementFactoryWorker;
 use massa_wallet::test_exports::create_test_wallet;
 
@@ -25,6 +26,7 @@ use massa_wallet::test_exports::create_test_wallet;
 pub struct BlockTestFactory {
     _factory_config: FactoryConfig,
     thread: Option<(MassaSender<()>, JoinHandle<()>)>,
+    pub(crate) command_sender: MassaSender<BlockFactoryCommand>,
     _genesis_blocks: Vec<(BlockId, u64)>,
     pub(crate) _storage: Storage,
     _keypair: KeyPair,
@@ -38,6 +40,30 @@ impl BlockTestFactory {
     /// Returns
     /// - `TestFactory`: the structure that will be used to manage the tests
     pub fn new(
+        default_keypair: &KeyPair,
+        storage: Storage,
+        consensus_controller: Box<MockConsensusController>,
+        selector_controller: Box<MockSelectorController>,
+        pool_controller: Box<MockPoolController>,
+    ) -> BlockTestFactory {
+        let mut factory_config = FactoryConfig::default();
+        factory_config.genesis_timestamp = factory_config
+            .genesis_timestamp
+            .checked_sub(factory_config.t0.checked_div_u64(2).unwrap())
+            .unwrap();
+        BlockTestFactory::with_config(
+            factory_config,
+            default_keypair,
+            storage,
+            consensus_controller,
+            selector_controller,
+            pool_controller,
+        )
+    }
+
+    /// Same as `BlockTestFactory::new` with a given factory configuration
+    pub fn with_config(
+        factory_config: FactoryConfig,
         default_keypair: &KeyPair,
         mut storage: Storage,
         consensus_controller: Box<MockConsensusController>,
@@ -49,11 +75,6 @@ impl BlockTestFactory {
         protocol_controller
             .expect_clone_box()
             .return_once(move || block_protocol_controller);
-        let mut factory_config = FactoryConfig::default();
-        factory_config.genesis_timestamp = factory_config
-            .genesis_timestamp
-            .checked_sub(factory_config.t0.checked_div_u64(2).unwrap())
-            .unwrap();
         let producer_keypair = default_keypair;
         let producer_address = Address::from_public_key(&producer_keypair.get_public_key());
         let mut accounts = PreHashMap::default();
@@ -77,6 +98,8 @@ impl BlockTestFactory {
 
         let wallet = create_test_wallet(Some(accounts));
         let (tx, rx) = MassaChannel::new(String::from("test_block_factory"), None);
+        let (command_tx, command_rx) =
+            MassaChannel::new(String::from("test_block_factory_command"), None);
         let join_handle = BlockFactoryWorker::spawn(
             factory_config.clone(),
             Arc::new(RwLock::new(wallet)),
@@ -88,12 +111,14 @@ impl BlockTestFactory {
                 storage: storage.clone_without_refs(),
             },
             rx,
+            command_rx,
             mip_store,
         );
 
         BlockTestFactory {
             _factory_config: factory_config,
             thread: Some((tx, join_handle)),
+            command_sender: command_tx,
             _genesis_blocks: genesis_blocks,
             _storage: storage,
             _keypair: default_keypair.clone(),
@@ -4,7 +4,7 @@ use massa_executed_ops::ExecutedDenunciations;
 use massa_hash::Hash;
 use massa_ledger_exports::LedgerController;
 use massa_models::{operation::OperationId, slot::Slot};
-use massa_pos_exports::PoSFinalState;
+use massa_pos_exports::{PoSFinalState, StakingHistoryChanges};
 use massa_versioning::versioning::MipStore;
 
 use crate::{FinalStateError, StateChanges};
@@ -33,8 +33,16 @@ pub trait FinalStateController: Send + Sync {
     /// Applies changes to the execution state at a given slot, and settles that slot forever.
     /// Once this is called, the state is attached at the output of the provided slot.
     ///
+    /// The staking activity of the slot is added to the node local staking history
+    /// in the same write.
+    ///
     /// Panics if the new slot is not the one coming just after the current one.
-    fn finalize(&mut self, slot: Slot, changes: StateChanges);
+    fn finalize(
+        &mut self,
+        slot: Slot,
+        changes: StateChanges,
+        staking_history_changes: StakingHistoryChanges,
+    );
 
     /// After bootstrap or load from disk, recompute all the caches.
     fn recompute_caches(&mut self);
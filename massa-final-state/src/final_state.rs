@@ -6,7 +6,10 @@
 //! and need to be bootstrapped by nodes joining the network.
 
 use crate::controller_trait::FinalStateController;
-use crate::{config::FinalStateConfig, error::FinalStateError, state_changes::StateChanges};
+use crate::{
+    config::FinalStateConfig, error::FinalStateError, state_changes::StateChanges,
+    state_fork::StateFork,
+};
 
 use anyhow::{anyhow, Result as AnyResult};
 use massa_async_pool::AsyncPool;
@@ -19,11 +22,11 @@ use massa_db_exports::{EXECUTION_TRAIL_HASH_PREFIX, MIP_STORE_STATS_PREFIX, VERS
 use massa_executed_ops::ExecutedDenunciations;
 use massa_executed_ops::ExecutedOps;
 use massa_hash::Hash;
-use massa_ledger_exports::LedgerController;
 use massa_ledger_exports::SetOrKeep;
+use massa_ledger_exports::{LedgerChanges, LedgerController};
 use massa_models::operation::OperationId;
 use massa_models::slot::Slot;
-use massa_pos_exports::{PoSFinalState, SelectorController};
+use massa_pos_exports::{PoSFinalState, SelectorController, StakingHistoryChanges};
 use massa_versioning::versioning::MipStore;
 use tracing::{debug, info, warn};
 
@@ -426,7 +429,12 @@ impl FinalState {
         Ok(())
     }
 
-    fn _finalize(&mut self, slot: Slot, changes: StateChanges) -> AnyResult<()> {
+    fn _finalize(
+        &mut self,
+        slot: Slot,
+        changes: StateChanges,
+        staking_history_changes: StakingHistoryChanges,
+    ) -> AnyResult<()> {
         let cur_slot = self.db.read().get_change_id()?;
         // check slot consistency
         let next_slot = cur_slot.get_next_slot(self.config.thread_count)?;
@@ -442,6 +450,7 @@ impl FinalState {
 
         let mut db_batch = DBBatch::new();
         let mut db_versioning_batch = DBBatch::new();
+        let mut db_indexes_batch = DBBatch::new();
 
         // apply the state changes to the batch
 
@@ -491,9 +500,19 @@ impl FinalState {
             );
         }
 
-        self.db
-            .write()
-            .write_batch(db_batch, db_versioning_batch, Some(slot));
+        // append the staking activity of the slot to the node local staking history
+        self.pos_state.apply_staking_history_changes_to_batch(
+            slot.get_cycle(self.config.periods_per_cycle),
+            &staking_history_changes,
+            &mut db_indexes_batch,
+        );
+
+        self.db.write().write_batch_with_indexes(
+            db_batch,
+            db_versioning_batch,
+            db_indexes_batch,
+            Some(slot),
+        );
 
         let final_state_hash = self.db.read().get_xof_db_hash();
 
@@ -708,8 +727,95 @@ impl FinalState {
     ) -> Result<Self, FinalStateError> {
         info!("Restarting from snapshot");
 
-        let mut final_state =
-            FinalState::new(db, config.clone(), ledger, selector, mip_store, false)?;
+        let final_state = FinalState::new(db, config, ledger, selector, mip_store, false)?;
+        FinalState::derive_from_snapshot(final_state, last_start_period)
+    }
+
+    /// Used to start a local network from a snapshot of another network:
+    /// the final state is modified by `fork` before restarting from the snapshot.
+    /// See `new_derived_from_snapshot`.
+    pub fn new_forked_from_snapshot(
+        db: ShareableMassaDBController,
+        config: FinalStateConfig,
+        ledger: Box<dyn LedgerController>,
+        selector: Box<dyn SelectorController>,
+        mip_store: MipStore,
+        last_start_period: u64,
+        fork: &StateFork,
+    ) -> Result<Self, FinalStateError> {
+        info!("Forking from snapshot");
+
+        let mut final_state = FinalState::new(db, config, ledger, selector, mip_store, false)?;
+        final_state.apply_state_fork(fork)?;
+        FinalState::derive_from_snapshot(final_state, last_start_period)
+    }
+
+    /// Writes the modifications of a fork to the final state, at its current slot
+    fn apply_state_fork(&mut self, fork: &StateFork) -> Result<(), FinalStateError> {
+        let slot =
+            self.db.read().get_change_id().map_err(|_| {
+                FinalStateError::InvalidSlot(String::from("Could not get slot in db"))
+            })?;
+
+        let mut ledger_changes = LedgerChanges::default();
+        for address in fork.balances.keys().chain(fork.bytecodes.keys()) {
+            if !self.ledger.entry_exists(address) {
+                ledger_changes.create_address(address);
+            }
+        }
+        for (address, balance) in &fork.balances {
+            ledger_changes.set_balance(*address, *balance);
+        }
+        for (address, bytecode) in &fork.bytecodes {
+            ledger_changes.set_bytecode(*address, bytecode.clone());
+        }
+
+        // the node local indexes are updated before the ledger, as they read the previous values from it.
+        // An index that does not match the final slot is left stale, to be rebuilt from the whole ledger.
+        if self.ledger.get_analytics_slot() == Some(slot) {
+            self.ledger.apply_analytics_changes(&ledger_changes, &slot);
+        }
+        if self
+            .ledger
+            .get_merkle_root()
+            .map(|(tree_slot, _)| tree_slot)
+            == Some(slot)
+        {
+            self.ledger.apply_merkle_changes(&ledger_changes, &slot);
+        }
+
+        let mut batch = DBBatch::new();
+        self.ledger
+            .apply_changes_to_batch(ledger_changes, &mut batch);
+
+        // the cycle history is read from the db
+        self.pos_state.recompute_pos_state_caches();
+        if self.pos_state.cycle_history_cache.is_empty() {
+            return Err(FinalStateError::SnapshotError(String::from(
+                "Impossible to fork: no cycle in the given snapshot",
+            )));
+        }
+        self.pos_state
+            .overwrite_roll_counts(&fork.rolls, fork.reset_stakers, &mut batch);
+
+        self.db
+            .write()
+            .write_batch(batch, Default::default(), Some(slot));
+        info!(
+            "final_state hash at slot {} after fork: {}",
+            slot,
+            self.db.read().get_xof_db_hash()
+        );
+        Ok(())
+    }
+
+    /// Attaches a final state loaded from a snapshot at the given last start period,
+    /// interpolating the downtime. See `new_derived_from_snapshot`.
+    fn derive_from_snapshot(
+        mut final_state: FinalState,
+        last_start_period: u64,
+    ) -> Result<Self, FinalStateError> {
+        let config = final_state.config.clone();
 
         let recovered_slot =
             final_state.db.read().get_change_id().map_err(|_| {
@@ -794,8 +900,14 @@ impl FinalStateController for FinalState {
             .map_err(|err| FinalStateError::PosError(err.to_string()))
     }
 
-    fn finalize(&mut self, slot: Slot, changes: StateChanges) {
-        self._finalize(slot, changes).unwrap()
+    fn finalize(
+        &mut self,
+        slot: Slot,
+        changes: StateChanges,
+        staking_history_changes: StakingHistoryChanges,
+    ) {
+        self._finalize(slot, changes, staking_history_changes)
+            .unwrap()
     }
 
     fn get_execution_trail_hash(&self) -> Hash {
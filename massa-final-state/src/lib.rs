@@ -83,6 +83,12 @@
 //! Backups for `Slot {period, thread}` are stored in `massa > massa-node > storage > ledger > rocks_db_backup > backup_[period]_[thread]`
 //! Backups are hard links of the rocks_db, so the overhead of storing them should be minimal.
 //! To recover from a backup, simply replace the contents of the rocks_db folder by the contents of the target backup folder.
+//!
+//! ### Forks
+//!
+//! A backup of a real network can also be used to start a local single-node network with realistic data:
+//! `FinalState::new_forked_from_snapshot` applies a `StateFork` (balances, bytecodes, roll counts and stakers)
+//! to the snapshot before restarting from it. See the `--fork` option of the node.
 
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
@@ -93,6 +99,7 @@ mod error;
 mod final_state;
 mod mapping_grpc;
 mod state_changes;
+mod state_fork;
 
 pub use config::FinalStateConfig;
 pub use controller_trait::FinalStateController;
@@ -100,6 +107,7 @@ pub use error::FinalStateError;
 pub use final_state::FinalState;
 use num as _;
 pub use state_changes::{StateChanges, StateChangesDeserializer, StateChangesSerializer};
+pub use state_fork::StateFork;
 
 #[cfg(feature = "test-exports")]
 pub use controller_trait::MockFinalStateController;
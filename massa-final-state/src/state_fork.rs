@@ -0,0 +1,21 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This file defines the modifications applied to a final state snapshot
+//! to fork a local network from it.
+
+use massa_models::{address::Address, amount::Amount, bytecode::Bytecode};
+use std::collections::BTreeMap;
+
+/// Modifications applied to a final state snapshot before starting a local network on top of it
+#[derive(Debug, Default, Clone)]
+pub struct StateFork {
+    /// balances to set, creating the ledger entries that do not exist
+    pub balances: BTreeMap<Address, Amount>,
+    /// bytecodes to set, creating the ledger entries that do not exist
+    pub bytecodes: BTreeMap<Address, Bytecode>,
+    /// roll counts to set in the whole cycle history
+    pub rolls: BTreeMap<Address, u64>,
+    /// if true, the rolls of the addresses absent from `rolls` are removed,
+    /// so that only the addresses of `rolls` are selected to produce blocks and endorsements
+    pub reset_stakers: bool,
+}
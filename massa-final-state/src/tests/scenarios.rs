@@ -3,10 +3,12 @@
 use crate::controller_trait::FinalStateController;
 use crate::{
     /*test_exports::{assert_eq_final_state, assert_eq_final_state_hash},*/
-    FinalState, FinalStateConfig, StateChanges,
+    FinalState, FinalStateConfig, StateChanges, StateFork,
 };
 use massa_async_pool::{AsyncMessage, AsyncPoolChanges, AsyncPoolConfig};
-use massa_db_exports::{DBBatch, MassaDBConfig, MassaDBController};
+use massa_db_exports::{
+    DBBatch, MassaDBConfig, MassaDBController, INDEXES_CF, LEDGER_MERKLE_PREFIX,
+};
 use massa_db_worker::MassaDB;
 use massa_executed_ops::{ExecutedDenunciationsConfig, ExecutedOpsConfig};
 use massa_ledger_exports::{
@@ -23,7 +25,7 @@ use massa_models::config::{
     MAX_PARAMETERS_SIZE, MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH, POS_SAVED_CYCLES, T0,
 };
 use massa_models::{config::MAX_DATASTORE_VALUE_LENGTH, slot::Slot};
-use massa_pos_exports::{PoSConfig, SelectorConfig};
+use massa_pos_exports::{PoSConfig, SelectorChannels, SelectorConfig};
 use massa_pos_worker::start_selector_worker;
 use parking_lot::RwLock;
 use std::collections::BTreeMap;
@@ -102,8 +104,13 @@ fn create_final_state(temp_dir: &TempDir, reset_final_state: bool) -> Arc<RwLock
     };
 
     // start proof-of-stake selectors
-    let (mut _selector_manager, selector_controller) = start_selector_worker(selector_local_config)
-        .expect("could not start server selector controller");
+    let (mut _selector_manager, selector_controller) = start_selector_worker(
+        selector_local_config,
+        SelectorChannels {
+            cycle_draws_sender: tokio::sync::broadcast::channel(10).0,
+        },
+    )
+    .expect("could not start server selector controller");
 
     // MIP store
     let mip_store = MipStore::try_from((
@@ -208,7 +215,7 @@ fn test_final_state() {
         );
         state_changes.ledger_changes = ledger_changes;
 
-        fs.write().finalize(slot, state_changes);
+        fs.write().finalize(slot, state_changes, Default::default());
 
         hash = fs.read().db.read().get_xof_db_hash();
 
@@ -222,3 +229,59 @@ fn test_final_state() {
 
     assert_eq!(hash, hash2);
 }
+
+#[test]
+fn test_state_fork() {
+    let temp_dir = TempDir::new().unwrap();
+    let fs = create_final_state(&temp_dir, true);
+
+    let mut batch = DBBatch::new();
+    fs.write().pos_state.create_initial_cycle(&mut batch);
+    let slot = fs.read().db.read().get_change_id().unwrap();
+    fs.write()
+        .db
+        .write()
+        .write_batch(batch, DBBatch::new(), Some(slot));
+    assert!(fs.read().pos_state.get_all_roll_counts(0).len() > 1);
+    fs.read().ledger.rebuild_merkle_tree();
+    let (_, root_before_fork) = fs.read().ledger.get_merkle_root().unwrap();
+
+    let funded =
+        Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let contract =
+        Address::from_str("AU12htxRWiEm8jDJpJptr6cwEhWNcCSFWstN1MLSa96DDkVM9Y42G").unwrap();
+    let mut fork = StateFork {
+        reset_stakers: true,
+        ..Default::default()
+    };
+    fork.balances
+        .insert(funded, Amount::from_str("1000").unwrap());
+    fork.bytecodes.insert(contract, Bytecode(vec![1, 2, 3]));
+    fork.rolls.insert(funded, 5);
+    fs.write().apply_state_fork(&fork).unwrap();
+
+    let fs = fs.read();
+    assert_eq!(fs.db.read().get_change_id().unwrap(), slot);
+    assert_eq!(
+        fs.ledger.get_balance(&funded),
+        Some(Amount::from_str("1000").unwrap())
+    );
+    assert_eq!(
+        fs.ledger.get_bytecode(&contract),
+        Some(Bytecode(vec![1, 2, 3]))
+    );
+    assert_eq!(
+        fs.pos_state.get_all_roll_counts(0),
+        BTreeMap::from([(funded, 5)])
+    );
+
+    // the Merkle tree follows the fork: it matches a tree rebuilt from the forked ledger
+    let (tree_slot, root) = fs.ledger.get_merkle_root().unwrap();
+    assert_eq!(tree_slot, slot);
+    assert_ne!(root, root_before_fork);
+    fs.db
+        .write()
+        .delete_prefix(LEDGER_MERKLE_PREFIX, INDEXES_CF, None);
+    fs.ledger.rebuild_merkle_tree();
+    assert_eq!(fs.ledger.get_merkle_root(), Some((slot, root)));
+}
@@ -1,7 +1,7 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
 use massa_signature::KeyPair;
-use massa_time::MassaTime;
+use massa_time::{MassaTime, SharedClock};
 use serde::Deserialize;
 use std::{net::SocketAddr, path::PathBuf, time::Duration};
 
@@ -103,6 +103,9 @@ pub struct GrpcConfig {
     pub draw_lookahead_period_count: u64,
     /// last_start_period of the network, used to deserialize blocks
     pub last_start_period: u64,
+    /// clock giving the current slot, fast-forwarded on a devnet
+    #[serde(skip)]
+    pub clock: SharedClock,
     /// max denunciations in block header
     pub max_denunciations_per_block_header: u32,
     /// max number of addresses that can be included in a single request
@@ -15,7 +15,6 @@ use massa_proto_rs::massa::api::v1 as grpc_api;
 use massa_proto_rs::massa::model::v1 as grpc_model;
 use massa_protocol_exports::{PeerConnectionType, PeerId};
 use massa_signature::KeyPair;
-use massa_time::MassaTime;
 use tracing::warn;
 // use massa_proto_rs::massa::model::v1 "add_to_bootstrap_blacklist"as grpc_model;
 
@@ -228,7 +227,7 @@ pub(crate) fn get_node_status(
     _request: tonic::Request<grpc_api::GetNodeStatusRequest>,
 ) -> Result<grpc_api::GetNodeStatusResponse, GrpcError> {
     let config = CompactConfig::default();
-    let now = MassaTime::now();
+    let now = grpc.grpc_config.clock.now();
     let last_slot = get_latest_block_slot_at_timestamp(
         grpc.grpc_config.thread_count,
         grpc.grpc_config.t0,
@@ -9,7 +9,6 @@ use massa_models::timeslots::get_latest_block_slot_at_timestamp;
 use massa_proto_rs::massa::api::v1 as grpc_api;
 use massa_proto_rs::massa::model::v1 as grpc_model;
 use massa_serialization::{DeserializeError, Deserializer};
-use massa_time::MassaTime;
 use std::collections::HashMap;
 use std::io::ErrorKind;
 use std::pin::Pin;
@@ -55,7 +54,7 @@ pub(crate) async fn send_operations(
                         )
                         .await;
                     } else {
-                        let now = MassaTime::now();
+                        let now = config.clock.now();
                         let Ok(last_slot) = get_latest_block_slot_at_timestamp(
                             config.thread_count,
                             config.t0,
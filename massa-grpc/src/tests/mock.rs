@@ -19,7 +19,7 @@ use massa_pool_exports::{MockPoolController, PoolBroadcasts};
 use massa_pos_exports::MockSelectorController;
 use massa_protocol_exports::{MockProtocolController, ProtocolConfig};
 use massa_signature::KeyPair;
-use massa_time::MassaTime;
+use massa_time::{MassaTime, SharedClock};
 use massa_versioning::keypair_factory::KeyPairFactory;
 use massa_versioning::versioning::{MipStatsConfig, MipStore};
 // use massa_wallet::test_exports::create_test_wallet;
@@ -93,6 +93,7 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
         max_channel_size: 128,
         draw_lookahead_period_count: 10,
         last_start_period: 0,
+        clock: SharedClock::default(),
         max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
         max_addresses_per_request: 50,
         max_slot_ranges_per_request: 50,
@@ -126,6 +127,7 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
         execution_controller: execution_ctrl,
         execution_channels: ExecutionChannels {
             slot_execution_output_sender,
+            async_message_event_sender: tokio::sync::broadcast::channel(100).0,
         },
         pool_broadcasts: PoolBroadcasts {
             endorsement_sender,
@@ -351,9 +351,12 @@ async fn execute_read_only_call() {
                     block_info: None,
                     state_changes: massa_final_state::StateChanges::default(),
                     events: EventStore::default(),
+                    staking_history_changes: Default::default(),
+                    async_message_events: Default::default(),
                 },
                 gas_cost: 100,
                 call_result: "toto".as_bytes().to_vec(),
+                previous_values: Default::default(),
             })
         });
 
@@ -1067,6 +1067,8 @@ async fn new_slot_execution_outputs() {
         block_info: None,
         state_changes: massa_final_state::StateChanges::default(),
         events: Default::default(),
+        staking_history_changes: Default::default(),
+        async_message_events: Default::default(),
     };
 
     let (tx_request, rx) = tokio::sync::mpsc::channel(10);
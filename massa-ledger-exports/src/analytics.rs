@@ -0,0 +1,230 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This file defines the aggregates maintained by the node local ledger analytics index
+
+use massa_models::amount::{Amount, AmountDeserializer, AmountSerializer};
+use massa_serialization::{
+    Deserializer, SerializeError, Serializer, U64VarIntDeserializer, U64VarIntSerializer,
+};
+use nom::error::{context, ContextError, ParseError};
+use nom::sequence::tuple;
+use nom::{IResult, Parser};
+use serde::{Deserialize, Serialize};
+use std::ops::Bound::Included;
+
+/// Analytics of a single entry of the final ledger
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+pub struct LedgerEntryAnalytics {
+    /// Balance of the entry
+    pub balance: Amount,
+    /// Number of entries in the datastore
+    pub datastore_entry_count: u64,
+    /// Cumulated size in bytes of the keys and values of the datastore
+    pub datastore_size: u64,
+}
+
+/// Analytics of the whole final ledger
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+pub struct LedgerAnalyticsTotals {
+    /// Number of entries in the ledger
+    pub address_count: u64,
+    /// Sum of the balances of all the entries
+    pub total_balance: Amount,
+    /// Number of datastore entries of all the ledger entries
+    pub datastore_entry_count: u64,
+    /// Cumulated size in bytes of the keys and values of all the datastores
+    pub datastore_size: u64,
+}
+
+/// Serializer for `LedgerEntryAnalytics`
+pub struct LedgerEntryAnalyticsSerializer {
+    amount_serializer: AmountSerializer,
+    u64_serializer: U64VarIntSerializer,
+}
+
+impl LedgerEntryAnalyticsSerializer {
+    /// Creates a new `LedgerEntryAnalyticsSerializer`
+    pub fn new() -> Self {
+        Self {
+            amount_serializer: AmountSerializer::new(),
+            u64_serializer: U64VarIntSerializer::new(),
+        }
+    }
+}
+
+impl Default for LedgerEntryAnalyticsSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serializer<LedgerEntryAnalytics> for LedgerEntryAnalyticsSerializer {
+    fn serialize(
+        &self,
+        value: &LedgerEntryAnalytics,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        self.amount_serializer.serialize(&value.balance, buffer)?;
+        self.u64_serializer
+            .serialize(&value.datastore_entry_count, buffer)?;
+        self.u64_serializer
+            .serialize(&value.datastore_size, buffer)?;
+        Ok(())
+    }
+}
+
+/// Deserializer for `LedgerEntryAnalytics`
+pub struct LedgerEntryAnalyticsDeserializer {
+    amount_deserializer: AmountDeserializer,
+    u64_deserializer: U64VarIntDeserializer,
+}
+
+impl LedgerEntryAnalyticsDeserializer {
+    /// Creates a new `LedgerEntryAnalyticsDeserializer`
+    pub fn new() -> Self {
+        Self {
+            amount_deserializer: AmountDeserializer::new(
+                Included(Amount::MIN),
+                Included(Amount::MAX),
+            ),
+            u64_deserializer: U64VarIntDeserializer::new(Included(u64::MIN), Included(u64::MAX)),
+        }
+    }
+}
+
+impl Default for LedgerEntryAnalyticsDeserializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deserializer<LedgerEntryAnalytics> for LedgerEntryAnalyticsDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], LedgerEntryAnalytics, E> {
+        context(
+            "Failed LedgerEntryAnalytics deserialization",
+            tuple((
+                context("Failed balance deserialization", |input| {
+                    self.amount_deserializer.deserialize(input)
+                }),
+                context("Failed datastore_entry_count deserialization", |input| {
+                    self.u64_deserializer.deserialize(input)
+                }),
+                context("Failed datastore_size deserialization", |input| {
+                    self.u64_deserializer.deserialize(input)
+                }),
+            )),
+        )
+        .map(
+            |(balance, datastore_entry_count, datastore_size)| LedgerEntryAnalytics {
+                balance,
+                datastore_entry_count,
+                datastore_size,
+            },
+        )
+        .parse(buffer)
+    }
+}
+
+/// Serializer for `LedgerAnalyticsTotals`
+pub struct LedgerAnalyticsTotalsSerializer {
+    amount_serializer: AmountSerializer,
+    u64_serializer: U64VarIntSerializer,
+}
+
+impl LedgerAnalyticsTotalsSerializer {
+    /// Creates a new `LedgerAnalyticsTotalsSerializer`
+    pub fn new() -> Self {
+        Self {
+            amount_serializer: AmountSerializer::new(),
+            u64_serializer: U64VarIntSerializer::new(),
+        }
+    }
+}
+
+impl Default for LedgerAnalyticsTotalsSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serializer<LedgerAnalyticsTotals> for LedgerAnalyticsTotalsSerializer {
+    fn serialize(
+        &self,
+        value: &LedgerAnalyticsTotals,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        self.u64_serializer
+            .serialize(&value.address_count, buffer)?;
+        self.amount_serializer
+            .serialize(&value.total_balance, buffer)?;
+        self.u64_serializer
+            .serialize(&value.datastore_entry_count, buffer)?;
+        self.u64_serializer
+            .serialize(&value.datastore_size, buffer)?;
+        Ok(())
+    }
+}
+
+/// Deserializer for `LedgerAnalyticsTotals`
+pub struct LedgerAnalyticsTotalsDeserializer {
+    amount_deserializer: AmountDeserializer,
+    u64_deserializer: U64VarIntDeserializer,
+}
+
+impl LedgerAnalyticsTotalsDeserializer {
+    /// Creates a new `LedgerAnalyticsTotalsDeserializer`
+    pub fn new() -> Self {
+        Self {
+            amount_deserializer: AmountDeserializer::new(
+                Included(Amount::MIN),
+                Included(Amount::MAX),
+            ),
+            u64_deserializer: U64VarIntDeserializer::new(Included(u64::MIN), Included(u64::MAX)),
+        }
+    }
+}
+
+impl Default for LedgerAnalyticsTotalsDeserializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deserializer<LedgerAnalyticsTotals> for LedgerAnalyticsTotalsDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], LedgerAnalyticsTotals, E> {
+        context(
+            "Failed LedgerAnalyticsTotals deserialization",
+            tuple((
+                context("Failed address_count deserialization", |input| {
+                    self.u64_deserializer.deserialize(input)
+                }),
+                context("Failed total_balance deserialization", |input| {
+                    self.amount_deserializer.deserialize(input)
+                }),
+                context("Failed datastore_entry_count deserialization", |input| {
+                    self.u64_deserializer.deserialize(input)
+                }),
+                context("Failed datastore_size deserialization", |input| {
+                    self.u64_deserializer.deserialize(input)
+                }),
+            )),
+        )
+        .map(
+            |(address_count, total_balance, datastore_entry_count, datastore_size)| {
+                LedgerAnalyticsTotals {
+                    address_count,
+                    total_balance,
+                    datastore_entry_count,
+                    datastore_size,
+                }
+            },
+        )
+        .parse(buffer)
+    }
+}
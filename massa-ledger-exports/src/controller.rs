@@ -1,7 +1,14 @@
-use massa_models::{address::Address, amount::Amount, bytecode::Bytecode};
+use massa_hash::Hash;
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    bytecode::Bytecode,
+    ledger_tree::{LedgerTreeItem, LedgerTreePaths},
+    slot::Slot,
+};
 use std::collections::BTreeSet;
 
-use crate::{LedgerChanges, LedgerError};
+use crate::{LedgerAnalyticsTotals, LedgerChanges, LedgerEntryAnalytics, LedgerError};
 use massa_db_exports::DBBatch;
 
 #[cfg(feature = "test-exports")]
@@ -56,6 +63,52 @@ pub trait LedgerController: Send + Sync {
     /// Deserializes the key and value, useful after bootstrap
     fn is_key_value_valid(&self, serialized_key: &[u8], serialized_value: &[u8]) -> bool;
 
+    /// Updates the node local analytics index with the changes of a final slot.
+    ///
+    /// Must be called before the changes are applied to the ledger, as the previous values are read from it.
+    fn apply_analytics_changes(&self, changes: &LedgerChanges, slot: &Slot);
+
+    /// Rebuilds the node local analytics index from the whole ledger, if it does not match the final ledger
+    fn rebuild_analytics(&self);
+
+    /// Gets the final slot the analytics index matches
+    ///
+    /// # Returns
+    /// None if the index was never built
+    fn get_analytics_slot(&self) -> Option<Slot>;
+
+    /// Gets the analytics of the whole ledger
+    fn get_analytics_totals(&self) -> LedgerAnalyticsTotals;
+
+    /// Gets the analytics of a ledger entry
+    ///
+    /// # Returns
+    /// The analytics, or None if the ledger entry was not found in the index
+    fn get_entry_analytics(&self, addr: &Address) -> Option<LedgerEntryAnalytics>;
+
+    /// Gets the `count` addresses with the highest balances, sorted by decreasing balance
+    fn get_rich_list(&self, count: usize) -> Vec<(Address, Amount)>;
+
+    /// Updates the node local Merkle tree of the ledger with the changes of a final slot.
+    ///
+    /// Must be called before the changes are applied to the ledger, as the deleted datastore keys are read from it.
+    fn apply_merkle_changes(&self, changes: &LedgerChanges, slot: &Slot);
+
+    /// Rebuilds the node local Merkle tree from the whole ledger, if it does not match the final ledger
+    fn rebuild_merkle_tree(&self);
+
+    /// Gets the final slot the Merkle tree matches and its root
+    ///
+    /// # Returns
+    /// None if the tree was never built
+    fn get_merkle_root(&self) -> Option<(Slot, Hash)>;
+
+    /// Gets the values of ledger items with their paths in the node local Merkle tree
+    ///
+    /// # Returns
+    /// None if the tree does not match the final ledger, for example while it is being updated
+    fn get_tree_paths(&self, items: &[(Address, LedgerTreeItem)]) -> Option<LedgerTreePaths>;
+
     /// Get every address and their corresponding balance.
     ///
     /// IMPORTANT: This should only be used for debug and test purposes.
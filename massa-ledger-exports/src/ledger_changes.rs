@@ -865,6 +865,34 @@ impl LedgerChanges {
         }
     }
 
+    /// Tries to return whether the ledger changes modify a datastore entry of a given address
+    /// whose key starts with a given prefix.
+    ///
+    /// # Arguments
+    /// * `addr`: target address
+    /// * `prefix`: datastore key prefix
+    ///
+    /// # Returns
+    /// * true if a datastore key starting with `prefix` is set or deleted for the address in the ledger changes
+    pub fn has_prefix_changes(&self, addr: &Address, prefix: &[u8]) -> bool {
+        match self.0.get(addr) {
+            // This ledger entry is being replaced by a new one:
+            // check if the new ledger entry has a datastore entry matching the prefix
+            Some(SetUpdateOrDelete::Set(v)) => v.datastore.keys().any(|k| k.starts_with(prefix)),
+
+            // This ledger entry is being updated: check the datastore entries being updated
+            Some(SetUpdateOrDelete::Update(LedgerEntryUpdate { datastore, .. })) => {
+                datastore.keys().any(|k| k.starts_with(prefix))
+            }
+
+            // This ledger entry is being deleted: return true
+            Some(SetUpdateOrDelete::Delete) => true,
+
+            // This ledger entry is not being changed.
+            None => false,
+        }
+    }
+
     /// Tries to return whether a datastore entry exists for a given address,
     /// or gets it from a function if the datastore entry's status is unknown.
     ///
@@ -2,6 +2,7 @@
 //!
 //! TODO
 
+mod analytics;
 mod config;
 mod controller;
 mod error;
@@ -11,6 +12,10 @@ mod ledger_entry;
 mod mapping_grpc;
 mod types;
 
+pub use analytics::{
+    LedgerAnalyticsTotals, LedgerAnalyticsTotalsDeserializer, LedgerAnalyticsTotalsSerializer,
+    LedgerEntryAnalytics, LedgerEntryAnalyticsDeserializer, LedgerEntryAnalyticsSerializer,
+};
 pub use config::LedgerConfig;
 pub use controller::LedgerController;
 pub use error::LedgerError;
@@ -4,13 +4,17 @@
 
 use crate::ledger_db::{LedgerDB, LedgerSubEntry};
 use massa_db_exports::{DBBatch, ShareableMassaDBController};
+use massa_hash::Hash;
 use massa_ledger_exports::{
-    LedgerChanges, LedgerConfig, LedgerController, LedgerEntry, LedgerError,
+    LedgerAnalyticsTotals, LedgerChanges, LedgerConfig, LedgerController, LedgerEntry,
+    LedgerEntryAnalytics, LedgerError,
 };
 use massa_models::{
     address::Address,
     amount::{Amount, AmountDeserializer},
     bytecode::{Bytecode, BytecodeDeserializer},
+    ledger_tree::{LedgerTreeItem, LedgerTreePaths},
+    slot::Slot,
 };
 use massa_serialization::{DeserializeError, Deserializer};
 use std::collections::{BTreeSet, HashMap};
@@ -161,6 +165,56 @@ impl LedgerController for FinalLedger {
             .is_key_value_valid(serialized_key, serialized_value)
     }
 
+    /// Updates the node local analytics index with the changes of a final slot
+    fn apply_analytics_changes(&self, changes: &LedgerChanges, slot: &Slot) {
+        self.sorted_ledger.apply_analytics_changes(changes, slot);
+    }
+
+    /// Rebuilds the node local analytics index from the whole ledger, if it does not match the final ledger
+    fn rebuild_analytics(&self) {
+        self.sorted_ledger.rebuild_analytics();
+    }
+
+    /// Gets the final slot the analytics index matches
+    fn get_analytics_slot(&self) -> Option<Slot> {
+        self.sorted_ledger.get_analytics_slot()
+    }
+
+    /// Gets the analytics of the whole ledger
+    fn get_analytics_totals(&self) -> LedgerAnalyticsTotals {
+        self.sorted_ledger.get_analytics_totals()
+    }
+
+    /// Gets the analytics of a ledger entry
+    fn get_entry_analytics(&self, addr: &Address) -> Option<LedgerEntryAnalytics> {
+        self.sorted_ledger.get_entry_analytics(addr)
+    }
+
+    /// Gets the `count` addresses with the highest balances, sorted by decreasing balance
+    fn get_rich_list(&self, count: usize) -> Vec<(Address, Amount)> {
+        self.sorted_ledger.get_rich_list(count)
+    }
+
+    /// Updates the node local Merkle tree of the ledger with the changes of a final slot
+    fn apply_merkle_changes(&self, changes: &LedgerChanges, slot: &Slot) {
+        self.sorted_ledger.apply_merkle_changes(changes, slot);
+    }
+
+    /// Rebuilds the node local Merkle tree from the whole ledger, if it does not match the final ledger
+    fn rebuild_merkle_tree(&self) {
+        self.sorted_ledger.rebuild_merkle_tree();
+    }
+
+    /// Gets the final slot the Merkle tree matches and its root
+    fn get_merkle_root(&self) -> Option<(Slot, Hash)> {
+        self.sorted_ledger.get_merkle_root()
+    }
+
+    /// Gets the values of ledger items with their paths in the node local Merkle tree
+    fn get_tree_paths(&self, items: &[(Address, LedgerTreeItem)]) -> Option<LedgerTreePaths> {
+        self.sorted_ledger.get_tree_paths(items)
+    }
+
     /// Get every address and their corresponding balance.
     ///
     /// IMPORTANT: This should only be used for debug and test purposes.
@@ -3,25 +3,38 @@
 //! Module to interact with the disk ledger
 
 use massa_db_exports::{
-    DBBatch, MassaDirection, MassaIteratorMode, ShareableMassaDBController, CRUD_ERROR,
-    KEY_SER_ERROR, LEDGER_PREFIX, STATE_CF,
+    DBBatch, MassaDBController, MassaDirection, MassaIteratorMode, ShareableMassaDBController,
+    CRUD_ERROR, INDEXES_CF, KEY_DESER_ERROR, KEY_SER_ERROR, LEDGER_ANALYTICS_DESER_ERROR,
+    LEDGER_ANALYTICS_PREFIX, LEDGER_ANALYTICS_SER_ERROR, LEDGER_MERKLE_DESER_ERROR,
+    LEDGER_MERKLE_PREFIX, LEDGER_MERKLE_SER_ERROR, LEDGER_PREFIX, STATE_CF,
 };
+use massa_hash::Hash;
 use massa_ledger_exports::*;
 use massa_models::amount::AmountDeserializer;
 use massa_models::bytecode::BytecodeDeserializer;
 use massa_models::datastore::get_prefix_bounds;
 use massa_models::{
-    address::Address, amount::AmountSerializer, bytecode::BytecodeSerializer, slot::Slot,
+    address::{Address, AddressDeserializer, AddressSerializer},
+    amount::AmountSerializer,
+    bytecode::{Bytecode, BytecodeSerializer},
+    ledger_tree::{
+        key_bit, LedgerTreeItem, LedgerTreePaths, SparseMerkleNode, SparseMerkleNodeDeserializer,
+        SparseMerkleNodeSerializer, SparseMerklePath,
+    },
+    slot::Slot,
 };
 use massa_serialization::{
     DeserializeError, Deserializer, Serializer, U64VarIntDeserializer, U64VarIntSerializer,
 };
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt::Debug;
 
 use massa_models::amount::Amount;
 use std::ops::Bound;
 
+/// Number of ledger items inserted in the Merkle tree between two writes of its nodes, when it is rebuilt
+const MERKLE_REBUILD_CHUNK_SIZE: usize = 10_000;
+
 /// Ledger sub entry enum
 pub enum LedgerSubEntry {
     /// Version
@@ -61,6 +74,14 @@ pub struct LedgerDB {
     bytecode_deserializer: BytecodeDeserializer,
     max_datastore_value_length: u64,
     max_datastore_key_length: u8,
+    address_serializer: AddressSerializer,
+    address_deserializer: AddressDeserializer,
+    entry_analytics_serializer: LedgerEntryAnalyticsSerializer,
+    entry_analytics_deserializer: LedgerEntryAnalyticsDeserializer,
+    analytics_totals_serializer: LedgerAnalyticsTotalsSerializer,
+    analytics_totals_deserializer: LedgerAnalyticsTotalsDeserializer,
+    merkle_node_serializer: SparseMerkleNodeSerializer,
+    merkle_node_deserializer: SparseMerkleNodeDeserializer,
 }
 
 impl Debug for LedgerDB {
@@ -100,6 +121,14 @@ impl LedgerDB {
             ),
             max_datastore_value_length,
             max_datastore_key_length,
+            address_serializer: AddressSerializer::new(),
+            address_deserializer: AddressDeserializer::new(),
+            entry_analytics_serializer: LedgerEntryAnalyticsSerializer::new(),
+            entry_analytics_deserializer: LedgerEntryAnalyticsDeserializer::new(),
+            analytics_totals_serializer: LedgerAnalyticsTotalsSerializer::new(),
+            analytics_totals_deserializer: LedgerAnalyticsTotalsDeserializer::new(),
+            merkle_node_serializer: SparseMerkleNodeSerializer::new(),
+            merkle_node_deserializer: SparseMerkleNodeDeserializer::new(),
         }
     }
 
@@ -471,6 +500,789 @@ impl LedgerDB {
     }
 }
 
+// Analytics index
+//
+// The analytics are node local indexes: they are written directly to `INDEXES_CF`,
+// outside of the state hash and of the change history.
+// Keys are `LEDGER_ANALYTICS_PREFIX` followed by:
+// * `s` for the final slot the index matches
+// * `t` for the totals of the ledger
+// * `e` and the address for the analytics of an entry
+// * `r`, the complement of the raw balance in big endian and the address for the rich list,
+//   so that iterating over the rich list yields the addresses by decreasing balance
+impl LedgerDB {
+    /// Updates the analytics index with the changes of a final slot.
+    ///
+    /// Must be called before the changes are applied to the ledger, as the previous datastore values are read from it.
+    pub fn apply_analytics_changes(&self, changes: &LedgerChanges, slot: &Slot) {
+        let mut totals = self.get_analytics_totals();
+        let mut updates = Vec::with_capacity(changes.0.len());
+        for (addr, change) in changes.0.iter() {
+            let previous = self.get_entry_analytics(addr);
+            let next = match change {
+                // `put_entry` does not delete the datastore entries absent from the new entry,
+                // so the datastore of the new entry is accounted like an update of the previous one
+                SetUpdateOrDelete::Set(entry) => {
+                    let mut analytics = previous.unwrap_or_default();
+                    analytics.balance = entry.balance;
+                    for (key, value) in entry.datastore.iter() {
+                        self.account_datastore_write(
+                            addr,
+                            key,
+                            Some(value),
+                            previous.is_some(),
+                            &mut analytics,
+                        );
+                    }
+                    Some(analytics)
+                }
+                SetUpdateOrDelete::Update(entry_update) => {
+                    let mut analytics = previous.unwrap_or_default();
+                    if let SetOrKeep::Set(balance) = entry_update.balance {
+                        analytics.balance = balance;
+                    }
+                    for (key, update) in entry_update.datastore.iter() {
+                        let value = match update {
+                            SetOrDelete::Set(value) => Some(value),
+                            SetOrDelete::Delete => None,
+                        };
+                        self.account_datastore_write(
+                            addr,
+                            key,
+                            value,
+                            previous.is_some(),
+                            &mut analytics,
+                        );
+                    }
+                    Some(analytics)
+                }
+                SetUpdateOrDelete::Delete => None,
+            };
+
+            if let Some(previous) = previous {
+                totals.address_count = totals.address_count.saturating_sub(1);
+                totals.total_balance = totals.total_balance.saturating_sub(previous.balance);
+                totals.datastore_entry_count = totals
+                    .datastore_entry_count
+                    .saturating_sub(previous.datastore_entry_count);
+                totals.datastore_size = totals
+                    .datastore_size
+                    .saturating_sub(previous.datastore_size);
+            }
+            if let Some(next) = next {
+                totals.address_count = totals.address_count.saturating_add(1);
+                totals.total_balance = totals.total_balance.saturating_add(next.balance);
+                totals.datastore_entry_count = totals
+                    .datastore_entry_count
+                    .saturating_add(next.datastore_entry_count);
+                totals.datastore_size = totals.datastore_size.saturating_add(next.datastore_size);
+            }
+            updates.push((*addr, previous, next));
+        }
+
+        let mut batch = DBBatch::new();
+        {
+            let db = self.db.read();
+            for (addr, previous, next) in updates {
+                if let Some(previous) = previous {
+                    db.delete_key(&mut batch, self.rich_list_key(&addr, &previous.balance));
+                }
+                match next {
+                    Some(next) => self.put_entry_analytics(&**db, &addr, &next, &mut batch),
+                    None => db.delete_key(&mut batch, self.entry_analytics_key(&addr)),
+                }
+            }
+            self.put_analytics_totals(&**db, &totals, &mut batch);
+            db.put_or_update_entry_value(
+                &mut batch,
+                [LEDGER_ANALYTICS_PREFIX.as_bytes(), b"s"].concat(),
+                &slot.to_bytes_key(),
+            );
+        }
+        self.db.write().write_index_batch(batch);
+    }
+
+    /// Rebuilds the analytics index from the whole ledger, if it does not match the final ledger
+    pub fn rebuild_analytics(&self) {
+        let final_slot = self.db.read().get_change_id().ok();
+        if final_slot.is_some() && self.get_analytics_slot() == final_slot {
+            return;
+        }
+        self.db
+            .write()
+            .delete_prefix(LEDGER_ANALYTICS_PREFIX, INDEXES_CF, None);
+
+        let mut totals = LedgerAnalyticsTotals::default();
+        let mut batch = DBBatch::new();
+        {
+            let db = self.db.read();
+            let mut current: Option<(Address, LedgerEntryAnalytics)> = None;
+            for (serialized_key, serialized_value) in
+                db.prefix_iterator_cf(STATE_CF, LEDGER_PREFIX.as_bytes())
+            {
+                if !serialized_key.starts_with(LEDGER_PREFIX.as_bytes()) {
+                    break;
+                }
+                let (_, key) = self
+                    .key_deserializer_db
+                    .deserialize::<DeserializeError>(&serialized_key)
+                    .expect(KEY_DESER_ERROR);
+                if current.as_ref().map(|(addr, _)| addr) != Some(&key.address) {
+                    if let Some((addr, analytics)) = current.take() {
+                        self.account_rebuilt_entry(
+                            &**db,
+                            &addr,
+                            &analytics,
+                            &mut totals,
+                            &mut batch,
+                        );
+                    }
+                    current = Some((key.address, LedgerEntryAnalytics::default()));
+                }
+                let Some((_, analytics)) = current.as_mut() else {
+                    continue;
+                };
+                match key.key_type {
+                    KeyType::BALANCE => {
+                        let (_, balance) = self
+                            .amount_deserializer
+                            .deserialize::<DeserializeError>(&serialized_value)
+                            .expect(LEDGER_ANALYTICS_DESER_ERROR);
+                        analytics.balance = balance;
+                    }
+                    KeyType::DATASTORE(datastore_key) => {
+                        analytics.datastore_entry_count += 1;
+                        analytics.datastore_size +=
+                            (datastore_key.len() + serialized_value.len()) as u64;
+                    }
+                    KeyType::VERSION | KeyType::BYTECODE => {}
+                }
+            }
+            if let Some((addr, analytics)) = current.take() {
+                self.account_rebuilt_entry(&**db, &addr, &analytics, &mut totals, &mut batch);
+            }
+            self.put_analytics_totals(&**db, &totals, &mut batch);
+            if let Some(final_slot) = final_slot {
+                db.put_or_update_entry_value(
+                    &mut batch,
+                    [LEDGER_ANALYTICS_PREFIX.as_bytes(), b"s"].concat(),
+                    &final_slot.to_bytes_key(),
+                );
+            }
+        }
+        self.db.write().write_index_batch(batch);
+    }
+
+    /// Gets the final slot the analytics index matches
+    pub fn get_analytics_slot(&self) -> Option<Slot> {
+        let serialized_slot = self
+            .db
+            .read()
+            .get_cf(
+                INDEXES_CF,
+                [LEDGER_ANALYTICS_PREFIX.as_bytes(), b"s"].concat(),
+            )
+            .expect(CRUD_ERROR)?;
+        Some(Slot::from_bytes_key(
+            serialized_slot
+                .as_slice()
+                .try_into()
+                .expect(LEDGER_ANALYTICS_DESER_ERROR),
+        ))
+    }
+
+    /// Gets the analytics of the whole ledger
+    pub fn get_analytics_totals(&self) -> LedgerAnalyticsTotals {
+        let key = [LEDGER_ANALYTICS_PREFIX.as_bytes(), b"t"].concat();
+        match self.db.read().get_cf(INDEXES_CF, key).expect(CRUD_ERROR) {
+            Some(serialized_totals) => {
+                self.analytics_totals_deserializer
+                    .deserialize::<DeserializeError>(&serialized_totals)
+                    .expect(LEDGER_ANALYTICS_DESER_ERROR)
+                    .1
+            }
+            None => LedgerAnalyticsTotals::default(),
+        }
+    }
+
+    /// Gets the analytics of a ledger entry
+    pub fn get_entry_analytics(&self, addr: &Address) -> Option<LedgerEntryAnalytics> {
+        let serialized_analytics = self
+            .db
+            .read()
+            .get_cf(INDEXES_CF, self.entry_analytics_key(addr))
+            .expect(CRUD_ERROR)?;
+        Some(
+            self.entry_analytics_deserializer
+                .deserialize::<DeserializeError>(&serialized_analytics)
+                .expect(LEDGER_ANALYTICS_DESER_ERROR)
+                .1,
+        )
+    }
+
+    /// Gets the `count` addresses with the highest balances, sorted by decreasing balance
+    pub fn get_rich_list(&self, count: usize) -> Vec<(Address, Amount)> {
+        let prefix = [LEDGER_ANALYTICS_PREFIX.as_bytes(), b"r"].concat();
+        let db = self.db.read();
+        db.prefix_iterator_cf(INDEXES_CF, &prefix)
+            .take_while(|(key, _)| key.starts_with(&prefix))
+            .take(count)
+            .map(|(key, _)| {
+                let (raw_balance, serialized_address) = key[prefix.len()..].split_at(8);
+                let raw_balance = u64::MAX
+                    - u64::from_be_bytes(
+                        raw_balance.try_into().expect(LEDGER_ANALYTICS_DESER_ERROR),
+                    );
+                let (_, address) = self
+                    .address_deserializer
+                    .deserialize::<DeserializeError>(serialized_address)
+                    .expect(LEDGER_ANALYTICS_DESER_ERROR);
+                (address, Amount::from_raw(raw_balance))
+            })
+            .collect()
+    }
+
+    /// Accounts for the write (`Some`) or the deletion (`None`) of a datastore entry in the analytics of its ledger entry
+    fn account_datastore_write(
+        &self,
+        addr: &Address,
+        key: &[u8],
+        value: Option<&Vec<u8>>,
+        entry_exists: bool,
+        analytics: &mut LedgerEntryAnalytics,
+    ) {
+        let previous_value = if entry_exists {
+            self.get_sub_entry(addr, LedgerSubEntry::Datastore(key.to_vec()))
+        } else {
+            None
+        };
+        if let Some(previous_value) = previous_value {
+            analytics.datastore_entry_count = analytics.datastore_entry_count.saturating_sub(1);
+            analytics.datastore_size = analytics
+                .datastore_size
+                .saturating_sub((key.len() + previous_value.len()) as u64);
+        }
+        if let Some(value) = value {
+            analytics.datastore_entry_count = analytics.datastore_entry_count.saturating_add(1);
+            analytics.datastore_size = analytics
+                .datastore_size
+                .saturating_add((key.len() + value.len()) as u64);
+        }
+    }
+
+    /// Adds a ledger entry read during a rebuild to the index batch and to the totals
+    fn account_rebuilt_entry(
+        &self,
+        db: &dyn MassaDBController,
+        addr: &Address,
+        analytics: &LedgerEntryAnalytics,
+        totals: &mut LedgerAnalyticsTotals,
+        batch: &mut DBBatch,
+    ) {
+        totals.address_count += 1;
+        totals.total_balance = totals.total_balance.saturating_add(analytics.balance);
+        totals.datastore_entry_count += analytics.datastore_entry_count;
+        totals.datastore_size += analytics.datastore_size;
+        self.put_entry_analytics(db, addr, analytics, batch);
+    }
+
+    /// Adds the analytics of an entry and its rich list key to the batch
+    fn put_entry_analytics(
+        &self,
+        db: &dyn MassaDBController,
+        addr: &Address,
+        analytics: &LedgerEntryAnalytics,
+        batch: &mut DBBatch,
+    ) {
+        let mut serialized_analytics = Vec::new();
+        self.entry_analytics_serializer
+            .serialize(analytics, &mut serialized_analytics)
+            .expect(LEDGER_ANALYTICS_SER_ERROR);
+        db.put_or_update_entry_value(batch, self.entry_analytics_key(addr), &serialized_analytics);
+        db.put_or_update_entry_value(batch, self.rich_list_key(addr, &analytics.balance), &[]);
+    }
+
+    /// Adds the totals of the ledger to the batch
+    fn put_analytics_totals(
+        &self,
+        db: &dyn MassaDBController,
+        totals: &LedgerAnalyticsTotals,
+        batch: &mut DBBatch,
+    ) {
+        let mut serialized_totals = Vec::new();
+        self.analytics_totals_serializer
+            .serialize(totals, &mut serialized_totals)
+            .expect(LEDGER_ANALYTICS_SER_ERROR);
+        db.put_or_update_entry_value(
+            batch,
+            [LEDGER_ANALYTICS_PREFIX.as_bytes(), b"t"].concat(),
+            &serialized_totals,
+        );
+    }
+
+    fn entry_analytics_key(&self, addr: &Address) -> Vec<u8> {
+        let mut key = [LEDGER_ANALYTICS_PREFIX.as_bytes(), b"e"].concat();
+        self.address_serializer
+            .serialize(addr, &mut key)
+            .expect(LEDGER_ANALYTICS_SER_ERROR);
+        key
+    }
+
+    fn rich_list_key(&self, addr: &Address, balance: &Amount) -> Vec<u8> {
+        let mut key = [
+            LEDGER_ANALYTICS_PREFIX.as_bytes(),
+            b"r",
+            &(u64::MAX - balance.to_raw()).to_be_bytes(),
+        ]
+        .concat();
+        self.address_serializer
+            .serialize(addr, &mut key)
+            .expect(LEDGER_ANALYTICS_SER_ERROR);
+        key
+    }
+}
+
+// Merkle tree index
+//
+// The sparse Merkle tree of the ledger (see `massa_models::ledger_tree`) is a node local index:
+// it is written directly to `INDEXES_CF`, outside of the state hash and of the change history.
+// Keys are `LEDGER_MERKLE_PREFIX` followed by:
+// * `s` for the final slot the tree matches
+// * `n`, the depth in big endian and the tree key truncated to the depth for the node at that position
+impl LedgerDB {
+    /// Updates the Merkle tree with the changes of a final slot.
+    ///
+    /// Must be called before the changes are applied to the ledger, as the deleted datastore keys are read from it.
+    pub fn apply_merkle_changes(&self, changes: &LedgerChanges, slot: &Slot) {
+        let mut items: Vec<(Hash, Option<Hash>)> = Vec::new();
+        for (addr, change) in changes.0.iter() {
+            match change {
+                // `put_entry` does not delete the datastore entries absent from the new entry
+                SetUpdateOrDelete::Set(entry) => {
+                    items.push(self.balance_tree_item(addr, Some(&entry.balance)));
+                    items.push(self.bytecode_tree_item(addr, Some(&entry.bytecode)));
+                    for (key, value) in entry.datastore.iter() {
+                        items.push(datastore_tree_item(addr, key, Some(value)));
+                    }
+                }
+                SetUpdateOrDelete::Update(entry_update) => {
+                    if let SetOrKeep::Set(balance) = &entry_update.balance {
+                        items.push(self.balance_tree_item(addr, Some(balance)));
+                    }
+                    if let SetOrKeep::Set(bytecode) = &entry_update.bytecode {
+                        items.push(self.bytecode_tree_item(addr, Some(bytecode)));
+                    }
+                    for (key, update) in entry_update.datastore.iter() {
+                        let value = match update {
+                            SetOrDelete::Set(value) => Some(value),
+                            SetOrDelete::Delete => None,
+                        };
+                        items.push(datastore_tree_item(addr, key, value));
+                    }
+                }
+                SetUpdateOrDelete::Delete => {
+                    items.push(self.balance_tree_item(addr, None));
+                    items.push(self.bytecode_tree_item(addr, None));
+                    for key in self.get_datastore_keys(addr, &[]).unwrap_or_default() {
+                        items.push(datastore_tree_item(addr, &key, None));
+                    }
+                }
+            }
+        }
+
+        let mut batch = DBBatch::new();
+        {
+            let db = self.db.read();
+            let mut tree = MerkleTreeOverlay::new(&**db, &self.merkle_node_deserializer);
+            for (key, value_hash) in items {
+                tree.update(&key, value_hash);
+            }
+            tree.write_to_batch(&self.merkle_node_serializer, &mut batch);
+            db.put_or_update_entry_value(
+                &mut batch,
+                [LEDGER_MERKLE_PREFIX.as_bytes(), b"s"].concat(),
+                &slot.to_bytes_key(),
+            );
+        }
+        self.db.write().write_index_batch(batch);
+    }
+
+    /// Rebuilds the Merkle tree from the whole ledger, if it does not match the final ledger
+    pub fn rebuild_merkle_tree(&self) {
+        let final_slot = self.db.read().get_change_id().ok();
+        if final_slot.is_some() && self.get_merkle_root().map(|(slot, _)| slot) == final_slot {
+            return;
+        }
+        self.build_merkle_tree(final_slot, MERKLE_REBUILD_CHUNK_SIZE);
+    }
+
+    /// Builds the Merkle tree from the whole ledger, matching `final_slot`.
+    ///
+    /// The ledger is streamed by chunks of `chunk_size` items, inserted in the tree like incremental changes,
+    /// so that only the nodes written by one chunk are held in memory.
+    fn build_merkle_tree(&self, final_slot: Option<Slot>, chunk_size: usize) {
+        self.db
+            .write()
+            .delete_prefix(LEDGER_MERKLE_PREFIX, INDEXES_CF, None);
+
+        let mut resume_key = LEDGER_PREFIX.as_bytes().to_vec();
+        loop {
+            let mut item_count = 0;
+            let mut batch = DBBatch::new();
+            {
+                let db = self.db.read();
+                let mut tree = MerkleTreeOverlay::new(&**db, &self.merkle_node_deserializer);
+                for (serialized_key, serialized_value) in db
+                    .iterator_cf(
+                        STATE_CF,
+                        MassaIteratorMode::From(&resume_key, MassaDirection::Forward),
+                    )
+                    .take(chunk_size)
+                {
+                    if !serialized_key.starts_with(LEDGER_PREFIX.as_bytes()) {
+                        break;
+                    }
+                    item_count += 1;
+                    // the smallest key following this one
+                    resume_key = [serialized_key.as_slice(), &[0]].concat();
+                    let (_, key) = self
+                        .key_deserializer_db
+                        .deserialize::<DeserializeError>(&serialized_key)
+                        .expect(KEY_DESER_ERROR);
+                    let item = match key.key_type {
+                        KeyType::BALANCE => LedgerTreeItem::Balance,
+                        KeyType::BYTECODE => LedgerTreeItem::Bytecode,
+                        KeyType::DATASTORE(datastore_key) => {
+                            LedgerTreeItem::Datastore(datastore_key)
+                        }
+                        KeyType::VERSION => continue,
+                    };
+                    tree.update(
+                        &item.tree_key(&key.address),
+                        Some(Hash::compute_from(&serialized_value)),
+                    );
+                }
+                tree.write_to_batch(&self.merkle_node_serializer, &mut batch);
+            }
+            self.db.write().write_index_batch(batch);
+            if item_count < chunk_size {
+                break;
+            }
+        }
+
+        if let Some(final_slot) = final_slot {
+            let mut batch = DBBatch::new();
+            self.db.read().put_or_update_entry_value(
+                &mut batch,
+                [LEDGER_MERKLE_PREFIX.as_bytes(), b"s"].concat(),
+                &final_slot.to_bytes_key(),
+            );
+            self.db.write().write_index_batch(batch);
+        }
+    }
+
+    /// Gets the final slot the Merkle tree matches and its root
+    pub fn get_merkle_root(&self) -> Option<(Slot, Hash)> {
+        let db = self.db.read();
+        let slot = get_merkle_slot(&**db)?;
+        let tree = MerkleTreeOverlay::new(&**db, &self.merkle_node_deserializer);
+        Some((slot, tree.root()))
+    }
+
+    /// Gets the values of ledger items with their paths in the node local Merkle tree
+    ///
+    /// The values and the tree are read under the same lock so that the paths match the values.
+    pub fn get_tree_paths(&self, items: &[(Address, LedgerTreeItem)]) -> Option<LedgerTreePaths> {
+        let db = self.db.read();
+        let slot = get_merkle_slot(&**db)?;
+        if db.get_change_id().ok() != Some(slot) {
+            return None;
+        }
+        let tree = MerkleTreeOverlay::new(&**db, &self.merkle_node_deserializer);
+        let paths = items
+            .iter()
+            .map(|(addr, item)| {
+                let sub_entry = match item {
+                    LedgerTreeItem::Balance => LedgerSubEntry::Balance,
+                    LedgerTreeItem::Bytecode => LedgerSubEntry::Bytecode,
+                    LedgerTreeItem::Datastore(key) => LedgerSubEntry::Datastore(key.clone()),
+                };
+                let mut serialized_key = Vec::new();
+                self.key_serializer_db
+                    .serialize(&sub_entry.derive_key(addr), &mut serialized_key)
+                    .expect(KEY_SER_ERROR);
+                let value = db.get_cf(STATE_CF, serialized_key).expect(CRUD_ERROR);
+                (value, tree.path_of(&item.tree_key(addr)))
+            })
+            .collect();
+        Some(LedgerTreePaths {
+            slot,
+            local_root: tree.root(),
+            paths,
+        })
+    }
+
+    /// Tree key and value hash of the balance of an address, None to delete it
+    fn balance_tree_item(&self, addr: &Address, balance: Option<&Amount>) -> (Hash, Option<Hash>) {
+        let value_hash = balance.map(|balance| {
+            let mut bytes = Vec::new();
+            // Amount serialization never fails
+            self.amount_serializer
+                .serialize(balance, &mut bytes)
+                .unwrap();
+            Hash::compute_from(&bytes)
+        });
+        (LedgerTreeItem::Balance.tree_key(addr), value_hash)
+    }
+
+    /// Tree key and value hash of the bytecode of an address, None to delete it
+    fn bytecode_tree_item(
+        &self,
+        addr: &Address,
+        bytecode: Option<&Bytecode>,
+    ) -> (Hash, Option<Hash>) {
+        let value_hash = bytecode.map(|bytecode| {
+            let mut bytes = Vec::new();
+            self.bytecode_serializer
+                .serialize(bytecode, &mut bytes)
+                .unwrap();
+            Hash::compute_from(&bytes)
+        });
+        (LedgerTreeItem::Bytecode.tree_key(addr), value_hash)
+    }
+}
+
+/// Tree key and value hash of a datastore entry, None to delete it
+fn datastore_tree_item(
+    addr: &Address,
+    key: &[u8],
+    value: Option<&Vec<u8>>,
+) -> (Hash, Option<Hash>) {
+    (
+        LedgerTreeItem::Datastore(key.to_vec()).tree_key(addr),
+        value.map(|value| Hash::compute_from(value)),
+    )
+}
+
+/// Gets the final slot the Merkle tree matches, None if it was never built
+fn get_merkle_slot(db: &dyn MassaDBController) -> Option<Slot> {
+    let serialized_slot = db
+        .get_cf(INDEXES_CF, [LEDGER_MERKLE_PREFIX.as_bytes(), b"s"].concat())
+        .expect(CRUD_ERROR)?;
+    Some(Slot::from_bytes_key(
+        serialized_slot
+            .as_slice()
+            .try_into()
+            .expect(LEDGER_MERKLE_DESER_ERROR),
+    ))
+}
+
+/// Key of the node at the given depth on the path of `path`
+fn merkle_node_key(depth: usize, path: &Hash) -> Vec<u8> {
+    let mut truncated_path = *path.to_bytes();
+    for (index, byte) in truncated_path.iter_mut().enumerate() {
+        let kept_bits = depth.saturating_sub(index * 8).min(8);
+        *byte &= !(0xffu16 >> kept_bits) as u8;
+    }
+    [
+        LEDGER_MERKLE_PREFIX.as_bytes(),
+        b"n",
+        &(depth as u16).to_be_bytes(),
+        &truncated_path,
+    ]
+    .concat()
+}
+
+/// Returns `path` with its bit at `depth` flipped, which is the path of the sibling of the node at `depth + 1`
+fn flip_bit(path: &Hash, depth: usize) -> Hash {
+    let mut bytes = *path.to_bytes();
+    bytes[depth / 8] ^= 0x80 >> (depth % 8);
+    Hash::from_bytes(&bytes)
+}
+
+/// Nodes of the Merkle tree written while applying changes, read through to the database
+struct MerkleTreeOverlay<'a> {
+    db: &'a dyn MassaDBController,
+    node_deserializer: &'a SparseMerkleNodeDeserializer,
+    /// written nodes by key, None for deleted ones
+    nodes: BTreeMap<Vec<u8>, Option<SparseMerkleNode>>,
+}
+
+impl<'a> MerkleTreeOverlay<'a> {
+    fn new(
+        db: &'a dyn MassaDBController,
+        node_deserializer: &'a SparseMerkleNodeDeserializer,
+    ) -> Self {
+        MerkleTreeOverlay {
+            db,
+            node_deserializer,
+            nodes: BTreeMap::new(),
+        }
+    }
+
+    fn get(&self, depth: usize, path: &Hash) -> Option<SparseMerkleNode> {
+        let key = merkle_node_key(depth, path);
+        if let Some(node) = self.nodes.get(&key) {
+            return *node;
+        }
+        let serialized_node = self.db.get_cf(INDEXES_CF, key).expect(CRUD_ERROR)?;
+        Some(
+            self.node_deserializer
+                .deserialize::<DeserializeError>(&serialized_node)
+                .expect(LEDGER_MERKLE_DESER_ERROR)
+                .1,
+        )
+    }
+
+    fn set(&mut self, depth: usize, path: &Hash, node: Option<SparseMerkleNode>) {
+        self.nodes.insert(merkle_node_key(depth, path), node);
+    }
+
+    fn root(&self) -> Hash {
+        self.get(0, &Hash::zero())
+            .map(|node| node.hash())
+            .unwrap_or_else(Hash::zero)
+    }
+
+    /// Sets the value hash of a key, or deletes the key if `value_hash` is None
+    fn update(&mut self, key: &Hash, value_hash: Option<Hash>) {
+        let root = self.update_subtree(0, key, value_hash);
+        self.set(0, key, root);
+    }
+
+    /// Applies the update to the subtree at `depth` on the path of `key`, and returns its new top node.
+    /// The caller writes the returned node at the position of the subtree.
+    fn update_subtree(
+        &mut self,
+        depth: usize,
+        key: &Hash,
+        value_hash: Option<Hash>,
+    ) -> Option<SparseMerkleNode> {
+        let new_leaf = value_hash.map(|value_hash| SparseMerkleNode::Leaf {
+            key: *key,
+            value_hash,
+        });
+        match self.get(depth, key) {
+            None => new_leaf,
+            Some(SparseMerkleNode::Leaf { key: leaf_key, .. }) if leaf_key == *key => new_leaf,
+            Some(leaf @ SparseMerkleNode::Leaf { .. }) => match new_leaf {
+                Some(new_leaf) => Some(self.split_leaves(depth, leaf, new_leaf)),
+                None => Some(leaf),
+            },
+            Some(SparseMerkleNode::Internal { .. }) => {
+                let child = self.update_subtree(depth + 1, key, value_hash);
+                self.set(depth + 1, key, child);
+                let sibling_path = flip_bit(key, depth);
+                let sibling = self.get(depth + 1, &sibling_path);
+                match (child, sibling) {
+                    (None, None) => None,
+                    // a subtree left with a single leaf is replaced by that leaf
+                    (None, Some(leaf @ SparseMerkleNode::Leaf { .. })) => {
+                        self.set(depth + 1, &sibling_path, None);
+                        Some(leaf)
+                    }
+                    (Some(leaf @ SparseMerkleNode::Leaf { .. }), None) => {
+                        self.set(depth + 1, key, None);
+                        Some(leaf)
+                    }
+                    (child, sibling) => Some(internal_node(
+                        key_bit(key, depth),
+                        child.map(|node| node.hash()).unwrap_or_else(Hash::zero),
+                        sibling.map(|node| node.hash()).unwrap_or_else(Hash::zero),
+                    )),
+                }
+            }
+        }
+    }
+
+    /// Places two leaves of different keys in a subtree at `depth`, and returns its top node
+    fn split_leaves(
+        &mut self,
+        depth: usize,
+        first: SparseMerkleNode,
+        second: SparseMerkleNode,
+    ) -> SparseMerkleNode {
+        let (
+            SparseMerkleNode::Leaf { key: first_key, .. },
+            SparseMerkleNode::Leaf {
+                key: second_key, ..
+            },
+        ) = (first, second)
+        else {
+            unreachable!("only leaves are split");
+        };
+        let first_bit = key_bit(&first_key, depth);
+        if first_bit != key_bit(&second_key, depth) {
+            self.set(depth + 1, &first_key, Some(first));
+            self.set(depth + 1, &second_key, Some(second));
+            internal_node(first_bit, first.hash(), second.hash())
+        } else {
+            let child = self.split_leaves(depth + 1, first, second);
+            self.set(depth + 1, &first_key, Some(child));
+            internal_node(first_bit, child.hash(), Hash::zero())
+        }
+    }
+
+    /// Builds the path of `key` by walking down the tree
+    fn path_of(&self, key: &Hash) -> SparseMerklePath {
+        let mut siblings = Vec::new();
+        loop {
+            match self.get(siblings.len(), key) {
+                None => {
+                    return SparseMerklePath {
+                        siblings,
+                        leaf: None,
+                    }
+                }
+                Some(SparseMerkleNode::Leaf {
+                    key: leaf_key,
+                    value_hash,
+                }) => {
+                    return SparseMerklePath {
+                        siblings,
+                        leaf: Some((leaf_key, value_hash)),
+                    }
+                }
+                Some(SparseMerkleNode::Internal { left, right }) => {
+                    let sibling = if key_bit(key, siblings.len()) {
+                        left
+                    } else {
+                        right
+                    };
+                    siblings.push(sibling);
+                }
+            }
+        }
+    }
+
+    /// Adds the written nodes to the batch
+    fn write_to_batch(self, node_serializer: &SparseMerkleNodeSerializer, batch: &mut DBBatch) {
+        for (key, node) in self.nodes {
+            match node {
+                Some(node) => {
+                    let mut serialized_node = Vec::new();
+                    node_serializer
+                        .serialize(&node, &mut serialized_node)
+                        .expect(LEDGER_MERKLE_SER_ERROR);
+                    self.db
+                        .put_or_update_entry_value(batch, key, &serialized_node);
+                }
+                None => self.db.delete_key(batch, key),
+            }
+        }
+    }
+}
+
+/// Internal node with `child` on the side of `bit` and `sibling` on the other side
+fn internal_node(bit: bool, child: Hash, sibling: Hash) -> SparseMerkleNode {
+    let (left, right) = if bit {
+        (sibling, child)
+    } else {
+        (child, sibling)
+    };
+    SparseMerkleNode::Internal { left, right }
+}
+
 // test helpers
 impl LedgerDB {
     /// Get every address and their corresponding balance.
@@ -564,6 +1376,7 @@ mod tests {
     use massa_models::{
         address::Address,
         amount::{Amount, AmountDeserializer},
+        ledger_tree::matches_local_root,
     };
     use massa_serialization::{DeserializeError, Deserializer};
     use massa_signature::KeyPair;
@@ -674,6 +1487,216 @@ mod tests {
         assert!(ledger_db.get_entire_datastore(&addr).is_empty());
     }
 
+    /// Functional test of the analytics index of `LedgerDB`
+    #[test]
+    fn test_ledger_analytics() {
+        let addr = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let addr_2 = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let (ledger_db, _data) = init_test_ledger(addr);
+        ledger_db.db.read().set_initial_change_id(Slot::new(0, 0));
+
+        // the initial entry has a balance of 21 and 3 datastore entries of 2 bytes each
+        ledger_db.rebuild_analytics();
+        assert_eq!(ledger_db.get_analytics_slot(), Some(Slot::new(0, 0)));
+        assert_eq!(
+            ledger_db.get_analytics_totals(),
+            LedgerAnalyticsTotals {
+                address_count: 1,
+                total_balance: Amount::from_str("21").unwrap(),
+                datastore_entry_count: 3,
+                datastore_size: 6,
+            }
+        );
+
+        // create a new entry, delete a datastore entry and grow another one
+        let mut changes = LedgerChanges::default();
+        changes.0.insert(
+            addr_2,
+            SetUpdateOrDelete::Set(LedgerEntry {
+                balance: Amount::from_str("100").unwrap(),
+                datastore: BTreeMap::from([(b"k".to_vec(), b"vv".to_vec())]),
+                ..Default::default()
+            }),
+        );
+        changes.0.insert(
+            addr,
+            SetUpdateOrDelete::Update(LedgerEntryUpdate {
+                datastore: BTreeMap::from([
+                    (b"1".to_vec(), SetOrDelete::Delete),
+                    (b"2".to_vec(), SetOrDelete::Set(b"bbb".to_vec())),
+                ]),
+                ..Default::default()
+            }),
+        );
+        let slot = Slot::new(0, 1);
+        ledger_db.apply_analytics_changes(&changes, &slot);
+        let mut batch = DBBatch::new();
+        ledger_db.apply_changes_to_batch(changes, &mut batch);
+        ledger_db
+            .db
+            .write()
+            .write_batch(batch, Default::default(), Some(slot));
+
+        let expected_totals = LedgerAnalyticsTotals {
+            address_count: 2,
+            total_balance: Amount::from_str("121").unwrap(),
+            datastore_entry_count: 3,
+            datastore_size: 9,
+        };
+        assert_eq!(ledger_db.get_analytics_totals(), expected_totals);
+        assert_eq!(
+            ledger_db.get_entry_analytics(&addr),
+            Some(LedgerEntryAnalytics {
+                balance: Amount::from_str("21").unwrap(),
+                datastore_entry_count: 2,
+                datastore_size: 6,
+            })
+        );
+        assert_eq!(
+            ledger_db.get_rich_list(10),
+            vec![
+                (addr_2, Amount::from_str("100").unwrap()),
+                (addr, Amount::from_str("21").unwrap())
+            ]
+        );
+        assert_eq!(ledger_db.get_rich_list(1).len(), 1);
+
+        // the index matches the final ledger
+        assert_eq!(ledger_db.get_analytics_slot(), Some(slot));
+
+        // the incremental updates match a full rebuild
+        ledger_db
+            .db
+            .write()
+            .delete_prefix(LEDGER_ANALYTICS_PREFIX, INDEXES_CF, None);
+        ledger_db.rebuild_analytics();
+        assert_eq!(ledger_db.get_analytics_slot(), Some(slot));
+        assert_eq!(ledger_db.get_analytics_totals(), expected_totals);
+
+        // deleting an entry removes it from the index
+        let mut changes = LedgerChanges::default();
+        changes.0.insert(addr_2, SetUpdateOrDelete::Delete);
+        ledger_db.apply_analytics_changes(&changes, &Slot::new(0, 2));
+        assert_eq!(ledger_db.get_entry_analytics(&addr_2), None);
+        assert_eq!(
+            ledger_db.get_rich_list(10),
+            vec![(addr, Amount::from_str("21").unwrap())]
+        );
+        assert_eq!(ledger_db.get_analytics_totals().address_count, 1);
+
+        // the deletion was not applied to the ledger: the index does not match it anymore and is rebuilt
+        ledger_db.rebuild_analytics();
+        assert_eq!(ledger_db.get_analytics_slot(), Some(slot));
+        assert_eq!(ledger_db.get_analytics_totals(), expected_totals);
+    }
+
+    /// Functional test of the Merkle tree index of `LedgerDB`
+    #[test]
+    fn test_ledger_merkle_tree() {
+        let addr = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let addr_2 = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let (ledger_db, _data) = init_test_ledger(addr);
+        ledger_db.db.read().set_initial_change_id(Slot::new(0, 0));
+        ledger_db.rebuild_merkle_tree();
+
+        let items = vec![
+            (addr, LedgerTreeItem::Balance),
+            (addr, LedgerTreeItem::Datastore(b"1".to_vec())),
+            (addr, LedgerTreeItem::Datastore(b"4".to_vec())),
+            (addr_2, LedgerTreeItem::Balance),
+        ];
+        let get_consistent_paths = || {
+            let paths = ledger_db.get_tree_paths(&items).unwrap();
+            for ((address, item), (value, path)) in items.iter().zip(paths.paths.iter()) {
+                assert!(matches_local_root(
+                    &paths.local_root,
+                    address,
+                    item,
+                    value.as_deref(),
+                    path
+                ));
+            }
+            paths
+        };
+
+        // values and absences in the initial ledger
+        let paths = get_consistent_paths();
+        assert_eq!(paths.slot, Slot::new(0, 0));
+        assert_eq!(paths.paths[1].0, Some(b"a".to_vec()));
+        assert_eq!(paths.paths[2].0, None);
+        assert_eq!(paths.paths[3].0, None);
+        assert!(!matches_local_root(
+            &paths.local_root,
+            &addr,
+            &items[1].1,
+            Some(b"z"),
+            &paths.paths[1].1
+        ));
+        assert!(!matches_local_root(
+            &paths.local_root,
+            &addr,
+            &items[1].1,
+            None,
+            &paths.paths[1].1
+        ));
+
+        // create a new entry, delete a datastore entry and add another one
+        let slot = Slot::new(1, 0);
+        let mut changes = LedgerChanges::default();
+        changes.0.insert(
+            addr_2,
+            SetUpdateOrDelete::Set(LedgerEntry {
+                balance: Amount::from_str("100").unwrap(),
+                datastore: BTreeMap::from([(b"k".to_vec(), b"vv".to_vec())]),
+                ..Default::default()
+            }),
+        );
+        changes.0.insert(
+            addr,
+            SetUpdateOrDelete::Update(LedgerEntryUpdate {
+                datastore: BTreeMap::from([
+                    (b"1".to_vec(), SetOrDelete::Delete),
+                    (b"4".to_vec(), SetOrDelete::Set(b"d".to_vec())),
+                ]),
+                ..Default::default()
+            }),
+        );
+        ledger_db.apply_merkle_changes(&changes, &slot);
+        // no paths while the tree is ahead of the ledger
+        assert!(ledger_db.get_tree_paths(&items).is_none());
+        let mut batch = DBBatch::new();
+        ledger_db.apply_changes_to_batch(changes, &mut batch);
+        ledger_db
+            .db
+            .write()
+            .write_batch(batch, Default::default(), Some(slot));
+
+        let paths = get_consistent_paths();
+        assert_eq!(paths.slot, slot);
+        assert_eq!(paths.paths[1].0, None);
+        assert_eq!(paths.paths[2].0, Some(b"d".to_vec()));
+        assert!(paths.paths[3].0.is_some());
+
+        // the incremental updates match a full rebuild
+        ledger_db
+            .db
+            .write()
+            .delete_prefix(LEDGER_MERKLE_PREFIX, INDEXES_CF, None);
+        ledger_db.rebuild_merkle_tree();
+        assert_eq!(ledger_db.get_merkle_root(), Some((slot, paths.local_root)));
+        // whatever the number of items streamed at once
+        ledger_db.build_merkle_tree(Some(slot), 2);
+        assert_eq!(ledger_db.get_merkle_root(), Some((slot, paths.local_root)));
+
+        // deleting all the entries empties the tree
+        let slot = Slot::new(2, 0);
+        let mut changes = LedgerChanges::default();
+        changes.0.insert(addr, SetUpdateOrDelete::Delete);
+        changes.0.insert(addr_2, SetUpdateOrDelete::Delete);
+        ledger_db.apply_merkle_changes(&changes, &slot);
+        assert_eq!(ledger_db.get_merkle_root(), Some((slot, Hash::zero())));
+    }
+
     #[test]
     fn test_end_prefix() {
         assert_eq!(end_prefix(&[5, 6, 7]), Some(vec![5, 6, 8]));
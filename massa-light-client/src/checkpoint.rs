@@ -0,0 +1,15 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use std::collections::BTreeMap;
+
+use massa_models::{block_header::SecuredHeader, slot::Slot};
+use massa_pos_exports::Selection;
+
+/// Trusted starting point of a light client
+#[derive(Debug, Clone)]
+pub struct LightClientCheckpoint {
+    /// header of the latest final block of each thread, ordered by thread
+    pub final_headers: Vec<SecuredHeader>,
+    /// producer and endorser draws of the slots following the checkpoint
+    pub draws: BTreeMap<Slot, Selection>,
+}
@@ -0,0 +1,18 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+/// Light client configuration
+#[derive(Debug, Clone)]
+pub struct LightClientConfig {
+    /// Number of threads
+    pub thread_count: u8,
+    /// target number of endorsement per block
+    pub endorsement_count: u32,
+    /// Threshold for fitness.
+    pub delta_f0: u64,
+    /// force keep at least this number of final periods in RAM for each thread
+    pub force_keep_final_periods: u64,
+    /// Maximum number of headers waiting for their parents or for the draws of their slot
+    pub max_waiting_headers: usize,
+    /// Maximum number of discarded block ids kept in memory
+    pub max_discarded_blocks: usize,
+}
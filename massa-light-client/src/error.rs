@@ -0,0 +1,26 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use displaydoc::Display;
+use massa_models::{block_graph::BlockGraphError, error::ModelsError, slot::Slot};
+use thiserror::Error;
+
+/// Light client result
+pub type LightClientResult<T, E = LightClientError> = core::result::Result<T, E>;
+
+/// Light client error
+#[non_exhaustive]
+#[derive(Display, Error, Debug)]
+pub enum LightClientError {
+    /// models error: {0}
+    ModelsError(#[from] ModelsError),
+    /// invalid checkpoint: {0}
+    InvalidCheckpoint(String),
+    /// there was an inconsistency between containers {0}
+    ContainerInconsistency(String),
+    /// block graph error: {0}
+    BlockGraphError(#[from] BlockGraphError),
+    /// slot {0} is not final yet
+    SlotNotFinal(Slot),
+    /// invalid ledger tree paths: {0}
+    InvalidLedgerTreePaths(String),
+}
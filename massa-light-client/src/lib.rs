@@ -0,0 +1,31 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Light client following the block headers and their finality only.
+//!
+//! Starting from a trusted checkpoint (the latest final block of each thread and the
+//! selector draws of the following slots), the light client checks the producer and
+//! endorser draws of incoming headers and tracks their finality with the same graph
+//! rules as the consensus, without storing blocks, operations or the ledger.
+//!
+//! Headers can be fed from an untrusted node, for example through the
+//! `subscribe_new_blocks_headers` subscription, while the draws of later cycles have to
+//! come from a trusted source through `LightClient::add_draws`.
+//!
+//! A node started with `--light` runs a light client fed by its peers: it retrieves the
+//! headers only, asks the draws to its trusted peers and serves the headers and their
+//! finality through its light JSON-RPC API.
+
+#![warn(missing_docs)]
+#![warn(unused_imports)]
+
+mod checkpoint;
+mod config;
+mod error;
+mod state;
+
+pub use checkpoint::LightClientCheckpoint;
+pub use config::LightClientConfig;
+pub use error::*;
+pub use state::{HeaderOutcome, LightClient};
+
+#[cfg(test)]
+mod tests;
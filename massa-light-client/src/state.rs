@@ -0,0 +1,678 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Header graph of the light client.
+//!
+//! The graph and finality rules mirror the ones of the consensus worker (`massa_models::block_graph`),
+//! and the header checks are restricted to what can be verified from headers and selector draws.
+
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use massa_models::{
+    active_block::ActiveBlock,
+    address::Address,
+    block_graph::{self, Incompatibilities, ParentsTopology},
+    block_header::SecuredHeader,
+    block_id::BlockId,
+    clique::Clique,
+    ledger_tree::{matches_local_root, LedgerTreeItem, LedgerTreePaths},
+    prehash::{PreHashMap, PreHashSet},
+    slot::Slot,
+};
+use massa_pos_exports::Selection;
+use tracing::debug;
+
+use crate::{LightClientCheckpoint, LightClientConfig, LightClientError, LightClientResult};
+
+/// Outcome of the processing of a header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeaderOutcome {
+    /// the header is already known (active, waiting or discarded)
+    Known,
+    /// the header was added to the graph
+    Added,
+    /// the header waits for missing parents or for the draws of its slot
+    Waiting,
+    /// the header was discarded for the given reason
+    Discarded(String),
+}
+
+/// Result of the checks of an incoming header
+enum HeaderCheckOutcome {
+    Proceed {
+        parents_hash_period: Vec<(BlockId, u64)>,
+        incompatibilities: PreHashSet<BlockId>,
+        inherited_incompatibilities_count: usize,
+    },
+    Wait,
+    Discard(String),
+}
+
+/// Light client following the block headers and their finality
+pub struct LightClient {
+    /// configuration
+    config: LightClientConfig,
+    /// headers of the blocks of the graph
+    headers: PreHashMap<BlockId, SecuredHeader>,
+    /// graph information of the blocks of the graph
+    active_blocks: PreHashMap<BlockId, ActiveBlock>,
+    /// blocks of the checkpoint, trusted as final without knowing their parents
+    checkpoint_blocks: PreHashSet<BlockId>,
+    /// incompatibility graph of the non-final blocks
+    gi_head: PreHashMap<BlockId, PreHashSet<BlockId>>,
+    /// max cliques of compatible non-final blocks
+    max_cliques: Vec<Clique>,
+    /// latest final block id and period of each thread
+    latest_final_blocks_periods: Vec<(BlockId, u64)>,
+    /// selector draws known by the light client
+    draws: BTreeMap<Slot, Selection>,
+    /// headers waiting for their parents or for the draws of their slot
+    waiting_headers: PreHashMap<BlockId, SecuredHeader>,
+    /// discarded block ids, and their order of arrival for pruning
+    discarded_blocks: PreHashSet<BlockId>,
+    discarded_order: VecDeque<BlockId>,
+    /// blocks that became final since the last call to `take_new_final_blocks`
+    new_final_blocks: Vec<BlockId>,
+}
+
+impl LightClient {
+    /// Creates a light client starting from a trusted checkpoint
+    pub fn new(
+        config: LightClientConfig,
+        checkpoint: LightClientCheckpoint,
+    ) -> LightClientResult<Self> {
+        if checkpoint.final_headers.len() != config.thread_count as usize {
+            return Err(LightClientError::InvalidCheckpoint(format!(
+                "expected {} final headers, got {}",
+                config.thread_count,
+                checkpoint.final_headers.len()
+            )));
+        }
+        let mut headers = PreHashMap::default();
+        let mut active_blocks = PreHashMap::default();
+        let mut latest_final_blocks_periods = Vec::with_capacity(config.thread_count as usize);
+        for (thread, header) in checkpoint.final_headers.into_iter().enumerate() {
+            if header.content.slot.thread as usize != thread {
+                return Err(LightClientError::InvalidCheckpoint(format!(
+                    "final header {} is not in thread {}",
+                    header.id, thread
+                )));
+            }
+            header.verify_signature()?;
+            active_blocks.insert(
+                header.id,
+                ActiveBlock {
+                    creator_address: header.content_creator_address,
+                    block_id: header.id,
+                    parents: Vec::new(),
+                    children: vec![Default::default(); config.thread_count as usize],
+                    descendants: Default::default(),
+                    is_final: true,
+                    slot: header.content.slot,
+                    fitness: header.get_fitness(),
+                    same_thread_parent_creator: None,
+                },
+            );
+            latest_final_blocks_periods.push((header.id, header.content.slot.period));
+            headers.insert(header.id, header);
+        }
+        Ok(LightClient {
+            checkpoint_blocks: headers.keys().copied().collect(),
+            config,
+            headers,
+            active_blocks,
+            gi_head: Default::default(),
+            max_cliques: vec![Clique::default()],
+            latest_final_blocks_periods,
+            draws: checkpoint.draws,
+            waiting_headers: Default::default(),
+            discarded_blocks: Default::default(),
+            discarded_order: Default::default(),
+            new_final_blocks: Vec::new(),
+        })
+    }
+
+    /// Adds selector draws obtained from a trusted source,
+    /// and processes the headers that were waiting for them
+    pub fn add_draws(&mut self, draws: BTreeMap<Slot, Selection>) -> LightClientResult<()> {
+        self.draws.extend(draws);
+        self.process_waiting_headers()
+    }
+
+    /// Processes an incoming header, and the waiting headers it unlocks
+    pub fn process_header(&mut self, header: SecuredHeader) -> LightClientResult<HeaderOutcome> {
+        let outcome = self.process_incoming_header(header)?;
+        if outcome == HeaderOutcome::Added {
+            self.process_waiting_headers()?;
+        }
+        Ok(outcome)
+    }
+
+    /// Gets the header of a block of the graph
+    pub fn get_header(&self, block_id: &BlockId) -> Option<&SecuredHeader> {
+        self.headers.get(block_id)
+    }
+
+    /// Returns whether a block of the graph is final, `None` if the block is not in the graph
+    pub fn is_final(&self, block_id: &BlockId) -> Option<bool> {
+        self.active_blocks
+            .get(block_id)
+            .map(|a_block| a_block.is_final)
+    }
+
+    /// Returns whether the blocks of a slot are final
+    pub fn is_slot_final(&self, slot: &Slot) -> bool {
+        self.latest_final_blocks_periods
+            .get(slot.thread as usize)
+            .map_or(false, |(_, period)| slot.period <= *period)
+    }
+
+    /// Gets the latest final block id and period of each thread
+    pub fn get_latest_final_blocks_periods(&self) -> &[(BlockId, u64)] {
+        &self.latest_final_blocks_periods
+    }
+
+    /// Gets the clique of higher fitness
+    pub fn get_blockclique(&self) -> PreHashSet<BlockId> {
+        block_graph::get_blockclique(&self.max_cliques)
+    }
+
+    /// Gets the draws of a slot, if known
+    pub fn get_selection(&self, slot: &Slot) -> Option<&Selection> {
+        self.draws.get(slot)
+    }
+
+    /// Gets the known draws of a range of slots
+    pub fn get_selections_in_range(
+        &self,
+        slot_range: std::ops::RangeInclusive<Slot>,
+    ) -> BTreeMap<Slot, Selection> {
+        self.draws
+            .range(slot_range)
+            .map(|(slot, selection)| (*slot, selection.clone()))
+            .collect()
+    }
+
+    /// Gets the first slot following the known draws,
+    /// or the slot following the latest final blocks if no draws are known
+    pub fn next_missing_draws_slot(&self) -> LightClientResult<Slot> {
+        let last_slot = match self.draws.last_key_value() {
+            Some((slot, _)) => *slot,
+            None => self
+                .latest_final_blocks_periods
+                .iter()
+                .enumerate()
+                .map(|(thread, (_, period))| Slot::new(*period, thread as u8))
+                .min()
+                .unwrap_or_else(Slot::min),
+        };
+        Ok(last_slot.get_next_slot(self.config.thread_count)?)
+    }
+
+    /// Gets the parents of the waiting headers that are not known by the light client
+    pub fn get_missing_parents(&self) -> PreHashSet<BlockId> {
+        self.waiting_headers
+            .values()
+            .flat_map(|header| header.content.parents.iter())
+            .filter(|parent_id| {
+                !self.active_blocks.contains_key(parent_id)
+                    && !self.waiting_headers.contains_key(parent_id)
+                    && !self.discarded_blocks.contains(parent_id)
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Gets the number of headers waiting for their parents or for the draws of their slot
+    pub fn get_waiting_headers_count(&self) -> usize {
+        self.waiting_headers.len()
+    }
+
+    /// Returns the blocks that became final since the last call
+    pub fn take_new_final_blocks(&mut self) -> Vec<BlockId> {
+        std::mem::take(&mut self.new_final_blocks)
+    }
+
+    /// Checks that ledger items returned by a full node are consistent with the local root of its ledger Merkle tree.
+    ///
+    /// The slot of the paths must be final for the light client.
+    /// Note that the Merkle root is not committed in the block headers nor in the final state hash:
+    /// this only shows the consistency of the values with the tree of the full node, which has to be trusted.
+    pub fn check_ledger_tree_paths(
+        &self,
+        paths: &LedgerTreePaths,
+        items: &[(Address, LedgerTreeItem)],
+    ) -> LightClientResult<bool> {
+        if !self.is_slot_final(&paths.slot) {
+            return Err(LightClientError::SlotNotFinal(paths.slot));
+        }
+        if paths.paths.len() != items.len() {
+            return Err(LightClientError::InvalidLedgerTreePaths(format!(
+                "expected {} paths, got {}",
+                items.len(),
+                paths.paths.len()
+            )));
+        }
+        Ok(items
+            .iter()
+            .zip(paths.paths.iter())
+            .all(|((address, item), (value, path))| {
+                matches_local_root(&paths.local_root, address, item, value.as_deref(), path)
+            }))
+    }
+
+    fn process_incoming_header(
+        &mut self,
+        header: SecuredHeader,
+    ) -> LightClientResult<HeaderOutcome> {
+        let block_id = header.id;
+        if self.active_blocks.contains_key(&block_id)
+            || self.waiting_headers.contains_key(&block_id)
+            || self.discarded_blocks.contains(&block_id)
+        {
+            return Ok(HeaderOutcome::Known);
+        }
+        match self.check_header(&header)? {
+            HeaderCheckOutcome::Wait => {
+                if self.waiting_headers.len() >= self.config.max_waiting_headers {
+                    self.discard_block(block_id);
+                    return Ok(HeaderOutcome::Discarded(
+                        "too many waiting headers".to_string(),
+                    ));
+                }
+                self.waiting_headers.insert(block_id, header);
+                Ok(HeaderOutcome::Waiting)
+            }
+            HeaderCheckOutcome::Discard(reason) => {
+                debug!("light client discarded header {}: {}", block_id, reason);
+                self.discard_block(block_id);
+                Ok(HeaderOutcome::Discarded(reason))
+            }
+            HeaderCheckOutcome::Proceed {
+                parents_hash_period,
+                incompatibilities,
+                inherited_incompatibilities_count,
+            } => {
+                self.add_header_to_graph(
+                    header,
+                    parents_hash_period,
+                    incompatibilities,
+                    inherited_incompatibilities_count,
+                )?;
+                Ok(HeaderOutcome::Added)
+            }
+        }
+    }
+
+    /// Retries the waiting headers, by slot order, until none of them can progress
+    fn process_waiting_headers(&mut self) -> LightClientResult<()> {
+        loop {
+            let mut waiting: Vec<SecuredHeader> =
+                self.waiting_headers.drain().map(|(_, h)| h).collect();
+            waiting.sort_unstable_by_key(|h| h.content.slot);
+            let mut progress = false;
+            for header in waiting {
+                if self.process_incoming_header(header)? != HeaderOutcome::Waiting {
+                    progress = true;
+                }
+            }
+            if !progress {
+                return Ok(());
+            }
+        }
+    }
+
+    fn discard_block(&mut self, block_id: BlockId) {
+        if self.discarded_blocks.insert(block_id) {
+            self.discarded_order.push_back(block_id);
+        }
+        while self.discarded_order.len() > self.config.max_discarded_blocks {
+            if let Some(old_id) = self.discarded_order.pop_front() {
+                self.discarded_blocks.remove(&old_id);
+            }
+        }
+    }
+
+    /// Checks an incoming header:
+    /// - Check the signatures of the header and of its endorsements.
+    /// - Check the producer and endorser draws.
+    /// - Check parents are present and their topological consistency.
+    /// - Check thread and grandpa incompatibilities.
+    /// - Check the header is not incompatible with a parent or a final block.
+    fn check_header(&self, header: &SecuredHeader) -> LightClientResult<HeaderCheckOutcome> {
+        let thread_count = self.config.thread_count;
+        let slot = header.content.slot;
+        if slot.thread >= thread_count {
+            return Ok(HeaderCheckOutcome::Discard(format!(
+                "invalid thread for slot {}",
+                slot
+            )));
+        }
+        if header.content.parents.len() != thread_count as usize {
+            return Ok(HeaderCheckOutcome::Discard(format!(
+                "invalid parent count {}",
+                header.content.parents.len()
+            )));
+        }
+        if header.content.endorsements.len() > self.config.endorsement_count as usize {
+            return Ok(HeaderCheckOutcome::Discard(format!(
+                "invalid endorsement count {}",
+                header.content.endorsements.len()
+            )));
+        }
+
+        // check that is older than the latest final block in that thread
+        if slot.period <= self.latest_final_blocks_periods[slot.thread as usize].1 {
+            return Ok(HeaderCheckOutcome::Discard("stale".to_string()));
+        }
+
+        if let Err(err) = header.verify_signature() {
+            return Ok(HeaderCheckOutcome::Discard(format!(
+                "invalid signature: {}",
+                err
+            )));
+        }
+
+        // check if it was the creator's turn to create this block
+        let selection = match self.draws.get(&slot) {
+            Some(selection) => selection,
+            None => return Ok(HeaderCheckOutcome::Wait),
+        };
+        if header.content_creator_address != selection.producer {
+            return Ok(HeaderCheckOutcome::Discard(format!(
+                "Bad creator turn for the slot:{}",
+                slot
+            )));
+        }
+
+        // check endorsements: draws, signatures, slot and endorsed block
+        let mut endorsement_indices = HashSet::new();
+        for endorsement in header.content.endorsements.iter() {
+            if endorsement.content.slot != slot
+                || endorsement.content.endorsed_block
+                    != header.content.parents[slot.thread as usize]
+            {
+                return Ok(HeaderCheckOutcome::Discard(format!(
+                    "endorsement {} does not endorse the parent in thread {} at slot {}",
+                    endorsement.id, slot.thread, slot
+                )));
+            }
+            if !endorsement_indices.insert(endorsement.content.index) {
+                return Ok(HeaderCheckOutcome::Discard(format!(
+                    "duplicate endorsement index {}",
+                    endorsement.content.index
+                )));
+            }
+            if selection
+                .endorsements
+                .get(endorsement.content.index as usize)
+                != Some(&endorsement.content_creator_address)
+            {
+                return Ok(HeaderCheckOutcome::Discard(format!(
+                    "endorser draw mismatch for header in slot: {}",
+                    slot
+                )));
+            }
+            if let Err(err) = endorsement.verify_signature() {
+                return Ok(HeaderCheckOutcome::Discard(format!(
+                    "invalid endorsement signature: {}",
+                    err
+                )));
+            }
+        }
+
+        // list parents and ensure they are present
+        let parent_set: PreHashSet<BlockId> = header.content.parents.iter().copied().collect();
+        let mut parents: Vec<(BlockId, u64)> = Vec::with_capacity(thread_count as usize);
+        let mut incomp = PreHashSet::<BlockId>::default();
+        let mut missing_deps = false;
+        for (parent_thread, parent_id) in header.content.parents.iter().enumerate() {
+            if self.discarded_blocks.contains(parent_id) {
+                return Ok(HeaderCheckOutcome::Discard(format!(
+                    "parent {} was discarded",
+                    parent_id
+                )));
+            }
+            let parent = match self.active_blocks.get(parent_id) {
+                Some(parent) => parent,
+                None => {
+                    missing_deps = true;
+                    continue;
+                }
+            };
+            // check that the parent is from an earlier slot in the right thread
+            if parent.slot.thread as usize != parent_thread || parent.slot >= slot {
+                return Ok(HeaderCheckOutcome::Discard(format!(
+                    "Bad parent {} in thread:{} or slot:{} for {}.",
+                    parent_id, parent_thread, parent.slot, slot
+                )));
+            }
+            // inherit parent incompatibilities
+            // and ensure parents are mutually compatible
+            if let Some(p_incomp) = self.gi_head.get(parent_id) {
+                if !p_incomp.is_disjoint(&parent_set) {
+                    return Ok(HeaderCheckOutcome::Discard(
+                        "Parent not mutually compatible".to_string(),
+                    ));
+                }
+                incomp.extend(p_incomp);
+            }
+            parents.push((*parent_id, parent.slot.period));
+        }
+        if missing_deps {
+            return Ok(HeaderCheckOutcome::Wait);
+        }
+        let inherited_incomp_count = incomp.len();
+
+        // check the topological consistency of the parents
+        match block_graph::check_parents_topology(&self.active_blocks, &parents, |id, _| {
+            // the parents of the checkpoint blocks are unknown
+            self.checkpoint_blocks.contains(id)
+        })? {
+            ParentsTopology::Consistent => {}
+            ParentsTopology::Invalid(reason) => return Ok(HeaderCheckOutcome::Discard(reason)),
+            // this grandpa is missing, assume stale
+            ParentsTopology::MissingGrandparent(_) => {
+                return Ok(HeaderCheckOutcome::Discard("stale".to_string()))
+            }
+        }
+
+        // incompatibility test
+        let incomp = match block_graph::compute_incompatibilities(
+            &self.active_blocks,
+            slot,
+            &parents,
+            incomp,
+            thread_count,
+        )? {
+            Incompatibilities::Proceed(incomp) => incomp,
+            Incompatibilities::IncompatibleWithParent => {
+                return Ok(HeaderCheckOutcome::Discard(
+                    "Block incompatible with a parent".to_string(),
+                ))
+            }
+            Incompatibilities::IncompatibleWithFinal => {
+                return Ok(HeaderCheckOutcome::Discard("stale".to_string()))
+            }
+        };
+
+        Ok(HeaderCheckOutcome::Proceed {
+            parents_hash_period: parents,
+            incompatibilities: incomp,
+            inherited_incompatibilities_count: inherited_incomp_count,
+        })
+    }
+
+    fn add_header_to_graph(
+        &mut self,
+        header: SecuredHeader,
+        parents_hash_period: Vec<(BlockId, u64)>,
+        incomp: PreHashSet<BlockId>,
+        inherited_incomp_count: usize,
+    ) -> LightClientResult<()> {
+        let add_block_id = header.id;
+        let add_block_slot = header.content.slot;
+
+        // add as child to parents, and as descendant to non-final ancestors
+        let parent_ids: Vec<BlockId> = parents_hash_period.iter().map(|(id, _)| *id).collect();
+        block_graph::insert_parents_descendants(
+            &mut self.active_blocks,
+            add_block_id,
+            add_block_slot,
+            &parent_ids,
+        );
+
+        let same_thread_parent_creator = self
+            .active_blocks
+            .get(&parents_hash_period[add_block_slot.thread as usize].0)
+            .map(|parent| parent.creator_address);
+        self.active_blocks.insert(
+            add_block_id,
+            ActiveBlock {
+                creator_address: header.content_creator_address,
+                block_id: add_block_id,
+                parents: parents_hash_period,
+                children: vec![Default::default(); self.config.thread_count as usize],
+                descendants: Default::default(),
+                is_final: false,
+                slot: add_block_slot,
+                fitness: header.get_fitness(),
+                same_thread_parent_creator,
+            },
+        );
+        self.headers.insert(add_block_id, header);
+
+        // add incompatibilities to gi_head and update the max cliques
+        block_graph::add_incompatibilities(
+            &mut self.gi_head,
+            &mut self.max_cliques,
+            add_block_id,
+            incomp,
+            inherited_incomp_count,
+        )?;
+
+        // compute clique fitnesses and find blockclique
+        let position_blockclique = block_graph::compute_fitness_find_blockclique(
+            &mut self.max_cliques,
+            &self.active_blocks,
+        )?;
+
+        // list and remove stale blocks
+        let fitness_threshold = self.max_cliques[position_blockclique]
+            .fitness
+            .saturating_sub(self.config.delta_f0);
+        let stale_blocks = block_graph::list_stale_blocks(&self.max_cliques, fitness_threshold);
+        self.max_cliques.retain(|c| c.fitness >= fitness_threshold);
+        for stale_block_id in stale_blocks.into_iter() {
+            self.remove_block(&stale_block_id);
+        }
+
+        // list and mark final blocks
+        let final_blocks = block_graph::list_final_blocks(
+            &self.max_cliques,
+            &self.active_blocks,
+            self.config.delta_f0,
+        )?;
+        self.mark_final_blocks(final_blocks)?;
+
+        self.prune();
+        Ok(())
+    }
+
+    /// Removes a stale block from the graph
+    fn remove_block(&mut self, block_id: &BlockId) {
+        let active_block = match self.active_blocks.remove(block_id) {
+            Some(active_block) => active_block,
+            None => return,
+        };
+        self.headers.remove(block_id);
+
+        // remove from gi_head and cliques
+        block_graph::remove_from_cliques(
+            &mut self.gi_head,
+            &mut self.max_cliques,
+            block_id,
+            active_block.fitness,
+        );
+
+        // remove from parent's children
+        for (parent_id, _) in active_block.parents.iter() {
+            if let Some(parent) = self.active_blocks.get_mut(parent_id) {
+                parent.children[active_block.slot.thread as usize].remove(block_id);
+            }
+        }
+
+        debug!("light client block {} became stale", block_id);
+        self.discard_block(*block_id);
+    }
+
+    fn mark_final_blocks(&mut self, final_blocks: PreHashSet<BlockId>) -> LightClientResult<()> {
+        for block_id in final_blocks.into_iter() {
+            let final_block = self.active_blocks.get_mut(&block_id).ok_or_else(|| {
+                LightClientError::ContainerInconsistency(format!(
+                    "missing final block {}",
+                    block_id
+                ))
+            })?;
+            final_block.is_final = true;
+
+            // remove from gi_head and cliques
+            block_graph::remove_from_cliques(
+                &mut self.gi_head,
+                &mut self.max_cliques,
+                &block_id,
+                final_block.fitness,
+            );
+
+            // update latest final blocks
+            let thread = final_block.slot.thread as usize;
+            if final_block.slot.period > self.latest_final_blocks_periods[thread].1 {
+                self.latest_final_blocks_periods[thread] = (block_id, final_block.slot.period);
+            }
+            self.new_final_blocks.push(block_id);
+        }
+        Ok(())
+    }
+
+    /// Drops the old final blocks, and the waiting headers and draws that became stale
+    fn prune(&mut self) {
+        // the latest final blocks and their parents are required to check the next headers
+        let mut required: PreHashSet<BlockId> = PreHashSet::default();
+        for (latest_final_id, _) in self.latest_final_blocks_periods.iter() {
+            required.insert(*latest_final_id);
+            if let Some(a_block) = self.active_blocks.get(latest_final_id) {
+                required.extend(a_block.parents.iter().map(|(id, _)| *id));
+            }
+        }
+        let to_remove: Vec<BlockId> = self
+            .active_blocks
+            .iter()
+            .filter(|(id, a_block)| {
+                let latest_final_period =
+                    self.latest_final_blocks_periods[a_block.slot.thread as usize].1;
+                a_block.is_final
+                    && !required.contains(*id)
+                    && a_block.slot.period
+                        < latest_final_period.saturating_sub(self.config.force_keep_final_periods)
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        for block_id in to_remove {
+            if let Some(a_block) = self.active_blocks.remove(&block_id) {
+                for (parent_id, _) in a_block.parents.iter() {
+                    if let Some(parent) = self.active_blocks.get_mut(parent_id) {
+                        parent.children[a_block.slot.thread as usize].remove(&block_id);
+                    }
+                }
+            }
+            self.headers.remove(&block_id);
+            self.checkpoint_blocks.remove(&block_id);
+        }
+
+        let latest_final_blocks_periods = &self.latest_final_blocks_periods;
+        let is_stale =
+            |slot: &Slot| slot.period <= latest_final_blocks_periods[slot.thread as usize].1;
+        self.waiting_headers
+            .retain(|_, header| !is_stale(&header.content.slot));
+        self.draws.retain(|slot, _| !is_stale(slot));
+    }
+}
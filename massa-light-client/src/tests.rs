@@ -0,0 +1,265 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use std::collections::BTreeMap;
+
+use massa_hash::Hash;
+use massa_models::{
+    address::Address,
+    block_header::{BlockHeader, BlockHeaderSerializer, SecuredHeader},
+    block_id::BlockId,
+    endorsement::{Endorsement, EndorsementSerializer, SecureShareEndorsement},
+    prehash::PreHashSet,
+    secure_share::SecureShareContent,
+    slot::Slot,
+};
+use massa_pos_exports::Selection;
+use massa_signature::KeyPair;
+
+use crate::{HeaderOutcome, LightClient, LightClientCheckpoint, LightClientConfig};
+
+const THREAD_COUNT: u8 = 2;
+
+fn light_client_config() -> LightClientConfig {
+    LightClientConfig {
+        thread_count: THREAD_COUNT,
+        endorsement_count: 2,
+        delta_f0: 2,
+        force_keep_final_periods: 2,
+        max_waiting_headers: 10,
+        max_discarded_blocks: 10,
+    }
+}
+
+fn create_header(
+    keypair: &KeyPair,
+    slot: Slot,
+    parents: Vec<BlockId>,
+    endorsements: Vec<SecureShareEndorsement>,
+) -> SecuredHeader {
+    BlockHeader::new_verifiable(
+        BlockHeader {
+            current_version: 0,
+            announced_version: None,
+            slot,
+            parents,
+            operation_merkle_root: Hash::compute_from(&[]),
+            endorsements,
+            denunciations: Vec::new(),
+        },
+        BlockHeaderSerializer::new(),
+        keypair,
+    )
+    .unwrap()
+}
+
+/// header of `slot` with the given parents, endorsing the parent in its own thread
+fn create_endorsed_header(keypair: &KeyPair, slot: Slot, parents: Vec<BlockId>) -> SecuredHeader {
+    let endorsement = Endorsement::new_verifiable(
+        Endorsement {
+            slot,
+            index: 0,
+            endorsed_block: parents[slot.thread as usize],
+        },
+        EndorsementSerializer::new(),
+        keypair,
+    )
+    .unwrap();
+    create_header(keypair, slot, parents, vec![endorsement])
+}
+
+fn draws(producer: Address, periods: std::ops::Range<u64>) -> BTreeMap<Slot, Selection> {
+    periods
+        .flat_map(|period| (0..THREAD_COUNT).map(move |thread| Slot::new(period, thread)))
+        .map(|slot| {
+            (
+                slot,
+                Selection {
+                    endorsements: vec![producer; 2],
+                    producer,
+                },
+            )
+        })
+        .collect()
+}
+
+fn start_light_client(keypair: &KeyPair, draw_periods: std::ops::Range<u64>) -> LightClient {
+    let address = Address::from_public_key(&keypair.get_public_key());
+    let final_headers = (0..THREAD_COUNT)
+        .map(|thread| create_header(keypair, Slot::new(0, thread), Vec::new(), Vec::new()))
+        .collect();
+    LightClient::new(
+        light_client_config(),
+        LightClientCheckpoint {
+            final_headers,
+            draws: draws(address, draw_periods),
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_light_client_finality() {
+    let keypair = KeyPair::generate(0).unwrap();
+    let mut light_client = start_light_client(&keypair, 1..10);
+    let mut best_parents: Vec<BlockId> = light_client
+        .get_latest_final_blocks_periods()
+        .iter()
+        .map(|(id, _)| *id)
+        .collect();
+    let mut chain = Vec::new();
+    for period in 1..6 {
+        for thread in 0..THREAD_COUNT {
+            let header =
+                create_endorsed_header(&keypair, Slot::new(period, thread), best_parents.clone());
+            best_parents[thread as usize] = header.id;
+            chain.push(header.id);
+            assert_eq!(
+                light_client.process_header(header).unwrap(),
+                HeaderOutcome::Added
+            );
+        }
+    }
+
+    // every block of the chain is in the blockclique
+    let blockclique = light_client.get_blockclique();
+    let non_final: Vec<&BlockId> = chain
+        .iter()
+        .filter(|id| light_client.is_final(id) == Some(false))
+        .collect();
+    assert!(non_final.iter().all(|id| blockclique.contains(id)));
+
+    // the first blocks became final as their descendants accumulated fitness
+    let new_final_blocks = light_client.take_new_final_blocks();
+    assert!(new_final_blocks.contains(&chain[0]));
+    assert!(new_final_blocks.contains(&chain[1]));
+    assert_eq!(light_client.is_final(&chain[0]), Some(true));
+    assert!(light_client.is_slot_final(&Slot::new(1, 0)));
+    assert!(!light_client.is_slot_final(&Slot::new(5, 1)));
+    assert_eq!(light_client.is_final(&chain[chain.len() - 1]), Some(false));
+    assert!(light_client.take_new_final_blocks().is_empty());
+}
+
+#[test]
+fn test_light_client_header_checks() {
+    let keypair = KeyPair::generate(0).unwrap();
+    let other_keypair = KeyPair::generate(0).unwrap();
+    let mut light_client = start_light_client(&keypair, 1..2);
+    let genesis: Vec<BlockId> = light_client
+        .get_latest_final_blocks_periods()
+        .iter()
+        .map(|(id, _)| *id)
+        .collect();
+
+    // the producer was not drawn for that slot
+    let header = create_header(&other_keypair, Slot::new(1, 0), genesis.clone(), Vec::new());
+    assert!(matches!(
+        light_client.process_header(header).unwrap(),
+        HeaderOutcome::Discarded(_)
+    ));
+
+    // the endorser was not drawn for that slot
+    let endorsement = Endorsement::new_verifiable(
+        Endorsement {
+            slot: Slot::new(1, 0),
+            index: 1,
+            endorsed_block: genesis[0],
+        },
+        EndorsementSerializer::new(),
+        &other_keypair,
+    )
+    .unwrap();
+    let header = create_header(
+        &keypair,
+        Slot::new(1, 0),
+        genesis.clone(),
+        vec![endorsement],
+    );
+    assert!(matches!(
+        light_client.process_header(header).unwrap(),
+        HeaderOutcome::Discarded(_)
+    ));
+
+    // the child arrives before its parent
+    let parent = create_endorsed_header(&keypair, Slot::new(1, 0), genesis.clone());
+    let child = create_endorsed_header(&keypair, Slot::new(1, 1), vec![parent.id, genesis[1]]);
+    let (parent_id, child_id) = (parent.id, child.id);
+    assert_eq!(
+        light_client.process_header(child.clone()).unwrap(),
+        HeaderOutcome::Waiting
+    );
+    assert_eq!(
+        light_client.process_header(child).unwrap(),
+        HeaderOutcome::Known
+    );
+    assert_eq!(
+        light_client.process_header(parent).unwrap(),
+        HeaderOutcome::Added
+    );
+    assert!(light_client.get_header(&child_id).is_some());
+
+    // the draws of the slot are not known yet
+    let header = create_endorsed_header(&keypair, Slot::new(2, 0), vec![parent_id, child_id]);
+    let header_id = header.id;
+    assert_eq!(
+        light_client.process_header(header).unwrap(),
+        HeaderOutcome::Waiting
+    );
+    let address = Address::from_public_key(&keypair.get_public_key());
+    light_client.add_draws(draws(address, 2..3)).unwrap();
+    assert_eq!(light_client.is_final(&header_id), Some(false));
+}
+
+#[test]
+fn test_light_client_sync_needs() {
+    let keypair = KeyPair::generate(0).unwrap();
+    let address = Address::from_public_key(&keypair.get_public_key());
+
+    // without draws, the draws following the checkpoint are missing
+    let mut light_client = start_light_client(&keypair, 0..0);
+    assert_eq!(
+        light_client.next_missing_draws_slot().unwrap(),
+        Slot::new(0, 1)
+    );
+    light_client.add_draws(draws(address, 1..3)).unwrap();
+    assert_eq!(
+        light_client.next_missing_draws_slot().unwrap(),
+        Slot::new(3, 0)
+    );
+    assert_eq!(
+        light_client.get_selection(&Slot::new(2, 1)),
+        Some(&Selection {
+            endorsements: vec![address; 2],
+            producer: address,
+        })
+    );
+    assert_eq!(
+        light_client
+            .get_selections_in_range(Slot::new(2, 1)..=Slot::new(5, 0))
+            .len(),
+        1
+    );
+
+    // a header with an unknown parent waits for it, and the parent is reported missing
+    let genesis: Vec<BlockId> = light_client
+        .get_latest_final_blocks_periods()
+        .iter()
+        .map(|(id, _)| *id)
+        .collect();
+    let parent = create_endorsed_header(&keypair, Slot::new(1, 0), genesis.clone());
+    let child = create_endorsed_header(&keypair, Slot::new(1, 1), vec![parent.id, genesis[1]]);
+    assert_eq!(
+        light_client.process_header(child).unwrap(),
+        HeaderOutcome::Waiting
+    );
+    assert_eq!(light_client.get_waiting_headers_count(), 1);
+    assert_eq!(
+        light_client.get_missing_parents(),
+        [parent.id].into_iter().collect::<PreHashSet<BlockId>>()
+    );
+    assert_eq!(
+        light_client.process_header(parent).unwrap(),
+        HeaderOutcome::Added
+    );
+    assert_eq!(light_client.get_waiting_headers_count(), 0);
+    assert!(light_client.get_missing_parents().is_empty());
+}
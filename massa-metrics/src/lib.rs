@@ -13,7 +13,9 @@ use std::{
 };
 
 use lazy_static::lazy_static;
-use prometheus::{register_int_gauge, Gauge, Histogram, IntCounter, IntGauge};
+use prometheus::{
+    register_int_gauge, Gauge, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts,
+};
 use tokio::sync::oneshot::Sender;
 use tracing::warn;
 
@@ -115,11 +117,23 @@ pub struct MassaMetrics {
     protocol_tester_success: IntCounter,
     /// number of times we failed to test someone
     protocol_tester_failed: IntCounter,
+    /// number of compact block operation lists we could not rebuild
+    protocol_compact_block_fallbacks: IntCounter,
 
     /// know peers in protocol
     protocol_known_peers: IntGauge,
     /// banned peers in protocol
     protocol_banned_peers: IntGauge,
+    /// messages received in protocol, per message type
+    protocol_messages_received: IntCounterVec,
+    /// bytes received in protocol, per message type
+    protocol_bytes_received: IntCounterVec,
+    /// messages dropped or delayed by the protocol rate limiter, per message type
+    protocol_messages_rate_limited: IntCounterVec,
+    /// messages sent in protocol, per message type
+    protocol_messages_sent: IntCounterVec,
+    /// bytes sent in protocol, per message type
+    protocol_bytes_sent: IntCounterVec,
 
     /// executed final slot
     executed_final_slot: IntCounter,
@@ -169,6 +183,22 @@ pub struct MassaMetrics {
     final_cursor_thread: IntGauge,
     final_cursor_period: IntGauge,
 
+    // module cache
+    module_cache_lru_hits: IntGauge,
+    module_cache_lru_misses: IntGauge,
+    module_cache_hd_hits: IntGauge,
+    module_cache_hd_misses: IntGauge,
+    module_cache_compilations: IntGauge,
+    module_cache_compilation_time: IntGauge,
+
+    // execution profiling
+    execution_slot_time: Histogram,
+    execution_slot_gas: Histogram,
+    execution_operation_time: HistogramVec,
+    execution_operation_gas: HistogramVec,
+    execution_async_messages_time: Histogram,
+    execution_async_messages_gas: Histogram,
+
     // peer bandwidth (bytes sent, bytes received)
     peers_bandwidth: Arc<RwLock<HashMap<String, (IntCounter, IntCounter)>>>,
 
@@ -232,6 +262,11 @@ impl MassaMetrics {
             "number of times we failed to test someone",
         )
         .unwrap();
+        let protocol_compact_block_fallbacks = IntCounter::new(
+            "protocol_compact_block_fallbacks",
+            "number of compact block operation lists we could not rebuild",
+        )
+        .unwrap();
 
         // pool
         let operations_pool = IntGauge::new(
@@ -292,6 +327,47 @@ impl MassaMetrics {
         )
         .unwrap();
 
+        let protocol_messages_received = IntCounterVec::new(
+            Opts::new(
+                "protocol_messages_received",
+                "number of messages received in protocol",
+            ),
+            &["message_type"],
+        )
+        .unwrap();
+        let protocol_bytes_received = IntCounterVec::new(
+            Opts::new(
+                "protocol_bytes_received",
+                "number of bytes received in protocol messages",
+            ),
+            &["message_type"],
+        )
+        .unwrap();
+        let protocol_messages_rate_limited = IntCounterVec::new(
+            Opts::new(
+                "protocol_messages_rate_limited",
+                "number of protocol messages dropped or delayed by the per peer rate limiter",
+            ),
+            &["message_type"],
+        )
+        .unwrap();
+        let protocol_messages_sent = IntCounterVec::new(
+            Opts::new(
+                "protocol_messages_sent",
+                "number of messages sent in protocol",
+            ),
+            &["message_type"],
+        )
+        .unwrap();
+        let protocol_bytes_sent = IntCounterVec::new(
+            Opts::new(
+                "protocol_bytes_sent",
+                "number of bytes sent in protocol messages",
+            ),
+            &["message_type"],
+        )
+        .unwrap();
+
         // active cursor
         let active_cursor_thread =
             IntGauge::new("active_cursor_thread", "execution active cursor thread").unwrap();
@@ -304,6 +380,38 @@ impl MassaMetrics {
         let final_cursor_period =
             IntGauge::new("final_cursor_period", "execution final cursor period").unwrap();
 
+        // module cache
+        let module_cache_lru_hits = IntGauge::new(
+            "module_cache_lru_hits",
+            "number of modules found in the module cache RAM",
+        )
+        .unwrap();
+        let module_cache_lru_misses = IntGauge::new(
+            "module_cache_lru_misses",
+            "number of modules missing from the module cache RAM",
+        )
+        .unwrap();
+        let module_cache_hd_hits = IntGauge::new(
+            "module_cache_hd_hits",
+            "number of modules found in the module cache HD",
+        )
+        .unwrap();
+        let module_cache_hd_misses = IntGauge::new(
+            "module_cache_hd_misses",
+            "number of modules missing from the module cache HD",
+        )
+        .unwrap();
+        let module_cache_compilations = IntGauge::new(
+            "module_cache_compilations",
+            "number of modules compiled by the module cache",
+        )
+        .unwrap();
+        let module_cache_compilation_time = IntGauge::new(
+            "module_cache_compilation_time",
+            "total time spent compiling modules in microseconds",
+        )
+        .unwrap();
+
         // active connections IN
         let active_in_connections =
             IntGauge::new("active_in_connections", "active connections IN len").unwrap();
@@ -406,6 +514,62 @@ impl MassaMetrics {
         )
         .unwrap();
 
+        // execution profiling
+        let time_buckets = vec![
+            0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0,
+        ];
+        let gas_buckets = prometheus::exponential_buckets(100_000.0, 4.0, 12).unwrap();
+        let execution_slot_time = Histogram::with_opts(
+            prometheus::HistogramOpts::new(
+                "execution_slot_time",
+                "wall time in seconds of the slot executions",
+            )
+            .buckets(time_buckets.clone()),
+        )
+        .unwrap();
+        let execution_slot_gas = Histogram::with_opts(
+            prometheus::HistogramOpts::new(
+                "execution_slot_gas",
+                "gas spent by the slot executions",
+            )
+            .buckets(gas_buckets.clone()),
+        )
+        .unwrap();
+        let execution_operation_time = HistogramVec::new(
+            prometheus::HistogramOpts::new(
+                "execution_operation_time",
+                "wall time in seconds of the operation executions",
+            )
+            .buckets(time_buckets.clone()),
+            &["op_type"],
+        )
+        .unwrap();
+        let execution_operation_gas = HistogramVec::new(
+            prometheus::HistogramOpts::new(
+                "execution_operation_gas",
+                "gas spent by the operation executions",
+            )
+            .buckets(gas_buckets.clone()),
+            &["op_type"],
+        )
+        .unwrap();
+        let execution_async_messages_time = Histogram::with_opts(
+            prometheus::HistogramOpts::new(
+                "execution_async_messages_time",
+                "wall time in seconds of the asynchronous message batch executions",
+            )
+            .buckets(time_buckets),
+        )
+        .unwrap();
+        let execution_async_messages_gas = Histogram::with_opts(
+            prometheus::HistogramOpts::new(
+                "execution_async_messages_gas",
+                "gas spent by the asynchronous message batch executions",
+            )
+            .buckets(gas_buckets),
+        )
+        .unwrap();
+
         let mut stopper = MetricsStopper::default();
 
         if enabled {
@@ -415,6 +579,12 @@ impl MassaMetrics {
                 let _ = prometheus::register(Box::new(final_cursor_period.clone()));
                 let _ = prometheus::register(Box::new(active_cursor_thread.clone()));
                 let _ = prometheus::register(Box::new(active_cursor_period.clone()));
+                let _ = prometheus::register(Box::new(module_cache_lru_hits.clone()));
+                let _ = prometheus::register(Box::new(module_cache_lru_misses.clone()));
+                let _ = prometheus::register(Box::new(module_cache_hd_hits.clone()));
+                let _ = prometheus::register(Box::new(module_cache_hd_misses.clone()));
+                let _ = prometheus::register(Box::new(module_cache_compilations.clone()));
+                let _ = prometheus::register(Box::new(module_cache_compilation_time.clone()));
                 let _ = prometheus::register(Box::new(active_out_connections.clone()));
                 let _ = prometheus::register(Box::new(block_cache_blocks_known_by_peer.clone()));
                 let _ = prometheus::register(Box::new(block_cache_checked_headers_size.clone()));
@@ -441,6 +611,11 @@ impl MassaMetrics {
                 let _ = prometheus::register(Box::new(rolls.clone()));
                 let _ = prometheus::register(Box::new(know_peers.clone()));
                 let _ = prometheus::register(Box::new(banned_peers.clone()));
+                let _ = prometheus::register(Box::new(protocol_messages_received.clone()));
+                let _ = prometheus::register(Box::new(protocol_bytes_received.clone()));
+                let _ = prometheus::register(Box::new(protocol_messages_rate_limited.clone()));
+                let _ = prometheus::register(Box::new(protocol_messages_sent.clone()));
+                let _ = prometheus::register(Box::new(protocol_bytes_sent.clone()));
                 let _ = prometheus::register(Box::new(executed_final_slot.clone()));
                 let _ = prometheus::register(Box::new(executed_final_slot_with_block.clone()));
                 let _ = prometheus::register(Box::new(active_history.clone()));
@@ -453,11 +628,18 @@ impl MassaMetrics {
                 let _ = prometheus::register(Box::new(denunciations_pool.clone()));
                 let _ = prometheus::register(Box::new(protocol_tester_success.clone()));
                 let _ = prometheus::register(Box::new(protocol_tester_failed.clone()));
+                let _ = prometheus::register(Box::new(protocol_compact_block_fallbacks.clone()));
                 let _ = prometheus::register(Box::new(sc_messages_final.clone()));
                 let _ = prometheus::register(Box::new(async_message_pool_size.clone()));
                 let _ = prometheus::register(Box::new(current_time_period.clone()));
                 let _ = prometheus::register(Box::new(current_time_thread.clone()));
                 let _ = prometheus::register(Box::new(block_slot_delay.clone()));
+                let _ = prometheus::register(Box::new(execution_slot_time.clone()));
+                let _ = prometheus::register(Box::new(execution_slot_gas.clone()));
+                let _ = prometheus::register(Box::new(execution_operation_time.clone()));
+                let _ = prometheus::register(Box::new(execution_operation_gas.clone()));
+                let _ = prometheus::register(Box::new(execution_async_messages_time.clone()));
+                let _ = prometheus::register(Box::new(execution_async_messages_gas.clone()));
 
                 stopper = server::bind_metrics(addr);
             }
@@ -483,8 +665,14 @@ impl MassaMetrics {
                 bootstrap_peers_failed: bootstrap_failed,
                 protocol_tester_success,
                 protocol_tester_failed,
+                protocol_compact_block_fallbacks,
                 protocol_known_peers: know_peers,
                 protocol_banned_peers: banned_peers,
+                protocol_messages_received,
+                protocol_bytes_received,
+                protocol_messages_rate_limited,
+                protocol_messages_sent,
+                protocol_bytes_sent,
                 executed_final_slot,
                 executed_final_slot_with_block,
                 peernet_total_bytes_received,
@@ -512,6 +700,18 @@ impl MassaMetrics {
                 active_cursor_period,
                 final_cursor_thread,
                 final_cursor_period,
+                module_cache_lru_hits,
+                module_cache_lru_misses,
+                module_cache_hd_hits,
+                module_cache_hd_misses,
+                module_cache_compilations,
+                module_cache_compilation_time,
+                execution_slot_time,
+                execution_slot_gas,
+                execution_operation_time,
+                execution_operation_gas,
+                execution_async_messages_time,
+                execution_async_messages_gas,
                 peers_bandwidth: Arc::new(RwLock::new(HashMap::new())),
                 tick_delay,
             },
@@ -547,6 +747,43 @@ impl MassaMetrics {
         self.final_cursor_period.set(period as i64);
     }
 
+    pub fn set_module_cache_stats(
+        &self,
+        lru_hits: u64,
+        lru_misses: u64,
+        hd_hits: u64,
+        hd_misses: u64,
+        compilations: u64,
+        compilation_time_micros: u64,
+    ) {
+        self.module_cache_lru_hits.set(lru_hits as i64);
+        self.module_cache_lru_misses.set(lru_misses as i64);
+        self.module_cache_hd_hits.set(hd_hits as i64);
+        self.module_cache_hd_misses.set(hd_misses as i64);
+        self.module_cache_compilations.set(compilations as i64);
+        self.module_cache_compilation_time
+            .set(compilation_time_micros as i64);
+    }
+
+    pub fn observe_execution_slot(&self, time_secs: f64, gas: u64) {
+        self.execution_slot_time.observe(time_secs);
+        self.execution_slot_gas.observe(gas as f64);
+    }
+
+    pub fn observe_execution_operation(&self, op_type: &str, time_secs: f64, gas: u64) {
+        self.execution_operation_time
+            .with_label_values(&[op_type])
+            .observe(time_secs);
+        self.execution_operation_gas
+            .with_label_values(&[op_type])
+            .observe(gas as f64);
+    }
+
+    pub fn observe_execution_async_messages(&self, time_secs: f64, gas: u64) {
+        self.execution_async_messages_time.observe(time_secs);
+        self.execution_async_messages_gas.observe(gas as f64);
+    }
+
     pub fn set_consensus_period(&self, thread: usize, period: u64) {
         if let Some(g) = self.consensus_vec.get(thread) {
             g.set(period as f64);
@@ -614,6 +851,30 @@ impl MassaMetrics {
         self.peernet_total_bytes_sent.inc_by(diff);
     }
 
+    /// Update the protocol traffic counters of a message type
+    /// with the totals counted since the node started
+    pub fn set_protocol_messages_traffic(
+        &self,
+        message_type: &str,
+        messages_received: u64,
+        bytes_received: u64,
+        messages_rate_limited: u64,
+        messages_sent: u64,
+        bytes_sent: u64,
+    ) {
+        for (counter, new_value) in [
+            (&self.protocol_messages_received, messages_received),
+            (&self.protocol_bytes_received, bytes_received),
+            (&self.protocol_messages_rate_limited, messages_rate_limited),
+            (&self.protocol_messages_sent, messages_sent),
+            (&self.protocol_bytes_sent, bytes_sent),
+        ] {
+            let counter = counter.with_label_values(&[message_type]);
+            let diff = new_value.saturating_sub(counter.get());
+            counter.inc_by(diff);
+        }
+    }
+
     pub fn inc_operations_final_counter(&self, diff: u64) {
         self.operations_final_counter.inc_by(diff);
     }
@@ -670,6 +931,10 @@ impl MassaMetrics {
         self.protocol_tester_failed.inc();
     }
 
+    pub fn inc_protocol_compact_block_fallbacks(&self) {
+        self.protocol_compact_block_fallbacks.inc();
+    }
+
     pub fn set_stakers(&self, nb: usize) {
         self.stakers.set(nb as i64);
     }
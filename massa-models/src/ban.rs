@@ -0,0 +1,164 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::{block_id::BlockId, endorsement::EndorsementId, operation::OperationId};
+use massa_time::MassaTime;
+use serde::{Deserialize, Serialize};
+use std::fmt::Formatter;
+
+/// Why a peer was banned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BanReason {
+    /// the peer sent a header that failed the validity checks
+    InvalidHeader,
+    /// the peer knows about a block that was marked as invalid
+    InvalidBlock,
+    /// the peer knows about a block flagged as an attack attempt by consensus
+    AttackBlock,
+    /// the hash of the operation id list sent by the peer doesn't match the block header
+    OperationListHashMismatch,
+    /// the peer sent block operations that failed the validity checks
+    InvalidBlockOperations,
+    /// the peer sent endorsements that failed the validity checks
+    InvalidEndorsements,
+    /// the peer sent operations that failed the validity checks
+    InvalidOperations,
+    /// the peer kept sending more data than allowed by the rate limits
+    RateLimitExceeded,
+    /// the node operator asked for the ban
+    Manual,
+}
+
+impl std::fmt::Display for BanReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            BanReason::InvalidHeader => "invalid block header",
+            BanReason::InvalidBlock => "knows an invalid block",
+            BanReason::AttackBlock => "knows an attack block",
+            BanReason::OperationListHashMismatch => "operation list hash mismatch",
+            BanReason::InvalidBlockOperations => "invalid block operations",
+            BanReason::InvalidEndorsements => "invalid endorsements",
+            BanReason::InvalidOperations => "invalid operations",
+            BanReason::RateLimitExceeded => "rate limit exceeded",
+            BanReason::Manual => "manual ban",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+/// Component that issued a ban
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BanOrigin {
+    /// block retrieval handler
+    BlockRetrieval,
+    /// block propagation handler
+    BlockPropagation,
+    /// endorsement retrieval handler
+    EndorsementRetrieval,
+    /// operation retrieval handler
+    OperationRetrieval,
+    /// inbound traffic rate limiter
+    RateLimiter,
+    /// private API
+    Api,
+}
+
+impl std::fmt::Display for BanOrigin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let origin = match self {
+            BanOrigin::BlockRetrieval => "block retrieval",
+            BanOrigin::BlockPropagation => "block propagation",
+            BanOrigin::EndorsementRetrieval => "endorsement retrieval",
+            BanOrigin::OperationRetrieval => "operation retrieval",
+            BanOrigin::RateLimiter => "rate limiter",
+            BanOrigin::Api => "api",
+        };
+        write!(f, "{}", origin)
+    }
+}
+
+/// Object that motivated a ban
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BanEvidence {
+    /// a block
+    Block(BlockId),
+    /// an endorsement
+    Endorsement(EndorsementId),
+    /// an operation
+    Operation(OperationId),
+}
+
+impl std::fmt::Display for BanEvidence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BanEvidence::Block(block_id) => write!(f, "block {}", block_id),
+            BanEvidence::Endorsement(endorsement_id) => {
+                write!(f, "endorsement {}", endorsement_id)
+            }
+            BanEvidence::Operation(operation_id) => write!(f, "operation {}", operation_id),
+        }
+    }
+}
+
+/// Full description of why and by whom a ban was issued
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BanCause {
+    /// reason of the ban
+    pub reason: BanReason,
+    /// component that issued the ban
+    pub origin: BanOrigin,
+    /// optional object that motivated the ban
+    pub evidence: Option<BanEvidence>,
+}
+
+impl BanCause {
+    /// Create a new ban cause without evidence
+    pub fn new(reason: BanReason, origin: BanOrigin) -> Self {
+        BanCause {
+            reason,
+            origin,
+            evidence: None,
+        }
+    }
+
+    /// Attach an evidence to the ban cause
+    pub fn with_evidence(mut self, evidence: BanEvidence) -> Self {
+        self.evidence = Some(evidence);
+        self
+    }
+}
+
+impl std::fmt::Display for BanCause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (from {})", self.reason, self.origin)?;
+        if let Some(evidence) = &self.evidence {
+            write!(f, ", evidence: {}", evidence)?;
+        }
+        Ok(())
+    }
+}
+
+/// Information about an active ban
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BanInfo {
+    /// why and by whom the ban was issued
+    pub cause: BanCause,
+    /// when the ban was issued
+    pub banned_at: MassaTime,
+    /// when the ban will be lifted, `None` if it has to be lifted manually
+    pub expires_at: Option<MassaTime>,
+    /// number of times the peer has been banned, including this one
+    pub offense_count: u64,
+}
+
+impl std::fmt::Display for BanInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "\tCause: {}", self.cause)?;
+        writeln!(f, "\tBanned at: {}", self.banned_at.format_instant())?;
+        match self.expires_at {
+            Some(expires_at) => writeln!(f, "\tExpires at: {}", expires_at.format_instant())?,
+            None => writeln!(f, "\tExpires at: never (manual unban required)")?,
+        }
+        writeln!(f, "\tOffense count: {}", self.offense_count)?;
+        Ok(())
+    }
+}
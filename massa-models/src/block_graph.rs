@@ -0,0 +1,511 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Rules of the block graph used by the light client:
+//! parents topology, incompatibilities, max cliques, blockclique, staleness and finality.
+//!
+//! They follow the rules of the consensus worker (`massa_consensus_worker::state`),
+//! which keeps its own implementation: any change to the consensus rules must be mirrored here.
+//!
+//! The functions work on the active blocks through the `ActiveBlocks` trait,
+//! and on the incompatibility graph (`gi_head`) and max cliques of the non-final blocks.
+
+use std::collections::VecDeque;
+
+use displaydoc::Display;
+use massa_serialization::Serializer;
+use thiserror::Error;
+
+use crate::{
+    active_block::ActiveBlock,
+    block_id::{BlockId, BlockIdSerializer},
+    clique::{compute_max_cliques, Clique},
+    error::ModelsError,
+    prehash::{PreHashMap, PreHashSet},
+    slot::Slot,
+};
+
+/// Block graph error
+#[non_exhaustive]
+#[derive(Display, Error, Debug, Clone)]
+pub enum BlockGraphError {
+    /// there was an inconsistency between containers {0}
+    ContainerInconsistency(String),
+    /// missing block {0}
+    MissingBlock(String),
+    /// fitness overflow
+    FitnessOverflow,
+    /// models error: {0}
+    ModelsError(#[from] ModelsError),
+}
+
+/// Access to the active blocks of a block graph
+pub trait ActiveBlocks {
+    /// Gets an active block
+    fn get_active_block(&self, block_id: &BlockId) -> Option<&ActiveBlock>;
+
+    /// Gets a mutable reference on an active block
+    fn get_active_block_mut(&mut self, block_id: &BlockId) -> Option<&mut ActiveBlock>;
+
+    /// Iterates over all the active blocks
+    fn iter_active_blocks(&self) -> Box<dyn Iterator<Item = &ActiveBlock> + '_>;
+}
+
+impl ActiveBlocks for PreHashMap<BlockId, ActiveBlock> {
+    fn get_active_block(&self, block_id: &BlockId) -> Option<&ActiveBlock> {
+        self.get(block_id)
+    }
+
+    fn get_active_block_mut(&mut self, block_id: &BlockId) -> Option<&mut ActiveBlock> {
+        self.get_mut(block_id)
+    }
+
+    fn iter_active_blocks(&self) -> Box<dyn Iterator<Item = &ActiveBlock> + '_> {
+        Box::new(self.values())
+    }
+}
+
+/// Result of the topological check of the parents of a block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParentsTopology {
+    /// the parents are consistent
+    Consistent,
+    /// the parents are inconsistent for the given reason
+    Invalid(String),
+    /// a grandparent is not an active block
+    MissingGrandparent(BlockId),
+}
+
+/// Result of the incompatibility check of a block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Incompatibilities {
+    /// the block can be added with these incompatibilities
+    Proceed(PreHashSet<BlockId>),
+    /// the block is incompatible with one of its parents
+    IncompatibleWithParent,
+    /// the block is incompatible with a final block
+    IncompatibleWithFinal,
+}
+
+/// Checks the topological consistency of the parents of a block:
+/// a parent cannot be earlier than a block of its thread known by another parent.
+///
+/// # Arguments
+/// * `blocks`: active blocks of the graph, containing all the parents
+/// * `parents`: (id, period) of the parent of each thread
+/// * `is_root`: whether a parent has no known parents (genesis or checkpoint blocks)
+pub fn check_parents_topology(
+    blocks: &impl ActiveBlocks,
+    parents: &[(BlockId, u64)],
+    is_root: impl Fn(&BlockId, u64) -> bool,
+) -> Result<ParentsTopology, BlockGraphError> {
+    let thread_count = parents.len();
+    let mut gp_max_periods = vec![0u64; thread_count];
+    for (parent_i, (parent_id, parent_period)) in parents.iter().enumerate() {
+        let parent = blocks.get_active_block(parent_id).ok_or_else(|| {
+            BlockGraphError::MissingBlock(format!("parent {} is not active", parent_id))
+        })?;
+        if *parent_period < gp_max_periods[parent_i] {
+            // a parent is earlier than a block known by another parent in that thread
+            return Ok(ParentsTopology::Invalid(
+                "a parent is earlier than a block known by another parent in that thread"
+                    .to_string(),
+            ));
+        }
+        gp_max_periods[parent_i] = *parent_period;
+        if is_root(parent_id, *parent_period) {
+            continue;
+        }
+        for (gp_i, (gp_id, _)) in parent.parents.iter().enumerate() {
+            if gp_i == parent_i {
+                continue;
+            }
+            let Some(gp) = blocks.get_active_block(gp_id) else {
+                return Ok(ParentsTopology::MissingGrandparent(*gp_id));
+            };
+            if gp.slot.period > gp_max_periods[gp_i] {
+                if gp_i < parent_i {
+                    return Ok(ParentsTopology::Invalid(
+                        "grandpa error: gp_i < parent_i".to_string(),
+                    ));
+                }
+                gp_max_periods[gp_i] = gp.slot.period;
+            }
+        }
+    }
+    Ok(ParentsTopology::Consistent)
+}
+
+/// Lists the incompatibilities of a block that is not in the graph yet.
+///
+/// A block is incompatible with the non-ancestor blocks of the same slot (thread incompatibility)
+/// or of a slot at least `thread_count` slots away (grandpa incompatibility), and with their descendants.
+///
+/// # Arguments
+/// * `blocks`: active blocks of the graph
+/// * `slot`: slot of the block
+/// * `parents`: (id, period) of the parent of each thread
+/// * `incomp`: incompatibilities inherited from the parents
+/// * `thread_count`: number of threads
+pub fn compute_incompatibilities(
+    blocks: &impl ActiveBlocks,
+    slot: Slot,
+    parents: &[(BlockId, u64)],
+    mut incomp: PreHashSet<BlockId>,
+    thread_count: u8,
+) -> Result<Incompatibilities, BlockGraphError> {
+    // list all ancestors until we reach a final block (included)
+    let mut ancestor_ids = PreHashSet::<BlockId>::default();
+    let mut to_traverse: Vec<BlockId> = parents.iter().map(|(id, _)| *id).collect();
+    let mut earliest_visited_periods: Vec<u64> = parents.iter().map(|(_, p)| *p).collect();
+    while let Some(ancestor_id) = to_traverse.pop() {
+        if !ancestor_ids.insert(ancestor_id) {
+            // ancestor already visited
+            continue;
+        }
+        if let Some(ancestor) = blocks.get_active_block(&ancestor_id) {
+            earliest_visited_periods[ancestor.slot.thread as usize] = std::cmp::min(
+                earliest_visited_periods[ancestor.slot.thread as usize],
+                ancestor.slot.period,
+            );
+            // Continue traversing if not final.
+            // Note that we use an optimization:
+            // only traverse in the same thread,
+            // which is OK because of the parent time consistency check done before
+            if !ancestor.is_final {
+                if let Some((parent_id, _)) = ancestor.parents.get(ancestor.slot.thread as usize) {
+                    to_traverse.push(*parent_id);
+                }
+            }
+        }
+    }
+
+    // check incompatibilities with non-ancestors
+    for traversed_block in blocks.iter_active_blocks() {
+        // skip if the traversed block is an ancestor of the incoming block,
+        // or if it is before the earliest listed ancestors of that thread
+        if ancestor_ids.contains(&traversed_block.block_id)
+            || traversed_block.slot.period
+                < earliest_visited_periods[traversed_block.slot.thread as usize]
+        {
+            continue;
+        }
+
+        let incompatible = match slot.cmp(&traversed_block.slot) {
+            // the traversed block is at the same slot as the incoming block => they are incompatible
+            std::cmp::Ordering::Equal => true,
+            // the time interval between the traversed block and the incoming block is higher or equal to t0 => they are incompatible
+            std::cmp::Ordering::Greater => {
+                slot.slots_since(&traversed_block.slot, thread_count)? >= thread_count as u64
+            }
+            std::cmp::Ordering::Less => {
+                traversed_block.slot.slots_since(&slot, thread_count)? >= thread_count as u64
+            }
+        };
+
+        // if incompatible, add to incompatibilities and exit early if the incoming block is incompatible with a final block
+        if incompatible {
+            if traversed_block.is_final {
+                return Ok(Incompatibilities::IncompatibleWithFinal);
+            }
+            incomp.extend(get_active_block_and_descendants(
+                blocks,
+                &traversed_block.block_id,
+            )?);
+        }
+    }
+
+    if parents
+        .iter()
+        .any(|(parent_id, _)| incomp.contains(parent_id))
+    {
+        return Ok(Incompatibilities::IncompatibleWithParent);
+    }
+    if incomp.iter().any(|id| {
+        blocks
+            .get_active_block(id)
+            .map_or(false, |a_block| a_block.is_final)
+    }) {
+        return Ok(Incompatibilities::IncompatibleWithFinal);
+    }
+    Ok(Incompatibilities::Proceed(incomp))
+}
+
+/// Gets a block and all its descendants
+pub fn get_active_block_and_descendants(
+    blocks: &impl ActiveBlocks,
+    block_id: &BlockId,
+) -> Result<PreHashSet<BlockId>, BlockGraphError> {
+    let mut to_visit = vec![*block_id];
+    let mut result = PreHashSet::<BlockId>::default();
+    while let Some(visit_id) = to_visit.pop() {
+        if !result.insert(visit_id) {
+            continue; // already visited
+        }
+        let a_block = blocks.get_active_block(&visit_id).ok_or_else(|| {
+            BlockGraphError::ContainerInconsistency(format!(
+                "iterating through descendants of {} - missing {}",
+                block_id, visit_id
+            ))
+        })?;
+        a_block
+            .children
+            .iter()
+            .for_each(|thread_children| to_visit.extend(thread_children.keys()));
+    }
+    Ok(result)
+}
+
+/// Adds a new block as a child of its parents, and as a descendant of its non-final ancestors.
+/// Note: descendants are never removed.
+pub fn insert_parents_descendants(
+    blocks: &mut impl ActiveBlocks,
+    add_block_id: BlockId,
+    add_block_slot: Slot,
+    parents: &[BlockId],
+) {
+    // add as child to parents
+    for parent_id in parents.iter() {
+        if let Some(parent) = blocks.get_active_block_mut(parent_id) {
+            parent.children[add_block_slot.thread as usize]
+                .insert(add_block_id, add_block_slot.period);
+        }
+    }
+
+    // add as descendant to ancestors if not final
+    let mut ancestors: VecDeque<BlockId> = parents.iter().copied().collect();
+    let mut visited = PreHashSet::<BlockId>::default();
+    while let Some(ancestor_id) = ancestors.pop_back() {
+        if !visited.insert(ancestor_id) {
+            continue;
+        }
+        if let Some(ancestor) = blocks.get_active_block_mut(&ancestor_id) {
+            // No need to add descendants if ancestor is final,
+            // because only non-final active blocks are scanned for descendents for finality detection.
+            if ancestor.is_final {
+                continue;
+            }
+            ancestor.descendants.insert(add_block_id);
+            for (ancestor_parent_id, _) in ancestor.parents.iter() {
+                ancestors.push_front(*ancestor_parent_id);
+            }
+        }
+    }
+}
+
+/// Adds the incompatibilities of a new block to the incompatibility graph, and updates the max cliques.
+///
+/// # Arguments
+/// * `gi_head`: incompatibility graph of the non-final blocks
+/// * `max_cliques`: max cliques of compatible non-final blocks
+/// * `add_block_id`: block being added
+/// * `incomp`: incompatibilities of the block
+/// * `inherited_incomp_count`: number of incompatibilities inherited from its parents
+pub fn add_incompatibilities(
+    gi_head: &mut PreHashMap<BlockId, PreHashSet<BlockId>>,
+    max_cliques: &mut Vec<Clique>,
+    add_block_id: BlockId,
+    incomp: PreHashSet<BlockId>,
+    inherited_incomp_count: usize,
+) -> Result<(), BlockGraphError> {
+    for incomp_id in incomp.iter() {
+        gi_head
+            .get_mut(incomp_id)
+            .ok_or_else(|| {
+                BlockGraphError::MissingBlock(format!(
+                    "missing block when adding incomp to gi_head: {}",
+                    incomp_id
+                ))
+            })?
+            .insert(add_block_id);
+    }
+
+    if incomp.len() == inherited_incomp_count {
+        // clique optimization routine:
+        //   the block only has incompatibilities inherited from its parents
+        //   therefore it is not forking and can simply be added to the cliques it is compatible with
+        max_cliques
+            .iter_mut()
+            .filter(|c| incomp.is_disjoint(&c.block_ids))
+            .for_each(|c| {
+                c.block_ids.insert(add_block_id);
+            });
+        gi_head.insert(add_block_id, incomp);
+    } else {
+        // fully recompute max cliques
+        gi_head.insert(add_block_id, incomp);
+        *max_cliques = compute_max_cliques(gi_head)
+            .into_iter()
+            .map(|c| Clique {
+                block_ids: c,
+                fitness: 0,
+                is_blockclique: false,
+            })
+            .collect();
+    }
+    Ok(())
+}
+
+/// Computes the fitness of each clique and marks the clique of higher fitness as the blockclique.
+/// Ties are broken by the lowest sum of the block hashes.
+/// Returns the index of the blockclique.
+pub fn compute_fitness_find_blockclique(
+    max_cliques: &mut [Clique],
+    blocks: &impl ActiveBlocks,
+) -> Result<usize, BlockGraphError> {
+    let block_id_serializer = BlockIdSerializer::new();
+    let mut blockclique_i = 0usize;
+    let mut max_clique_fitness = (0u64, num::BigInt::default());
+    for (clique_i, clique) in max_cliques.iter_mut().enumerate() {
+        clique.fitness = 0;
+        clique.is_blockclique = false;
+        let mut sum_hash = num::BigInt::default();
+        for block_id in clique.block_ids.iter() {
+            let fitness = blocks
+                .get_active_block(block_id)
+                .ok_or_else(|| {
+                    BlockGraphError::ContainerInconsistency(format!(
+                        "computing clique fitness - missing {}",
+                        block_id
+                    ))
+                })?
+                .fitness;
+            clique.fitness = clique
+                .fitness
+                .checked_add(fitness)
+                .ok_or(BlockGraphError::FitnessOverflow)?;
+            let mut bytes = Vec::new();
+            block_id_serializer
+                .serialize(block_id, &mut bytes)
+                .map_err(ModelsError::from)?;
+            sum_hash -= num::BigInt::from_bytes_be(num::bigint::Sign::Plus, &bytes);
+        }
+        let cur_fit = (clique.fitness, sum_hash);
+        if cur_fit > max_clique_fitness {
+            blockclique_i = clique_i;
+            max_clique_fitness = cur_fit;
+        }
+    }
+    max_cliques[blockclique_i].is_blockclique = true;
+    Ok(blockclique_i)
+}
+
+/// Lists the blocks that only belong to cliques of fitness lower than `fitness_threshold`
+pub fn list_stale_blocks(max_cliques: &[Clique], fitness_threshold: u64) -> PreHashSet<BlockId> {
+    // iterate from largest to smallest to minimize reallocations
+    let mut indices: Vec<usize> = (0..max_cliques.len()).collect();
+    indices.sort_unstable_by_key(|&i| std::cmp::Reverse(max_cliques[i].block_ids.len()));
+    let mut high_set = PreHashSet::<BlockId>::default();
+    let mut low_set = PreHashSet::<BlockId>::default();
+    for clique_i in indices.into_iter() {
+        if max_cliques[clique_i].fitness >= fitness_threshold {
+            high_set.extend(&max_cliques[clique_i].block_ids);
+        } else {
+            low_set.extend(&max_cliques[clique_i].block_ids);
+        }
+    }
+    &low_set - &high_set
+}
+
+/// Lists the blocks that became final: the blocks of every clique whose descendants
+/// in a clique of fitness higher than `delta_f0` have a total fitness higher than `delta_f0`
+pub fn list_final_blocks(
+    max_cliques: &[Clique],
+    blocks: &impl ActiveBlocks,
+    delta_f0: u64,
+) -> Result<PreHashSet<BlockId>, BlockGraphError> {
+    // short-circuiting intersection of cliques from smallest to largest
+    let mut indices: Vec<usize> = (0..max_cliques.len()).collect();
+    indices.sort_unstable_by_key(|&i| max_cliques[i].block_ids.len());
+    let mut indices_iter = indices.iter();
+    let mut final_candidates = max_cliques
+        [*indices_iter.next().expect("expected at least one clique")]
+    .block_ids
+    .clone();
+    for i in indices_iter {
+        final_candidates.retain(|v| max_cliques[*i].block_ids.contains(v));
+        if final_candidates.is_empty() {
+            break;
+        }
+    }
+
+    // restrict search to cliques with high enough fitness, sort cliques by fitness (highest to lowest)
+    indices.retain(|&i| max_cliques[i].fitness > delta_f0);
+    indices.sort_unstable_by_key(|&i| std::cmp::Reverse(max_cliques[i].fitness));
+
+    let mut final_blocks = PreHashSet::<BlockId>::default();
+    for clique_i in indices.into_iter() {
+        // check in cliques from highest to lowest fitness
+        if final_candidates.is_empty() {
+            // no more final candidates
+            break;
+        }
+        let clique = &max_cliques[clique_i];
+
+        // compute the total fitness of all the descendants of the candidate within the clique
+        let loc_candidates = final_candidates.clone();
+        for candidate_id in loc_candidates.into_iter() {
+            let descendants = &blocks
+                .get_active_block(&candidate_id)
+                .ok_or_else(|| {
+                    BlockGraphError::MissingBlock(format!(
+                        "missing block when computing total fitness of descendants: {}",
+                        candidate_id
+                    ))
+                })?
+                .descendants;
+            let desc_fit: u64 = descendants
+                .intersection(&clique.block_ids)
+                .filter_map(|id| blocks.get_active_block(id).map(|a_block| a_block.fitness))
+                .sum();
+            if desc_fit > delta_f0 {
+                // candidate is final
+                final_candidates.remove(&candidate_id);
+                final_blocks.insert(candidate_id);
+            }
+        }
+    }
+    Ok(final_blocks)
+}
+
+/// Removes a block that became final or stale from the incompatibility graph and from the cliques
+pub fn remove_from_cliques(
+    gi_head: &mut PreHashMap<BlockId, PreHashSet<BlockId>>,
+    max_cliques: &mut Vec<Clique>,
+    block_id: &BlockId,
+    fitness: u64,
+) {
+    // remove from gi_head
+    if let Some(other_incomps) = gi_head.remove(block_id) {
+        for other_incomp in other_incomps.into_iter() {
+            if let Some(other_incomp_lst) = gi_head.get_mut(&other_incomp) {
+                other_incomp_lst.remove(block_id);
+            }
+        }
+    }
+
+    // remove from cliques
+    max_cliques.iter_mut().for_each(|c| {
+        if c.block_ids.remove(block_id) {
+            c.fitness -= fitness;
+        }
+    });
+    max_cliques.retain(|c| !c.block_ids.is_empty()); // remove empty cliques
+    if max_cliques.is_empty() {
+        // make sure at least one clique remains
+        *max_cliques = vec![Clique {
+            block_ids: PreHashSet::<BlockId>::default(),
+            fitness: 0,
+            is_blockclique: true,
+        }];
+    }
+}
+
+/// Gets the clique of higher fitness
+pub fn get_blockclique(max_cliques: &[Clique]) -> PreHashSet<BlockId> {
+    max_cliques
+        .iter()
+        .find(|c| c.is_blockclique)
+        .expect("blockclique missing")
+        .block_ids
+        .clone()
+}
@@ -84,6 +84,10 @@ lazy_static::lazy_static! {
         .parse()
         .unwrap()
     };
+    /// first node version able to reply to operation filters: the version that introduced them
+    pub static ref OPERATION_RECONCILIATION_MIN_VERSION: Version = *VERSION;
+    /// first node version able to answer compact block operation lists: the version that introduced them
+    pub static ref COMPACT_BLOCK_RELAY_MIN_VERSION: Version = *VERSION;
 }
 
 /// Helper function to parse args for lazy_static evaluations
@@ -255,6 +259,8 @@ pub const MAX_EVENT_DATA_SIZE: usize = 50_000;
 
 /// Max number of endorsements per message
 pub const MAX_ENDORSEMENTS_PER_MESSAGE: u32 = 1024;
+/// Max number of slot draws per message
+pub const MAX_DRAWS_PER_MESSAGE: u32 = 512;
 /// node send channel size
 pub const NODE_SEND_CHANNEL_SIZE: usize = 10_000;
 /// max duplex buffer size
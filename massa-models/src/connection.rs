@@ -0,0 +1,122 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::{node::NodeId, stats::PeerTrafficStats};
+use massa_time::MassaTime;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Formatter, net::SocketAddr};
+
+/// Transport used by a peer connection or listener
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConnectionTransport {
+    /// TCP
+    Tcp,
+    /// QUIC
+    Quic,
+}
+
+impl std::fmt::Display for ConnectionTransport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionTransport::Tcp => write!(f, "TCP"),
+            ConnectionTransport::Quic => write!(f, "QUIC"),
+        }
+    }
+}
+
+/// Last announcement received from a peer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerAnnouncementInfo {
+    /// time at which the peer created the announcement
+    pub timestamp: MassaTime,
+    /// listeners announced by the peer
+    pub listeners: BTreeMap<SocketAddr, ConnectionTransport>,
+}
+
+/// Detailed information about an active peer connection
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectionInfo {
+    /// id of the connected node
+    pub node_id: NodeId,
+    /// remote address of the connection
+    pub address: SocketAddr,
+    /// true if we initiated the connection
+    pub is_outgoing: bool,
+    /// transport of the connection
+    pub transport: ConnectionTransport,
+    /// peer category of the connection, `None` for the default category
+    pub category: Option<String>,
+    /// time at which the handshake of the connection succeeded
+    pub connected_since: Option<MassaTime>,
+    /// last announcement received from the peer
+    pub last_announce: Option<PeerAnnouncementInfo>,
+    /// total bytes sent to the peer on this connection
+    pub bytes_sent: u64,
+    /// total bytes received from the peer on this connection
+    pub bytes_received: u64,
+    /// inbound messages of the peer, per message type
+    pub traffic: PeerTrafficStats,
+    /// number of blocks we know the peer knows about
+    pub known_blocks_count: usize,
+    /// number of operations we know the peer knows about
+    pub known_operations_count: usize,
+    /// number of endorsements we know the peer knows about
+    pub known_endorsements_count: usize,
+    /// number of blocks of our wishlist currently asked to the peer
+    pub asked_blocks_count: usize,
+    /// size of our block wishlist, common to all peers
+    pub block_wishlist_size: usize,
+}
+
+impl std::fmt::Display for ConnectionInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Node {}:", self.node_id)?;
+        writeln!(
+            f,
+            "\tAddress: {} ({}, {})",
+            self.address,
+            if self.is_outgoing { "out" } else { "in" },
+            self.transport
+        )?;
+        writeln!(
+            f,
+            "\tCategory: {}",
+            self.category.as_deref().unwrap_or("default")
+        )?;
+        if let Some(connected_since) = self.connected_since {
+            writeln!(f, "\tConnected since: {}", connected_since.format_instant())?;
+        }
+        match &self.last_announce {
+            Some(announce) => {
+                let listeners: Vec<String> = announce
+                    .listeners
+                    .iter()
+                    .map(|(addr, transport)| format!("{} ({})", addr, transport))
+                    .collect();
+                writeln!(
+                    f,
+                    "\tLast announcement: {}, listeners: [{}]",
+                    announce.timestamp.format_instant(),
+                    listeners.join(", ")
+                )?;
+            }
+            None => writeln!(f, "\tLast announcement: none")?,
+        }
+        writeln!(
+            f,
+            "\tBytes sent: {}, bytes received: {}",
+            self.bytes_sent, self.bytes_received
+        )?;
+        write!(f, "{}", self.traffic)?;
+        writeln!(
+            f,
+            "\tKnown by peer: {} blocks, {} operations, {} endorsements",
+            self.known_blocks_count, self.known_operations_count, self.known_endorsements_count
+        )?;
+        writeln!(
+            f,
+            "\tAsked blocks: {} (wishlist size: {})",
+            self.asked_blocks_count, self.block_wishlist_size
+        )?;
+        Ok(())
+    }
+}
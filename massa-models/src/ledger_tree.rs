@@ -0,0 +1,378 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Node local sparse Merkle tree over the final ledger, and the paths of its items.
+//!
+//! Each item of the ledger (balance, bytecode or datastore entry of an address) is a leaf
+//! placed on the 256 bits path given by the hash of its key. A subtree holding a single leaf
+//! is represented by that leaf, and an empty subtree by the zero hash, so that the depth of
+//! the tree stays logarithmic in the number of items.
+//!
+//! The path of an item lists the hashes of the siblings on the path from the root to the
+//! position where the search for its key ends, which holds either:
+//! * the leaf of the item: the path shows the value of the item
+//! * nothing, or the leaf of another item: the path shows the absence of the item
+//!
+//! The tree is a node local index: its root is not committed in the blocks nor in the final state hash.
+//! A path is not a trust-minimized proof: it only shows that a value is consistent with the tree
+//! of the node that computed the root, and gives no guarantee about the ledger agreed on by the consensus.
+
+use crate::{address::Address, slot::Slot};
+use massa_hash::{Hash, HashDeserializer, HashSerializer, HASH_SIZE_BYTES};
+use massa_serialization::{Deserializer, SerializeError, Serializer};
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    error::{context, ContextError, ParseError},
+    sequence::{preceded, tuple},
+    IResult, Parser,
+};
+use serde::{Deserialize, Serialize};
+
+/// Maximum depth of the tree: the number of bits of a key
+pub const SPARSE_MERKLE_TREE_DEPTH: usize = HASH_SIZE_BYTES * 8;
+
+/// Item of a ledger entry committed in the ledger Merkle tree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LedgerTreeItem {
+    /// Balance, the value is the serialized `Amount`
+    Balance,
+    /// Bytecode, the value is the serialized `Bytecode`
+    Bytecode,
+    /// Datastore entry of the given key, the value is the raw datastore value
+    Datastore(Vec<u8>),
+}
+
+impl LedgerTreeItem {
+    /// Computes the key of the item of an address in the tree
+    pub fn tree_key(&self, address: &Address) -> Hash {
+        let address_bytes = address.to_prefixed_bytes();
+        match self {
+            LedgerTreeItem::Balance => Hash::compute_from_tuple(&[&address_bytes, &[0]]),
+            LedgerTreeItem::Bytecode => Hash::compute_from_tuple(&[&address_bytes, &[1]]),
+            LedgerTreeItem::Datastore(key) => {
+                Hash::compute_from_tuple(&[&address_bytes, &[2], key])
+            }
+        }
+    }
+}
+
+/// Returns the bit of `key` at the given depth, the most significant bit of the first byte being at depth 0
+pub fn key_bit(key: &Hash, depth: usize) -> bool {
+    (key.to_bytes()[depth / 8] >> (7 - depth % 8)) & 1 == 1
+}
+
+/// Node of the sparse Merkle tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SparseMerkleNode {
+    /// Single item of a subtree
+    Leaf {
+        /// key of the item
+        key: Hash,
+        /// hash of the value of the item
+        value_hash: Hash,
+    },
+    /// Subtree holding at least two items
+    Internal {
+        /// hash of the child whose keys have a 0 bit at this depth
+        left: Hash,
+        /// hash of the child whose keys have a 1 bit at this depth
+        right: Hash,
+    },
+}
+
+impl SparseMerkleNode {
+    /// Creates the leaf of an item from its value
+    pub fn new_leaf(key: Hash, value: &[u8]) -> Self {
+        SparseMerkleNode::Leaf {
+            key,
+            value_hash: Hash::compute_from(value),
+        }
+    }
+
+    /// Computes the hash of the node. Leaves and internal nodes are domain separated.
+    pub fn hash(&self) -> Hash {
+        match self {
+            SparseMerkleNode::Leaf { key, value_hash } => {
+                Hash::compute_from_tuple(&[&[0], key.to_bytes(), value_hash.to_bytes()])
+            }
+            SparseMerkleNode::Internal { left, right } => {
+                Hash::compute_from_tuple(&[&[1], left.to_bytes(), right.to_bytes()])
+            }
+        }
+    }
+}
+
+/// Serializer for `SparseMerkleNode`
+#[derive(Default)]
+pub struct SparseMerkleNodeSerializer {
+    hash_serializer: HashSerializer,
+}
+
+impl SparseMerkleNodeSerializer {
+    /// Creates a new `SparseMerkleNodeSerializer`
+    pub fn new() -> Self {
+        Self {
+            hash_serializer: HashSerializer::new(),
+        }
+    }
+}
+
+impl Serializer<SparseMerkleNode> for SparseMerkleNodeSerializer {
+    fn serialize(
+        &self,
+        value: &SparseMerkleNode,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        let (tag, first, second) = match value {
+            SparseMerkleNode::Leaf { key, value_hash } => (0u8, key, value_hash),
+            SparseMerkleNode::Internal { left, right } => (1u8, left, right),
+        };
+        buffer.push(tag);
+        self.hash_serializer.serialize(first, buffer)?;
+        self.hash_serializer.serialize(second, buffer)?;
+        Ok(())
+    }
+}
+
+/// Deserializer for `SparseMerkleNode`
+#[derive(Default)]
+pub struct SparseMerkleNodeDeserializer {
+    hash_deserializer: HashDeserializer,
+}
+
+impl SparseMerkleNodeDeserializer {
+    /// Creates a new `SparseMerkleNodeDeserializer`
+    pub fn new() -> Self {
+        Self {
+            hash_deserializer: HashDeserializer::new(),
+        }
+    }
+}
+
+impl Deserializer<SparseMerkleNode> for SparseMerkleNodeDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], SparseMerkleNode, E> {
+        let hashes = |input: &'a [u8]| {
+            tuple((
+                |input| self.hash_deserializer.deserialize(input),
+                |input| self.hash_deserializer.deserialize(input),
+            ))(input)
+        };
+        context(
+            "Failed SparseMerkleNode deserialization",
+            alt((
+                preceded(tag(&[0u8][..]), hashes)
+                    .map(|(key, value_hash)| SparseMerkleNode::Leaf { key, value_hash }),
+                preceded(tag(&[1u8][..]), hashes)
+                    .map(|(left, right)| SparseMerkleNode::Internal { left, right }),
+            )),
+        )
+        .parse(buffer)
+    }
+}
+
+/// Path of an item in the sparse Merkle tree, showing its value or its absence
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SparseMerklePath {
+    /// hashes of the siblings of the nodes on the path of the key, from the root down
+    pub siblings: Vec<Hash>,
+    /// leaf found at the end of the path, `(key, value_hash)`, if any
+    pub leaf: Option<(Hash, Hash)>,
+}
+
+impl SparseMerklePath {
+    /// Checks that the tree of the given root holds `value` for `key`,
+    /// or does not hold `key` at all if `value` is None.
+    pub fn matches_root(&self, root: &Hash, key: &Hash, value: Option<&[u8]>) -> bool {
+        let depth = self.siblings.len();
+        if depth > SPARSE_MERKLE_TREE_DEPTH {
+            return false;
+        }
+        let mut hash = match (self.leaf, value) {
+            // inclusion: the leaf must be the one of the item
+            (Some((leaf_key, value_hash)), Some(value)) => {
+                if leaf_key != *key || value_hash != Hash::compute_from(value) {
+                    return false;
+                }
+                SparseMerkleNode::Leaf {
+                    key: leaf_key,
+                    value_hash,
+                }
+                .hash()
+            }
+            // exclusion by another leaf standing alone in the subtree of the key
+            (Some((leaf_key, value_hash)), None) => {
+                if leaf_key == *key || (0..depth).any(|d| key_bit(&leaf_key, d) != key_bit(key, d))
+                {
+                    return false;
+                }
+                SparseMerkleNode::Leaf {
+                    key: leaf_key,
+                    value_hash,
+                }
+                .hash()
+            }
+            (None, Some(_)) => return false,
+            // exclusion by an empty subtree
+            (None, None) => Hash::zero(),
+        };
+        for (d, sibling) in self.siblings.iter().enumerate().rev() {
+            let (left, right) = if key_bit(key, d) {
+                (*sibling, hash)
+            } else {
+                (hash, *sibling)
+            };
+            hash = SparseMerkleNode::Internal { left, right }.hash();
+        }
+        hash == *root
+    }
+}
+
+/// Values and paths of ledger items in the node local ledger Merkle tree at a final slot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerTreePaths {
+    /// final slot the tree matches
+    pub slot: Slot,
+    /// root of the tree computed by the node, not committed by the consensus
+    pub local_root: Hash,
+    /// serialized value, None if absent, and path of each requested item
+    pub paths: Vec<(Option<Vec<u8>>, SparseMerklePath)>,
+}
+
+/// Checks that the value of an item of a ledger entry is consistent with the local root of a node's ledger Merkle tree.
+/// This only checks the consistency with the tree of that node, not with the consensus.
+///
+/// # Arguments
+/// * `local_root`: root of the ledger Merkle tree computed by the node
+/// * `address`: address of the ledger entry
+/// * `item`: item of the entry
+/// * `value`: value of the item, None to check its absence
+/// * `path`: path of the item returned by the node
+pub fn matches_local_root(
+    local_root: &Hash,
+    address: &Address,
+    item: &LedgerTreeItem,
+    value: Option<&[u8]>,
+    path: &SparseMerklePath,
+) -> bool {
+    path.matches_root(local_root, &item.tree_key(address), value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_serialization::DeserializeError;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_sparse_merkle_paths() {
+        let address =
+            Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+        let balance_key = LedgerTreeItem::Balance.tree_key(&address);
+        let bytecode_key = LedgerTreeItem::Bytecode.tree_key(&address);
+        let balance_leaf = SparseMerkleNode::new_leaf(balance_key, b"balance");
+        let bytecode_leaf = SparseMerkleNode::new_leaf(bytecode_key, b"bytecode");
+
+        // tree holding the balance only: the root is its leaf, which also proves the absence of any other key
+        let root = balance_leaf.hash();
+        let path = SparseMerklePath {
+            siblings: vec![],
+            leaf: Some((balance_key, Hash::compute_from(b"balance"))),
+        };
+        assert!(matches_local_root(
+            &root,
+            &address,
+            &LedgerTreeItem::Balance,
+            Some(b"balance"),
+            &path
+        ));
+        assert!(!path.matches_root(&root, &balance_key, Some(b"other")));
+        assert!(!path.matches_root(&root, &balance_key, None));
+        assert!(path.matches_root(&root, &bytecode_key, None));
+
+        // tree holding the balance and the bytecode, whose keys diverge at depth `split`:
+        // `path[d]` is the hash of the node at depth `d` on their common path
+        let split = (0..SPARSE_MERKLE_TREE_DEPTH)
+            .find(|d| key_bit(&balance_key, *d) != key_bit(&bytecode_key, *d))
+            .unwrap();
+        let children = |child: Hash, sibling: Hash, bit: bool| {
+            let (left, right) = if bit {
+                (sibling, child)
+            } else {
+                (child, sibling)
+            };
+            SparseMerkleNode::Internal { left, right }.hash()
+        };
+        let mut path = vec![Hash::zero(); split + 1];
+        path[split] = children(
+            balance_leaf.hash(),
+            bytecode_leaf.hash(),
+            key_bit(&balance_key, split),
+        );
+        for d in (0..split).rev() {
+            path[d] = children(path[d + 1], Hash::zero(), key_bit(&balance_key, d));
+        }
+        let root = path[0];
+
+        let mut siblings = vec![Hash::zero(); split];
+        siblings.push(bytecode_leaf.hash());
+        let path = SparseMerklePath {
+            siblings,
+            leaf: Some((balance_key, Hash::compute_from(b"balance"))),
+        };
+        assert!(path.matches_root(&root, &balance_key, Some(b"balance")));
+        assert!(!path.matches_root(&root, &bytecode_key, Some(b"bytecode")));
+        assert!(!path.matches_root(&root, &balance_key, None));
+
+        // absence of a datastore entry, whose path leaves the common path at depth `fork`
+        let datastore_key = LedgerTreeItem::Datastore(b"key".to_vec()).tree_key(&address);
+        let fork = (0..SPARSE_MERKLE_TREE_DEPTH)
+            .find(|d| key_bit(&datastore_key, *d) != key_bit(&balance_key, *d))
+            .unwrap();
+        let path = if fork < split {
+            // the path ends in an empty subtree
+            let mut siblings = vec![Hash::zero(); fork];
+            siblings.push(path[fork + 1]);
+            SparseMerklePath {
+                siblings,
+                leaf: None,
+            }
+        } else {
+            // the path ends on the leaf of the balance or of the bytecode
+            let (leaf_key, leaf_value, sibling) = if fork == split {
+                (bytecode_key, &b"bytecode"[..], balance_leaf.hash())
+            } else {
+                (balance_key, &b"balance"[..], bytecode_leaf.hash())
+            };
+            let mut siblings = vec![Hash::zero(); split];
+            siblings.push(sibling);
+            SparseMerklePath {
+                siblings,
+                leaf: Some((leaf_key, Hash::compute_from(leaf_value))),
+            }
+        };
+        assert!(path.matches_root(&root, &datastore_key, None));
+        assert!(!path.matches_root(&root, &datastore_key, Some(b"value")));
+
+        // node serialization
+        for node in [
+            balance_leaf,
+            SparseMerkleNode::Internal {
+                left: root,
+                right: Hash::zero(),
+            },
+        ] {
+            let mut buffer = Vec::new();
+            SparseMerkleNodeSerializer::new()
+                .serialize(&node, &mut buffer)
+                .unwrap();
+            let (rest, deserialized) = SparseMerkleNodeDeserializer::new()
+                .deserialize::<DeserializeError>(&buffer)
+                .unwrap();
+            assert!(rest.is_empty());
+            assert_eq!(deserialized, node);
+        }
+    }
+}
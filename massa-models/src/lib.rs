@@ -12,8 +12,12 @@ pub mod active_block;
 pub mod address;
 /// amount related structures
 pub mod amount;
+/// peer ban related structures
+pub mod ban;
 /// block structure
 pub mod block;
+/// block graph rules shared by the consensus and the light client
+pub mod block_graph;
 /// block-related structure: block_header
 pub mod block_header;
 /// block-related structure: block_id
@@ -26,6 +30,8 @@ pub mod clique;
 pub mod composite;
 /// node configuration
 pub mod config;
+/// peer connection related structures
+pub mod connection;
 /// datastore serialization / deserialization
 pub mod datastore;
 /// denunciation
@@ -38,6 +44,8 @@ pub mod error;
 pub mod execution;
 /// ledger related structures
 pub mod ledger;
+/// node local sparse Merkle tree of the ledger items
+pub mod ledger_tree;
 /// mapping grpc
 pub mod mapping_grpc;
 /// node related structure
@@ -226,6 +226,8 @@ impl From<OperationType> for grpc_model::OperationType {
                 grpc_operation_type.r#type =
                     Some(grpc_model::operation_type::Type::CallSc(call_sc));
             }
+            // the cancellation of asynchronous messages is not part of the gRPC API yet
+            OperationType::CancelAsyncMessage { .. } => {}
         }
 
         grpc_operation_type
@@ -250,6 +252,7 @@ impl From<OperationType> for grpc_model::OpType {
             OperationType::RollSell { .. } => grpc_model::OpType::RollSell,
             OperationType::ExecuteSC { .. } => grpc_model::OpType::ExecuteSc,
             OperationType::CallSC { .. } => grpc_model::OpType::CallSc,
+            OperationType::CancelAsyncMessage { .. } => grpc_model::OpType::Unspecified,
         }
     }
 }
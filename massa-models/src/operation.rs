@@ -11,6 +11,7 @@ use crate::{
     amount::{Amount, AmountDeserializer, AmountSerializer},
     error::ModelsError,
     serialization::{StringDeserializer, StringSerializer, VecU8Deserializer, VecU8Serializer},
+    slot::{Slot, SlotDeserializer, SlotSerializer},
 };
 use massa_hash::{Hash, HashDeserializer};
 use massa_serialization::{
@@ -385,6 +386,7 @@ enum OperationTypeId {
     RollSell = 2,
     ExecuteSC = 3,
     CallSC = 4,
+    CancelAsyncMessage = 5,
 }
 
 /// the operation as sent in the network
@@ -612,6 +614,14 @@ pub enum OperationType {
         /// Extra coins that are spent from the caller's balance and transferred to the target
         coins: Amount,
     },
+    /// the sender cancels one of the asynchronous messages it sent and that is still in the pool,
+    /// the coins of the message are reimbursed
+    CancelAsyncMessage {
+        /// slot at which the message was emitted
+        emission_slot: Slot,
+        /// index of the message among the ones emitted at `emission_slot`
+        emission_index: u64,
+    },
 }
 
 impl std::fmt::Display for OperationType {
@@ -657,6 +667,14 @@ impl std::fmt::Display for OperationType {
                 writeln!(f, "\t- max_gas:{}", max_gas)?;
                 writeln!(f, "\t- coins:{}", coins)?;
             }
+            OperationType::CancelAsyncMessage {
+                emission_slot,
+                emission_index,
+            } => {
+                writeln!(f, "Cancel async message:")?;
+                writeln!(f, "\t- emission slot:{}", emission_slot)?;
+                writeln!(f, "\t- emission index:{}", emission_index)?;
+            }
         }
         Ok(())
     }
@@ -671,6 +689,7 @@ pub struct OperationTypeSerializer {
     address_serializer: AddressSerializer,
     function_name_serializer: StringSerializer<U16VarIntSerializer, u16>,
     datastore_serializer: DatastoreSerializer,
+    slot_serializer: SlotSerializer,
 }
 
 impl OperationTypeSerializer {
@@ -684,6 +703,7 @@ impl OperationTypeSerializer {
             address_serializer: AddressSerializer::new(),
             function_name_serializer: StringSerializer::new(U16VarIntSerializer::new()),
             datastore_serializer: DatastoreSerializer::new(),
+            slot_serializer: SlotSerializer::new(),
         }
     }
 }
@@ -764,6 +784,15 @@ impl Serializer<OperationType> for OperationTypeSerializer {
                     .serialize(target_func, buffer)?;
                 self.vec_u8_serializer.serialize(param, buffer)?;
             }
+            OperationType::CancelAsyncMessage {
+                emission_slot,
+                emission_index,
+            } => {
+                self.u32_serializer
+                    .serialize(&u32::from(OperationTypeId::CancelAsyncMessage), buffer)?;
+                self.slot_serializer.serialize(emission_slot, buffer)?;
+                self.u64_serializer.serialize(emission_index, buffer)?;
+            }
         }
         Ok(())
     }
@@ -780,6 +809,8 @@ pub struct OperationTypeDeserializer {
     function_name_deserializer: StringDeserializer<U16VarIntDeserializer, u16>,
     parameter_deserializer: VecU8Deserializer,
     datastore_deserializer: DatastoreDeserializer,
+    slot_deserializer: SlotDeserializer,
+    emission_index_deserializer: U64VarIntDeserializer,
 }
 
 impl OperationTypeDeserializer {
@@ -818,6 +849,15 @@ impl OperationTypeDeserializer {
                 max_op_datastore_key_length,
                 max_op_datastore_value_length,
             ),
+            // a slot with an invalid thread designates no message and fails at execution
+            slot_deserializer: SlotDeserializer::new(
+                (Included(0), Included(u64::MAX)),
+                (Included(0), Included(u8::MAX)),
+            ),
+            emission_index_deserializer: U64VarIntDeserializer::new(
+                Included(0),
+                Included(u64::MAX),
+            ),
         }
     }
 }
@@ -952,6 +992,24 @@ impl Deserializer<OperationType> for OperationTypeDeserializer {
                     },
                 )
                 .parse(input),
+                OperationTypeId::CancelAsyncMessage => context(
+                    "Failed CancelAsyncMessage deserialization",
+                    tuple((
+                        context("Failed emission_slot deserialization", |input| {
+                            self.slot_deserializer.deserialize(input)
+                        }),
+                        context("Failed emission_index deserialization", |input| {
+                            self.emission_index_deserializer.deserialize(input)
+                        }),
+                    )),
+                )
+                .map(
+                    |(emission_slot, emission_index)| OperationType::CancelAsyncMessage {
+                        emission_slot,
+                        emission_index,
+                    },
+                )
+                .parse(input),
             }
         })
         .parse(buffer)
@@ -977,6 +1035,7 @@ impl SecureShareOperation {
         match &self.content.op {
             OperationType::ExecuteSC { max_gas, .. } => max_gas.saturating_add(sp_compilation_cost),
             OperationType::CallSC { max_gas, .. } => *max_gas,
+            OperationType::CancelAsyncMessage { .. } => 0,
             OperationType::RollBuy { .. } => 0,
             OperationType::RollSell { .. } => 0,
             OperationType::Transaction { .. } => 0,
@@ -1001,6 +1060,7 @@ impl SecureShareOperation {
             OperationType::CallSC { target_addr, .. } => {
                 res.insert(*target_addr);
             }
+            OperationType::CancelAsyncMessage { .. } => {}
         }
         res
     }
@@ -1014,6 +1074,7 @@ impl SecureShareOperation {
             OperationType::RollSell { .. } => Amount::zero(),
             OperationType::ExecuteSC { max_coins, .. } => *max_coins,
             OperationType::CallSC { coins, .. } => *coins,
+            OperationType::CancelAsyncMessage { .. } => Amount::zero(),
         };
 
         // add all fees and return
@@ -1033,6 +1094,7 @@ impl SecureShareOperation {
             }
             OperationType::ExecuteSC { .. } => {}
             OperationType::CallSC { .. } => {}
+            OperationType::CancelAsyncMessage { .. } => {}
         }
         Ok(res)
     }
@@ -1703,6 +1765,50 @@ mod tests {
         assert_eq!(op.get_validity_range(10), 40..=50);
     }
 
+    #[test]
+    #[serial]
+    fn test_cancel_async_message() {
+        let sender_keypair = KeyPair::generate(0).unwrap();
+
+        let op = OperationType::CancelAsyncMessage {
+            emission_slot: Slot::new(12, 3),
+            emission_index: 4,
+        };
+        let mut ser_type = Vec::new();
+        OperationTypeSerializer::new()
+            .serialize(&op, &mut ser_type)
+            .unwrap();
+        let (rest, res_type) = OperationTypeDeserializer::new(
+            MAX_DATASTORE_VALUE_LENGTH,
+            MAX_FUNCTION_NAME_LENGTH,
+            MAX_PARAMETERS_SIZE,
+            MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+            MAX_OPERATION_DATASTORE_KEY_LENGTH,
+            MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        )
+        .deserialize::<DeserializeError>(&ser_type)
+        .unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(res_type, op);
+
+        let content = Operation {
+            fee: Amount::from_str("20").unwrap(),
+            op,
+            expire_period: 50,
+        };
+        let op = Operation::new_verifiable(content, OperationSerializer::new(), &sender_keypair)
+            .unwrap();
+        assert_eq!(op.get_gas_usage(100, 1000), 100);
+        assert_eq!(
+            op.get_max_spending(Amount::from_str("100").unwrap()),
+            Amount::from_str("20").unwrap()
+        );
+        assert_eq!(
+            op.get_ledger_involved_addresses(),
+            PreHashSet::from_iter([op.content_creator_address])
+        );
+    }
+
     #[test]
     #[serial]
     fn test_transaction_serde() {
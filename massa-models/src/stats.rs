@@ -64,6 +64,9 @@ pub struct NetworkStats {
     pub banned_peer_count: u64,
     /// active node count
     pub active_node_count: u64,
+    /// traffic of the node since startup, per message type
+    #[serde(default)]
+    pub traffic: PeerTrafficStats,
 }
 
 impl std::fmt::Display for NetworkStats {
@@ -74,6 +77,91 @@ impl std::fmt::Display for NetworkStats {
         writeln!(f, "\tKnown peers: {}", self.known_peer_count)?;
         writeln!(f, "\tBanned peers: {}", self.banned_peer_count)?;
         writeln!(f, "\tActive nodes: {}", self.active_node_count)?;
+        writeln!(
+            f,
+            "\tRate limited messages: {}",
+            self.traffic.total().messages_rate_limited
+        )?;
+        Ok(())
+    }
+}
+
+/// traffic counters for one message type
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MessageTrafficStats {
+    /// number of messages received
+    pub messages_received: u64,
+    /// number of bytes received
+    pub bytes_received: u64,
+    /// number of received messages dropped, or delayed for blocks, because the rate limit was exceeded
+    pub messages_rate_limited: u64,
+    /// number of messages sent
+    pub messages_sent: u64,
+    /// number of bytes sent
+    pub bytes_sent: u64,
+}
+
+impl MessageTrafficStats {
+    /// Sum two sets of counters
+    pub fn saturating_add(&self, other: &MessageTrafficStats) -> MessageTrafficStats {
+        MessageTrafficStats {
+            messages_received: self
+                .messages_received
+                .saturating_add(other.messages_received),
+            bytes_received: self.bytes_received.saturating_add(other.bytes_received),
+            messages_rate_limited: self
+                .messages_rate_limited
+                .saturating_add(other.messages_rate_limited),
+            messages_sent: self.messages_sent.saturating_add(other.messages_sent),
+            bytes_sent: self.bytes_sent.saturating_add(other.bytes_sent),
+        }
+    }
+}
+
+impl std::fmt::Display for MessageTrafficStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} messages, {} bytes received ({} rate limited), {} messages, {} bytes sent",
+            self.messages_received,
+            self.bytes_received,
+            self.messages_rate_limited,
+            self.messages_sent,
+            self.bytes_sent
+        )
+    }
+}
+
+/// traffic of a peer, per message type
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerTrafficStats {
+    /// block messages (headers, requests and responses)
+    pub block: MessageTrafficStats,
+    /// endorsement messages
+    pub endorsement: MessageTrafficStats,
+    /// operation messages
+    pub operation: MessageTrafficStats,
+    /// peer management messages
+    pub peer_management: MessageTrafficStats,
+}
+
+impl PeerTrafficStats {
+    /// Sum of the counters of all message types
+    pub fn total(&self) -> MessageTrafficStats {
+        self.block
+            .saturating_add(&self.endorsement)
+            .saturating_add(&self.operation)
+            .saturating_add(&self.peer_management)
+    }
+}
+
+impl std::fmt::Display for PeerTrafficStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "\tBlocks: {}", self.block)?;
+        writeln!(f, "\tEndorsements: {}", self.endorsement)?;
+        writeln!(f, "\tOperations: {}", self.operation)?;
+        writeln!(f, "\tPeer management: {}", self.peer_management)?;
         Ok(())
     }
 }
@@ -193,6 +193,20 @@ impl Version {
     pub fn is_compatible(&self, other: &Version) -> bool {
         self.instance == other.instance && self.major == other.major
     }
+
+    /// true if compatible with `other` and with a minor version greater or equal to `other`'s
+    ///
+    /// ```rust
+    /// # use massa_models::version::Version;
+    /// # use std::str::FromStr;
+    /// let v: Version = Version::from_str("TEST.1.10").unwrap();
+    /// assert!(v.is_at_least(&Version::from_str("TEST.1.9").unwrap()));
+    /// assert!(!v.is_at_least(&Version::from_str("TEST.1.11").unwrap()));
+    /// assert!(!v.is_at_least(&Version::from_str("TEST.0.1").unwrap()));
+    /// ```
+    pub fn is_at_least(&self, other: &Version) -> bool {
+        self.is_compatible(other) && self.minor >= other.minor
+    }
 }
 
 impl fmt::Display for Version {
@@ -0,0 +1,244 @@
+use massa_hash::{Hash, HASH_SIZE_BYTES};
+use massa_models::prehash::{PreHashMap, PreHashSet};
+use rocksdb::{IteratorMode, WriteBatch, DB};
+use std::path::PathBuf;
+use tracing::{debug, warn};
+
+const OPEN_ERROR: &str = "critical: rocksdb open operation failed";
+const CRUD_ERROR: &str = "critical: rocksdb crud operation failed";
+const BYTECODE_IDENT: u8 = 0u8;
+const COUNT_IDENT: u8 = 1u8;
+
+/// Number of recorded calls after which the updated call counts are written to disk
+const FLUSH_INTERVAL: u64 = 1000;
+
+/// Call statistics of a module
+#[derive(Clone, Copy, Default)]
+pub(crate) struct ModuleCallCount {
+    /// number of times the module was loaded for execution
+    pub count: u64,
+    /// the module is pinned in RAM
+    pub pinned: bool,
+}
+
+/// Disk stored call statistics of the modules.
+///
+/// It is kept separately from the `HDCache` so that it survives the cache being wiped,
+/// and it stores the bytecode of the tracked modules so that they can be compiled again at startup.
+/// The call counts are kept in RAM and written to disk periodically.
+pub(crate) struct CallStats {
+    /// RocksDB database
+    db: DB,
+    /// call statistics of the tracked modules
+    counts: PreHashMap<Hash, ModuleCallCount>,
+    /// modules whose statistics changed since the last flush
+    dirty: PreHashSet<Hash>,
+    /// calls recorded since the last flush
+    calls_since_flush: u64,
+    /// Maximum number of tracked modules.
+    /// When this maximum is reached the `snip_amount` least called unpinned modules are forgotten
+    max_entry_count: usize,
+    /// How many modules are forgotten when `max_entry_count` is reached
+    snip_amount: usize,
+}
+
+impl CallStats {
+    /// Open the call statistics stored at `path`, or create them
+    pub fn new(path: PathBuf, max_entry_count: usize, snip_amount: usize) -> Self {
+        let db = DB::open_default(path).expect(OPEN_ERROR);
+        let mut counts = PreHashMap::default();
+        for (key, value) in db.iterator(IteratorMode::Start).flatten() {
+            if key.len() != HASH_SIZE_BYTES + 1 || key[HASH_SIZE_BYTES] != COUNT_IDENT {
+                continue;
+            }
+            match (
+                key[..HASH_SIZE_BYTES].try_into(),
+                value.get(..8).map(|count| count.try_into()),
+            ) {
+                (Ok(hash), Some(Ok(count))) => {
+                    counts.insert(
+                        Hash::from_bytes(hash),
+                        ModuleCallCount {
+                            count: u64::from_le_bytes(count),
+                            pinned: value.get(8) == Some(&1u8),
+                        },
+                    );
+                }
+                _ => warn!("ignoring malformed module call statistics entry"),
+            }
+        }
+        debug!("loaded the call statistics of {} modules", counts.len());
+        Self {
+            db,
+            counts,
+            dirty: Default::default(),
+            calls_since_flush: 0,
+            max_entry_count,
+            snip_amount,
+        }
+    }
+
+    /// Record a call to a module
+    pub fn record_call(&mut self, hash: Hash, bytecode: &[u8]) {
+        if let Some(stats) = self.counts.get_mut(&hash) {
+            stats.count = stats.count.saturating_add(1);
+        } else {
+            if self.counts.len() >= self.max_entry_count {
+                self.snip();
+            }
+            self.counts.insert(
+                hash,
+                ModuleCallCount {
+                    count: 1,
+                    pinned: false,
+                },
+            );
+            self.db
+                .put(key(hash, BYTECODE_IDENT), bytecode)
+                .expect(CRUD_ERROR);
+        }
+        self.dirty.insert(hash);
+        self.calls_since_flush += 1;
+        if self.calls_since_flush >= FLUSH_INTERVAL {
+            self.flush();
+        }
+    }
+
+    /// Get the call statistics of a module
+    pub fn get(&self, hash: &Hash) -> Option<ModuleCallCount> {
+        self.counts.get(hash).copied()
+    }
+
+    /// Number of tracked modules
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Get the stored bytecode of a tracked module
+    pub fn get_bytecode(&self, hash: Hash) -> Option<Vec<u8>> {
+        self.db.get(key(hash, BYTECODE_IDENT)).expect(CRUD_ERROR)
+    }
+
+    /// Set whether a tracked module is pinned.
+    /// Returns false if the module is not tracked.
+    pub fn set_pinned(&mut self, hash: Hash, pinned: bool) -> bool {
+        match self.counts.get_mut(&hash) {
+            Some(stats) => {
+                stats.pinned = pinned;
+                self.dirty.insert(hash);
+                self.flush();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Get the pinned modules
+    pub fn get_pinned(&self) -> Vec<Hash> {
+        self.counts
+            .iter()
+            .filter(|(_, stats)| stats.pinned)
+            .map(|(hash, _)| *hash)
+            .collect()
+    }
+
+    /// Get the `count` most called modules, most called first
+    pub fn get_most_called(&self, count: usize) -> Vec<Hash> {
+        let mut modules: Vec<(&Hash, &ModuleCallCount)> = self.counts.iter().collect();
+        modules.sort_unstable_by(|(hash_a, a), (hash_b, b)| {
+            b.count.cmp(&a.count).then_with(|| hash_a.cmp(hash_b))
+        });
+        modules
+            .into_iter()
+            .take(count)
+            .map(|(hash, _)| *hash)
+            .collect()
+    }
+
+    /// Forget a module
+    pub fn remove(&mut self, hash: Hash) {
+        if self.counts.remove(&hash).is_some() {
+            self.dirty.remove(&hash);
+            let mut batch = WriteBatch::default();
+            batch.delete(key(hash, BYTECODE_IDENT));
+            batch.delete(key(hash, COUNT_IDENT));
+            self.db.write(batch).expect(CRUD_ERROR);
+        }
+    }
+
+    /// Write the updated call counts to disk
+    pub fn flush(&mut self) {
+        let mut batch = WriteBatch::default();
+        for hash in self.dirty.drain() {
+            if let Some(stats) = self.counts.get(&hash) {
+                let mut value = stats.count.to_le_bytes().to_vec();
+                value.push(stats.pinned as u8);
+                batch.put(key(hash, COUNT_IDENT), value);
+            }
+        }
+        self.db.write(batch).expect(CRUD_ERROR);
+        self.calls_since_flush = 0;
+    }
+
+    /// Forget the `snip_amount` least called unpinned modules
+    fn snip(&mut self) {
+        let mut candidates: Vec<(Hash, u64)> = self
+            .counts
+            .iter()
+            .filter(|(_, stats)| !stats.pinned)
+            .map(|(hash, stats)| (*hash, stats.count))
+            .collect();
+        candidates.sort_unstable_by_key(|(_, count)| *count);
+        for (hash, _) in candidates.into_iter().take(self.snip_amount.max(1)) {
+            self.remove(hash);
+        }
+    }
+}
+
+impl Drop for CallStats {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+/// Build the database key of a module item
+fn key(hash: Hash, ident: u8) -> Vec<u8> {
+    [&hash.to_bytes()[..], &[ident]].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use tempfile::TempDir;
+
+    #[test]
+    #[serial]
+    fn test_call_stats_persistence() {
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.path().to_path_buf();
+        let bytecodes: Vec<Vec<u8>> = (0u8..4).map(|i| vec![i; 10]).collect();
+        let hashes: Vec<Hash> = bytecodes.iter().map(|b| Hash::compute_from(b)).collect();
+        {
+            let mut stats = CallStats::new(path.clone(), 3, 1);
+            for (i, bytecode) in bytecodes.iter().take(3).enumerate() {
+                for _ in 0..=i {
+                    stats.record_call(hashes[i], bytecode);
+                }
+            }
+            assert!(stats.set_pinned(hashes[0], true));
+            assert_eq!(stats.get_most_called(2), vec![hashes[2], hashes[1]]);
+
+            // the least called unpinned module is forgotten
+            stats.record_call(hashes[3], &bytecodes[3]);
+            assert!(stats.get(&hashes[1]).is_none());
+            assert!(stats.get_bytecode(hashes[1]).is_none());
+            stats.flush();
+        }
+
+        let stats = CallStats::new(path, 3, 1);
+        assert_eq!(stats.get(&hashes[2]).unwrap().count, 3);
+        assert_eq!(stats.get_pinned(), vec![hashes[0]]);
+        assert_eq!(stats.get_bytecode(hashes[3]), Some(bytecodes[3].clone()));
+    }
+}
@@ -16,4 +16,8 @@ pub struct ModuleCacheConfig {
     pub snip_amount: usize,
     /// Maximum length of a module
     pub max_module_length: u64,
+    /// Path to the module call statistics storage
+    pub call_stats_path: PathBuf,
+    /// Number of most called modules compiled and loaded in RAM at startup
+    pub warm_up_module_count: usize,
 }
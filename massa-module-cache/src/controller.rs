@@ -1,12 +1,17 @@
 use massa_hash::Hash;
-use massa_models::prehash::BuildHashMapper;
+use massa_models::prehash::{BuildHashMapper, PreHashMap};
 use massa_sc_runtime::{Compiler, RuntimeModule};
 use schnellru::{ByLength, LruMap};
-use tracing::debug;
+use std::time::Instant;
+use tracing::{debug, info};
 
 use crate::{
-    config::ModuleCacheConfig, error::CacheError, hd_cache::HDCache, lru_cache::LRUCache,
-    types::ModuleInfo,
+    call_stats::CallStats,
+    config::ModuleCacheConfig,
+    error::CacheError,
+    hd_cache::HDCache,
+    lru_cache::LRUCache,
+    types::{ModuleCacheEntryInfo, ModuleCacheStats, ModuleInfo, ModuleMetadata},
 };
 
 /// `LruMap` specialization for `PreHashed` keys
@@ -23,25 +28,91 @@ pub struct ModuleCache {
     /// Disk stored cache.
     /// See the `HDCache` documentation for more information.
     hd_cache: HDCache,
+    /// Modules pinned in RAM, they are never evicted by the LRU caching scheme
+    pinned: PreHashMap<Hash, ModuleInfo>,
+    /// Disk stored call statistics, used to warm the cache up at startup.
+    /// See the `CallStats` documentation for more information.
+    call_stats: CallStats,
+    /// Cache statistics
+    stats: ModuleCacheStats,
 }
 
 impl ModuleCache {
     /// Creates a new `ModuleCache`
+    ///
+    /// The pinned modules and the `warm_up_module_count` most called ones are loaded in RAM,
+    /// and compiled again if they are missing from the HD cache.
     pub fn new(cfg: ModuleCacheConfig) -> Self {
-        Self {
+        let mut module_cache = Self {
             lru_cache: LRUCache::new(cfg.lru_cache_size),
             hd_cache: HDCache::new(
                 cfg.hd_cache_path.clone(),
                 cfg.hd_cache_size,
                 cfg.snip_amount,
             ),
+            pinned: Default::default(),
+            call_stats: CallStats::new(
+                cfg.call_stats_path.clone(),
+                cfg.hd_cache_size,
+                cfg.snip_amount,
+            ),
+            stats: Default::default(),
             cfg,
+        };
+        module_cache.warm_up();
+        module_cache
+    }
+
+    /// Load the pinned and the most called modules in RAM
+    fn warm_up(&mut self) {
+        for hash in self.call_stats.get_pinned() {
+            if let Some(module_info) = self.fetch_module_info(hash) {
+                self.pinned.insert(hash, module_info);
+            }
         }
+        let most_called = self.call_stats.get_most_called(
+            self.cfg
+                .warm_up_module_count
+                .min(self.cfg.lru_cache_size as usize),
+        );
+        // insert the most called modules last so that they are the most recently used ones
+        for hash in most_called.into_iter().rev() {
+            if self.pinned.contains_key(&hash) {
+                continue;
+            }
+            if let Some(module_info) = self.fetch_module_info(hash) {
+                self.lru_cache.insert(hash, module_info);
+                self.stats.warmed_up_count += 1;
+            }
+        }
+        info!(
+            "module cache warm-up loaded {} pinned and {} frequently called modules",
+            self.pinned.len(),
+            self.stats.warmed_up_count
+        );
+    }
+
+    /// Retrieve a module from the HD cache, or compile it from its tracked bytecode
+    fn fetch_module_info(&mut self, hash: Hash) -> Option<ModuleInfo> {
+        if let Some(hd_module_info) = self.hd_cache.get(hash, self.cfg.gas_costs.clone()) {
+            return Some(hd_module_info);
+        }
+        let bytecode = self.call_stats.get_bytecode(hash)?;
+        let module_info = self.compile_cached(&bytecode, hash);
+        self.hd_cache.insert(hash, module_info.clone());
+        Some(module_info)
     }
 
     /// Internal function to compile and build `ModuleInfo`
     fn compile_cached(&mut self, bytecode: &[u8], hash: Hash) -> ModuleInfo {
-        match RuntimeModule::new(bytecode, self.cfg.gas_costs.clone(), Compiler::CL) {
+        let compilation_start = Instant::now();
+        let compilation = RuntimeModule::new(bytecode, self.cfg.gas_costs.clone(), Compiler::CL);
+        self.stats.compilations += 1;
+        self.stats.compilation_time_micros = self
+            .stats
+            .compilation_time_micros
+            .saturating_add(compilation_start.elapsed().as_micros() as u64);
+        match compilation {
             Ok(module) => {
                 debug!("compilation of module {} succeeded", hash);
                 ModuleInfo::Module(module)
@@ -74,6 +145,15 @@ impl ModuleCache {
     /// Set the initialization cost of a cached module
     pub fn set_init_cost(&mut self, bytecode: &[u8], init_cost: u64) {
         let hash = Hash::compute_from(bytecode);
+        if let Some(content) = self.pinned.get_mut(&hash) {
+            match content {
+                ModuleInfo::Module(module) => {
+                    *content = ModuleInfo::ModuleAndDelta((module.clone(), init_cost))
+                }
+                ModuleInfo::ModuleAndDelta((_module, delta)) => *delta = init_cost,
+                ModuleInfo::Invalid(_) => {}
+            }
+        }
         self.lru_cache.set_init_cost(hash, init_cost);
         self.hd_cache.set_init_cost(hash, init_cost);
     }
@@ -81,6 +161,9 @@ impl ModuleCache {
     /// Set a cached module as invalid
     pub fn set_invalid(&mut self, bytecode: &[u8], err_msg: String) {
         let hash = Hash::compute_from(bytecode);
+        if let Some(content) = self.pinned.get_mut(&hash) {
+            *content = ModuleInfo::Invalid(err_msg.clone());
+        }
         self.lru_cache.set_invalid(hash, err_msg.clone());
         self.hd_cache.set_invalid(hash, err_msg);
     }
@@ -91,7 +174,9 @@ impl ModuleCache {
     /// * `ModuleInfo::Invalid` if the module is invalid
     /// * `ModuleInfo::Module` if the module is valid and has no delta
     /// * `ModuleInfo::ModuleAndDelta` if the module is valid and has a delta
-    fn load_module_info(&mut self, bytecode: &[u8]) -> ModuleInfo {
+    ///
+    /// Calls made by read-only executions are not recorded in the call statistics.
+    fn load_module_info(&mut self, bytecode: &[u8], read_only: bool) -> ModuleInfo {
         if bytecode.is_empty() {
             let error_msg = "load_module: bytecode is absent".to_string();
             debug!(error_msg);
@@ -107,15 +192,27 @@ impl ModuleCache {
             return ModuleInfo::Invalid(error_msg);
         }
         let hash = Hash::compute_from(bytecode);
-        if let Some(lru_module_info) = self.lru_cache.get(hash) {
+        if !read_only {
+            self.call_stats.record_call(hash, bytecode);
+        }
+        if let Some(pinned_module_info) = self.pinned.get(&hash) {
+            debug!("load_module: {} pinned", hash);
+            self.stats.lru_hits += 1;
+            pinned_module_info.clone()
+        } else if let Some(lru_module_info) = self.lru_cache.get(hash) {
             debug!("load_module: {} present in lru", hash);
+            self.stats.lru_hits += 1;
             lru_module_info
         } else if let Some(hd_module_info) = self.hd_cache.get(hash, self.cfg.gas_costs.clone()) {
             debug!("load_module: {} missing in lru but present in hd", hash);
+            self.stats.lru_misses += 1;
+            self.stats.hd_hits += 1;
             self.lru_cache.insert(hash, hd_module_info.clone());
             hd_module_info
         } else {
             debug!("load_module: {} missing", hash);
+            self.stats.lru_misses += 1;
+            self.stats.hd_misses += 1;
             let module_info = self.compile_cached(bytecode, hash);
             self.hd_cache.insert(hash, module_info.clone());
             self.lru_cache.insert(hash, module_info.clone());
@@ -126,11 +223,14 @@ impl ModuleCache {
     /// Load a cached module for execution and check its validity for execution.
     /// Also checks that the provided execution gas is enough to pay for the instance creation cost.
     ///
+    /// `read_only` must be set for read-only executions so that they do not weigh in the startup warm-up.
+    ///
     /// Returns the module and the remaining gas after loading.
     pub fn load_module(
         &mut self,
         bytecode: &[u8],
         execution_gas: u64,
+        read_only: bool,
     ) -> Result<(RuntimeModule, u64), CacheError> {
         // Do not actually debit the instance creation cost from the provided gas
         // This is only supposed to be a check
@@ -142,7 +242,7 @@ impl ModuleCache {
             )))?;
         // TODO: interesting but unimportant optim
         // remove max_instance_cost hard check if module is cached and has a delta
-        let module_info = self.load_module_info(bytecode);
+        let module_info = self.load_module_info(bytecode, read_only);
         let module = match module_info {
             ModuleInfo::Invalid(err) => {
                 let err_msg = format!("invalid module: {}", err);
@@ -163,6 +263,77 @@ impl ModuleCache {
         Ok((module, execution_gas))
     }
 
+    /// Get the cache statistics
+    pub fn get_stats(&self) -> ModuleCacheStats {
+        ModuleCacheStats {
+            lru_entry_count: self.lru_cache.len(),
+            hd_entry_count: self.hd_cache.entry_count(),
+            pinned_count: self.pinned.len(),
+            tracked_count: self.call_stats.len(),
+            ..self.stats.clone()
+        }
+    }
+
+    /// Get the cache status of a module
+    pub fn get_entry_info(&self, hash: Hash) -> ModuleCacheEntryInfo {
+        let pinned_module_info = self.pinned.get(&hash).cloned();
+        let lru_module_info = self.lru_cache.peek(hash);
+        let hd_metadata = self.hd_cache.get_metadata(hash);
+        let (init_cost, invalid) = match pinned_module_info.as_ref().or(lru_module_info.as_ref()) {
+            Some(ModuleInfo::Invalid(err_msg)) => (None, Some(err_msg.clone())),
+            Some(ModuleInfo::Module(_)) => (None, None),
+            Some(ModuleInfo::ModuleAndDelta((_, delta))) => (Some(*delta), None),
+            None => match &hd_metadata {
+                Some(ModuleMetadata::Invalid(err_msg)) => (None, Some(err_msg.clone())),
+                Some(ModuleMetadata::Delta(delta)) => (Some(*delta), None),
+                Some(ModuleMetadata::NotExecuted) | None => (None, None),
+            },
+        };
+        ModuleCacheEntryInfo {
+            hash,
+            in_lru: lru_module_info.is_some(),
+            in_hd: hd_metadata.is_some(),
+            pinned: pinned_module_info.is_some(),
+            call_count: self.call_stats.get(&hash).map(|stats| stats.count),
+            init_cost,
+            invalid,
+        }
+    }
+
+    /// Remove a module from every cache layer and forget its call statistics.
+    /// It will be compiled again the next time it is loaded.
+    pub fn evict(&mut self, hash: Hash) {
+        self.pinned.remove(&hash);
+        self.lru_cache.remove(hash);
+        self.hd_cache.remove(hash);
+        self.call_stats.remove(hash);
+    }
+
+    /// Pin a module in RAM so that it is never evicted by the LRU caching scheme.
+    /// The module must be cached or tracked by the call statistics.
+    /// Pins are persisted for tracked modules.
+    pub fn pin(&mut self, hash: Hash) -> Result<(), CacheError> {
+        if !self.pinned.contains_key(&hash) {
+            let module_info = match self.lru_cache.peek(hash) {
+                Some(lru_module_info) => lru_module_info,
+                None => self.fetch_module_info(hash).ok_or_else(|| {
+                    CacheError::NotFound(format!("module {} is neither cached nor tracked", hash))
+                })?,
+            };
+            self.pinned.insert(hash, module_info);
+        }
+        self.call_stats.set_pinned(hash, true);
+        Ok(())
+    }
+
+    /// Unpin a module, it is moved to the LRU cache
+    pub fn unpin(&mut self, hash: Hash) {
+        if let Some(module_info) = self.pinned.remove(&hash) {
+            self.lru_cache.insert(hash, module_info);
+        }
+        self.call_stats.set_pinned(hash, false);
+    }
+
     /// Load a temporary module from arbitrary bytecode.
     /// Also checks that the provided execution gas is enough to pay for the instance creation cost.
     ///
@@ -185,3 +356,137 @@ impl ModuleCache {
         Ok((module, limit))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_sc_runtime::GasCosts;
+    use serial_test::serial;
+    use tempfile::TempDir;
+
+    /// Valid module bytecode, `name` is written in its custom name section
+    fn make_bytecode(name: u8) -> Vec<u8> {
+        vec![
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x06, 0x01, 0x60, 0x01, 0x7f,
+            0x01, 0x7f, 0x03, 0x02, 0x01, 0x00, 0x07, 0x0b, 0x01, 0x07, 0x61, 0x64, 0x64, 0x5f,
+            0x6f, 0x6e, 0x65, 0x00, 0x00, 0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x41, 0x01,
+            0x6a, 0x0b, 0x00, 0x1a, 0x04, 0x6e, 0x61, 0x6d, 0x65, 0x01, 0x0a, 0x01, 0x00, 0x07,
+            0x61, 0x64, 0x64, 0x5f, 0x6f, 0x6e, 0x65, 0x02, 0x07, 0x01, 0x00, 0x01, 0x00, 0x02,
+            0x70, name,
+        ]
+    }
+
+    fn make_config(tmp_dir: &TempDir, warm_up_module_count: usize) -> ModuleCacheConfig {
+        ModuleCacheConfig {
+            hd_cache_path: tmp_dir.path().join("hd_cache"),
+            gas_costs: GasCosts::default(),
+            lru_cache_size: 10,
+            hd_cache_size: 100,
+            snip_amount: 10,
+            max_module_length: 1_000_000,
+            call_stats_path: tmp_dir.path().join("call_stats"),
+            warm_up_module_count,
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_read_only_calls_not_recorded() {
+        let tmp_dir = TempDir::new().unwrap();
+        let mut cache = ModuleCache::new(make_config(&tmp_dir, 10));
+        let bytecode = make_bytecode(b'0');
+        let hash = Hash::compute_from(&bytecode);
+
+        cache.load_module(&bytecode, u64::MAX, true).unwrap();
+        assert!(cache.get_entry_info(hash).call_count.is_none());
+        assert_eq!(cache.get_stats().tracked_count, 0);
+
+        cache.load_module(&bytecode, u64::MAX, false).unwrap();
+        cache.load_module(&bytecode, u64::MAX, true).unwrap();
+        assert_eq!(cache.get_entry_info(hash).call_count, Some(1));
+    }
+
+    #[test]
+    #[serial]
+    fn test_pin_unpin() {
+        let tmp_dir = TempDir::new().unwrap();
+        let mut cache = ModuleCache::new(make_config(&tmp_dir, 10));
+        let bytecode = make_bytecode(b'0');
+        let hash = Hash::compute_from(&bytecode);
+
+        // unknown modules can not be pinned
+        assert!(matches!(cache.pin(hash), Err(CacheError::NotFound(_))));
+
+        cache.load_module(&bytecode, u64::MAX, false).unwrap();
+        cache.pin(hash).unwrap();
+        let info = cache.get_entry_info(hash);
+        assert!(info.pinned);
+        assert_eq!(cache.get_stats().pinned_count, 1);
+
+        // pinned modules are served from RAM
+        let lru_hits = cache.get_stats().lru_hits;
+        cache.load_module(&bytecode, u64::MAX, false).unwrap();
+        assert_eq!(cache.get_stats().lru_hits, lru_hits + 1);
+
+        cache.unpin(hash);
+        let info = cache.get_entry_info(hash);
+        assert!(!info.pinned);
+        assert!(info.in_lru);
+        assert_eq!(cache.get_stats().pinned_count, 0);
+    }
+
+    #[test]
+    #[serial]
+    fn test_evict() {
+        let tmp_dir = TempDir::new().unwrap();
+        let mut cache = ModuleCache::new(make_config(&tmp_dir, 10));
+        let bytecode = make_bytecode(b'0');
+        let hash = Hash::compute_from(&bytecode);
+
+        cache.load_module(&bytecode, u64::MAX, false).unwrap();
+        cache.pin(hash).unwrap();
+        let info = cache.get_entry_info(hash);
+        assert!(info.in_lru && info.in_hd && info.pinned);
+        assert_eq!(info.call_count, Some(1));
+
+        cache.evict(hash);
+        let info = cache.get_entry_info(hash);
+        assert!(!info.in_lru && !info.in_hd && !info.pinned);
+        assert!(info.call_count.is_none());
+        assert!(matches!(cache.pin(hash), Err(CacheError::NotFound(_))));
+
+        // the module is compiled again on its next load
+        let compilations = cache.get_stats().compilations;
+        cache.load_module(&bytecode, u64::MAX, false).unwrap();
+        assert_eq!(cache.get_stats().compilations, compilations + 1);
+    }
+
+    #[test]
+    #[serial]
+    fn test_warm_up() {
+        let tmp_dir = TempDir::new().unwrap();
+        let bytecodes: Vec<Vec<u8>> = (b'0'..b'3').map(make_bytecode).collect();
+        let hashes: Vec<Hash> = bytecodes.iter().map(Hash::compute_from).collect();
+        {
+            let mut cache = ModuleCache::new(make_config(&tmp_dir, 1));
+            for (i, bytecode) in bytecodes.iter().enumerate() {
+                for _ in 0..=i {
+                    cache.load_module(bytecode, u64::MAX, false).unwrap();
+                }
+            }
+            cache.pin(hashes[0]).unwrap();
+        }
+
+        // wipe the HD cache: warmed up modules are compiled again from their tracked bytecode
+        std::fs::remove_dir_all(tmp_dir.path().join("hd_cache")).unwrap();
+        let cache = ModuleCache::new(make_config(&tmp_dir, 1));
+        let stats = cache.get_stats();
+        assert_eq!(stats.pinned_count, 1);
+        assert_eq!(stats.warmed_up_count, 1);
+        assert_eq!(stats.compilations, 2);
+        assert!(cache.get_entry_info(hashes[0]).pinned);
+        assert!(cache.get_entry_info(hashes[2]).in_lru);
+        assert!(!cache.get_entry_info(hashes[1]).in_lru);
+        assert_eq!(cache.get_entry_info(hashes[2]).call_count, Some(3));
+    }
+}
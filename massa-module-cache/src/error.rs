@@ -9,6 +9,8 @@ pub enum CacheError {
     VMError(String),
     /// Load error: {0}
     LoadError(String),
+    /// Module not found: {0}
+    NotFound(String),
 }
 
 impl From<anyhow::Error> for CacheError {
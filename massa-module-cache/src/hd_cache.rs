@@ -170,6 +170,42 @@ impl HDCache {
         }
     }
 
+    /// Retrieve the metadata of a module without deserializing the module itself
+    pub fn get_metadata(&self, hash: Hash) -> Option<ModuleMetadata> {
+        self.db
+            .get(metadata_key!(hash))
+            .expect(CRUD_ERROR)
+            .map(|ser_metadata| {
+                let (_, metadata) = self
+                    .meta_deser
+                    .deserialize::<DeserializeError>(&ser_metadata)
+                    .expect(DATA_DESER_ERROR);
+                metadata
+            })
+    }
+
+    /// Remove a module from the cache
+    pub fn remove(&mut self, hash: Hash) {
+        if self
+            .db
+            .get(metadata_key!(hash))
+            .expect(CRUD_ERROR)
+            .is_none()
+        {
+            return;
+        }
+        let mut batch = WriteBatch::default();
+        batch.delete(module_key!(hash));
+        batch.delete(metadata_key!(hash));
+        self.db.write(batch).expect(CRUD_ERROR);
+        self.entry_count = self.entry_count.saturating_sub(1);
+    }
+
+    /// Number of entries in the cache
+    pub fn entry_count(&self) -> usize {
+        self.entry_count
+    }
+
     /// Try to remove as much as `self.amount_to_snip` entries from the db
     fn snip(&mut self) {
         let mut iter = self.db.raw_iterator();
@@ -4,6 +4,7 @@
 //! See [this discussion](https://github.com/massalabs/massa/discussions/3560#discussioncomment-5190071)
 //! for more information about its behaviour.
 
+mod call_stats;
 pub mod config;
 pub mod controller;
 pub mod error;
@@ -34,6 +34,21 @@ impl LRUCache {
         self.cache.get(&hash).cloned()
     }
 
+    /// Retrieve a copy of a cached module without moving it up in the LRU cache
+    pub fn peek(&self, hash: Hash) -> Option<ModuleInfo> {
+        self.cache.peek(&hash).cloned()
+    }
+
+    /// Remove a module from the LRU cache
+    pub fn remove(&mut self, hash: Hash) -> Option<ModuleInfo> {
+        self.cache.remove(&hash)
+    }
+
+    /// Number of modules in the LRU cache
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
     /// Save a module in the LRU cache
     pub fn insert(&mut self, hash: Hash, module_info: ModuleInfo) {
         self.cache.insert(hash, module_info);
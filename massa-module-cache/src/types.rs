@@ -1,3 +1,4 @@
+use massa_hash::Hash;
 use massa_models::serialization::{StringDeserializer, StringSerializer};
 use massa_sc_runtime::RuntimeModule;
 use massa_serialization::{
@@ -18,6 +19,52 @@ pub enum ModuleInfo {
     ModuleAndDelta((RuntimeModule, u64)),
 }
 
+/// Module cache statistics, cumulated since the node started
+#[derive(Clone, Debug, Default)]
+pub struct ModuleCacheStats {
+    /// number of modules found in RAM, either pinned or in the LRU cache
+    pub lru_hits: u64,
+    /// number of modules missing from RAM
+    pub lru_misses: u64,
+    /// number of modules missing from RAM but found in the HD cache
+    pub hd_hits: u64,
+    /// number of modules missing from both caches
+    pub hd_misses: u64,
+    /// number of module compilations
+    pub compilations: u64,
+    /// total time spent compiling modules, in microseconds
+    pub compilation_time_micros: u64,
+    /// number of modules in the LRU cache
+    pub lru_entry_count: usize,
+    /// number of entries in the HD cache
+    pub hd_entry_count: usize,
+    /// number of modules pinned in RAM
+    pub pinned_count: usize,
+    /// number of modules whose call statistics are tracked
+    pub tracked_count: usize,
+    /// number of modules loaded in RAM by the startup warm-up
+    pub warmed_up_count: usize,
+}
+
+/// Cache status of a module
+#[derive(Clone, Debug)]
+pub struct ModuleCacheEntryInfo {
+    /// hash of the module bytecode
+    pub hash: Hash,
+    /// the module is in the LRU cache
+    pub in_lru: bool,
+    /// the module is in the HD cache
+    pub in_hd: bool,
+    /// the module is pinned in RAM
+    pub pinned: bool,
+    /// number of times the module was loaded for execution, if tracked
+    pub call_count: Option<u64>,
+    /// instance initialization cost, if known
+    pub init_cost: Option<u64>,
+    /// invalidity reason, if the module is invalid
+    pub invalid: Option<String>,
+}
+
 #[derive(PartialEq, Eq)]
 /// Metadata type
 pub enum ModuleMetadata {
@@ -0,0 +1,395 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Local development network: a single staking node with its own generated genesis.
+//!
+//! The test keys are derived deterministically from their index, so that the same addresses
+//! are funded at each start. The first test key is the only staker of the network.
+//!
+//! A devnet can also be forked from a final state snapshot of another network (see `MassaDBController::backup_db`):
+//! the snapshot is copied to the devnet ledger, modified as described by a fork file,
+//! and the network restarts from it with a new genesis timestamp.
+//!
+//! Example of fork file, relative paths being resolved from the directory of the fork file:
+//! ```json
+//! {
+//!     "snapshot": "backup_1200_31",
+//!     "balances": { "AU12...": "1000000" },
+//!     "bytecodes": { "AS12...": "patched_contract.wasm" },
+//!     "rolls": { "AU12...": 100 },
+//!     "reset_stakers": true
+//! }
+//! ```
+
+use crate::settings::DevnetSettings;
+use massa_db_exports::{MassaDBConfig, MassaDBController};
+use massa_db_worker::MassaDB;
+use massa_final_state::StateFork;
+use massa_hash::Hash;
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    bytecode::Bytecode,
+    config::{
+        MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE, MAX_BOOTSTRAP_VERSIONING_ELEMENTS_SIZE, THREAD_COUNT,
+    },
+    slot::Slot,
+};
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Marker file written in the devnet directory, which is only wiped at the next start if it contains it
+const DEVNET_MARKER_FILE: &str = ".massa_devnet";
+
+/// Genesis of a local development network, written to disk by `generate_devnet`
+pub struct DevnetGenesis {
+    /// time between the periods in the same thread
+    pub t0: MassaTime,
+    /// genesis timestamp
+    pub genesis_timestamp: MassaTime,
+    /// path of the initial ledger
+    pub initial_ledger_path: PathBuf,
+    /// path of the initial rolls
+    pub initial_rolls_path: PathBuf,
+    /// path of the initial deferred credits
+    pub initial_deferred_credits_path: PathBuf,
+    /// path of the initial peers (none)
+    pub initial_peers_path: PathBuf,
+    /// path of the staking wallet
+    pub staking_wallet_path: PathBuf,
+    /// path of the ledger database
+    pub disk_ledger_path: PathBuf,
+    /// keypair of the single staker
+    pub staker: KeyPair,
+    /// fork of another network, if the devnet is started from a snapshot
+    pub fork: Option<DevnetFork>,
+}
+
+/// Fork of another network on which a devnet is started
+pub struct DevnetFork {
+    /// period at which the devnet restarts from the snapshot
+    pub last_start_period: u64,
+    /// modifications applied to the snapshot
+    pub state_fork: StateFork,
+}
+
+/// Fork file, describing the snapshot to fork and the modifications to apply to it
+#[derive(Debug, Deserialize)]
+struct ForkFile {
+    /// snapshot of the final state (backup of the ledger database)
+    snapshot: PathBuf,
+    /// balances to set
+    #[serde(default)]
+    balances: BTreeMap<Address, Amount>,
+    /// files of the bytecodes to set
+    #[serde(default)]
+    bytecodes: BTreeMap<Address, PathBuf>,
+    /// roll counts to set
+    #[serde(default)]
+    rolls: BTreeMap<Address, u64>,
+    /// only keep the rolls of the local stakers (the devnet staker and the addresses of `rolls`)
+    #[serde(default = "default_reset_stakers")]
+    reset_stakers: bool,
+}
+
+fn default_reset_stakers() -> bool {
+    true
+}
+
+/// Returns the test keypair of the given index, identical on every devnet
+pub fn devnet_keypair(index: u64) -> KeyPair {
+    let seed = Hash::compute_from(format!("massa devnet key {}", index).as_bytes());
+    // keypair version 0 followed by the secret key bytes
+    let mut bytes = vec![0u8];
+    bytes.extend_from_slice(seed.to_bytes());
+    KeyPair::from_bytes(&bytes).expect("could not build devnet keypair")
+}
+
+/// Writes a JSON value to a file of the devnet directory
+fn write_json(path: &Path, value: &Value) -> anyhow::Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+/// Copies a directory recursively
+fn copy_dir(from: &Path, to: &Path) -> anyhow::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the slot of the final state stored in a ledger database
+fn read_db_slot(path: &Path) -> anyhow::Result<Slot> {
+    let db = MassaDB::new(MassaDBConfig {
+        path: path.to_path_buf(),
+        max_history_length: 0,
+        max_final_state_elements_size: MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE.try_into()?,
+        max_versioning_elements_size: MAX_BOOTSTRAP_VERSIONING_ELEMENTS_SIZE.try_into()?,
+        thread_count: THREAD_COUNT,
+    });
+    Ok(db.get_change_id()?)
+}
+
+/// Generates the genesis of a local development network in the devnet directory,
+/// starting from a clean directory.
+/// A non-empty directory is only wiped if it was created by a previous devnet.
+/// If a fork file is given, the devnet is forked from the snapshot it describes.
+pub fn generate_devnet(
+    settings: &DevnetSettings,
+    fork_file: Option<&Path>,
+) -> anyhow::Result<DevnetGenesis> {
+    if settings.t0.as_millis() == 0 || settings.t0.as_millis() % (THREAD_COUNT as u64) != 0 {
+        anyhow::bail!(
+            "devnet t0 must be a non-zero multiple of the thread count ({})",
+            THREAD_COUNT
+        );
+    }
+    if settings.test_key_count == 0 {
+        anyhow::bail!("devnet needs at least one test key to stake");
+    }
+
+    if settings.path.exists() {
+        let is_empty = std::fs::read_dir(&settings.path)?.next().is_none();
+        if !is_empty && !settings.path.join(DEVNET_MARKER_FILE).is_file() {
+            anyhow::bail!(
+                "refusing to wipe {}: the directory is not empty and was not created by a devnet",
+                settings.path.display()
+            );
+        }
+        std::fs::remove_dir_all(&settings.path)?;
+    }
+    std::fs::create_dir_all(&settings.path)?;
+    std::fs::write(settings.path.join(DEVNET_MARKER_FILE), "")?;
+
+    let mut ledger = Map::new();
+    let mut test_keys = Vec::new();
+    for index in 0..settings.test_key_count {
+        let keypair = devnet_keypair(index);
+        let address = Address::from_public_key(&keypair.get_public_key());
+        ledger.insert(
+            address.to_string(),
+            json!({
+                "balance": settings.test_key_balance.to_string(),
+                "datastore": [],
+                "bytecode": [],
+            }),
+        );
+        test_keys.push(json!({
+            "address": address.to_string(),
+            "secret_key": keypair.to_string(),
+            "staker": index == 0,
+        }));
+    }
+    let staker = devnet_keypair(0);
+    let staker_address = Address::from_public_key(&staker.get_public_key());
+
+    let disk_ledger_path = settings.path.join("ledger");
+    let mut genesis_timestamp = MassaTime::now().saturating_add(settings.genesis_delay);
+    let fork = match fork_file {
+        Some(fork_file) => {
+            let (fork, last_start_period) = load_fork(
+                settings,
+                fork_file,
+                &ledger,
+                staker_address,
+                &disk_ledger_path,
+            )?;
+            // the first slot after the restart is reached after the genesis delay
+            genesis_timestamp = genesis_timestamp.checked_sub(
+                settings
+                    .t0
+                    .checked_mul(last_start_period.saturating_add(1))?,
+            )?;
+            Some(DevnetFork {
+                last_start_period,
+                state_fork: fork,
+            })
+        }
+        None => None,
+    };
+
+    let genesis = DevnetGenesis {
+        t0: settings.t0,
+        genesis_timestamp,
+        initial_ledger_path: settings.path.join("initial_ledger.json"),
+        initial_rolls_path: settings.path.join("initial_rolls.json"),
+        initial_deferred_credits_path: settings.path.join("deferred_credits.json"),
+        initial_peers_path: settings.path.join("initial_peers.json"),
+        staking_wallet_path: settings.path.join("staking_wallets"),
+        disk_ledger_path,
+        staker,
+        fork,
+    };
+    write_json(&genesis.initial_ledger_path, &Value::Object(ledger))?;
+    write_json(
+        &genesis.initial_rolls_path,
+        &json!({ staker_address.to_string(): settings.staker_rolls }),
+    )?;
+    write_json(&genesis.initial_deferred_credits_path, &json!({}))?;
+    write_json(&genesis.initial_peers_path, &json!({}))?;
+    write_json(
+        &settings.path.join("test_keys.json"),
+        &Value::Array(test_keys),
+    )?;
+    Ok(genesis)
+}
+
+/// Loads a fork file and copies its snapshot to the devnet ledger.
+/// The test keys are funded and the staker gets its rolls, unless the fork file sets them.
+///
+/// # Returns
+/// The modifications to apply to the snapshot, and the period at which the devnet restarts
+fn load_fork(
+    settings: &DevnetSettings,
+    fork_file: &Path,
+    test_ledger: &Map<String, Value>,
+    staker_address: Address,
+    disk_ledger_path: &Path,
+) -> anyhow::Result<(StateFork, u64)> {
+    let fork_dir = fork_file.parent().unwrap_or_else(|| Path::new("."));
+    let fork_file: ForkFile = serde_json::from_str(&std::fs::read_to_string(fork_file)?)?;
+
+    let snapshot_path = fork_dir.join(&fork_file.snapshot);
+    copy_dir(&snapshot_path, disk_ledger_path)?;
+    let snapshot_slot = read_db_slot(disk_ledger_path)?;
+
+    let mut state_fork = StateFork {
+        reset_stakers: fork_file.reset_stakers,
+        ..Default::default()
+    };
+    for address in test_ledger.keys() {
+        state_fork
+            .balances
+            .insert(address.parse()?, settings.test_key_balance);
+    }
+    state_fork.balances.extend(fork_file.balances);
+    for (address, path) in fork_file.bytecodes {
+        let bytecode = std::fs::read(fork_dir.join(path))?;
+        state_fork.bytecodes.insert(address, Bytecode(bytecode));
+    }
+    state_fork
+        .rolls
+        .insert(staker_address, settings.staker_rolls);
+    state_fork.rolls.extend(fork_file.rolls);
+
+    // leave a full period of downtime after the snapshot
+    Ok((state_fork, snapshot_slot.period.saturating_add(2)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_settings(dir: &Path) -> DevnetSettings {
+        DevnetSettings {
+            path: dir.join("devnet"),
+            t0: MassaTime::from_millis(100 * THREAD_COUNT as u64),
+            genesis_delay: MassaTime::from_millis(1000),
+            test_key_count: 3,
+            test_key_balance: Amount::const_init(1000, 0),
+            staker_rolls: 10,
+        }
+    }
+
+    fn read_json(path: &Path) -> Value {
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_generate_devnet_rejects_invalid_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        for t0 in [0, 100 * THREAD_COUNT as u64 + 1] {
+            let settings = DevnetSettings {
+                t0: MassaTime::from_millis(t0),
+                ..test_settings(dir.path())
+            };
+            assert!(generate_devnet(&settings, None).is_err());
+        }
+        let settings = DevnetSettings {
+            test_key_count: 0,
+            ..test_settings(dir.path())
+        };
+        assert!(generate_devnet(&settings, None).is_err());
+        // nothing is written when the settings are rejected
+        assert!(!settings.path.exists());
+    }
+
+    #[test]
+    fn test_generate_devnet_writes_genesis_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = test_settings(dir.path());
+        generate_devnet(&settings, None).unwrap();
+        std::fs::write(settings.path.join("stale.json"), "{}").unwrap();
+
+        // the directory of a previous devnet is wiped
+        let genesis = generate_devnet(&settings, None).unwrap();
+        assert_eq!(genesis.t0, settings.t0);
+        assert!(genesis.fork.is_none());
+        assert!(!settings.path.join("stale.json").exists());
+
+        let ledger = read_json(&genesis.initial_ledger_path);
+        assert_eq!(ledger.as_object().unwrap().len(), 3);
+        for index in 0..3 {
+            let address = Address::from_public_key(&devnet_keypair(index).get_public_key());
+            assert_eq!(
+                ledger[address.to_string()]["balance"],
+                json!(settings.test_key_balance.to_string())
+            );
+        }
+        let staker_address = Address::from_public_key(&genesis.staker.get_public_key());
+        assert_eq!(
+            read_json(&genesis.initial_rolls_path),
+            json!({ staker_address.to_string(): 10 })
+        );
+        assert_eq!(read_json(&genesis.initial_deferred_credits_path), json!({}));
+        assert_eq!(read_json(&genesis.initial_peers_path), json!({}));
+        let test_keys = read_json(&settings.path.join("test_keys.json"));
+        assert_eq!(test_keys.as_array().unwrap().len(), 3);
+        assert_eq!(test_keys[0]["address"], json!(staker_address.to_string()));
+        assert_eq!(test_keys[0]["staker"], json!(true));
+        assert_eq!(test_keys[1]["staker"], json!(false));
+    }
+
+    #[test]
+    fn test_generate_devnet_keeps_foreign_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = test_settings(dir.path());
+        std::fs::create_dir_all(&settings.path).unwrap();
+        std::fs::write(settings.path.join("data.json"), "{}").unwrap();
+
+        assert!(generate_devnet(&settings, None).is_err());
+        assert!(settings.path.join("data.json").exists());
+
+        // an empty directory is used as is
+        std::fs::remove_file(settings.path.join("data.json")).unwrap();
+        generate_devnet(&settings, None).unwrap();
+        assert!(settings.path.join(DEVNET_MARKER_FILE).exists());
+    }
+
+    #[test]
+    fn test_devnet_keys_are_deterministic() {
+        let first_dir = tempfile::tempdir().unwrap();
+        let second_dir = tempfile::tempdir().unwrap();
+        let first = generate_devnet(&test_settings(first_dir.path()), None).unwrap();
+        let second = generate_devnet(&test_settings(second_dir.path()), None).unwrap();
+
+        assert_eq!(first.staker.to_string(), second.staker.to_string());
+        assert_eq!(first.staker.to_string(), devnet_keypair(0).to_string());
+        assert_ne!(devnet_keypair(0).to_string(), devnet_keypair(1).to_string());
+        assert_eq!(
+            read_json(&first_dir.path().join("devnet").join("test_keys.json")),
+            read_json(&second_dir.path().join("devnet").join("test_keys.json"))
+        );
+    }
+}
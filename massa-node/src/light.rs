@@ -0,0 +1,581 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Light sync mode of the node, started with `--light`.
+//!
+//! The node connects to the network like a full node, but only follows the block headers
+//! and their finality with a `LightClient`: it neither executes blocks nor keeps the ledger.
+//! The protocol worker runs in headers-only mode and hands the headers to a consensus
+//! controller backed by the light client, which wishes the missing parents back to protocol.
+//!
+//! Starting from a trusted checkpoint file, the draws of the following slots are asked to
+//! the trusted peers of the configuration, as they can't be verified without the rolls.
+//! The headers and the finality are served through a dedicated JSON-RPC API.
+//!
+//! Known limitations:
+//! * the endorsements of the headers are checked by protocol against the draws: peers sending
+//!   headers whose draws are not known yet are banned, so the draws have to be kept ahead
+//! * the MIP store is not updated from the network, headers announcing a version unknown to the
+//!   compiled MIP list are refused
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use anyhow::Context;
+use crossbeam_channel::{select, tick};
+use massa_api::{Light, LightServer, StopHandle, API};
+use massa_channel::{sender::MassaSender, MassaChannel};
+use massa_consensus_exports::{
+    block_graph_export::BlockGraphExport, bootstrapable_graph::BootstrapableGraph,
+    error::ConsensusError, ConsensusController,
+};
+use massa_light_client::{HeaderOutcome, LightClient, LightClientCheckpoint, LightClientConfig};
+use massa_metrics::{MassaMetrics, MetricsStopper};
+use massa_models::{
+    address::Address,
+    block::BlockGraphStatus,
+    block_header::{BlockHeader, SecuredHeader},
+    block_id::BlockId,
+    clique::Clique,
+    config::constants::{
+        DELTA_F0, ENDORSEMENT_COUNT, MIP_STORE_STATS_BLOCK_CONSIDERED, PERIODS_PER_CYCLE,
+        THREAD_COUNT, VERSION,
+    },
+    denunciation::{Denunciation, DenunciationPrecursor},
+    endorsement::EndorsementId,
+    operation::OperationId,
+    prehash::{PreHashMap, PreHashSet},
+    secure_share::SecureShare,
+    slot::Slot,
+    stats::ConsensusStats,
+    streaming_step::StreamingStep,
+    timeslots::get_latest_block_slot_at_timestamp,
+};
+use massa_pool_exports::PoolController;
+use massa_pos_exports::{CycleDrawStats, PosError, PosResult, Selection, SelectorController};
+use massa_protocol_exports::{PeerId, ProtocolController, ProtocolManager, TransportType};
+use massa_protocol_worker::{create_protocol_controller, start_protocol_controller};
+use massa_storage::Storage;
+use massa_time::{MassaTime, SharedClock};
+use massa_versioning::mips::{get_mip_list, load_mip_list};
+use massa_versioning::versioning::{MipStatsConfig, MipStore};
+use num::rational::Ratio;
+use parking_lot::{Mutex, RwLock};
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::{debug, info, warn};
+
+use crate::settings::SETTINGS;
+
+/// Checkpoint file of a light node
+#[derive(Debug, Deserialize)]
+struct CheckpointFile {
+    /// header of the latest final block of each thread, ordered by thread
+    final_headers: Vec<SecuredHeader>,
+    /// draws of the slots following the checkpoint, asked to the trusted peers if absent
+    #[serde(default)]
+    draws: Vec<(Slot, Selection)>,
+}
+
+/// Load the trusted checkpoint of the light client
+fn load_checkpoint(path: &Path) -> anyhow::Result<LightClientCheckpoint> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read light checkpoint {}", path.display()))?;
+    let checkpoint: CheckpointFile = serde_json::from_str(&content)
+        .with_context(|| format!("could not parse light checkpoint {}", path.display()))?;
+    Ok(LightClientCheckpoint {
+        final_headers: checkpoint.final_headers,
+        draws: checkpoint.draws.into_iter().collect(),
+    })
+}
+
+/// Consensus controller of a light node, backed by the light client
+#[derive(Clone)]
+struct LightConsensusController {
+    light_client: Arc<RwLock<LightClient>>,
+    protocol_controller: Box<dyn ProtocolController>,
+    /// blocks wished to protocol
+    wished_blocks: Arc<Mutex<PreHashSet<BlockId>>>,
+}
+
+impl LightConsensusController {
+    /// Wish the missing parents of the waiting headers, and stop wishing the others
+    fn update_wishlist(&self) {
+        let missing = self.light_client.read().get_missing_parents();
+        let mut wished_blocks = self.wished_blocks.lock();
+        let new: PreHashMap<BlockId, Option<SecuredHeader>> = missing
+            .difference(&wished_blocks)
+            .map(|block_id| (*block_id, None))
+            .collect();
+        let remove: PreHashSet<BlockId> = wished_blocks.difference(&missing).copied().collect();
+        if new.is_empty() && remove.is_empty() {
+            return;
+        }
+        *wished_blocks = missing;
+        if let Err(err) = self.protocol_controller.send_wishlist_delta(new, remove) {
+            warn!("Light | could not update the block wishlist: {}", err);
+        }
+    }
+
+    fn unsupported(&self, what: &str) -> ConsensusError {
+        ConsensusError::ContainerInconsistency(format!("{} is not available on a light node", what))
+    }
+}
+
+impl ConsensusController for LightConsensusController {
+    fn get_block_graph_status(
+        &self,
+        _start_slot: Option<Slot>,
+        _end_slot: Option<Slot>,
+    ) -> Result<BlockGraphExport, ConsensusError> {
+        Err(self.unsupported("the block graph export"))
+    }
+
+    fn get_block_statuses(&self, ids: &[BlockId]) -> Vec<BlockGraphStatus> {
+        let light_client = self.light_client.read();
+        let blockclique = light_client.get_blockclique();
+        ids.iter()
+            .map(|block_id| match light_client.is_final(block_id) {
+                Some(true) => BlockGraphStatus::Final,
+                Some(false) if blockclique.contains(block_id) => {
+                    BlockGraphStatus::ActiveInBlockclique
+                }
+                Some(false) => BlockGraphStatus::ActiveInAlternativeCliques,
+                None => BlockGraphStatus::NotFound,
+            })
+            .collect()
+    }
+
+    fn get_cliques(&self) -> Vec<Clique> {
+        Vec::new()
+    }
+
+    fn get_bootstrap_part(
+        &self,
+        _cursor: StreamingStep<PreHashSet<BlockId>>,
+        _execution_cursor: StreamingStep<Slot>,
+    ) -> Result<
+        (
+            BootstrapableGraph,
+            PreHashSet<BlockId>,
+            StreamingStep<PreHashSet<BlockId>>,
+        ),
+        ConsensusError,
+    > {
+        Err(self.unsupported("bootstrap"))
+    }
+
+    fn get_stats(&self) -> Result<ConsensusStats, ConsensusError> {
+        Err(self.unsupported("the consensus stats"))
+    }
+
+    fn get_best_parents(&self) -> Vec<(BlockId, u64)> {
+        self.light_client
+            .read()
+            .get_latest_final_blocks_periods()
+            .to_vec()
+    }
+
+    fn get_blockclique_block_at_slot(&self, _slot: Slot) -> Option<BlockId> {
+        None
+    }
+
+    fn get_latest_blockclique_block_at_slot(&self, slot: Slot) -> BlockId {
+        self.light_client.read().get_latest_final_blocks_periods()[slot.thread as usize].0
+    }
+
+    fn register_block(
+        &self,
+        block_id: BlockId,
+        _slot: Slot,
+        _block_storage: Storage,
+        _created: bool,
+    ) {
+        debug!("Light | ignoring full block {}", block_id);
+    }
+
+    fn register_block_header(&self, block_id: BlockId, header: SecureShare<BlockHeader, BlockId>) {
+        {
+            let mut light_client = self.light_client.write();
+            match light_client.process_header(header) {
+                Ok(HeaderOutcome::Discarded(reason)) => {
+                    debug!("Light | header {} discarded: {}", block_id, reason)
+                }
+                Ok(_) => {}
+                Err(err) => warn!("Light | could not process header {}: {}", block_id, err),
+            }
+            for final_block_id in light_client.take_new_final_blocks() {
+                debug!("Light | block {} is final", final_block_id);
+            }
+        }
+        self.update_wishlist();
+    }
+
+    fn mark_invalid_block(&self, block_id: BlockId, _header: SecureShare<BlockHeader, BlockId>) {
+        debug!("Light | block {} marked as invalid by protocol", block_id);
+    }
+
+    fn clone_box(&self) -> Box<dyn ConsensusController> {
+        Box::new(self.clone())
+    }
+}
+
+/// Selector of a light node, serving the draws known by the light client
+#[derive(Clone)]
+struct LightSelectorController {
+    light_client: Arc<RwLock<LightClient>>,
+}
+
+impl SelectorController for LightSelectorController {
+    fn wait_for_draws(&self, _cycle: u64) -> PosResult<u64> {
+        Err(PosError::UnsupportedOperation(
+            "a light node does not draw".into(),
+        ))
+    }
+
+    fn feed_cycle(
+        &self,
+        _cycle: u64,
+        _lookback_rolls: BTreeMap<Address, u64>,
+        _lookback_seed: massa_hash::Hash,
+    ) -> PosResult<()> {
+        Err(PosError::UnsupportedOperation(
+            "a light node does not draw".into(),
+        ))
+    }
+
+    fn get_selection(&self, slot: Slot) -> PosResult<Selection> {
+        self.light_client
+            .read()
+            .get_selection(&slot)
+            .cloned()
+            .ok_or_else(|| PosError::CycleUnavailable(slot.get_cycle(PERIODS_PER_CYCLE)))
+    }
+
+    fn get_producer(&self, slot: Slot) -> PosResult<Address> {
+        self.get_selection(slot).map(|selection| selection.producer)
+    }
+
+    fn get_available_selections_in_range<'a>(
+        &self,
+        slot_range: std::ops::RangeInclusive<Slot>,
+        restrict_to_addresses: Option<&'a PreHashSet<Address>>,
+    ) -> PosResult<BTreeMap<Slot, Selection>> {
+        let mut selections = self.light_client.read().get_selections_in_range(slot_range);
+        if let Some(addresses) = restrict_to_addresses {
+            selections.retain(|_, selection| {
+                addresses.contains(&selection.producer)
+                    || selection
+                        .endorsements
+                        .iter()
+                        .any(|address| addresses.contains(address))
+            });
+        }
+        Ok(selections)
+    }
+
+    fn get_next_selections<'a>(
+        &self,
+        _from_slot: Slot,
+        _addresses: &'a PreHashSet<Address>,
+        _max_count: usize,
+    ) -> PosResult<BTreeMap<Slot, Selection>> {
+        Err(PosError::UnsupportedOperation(
+            "a light node does not look ahead draws".into(),
+        ))
+    }
+
+    fn get_draw_stats<'a>(
+        &self,
+        _addresses: &'a PreHashSet<Address>,
+    ) -> PosResult<BTreeMap<u64, BTreeMap<Address, CycleDrawStats>>> {
+        Err(PosError::UnsupportedOperation(
+            "a light node does not know the rolls".into(),
+        ))
+    }
+
+    fn feed_selections(&self, selections: BTreeMap<Slot, Selection>) -> PosResult<()> {
+        self.light_client
+            .write()
+            .add_draws(selections)
+            .map_err(|err| PosError::ContainerInconsistency(err.to_string()))
+    }
+
+    fn clone_box(&self) -> Box<dyn SelectorController> {
+        Box::new(self.clone())
+    }
+}
+
+/// Pool of a light node, which neither keeps operations nor endorsements
+#[derive(Clone)]
+struct LightPoolController {
+    storage: Storage,
+}
+
+impl PoolController for LightPoolController {
+    fn add_operations(&mut self, _ops: Storage) {}
+
+    fn add_endorsements(&mut self, _endorsements: Storage) {}
+
+    fn add_denunciation_precursor(&self, _denunciation_precursor: DenunciationPrecursor) {}
+
+    fn notify_final_cs_periods(&mut self, _final_cs_periods: &[u64]) {}
+
+    fn get_block_operations(&self, _slot: &Slot) -> (Vec<OperationId>, Storage) {
+        (Vec::new(), self.storage.clone_without_refs())
+    }
+
+    fn get_block_endorsements(
+        &self,
+        _target_block: &BlockId,
+        _slot: &Slot,
+    ) -> (Vec<Option<EndorsementId>>, Storage) {
+        (Vec::new(), self.storage.clone_without_refs())
+    }
+
+    fn get_block_denunciations(&self, _target_slot: &Slot) -> Vec<Denunciation> {
+        Vec::new()
+    }
+
+    fn get_endorsement_count(&self) -> usize {
+        0
+    }
+
+    fn get_operation_count(&self) -> usize {
+        0
+    }
+
+    fn contains_endorsements(&self, endorsements: &[EndorsementId]) -> Vec<bool> {
+        vec![false; endorsements.len()]
+    }
+
+    fn contains_operations(&self, operations: &[OperationId]) -> Vec<bool> {
+        vec![false; operations.len()]
+    }
+
+    fn get_denunciation_count(&self) -> usize {
+        0
+    }
+
+    fn clone_box(&self) -> Box<dyn PoolController> {
+        Box::new(self.clone())
+    }
+
+    fn get_final_cs_periods(&self) -> Vec<u64> {
+        Vec::new()
+    }
+}
+
+/// Components of a running light node
+pub struct LightNode {
+    protocol_manager: Box<dyn ProtocolManager>,
+    api_handle: StopHandle,
+    metrics_stopper: MetricsStopper,
+    sync_stopper: Option<MassaSender<()>>,
+    sync_handle: Option<JoinHandle<()>>,
+}
+
+impl LightNode {
+    /// Stop the light node
+    pub async fn stop(mut self) {
+        self.api_handle.stop().await;
+        info!("API | LIGHT JsonRPC | stopped");
+
+        if let Some(tx) = self.sync_stopper.take() {
+            if let Err(e) = tx.send(()) {
+                warn!("failed to send stop signal to light sync thread: {:?}", e);
+            }
+        }
+        if let Some(handle) = self.sync_handle.take() {
+            if handle.join().is_err() {
+                warn!("failed to join light sync thread");
+            }
+        }
+
+        self.protocol_manager.stop();
+        self.metrics_stopper.stop();
+    }
+}
+
+/// Start a light node following the headers from the configured checkpoint
+pub async fn launch_light(
+    genesis_timestamp: MassaTime,
+    t0: MassaTime,
+    clock: SharedClock,
+) -> anyhow::Result<LightNode> {
+    let light_settings = &SETTINGS.light;
+    let checkpoint = load_checkpoint(&light_settings.checkpoint_path)?;
+    let light_client = Arc::new(RwLock::new(LightClient::new(
+        LightClientConfig {
+            thread_count: THREAD_COUNT,
+            endorsement_count: ENDORSEMENT_COUNT,
+            delta_f0: DELTA_F0,
+            force_keep_final_periods: SETTINGS.consensus.force_keep_final_periods,
+            max_waiting_headers: light_settings.max_waiting_headers,
+            max_discarded_blocks: light_settings.max_discarded_blocks,
+        },
+        checkpoint,
+    )?));
+    // headers older than the checkpoint are refused by the light client anyway
+    let last_start_period = light_client
+        .read()
+        .get_latest_final_blocks_periods()
+        .iter()
+        .map(|(_, period)| *period)
+        .min()
+        .unwrap_or_default();
+
+    let (massa_metrics, metrics_stopper) = MassaMetrics::new(
+        SETTINGS.metrics.enabled,
+        SETTINGS.metrics.bind,
+        THREAD_COUNT,
+        SETTINGS.metrics.tick_delay.to_duration(),
+    );
+
+    let mip_stats_config = MipStatsConfig {
+        block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+        warn_announced_version_ratio: Ratio::new(
+            u64::from(SETTINGS.versioning.mip_stats_warn_announced_version),
+            100,
+        ),
+    };
+    let mip_list = match &SETTINGS.versioning.mip_list_path {
+        Some(path) => load_mip_list(path)?,
+        None => get_mip_list().into(),
+    };
+    let mip_store = MipStore::try_from((mip_list, mip_stats_config))?;
+
+    let mut listeners = HashMap::default();
+    listeners.insert(SETTINGS.protocol.bind, TransportType::Tcp);
+    let mut protocol_config = crate::protocol_config(
+        None,
+        genesis_timestamp,
+        t0,
+        last_start_period,
+        listeners,
+        clock.clone(),
+    );
+    protocol_config.headers_only = true;
+    let (protocol_controller, protocol_channels) =
+        create_protocol_controller(protocol_config.clone());
+
+    let consensus_controller = LightConsensusController {
+        light_client: light_client.clone(),
+        protocol_controller: protocol_controller.clone(),
+        wished_blocks: Default::default(),
+    };
+    let storage = Storage::create_root();
+    let (protocol_manager, keypair, node_id) = start_protocol_controller(
+        protocol_config,
+        Box::new(LightSelectorController {
+            light_client: light_client.clone(),
+        }),
+        Box::new(consensus_controller.clone()),
+        None,
+        Box::new(LightPoolController {
+            storage: storage.clone_without_refs(),
+        }),
+        storage,
+        protocol_channels,
+        mip_store,
+        massa_metrics,
+    )?;
+    info!("Light | node {} following the headers", node_id);
+
+    let (sync_stopper, sync_handle) = start_light_sync(
+        light_client.clone(),
+        consensus_controller,
+        protocol_controller,
+        light_settings.trusted_peers.clone(),
+        genesis_timestamp,
+        t0,
+        clock.clone(),
+    );
+
+    let api_config = crate::api_config(keypair, genesis_timestamp, t0, last_start_period, 0, clock);
+    let api_handle = API::<Light>::new(light_client, api_config.clone(), *VERSION)
+        .serve(&light_settings.bind, &api_config)
+        .await?;
+    info!(
+        "API | LIGHT JsonRPC | listening on: {}",
+        light_settings.bind
+    );
+
+    Ok(LightNode {
+        protocol_manager,
+        api_handle,
+        metrics_stopper,
+        sync_stopper,
+        sync_handle,
+    })
+}
+
+/// Start the thread keeping the draws ahead of the current slot and the wishlist up to date
+fn start_light_sync(
+    light_client: Arc<RwLock<LightClient>>,
+    consensus_controller: LightConsensusController,
+    protocol_controller: Box<dyn ProtocolController>,
+    trusted_peers: Vec<PeerId>,
+    genesis_timestamp: MassaTime,
+    t0: MassaTime,
+    clock: SharedClock,
+) -> (Option<MassaSender<()>>, Option<JoinHandle<()>>) {
+    if trusted_peers.is_empty() {
+        warn!(
+            "Light | no trusted peer configured, the draws of the checkpoint will not be extended"
+        );
+    }
+    let (tx_stop, rx_stop) = MassaChannel::new("light_sync_stop".to_string(), Some(1));
+    let check_tick = tick(SETTINGS.light.ask_draws_interval.to_duration());
+    let lookahead = SETTINGS.light.draws_lookahead_periods;
+    match std::thread::Builder::new()
+        .name("light-sync".to_string())
+        .spawn(move || loop {
+            select! {
+                recv(rx_stop) -> _ => {
+                    break;
+                },
+                recv(check_tick) -> _ => {
+                    // parents can be missing after draws were received
+                    consensus_controller.update_wishlist();
+
+                    if trusted_peers.is_empty() {
+                        continue;
+                    }
+                    let next_missing = match light_client.read().next_missing_draws_slot() {
+                        Ok(slot) => slot,
+                        Err(err) => {
+                            warn!("Light | could not compute the missing draws: {}", err);
+                            continue;
+                        }
+                    };
+                    let current_period = match get_latest_block_slot_at_timestamp(
+                        THREAD_COUNT,
+                        t0,
+                        genesis_timestamp,
+                        clock.now(),
+                    ) {
+                        Ok(slot) => slot.map_or(0, |slot| slot.period),
+                        Err(err) => {
+                            warn!("Light | could not get the current slot: {}", err);
+                            continue;
+                        }
+                    };
+                    if next_missing.period <= current_period.saturating_add(lookahead) {
+                        if let Err(err) =
+                            protocol_controller.ask_for_draws(next_missing, trusted_peers.clone())
+                        {
+                            warn!("Light | could not ask for draws: {}", err);
+                        }
+                    }
+                }
+            }
+        }) {
+        Ok(handle) => (Some(tx_stop), Some(handle)),
+        Err(e) => {
+            warn!("Light | Failed to spawn light sync thread: {:?}", e);
+            (None, None)
+        }
+    }
+}
@@ -5,8 +5,13 @@
 #![warn(unused_crate_dependencies)]
 extern crate massa_logging;
 
+use crate::devnet::{generate_devnet, DevnetGenesis};
+use crate::light::launch_light;
 #[cfg(feature = "op_spammer")]
 use crate::operation_injector::start_operation_injector;
+use crate::roll_manager::{
+    disabled_roll_manager, start_roll_manager, RollManagerConfig, RollManagerStopper,
+};
 use crate::settings::SETTINGS;
 use crate::survey::MassaSurvey;
 
@@ -47,16 +52,17 @@ use massa_metrics::{MassaMetrics, MetricsStopper};
 use massa_models::address::Address;
 use massa_models::config::constants::{
     ASYNC_MSG_CST_GAS_COST, BLOCK_REWARD, BOOTSTRAP_RANDOMNESS_SIZE_BYTES, CHANNEL_SIZE,
-    CONSENSUS_BOOTSTRAP_PART_SIZE, DELTA_F0, DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT,
-    END_TIMESTAMP, GENESIS_KEY, GENESIS_TIMESTAMP, INITIAL_DRAW_SEED, LEDGER_COST_PER_BYTE,
-    LEDGER_ENTRY_BASE_COST, LEDGER_ENTRY_DATASTORE_BASE_SIZE, MAX_ADVERTISE_LENGTH, MAX_ASYNC_GAS,
-    MAX_ASYNC_POOL_LENGTH, MAX_BLOCK_SIZE, MAX_BOOTSTRAP_BLOCKS, MAX_BOOTSTRAP_ERROR_LENGTH,
-    MAX_BYTECODE_LENGTH, MAX_CONSENSUS_BLOCKS_IDS, MAX_DATASTORE_ENTRY_COUNT,
-    MAX_DATASTORE_KEY_LENGTH, MAX_DATASTORE_VALUE_LENGTH, MAX_DEFERRED_CREDITS_LENGTH,
-    MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_DENUNCIATION_CHANGES_LENGTH,
-    MAX_ENDORSEMENTS_PER_MESSAGE, MAX_EXECUTED_OPS_CHANGES_LENGTH, MAX_EXECUTED_OPS_LENGTH,
-    MAX_FUNCTION_NAME_LENGTH, MAX_GAS_PER_BLOCK, MAX_LEDGER_CHANGES_COUNT, MAX_LISTENERS_PER_PEER,
-    MAX_OPERATIONS_PER_BLOCK, MAX_OPERATIONS_PER_MESSAGE, MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+    COMPACT_BLOCK_RELAY_MIN_VERSION, CONSENSUS_BOOTSTRAP_PART_SIZE, DELTA_F0,
+    DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT, END_TIMESTAMP, GENESIS_KEY, GENESIS_TIMESTAMP,
+    INITIAL_DRAW_SEED, LEDGER_COST_PER_BYTE, LEDGER_ENTRY_BASE_COST,
+    LEDGER_ENTRY_DATASTORE_BASE_SIZE, MAX_ADVERTISE_LENGTH, MAX_ASYNC_GAS, MAX_ASYNC_POOL_LENGTH,
+    MAX_BLOCK_SIZE, MAX_BOOTSTRAP_BLOCKS, MAX_BOOTSTRAP_ERROR_LENGTH, MAX_BYTECODE_LENGTH,
+    MAX_CONSENSUS_BLOCKS_IDS, MAX_DATASTORE_ENTRY_COUNT, MAX_DATASTORE_KEY_LENGTH,
+    MAX_DATASTORE_VALUE_LENGTH, MAX_DEFERRED_CREDITS_LENGTH, MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+    MAX_DENUNCIATION_CHANGES_LENGTH, MAX_DRAWS_PER_MESSAGE, MAX_ENDORSEMENTS_PER_MESSAGE,
+    MAX_EXECUTED_OPS_CHANGES_LENGTH, MAX_EXECUTED_OPS_LENGTH, MAX_FUNCTION_NAME_LENGTH,
+    MAX_GAS_PER_BLOCK, MAX_LEDGER_CHANGES_COUNT, MAX_LISTENERS_PER_PEER, MAX_OPERATIONS_PER_BLOCK,
+    MAX_OPERATIONS_PER_MESSAGE, MAX_OPERATION_DATASTORE_ENTRY_COUNT,
     MAX_OPERATION_DATASTORE_KEY_LENGTH, MAX_OPERATION_DATASTORE_VALUE_LENGTH,
     MAX_OPERATION_STORAGE_TIME, MAX_PARAMETERS_SIZE, MAX_PEERS_IN_ANNOUNCEMENT_LIST,
     MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH, MAX_SIZE_CHANNEL_COMMANDS_CONNECTIVITY,
@@ -68,10 +74,10 @@ use massa_models::config::constants::{
     MAX_SIZE_CHANNEL_COMMANDS_RETRIEVAL_OPERATIONS, MAX_SIZE_CHANNEL_NETWORK_TO_BLOCK_HANDLER,
     MAX_SIZE_CHANNEL_NETWORK_TO_ENDORSEMENT_HANDLER, MAX_SIZE_CHANNEL_NETWORK_TO_OPERATION_HANDLER,
     MAX_SIZE_CHANNEL_NETWORK_TO_PEER_HANDLER, MIP_STORE_STATS_BLOCK_CONSIDERED,
-    OPERATION_VALIDITY_PERIODS, PERIODS_PER_CYCLE, POS_MISS_RATE_DEACTIVATION_THRESHOLD,
-    POS_SAVED_CYCLES, PROTOCOL_CONTROLLER_CHANNEL_SIZE, PROTOCOL_EVENT_CHANNEL_SIZE,
-    ROLL_COUNT_TO_SLASH_ON_DENUNCIATION, ROLL_PRICE, SELECTOR_DRAW_CACHE_SIZE, T0, THREAD_COUNT,
-    VERSION,
+    OPERATION_RECONCILIATION_MIN_VERSION, OPERATION_VALIDITY_PERIODS, PERIODS_PER_CYCLE,
+    POS_MISS_RATE_DEACTIVATION_THRESHOLD, POS_SAVED_CYCLES, PROTOCOL_CONTROLLER_CHANNEL_SIZE,
+    PROTOCOL_EVENT_CHANNEL_SIZE, ROLL_COUNT_TO_SLASH_ON_DENUNCIATION, ROLL_PRICE,
+    SELECTOR_DRAW_CACHE_SIZE, T0, THREAD_COUNT, VERSION,
 };
 use massa_models::config::{
     BASE_OPERATION_GAS_COST, KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
@@ -82,21 +88,22 @@ use massa_models::config::{
 use massa_models::slot::Slot;
 use massa_pool_exports::{PoolBroadcasts, PoolChannels, PoolConfig, PoolManager};
 use massa_pool_worker::start_pool_controller;
-use massa_pos_exports::{PoSConfig, SelectorConfig, SelectorManager};
+use massa_pos_exports::{PoSConfig, SelectorChannels, SelectorConfig, SelectorManager};
 use massa_pos_worker::start_selector_worker;
 use massa_protocol_exports::{ProtocolConfig, ProtocolManager, TransportType};
 use massa_protocol_worker::{create_protocol_controller, start_protocol_controller};
 use massa_signature::KeyPair;
 use massa_storage::Storage;
-use massa_time::MassaTime;
+use massa_time::{MassaTime, OffsetClock, SharedClock};
 use massa_versioning::keypair_factory::KeyPairFactory;
-use massa_versioning::mips::get_mip_list;
-use massa_versioning::versioning::{MipStatsConfig, MipStore};
+use massa_versioning::mips::{get_mip_list, load_mip_list};
+use massa_versioning::versioning::{MipInfo, MipState, MipStatsConfig, MipStore};
 use massa_wallet::Wallet;
 use num::rational::Ratio;
 use parking_lot::RwLock;
 use settings::GrpcSettings;
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Condvar, Mutex};
@@ -108,13 +115,18 @@ use tokio::sync::broadcast;
 use tracing::{debug, error, info, warn};
 use tracing_subscriber::filter::{filter_fn, LevelFilter};
 
+mod devnet;
+mod light;
 #[cfg(feature = "op_spammer")]
 mod operation_injector;
+mod roll_manager;
 mod settings;
 mod survey;
 
 async fn launch(
     args: &Args,
+    devnet: Option<&DevnetGenesis>,
+    clock: SharedClock,
     node_wallet: Arc<RwLock<Wallet>>,
     sig_int_toggled: Arc<(Mutex<bool>, Condvar)>,
 ) -> (
@@ -133,14 +145,24 @@ async fn launch(
     Option<massa_grpc::server::StopHandle>,
     MetricsStopper,
     MassaSurveyStopper,
+    RollManagerStopper,
 ) {
+    // a devnet has its own genesis and period duration
+    let t0 = devnet.map_or(T0, |genesis| genesis.t0);
+    let genesis_timestamp = devnet.map_or(*GENESIS_TIMESTAMP, |genesis| genesis.genesis_timestamp);
+    // a forked devnet restarts from the snapshot it was forked from
+    let fork = devnet.and_then(|genesis| genesis.fork.as_ref());
+    let restart_from_snapshot_at_period = fork
+        .map(|fork| fork.last_start_period)
+        .or(args.restart_from_snapshot_at_period);
+
     let now = MassaTime::now();
     // Do not start if genesis is in the future. This is meant to prevent nodes
     // from desync if the bootstrap nodes keep a previous ledger
     #[cfg(all(not(feature = "sandbox"), not(feature = "bootstrap_server")))]
     {
-        if *GENESIS_TIMESTAMP > now {
-            let (days, hours, mins, secs) = GENESIS_TIMESTAMP
+        if devnet.is_none() && genesis_timestamp > now {
+            let (days, hours, mins, secs) = genesis_timestamp
                 .saturating_sub(now)
                 .days_hours_mins_secs()
                 .unwrap();
@@ -165,7 +187,7 @@ async fn launch(
         // Simulate downtime
         // last_start_period should be set to trigger after the DOWNTIME_END_TIMESTAMP
         #[cfg(not(feature = "bootstrap_server"))]
-        if now >= DOWNTIME_START_TIMESTAMP && now <= DOWNTIME_END_TIMESTAMP {
+        if devnet.is_none() && now >= DOWNTIME_START_TIMESTAMP && now <= DOWNTIME_END_TIMESTAMP {
             let (days, hours, mins, secs) = DOWNTIME_END_TIMESTAMP
                 .saturating_sub(now)
                 .days_hours_mins_secs()
@@ -174,8 +196,8 @@ async fn launch(
             if let Ok(Some(end_period)) =
                 massa_models::timeslots::get_latest_block_slot_at_timestamp(
                     THREAD_COUNT,
-                    T0,
-                    *GENESIS_TIMESTAMP,
+                    t0,
+                    genesis_timestamp,
                     DOWNTIME_END_TIMESTAMP,
                 )
             {
@@ -193,11 +215,13 @@ async fn launch(
     }
     // Storage shared by multiple components.
     let shared_storage: Storage = Storage::create_root();
-
     // init final state
     let ledger_config = LedgerConfig {
         thread_count: THREAD_COUNT,
-        initial_ledger_path: SETTINGS.ledger.initial_ledger_path.clone(),
+        initial_ledger_path: devnet.map_or_else(
+            || SETTINGS.ledger.initial_ledger_path.clone(),
+            |genesis| genesis.initial_ledger_path.clone(),
+        ),
         max_key_length: MAX_DATASTORE_KEY_LENGTH,
         max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
     };
@@ -215,7 +239,10 @@ async fn launch(
         max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
         max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
         max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
-        initial_deferred_credits_path: SETTINGS.ledger.initial_deferred_credits_path.clone(),
+        initial_deferred_credits_path: match devnet {
+            Some(genesis) => Some(genesis.initial_deferred_credits_path.clone()),
+            None => SETTINGS.ledger.initial_deferred_credits_path.clone(),
+        },
     };
     let executed_ops_config = ExecutedOpsConfig {
         thread_count: THREAD_COUNT,
@@ -237,12 +264,15 @@ async fn launch(
         thread_count: THREAD_COUNT,
         periods_per_cycle: PERIODS_PER_CYCLE,
         initial_seed_string: INITIAL_DRAW_SEED.into(),
-        initial_rolls_path: SETTINGS.selector.initial_rolls_path.clone(),
+        initial_rolls_path: devnet.map_or_else(
+            || SETTINGS.selector.initial_rolls_path.clone(),
+            |genesis| genesis.initial_rolls_path.clone(),
+        ),
         endorsement_count: ENDORSEMENT_COUNT,
         max_executed_denunciations_length: MAX_DENUNCIATION_CHANGES_LENGTH,
         max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
-        t0: T0,
-        genesis_timestamp: *GENESIS_TIMESTAMP,
+        t0,
+        genesis_timestamp,
     };
 
     // Start massa metrics
@@ -253,14 +283,18 @@ async fn launch(
         SETTINGS.metrics.tick_delay.to_duration(),
     );
 
+    let disk_ledger_path = devnet.map_or_else(
+        || SETTINGS.ledger.disk_ledger_path.clone(),
+        |genesis| genesis.disk_ledger_path.clone(),
+    );
+
     // Remove current disk ledger if there is one and we don't want to restart from snapshot
     // NOTE: this is temporary, since we cannot currently handle bootstrap from remaining ledger
-    if args.keep_ledger || args.restart_from_snapshot_at_period.is_some() {
+    if args.keep_ledger || restart_from_snapshot_at_period.is_some() {
         info!("Loading old ledger for next episode");
     } else {
-        if SETTINGS.ledger.disk_ledger_path.exists() {
-            std::fs::remove_dir_all(SETTINGS.ledger.disk_ledger_path.clone())
-                .expect("disk ledger delete failed");
+        if disk_ledger_path.exists() {
+            std::fs::remove_dir_all(disk_ledger_path.clone()).expect("disk ledger delete failed");
         }
         if SETTINGS.execution.hd_cache_path.exists() {
             std::fs::remove_dir_all(SETTINGS.execution.hd_cache_path.clone())
@@ -269,7 +303,7 @@ async fn launch(
     }
 
     let db_config = MassaDBConfig {
-        path: SETTINGS.ledger.disk_ledger_path.clone(),
+        path: disk_ledger_path,
         max_history_length: SETTINGS.ledger.final_history_length,
         max_final_state_elements_size: MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE.try_into().unwrap(),
         max_versioning_elements_size: MAX_BOOTSTRAP_VERSIONING_ELEMENTS_SIZE.try_into().unwrap(),
@@ -283,15 +317,26 @@ async fn launch(
     let ledger = FinalLedger::new(ledger_config.clone(), db.clone());
 
     // launch selector worker
-    let (selector_manager, selector_controller) = start_selector_worker(SelectorConfig {
+    let selector_config = SelectorConfig {
         max_draw_cache: SELECTOR_DRAW_CACHE_SIZE,
         channel_size: CHANNEL_SIZE,
         thread_count: THREAD_COUNT,
         endorsement_count: ENDORSEMENT_COUNT,
         periods_per_cycle: PERIODS_PER_CYCLE,
         genesis_address: Address::from_public_key(&GENESIS_KEY.get_public_key()),
-    })
-    .expect("could not start selector worker");
+        broadcast_cycle_draws_channel_capacity: SETTINGS
+            .selector
+            .broadcast_cycle_draws_channel_capacity,
+    };
+    let selector_channels = SelectorChannels {
+        cycle_draws_sender: broadcast::channel(
+            selector_config.broadcast_cycle_draws_channel_capacity,
+        )
+        .0,
+    };
+    let (selector_manager, selector_controller) =
+        start_selector_worker(selector_config, selector_channels.clone())
+            .expect("could not start selector worker");
 
     // Creates an empty default store
     let mip_stats_config = MipStatsConfig {
@@ -305,7 +350,7 @@ async fn launch(
 
     // Create final state, either from a snapshot, or from scratch
     let final_state: Arc<RwLock<dyn FinalStateController>> = Arc::new(parking_lot::RwLock::new(
-        match args.restart_from_snapshot_at_period {
+        match restart_from_snapshot_at_period {
             Some(last_start_period) => {
                 // The node is restarted from a snapshot:
                 // MIP store by reading from the db as it must have been updated by the massa ledger editor
@@ -316,14 +361,25 @@ async fn launch(
                     .expect("MIP store creation failed");
                 debug!("After read from db, Mip store: {:?}", mip_store);
 
-                FinalState::new_derived_from_snapshot(
-                    db.clone(),
-                    final_state_config,
-                    Box::new(ledger),
-                    selector_controller.clone(),
-                    mip_store,
-                    last_start_period,
-                )
+                match fork {
+                    Some(fork) => FinalState::new_forked_from_snapshot(
+                        db.clone(),
+                        final_state_config,
+                        Box::new(ledger),
+                        selector_controller.clone(),
+                        mip_store,
+                        last_start_period,
+                        &fork.state_fork,
+                    ),
+                    None => FinalState::new_derived_from_snapshot(
+                        db.clone(),
+                        final_state_config,
+                        Box::new(ledger),
+                        selector_controller.clone(),
+                        mip_store,
+                        last_start_period,
+                    ),
+                }
                 .expect("could not init final state")
             }
             None => {
@@ -332,7 +388,10 @@ async fn launch(
                 // The resulting MIP store will likely be updated by the bootstrap process in order
                 // to get the latest information for the MIP store (new states, votes...)
 
-                let mip_list = get_mip_list();
+                let mip_list: Vec<(MipInfo, MipState)> = match &SETTINGS.versioning.mip_list_path {
+                    Some(path) => load_mip_list(path).expect("could not load MIP list file"),
+                    None => get_mip_list().into(),
+                };
                 debug!("MIP list: {:?}", mip_list);
                 let mip_store = MipStore::try_from((mip_list, mip_stats_config))
                     .expect("mip store creation failed");
@@ -352,12 +411,16 @@ async fn launch(
 
     let mip_store = final_state.read().get_mip_store().clone();
 
+    // a devnet neither bootstraps from nor serves other nodes
     let bootstrap_config: BootstrapConfig = BootstrapConfig {
-        bootstrap_list: SETTINGS.bootstrap.bootstrap_list.clone(),
+        bootstrap_list: match devnet {
+            Some(_) => Vec::new(),
+            None => SETTINGS.bootstrap.bootstrap_list.clone(),
+        },
         bootstrap_protocol: SETTINGS.bootstrap.bootstrap_protocol,
         bootstrap_whitelist_path: SETTINGS.bootstrap.bootstrap_whitelist_path.clone(),
         bootstrap_blacklist_path: SETTINGS.bootstrap.bootstrap_blacklist_path.clone(),
-        listen_addr: SETTINGS.bootstrap.bind,
+        listen_addr: SETTINGS.bootstrap.bind.filter(|_| devnet.is_none()),
         connect_timeout: SETTINGS.bootstrap.connect_timeout,
         bootstrap_timeout: SETTINGS.bootstrap.bootstrap_timeout,
         read_timeout: SETTINGS.bootstrap.read_timeout,
@@ -411,9 +474,9 @@ async fn launch(
         final_state.clone(),
         DefaultConnector,
         *VERSION,
-        *GENESIS_TIMESTAMP,
+        genesis_timestamp,
         *END_TIMESTAMP,
-        args.restart_from_snapshot_at_period,
+        restart_from_snapshot_at_period,
         sig_int_toggled.clone(),
         massa_metrics.clone(),
     ) {
@@ -430,7 +493,7 @@ async fn launch(
         panic!("critical: db is not valid after bootstrap");
     }
 
-    if args.restart_from_snapshot_at_period.is_none() {
+    if restart_from_snapshot_at_period.is_none() {
         final_state.write().recompute_caches();
 
         // give the controller to final state in order for it to feed the cycles
@@ -456,8 +519,8 @@ async fn launch(
                 last_shutdown_start,
                 last_shutdown_end,
                 THREAD_COUNT,
-                T0,
-                *GENESIS_TIMESTAMP,
+                t0,
+                genesis_timestamp,
             )
             .expect("Mip store is not consistent with shutdown period")
     }
@@ -488,8 +551,8 @@ async fn launch(
         max_gas_per_block: MAX_GAS_PER_BLOCK,
         roll_price: ROLL_PRICE,
         thread_count: THREAD_COUNT,
-        t0: T0,
-        genesis_timestamp: *GENESIS_TIMESTAMP,
+        t0,
+        genesis_timestamp,
         block_reward: BLOCK_REWARD,
         endorsement_count: ENDORSEMENT_COUNT as u64,
         operation_validity_period: OPERATION_VALIDITY_PERIODS,
@@ -501,6 +564,7 @@ async fn launch(
         max_datastore_value_size: MAX_DATASTORE_VALUE_LENGTH,
         storage_costs_constants,
         max_read_only_gas: SETTINGS.execution.max_read_only_gas,
+        max_read_only_lookahead_periods: SETTINGS.execution.max_read_only_lookahead_periods,
         gas_costs: gas_costs.clone(),
         base_operation_gas_cost: BASE_OPERATION_GAS_COST,
         last_start_period: final_state.read().get_last_start_period(),
@@ -508,15 +572,27 @@ async fn launch(
         lru_cache_size: SETTINGS.execution.lru_cache_size,
         hd_cache_size: SETTINGS.execution.hd_cache_size,
         snip_amount: SETTINGS.execution.snip_amount,
+        module_call_stats_path: SETTINGS.execution.module_call_stats_path.clone(),
+        module_cache_warm_up_count: SETTINGS.execution.module_cache_warm_up_count,
         roll_count_to_slash_on_denunciation: ROLL_COUNT_TO_SLASH_ON_DENUNCIATION,
         denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
         broadcast_enabled: SETTINGS.api.enable_broadcast,
         broadcast_slot_execution_output_channel_capacity: SETTINGS
             .execution
             .broadcast_slot_execution_output_channel_capacity,
+        broadcast_async_message_event_channel_capacity: SETTINGS
+            .execution
+            .broadcast_async_message_event_channel_capacity,
         max_event_size: MAX_EVENT_DATA_SIZE,
+        staking_history: SETTINGS.execution.staking_history,
+        ledger_analytics: SETTINGS.execution.ledger_analytics,
+        ledger_merkle_tree: SETTINGS.execution.ledger_merkle_tree,
+        parallel_operation_execution: SETTINGS.execution.parallel_operation_execution,
+        execution_profiling: SETTINGS.execution.execution_profiling,
+        execution_profiling_window: SETTINGS.execution.execution_profiling_window,
         max_function_length: MAX_FUNCTION_NAME_LENGTH,
         max_parameter_length: MAX_PARAMETERS_SIZE,
+        clock: clock.clone(),
     };
 
     let execution_channels = ExecutionChannels {
@@ -524,6 +600,10 @@ async fn launch(
             execution_config.broadcast_slot_execution_output_channel_capacity,
         )
         .0,
+        async_message_event_sender: broadcast::channel(
+            execution_config.broadcast_async_message_event_channel_capacity,
+        )
+        .0,
     };
 
     let (execution_manager, execution_controller) = start_execution_worker(
@@ -560,12 +640,13 @@ async fn launch(
             .pool
             .broadcast_endorsements_channel_capacity,
         broadcast_operations_channel_capacity: SETTINGS.pool.broadcast_operations_channel_capacity,
-        genesis_timestamp: *GENESIS_TIMESTAMP,
-        t0: T0,
+        genesis_timestamp,
+        t0,
         periods_per_cycle: PERIODS_PER_CYCLE,
         denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
         max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
         last_start_period: final_state.read().get_last_start_period(),
+        clock: clock.clone(),
     };
 
     let pool_channels = PoolChannels {
@@ -589,108 +670,28 @@ async fn launch(
     );
 
     // launch protocol controller
+    // a devnet does not connect to any peer
     let mut listeners = HashMap::default();
-    listeners.insert(SETTINGS.protocol.bind, TransportType::Tcp);
-    let protocol_config = ProtocolConfig {
-        thread_count: THREAD_COUNT,
-        ask_block_timeout: SETTINGS.protocol.ask_block_timeout,
-        max_known_blocks_size: SETTINGS.protocol.max_known_blocks_size,
-        max_node_known_blocks_size: SETTINGS.protocol.max_node_known_blocks_size,
-        max_block_propagation_time: SETTINGS.protocol.max_block_propagation_time,
-        max_node_wanted_blocks_size: SETTINGS.protocol.max_node_wanted_blocks_size,
-        max_known_ops_size: SETTINGS.protocol.max_known_ops_size,
-        max_node_known_ops_size: SETTINGS.protocol.max_node_known_ops_size,
-        max_known_endorsements_size: SETTINGS.protocol.max_known_endorsements_size,
-        max_node_known_endorsements_size: SETTINGS.protocol.max_node_known_endorsements_size,
-        max_simultaneous_ask_blocks_per_node: SETTINGS
-            .protocol
-            .max_simultaneous_ask_blocks_per_node,
-        max_send_wait: SETTINGS.protocol.max_send_wait,
-        operation_batch_buffer_capacity: SETTINGS.protocol.operation_batch_buffer_capacity,
-        operation_announcement_buffer_capacity: SETTINGS
-            .protocol
-            .operation_announcement_buffer_capacity,
-        operation_batch_proc_period: SETTINGS.protocol.operation_batch_proc_period,
-        operation_announcement_interval: SETTINGS.protocol.operation_announcement_interval,
-        max_operations_per_message: SETTINGS.protocol.max_operations_per_message,
-        max_serialized_operations_size_per_block: MAX_BLOCK_SIZE as usize,
-        max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
-        controller_channel_size: PROTOCOL_CONTROLLER_CHANNEL_SIZE,
-        event_channel_size: PROTOCOL_EVENT_CHANNEL_SIZE,
-        genesis_timestamp: *GENESIS_TIMESTAMP,
-        t0: T0,
-        endorsement_count: ENDORSEMENT_COUNT,
-        max_message_size: MAX_MESSAGE_SIZE as usize,
-        max_ops_kept_for_propagation: SETTINGS.protocol.max_ops_kept_for_propagation,
-        max_operations_propagation_time: SETTINGS.protocol.max_operations_propagation_time,
-        max_endorsements_propagation_time: SETTINGS.protocol.max_endorsements_propagation_time,
-        last_start_period: final_state.read().get_last_start_period(),
-        max_endorsements_per_message: MAX_ENDORSEMENTS_PER_MESSAGE as u64,
-        max_denunciations_in_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
-        initial_peers: SETTINGS.protocol.initial_peers_file.clone(),
+    if devnet.is_none() {
+        listeners.insert(SETTINGS.protocol.bind, TransportType::Tcp);
+    }
+    let protocol_config = protocol_config(
+        devnet,
+        genesis_timestamp,
+        t0,
+        final_state.read().get_last_start_period(),
         listeners,
-        keypair_file: SETTINGS.protocol.keypair_file.clone(),
-        max_blocks_kept_for_propagation: SETTINGS.protocol.max_blocks_kept_for_propagation,
-        block_propagation_tick: SETTINGS.protocol.block_propagation_tick,
-        asked_operations_buffer_capacity: SETTINGS.protocol.asked_operations_buffer_capacity,
-        thread_tester_count: SETTINGS.protocol.thread_tester_count,
-        max_operation_storage_time: MAX_OPERATION_STORAGE_TIME,
-        max_size_channel_commands_propagation_blocks: MAX_SIZE_CHANNEL_COMMANDS_PROPAGATION_BLOCKS,
-        max_size_channel_commands_propagation_operations:
-            MAX_SIZE_CHANNEL_COMMANDS_PROPAGATION_OPERATIONS,
-        max_size_channel_commands_propagation_endorsements:
-            MAX_SIZE_CHANNEL_COMMANDS_PROPAGATION_ENDORSEMENTS,
-        max_size_channel_commands_retrieval_blocks: MAX_SIZE_CHANNEL_COMMANDS_RETRIEVAL_BLOCKS,
-        max_size_channel_commands_retrieval_operations:
-            MAX_SIZE_CHANNEL_COMMANDS_RETRIEVAL_OPERATIONS,
-        max_size_channel_commands_retrieval_endorsements:
-            MAX_SIZE_CHANNEL_COMMANDS_RETRIEVAL_ENDORSEMENTS,
-        max_size_channel_commands_connectivity: MAX_SIZE_CHANNEL_COMMANDS_CONNECTIVITY,
-        max_size_channel_commands_peers: MAX_SIZE_CHANNEL_COMMANDS_PEERS,
-        max_size_channel_commands_peer_testers: MAX_SIZE_CHANNEL_COMMANDS_PEER_TESTERS,
-        max_size_channel_network_to_block_handler: MAX_SIZE_CHANNEL_NETWORK_TO_BLOCK_HANDLER,
-        max_size_channel_network_to_operation_handler:
-            MAX_SIZE_CHANNEL_NETWORK_TO_OPERATION_HANDLER,
-        max_size_channel_network_to_endorsement_handler:
-            MAX_SIZE_CHANNEL_NETWORK_TO_ENDORSEMENT_HANDLER,
-        max_size_channel_network_to_peer_handler: MAX_SIZE_CHANNEL_NETWORK_TO_PEER_HANDLER,
-        max_size_value_datastore: MAX_DATASTORE_VALUE_LENGTH,
-        max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
-        max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
-        max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
-        max_size_function_name: MAX_FUNCTION_NAME_LENGTH,
-        max_size_call_sc_parameter: MAX_PARAMETERS_SIZE,
-        max_size_listeners_per_peer: MAX_LISTENERS_PER_PEER,
-        max_size_peers_announcement: MAX_PEERS_IN_ANNOUNCEMENT_LIST,
-        read_write_limit_bytes_per_second: SETTINGS.protocol.read_write_limit_bytes_per_second
-            as u128,
-        try_connection_timer: SETTINGS.protocol.try_connection_timer,
-        unban_everyone_timer: SETTINGS.protocol.unban_everyone_timer,
-        max_in_connections: SETTINGS.protocol.max_in_connections,
-        timeout_connection: SETTINGS.protocol.timeout_connection,
-        message_timeout: SETTINGS.protocol.message_timeout,
-        tester_timeout: SETTINGS.protocol.tester_timeout,
-        routable_ip: SETTINGS
-            .protocol
-            .routable_ip
-            .or(SETTINGS.network.routable_ip),
-        debug: false,
-        peers_categories: SETTINGS.protocol.peers_categories.clone(),
-        default_category_info: SETTINGS.protocol.default_category_info,
-        version: *VERSION,
-        try_connection_timer_same_peer: SETTINGS.protocol.try_connection_timer_same_peer,
-        test_oldest_peer_cooldown: SETTINGS.protocol.test_oldest_peer_cooldown,
-        rate_limit: SETTINGS.protocol.rate_limit,
-    };
+        clock.clone(),
+    );
 
     let (protocol_controller, protocol_channels) =
         create_protocol_controller(protocol_config.clone());
 
     let consensus_config = ConsensusConfig {
-        genesis_timestamp: *GENESIS_TIMESTAMP,
+        genesis_timestamp,
         end_timestamp: *END_TIMESTAMP,
         thread_count: THREAD_COUNT,
-        t0: T0,
+        t0,
         genesis_key: GENESIS_KEY.clone(),
         max_discarded_blocks: SETTINGS.consensus.max_discarded_blocks,
         max_future_processing_blocks: SETTINGS.consensus.max_future_processing_blocks,
@@ -717,6 +718,7 @@ async fn launch(
         force_keep_final_periods_without_ops: SETTINGS
             .consensus
             .force_keep_final_periods_without_ops,
+        clock: clock.clone(),
     };
 
     let (consensus_event_sender, consensus_event_receiver) =
@@ -764,18 +766,21 @@ async fn launch(
     // launch factory
     let factory_config = FactoryConfig {
         thread_count: THREAD_COUNT,
-        genesis_timestamp: *GENESIS_TIMESTAMP,
-        t0: T0,
-        initial_delay: SETTINGS.factory.initial_delay,
+        genesis_timestamp,
+        t0,
+        initial_delay: match devnet {
+            Some(_) => MassaTime::from_millis(0),
+            None => SETTINGS.factory.initial_delay,
+        },
         max_block_size: MAX_BLOCK_SIZE as u64,
         max_block_gas: MAX_GAS_PER_BLOCK,
         max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
         last_start_period: final_state.read().get_last_start_period(),
         periods_per_cycle: PERIODS_PER_CYCLE,
         denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
-        stop_production_when_zero_connections: SETTINGS
-            .factory
-            .stop_production_when_zero_connections,
+        stop_production_when_zero_connections: devnet.is_none()
+            && SETTINGS.factory.stop_production_when_zero_connections,
+        clock: clock.clone(),
     };
     let factory_channels = FactoryChannels {
         selector: selector_controller.clone(),
@@ -784,7 +789,7 @@ async fn launch(
         protocol: protocol_controller.clone(),
         storage: shared_storage.clone(),
     };
-    let factory_manager = start_factory(
+    let (factory_manager, factory_controller) = start_factory(
         factory_config,
         node_wallet.clone(),
         factory_channels,
@@ -814,48 +819,28 @@ async fn launch(
         .expect("Could not start bootstrap server")
     });
 
-    let api_config: APIConfig = APIConfig {
-        bind_private: SETTINGS.api.bind_private,
-        bind_public: SETTINGS.api.bind_public,
-        bind_api: SETTINGS.api.bind_api,
-        draw_lookahead_period_count: SETTINGS.api.draw_lookahead_period_count,
-        max_arguments: SETTINGS.api.max_arguments,
-        openrpc_spec_path: SETTINGS.api.openrpc_spec_path.clone(),
-        bootstrap_whitelist_path: SETTINGS.bootstrap.bootstrap_whitelist_path.clone(),
-        bootstrap_blacklist_path: SETTINGS.bootstrap.bootstrap_blacklist_path.clone(),
-        max_request_body_size: SETTINGS.api.max_request_body_size,
-        max_response_body_size: SETTINGS.api.max_response_body_size,
-        max_connections: SETTINGS.api.max_connections,
-        max_subscriptions_per_connection: SETTINGS.api.max_subscriptions_per_connection,
-        max_log_length: SETTINGS.api.max_log_length,
-        allow_hosts: SETTINGS.api.allow_hosts.clone(),
-        batch_request_limit: SETTINGS.api.batch_request_limit,
-        ping_interval: SETTINGS.api.ping_interval,
-        enable_http: SETTINGS.api.enable_http,
-        enable_ws: SETTINGS.api.enable_ws,
-        max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
-        max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
-        max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
-        max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
-        max_gas_per_block: MAX_GAS_PER_BLOCK,
-        base_operation_gas_cost: BASE_OPERATION_GAS_COST,
-        sp_compilation_cost: gas_costs.sp_compilation_cost,
-        max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
-        max_parameter_size: MAX_PARAMETERS_SIZE,
-        thread_count: THREAD_COUNT,
-        keypair: keypair.clone(),
-        genesis_timestamp: *GENESIS_TIMESTAMP,
-        t0: T0,
-        periods_per_cycle: PERIODS_PER_CYCLE,
-        last_start_period: final_state.read().get_last_start_period(),
-    };
+    let api_config = api_config(
+        keypair.clone(),
+        genesis_timestamp,
+        t0,
+        final_state.read().get_last_start_period(),
+        gas_costs.sp_compilation_cost,
+        clock.clone(),
+    );
+
+    // roll operations sent by the roll manager, broadcasted by the API
+    let roll_manager_event_sender =
+        broadcast::channel(SETTINGS.roll_manager.broadcast_event_channel_capacity).0;
 
     // spawn Massa API
     let api = API::<ApiV2>::new(
         consensus_controller.clone(),
         consensus_channels.broadcasts.clone(),
         execution_controller.clone(),
+        execution_channels.clone(),
         pool_channels.broadcasts.clone(),
+        selector_channels.clone(),
+        roll_manager_event_sender.clone(),
         api_config.clone(),
         *VERSION,
     );
@@ -880,6 +865,9 @@ async fn launch(
             &SETTINGS.grpc.public,
             keypair.clone(),
             &final_state,
+            t0,
+            genesis_timestamp,
+            clock.clone(),
         );
 
         let grpc_public_api = MassaPublicGrpc {
@@ -920,6 +908,9 @@ async fn launch(
             &SETTINGS.grpc.private,
             keypair.clone(),
             &final_state,
+            t0,
+            genesis_timestamp,
+            clock.clone(),
         );
 
         let bs_white_black_list = bootstrap_manager
@@ -958,7 +949,7 @@ async fn launch(
 
     #[cfg(feature = "op_spammer")]
     start_operation_injector(
-        *GENESIS_TIMESTAMP,
+        genesis_timestamp,
         shared_storage.clone_without_refs(),
         node_wallet.read().clone(),
         pool_controller.clone(),
@@ -966,6 +957,33 @@ async fn launch(
         args.nb_op,
     );
 
+    // launch the roll manager of the staking addresses
+    let roll_manager_stopper = if SETTINGS.roll_manager.enabled {
+        start_roll_manager(
+            RollManagerConfig {
+                check_period: SETTINGS.roll_manager.check_period,
+                buy_threshold: SETTINGS.roll_manager.buy_threshold,
+                fee_reserve: SETTINGS.roll_manager.fee_reserve,
+                target_roll_count: SETTINGS.roll_manager.target_roll_count,
+                operation_fee: SETTINGS.roll_manager.operation_fee,
+                roll_price: ROLL_PRICE,
+                operation_validity_periods: OPERATION_VALIDITY_PERIODS,
+                thread_count: THREAD_COUNT,
+                t0,
+                genesis_timestamp,
+                last_start_period: final_state.read().get_last_start_period(),
+            },
+            execution_controller.clone(),
+            pool_controller.clone(),
+            protocol_controller.clone(),
+            node_wallet.clone(),
+            shared_storage.clone_without_refs(),
+            roll_manager_event_sender,
+        )
+    } else {
+        disabled_roll_manager()
+    };
+
     // spawn private API
     let api_private = API::<Private>::new(
         protocol_controller.clone(),
@@ -973,6 +991,8 @@ async fn launch(
         api_config.clone(),
         sig_int_toggled,
         node_wallet,
+        // the clock can only be fast-forwarded on a devnet
+        devnet.map(|_| factory_controller),
     );
     let api_private_handle = api_private
         .serve(&SETTINGS.api.bind_private, &api_config)
@@ -1065,15 +1085,185 @@ async fn launch(
         grpc_public_handle,
         metrics_stopper,
         massa_survey_stopper,
+        roll_manager_stopper,
     )
 }
 
+/// Configuration of the protocol worker
+fn protocol_config(
+    devnet: Option<&DevnetGenesis>,
+    genesis_timestamp: MassaTime,
+    t0: MassaTime,
+    last_start_period: u64,
+    listeners: HashMap<SocketAddr, TransportType>,
+    clock: SharedClock,
+) -> ProtocolConfig {
+    ProtocolConfig {
+        thread_count: THREAD_COUNT,
+        ask_block_timeout: SETTINGS.protocol.ask_block_timeout,
+        max_known_blocks_size: SETTINGS.protocol.max_known_blocks_size,
+        max_node_known_blocks_size: SETTINGS.protocol.max_node_known_blocks_size,
+        max_block_propagation_time: SETTINGS.protocol.max_block_propagation_time,
+        max_node_wanted_blocks_size: SETTINGS.protocol.max_node_wanted_blocks_size,
+        max_known_ops_size: SETTINGS.protocol.max_known_ops_size,
+        max_node_known_ops_size: SETTINGS.protocol.max_node_known_ops_size,
+        max_known_endorsements_size: SETTINGS.protocol.max_known_endorsements_size,
+        max_node_known_endorsements_size: SETTINGS.protocol.max_node_known_endorsements_size,
+        max_simultaneous_ask_blocks_per_node: SETTINGS
+            .protocol
+            .max_simultaneous_ask_blocks_per_node,
+        max_send_wait: SETTINGS.protocol.max_send_wait,
+        operation_batch_buffer_capacity: SETTINGS.protocol.operation_batch_buffer_capacity,
+        operation_announcement_buffer_capacity: SETTINGS
+            .protocol
+            .operation_announcement_buffer_capacity,
+        operation_batch_proc_period: SETTINGS.protocol.operation_batch_proc_period,
+        operation_announcement_interval: SETTINGS.protocol.operation_announcement_interval,
+        max_operations_per_message: SETTINGS.protocol.max_operations_per_message,
+        max_serialized_operations_size_per_block: MAX_BLOCK_SIZE as usize,
+        max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
+        controller_channel_size: PROTOCOL_CONTROLLER_CHANNEL_SIZE,
+        event_channel_size: PROTOCOL_EVENT_CHANNEL_SIZE,
+        genesis_timestamp,
+        t0,
+        endorsement_count: ENDORSEMENT_COUNT,
+        max_message_size: MAX_MESSAGE_SIZE as usize,
+        max_ops_kept_for_propagation: SETTINGS.protocol.max_ops_kept_for_propagation,
+        max_operations_propagation_time: SETTINGS.protocol.max_operations_propagation_time,
+        max_endorsements_propagation_time: SETTINGS.protocol.max_endorsements_propagation_time,
+        last_start_period,
+        max_endorsements_per_message: MAX_ENDORSEMENTS_PER_MESSAGE as u64,
+        max_draws_per_message: MAX_DRAWS_PER_MESSAGE,
+        headers_only: false,
+        max_denunciations_in_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+        initial_peers: devnet.map_or_else(
+            || SETTINGS.protocol.initial_peers_file.clone(),
+            |genesis| genesis.initial_peers_path.clone(),
+        ),
+        listeners,
+        keypair_file: SETTINGS.protocol.keypair_file.clone(),
+        max_blocks_kept_for_propagation: SETTINGS.protocol.max_blocks_kept_for_propagation,
+        block_propagation_tick: SETTINGS.protocol.block_propagation_tick,
+        asked_operations_buffer_capacity: SETTINGS.protocol.asked_operations_buffer_capacity,
+        thread_tester_count: SETTINGS.protocol.thread_tester_count,
+        max_operation_storage_time: MAX_OPERATION_STORAGE_TIME,
+        max_size_channel_commands_propagation_blocks: MAX_SIZE_CHANNEL_COMMANDS_PROPAGATION_BLOCKS,
+        max_size_channel_commands_propagation_operations:
+            MAX_SIZE_CHANNEL_COMMANDS_PROPAGATION_OPERATIONS,
+        max_size_channel_commands_propagation_endorsements:
+            MAX_SIZE_CHANNEL_COMMANDS_PROPAGATION_ENDORSEMENTS,
+        max_size_channel_commands_retrieval_blocks: MAX_SIZE_CHANNEL_COMMANDS_RETRIEVAL_BLOCKS,
+        max_size_channel_commands_retrieval_operations:
+            MAX_SIZE_CHANNEL_COMMANDS_RETRIEVAL_OPERATIONS,
+        max_size_channel_commands_retrieval_endorsements:
+            MAX_SIZE_CHANNEL_COMMANDS_RETRIEVAL_ENDORSEMENTS,
+        max_size_channel_commands_connectivity: MAX_SIZE_CHANNEL_COMMANDS_CONNECTIVITY,
+        max_size_channel_commands_peers: MAX_SIZE_CHANNEL_COMMANDS_PEERS,
+        max_size_channel_commands_peer_testers: MAX_SIZE_CHANNEL_COMMANDS_PEER_TESTERS,
+        max_size_channel_network_to_block_handler: MAX_SIZE_CHANNEL_NETWORK_TO_BLOCK_HANDLER,
+        max_size_channel_network_to_operation_handler:
+            MAX_SIZE_CHANNEL_NETWORK_TO_OPERATION_HANDLER,
+        max_size_channel_network_to_endorsement_handler:
+            MAX_SIZE_CHANNEL_NETWORK_TO_ENDORSEMENT_HANDLER,
+        max_size_channel_network_to_peer_handler: MAX_SIZE_CHANNEL_NETWORK_TO_PEER_HANDLER,
+        max_size_value_datastore: MAX_DATASTORE_VALUE_LENGTH,
+        max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+        max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
+        max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        max_size_function_name: MAX_FUNCTION_NAME_LENGTH,
+        max_size_call_sc_parameter: MAX_PARAMETERS_SIZE,
+        max_size_listeners_per_peer: MAX_LISTENERS_PER_PEER,
+        max_size_peers_announcement: MAX_PEERS_IN_ANNOUNCEMENT_LIST,
+        read_write_limit_bytes_per_second: SETTINGS.protocol.read_write_limit_bytes_per_second
+            as u128,
+        try_connection_timer: SETTINGS.protocol.try_connection_timer,
+        ban_duration: SETTINGS.protocol.ban_duration,
+        max_ban_duration: SETTINGS.protocol.max_ban_duration,
+        max_in_connections: SETTINGS.protocol.max_in_connections,
+        timeout_connection: SETTINGS.protocol.timeout_connection,
+        message_timeout: SETTINGS.protocol.message_timeout,
+        tester_timeout: SETTINGS.protocol.tester_timeout,
+        routable_ip: SETTINGS
+            .protocol
+            .routable_ip
+            .or(SETTINGS.network.routable_ip),
+        debug: false,
+        peers_categories: SETTINGS.protocol.peers_categories.clone(),
+        default_category_info: SETTINGS.protocol.default_category_info,
+        version: *VERSION,
+        try_connection_timer_same_peer: SETTINGS.protocol.try_connection_timer_same_peer,
+        test_oldest_peer_cooldown: SETTINGS.protocol.test_oldest_peer_cooldown,
+        rate_limit: SETTINGS.protocol.rate_limit,
+        rate_limit_blocks: SETTINGS.protocol.rate_limit_blocks,
+        rate_limit_endorsements: SETTINGS.protocol.rate_limit_endorsements,
+        rate_limit_operations: SETTINGS.protocol.rate_limit_operations,
+        rate_limit_ban_threshold: SETTINGS.protocol.rate_limit_ban_threshold,
+        compact_block_relay: SETTINGS.protocol.compact_block_relay,
+        compact_block_relay_min_version: *COMPACT_BLOCK_RELAY_MIN_VERSION,
+        operation_reconciliation: SETTINGS.protocol.operation_reconciliation,
+        operation_reconciliation_interval: SETTINGS.protocol.operation_reconciliation_interval,
+        operation_reconciliation_min_version: *OPERATION_RECONCILIATION_MIN_VERSION,
+        clock,
+    }
+}
+
+/// Configuration of the JSON-RPC APIs
+fn api_config(
+    keypair: KeyPair,
+    genesis_timestamp: MassaTime,
+    t0: MassaTime,
+    last_start_period: u64,
+    sp_compilation_cost: u64,
+    clock: SharedClock,
+) -> APIConfig {
+    APIConfig {
+        bind_private: SETTINGS.api.bind_private,
+        bind_public: SETTINGS.api.bind_public,
+        bind_api: SETTINGS.api.bind_api,
+        draw_lookahead_period_count: SETTINGS.api.draw_lookahead_period_count,
+        max_arguments: SETTINGS.api.max_arguments,
+        max_draws_lookahead_count: SETTINGS.api.max_draws_lookahead_count,
+        openrpc_spec_path: SETTINGS.api.openrpc_spec_path.clone(),
+        bootstrap_whitelist_path: SETTINGS.bootstrap.bootstrap_whitelist_path.clone(),
+        bootstrap_blacklist_path: SETTINGS.bootstrap.bootstrap_blacklist_path.clone(),
+        max_request_body_size: SETTINGS.api.max_request_body_size,
+        max_response_body_size: SETTINGS.api.max_response_body_size,
+        max_connections: SETTINGS.api.max_connections,
+        max_subscriptions_per_connection: SETTINGS.api.max_subscriptions_per_connection,
+        max_log_length: SETTINGS.api.max_log_length,
+        allow_hosts: SETTINGS.api.allow_hosts.clone(),
+        batch_request_limit: SETTINGS.api.batch_request_limit,
+        ping_interval: SETTINGS.api.ping_interval,
+        enable_http: SETTINGS.api.enable_http,
+        enable_ws: SETTINGS.api.enable_ws,
+        max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+        max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+        max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
+        max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        max_gas_per_block: MAX_GAS_PER_BLOCK,
+        base_operation_gas_cost: BASE_OPERATION_GAS_COST,
+        sp_compilation_cost,
+        max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
+        max_parameter_size: MAX_PARAMETERS_SIZE,
+        thread_count: THREAD_COUNT,
+        keypair,
+        genesis_timestamp,
+        t0,
+        periods_per_cycle: PERIODS_PER_CYCLE,
+        last_start_period,
+        clock,
+    }
+}
+
 // Get the configuration of the gRPC server
 fn configure_grpc(
     name: ServiceName,
     settings: &GrpcSettings,
     keypair: KeyPair,
     final_state: &Arc<RwLock<dyn FinalStateController>>,
+    t0: MassaTime,
+    genesis_timestamp: MassaTime,
+    clock: SharedClock,
 ) -> GrpcConfig {
     GrpcConfig {
         name,
@@ -1116,13 +1306,14 @@ fn configure_grpc(
         max_parameter_size: MAX_PARAMETERS_SIZE,
         max_operations_per_message: MAX_OPERATIONS_PER_MESSAGE,
         max_gas_per_block: MAX_GAS_PER_BLOCK,
-        genesis_timestamp: *GENESIS_TIMESTAMP,
-        t0: T0,
+        genesis_timestamp,
+        t0,
         periods_per_cycle: PERIODS_PER_CYCLE,
         keypair,
         max_channel_size: settings.max_channel_size,
         draw_lookahead_period_count: settings.draw_lookahead_period_count,
         last_start_period: final_state.read().get_last_start_period(),
+        clock,
         max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
         max_addresses_per_request: settings.max_addresses_per_request,
         max_slot_ranges_per_request: settings.max_slot_ranges_per_request,
@@ -1171,6 +1362,7 @@ async fn stop(
     grpc_public_handle: Option<massa_grpc::server::StopHandle>,
     mut metrics_stopper: MetricsStopper,
     mut massa_survey_stopper: MassaSurveyStopper,
+    mut roll_manager_stopper: RollManagerStopper,
 ) {
     // stop bootstrap
     if let Some(bootstrap_manager) = bootstrap_manager {
@@ -1211,6 +1403,9 @@ async fn stop(
     // stop massa survey thread
     massa_survey_stopper.stop();
 
+    // stop roll manager
+    roll_manager_stopper.stop();
+
     // stop factory
     factory_manager.stop();
 
@@ -1250,6 +1445,18 @@ struct Args {
     #[arg(long = "restart-from-snapshot-at-period")]
     restart_from_snapshot_at_period: Option<u64>,
 
+    /// Start a local development network with a generated genesis, funded test keys and a single staker
+    #[arg(long = "devnet")]
+    devnet: bool,
+
+    /// Start a local development network forked from the snapshot described by this file (implies --devnet)
+    #[arg(long = "fork")]
+    fork: Option<PathBuf>,
+
+    /// Only follow the block headers and their finality from the checkpoint of the `[light]` settings
+    #[arg(long = "light", conflicts_with_all = ["devnet", "fork"])]
+    light: bool,
+
     #[cfg(feature = "op_spammer")]
     /// number of operations
     #[arg(
@@ -1345,11 +1552,25 @@ async fn run(args: Args) -> anyhow::Result<()> {
 
     info!("Node version : {}", *VERSION);
 
-    // load or create wallet, asking for password if necessary
-    let node_wallet = load_wallet(
-        cur_args.password.clone(),
-        &SETTINGS.factory.staking_wallet_path,
-    )?;
+    // generate the devnet genesis once, it is kept if the node restarts
+    let devnet = if cur_args.devnet || cur_args.fork.is_some() {
+        let genesis = generate_devnet(&SETTINGS.devnet, cur_args.fork.as_deref())?;
+        info!(
+            "Devnet genesis generated in {}, starting at {}",
+            SETTINGS.devnet.path.display(),
+            genesis.genesis_timestamp.format_instant()
+        );
+        Some(genesis)
+    } else {
+        None
+    };
+
+    // clock read by the workers, kept if the node restarts:
+    // a devnet can fast-forward its own clock
+    let clock = match &devnet {
+        Some(_) => SharedClock::new(Arc::new(OffsetClock::new())),
+        None => SharedClock::system(),
+    };
 
     // interrupt signal listener
     let sig_int_toggled = Arc::new((Mutex::new(false), Condvar::new()));
@@ -1364,6 +1585,48 @@ async fn run(args: Args) -> anyhow::Result<()> {
     })
     .expect("Error setting Ctrl-C handler");
 
+    // a light node neither executes blocks nor stakes
+    if cur_args.light {
+        let light_node = launch_light(*GENESIS_TIMESTAMP, T0, clock).await?;
+        let mut int_sig = sig_int_toggled
+            .0
+            .lock()
+            .expect("double-lock() on interupted signal mutex");
+        while !*int_sig {
+            int_sig = sig_int_toggled
+                .1
+                .wait(int_sig)
+                .expect("interupt signal mutex poisoned");
+        }
+        drop(int_sig);
+        info!("interrupt signal received");
+        light_node.stop().await;
+        return Ok(());
+    }
+
+    // load or create wallet, asking for password if necessary
+    let node_wallet = match &devnet {
+        Some(genesis) => {
+            let node_wallet = load_wallet(
+                Some(
+                    cur_args
+                        .password
+                        .clone()
+                        .unwrap_or_else(|| "devnet".to_string()),
+                ),
+                &genesis.staking_wallet_path,
+            )?;
+            node_wallet
+                .write()
+                .add_keypairs(vec![genesis.staker.clone()])?;
+            node_wallet
+        }
+        None => load_wallet(
+            cur_args.password.clone(),
+            &SETTINGS.factory.staking_wallet_path,
+        )?,
+    };
+
     #[cfg(feature = "resync_check")]
     let mut resync_check = Some(std::time::Instant::now() + std::time::Duration::from_secs(10));
 
@@ -1384,7 +1647,15 @@ async fn run(args: Args) -> anyhow::Result<()> {
             grpc_public_handle,
             metrics_stopper,
             massa_survey_stopper,
-        ) = launch(&cur_args, node_wallet.clone(), Arc::clone(&sig_int_toggled)).await;
+            roll_manager_stopper,
+        ) = launch(
+            &cur_args,
+            devnet.as_ref(),
+            clock.clone(),
+            node_wallet.clone(),
+            Arc::clone(&sig_int_toggled),
+        )
+        .await;
 
         // loop over messages
         let restart = loop {
@@ -1451,6 +1722,7 @@ async fn run(args: Args) -> anyhow::Result<()> {
             grpc_public_handle,
             metrics_stopper,
             massa_survey_stopper,
+            roll_manager_stopper,
         )
         .await;
 
@@ -0,0 +1,417 @@
+//! Node side roll manager of the staking addresses.
+//!
+//! When enabled, it periodically looks at the candidate balance and roll count of each staking
+//! address, and buys or sells rolls according to the configured policy:
+//! * rolls are bought with the balance exceeding the buy threshold, minus a reserve kept for fees
+//! * rolls are sold down to the target roll count, which also caps the purchases
+//!
+//! The operations are signed with the staking wallet, then added to the pool and propagated
+//! to the network. Each action is logged as an event carrying the address, the operation ID
+//! and the roll count as fields, and broadcast to the `subscribe_roll_manager_events` API subscribers.
+
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use crossbeam_channel::{select, tick};
+use massa_api_exports::roll_manager::{RollManagerAction, RollManagerEvent};
+use massa_channel::{sender::MassaSender, MassaChannel};
+use massa_execution_exports::ExecutionController;
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    operation::{Operation, OperationId, OperationType},
+    prehash::PreHashMap,
+    timeslots::get_latest_block_slot_at_timestamp,
+};
+use massa_pool_exports::PoolController;
+use massa_protocol_exports::ProtocolController;
+use massa_storage::Storage;
+use massa_time::MassaTime;
+use massa_wallet::Wallet;
+use parking_lot::RwLock;
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+/// Roll manager configuration
+#[derive(Debug, Clone)]
+pub struct RollManagerConfig {
+    /// time between two checks of the staking addresses
+    pub check_period: MassaTime,
+    /// buy rolls when the candidate balance exceeds this amount, never buy if not set
+    pub buy_threshold: Option<Amount>,
+    /// amount always left on the address to pay the fees
+    pub fee_reserve: Amount,
+    /// sell rolls down to this count, and never buy more, if set
+    pub target_roll_count: Option<u64>,
+    /// fee of the roll operations
+    pub operation_fee: Amount,
+    /// price of a roll
+    pub roll_price: Amount,
+    /// number of periods during which the roll operations can be included
+    pub operation_validity_periods: u64,
+    /// thread count
+    pub thread_count: u8,
+    /// period duration
+    pub t0: MassaTime,
+    /// genesis timestamp
+    pub genesis_timestamp: MassaTime,
+    /// last start period
+    pub last_start_period: u64,
+}
+
+/// Roll operation decided for an address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RollAction {
+    Buy(u64),
+    Sell(u64),
+}
+
+/// Decide which roll operation to send for an address given its candidate balance and roll count
+fn decide_roll_action(
+    config: &RollManagerConfig,
+    balance: Amount,
+    roll_count: u64,
+) -> Option<RollAction> {
+    if let Some(target) = config.target_roll_count {
+        if roll_count > target {
+            if balance < config.operation_fee {
+                return None;
+            }
+            return Some(RollAction::Sell(roll_count - target));
+        }
+    }
+    let threshold = config.buy_threshold?;
+    if balance <= threshold {
+        return None;
+    }
+    let spendable = balance
+        .checked_sub(config.fee_reserve)?
+        .checked_sub(config.operation_fee)?;
+    let mut count = spendable.checked_div(config.roll_price)?;
+    if let Some(target) = config.target_roll_count {
+        count = count.min(target.saturating_sub(roll_count));
+    }
+    if count == 0 {
+        return None;
+    }
+    Some(RollAction::Buy(count))
+}
+
+pub struct RollManagerStopper {
+    tx_stopper: Option<MassaSender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl RollManagerStopper {
+    pub fn stop(&mut self) {
+        if let Some(tx) = self.tx_stopper.take() {
+            info!("RollManager | Stopping");
+            if let Err(e) = tx.send(()) {
+                warn!("failed to send stop signal to roll manager thread: {:?}", e);
+            }
+        }
+        if let Some(handle) = self.handle.take() {
+            match handle.join() {
+                Ok(_) => info!("RollManager | Stopped"),
+                Err(_) => warn!("failed to join roll manager thread"),
+            }
+        }
+    }
+}
+
+struct RollManager {
+    config: RollManagerConfig,
+    execution_controller: Box<dyn ExecutionController>,
+    pool_controller: Box<dyn PoolController>,
+    protocol_controller: Box<dyn ProtocolController>,
+    wallet: Arc<RwLock<Wallet>>,
+    storage: Storage,
+    /// roll operations sent and not yet executed nor expired, with their expire period
+    pending: PreHashMap<Address, (OperationId, u64)>,
+    /// broadcast of the roll operations sent
+    event_sender: broadcast::Sender<RollManagerEvent>,
+}
+
+impl RollManager {
+    /// Check every staking address and send the roll operations required by the policy
+    fn check_addresses(&mut self) {
+        let current_period = match get_latest_block_slot_at_timestamp(
+            self.config.thread_count,
+            self.config.t0,
+            self.config.genesis_timestamp,
+            MassaTime::now(),
+        ) {
+            Ok(Some(slot)) => slot.period.max(self.config.last_start_period),
+            Ok(None) => return,
+            Err(e) => {
+                warn!("RollManager | Failed to get the current slot: {}", e);
+                return;
+            }
+        };
+
+        // forget the operations that were executed or that expired
+        let pending_ids: Vec<OperationId> = self.pending.values().map(|(id, _)| *id).collect();
+        let statuses = self.execution_controller.get_ops_exec_status(&pending_ids);
+        let done: Vec<OperationId> = pending_ids
+            .into_iter()
+            .zip(statuses)
+            .filter_map(|(id, (speculative, _final))| speculative.map(|_| id))
+            .collect();
+        self.pending.retain(|_, (id, expire_period)| {
+            !done.contains(id) && *expire_period >= current_period
+        });
+
+        let addresses: Vec<Address> = self
+            .wallet
+            .read()
+            .get_wallet_address_list()
+            .into_iter()
+            .filter(|addr| !self.pending.contains_key(addr))
+            .collect();
+        if addresses.is_empty() {
+            return;
+        }
+        let infos = self.execution_controller.get_addresses_infos(&addresses);
+        for (address, info) in addresses.into_iter().zip(infos) {
+            let Some(action) = decide_roll_action(
+                &self.config,
+                info.candidate_balance,
+                info.candidate_roll_count,
+            ) else {
+                continue;
+            };
+            let expire_period =
+                current_period.saturating_add(self.config.operation_validity_periods);
+            let op = match action {
+                RollAction::Buy(roll_count) => OperationType::RollBuy { roll_count },
+                RollAction::Sell(roll_count) => OperationType::RollSell { roll_count },
+            };
+            let operation = match self.wallet.read().create_operation(
+                Operation {
+                    fee: self.config.operation_fee,
+                    expire_period,
+                    op,
+                },
+                address,
+            ) {
+                Ok(operation) => operation,
+                Err(e) => {
+                    warn!(
+                        "RollManager | Failed to create a roll operation for {}: {}",
+                        address, e
+                    );
+                    continue;
+                }
+            };
+            let operation_id = operation.id;
+            let mut storage = self.storage.clone_without_refs();
+            storage.store_operations(vec![operation]);
+            self.pool_controller.add_operations(storage.clone());
+            if let Err(e) = self.protocol_controller.propagate_operations(storage) {
+                warn!(
+                    "RollManager | Failed to propagate roll operation {}: {}",
+                    operation_id, e
+                );
+            }
+            self.pending.insert(address, (operation_id, expire_period));
+            match action {
+                RollAction::Buy(roll_count) => info!(
+                    %address,
+                    %operation_id,
+                    roll_count,
+                    balance = %info.candidate_balance,
+                    "RollManager | Buying {} rolls for {} (balance {}, {} rolls)",
+                    roll_count,
+                    address,
+                    info.candidate_balance,
+                    info.candidate_roll_count
+                ),
+                RollAction::Sell(roll_count) => info!(
+                    %address,
+                    %operation_id,
+                    roll_count,
+                    "RollManager | Selling {} rolls for {} ({} rolls, target {})",
+                    roll_count,
+                    address,
+                    info.candidate_roll_count,
+                    self.config.target_roll_count.unwrap_or_default()
+                ),
+            }
+            let (action, roll_count) = match action {
+                RollAction::Buy(roll_count) => (RollManagerAction::Buy, roll_count),
+                RollAction::Sell(roll_count) => (RollManagerAction::Sell, roll_count),
+            };
+            let event = RollManagerEvent {
+                address,
+                operation_id,
+                action,
+                roll_count,
+                candidate_balance: info.candidate_balance,
+                candidate_roll_count: info.candidate_roll_count,
+            };
+            if self.event_sender.send(event).is_err() {
+                debug!("RollManager | No subscriber to the roll manager events");
+            }
+        }
+    }
+}
+
+/// Start the roll manager thread
+pub fn start_roll_manager(
+    config: RollManagerConfig,
+    execution_controller: Box<dyn ExecutionController>,
+    pool_controller: Box<dyn PoolController>,
+    protocol_controller: Box<dyn ProtocolController>,
+    wallet: Arc<RwLock<Wallet>>,
+    storage: Storage,
+    event_sender: broadcast::Sender<RollManagerEvent>,
+) -> RollManagerStopper {
+    let (tx_stop, rx_stop) = MassaChannel::new("roll_manager_stop".to_string(), Some(1));
+    let check_tick = tick(config.check_period.to_duration());
+    let mut roll_manager = RollManager {
+        config,
+        execution_controller,
+        pool_controller,
+        protocol_controller,
+        wallet,
+        storage,
+        pending: PreHashMap::default(),
+        event_sender,
+    };
+    match std::thread::Builder::new()
+        .name("roll-manager".to_string())
+        .spawn(move || loop {
+            select! {
+                recv(rx_stop) -> _ => {
+                    break;
+                },
+                recv(check_tick) -> _ => {
+                    roll_manager.check_addresses();
+                }
+            }
+        }) {
+        Ok(handle) => RollManagerStopper {
+            handle: Some(handle),
+            tx_stopper: Some(tx_stop),
+        },
+        Err(e) => {
+            warn!("RollManager | Failed to spawn roll manager thread: {:?}", e);
+            RollManagerStopper {
+                handle: None,
+                tx_stopper: None,
+            }
+        }
+    }
+}
+
+/// Roll manager stopper doing nothing, used when the roll manager is disabled
+pub fn disabled_roll_manager() -> RollManagerStopper {
+    RollManagerStopper {
+        handle: None,
+        tx_stopper: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn amount(value: &str) -> Amount {
+        Amount::from_str(value).unwrap()
+    }
+
+    fn config(buy_threshold: Option<&str>, target_roll_count: Option<u64>) -> RollManagerConfig {
+        RollManagerConfig {
+            check_period: MassaTime::from_millis(16_000),
+            buy_threshold: buy_threshold.map(amount),
+            fee_reserve: amount("1"),
+            target_roll_count,
+            operation_fee: amount("0.01"),
+            roll_price: amount("100"),
+            operation_validity_periods: 10,
+            thread_count: 32,
+            t0: MassaTime::from_millis(16_000),
+            genesis_timestamp: MassaTime::from_millis(0),
+            last_start_period: 0,
+        }
+    }
+
+    #[test]
+    fn test_decide_roll_action() {
+        // (case, buy threshold, target roll count, candidate balance, candidate roll count, expected action)
+        let cases = [
+            // buy
+            (
+                "buy",
+                Some("200"),
+                None,
+                "350.5",
+                0,
+                Some(RollAction::Buy(3)),
+            ),
+            (
+                "buy keeps the rolls",
+                Some("200"),
+                None,
+                "250",
+                7,
+                Some(RollAction::Buy(2)),
+            ),
+            (
+                "buy up to the target",
+                Some("200"),
+                Some(5),
+                "1000",
+                4,
+                Some(RollAction::Buy(1)),
+            ),
+            // sell
+            ("sell", None, Some(2), "0.01", 5, Some(RollAction::Sell(3))),
+            (
+                "sell before buying",
+                Some("200"),
+                Some(2),
+                "1000",
+                3,
+                Some(RollAction::Sell(1)),
+            ),
+            // hold
+            (
+                "hold below the threshold",
+                Some("200"),
+                None,
+                "150",
+                0,
+                None,
+            ),
+            ("hold without threshold", None, None, "1000", 0, None),
+            ("hold at the target", Some("200"), Some(3), "1000", 3, None),
+            ("hold without fee to sell", None, Some(2), "0.009", 5, None),
+            // limits
+            ("threshold is exclusive", Some("200"), None, "200", 0, None),
+            (
+                "exactly one roll",
+                Some("50"),
+                None,
+                "101.01",
+                0,
+                Some(RollAction::Buy(1)),
+            ),
+            ("one roll minus the fee", Some("50"), None, "101", 0, None),
+            ("reserve above the balance", Some("0"), None, "0.5", 0, None),
+        ];
+        for (case, buy_threshold, target_roll_count, balance, roll_count, expected) in cases {
+            assert_eq!(
+                decide_roll_action(
+                    &config(buy_threshold, target_roll_count),
+                    amount(balance),
+                    roll_count
+                ),
+                expected,
+                "{}",
+                case
+            );
+        }
+    }
+}
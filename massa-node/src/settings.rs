@@ -4,8 +4,8 @@
 use std::{collections::HashMap, path::PathBuf};
 
 use massa_bootstrap::IpType;
-use massa_models::{config::build_massa_settings, node::NodeId};
-use massa_protocol_exports::PeerCategoryInfo;
+use massa_models::{amount::Amount, config::build_massa_settings, node::NodeId};
+use massa_protocol_exports::{PeerCategoryInfo, PeerId};
 use massa_time::MassaTime;
 use serde::Deserialize;
 use std::net::{IpAddr, SocketAddr};
@@ -26,19 +26,37 @@ pub struct ExecutionSettings {
     pub cursor_delay: MassaTime,
     pub stats_time_window_duration: MassaTime,
     pub max_read_only_gas: u64,
+    pub max_read_only_lookahead_periods: u64,
     pub abi_gas_costs_file: PathBuf,
     pub wasm_gas_costs_file: PathBuf,
     pub hd_cache_path: PathBuf,
     pub lru_cache_size: u32,
     pub hd_cache_size: usize,
     pub snip_amount: usize,
+    pub module_call_stats_path: PathBuf,
+    pub module_cache_warm_up_count: usize,
     /// slot execution outputs channel capacity
     pub broadcast_slot_execution_output_channel_capacity: usize,
+    /// asynchronous message events channel capacity
+    pub broadcast_async_message_event_channel_capacity: usize,
+    /// record the per-address staking history
+    pub staking_history: bool,
+    /// maintain the ledger analytics index
+    pub ledger_analytics: bool,
+    /// maintain the ledger Merkle tree
+    pub ledger_merkle_tree: bool,
+    /// execute the transactions and roll operations of a block optimistically in parallel
+    pub parallel_operation_execution: bool,
+    /// profile the wall time and gas of the executions
+    pub execution_profiling: bool,
+    /// duration of the window over which the execution profile is kept
+    pub execution_profiling_window: MassaTime,
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct SelectionSettings {
     pub initial_rolls_path: PathBuf,
+    pub broadcast_cycle_draws_channel_capacity: usize,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -85,6 +103,61 @@ pub struct FactorySettings {
     pub stop_production_when_zero_connections: bool,
 }
 
+/// Roll manager configuration, read from a file configuration
+#[derive(Debug, Deserialize, Clone)]
+pub struct RollManagerSettings {
+    /// buy and sell rolls automatically for the staking addresses
+    pub enabled: bool,
+    /// time between two checks of the staking addresses
+    pub check_period: MassaTime,
+    /// buy rolls when the candidate balance exceeds this amount, never buy if not set
+    pub buy_threshold: Option<Amount>,
+    /// amount always left on the addresses to pay the fees
+    pub fee_reserve: Amount,
+    /// sell rolls down to this count, and never buy more, if set
+    pub target_roll_count: Option<u64>,
+    /// fee of the roll operations
+    pub operation_fee: Amount,
+    /// capacity of the channel broadcasting the roll operations sent by the roll manager
+    pub broadcast_event_channel_capacity: usize,
+}
+
+/// Local development network configuration, used when the node is started with `--devnet`
+#[derive(Debug, Deserialize, Clone)]
+pub struct DevnetSettings {
+    /// directory where the devnet genesis files, wallet and ledger are written
+    pub path: PathBuf,
+    /// time between the periods in the same thread
+    pub t0: MassaTime,
+    /// delay between the start of the node and the genesis
+    pub genesis_delay: MassaTime,
+    /// number of funded test keys, the first one being the staker
+    pub test_key_count: u64,
+    /// initial balance of each test key
+    pub test_key_balance: Amount,
+    /// initial roll count of the staker
+    pub staker_rolls: u64,
+}
+
+/// Light sync mode configuration, used when the node is started with `--light`
+#[derive(Debug, Deserialize, Clone)]
+pub struct LightSettings {
+    /// JSON file with the trusted checkpoint: final headers of each thread and optional draws
+    pub checkpoint_path: PathBuf,
+    /// peers trusted to send the slot draws, which can't be verified without the rolls
+    pub trusted_peers: Vec<PeerId>,
+    /// keep the draws known this number of periods ahead of the current slot
+    pub draws_lookahead_periods: u64,
+    /// time between two checks of the missing draws and blocks
+    pub ask_draws_interval: MassaTime,
+    /// maximum number of headers waiting for their parents or for the draws of their slot
+    pub max_waiting_headers: usize,
+    /// maximum number of discarded block ids kept in memory
+    pub max_discarded_blocks: usize,
+    /// port on which the light node API listens
+    pub bind: SocketAddr,
+}
+
 /// Pool configuration, read from a file configuration
 #[derive(Debug, Deserialize, Clone)]
 pub struct PoolSettings {
@@ -108,6 +181,7 @@ pub struct APISettings {
     pub bind_public: SocketAddr,
     pub bind_api: SocketAddr,
     pub max_arguments: u64,
+    pub max_draws_lookahead_count: u64,
     pub openrpc_spec_path: PathBuf,
     pub max_request_body_size: u32,
     pub max_response_body_size: u32,
@@ -136,6 +210,9 @@ pub struct Settings {
     pub ledger: LedgerSettings,
     pub selector: SelectionSettings,
     pub factory: FactorySettings,
+    pub roll_manager: RollManagerSettings,
+    pub devnet: DevnetSettings,
+    pub light: LightSettings,
     pub grpc: GrpcApiSettings,
     pub metrics: MetricsSettings,
     pub versioning: VersioningSettings,
@@ -251,8 +328,10 @@ pub struct ProtocolSettings {
     pub try_connection_timer: MassaTime,
     /// try connection timer for the same peer
     pub try_connection_timer_same_peer: MassaTime,
-    /// periodically unban every peer
-    pub unban_everyone_timer: MassaTime,
+    /// duration of a first ban, doubled at each new offense of the same peer
+    pub ban_duration: MassaTime,
+    /// maximum duration of a ban
+    pub max_ban_duration: MassaTime,
     /// Timeout connection
     pub timeout_connection: MassaTime,
     /// Message timeout
@@ -269,6 +348,21 @@ pub struct ProtocolSettings {
     pub test_oldest_peer_cooldown: MassaTime,
     /// Rate limitation to apply to the data stream (per second)
     pub rate_limit: u64,
+    /// Per peer rate limitation of inbound block messages (bytes per second), 0 to disable
+    pub rate_limit_blocks: u64,
+    /// Per peer rate limitation of inbound endorsement messages (bytes per second), 0 to disable
+    pub rate_limit_endorsements: u64,
+    /// Per peer rate limitation of inbound operation messages (bytes per second), 0 to disable
+    pub rate_limit_operations: u64,
+    /// Number of rate limited messages after which a peer is banned, 0 to never ban
+    pub rate_limit_ban_threshold: u64,
+    /// Ask block operation lists as operation id prefixes to the peers supporting it
+    pub compact_block_relay: bool,
+    /// Send periodically to the peers supporting it a filter of the recent operations,
+    /// so that they only announce us the operations we miss
+    pub operation_reconciliation: bool,
+    /// Interval between two filters sent to a peer (in milliseconds)
+    pub operation_reconciliation_interval: MassaTime,
 }
 
 /// gRPC settings
@@ -374,6 +468,8 @@ pub struct GrpcApiSettings {
 pub struct VersioningSettings {
     // Warn user to update its node if we reach this percentage for announced network versions
     pub(crate) mip_stats_warn_announced_version: u32,
+    // Load the MIP list from this JSON file instead of the one compiled in the node
+    pub(crate) mip_list_path: Option<PathBuf>,
 }
 
 #[cfg(test)]
@@ -1,11 +1,11 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_models::amount::Amount;
-use massa_time::MassaTime;
+use massa_time::{MassaTime, SharedClock};
 use serde::{Deserialize, Serialize};
 
 /// Pool configuration
-#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PoolConfig {
     /// thread count
     pub thread_count: u8,
@@ -62,4 +62,7 @@ pub struct PoolConfig {
     /// * If from snapshot: retrieve from args
     /// * If from bootstrap: set during bootstrap
     pub last_start_period: u64,
+    /// source of the current time, the system clock outside of tests
+    #[serde(skip)]
+    pub clock: SharedClock,
 }
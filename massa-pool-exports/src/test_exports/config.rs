@@ -5,7 +5,7 @@ use massa_models::config::{
     MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_GAS_PER_BLOCK, MAX_OPERATIONS_PER_BLOCK,
     OPERATION_VALIDITY_PERIODS, PERIODS_PER_CYCLE, ROLL_PRICE, T0, THREAD_COUNT,
 };
-use massa_time::MassaTime;
+use massa_time::{MassaTime, SharedClock};
 
 use crate::PoolConfig;
 
@@ -38,6 +38,7 @@ impl Default for PoolConfig {
             last_start_period: 0,
             operation_pool_refresh_interval: MassaTime::from_millis(2000),
             operation_max_future_start_delay: T0.saturating_mul(5),
+            clock: SharedClock::default(),
         }
     }
 }
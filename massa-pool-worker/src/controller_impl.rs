@@ -2,6 +2,7 @@
 
 //! Pool controller implementation
 
+use crossbeam_channel::{Sender, TrySendError};
 use massa_models::{
     block_id::BlockId, denunciation::Denunciation, denunciation::DenunciationPrecursor,
     endorsement::EndorsementId, operation::OperationId, slot::Slot,
@@ -9,8 +10,7 @@ use massa_models::{
 use massa_pool_exports::{PoolConfig, PoolController, PoolManager};
 use massa_storage::Storage;
 use parking_lot::RwLock;
-use std::sync::mpsc::TrySendError;
-use std::sync::{mpsc::SyncSender, Arc};
+use std::sync::Arc;
 use tracing::{info, warn};
 
 use crate::{
@@ -43,11 +43,11 @@ pub struct PoolControllerImpl {
     /// Shared reference to the denunciation pool
     pub(crate) denunciation_pool: Arc<RwLock<DenunciationPool>>,
     /// Operation write worker command sender
-    pub(crate) operations_input_sender: SyncSender<Command>,
+    pub(crate) operations_input_sender: Sender<Command>,
     /// Endorsement write worker command sender
-    pub(crate) endorsements_input_sender: SyncSender<Command>,
+    pub(crate) endorsements_input_sender: Sender<Command>,
     /// Denunciation write worker command sender
-    pub(crate) denunciations_input_sender: SyncSender<Command>,
+    pub(crate) denunciations_input_sender: Sender<Command>,
     /// Last final periods from Consensus
     pub last_cs_final_periods: Vec<u64>,
 }
@@ -244,11 +244,11 @@ pub struct PoolManagerImpl {
     /// Handle used to join the denunciation thread
     pub(crate) denunciations_thread_handle: Option<std::thread::JoinHandle<()>>,
     /// Operations input data mpsc (used to stop the pool thread)
-    pub(crate) operations_input_sender: SyncSender<Command>,
+    pub(crate) operations_input_sender: Sender<Command>,
     /// Endorsements input data mpsc (used to stop the pool thread)
-    pub(crate) endorsements_input_sender: SyncSender<Command>,
+    pub(crate) endorsements_input_sender: Sender<Command>,
     /// Denunciations input data mpsc (used to stop the pool thread)
-    pub(crate) denunciations_input_sender: SyncSender<Command>,
+    pub(crate) denunciations_input_sender: Sender<Command>,
 }
 
 impl PoolManager for PoolManagerImpl {
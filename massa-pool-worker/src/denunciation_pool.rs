@@ -12,7 +12,6 @@ use massa_models::{
 };
 use massa_pool_exports::{PoolChannels, PoolConfig};
 use massa_storage::Storage;
-use massa_time::MassaTime;
 
 pub struct DenunciationPool {
     /// pool configuration
@@ -68,7 +67,7 @@ impl DenunciationPool {
             return;
         }
 
-        let now = MassaTime::now();
+        let now = self.config.clock.now();
 
         // get closest slot according to the current absolute time
         let slot_now = get_closest_slot_to_timestamp(
@@ -10,7 +10,6 @@ use massa_models::{
 };
 use massa_pool_exports::{PoolChannels, PoolConfig};
 use massa_storage::Storage;
-use massa_time::MassaTime;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
 use std::{cmp::max, cmp::Ordering, cmp::PartialOrd, collections::BTreeSet, sync::Arc};
@@ -61,7 +60,7 @@ impl OperationPool {
 
     /// Get the relevant PoS draws of our staking addresses
     fn get_pos_draws(&mut self) -> BTreeSet<Slot> {
-        let now = MassaTime::now();
+        let now = self.config.clock.now();
 
         // min slot for PoS draw search = the earliest final slot
         let min_slot = self
@@ -242,7 +241,7 @@ impl OperationPool {
         _exec_statuses: &PreHashMap<OperationId, bool>,
         pos_draws: &BTreeSet<Slot>,
     ) -> PreHashMap<OperationId, f32> {
-        let now = MassaTime::now();
+        let now = self.config.clock.now();
         let now_period = get_latest_block_slot_at_timestamp(
             self.config.thread_count,
             self.config.t0,
@@ -6,18 +6,13 @@ use crate::controller_impl::{Command, PoolManagerImpl};
 use crate::denunciation_pool::DenunciationPool;
 use crate::operation_pool::OperationPool;
 use crate::{controller_impl::PoolControllerImpl, endorsement_pool::EndorsementPool};
+use crossbeam_channel::{bounded, select, Receiver, RecvError};
 use massa_pool_exports::PoolConfig;
 use massa_pool_exports::{PoolChannels, PoolController, PoolManager};
 use massa_storage::Storage;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
-use std::time::Instant;
-use std::{
-    sync::mpsc::{sync_channel, Receiver, RecvError, RecvTimeoutError},
-    sync::Arc,
-    thread,
-    thread::JoinHandle,
-};
+use std::{sync::Arc, thread, thread::JoinHandle};
 use tracing::warn;
 
 /// Endorsement pool write thread instance
@@ -99,13 +94,19 @@ impl OperationPoolThread {
 
     /// Run the thread.
     fn run(self, config: PoolConfig) {
-        let mut start_time = Instant::now();
-        let tick = config.operation_pool_refresh_interval.to_duration();
+        let next_refresh = |config: &PoolConfig| {
+            config.clock.after(
+                config
+                    .clock
+                    .now()
+                    .saturating_add(config.operation_pool_refresh_interval),
+            )
+        };
+        let mut refresh_timer = next_refresh(&config);
         loop {
-            let duration = (start_time + tick).saturating_duration_since(Instant::now());
-            if !duration.is_zero() {
-                match self.receiver.recv_timeout(duration) {
-                    Err(RecvTimeoutError::Disconnected) | Ok(Command::Stop) => break,
+            select! {
+                recv(self.receiver) -> command => match command {
+                    Err(RecvError) | Ok(Command::Stop) => break,
                     Ok(Command::AddItems(operations)) => {
                         self.operation_pool.write().add_operations(operations)
                     }
@@ -117,11 +118,11 @@ impl OperationPoolThread {
                         warn!("OperationPoolThread received an unexpected command");
                         continue;
                     }
-                    Err(RecvTimeoutError::Timeout) => {}
-                };
-            } else {
-                self.operation_pool.write().refresh();
-                start_time = Instant::now();
+                },
+                recv(refresh_timer) -> _ => {
+                    self.operation_pool.write().refresh();
+                    refresh_timer = next_refresh(&config);
+                }
             }
         }
     }
@@ -189,26 +190,29 @@ pub fn start_pool_controller(
     wallet: Arc<RwLock<Wallet>>,
 ) -> (Box<dyn PoolManager>, Box<dyn PoolController>) {
     let (operations_input_sender, operations_input_receiver) =
-        sync_channel(config.operations_channel_size);
+        bounded(config.operations_channel_size);
     let (endorsements_input_sender, endorsements_input_receiver) =
-        sync_channel(config.endorsements_channel_size);
+        bounded(config.endorsements_channel_size);
     let (denunciations_input_sender, denunciations_input_receiver) =
-        sync_channel(config.denunciations_channel_size);
+        bounded(config.denunciations_channel_size);
     let operation_pool = Arc::new(RwLock::new(OperationPool::init(
-        config,
+        config.clone(),
         storage,
         channels.clone(),
         wallet.clone(),
     )));
     let endorsement_pool = Arc::new(RwLock::new(EndorsementPool::init(
-        config,
+        config.clone(),
         storage,
         channels.clone(),
         wallet,
     )));
-    let denunciation_pool = Arc::new(RwLock::new(DenunciationPool::init(config, channels)));
+    let denunciation_pool = Arc::new(RwLock::new(DenunciationPool::init(
+        config.clone(),
+        channels,
+    )));
     let controller = PoolControllerImpl {
-        _config: config,
+        _config: config.clone(),
         operation_pool: operation_pool.clone(),
         endorsement_pool: endorsement_pool.clone(),
         denunciation_pool: denunciation_pool.clone(),
@@ -0,0 +1,22 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::slot::Slot;
+use serde::Serialize;
+
+/// Notification sent when the draws of a new cycle become available
+#[derive(Debug, Clone, Serialize)]
+pub struct CycleDrawsNotification {
+    /// cycle whose draws were computed
+    pub cycle: u64,
+    /// first slot of the cycle
+    pub first_slot: Slot,
+    /// last slot of the cycle
+    pub last_slot: Slot,
+}
+
+/// channels used by the selector worker
+#[derive(Clone)]
+pub struct SelectorChannels {
+    /// Broadcast channel for the cycles whose draws become available
+    pub cycle_draws_sender: tokio::sync::broadcast::Sender<CycleDrawsNotification>,
+}
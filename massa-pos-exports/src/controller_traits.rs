@@ -5,15 +5,16 @@
 
 use std::collections::BTreeMap;
 
-use crate::PosResult;
+use crate::{PosError, PosResult};
 use massa_hash::Hash;
 use massa_models::{address::Address, prehash::PreHashSet, slot::Slot};
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "test-exports")]
 use std::collections::{HashMap, VecDeque};
 
 /// Selections of endorsements and producer
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Selection {
     /// Chosen endorsements
     pub endorsements: Vec<Address>,
@@ -21,6 +22,41 @@ pub struct Selection {
     pub producer: Address,
 }
 
+/// Draws of an address during a cycle, and the rolls they were drawn from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CycleDrawStats {
+    /// roll count of the address used for the draws of the cycle
+    pub rolls: u64,
+    /// total roll count used for the draws of the cycle
+    pub total_rolls: u64,
+    /// number of block slots of the cycle
+    pub block_slots: u64,
+    /// number of endorsement slots of the cycle
+    pub endorsement_slots: u64,
+    /// number of blocks the address was drawn to produce
+    pub block_draws: u64,
+    /// number of endorsements the address was drawn to produce
+    pub endorsement_draws: u64,
+}
+
+impl CycleDrawStats {
+    /// Number of block draws expected from the roll distribution
+    pub fn expected_block_draws(&self) -> f64 {
+        if self.total_rolls == 0 {
+            return 0.0;
+        }
+        self.block_slots as f64 * self.rolls as f64 / self.total_rolls as f64
+    }
+
+    /// Number of endorsement draws expected from the roll distribution
+    pub fn expected_endorsement_draws(&self) -> f64 {
+        if self.total_rolls == 0 {
+            return 0.0;
+        }
+        self.endorsement_slots as f64 * self.rolls as f64 / self.total_rolls as f64
+    }
+}
+
 #[cfg(feature = "test-exports")]
 use std::sync::Arc;
 
@@ -62,6 +98,36 @@ pub trait SelectorController: Send + Sync {
         restrict_to_addresses: Option<&'a PreHashSet<Address>>,
     ) -> PosResult<BTreeMap<Slot, Selection>>;
 
+    /// Get the next selections involving at least one of the given addresses,
+    /// looking through all the cycles available in the cache:
+    /// # Arguments
+    /// * `from_slot`: first slot to consider (included)
+    /// * `addresses`: addresses whose draws are looked for
+    /// * `max_count`: maximum number of selections returned
+    #[allow(clippy::needless_lifetimes)] // lifetime elision conflicts with Mockall
+    fn get_next_selections<'a>(
+        &self,
+        from_slot: Slot,
+        addresses: &'a PreHashSet<Address>,
+        max_count: usize,
+    ) -> PosResult<BTreeMap<Slot, Selection>>;
+
+    /// Get the draw statistics of the given addresses for every cycle available in the cache
+    #[allow(clippy::needless_lifetimes)] // lifetime elision conflicts with Mockall
+    fn get_draw_stats<'a>(
+        &self,
+        addresses: &'a PreHashSet<Address>,
+    ) -> PosResult<BTreeMap<u64, BTreeMap<Address, CycleDrawStats>>>;
+
+    /// Feed selections obtained from a trusted source instead of drawing them.
+    ///
+    /// Only supported by the selectors of light nodes, which do not know the rolls to draw from.
+    fn feed_selections(&self, _selections: BTreeMap<Slot, Selection>) -> PosResult<()> {
+        Err(PosError::UnsupportedOperation(
+            "this selector draws its own selections".into(),
+        ))
+    }
+
     /// Returns a boxed clone of self.
     /// Useful to allow cloning `Box<dyn SelectorController>`.
     fn clone_box(&self) -> Box<dyn SelectorController>;
@@ -24,4 +24,6 @@ pub enum PosError {
     DeferredCreditsFileLoadingError(String),
     /// Communication channel was down: {0}
     ChannelDown(String),
+    /// Operation not supported: {0}
+    UnsupportedOperation(String),
 }
@@ -7,6 +7,7 @@
 #![warn(missing_docs)]
 #![warn(unused_imports)]
 
+mod channels;
 mod config;
 mod controller_traits;
 mod cycle_info;
@@ -15,17 +16,20 @@ mod error;
 mod pos_changes;
 mod pos_final_state;
 mod settings;
+mod staking_history;
 
+pub use channels::{CycleDrawsNotification, SelectorChannels};
 pub use config::PoSConfig;
+pub use controller_traits::{CycleDrawStats, Selection, SelectorController, SelectorManager};
 #[cfg(any(test, feature = "test-exports"))]
 pub use controller_traits::{MockSelectorController, MockSelectorControllerWrapper};
-pub use controller_traits::{Selection, SelectorController, SelectorManager};
 pub use cycle_info::*;
 pub use deferred_credits::*;
 pub use error::*;
 pub use pos_changes::*;
 pub use pos_final_state::*;
 pub use settings::SelectorConfig;
+pub use staking_history::*;
 
 #[cfg(feature = "test-exports")]
 pub mod test_exports;
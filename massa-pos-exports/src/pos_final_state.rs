@@ -1,14 +1,16 @@
 use crate::{
     CycleHistoryDeserializer, CycleHistorySerializer, CycleInfo, DeferredCreditsDeserializer,
     DeferredCreditsSerializer, PoSChanges, PosError, PosResult, ProductionStats,
-    SelectorController,
+    SelectorController, StakingCycleRecord, StakingCycleRecordDeserializer,
+    StakingCycleRecordSerializer, StakingHistoryChanges,
 };
 use crate::{DeferredCredits, PoSConfig};
 use bitvec::vec::BitVec;
 use massa_db_exports::{
     DBBatch, MassaDirection, MassaIteratorMode, ShareableMassaDBController,
     CYCLE_HISTORY_DESER_ERROR, CYCLE_HISTORY_PREFIX, CYCLE_HISTORY_SER_ERROR,
-    DEFERRED_CREDITS_DESER_ERROR, DEFERRED_CREDITS_PREFIX, DEFERRED_CREDITS_SER_ERROR, STATE_CF,
+    DEFERRED_CREDITS_DESER_ERROR, DEFERRED_CREDITS_PREFIX, DEFERRED_CREDITS_SER_ERROR, INDEXES_CF,
+    STAKING_HISTORY_DESER_ERROR, STAKING_HISTORY_PREFIX, STAKING_HISTORY_SER_ERROR, STATE_CF,
 };
 use massa_hash::{Hash, HashXof, HASH_XOF_SIZE_BYTES};
 use massa_models::amount::Amount;
@@ -356,6 +358,36 @@ impl PoSFinalState {
         }
     }
 
+    /// Overwrites the roll counts of the given addresses in every cycle of the history,
+    /// so that the next draws computed from the history use them.
+    /// If `reset_others` is true, the rolls of all the other addresses are removed.
+    ///
+    /// USED ONLY TO FORK A LOCAL NETWORK FROM A SNAPSHOT
+    pub fn overwrite_roll_counts(
+        &mut self,
+        roll_counts: &BTreeMap<Address, u64>,
+        reset_others: bool,
+        batch: &mut DBBatch,
+    ) {
+        let cycles: Vec<u64> = self
+            .cycle_history_cache
+            .iter()
+            .map(|(cycle, _)| *cycle)
+            .collect();
+        for cycle in cycles {
+            if reset_others {
+                for address in self.get_all_roll_counts(cycle).keys() {
+                    if !roll_counts.contains_key(address) {
+                        self.put_cycle_history_address_entry(cycle, address, Some(&0), None, batch);
+                    }
+                }
+            }
+            for (address, roll_count) in roll_counts {
+                self.put_cycle_history_address_entry(cycle, address, Some(roll_count), None, batch);
+            }
+        }
+    }
+
     /// Sends the current draw inputs (initial or bootstrapped) to the selector.
     /// Waits for the initial draws to be performed.
     pub fn compute_initial_draws(&mut self) -> PosResult<()> {
@@ -1140,6 +1172,72 @@ impl PoSFinalState {
         }
     }
 
+    /// Adds the staking activity of a final slot to the staking history of its cycle,
+    /// in a batch of the node local indexes.
+    ///
+    /// The staking history is a node local index: it is written along with the final state,
+    /// outside of the state hash and of the change history.
+    pub fn apply_staking_history_changes_to_batch(
+        &self,
+        cycle: u64,
+        changes: &StakingHistoryChanges,
+        indexes_batch: &mut DBBatch,
+    ) {
+        let serializer = StakingCycleRecordSerializer::new();
+        let deserializer = StakingCycleRecordDeserializer::new();
+        let db = self.db.read();
+        for (address, changes) in changes {
+            let key = [
+                &self.staking_history_address_prefix(address)[..],
+                &cycle.to_be_bytes()[..],
+            ]
+            .concat();
+            let mut record = match db
+                .get_cf(INDEXES_CF, key.clone())
+                .expect(STAKING_HISTORY_DESER_ERROR)
+            {
+                Some(serialized_record) => {
+                    deserializer
+                        .deserialize::<DeserializeError>(&serialized_record)
+                        .expect(STAKING_HISTORY_DESER_ERROR)
+                        .1
+                }
+                None => StakingCycleRecord::default(),
+            };
+            record.extend(changes);
+
+            let mut serialized_record = Vec::new();
+            serializer
+                .serialize(&record, &mut serialized_record)
+                .expect(STAKING_HISTORY_SER_ERROR);
+            db.put_or_update_entry_value(indexes_batch, key, &serialized_record);
+        }
+    }
+
+    /// Gets the staking history of an address, per cycle
+    pub fn get_staking_history(&self, address: &Address) -> BTreeMap<u64, StakingCycleRecord> {
+        let db = self.db.read();
+        let deserializer = StakingCycleRecordDeserializer::new();
+        let prefix = self.staking_history_address_prefix(address);
+
+        let mut history = BTreeMap::new();
+        for (serialized_key, serialized_value) in db.prefix_iterator_cf(INDEXES_CF, &prefix) {
+            if !serialized_key.starts_with(&prefix) {
+                break;
+            }
+            let cycle = u64::from_be_bytes(
+                serialized_key[prefix.len()..]
+                    .try_into()
+                    .expect(STAKING_HISTORY_DESER_ERROR),
+            );
+            let (_, record) = deserializer
+                .deserialize::<DeserializeError>(&serialized_value)
+                .expect(STAKING_HISTORY_DESER_ERROR);
+            history.insert(cycle, record);
+        }
+        history
+    }
+
     /// Check if a cycle is complete (all slots finalized)
     pub fn is_cycle_complete(&self, cycle: u64) -> Option<bool> {
         let key = complete_key!(self.cycle_history_cycle_prefix(cycle));
@@ -1355,6 +1453,16 @@ impl PoSFinalState {
 
 /// Helpers for key and value management
 impl PoSFinalState {
+    /// Helper function to construct the key prefix of the staking history of an address.
+    /// The keys end with the cycle in big endian, so that they are sorted by cycle.
+    fn staking_history_address_prefix(&self, address: &Address) -> Vec<u8> {
+        [
+            STAKING_HISTORY_PREFIX.as_bytes(),
+            &address.to_prefixed_bytes()[..],
+        ]
+        .concat()
+    }
+
     /// Helper function to construct the key prefix associated with a given cycle
     fn cycle_history_cycle_prefix(&self, cycle: u64) -> Vec<u8> {
         let mut serialized_key = Vec::new();
@@ -2069,6 +2177,94 @@ mod tests {
         }
     }
 
+    // This test checks that the staking history accumulates per cycle without changing the state hash
+    #[test]
+    fn test_staking_history() {
+        let pos_config = PoSConfig {
+            periods_per_cycle: 2,
+            thread_count: 2,
+            cycle_history_length: POS_SAVED_CYCLES,
+            max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
+            max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
+            max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
+            initial_deferred_credits_path: None,
+        };
+
+        let tempdir = TempDir::new().expect("cannot create temp directory");
+        let db_config = MassaDBConfig {
+            path: tempdir.path().to_path_buf(),
+            max_history_length: 10,
+            max_final_state_elements_size: 100_000,
+            max_versioning_elements_size: 100_000,
+            thread_count: 2,
+        };
+        let db = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+        ));
+        let init_seed = Hash::compute_from(b"");
+        let initial_seeds = vec![Hash::compute_from(init_seed.to_bytes()), init_seed];
+
+        let pos_state = PoSFinalState {
+            config: pos_config.clone(),
+            db: db.clone(),
+            cycle_history_cache: Default::default(),
+            rng_seed_cache: None,
+            selector: Box::new(MockSelectorController::new()),
+            initial_rolls: Default::default(),
+            initial_seeds,
+            deferred_credits_serializer: DeferredCreditsSerializer::new(),
+            deferred_credits_deserializer: DeferredCreditsDeserializer::new(
+                pos_config.thread_count,
+                pos_config.max_credit_length,
+            ),
+            cycle_info_serializer: CycleHistorySerializer::new(),
+            cycle_info_deserializer: CycleHistoryDeserializer::new(
+                pos_config.cycle_history_length as u64,
+                pos_config.max_rolls_length,
+                pos_config.max_production_stats_length,
+            ),
+        };
+
+        let addr = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let other_addr = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let hash_before = db.read().get_xof_db_hash();
+
+        let produced = StakingCycleRecord {
+            block_success_count: 1,
+            rewards: Amount::from_str("0.5").unwrap(),
+            ..Default::default()
+        };
+        let missed = StakingCycleRecord {
+            block_failure_count: 1,
+            rolls_bought: 3,
+            ..Default::default()
+        };
+        for (cycle, changes) in [
+            (4, BTreeMap::from([(addr, produced)])),
+            (4, BTreeMap::from([(addr, missed), (other_addr, missed)])),
+            (300, BTreeMap::from([(addr, produced)])),
+        ] {
+            let mut indexes_batch = DBBatch::new();
+            pos_state.apply_staking_history_changes_to_batch(cycle, &changes, &mut indexes_batch);
+            db.write().write_batch_with_indexes(
+                DBBatch::new(),
+                DBBatch::new(),
+                indexes_batch,
+                None,
+            );
+        }
+
+        let history = pos_state.get_staking_history(&addr);
+        let mut cycle_4 = produced;
+        cycle_4.extend(&missed);
+        assert_eq!(history, BTreeMap::from([(4, cycle_4), (300, produced)]));
+        assert_eq!(
+            pos_state.get_staking_history(&other_addr),
+            BTreeMap::from([(4, missed)])
+        );
+        assert_eq!(db.read().get_xof_db_hash(), hash_before);
+    }
+
     // This test aims to check that the basic workflow of apply changes to the PoS state works.
     #[test]
     fn test_pos_final_state_hash_computation() {
@@ -16,4 +16,6 @@ pub struct SelectorConfig {
     pub genesis_address: Address,
     /// communication channel length
     pub channel_size: usize,
+    /// capacity of the broadcast channel notifying new cycle draws
+    pub broadcast_cycle_draws_channel_capacity: usize,
 }
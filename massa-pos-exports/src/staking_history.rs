@@ -0,0 +1,211 @@
+//! Per-address staking history.
+//!
+//! Unlike the cycle history, which only keeps the last cycles needed for the draws,
+//! the staking history is a node local index that is never pruned.
+//! It is not part of the consensus state: it is neither hashed nor bootstrapped,
+//! and only covers the slots finalized while the node had it enabled.
+
+use massa_models::{
+    address::Address,
+    amount::{Amount, AmountDeserializer, AmountSerializer},
+};
+use massa_serialization::{
+    Deserializer, SerializeError, Serializer, U64VarIntDeserializer, U64VarIntSerializer,
+};
+use nom::{
+    error::{context, ContextError, ParseError},
+    sequence::tuple,
+    IResult, Parser,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::Bound::Included;
+
+/// Staking activity of an address during a cycle
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct StakingCycleRecord {
+    /// Number of successfully created blocks
+    pub block_success_count: u64,
+    /// Number of blocks missed
+    pub block_failure_count: u64,
+    /// Number of endorsements included in blocks
+    pub endorsement_success_count: u64,
+    /// Number of endorsements missing from produced blocks
+    pub endorsement_failure_count: u64,
+    /// Coins earned by producing blocks and endorsements
+    pub rewards: Amount,
+    /// Number of rolls bought
+    pub rolls_bought: u64,
+    /// Number of rolls sold, including the implicit sells caused by too many misses
+    pub rolls_sold: u64,
+    /// Number of rolls slashed by denunciations
+    pub rolls_slashed: u64,
+    /// Deferred credits paid to the address
+    pub deferred_credits_released: Amount,
+}
+
+impl StakingCycleRecord {
+    /// Increment a staking record with another
+    pub fn extend(&mut self, other: &StakingCycleRecord) {
+        self.block_success_count = self
+            .block_success_count
+            .saturating_add(other.block_success_count);
+        self.block_failure_count = self
+            .block_failure_count
+            .saturating_add(other.block_failure_count);
+        self.endorsement_success_count = self
+            .endorsement_success_count
+            .saturating_add(other.endorsement_success_count);
+        self.endorsement_failure_count = self
+            .endorsement_failure_count
+            .saturating_add(other.endorsement_failure_count);
+        self.rewards = self.rewards.saturating_add(other.rewards);
+        self.rolls_bought = self.rolls_bought.saturating_add(other.rolls_bought);
+        self.rolls_sold = self.rolls_sold.saturating_add(other.rolls_sold);
+        self.rolls_slashed = self.rolls_slashed.saturating_add(other.rolls_slashed);
+        self.deferred_credits_released = self
+            .deferred_credits_released
+            .saturating_add(other.deferred_credits_released);
+    }
+}
+
+/// Staking activity caused by the execution of a slot, per address
+pub type StakingHistoryChanges = BTreeMap<Address, StakingCycleRecord>;
+
+/// Serializer for `StakingCycleRecord`
+#[derive(Clone)]
+pub struct StakingCycleRecordSerializer {
+    u64_ser: U64VarIntSerializer,
+    amount_ser: AmountSerializer,
+}
+
+impl Default for StakingCycleRecordSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StakingCycleRecordSerializer {
+    /// Creates a new `StakingCycleRecord` serializer
+    pub fn new() -> Self {
+        Self {
+            u64_ser: U64VarIntSerializer::new(),
+            amount_ser: AmountSerializer::new(),
+        }
+    }
+}
+
+impl Serializer<StakingCycleRecord> for StakingCycleRecordSerializer {
+    fn serialize(
+        &self,
+        value: &StakingCycleRecord,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        self.u64_ser.serialize(&value.block_success_count, buffer)?;
+        self.u64_ser.serialize(&value.block_failure_count, buffer)?;
+        self.u64_ser
+            .serialize(&value.endorsement_success_count, buffer)?;
+        self.u64_ser
+            .serialize(&value.endorsement_failure_count, buffer)?;
+        self.amount_ser.serialize(&value.rewards, buffer)?;
+        self.u64_ser.serialize(&value.rolls_bought, buffer)?;
+        self.u64_ser.serialize(&value.rolls_sold, buffer)?;
+        self.u64_ser.serialize(&value.rolls_slashed, buffer)?;
+        self.amount_ser
+            .serialize(&value.deferred_credits_released, buffer)?;
+        Ok(())
+    }
+}
+
+/// Deserializer for `StakingCycleRecord`
+#[derive(Clone)]
+pub struct StakingCycleRecordDeserializer {
+    u64_deserializer: U64VarIntDeserializer,
+    amount_deserializer: AmountDeserializer,
+}
+
+impl Default for StakingCycleRecordDeserializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StakingCycleRecordDeserializer {
+    /// Creates a new `StakingCycleRecord` deserializer
+    pub fn new() -> Self {
+        Self {
+            u64_deserializer: U64VarIntDeserializer::new(Included(u64::MIN), Included(u64::MAX)),
+            amount_deserializer: AmountDeserializer::new(
+                Included(Amount::MIN),
+                Included(Amount::MAX),
+            ),
+        }
+    }
+}
+
+impl Deserializer<StakingCycleRecord> for StakingCycleRecordDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], StakingCycleRecord, E> {
+        context(
+            "Failed StakingCycleRecord deserialization",
+            tuple((
+                context("Failed block_success_count deserialization", |input| {
+                    self.u64_deserializer.deserialize(input)
+                }),
+                context("Failed block_failure_count deserialization", |input| {
+                    self.u64_deserializer.deserialize(input)
+                }),
+                context(
+                    "Failed endorsement_success_count deserialization",
+                    |input| self.u64_deserializer.deserialize(input),
+                ),
+                context(
+                    "Failed endorsement_failure_count deserialization",
+                    |input| self.u64_deserializer.deserialize(input),
+                ),
+                context("Failed rewards deserialization", |input| {
+                    self.amount_deserializer.deserialize(input)
+                }),
+                context("Failed rolls_bought deserialization", |input| {
+                    self.u64_deserializer.deserialize(input)
+                }),
+                context("Failed rolls_sold deserialization", |input| {
+                    self.u64_deserializer.deserialize(input)
+                }),
+                context("Failed rolls_slashed deserialization", |input| {
+                    self.u64_deserializer.deserialize(input)
+                }),
+                context(
+                    "Failed deferred_credits_released deserialization",
+                    |input| self.amount_deserializer.deserialize(input),
+                ),
+            )),
+        )
+        .map(
+            |(
+                block_success_count,
+                block_failure_count,
+                endorsement_success_count,
+                endorsement_failure_count,
+                rewards,
+                rolls_bought,
+                rolls_sold,
+                rolls_slashed,
+                deferred_credits_released,
+            )| StakingCycleRecord {
+                block_success_count,
+                block_failure_count,
+                endorsement_success_count,
+                endorsement_failure_count,
+                rewards,
+                rolls_bought,
+                rolls_sold,
+                rolls_slashed,
+                deferred_credits_released,
+            },
+        )
+        .parse(buffer)
+    }
+}
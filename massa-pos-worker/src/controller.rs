@@ -8,7 +8,9 @@ use std::collections::BTreeMap;
 use crate::{Command, DrawCachePtr};
 use massa_hash::Hash;
 use massa_models::{address::Address, prehash::PreHashSet, slot::Slot};
-use massa_pos_exports::{PosError, PosResult, Selection, SelectorController, SelectorManager};
+use massa_pos_exports::{
+    CycleDrawStats, PosError, PosResult, Selection, SelectorController, SelectorManager,
+};
 #[cfg(feature = "test-exports")]
 use std::collections::{HashMap, VecDeque};
 use std::sync::mpsc::SyncSender;
@@ -21,6 +23,8 @@ pub struct SelectorControllerImpl {
     pub(crate) periods_per_cycle: u64,
     /// thread count
     pub(crate) thread_count: u8,
+    /// number of endorsements per block
+    pub(crate) endorsement_count: u32,
     /// Cache storing the computed selections for each cycle.
     pub(crate) cache: DrawCachePtr,
     /// MPSC to send commands to the selector thread
@@ -186,6 +190,121 @@ impl SelectorController for SelectorControllerImpl {
         Ok(res)
     }
 
+    /// Get the next selections involving at least one of the given addresses,
+    /// looking through all the cycles available in the cache:
+    /// # Arguments
+    /// * `from_slot`: first slot to consider (included)
+    /// * `addresses`: addresses whose draws are looked for
+    /// * `max_count`: maximum number of selections returned
+    #[allow(clippy::needless_lifetimes)] // lifetime elision conflicts with Mockall
+    fn get_next_selections<'a>(
+        &self,
+        from_slot: Slot,
+        addresses: &'a PreHashSet<Address>,
+        max_count: usize,
+    ) -> PosResult<BTreeMap<Slot, Selection>> {
+        let mut res = BTreeMap::new();
+        if addresses.is_empty() || max_count == 0 {
+            return Ok(res);
+        }
+        // take lock
+        let (_cache_cv, cache_lock) = &*self.cache;
+        let cache_guard = cache_lock.read();
+        let cache = cache_guard.as_ref().map_err(|err| err.clone())?;
+
+        for cycle_draws in cache.0.iter() {
+            let last_slot = Slot::new_last_of_cycle(
+                cycle_draws.cycle,
+                self.periods_per_cycle,
+                self.thread_count,
+            )
+            .expect("could not get last slot of available cycle");
+            if last_slot < from_slot {
+                continue;
+            }
+            let mut slot = std::cmp::max(
+                from_slot,
+                Slot::new_first_of_cycle(cycle_draws.cycle, self.periods_per_cycle)
+                    .expect("could not get first slot of available cycle"),
+            );
+            loop {
+                let selection = cycle_draws
+                    .draws
+                    .get(&slot)
+                    .ok_or(PosError::CycleUnavailable(cycle_draws.cycle))?;
+                if addresses.contains(&selection.producer)
+                    || selection.endorsements.iter().any(|a| addresses.contains(a))
+                {
+                    res.insert(slot, selection.clone());
+                    if res.len() >= max_count {
+                        return Ok(res);
+                    }
+                }
+                if slot == last_slot {
+                    break;
+                }
+                slot = match slot.get_next_slot(self.thread_count) {
+                    Ok(s) => s,
+                    Err(_) => return Ok(res),
+                };
+            }
+        }
+        Ok(res)
+    }
+
+    /// Get the draw statistics of the given addresses for every cycle available in the cache
+    #[allow(clippy::needless_lifetimes)] // lifetime elision conflicts with Mockall
+    fn get_draw_stats<'a>(
+        &self,
+        addresses: &'a PreHashSet<Address>,
+    ) -> PosResult<BTreeMap<u64, BTreeMap<Address, CycleDrawStats>>> {
+        // take lock
+        let (_cache_cv, cache_lock) = &*self.cache;
+        let cache_guard = cache_lock.read();
+        let cache = cache_guard.as_ref().map_err(|err| err.clone())?;
+
+        let mut res = BTreeMap::new();
+        for cycle_draws in cache.0.iter() {
+            let total_rolls = cycle_draws
+                .lookback_rolls
+                .values()
+                .fold(0u64, |acc, rolls| acc.saturating_add(*rolls));
+            let block_slots = cycle_draws.draws.len() as u64;
+            let mut cycle_stats: BTreeMap<Address, CycleDrawStats> = addresses
+                .iter()
+                .map(|addr| {
+                    (
+                        *addr,
+                        CycleDrawStats {
+                            rolls: cycle_draws
+                                .lookback_rolls
+                                .get(addr)
+                                .copied()
+                                .unwrap_or_default(),
+                            total_rolls,
+                            block_slots,
+                            endorsement_slots: block_slots
+                                .saturating_mul(self.endorsement_count as u64),
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect();
+            for selection in cycle_draws.draws.values() {
+                if let Some(stats) = cycle_stats.get_mut(&selection.producer) {
+                    stats.block_draws += 1;
+                }
+                for endorser in selection.endorsements.iter() {
+                    if let Some(stats) = cycle_stats.get_mut(endorser) {
+                        stats.endorsement_draws += 1;
+                    }
+                }
+            }
+            res.insert(cycle_draws.cycle, cycle_stats);
+        }
+        Ok(res)
+    }
+
     /// Returns a boxed clone of self.
     /// Allows cloning `Box<dyn SelectorController>`,
     /// see `massa-pos-exports/controller_traits.rs`
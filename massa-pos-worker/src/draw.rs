@@ -34,7 +34,10 @@ pub(crate) fn perform_draws(
     // get seeded RNG
     let mut rng = Xoshiro256PlusPlus::from_seed(*lookback_seed.to_bytes());
 
-    let (addresses, roll_counts): (Vec<_>, Vec<_>) = lookback_rolls.into_iter().unzip();
+    let (addresses, roll_counts): (Vec<_>, Vec<_>) = lookback_rolls
+        .iter()
+        .map(|(addr, rolls)| (*addr, *rolls))
+        .unzip();
 
     // prepare distribution
     let dist = WeightedAliasIndex::new(roll_counts).map_err(|err| {
@@ -57,6 +60,7 @@ pub(crate) fn perform_draws(
         draws: HashMap::with_capacity(
             (cfg.periods_per_cycle as usize) * (cfg.thread_count as usize),
         ),
+        lookback_rolls,
     };
 
     let mut five_first_slots: Vec<(Slot, Selection)> = Vec::new();
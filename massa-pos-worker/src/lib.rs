@@ -44,7 +44,11 @@ impl DrawCache {
             Some(cd) => {
                 let upper_bound = cd
                     .cycle
-                    .checked_add(self.0.len().try_into().expect("overflow on cycles length"))
+                    .checked_add(
+                        (self.0.len() - 1)
+                            .try_into()
+                            .expect("overflow on cycles length"),
+                    )
                     .expect("overflow on cycle delta");
                 Some(cd.cycle..=upper_bound)
             }
@@ -83,6 +87,8 @@ pub(crate) struct CycleDraws {
     pub cycle: u64,
     /// cache of draws
     pub draws: HashMap<Slot, Selection>,
+    /// look back rolls used for the draws
+    pub lookback_rolls: BTreeMap<Address, u64>,
 }
 
 /// Structure of the shared pointer to the computed draws, or error if the draw system failed.
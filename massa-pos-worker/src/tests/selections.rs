@@ -4,12 +4,15 @@ use massa_models::config::PERIODS_PER_CYCLE;
 use massa_models::config::THREAD_COUNT;
 use massa_models::slot::Slot;
 use massa_pos_exports::PosError;
+use massa_pos_exports::SelectorChannels;
 use massa_pos_exports::SelectorConfig;
 use rand::thread_rng;
 use rand::RngCore;
+use std::collections::{HashMap, VecDeque};
 use std::{collections::BTreeMap, str::FromStr};
+use tokio::sync::broadcast;
 
-use crate::start_selector_worker;
+use crate::{start_selector_worker, CycleDraws, DrawCache};
 
 #[test]
 fn test_standalone_selection() {
@@ -29,7 +32,13 @@ fn test_standalone_selection() {
     let lookback_seed = Hash::compute_from(&seed_bytes);
 
     // start the selector thread, get the controller and manager
-    let (mut manager, controller) = start_selector_worker(cfg).unwrap();
+    let (mut manager, controller) = start_selector_worker(
+        cfg,
+        SelectorChannels {
+            cycle_draws_sender: broadcast::channel(10).0,
+        },
+    )
+    .unwrap();
 
     // feed the information used to compute the draws of a new cycle
     // this is supposed to take the rolls from C-3 and the seed from C-2
@@ -75,10 +84,41 @@ fn test_standalone_selection() {
     });
     assert!(matches!(result, Err(PosError::CycleUnavailable(1))));
 
+    // a range reaching past the last computed cycle is clamped to the available cycles
+    let clamped_selection = controller
+        .get_available_selections_in_range(
+            Slot::new(0, 0)..=Slot::new(PERIODS_PER_CYCLE + 1, 0),
+            None,
+        )
+        .unwrap();
+    assert_eq!(
+        clamped_selection.len() as u64,
+        PERIODS_PER_CYCLE * THREAD_COUNT as u64
+    );
+
     // stop worker
     manager.stop();
 }
 
+#[test]
+fn test_draw_cache_available_cycles_range() {
+    let cycle_draws = |cycle: u64| CycleDraws {
+        cycle,
+        draws: HashMap::new(),
+        lookback_rolls: BTreeMap::new(),
+    };
+
+    let mut cache = DrawCache(VecDeque::new());
+    assert_eq!(cache.get_available_cycles_range(), None);
+
+    cache.0.push_back(cycle_draws(3));
+    assert_eq!(cache.get_available_cycles_range(), Some(3..=3));
+
+    cache.0.push_back(cycle_draws(4));
+    assert_eq!(cache.get_available_cycles_range(), Some(3..=4));
+    assert!(cache.get(5).is_none());
+}
+
 #[test]
 fn test_invalid_roll_distribution() {
     // initialize the selector configuration and the test inputs
@@ -93,7 +133,13 @@ fn test_invalid_roll_distribution() {
     let lookback_seed = Hash::compute_from(&seed_bytes);
 
     // start the selector thread, get the controller and manager
-    let (mut manager, controller) = start_selector_worker(cfg).unwrap();
+    let (mut manager, controller) = start_selector_worker(
+        cfg,
+        SelectorChannels {
+            cycle_draws_sender: broadcast::channel(10).0,
+        },
+    )
+    .unwrap();
 
     // feed lookback_rolls with invalid roll distribution
     // everything is set to 0
@@ -110,3 +156,115 @@ fn test_invalid_roll_distribution() {
     // stop worker
     manager.stop();
 }
+
+#[test]
+fn test_next_selections_and_draw_stats() {
+    // initialize the selector configuration and the test inputs
+    let cfg = SelectorConfig::default();
+    let endorsement_count = cfg.endorsement_count as u64;
+    let address_1 =
+        Address::from_str("AU12Cyu2f7C7isA3ADAhoNuq9ZUFPKP24jmiGj3sh9D1pHoAWKDYY").unwrap();
+    let address_2 =
+        Address::from_str("AU12BTfZ7k1z6PsLEUZeHYNirz6WJ3NdrWto9H4TkVpkV9xE2TJg2").unwrap();
+    let lookback_rolls: BTreeMap<Address, u64> = BTreeMap::from([(address_1, 1), (address_2, 3)]);
+
+    // start the selector thread and subscribe to the cycle draws notifications
+    let (cycle_draws_sender, mut cycle_draws_receiver) = broadcast::channel(10);
+    let (mut manager, controller) =
+        start_selector_worker(cfg, SelectorChannels { cycle_draws_sender }).unwrap();
+
+    // compute the draws of cycles 0 and 1
+    for cycle in 0..2 {
+        controller
+            .feed_cycle(
+                cycle,
+                lookback_rolls.clone(),
+                Hash::compute_from(&cycle.to_be_bytes()),
+            )
+            .unwrap();
+    }
+    controller.wait_for_draws(1).unwrap();
+
+    // each new cycle draws were notified
+    let notification = cycle_draws_receiver.try_recv().unwrap();
+    assert_eq!(notification.cycle, 0);
+    assert_eq!(notification.first_slot, Slot::new(0, 0));
+    let notification = cycle_draws_receiver.try_recv().unwrap();
+    assert_eq!(notification.cycle, 1);
+    assert_eq!(
+        notification.last_slot,
+        Slot::new(2 * PERIODS_PER_CYCLE - 1, THREAD_COUNT - 1)
+    );
+
+    // the range selections are limited to the available cycles
+    let all_selections = controller
+        .get_available_selections_in_range(
+            Slot::new(0, 0)..=Slot::new(10 * PERIODS_PER_CYCLE, 0),
+            None,
+        )
+        .unwrap();
+    assert_eq!(
+        all_selections.len() as u64,
+        2 * PERIODS_PER_CYCLE * THREAD_COUNT as u64
+    );
+
+    // the next selections only involve the requested address
+    let addresses = [address_1].into_iter().collect();
+    let next_selections = controller
+        .get_next_selections(Slot::new(1, 0), &addresses, 10)
+        .unwrap();
+    assert_eq!(next_selections.len(), 10);
+    assert!(next_selections.iter().all(|(slot, selection)| {
+        *slot >= Slot::new(1, 0)
+            && (selection.producer == address_1 || selection.endorsements.contains(&address_1))
+    }));
+
+    // without limit, they go through both cycles
+    let next_selections = controller
+        .get_next_selections(Slot::new(1, 0), &addresses, usize::MAX)
+        .unwrap();
+    assert_eq!(
+        next_selections
+            .keys()
+            .last()
+            .unwrap()
+            .get_cycle(PERIODS_PER_CYCLE),
+        1
+    );
+
+    // the draw stats match the selections and the roll distribution
+    let addresses = [address_1, address_2].into_iter().collect();
+    let stats = controller.get_draw_stats(&addresses).unwrap();
+    assert_eq!(stats.keys().copied().collect::<Vec<_>>(), vec![0, 1]);
+    let cycle_stats = stats.get(&1).unwrap();
+    let stats_1 = cycle_stats.get(&address_1).unwrap();
+    let stats_2 = cycle_stats.get(&address_2).unwrap();
+    assert_eq!((stats_1.rolls, stats_1.total_rolls), (1, 4));
+    assert_eq!(stats_1.block_slots, PERIODS_PER_CYCLE * THREAD_COUNT as u64);
+    assert_eq!(
+        stats_1.endorsement_slots,
+        stats_1.block_slots * endorsement_count
+    );
+    assert_eq!(
+        stats_1.block_draws + stats_2.block_draws,
+        stats_1.block_slots
+    );
+    assert_eq!(
+        stats_1.endorsement_draws + stats_2.endorsement_draws,
+        stats_1.endorsement_slots
+    );
+    assert_eq!(
+        stats_1.expected_block_draws(),
+        stats_1.block_slots as f64 / 4.0
+    );
+    let block_draws_1 = all_selections
+        .iter()
+        .filter(|(slot, selection)| {
+            slot.get_cycle(PERIODS_PER_CYCLE) == 1 && selection.producer == address_1
+        })
+        .count() as u64;
+    assert_eq!(stats_1.block_draws, block_draws_1);
+
+    // stop worker
+    manager.stop();
+}
@@ -7,8 +7,11 @@ use crate::CycleDraws;
 use crate::DrawCache;
 use crate::RwLockCondvar;
 use crate::{Command, DrawCachePtr};
+use massa_models::slot::Slot;
+use massa_pos_exports::CycleDrawsNotification;
 use massa_pos_exports::PosError;
 use massa_pos_exports::PosResult;
+use massa_pos_exports::SelectorChannels;
 use massa_pos_exports::SelectorConfig;
 use massa_pos_exports::SelectorController;
 use massa_pos_exports::SelectorManager;
@@ -19,6 +22,7 @@ use std::sync::mpsc::Receiver;
 use std::sync::Arc;
 use std::thread;
 use std::thread::JoinHandle;
+use tracing::trace;
 
 /// Structure gathering all elements needed by the selector thread
 #[allow(dead_code)]
@@ -29,6 +33,8 @@ pub(crate) struct SelectorThread {
     pub(crate) cache: DrawCachePtr,
     /// Configuration
     pub(crate) cfg: SelectorConfig,
+    /// Channels used to notify new cycle draws
+    pub(crate) channels: SelectorChannels,
 }
 
 impl SelectorThread {
@@ -38,6 +44,7 @@ impl SelectorThread {
         input_mpsc: Receiver<Command>,
         cache: DrawCachePtr,
         cfg: SelectorConfig,
+        channels: SelectorChannels,
     ) -> JoinHandle<PosResult<()>> {
         let thread_builder = thread::Builder::new().name("selector".into());
         thread_builder
@@ -46,6 +53,7 @@ impl SelectorThread {
                     input_mpsc,
                     cache,
                     cfg,
+                    channels,
                 };
                 this.run()
             })
@@ -97,12 +105,42 @@ impl SelectorThread {
             *cache_guard = Err(err.clone());
         }
 
+        // broadcast the availability of the new draws
+        if out_result.is_ok() {
+            self.notify_cycle_draws(cycle);
+        }
+
         // notify all waiters
         cache_cv.notify_all();
 
         out_result
     }
 
+    /// Broadcast that the draws of a cycle are available
+    fn notify_cycle_draws(&self, cycle: u64) {
+        let (Ok(first_slot), Ok(last_slot)) = (
+            Slot::new_first_of_cycle(cycle, self.cfg.periods_per_cycle),
+            Slot::new_last_of_cycle(cycle, self.cfg.periods_per_cycle, self.cfg.thread_count),
+        ) else {
+            return;
+        };
+        if let Err(err) = self
+            .channels
+            .cycle_draws_sender
+            .send(CycleDrawsNotification {
+                cycle,
+                first_slot,
+                last_slot,
+            })
+        {
+            trace!(
+                "error, failed to broadcast the draws of cycle {} due to: {}",
+                cycle,
+                err
+            );
+        }
+    }
+
     /// Thread loop.
     ///
     /// While a `Stop` command isn't sent, pop `input_data` and compute
@@ -131,7 +169,8 @@ impl SelectorThread {
 /// Launches a selector worker thread and returns a pair to interact with it.
 ///
 /// # parameters
-/// * none
+/// * `selector_config`: selector configuration
+/// * `channels`: channels used to notify new cycle draws
 ///
 /// # Returns
 /// A pair `(selector_manager, selector_controller)` where:
@@ -139,6 +178,7 @@ impl SelectorThread {
 /// * `selector_controller`: allows sending requests and notifications to the worker
 pub fn start_selector_worker(
     selector_config: SelectorConfig,
+    channels: SelectorChannels,
 ) -> PosResult<(Box<dyn SelectorManager>, Box<dyn SelectorController>)> {
     let (input_sender, input_receiver) = sync_channel(selector_config.channel_size);
     let cache = Arc::new((
@@ -152,10 +192,11 @@ pub fn start_selector_worker(
         cache: cache.clone(),
         periods_per_cycle: selector_config.periods_per_cycle,
         thread_count: selector_config.thread_count,
+        endorsement_count: selector_config.endorsement_count,
     };
 
     // launch the selector thread
-    let thread_handle = SelectorThread::spawn(input_receiver, cache, selector_config);
+    let thread_handle = SelectorThread::spawn(input_receiver, cache, selector_config, channels);
 
     let manager = SelectorManagerImpl {
         thread_handle: Some(thread_handle),
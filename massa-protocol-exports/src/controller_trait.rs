@@ -7,9 +7,11 @@ use crate::error::ProtocolError;
 use crate::BootstrapPeers;
 
 use crate::PeerId;
+use massa_models::ban::BanInfo;
+use massa_models::connection::ConnectionInfo;
 use massa_models::prehash::{PreHashMap, PreHashSet};
 use massa_models::stats::NetworkStats;
-use massa_models::{block_header::SecuredHeader, block_id::BlockId};
+use massa_models::{block_header::SecuredHeader, block_id::BlockId, slot::Slot};
 use massa_storage::Storage;
 use peernet::peer::PeerConnectionType;
 
@@ -46,6 +48,14 @@ pub trait ProtocolController: Send + Sync {
         remove: PreHashSet<BlockId>,
     ) -> Result<(), ProtocolError>;
 
+    /// Ask peers for the slot draws starting at a slot.
+    /// The draws they reply with are fed to the selector (light nodes only).
+    ///
+    /// # Arguments
+    /// * `from_slot`: first slot to get the draws for
+    /// * `peer_ids`: peers to ask, they have to be trusted as draws can't be verified
+    fn ask_for_draws(&self, from_slot: Slot, peer_ids: Vec<PeerId>) -> Result<(), ProtocolError>;
+
     /// Propagate a batch of operation (from pool).
     /// note: Full `OperationId` is replaced by a `OperationPrefixId` later by the worker.
     ///
@@ -81,6 +91,12 @@ pub trait ProtocolController: Send + Sync {
     /// Unban a list of Peer Id
     fn unban_peers(&self, peer_ids: Vec<PeerId>) -> Result<(), ProtocolError>;
 
+    /// Get the currently banned peers along with the cause and expiry of their ban
+    fn get_bans(&self) -> Result<HashMap<PeerId, BanInfo>, ProtocolError>;
+
+    /// Get the details of every active peer connection
+    fn get_peers(&self) -> Result<Vec<ConnectionInfo>, ProtocolError>;
+
     /// Returns a boxed clone of self.
     /// Useful to allow cloning `Box<dyn ProtocolController>`.
     fn clone_box(&self) -> Box<dyn ProtocolController>;
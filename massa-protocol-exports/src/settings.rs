@@ -7,7 +7,7 @@ use std::{
 };
 
 use massa_models::version::Version;
-use massa_time::MassaTime;
+use massa_time::{MassaTime, SharedClock};
 use peernet::transports::TransportType;
 use serde::Deserialize;
 
@@ -138,6 +138,10 @@ pub struct ProtocolConfig {
     pub max_denunciations_in_block_header: u32,
     /// Maximum number of endorsements that can be propagated in one message
     pub max_endorsements_per_message: u64,
+    /// Maximum number of slot draws sent in one message
+    pub max_draws_per_message: u32,
+    /// Only retrieve the headers of the wished blocks, and send them to consensus (light mode)
+    pub headers_only: bool,
     /// Maximum number of peers per announcement
     pub max_size_peers_announcement: u64,
     /// Maximum number of listeners per peer
@@ -148,8 +152,10 @@ pub struct ProtocolConfig {
     pub try_connection_timer: MassaTime,
     /// try connection timer same peer
     pub try_connection_timer_same_peer: MassaTime,
-    /// periodically unban every peer
-    pub unban_everyone_timer: MassaTime,
+    /// duration of a first ban, doubled at each new offense of the same peer
+    pub ban_duration: MassaTime,
+    /// maximum duration of a ban
+    pub max_ban_duration: MassaTime,
     /// Max in connections
     pub max_in_connections: usize,
     /// Timeout connection
@@ -174,4 +180,29 @@ pub struct ProtocolConfig {
     pub test_oldest_peer_cooldown: MassaTime,
     /// Rate limit to apply on the data stream
     pub rate_limit: u64,
+    /// Per peer rate limit (bytes per second) on inbound block messages, 0 to disable
+    pub rate_limit_blocks: u64,
+    /// Per peer rate limit (bytes per second) on inbound endorsement messages, 0 to disable
+    pub rate_limit_endorsements: u64,
+    /// Per peer rate limit (bytes per second) on inbound operation messages, 0 to disable
+    pub rate_limit_operations: u64,
+    /// Number of rate limited messages after which a peer is banned, 0 to never ban
+    pub rate_limit_ban_threshold: u64,
+    /// Ask block operation lists as operation id prefixes to the peers supporting it
+    pub compact_block_relay: bool,
+    /// Minimum version of a peer to ask it for compact block operation lists
+    pub compact_block_relay_min_version: Version,
+    /// Send periodically to the peers supporting it a filter of the recent operations,
+    /// so that they only announce us the operations we miss
+    pub operation_reconciliation: bool,
+    /// Interval between two filters sent to a peer
+    pub operation_reconciliation_interval: MassaTime,
+    /// Minimum version of a peer to send it operation filters
+    pub operation_reconciliation_min_version: Version,
+    /// source of the current time, the system clock outside of tests.
+    /// It dates the peer database, the rate limiters and the propagation expiries;
+    /// the network pacing timers (batches, asks and their timeouts) and the timestamp
+    /// of our own announcement follow the real time.
+    #[serde(skip)]
+    pub clock: SharedClock,
 }
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use crate::{settings::PeerCategoryInfo, ProtocolConfig};
 use massa_models::config::{ENDORSEMENT_COUNT, MAX_MESSAGE_SIZE};
-use massa_time::MassaTime;
+use massa_time::{MassaTime, SharedClock};
 use tempfile::NamedTempFile;
 
 const ONE_DAY_MS: u64 = 24 * 60 * 60 * 1000;
@@ -73,6 +73,8 @@ impl Default for ProtocolConfig {
             max_op_datastore_key_length: u8::MAX,
             max_op_datastore_value_length: 1000000,
             max_endorsements_per_message: 1000,
+            max_draws_per_message: 512,
+            headers_only: false,
             max_size_listeners_per_peer: 100,
             max_size_peers_announcement: 100,
             message_timeout: MassaTime::from_millis(10000),
@@ -81,7 +83,8 @@ impl Default for ProtocolConfig {
             read_write_limit_bytes_per_second: 1024 * 1000,
             timeout_connection: MassaTime::from_millis(1000),
             try_connection_timer: MassaTime::from_millis(5000),
-            unban_everyone_timer: MassaTime::from_millis(ONE_DAY_MS),
+            ban_duration: MassaTime::from_millis(ONE_DAY_MS),
+            max_ban_duration: MassaTime::from_millis(ONE_DAY_MS),
             routable_ip: None,
             max_in_connections: 10,
             debug: true,
@@ -96,6 +99,16 @@ impl Default for ProtocolConfig {
             try_connection_timer_same_peer: MassaTime::from_millis(1000),
             test_oldest_peer_cooldown: MassaTime::from_millis(720000),
             rate_limit: 1024 * 1024 * 2,
+            rate_limit_blocks: 0,
+            rate_limit_endorsements: 0,
+            rate_limit_operations: 0,
+            rate_limit_ban_threshold: 0,
+            compact_block_relay: false,
+            compact_block_relay_min_version: "TEST.23.2".parse().unwrap(),
+            operation_reconciliation: false,
+            operation_reconciliation_interval: MassaTime::from_millis(1000),
+            operation_reconciliation_min_version: "TEST.23.2".parse().unwrap(),
+            clock: SharedClock::default(),
         }
     }
 }
@@ -1,24 +1,35 @@
 use crossbeam::channel::tick;
 use crossbeam::select;
 use ip_rfc::global;
-use massa_channel::{receiver::MassaReceiver, sender::MassaSender};
+use massa_channel::{receiver::MassaReceiver, sender::MassaSender, MassaChannel};
 use massa_consensus_exports::ConsensusController;
 use massa_metrics::MassaMetrics;
+use massa_models::ban::{BanCause, BanOrigin, BanReason};
+use massa_models::connection::{ConnectionInfo, ConnectionTransport, PeerAnnouncementInfo};
+use massa_models::node::NodeId;
 use massa_models::stats::NetworkStats;
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
 use massa_protocol_exports::{PeerCategoryInfo, PeerId, ProtocolConfig, ProtocolError};
 use massa_storage::Storage;
+use massa_time::MassaTime;
 use massa_versioning::versioning::MipStore;
 use parking_lot::RwLock;
 use peernet::peer::PeerConnectionType;
+use peernet::transports::TransportType;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::{collections::HashMap, net::IpAddr};
 use std::{thread::JoinHandle, time::Duration};
 use tracing::{debug, warn};
 
-use crate::handlers::peer_handler::models::ConnectionMetadata;
+use crate::handlers::block_handler::cache::SharedBlockCache;
+use crate::handlers::block_handler::commands_retrieval::BlockHandlerRetrievalCommand;
+use crate::handlers::endorsement_handler::cache::SharedEndorsementCache;
+use crate::handlers::operation_handler::cache::SharedOperationCache;
+use crate::handlers::peer_handler::models::{ConnectionMetadata, PeerManagementCmd};
+use crate::traffic::SharedTrafficAccounting;
+use crate::wrap_network::ActiveConnectionsTrait;
 use crate::{
     handlers::peer_handler::models::{InitialPeers, PeerState, SharedPeerDB},
     ip::to_canonical,
@@ -45,6 +56,9 @@ pub enum ConnectivityCommand {
             HashMap<PeerId, (SocketAddr, PeerConnectionType)>,
         )>,
     },
+    GetPeers {
+        responder: MassaSender<Vec<ConnectionInfo>>,
+    },
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -86,6 +100,7 @@ pub(crate) fn start_connectivity_thread(
     .spawn({
         let sender_endorsements_propagation_ext = protocol_channels.endorsement_handler_propagation.0.clone();
         let sender_blocks_retrieval_ext = protocol_channels.block_handler_retrieval.0.clone();
+        let sender_blocks_retrieval_info = protocol_channels.block_handler_retrieval.0.clone();
         let sender_blocks_propagation_ext = protocol_channels.block_handler_propagation.0.clone();
         let sender_operations_propagation_ext = protocol_channels.operation_handler_propagation.0.clone();
         move || {
@@ -118,6 +133,8 @@ pub(crate) fn start_connectivity_thread(
                 config.max_node_known_blocks_size.try_into().unwrap(),
             )));
 
+            let traffic = messages_handler.traffic.clone();
+
             // Start handlers
             let mut peer_management_handler = PeerManagementHandler::new(
                 initial_peers,
@@ -146,6 +163,7 @@ pub(crate) fn start_connectivity_thread(
                 protocol_channels.operation_handler_propagation.1.clone(),
                 peer_management_handler.sender.command_sender.clone(),
                 massa_metrics.clone(),
+                peer_db.clone(),
             );
             let mut endorsement_handler = EndorsementHandler::new(
                 pool_controller.clone(),
@@ -176,17 +194,17 @@ pub(crate) fn start_connectivity_thread(
                 sender_endorsements_propagation_ext,
                 peer_management_handler.sender.command_sender.clone(),
                 config.clone(),
-                endorsement_cache,
-                operation_cache,
-                block_cache,
+                endorsement_cache.clone(),
+                operation_cache.clone(),
+                block_cache.clone(),
                 storage.clone_without_refs(),
                 mip_store,
                 massa_metrics.clone(),
+                peer_db.clone(),
             );
 
             let tick_metrics = tick(massa_metrics.tick_delay);
             let tick_try_connect = tick(config.try_connection_timer.to_duration());
-            let tick_unban_everyone = tick(config.unban_everyone_timer.to_duration());
 
             //Try to connect to peers
             loop {
@@ -223,12 +241,26 @@ pub(crate) fn start_connectivity_thread(
                                     out_connection_count,
                                     banned_peer_count,
                                     known_peer_count,
+                                    traffic: traffic.get_total_stats(),
                                 };
                                 let peers: HashMap<PeerId, (SocketAddr, PeerConnectionType)> = network_controller.get_active_connections().get_peers_connected().into_iter().map(|(peer_id, peer)| {
                                     (peer_id, (peer.0, peer.1))
                                 }).collect();
                                 responder.try_send((stats, peers)).unwrap_or_else(|_| warn!("Failed to send stats to responder"));
                             }
+                            Ok(ConnectivityCommand::GetPeers { responder }) => {
+                                let wishlist_info = get_wishlist_info(&sender_blocks_retrieval_info);
+                                let peers = get_connections_info(
+                                    network_controller.get_active_connections().as_ref(),
+                                    &peer_db,
+                                    &traffic,
+                                    &block_cache,
+                                    &operation_cache,
+                                    &endorsement_cache,
+                                    wishlist_info,
+                                );
+                                responder.try_send(peers).unwrap_or_else(|_| warn!("Failed to send peers to responder"));
+                            }
                             Err(_) => {
                                 warn!("Channel to connectivity thread is closed. Stopping the protocol");
                                 break;
@@ -242,6 +274,28 @@ pub(crate) fn start_connectivity_thread(
                         massa_metrics.set_active_connections(active_conn.get_nb_in_connections(), active_conn.get_nb_out_connections());
                         let peers_map = active_conn.get_peers_connections_bandwidth();
                         massa_metrics.update_peers_tx_rx(peers_map);
+                        let connected_peers = active_conn.get_peer_ids_connected();
+                        traffic.retain_peers(&connected_peers);
+                        peer_db.write().retain_connected_peers(&connected_peers);
+                        let total = traffic.get_total_stats();
+                        for (message_type, stats) in [
+                            ("block", total.block),
+                            ("endorsement", total.endorsement),
+                            ("operation", total.operation),
+                            ("peer_management", total.peer_management),
+                        ] {
+                            massa_metrics.set_protocol_messages_traffic(message_type, stats.messages_received, stats.bytes_received, stats.messages_rate_limited, stats.messages_sent, stats.bytes_sent);
+                        }
+                        let peers_over_rate_limit = traffic.take_peers_over_threshold(config.rate_limit_ban_threshold);
+                        if !peers_over_rate_limit.is_empty() {
+                            debug!("Banning peers that exceeded the rate limits: {:?}", peers_over_rate_limit);
+                            if let Err(err) = peer_management_handler.sender.command_sender.try_send(PeerManagementCmd::Ban {
+                                peer_ids: peers_over_rate_limit,
+                                cause: BanCause::new(BanReason::RateLimitExceeded, BanOrigin::RateLimiter),
+                            }) {
+                                warn!("Failed to send rate limit ban to peer handler: {}", err);
+                            }
+                        }
                         let peer_db_read = peer_db.read();
                         massa_metrics.set_known_peers(peer_db_read.get_known_peer_count() as usize);
                         massa_metrics.set_banned_peers(peer_db_read.get_banned_peer_count() as usize);
@@ -380,15 +434,6 @@ pub(crate) fn start_connectivity_thread(
                             }
                         }
                     }
-                    recv(tick_unban_everyone) -> _ => {
-                        debug!("Periodic unban of every peer");
-                        let mut peer_db_write = peer_db.write();
-                        for (peer_id, peer_status) in peer_db_write.get_peers().clone() {
-                            if peer_status.state == PeerState::Banned {
-                                peer_db_write.unban_peer(&peer_id);
-                            }
-                        }
-                    }
                 }
             }
         }
@@ -418,3 +463,174 @@ fn try_connect_peer(
     }
     conn_res
 }
+
+fn to_connection_transport(transport: &TransportType) -> ConnectionTransport {
+    match transport {
+        TransportType::Tcp => ConnectionTransport::Tcp,
+        TransportType::Quic => ConnectionTransport::Quic,
+    }
+}
+
+// Ask the block retrieval thread for the size of the wishlist and the number of blocks asked to each peer
+fn get_wishlist_info(
+    sender_blocks_retrieval: &MassaSender<BlockHandlerRetrievalCommand>,
+) -> (usize, HashMap<PeerId, usize>) {
+    let (sender, receiver) = MassaChannel::new("get_wishlist_info".to_string(), Some(1));
+    if sender_blocks_retrieval
+        .try_send(BlockHandlerRetrievalCommand::GetWishlistInfo { responder: sender })
+        .is_err()
+    {
+        warn!("Failed to ask wishlist info to the block retrieval thread");
+        return (0, HashMap::new());
+    }
+    receiver
+        .recv_timeout(Duration::from_secs(1))
+        .unwrap_or_else(|_| {
+            warn!("Failed to receive wishlist info from the block retrieval thread");
+            (0, HashMap::new())
+        })
+}
+
+// Gather the details of every active connection
+fn get_connections_info(
+    active_connections: &dyn ActiveConnectionsTrait,
+    peer_db: &SharedPeerDB,
+    traffic: &SharedTrafficAccounting,
+    block_cache: &SharedBlockCache,
+    operation_cache: &SharedOperationCache,
+    endorsement_cache: &SharedEndorsementCache,
+    (block_wishlist_size, asked_blocks): (usize, HashMap<PeerId, usize>),
+) -> Vec<ConnectionInfo> {
+    let bandwidth = active_connections.get_peers_connections_bandwidth();
+    let peer_db_read = peer_db.read();
+    let block_cache_read = block_cache.read();
+    let operation_cache_read = operation_cache.read();
+    let endorsement_cache_read = endorsement_cache.read();
+    active_connections
+        .get_peers_connected()
+        .into_iter()
+        .map(|(peer_id, (address, connection_type, category))| {
+            let last_announce = peer_db_read
+                .get_peers()
+                .get(&peer_id)
+                .and_then(|info| info.last_announce.as_ref());
+            // recorded by the handshake that established the connection
+            let connection = peer_db_read.get_peer_connection(&peer_id);
+            let (bytes_sent, bytes_received) = bandwidth
+                .get(&peer_id.to_string())
+                .copied()
+                .unwrap_or_default();
+            ConnectionInfo {
+                node_id: NodeId::new(peer_id.get_public_key()),
+                address,
+                is_outgoing: connection_type == PeerConnectionType::OUT,
+                transport: connection
+                    .as_ref()
+                    .map(|connection| to_connection_transport(&connection.transport))
+                    .unwrap_or(ConnectionTransport::Tcp),
+                category,
+                connected_since: connection.map(|connection| connection.connected_since),
+                last_announce: last_announce.map(|announce| PeerAnnouncementInfo {
+                    timestamp: MassaTime::from_millis(announce.timestamp),
+                    listeners: announce
+                        .listeners
+                        .iter()
+                        .map(|(addr, transport)| (*addr, to_connection_transport(transport)))
+                        .collect(),
+                }),
+                bytes_sent,
+                bytes_received,
+                traffic: traffic.get_peer_stats(&peer_id).unwrap_or_default(),
+                known_blocks_count: block_cache_read
+                    .blocks_known_by_peer
+                    .get(&peer_id)
+                    .map(|known| known.len())
+                    .unwrap_or_default(),
+                known_operations_count: operation_cache_read
+                    .ops_known_by_peer
+                    .get(&peer_id)
+                    .map(|known| known.len())
+                    .unwrap_or_default(),
+                known_endorsements_count: endorsement_cache_read
+                    .endorsements_known_by_peer
+                    .get(&peer_id)
+                    .map(|known| known.len())
+                    .unwrap_or_default(),
+                asked_blocks_count: asked_blocks.get(&peer_id).copied().unwrap_or_default(),
+                block_wishlist_size,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::block_handler::cache::BlockCache;
+    use crate::handlers::endorsement_handler::cache::EndorsementCache;
+    use crate::handlers::operation_handler::cache::OperationCache;
+    use crate::handlers::peer_handler::models::PeerDB;
+    use crate::traffic::TrafficAccounting;
+    use crate::wrap_network::MockActiveConnectionsTrait;
+    use crate::wrap_peer_db::PeerDBTrait;
+    use massa_signature::KeyPair;
+    use massa_time::{ManualClock, SharedClock};
+
+    #[test]
+    fn test_connections_info() {
+        let connected_at = MassaTime::from_millis(1_000_000);
+        let clock = Arc::new(ManualClock::new(connected_at));
+        let mut peer_db = PeerDB {
+            clock: SharedClock::new(clock.clone()),
+            ..Default::default()
+        };
+        let peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        let other_peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        let address: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+        let other_address: SocketAddr = "127.0.0.1:8081".parse().unwrap();
+        peer_db.set_peer_connected(&peer_id, TransportType::Quic);
+        clock.advance(MassaTime::from_millis(5_000));
+        let peer_db: SharedPeerDB = Arc::new(RwLock::new(peer_db));
+
+        let mut active_connections = MockActiveConnectionsTrait::new();
+        active_connections
+            .expect_get_peers_connections_bandwidth()
+            .return_const(HashMap::from([(peer_id.to_string(), (10, 20))]));
+        active_connections
+            .expect_get_peers_connected()
+            .return_const(HashMap::from([
+                (peer_id, (address, PeerConnectionType::IN, None)),
+                (
+                    other_peer_id,
+                    (other_address, PeerConnectionType::OUT, None),
+                ),
+            ]));
+
+        let infos = get_connections_info(
+            &active_connections,
+            &peer_db,
+            &TrafficAccounting::new_shared(&ProtocolConfig::default()),
+            &Arc::new(RwLock::new(BlockCache::new(10, 10))),
+            &Arc::new(RwLock::new(OperationCache::new(10, 10))),
+            &Arc::new(RwLock::new(EndorsementCache::new(10, 10))),
+            (0, HashMap::new()),
+        );
+        assert_eq!(infos.len(), 2);
+
+        // the connection is dated and typed by its handshake
+        let info = infos.iter().find(|info| info.address == address).unwrap();
+        assert!(!info.is_outgoing);
+        assert_eq!(info.transport, ConnectionTransport::Quic);
+        assert_eq!(info.connected_since, Some(connected_at));
+        assert_eq!((info.bytes_sent, info.bytes_received), (10, 20));
+
+        // no handshake recorded for the connection
+        let info = infos
+            .iter()
+            .find(|info| info.address == other_address)
+            .unwrap();
+        assert!(info.is_outgoing);
+        assert_eq!(info.transport, ConnectionTransport::Tcp);
+        assert_eq!(info.connected_since, None);
+    }
+}
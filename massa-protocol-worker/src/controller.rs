@@ -2,9 +2,12 @@ use std::{collections::HashMap, net::SocketAddr, time::Duration};
 
 use massa_channel::{sender::MassaSender, MassaChannel};
 use massa_models::{
+    ban::{BanCause, BanInfo, BanOrigin, BanReason},
     block_header::SecuredHeader,
     block_id::BlockId,
+    connection::ConnectionInfo,
     prehash::{PreHashMap, PreHashSet},
+    slot::Slot,
     stats::NetworkStats,
 };
 use massa_protocol_exports::{BootstrapPeers, PeerId, ProtocolController, ProtocolError};
@@ -108,6 +111,18 @@ impl ProtocolController for ProtocolControllerImpl {
             })
     }
 
+    /// ask peers for slot draws
+    fn ask_for_draws(&self, from_slot: Slot, peer_ids: Vec<PeerId>) -> Result<(), ProtocolError> {
+        self.sender_block_retrieval_handler
+            .as_ref()
+            .unwrap()
+            .send(BlockHandlerRetrievalCommand::AskForDraws {
+                from_slot,
+                peer_ids,
+            })
+            .map_err(|_| ProtocolError::ChannelError("ask_for_draws command send error".into()))
+    }
+
     /// Propagate a batch of operation ids (from pool).
     ///
     /// note: Full `OperationId` is replaced by a `OperationPrefixId` later by the worker.
@@ -160,7 +175,10 @@ impl ProtocolController for ProtocolControllerImpl {
         self.sender_peer_management_thread
             .as_ref()
             .unwrap()
-            .try_send(PeerManagementCmd::Ban(peer_ids))
+            .try_send(PeerManagementCmd::Ban {
+                peer_ids,
+                cause: BanCause::new(BanReason::Manual, BanOrigin::Api),
+            })
             .map_err(|_| ProtocolError::ChannelError("ban_peers command send error".into()))
     }
 
@@ -172,6 +190,30 @@ impl ProtocolController for ProtocolControllerImpl {
             .map_err(|_| ProtocolError::ChannelError("unban_peers command send error".into()))
     }
 
+    fn get_bans(&self) -> Result<HashMap<PeerId, BanInfo>, ProtocolError> {
+        let (sender, receiver) = MassaChannel::new("get_bans".to_string(), Some(1));
+        self.sender_peer_management_thread
+            .as_ref()
+            .unwrap()
+            .try_send(PeerManagementCmd::GetBans { responder: sender })
+            .map_err(|_| ProtocolError::ChannelError("get_bans command send error".into()))?;
+        receiver
+            .recv_timeout(Duration::from_secs(10))
+            .map_err(|_| ProtocolError::ChannelError("get_bans command receive error".into()))
+    }
+
+    fn get_peers(&self) -> Result<Vec<ConnectionInfo>, ProtocolError> {
+        let (sender, receiver) = MassaChannel::new("get_peers".to_string(), Some(1));
+        self.sender_connectivity_thread
+            .as_ref()
+            .unwrap()
+            .try_send(ConnectivityCommand::GetPeers { responder: sender })
+            .map_err(|_| ProtocolError::ChannelError("get_peers command send error".into()))?;
+        receiver
+            .recv_timeout(Duration::from_secs(10))
+            .map_err(|_| ProtocolError::ChannelError("get_peers command receive error".into()))
+    }
+
     fn get_bootstrap_peers(&self) -> Result<BootstrapPeers, ProtocolError> {
         let (sender, receiver) = MassaChannel::new("get_bootstrap_peers".to_string(), Some(1));
         self.sender_peer_management_thread
@@ -1,8 +1,13 @@
+use std::collections::HashMap;
+
+use massa_channel::sender::MassaSender;
 use massa_models::{
     block_header::SecuredHeader,
     block_id::BlockId,
     prehash::{PreHashMap, PreHashSet},
+    slot::Slot,
 };
+use massa_protocol_exports::PeerId;
 
 #[derive(Clone)]
 pub enum BlockHandlerRetrievalCommand {
@@ -14,4 +19,15 @@ pub enum BlockHandlerRetrievalCommand {
         /// remove from wish list
         remove: PreHashSet<BlockId>,
     },
+    /// Get the size of the wish list and the number of blocks currently asked to each peer
+    GetWishlistInfo {
+        responder: MassaSender<(usize, HashMap<PeerId, usize>)>,
+    },
+    /// Ask peers for the slot draws starting at a slot
+    AskForDraws {
+        /// first slot to ask the draws for
+        from_slot: Slot,
+        /// peers to ask
+        peer_ids: Vec<PeerId>,
+    },
 }
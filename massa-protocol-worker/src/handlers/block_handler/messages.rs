@@ -1,22 +1,27 @@
 use massa_models::{
+    address::{Address, AddressDeserializer, AddressSerializer},
     block_header::{BlockHeader, BlockHeaderDeserializer, SecuredHeader},
     block_id::{BlockId, BlockIdDeserializer, BlockIdSerializer},
     operation::{
-        OperationId, OperationIdSerializer, OperationIdsDeserializer, OperationsDeserializer,
-        SecureShareOperation,
+        OperationId, OperationIdSerializer, OperationIdsDeserializer, OperationPrefixId,
+        OperationPrefixIdDeserializer, OperationsDeserializer, SecureShareOperation,
     },
     secure_share::{SecureShareDeserializer, SecureShareSerializer},
+    slot::{Slot, SlotDeserializer, SlotSerializer},
 };
+use massa_pos_exports::Selection;
 use massa_serialization::{
-    Deserializer, SerializeError, Serializer, U64VarIntDeserializer, U64VarIntSerializer,
+    Deserializer, SerializeError, Serializer, U32VarIntDeserializer, U64VarIntDeserializer,
+    U64VarIntSerializer,
 };
 use nom::{
     error::{context, ContextError, ParseError},
+    multi::length_count,
     sequence::tuple,
     IResult, Parser,
 };
 use num_enum::{IntoPrimitive, TryFromPrimitive};
-use std::ops::Bound::Included;
+use std::ops::Bound::{Excluded, Included};
 
 /// Request block data
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
@@ -28,6 +33,8 @@ pub enum AskForBlockInfo {
     OperationIds,
     /// Ask for a subset of operations of the block
     Operations(Vec<OperationId>),
+    /// Ask for the ordered list of operation ID prefixes of the block
+    CompactOperationIds,
 }
 
 /// Reply to a block data request
@@ -40,6 +47,9 @@ pub enum BlockInfoReply {
     OperationIds(Vec<OperationId>),
     /// Requested full operations of the block
     Operations(Vec<SecureShareOperation>),
+    /// Ordered list of the operation ID prefixes of the block.
+    /// The full list rebuilt from them is checked against the operation merkle root of the header.
+    CompactOperationIds(Vec<OperationPrefixId>),
     /// Block not found
     NotFound,
 }
@@ -64,6 +74,16 @@ pub enum BlockMessage {
         /// Block info reply.
         block_info: BlockInfoReply,
     },
+    /// Message asking the peer for the slot draws starting at a slot (light nodes).
+    DrawsRequest {
+        /// First slot to ask the draws for.
+        from_slot: Slot,
+    },
+    /// Message replying with consecutive slot draws.
+    DrawsResponse {
+        /// Draws of the slots, in slot order.
+        draws: Vec<(Slot, Selection)>,
+    },
 }
 
 #[derive(IntoPrimitive, Debug, Eq, PartialEq, TryFromPrimitive)]
@@ -72,6 +92,8 @@ pub enum MessageTypeId {
     Header,
     DataRequest,
     DataResponse,
+    DrawsRequest,
+    DrawsResponse,
 }
 
 impl From<&BlockMessage> for MessageTypeId {
@@ -80,6 +102,8 @@ impl From<&BlockMessage> for MessageTypeId {
             BlockMessage::Header(_) => MessageTypeId::Header,
             BlockMessage::DataRequest { .. } => MessageTypeId::DataRequest,
             BlockMessage::DataResponse { .. } => MessageTypeId::DataResponse,
+            BlockMessage::DrawsRequest { .. } => MessageTypeId::DrawsRequest,
+            BlockMessage::DrawsResponse { .. } => MessageTypeId::DrawsResponse,
         }
     }
 }
@@ -91,6 +115,7 @@ pub enum BlockInfoType {
     OperationIds = 1,
     Operations = 2,
     NotFound = 3,
+    CompactOperationIds = 4,
 }
 
 #[derive(Default, Clone)]
@@ -100,6 +125,8 @@ pub struct BlockMessageSerializer {
     length_serializer: U64VarIntSerializer,
     block_id_serializer: BlockIdSerializer,
     operation_id_serializer: OperationIdSerializer,
+    slot_serializer: SlotSerializer,
+    address_serializer: AddressSerializer,
 }
 
 impl BlockMessageSerializer {
@@ -110,6 +137,8 @@ impl BlockMessageSerializer {
             length_serializer: U64VarIntSerializer::new(),
             block_id_serializer: BlockIdSerializer::new(),
             operation_id_serializer: OperationIdSerializer::new(),
+            slot_serializer: SlotSerializer::new(),
+            address_serializer: AddressSerializer::new(),
         }
     }
 }
@@ -154,6 +183,10 @@ impl Serializer<BlockMessage> for BlockMessageSerializer {
                                 .serialize(operation_id, buffer)?;
                         }
                     }
+                    AskForBlockInfo::CompactOperationIds => {
+                        self.id_serializer
+                            .serialize(&(BlockInfoType::CompactOperationIds as u64), buffer)?;
+                    }
                 }
             }
             BlockMessage::DataResponse {
@@ -186,12 +219,38 @@ impl Serializer<BlockMessage> for BlockMessageSerializer {
                             self.secure_share_serializer.serialize(operation, buffer)?;
                         }
                     }
+                    BlockInfoReply::CompactOperationIds(operation_prefix_ids) => {
+                        self.id_serializer
+                            .serialize(&(BlockInfoType::CompactOperationIds as u64), buffer)?;
+                        self.length_serializer
+                            .serialize(&(operation_prefix_ids.len() as u64), buffer)?;
+                        for operation_prefix_id in operation_prefix_ids {
+                            buffer.extend(Vec::<u8>::from(operation_prefix_id));
+                        }
+                    }
                     BlockInfoReply::NotFound => {
                         self.id_serializer
                             .serialize(&(BlockInfoType::NotFound as u64), buffer)?;
                     }
                 }
             }
+            BlockMessage::DrawsRequest { from_slot } => {
+                self.slot_serializer.serialize(from_slot, buffer)?;
+            }
+            BlockMessage::DrawsResponse { draws } => {
+                self.length_serializer
+                    .serialize(&(draws.len() as u64), buffer)?;
+                for (slot, selection) in draws {
+                    self.slot_serializer.serialize(slot, buffer)?;
+                    self.address_serializer
+                        .serialize(&selection.producer, buffer)?;
+                    self.length_serializer
+                        .serialize(&(selection.endorsements.len() as u64), buffer)?;
+                    for address in &selection.endorsements {
+                        self.address_serializer.serialize(address, buffer)?;
+                    }
+                }
+            }
         }
         Ok(())
     }
@@ -202,7 +261,13 @@ pub struct BlockMessageDeserializer {
     block_header_deserializer: SecureShareDeserializer<BlockHeader, BlockHeaderDeserializer>,
     block_id_deserializer: BlockIdDeserializer,
     operation_ids_deserializer: OperationIdsDeserializer,
+    operation_prefix_ids_length_deserializer: U32VarIntDeserializer,
+    operation_prefix_id_deserializer: OperationPrefixIdDeserializer,
     operations_deserializer: OperationsDeserializer,
+    slot_deserializer: SlotDeserializer,
+    address_deserializer: AddressDeserializer,
+    draws_length_deserializer: U32VarIntDeserializer,
+    endorsements_length_deserializer: U32VarIntDeserializer,
 }
 
 pub struct BlockMessageDeserializerArgs {
@@ -217,6 +282,7 @@ pub struct BlockMessageDeserializerArgs {
     pub max_op_datastore_value_length: u64,
     pub max_denunciations_in_block_header: u32,
     pub last_start_period: Option<u64>,
+    pub max_draws_per_message: u32,
 }
 
 impl BlockMessageDeserializer {
@@ -233,6 +299,11 @@ impl BlockMessageDeserializer {
             operation_ids_deserializer: OperationIdsDeserializer::new(
                 args.max_operations_per_block,
             ),
+            operation_prefix_ids_length_deserializer: U32VarIntDeserializer::new(
+                Included(0),
+                Included(args.max_operations_per_block),
+            ),
+            operation_prefix_id_deserializer: OperationPrefixIdDeserializer::new(),
             operations_deserializer: OperationsDeserializer::new(
                 args.max_operations_per_block,
                 args.max_datastore_value_length,
@@ -242,6 +313,19 @@ impl BlockMessageDeserializer {
                 args.max_op_datastore_key_length,
                 args.max_op_datastore_value_length,
             ),
+            slot_deserializer: SlotDeserializer::new(
+                (Included(0), Included(u64::MAX)),
+                (Included(0), Excluded(args.thread_count)),
+            ),
+            address_deserializer: AddressDeserializer::new(),
+            draws_length_deserializer: U32VarIntDeserializer::new(
+                Included(0),
+                Included(args.max_draws_per_message),
+            ),
+            endorsements_length_deserializer: U32VarIntDeserializer::new(
+                Included(0),
+                Included(args.endorsement_count),
+            ),
         }
     }
 }
@@ -292,6 +376,9 @@ impl Deserializer<BlockMessage> for BlockMessageDeserializer {
                                     .map(|(rest, operation_ids)| {
                                         (rest, AskForBlockInfo::Operations(operation_ids))
                                     }),
+                                BlockInfoType::CompactOperationIds => {
+                                    Ok((rest, AskForBlockInfo::CompactOperationIds))
+                                }
                                 BlockInfoType::NotFound => {
                                     Err(nom::Err::Error(ParseError::from_error_kind(
                                         buffer,
@@ -340,6 +427,17 @@ impl Deserializer<BlockMessage> for BlockMessageDeserializer {
                                     .map(|(rest, operations)| {
                                         (rest, BlockInfoReply::Operations(operations))
                                     }),
+                                BlockInfoType::CompactOperationIds => length_count(
+                                    context("Failed length deserialization", |input| {
+                                        self.operation_prefix_ids_length_deserializer
+                                            .deserialize(input)
+                                    }),
+                                    context("Failed OperationPrefixId deserialization", |input| {
+                                        self.operation_prefix_id_deserializer.deserialize(input)
+                                    }),
+                                )
+                                .map(BlockInfoReply::CompactOperationIds)
+                                .parse(rest),
                                 BlockInfoType::NotFound => Ok((rest, BlockInfoReply::NotFound)),
                             }
                         }),
@@ -350,6 +448,54 @@ impl Deserializer<BlockMessage> for BlockMessageDeserializer {
                     block_info,
                 })
                 .parse(buffer),
+                MessageTypeId::DrawsRequest => {
+                    context("Failed DrawsRequest deserialization", |input| {
+                        self.slot_deserializer.deserialize(input)
+                    })
+                    .map(|from_slot| BlockMessage::DrawsRequest { from_slot })
+                    .parse(buffer)
+                }
+                MessageTypeId::DrawsResponse => context(
+                    "Failed DrawsResponse deserialization",
+                    length_count(
+                        context("Failed draws length deserialization", |input| {
+                            self.draws_length_deserializer.deserialize(input)
+                        }),
+                        tuple((
+                            context("Failed Slot deserialization", |input| {
+                                self.slot_deserializer.deserialize(input)
+                            }),
+                            context("Failed producer deserialization", |input| {
+                                self.address_deserializer.deserialize(input)
+                            }),
+                            length_count(
+                                context("Failed endorsements length deserialization", |input| {
+                                    self.endorsements_length_deserializer.deserialize(input)
+                                }),
+                                context("Failed endorser deserialization", |input| {
+                                    self.address_deserializer.deserialize(input)
+                                }),
+                            ),
+                        )),
+                    ),
+                )
+                .map(
+                    |draws: Vec<(Slot, Address, Vec<Address>)>| BlockMessage::DrawsResponse {
+                        draws: draws
+                            .into_iter()
+                            .map(|(slot, producer, endorsements)| {
+                                (
+                                    slot,
+                                    Selection {
+                                        endorsements,
+                                        producer,
+                                    },
+                                )
+                            })
+                            .collect(),
+                    },
+                )
+                .parse(buffer),
             }
         })
         .parse(buffer)
@@ -360,7 +506,8 @@ impl Deserializer<BlockMessage> for BlockMessageDeserializer {
 mod tests {
     use std::str::FromStr;
 
-    use massa_models::{block_id::BlockId, operation::OperationId};
+    use massa_models::{block_id::BlockId, operation::OperationId, slot::Slot};
+    use massa_pos_exports::Selection;
     use massa_serialization::{DeserializeError, Deserializer, Serializer};
 
     #[test]
@@ -386,6 +533,7 @@ mod tests {
                 max_op_datastore_value_length: 1,
                 max_denunciations_in_block_header: 1,
                 last_start_period: None,
+                max_draws_per_message: 1,
             });
         let (rest, deserialized_message) = deserializer
             .deserialize::<DeserializeError>(&buffer)
@@ -472,6 +620,7 @@ mod tests {
                 max_op_datastore_value_length: 1,
                 max_denunciations_in_block_header: 1,
                 last_start_period: None,
+                max_draws_per_message: 1,
             });
         deserializer
             .deserialize::<DeserializeError>(&buffer)
@@ -489,6 +638,7 @@ mod tests {
                 max_op_datastore_value_length: 1,
                 max_denunciations_in_block_header: 1,
                 last_start_period: None,
+                max_draws_per_message: 1,
             });
         let (rest, deserialized_message) = deserializer
             .deserialize::<DeserializeError>(&buffer)
@@ -511,4 +661,177 @@ mod tests {
             _ => panic!("Wrong message type"),
         }
     }
+
+    #[test]
+    fn test_compact_operation_ids_message() {
+        let op_id =
+            OperationId::from_str("O1yrsTtyyhDJtPD7jZHkodstNCjUSsfGbVZ5xdG6bVZWABeze8y").unwrap();
+        let block_id =
+            BlockId::from_str("B12DvrcQkzF1Wi8BVoNfc4n93CD3E2qhCNe7nVhnEQGWHZ24fEmg").unwrap();
+        let serializer = super::BlockMessageSerializer::new();
+        let deserializer =
+            super::BlockMessageDeserializer::new(super::BlockMessageDeserializerArgs {
+                thread_count: 1,
+                endorsement_count: 1,
+                max_operations_per_block: 2,
+                max_datastore_value_length: 1,
+                max_function_name_length: 1,
+                max_parameters_size: 1,
+                max_op_datastore_entry_count: 1,
+                max_op_datastore_key_length: 1,
+                max_op_datastore_value_length: 1,
+                max_denunciations_in_block_header: 1,
+                last_start_period: None,
+                max_draws_per_message: 1,
+            });
+
+        let request = super::BlockMessage::DataRequest {
+            block_id,
+            block_info: super::AskForBlockInfo::CompactOperationIds,
+        };
+        let mut buffer = Vec::new();
+        serializer.serialize(&request, &mut buffer).unwrap();
+        let (rest, deserialized_request) = deserializer
+            .deserialize::<DeserializeError>(&buffer)
+            .unwrap();
+        assert!(rest.is_empty());
+        match deserialized_request {
+            super::BlockMessage::DataRequest {
+                block_id: deserialized_block_id,
+                block_info,
+            } => {
+                assert_eq!(deserialized_block_id, block_id);
+                assert_eq!(block_info, super::AskForBlockInfo::CompactOperationIds);
+            }
+            _ => panic!("Wrong message type"),
+        }
+
+        // duplicates are kept and the order is preserved
+        let prefixes = vec![op_id.prefix(), op_id.prefix()];
+        let response = super::BlockMessage::DataResponse {
+            block_id,
+            block_info: super::BlockInfoReply::CompactOperationIds(prefixes.clone()),
+        };
+        let mut buffer = Vec::new();
+        serializer.serialize(&response, &mut buffer).unwrap();
+        let (rest, deserialized_response) = deserializer
+            .deserialize::<DeserializeError>(&buffer)
+            .unwrap();
+        assert!(rest.is_empty());
+        match deserialized_response {
+            super::BlockMessage::DataResponse {
+                block_info: super::BlockInfoReply::CompactOperationIds(deserialized_prefixes),
+                ..
+            } => assert_eq!(deserialized_prefixes, prefixes),
+            _ => panic!("Wrong message type"),
+        }
+
+        // the number of prefixes is limited like the number of operations of a block
+        let response = super::BlockMessage::DataResponse {
+            block_id,
+            block_info: super::BlockInfoReply::CompactOperationIds(vec![op_id.prefix(); 3]),
+        };
+        let mut buffer = Vec::new();
+        serializer.serialize(&response, &mut buffer).unwrap();
+        deserializer
+            .deserialize::<DeserializeError>(&buffer)
+            .expect_err("Should raise error because there is three prefixes and only 2 allowed");
+    }
+
+    #[test]
+    fn test_draws_messages() {
+        let address = || {
+            massa_models::address::Address::from_public_key(
+                &massa_signature::KeyPair::generate(0)
+                    .unwrap()
+                    .get_public_key(),
+            )
+        };
+        let serializer = super::BlockMessageSerializer::new();
+        let deserializer =
+            super::BlockMessageDeserializer::new(super::BlockMessageDeserializerArgs {
+                thread_count: 2,
+                endorsement_count: 2,
+                max_operations_per_block: 1,
+                max_datastore_value_length: 1,
+                max_function_name_length: 1,
+                max_parameters_size: 1,
+                max_op_datastore_entry_count: 1,
+                max_op_datastore_key_length: 1,
+                max_op_datastore_value_length: 1,
+                max_denunciations_in_block_header: 1,
+                last_start_period: None,
+                max_draws_per_message: 2,
+            });
+
+        let request = super::BlockMessage::DrawsRequest {
+            from_slot: Slot::new(3, 1),
+        };
+        let mut buffer = Vec::new();
+        serializer.serialize(&request, &mut buffer).unwrap();
+        let (rest, deserialized_request) = deserializer
+            .deserialize::<DeserializeError>(&buffer)
+            .unwrap();
+        assert!(rest.is_empty());
+        match deserialized_request {
+            super::BlockMessage::DrawsRequest { from_slot } => {
+                assert_eq!(from_slot, Slot::new(3, 1))
+            }
+            _ => panic!("Wrong message type"),
+        }
+
+        let draws = vec![
+            (
+                Slot::new(3, 1),
+                Selection {
+                    endorsements: vec![address(), address()],
+                    producer: address(),
+                },
+            ),
+            (
+                Slot::new(4, 0),
+                Selection {
+                    endorsements: vec![address(), address()],
+                    producer: address(),
+                },
+            ),
+        ];
+        let response = super::BlockMessage::DrawsResponse {
+            draws: draws.clone(),
+        };
+        let mut buffer = Vec::new();
+        serializer.serialize(&response, &mut buffer).unwrap();
+        let (rest, deserialized_response) = deserializer
+            .deserialize::<DeserializeError>(&buffer)
+            .unwrap();
+        assert!(rest.is_empty());
+        match deserialized_response {
+            super::BlockMessage::DrawsResponse {
+                draws: deserialized_draws,
+            } => assert_eq!(deserialized_draws, draws),
+            _ => panic!("Wrong message type"),
+        }
+
+        // the number of draws is limited
+        let mut too_many_draws = draws.clone();
+        too_many_draws.push(draws[0].clone());
+        let response = super::BlockMessage::DrawsResponse {
+            draws: too_many_draws,
+        };
+        let mut buffer = Vec::new();
+        serializer.serialize(&response, &mut buffer).unwrap();
+        deserializer
+            .deserialize::<DeserializeError>(&buffer)
+            .expect_err("Should raise error because there is three draws and only 2 allowed");
+
+        // slots outside of the threads are rejected
+        let request = super::BlockMessage::DrawsRequest {
+            from_slot: Slot::new(3, 2),
+        };
+        let mut buffer = Vec::new();
+        serializer.serialize(&request, &mut buffer).unwrap();
+        deserializer
+            .deserialize::<DeserializeError>(&buffer)
+            .expect_err("Should raise error because the thread does not exist");
+    }
 }
@@ -38,7 +38,7 @@ use super::{
     operation_handler::{
         cache::SharedOperationCache, commands_propagation::OperationHandlerPropagationCommand,
     },
-    peer_handler::models::{PeerManagementCmd, PeerMessageTuple},
+    peer_handler::models::{PeerManagementCmd, PeerMessageTuple, SharedPeerDB},
 };
 
 pub struct BlockHandler {
@@ -69,6 +69,7 @@ impl BlockHandler {
         storage: Storage,
         mip_store: MipStore,
         massa_metrics: MassaMetrics,
+        peer_db: SharedPeerDB,
     ) -> Self {
         let block_retrieval_thread = start_retrieval_thread(
             active_connections.clone(),
@@ -88,6 +89,7 @@ impl BlockHandler {
             storage.clone_without_refs(),
             mip_store,
             massa_metrics,
+            peer_db,
         );
         let block_propagation_thread = start_propagation_thread(
             active_connections,
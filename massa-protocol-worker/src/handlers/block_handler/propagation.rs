@@ -22,6 +22,7 @@ use crate::{
 };
 use crossbeam::channel::RecvTimeoutError;
 use massa_channel::{receiver::MassaReceiver, sender::MassaSender};
+use massa_models::ban::{BanCause, BanEvidence, BanOrigin, BanReason};
 use massa_models::block_header::SecuredHeader;
 use massa_models::block_id::BlockId;
 use massa_protocol_exports::PeerId;
@@ -122,7 +123,7 @@ impl PropagationThread {
                                     }
                                 })
                                 .collect();
-                            self.ban_peers(&peers_to_ban);
+                            self.ban_peers(&peers_to_ban, BanEvidence::Block(block_id));
                         }
                         BlockHandlerPropagationCommand::Stop => {
                             info!("Stop block propagation thread");
@@ -202,11 +203,15 @@ impl PropagationThread {
         }
     }
 
-    /// try to ban a list of peers
-    fn ban_peers(&mut self, peer_ids: &[PeerId]) {
+    /// try to ban a list of peers that know about an attack block
+    fn ban_peers(&mut self, peer_ids: &[PeerId], evidence: BanEvidence) {
         if let Err(err) = self
             .peer_cmd_sender
-            .try_send(PeerManagementCmd::Ban(peer_ids.to_vec()))
+            .try_send(PeerManagementCmd::Ban {
+                peer_ids: peer_ids.to_vec(),
+                cause: BanCause::new(BanReason::AttackBlock, BanOrigin::BlockPropagation)
+                    .with_evidence(evidence),
+            })
             .map_err(|err| ProtocolError::SendError(err.to_string()))
         {
             warn!("could not send Ban command to peer manager: {}", err);
@@ -1,5 +1,5 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     thread::JoinHandle,
     time::Instant,
 };
@@ -14,7 +14,7 @@ use crate::{
         operation_handler::{
             cache::SharedOperationCache, commands_propagation::OperationHandlerPropagationCommand,
         },
-        peer_handler::models::{PeerManagementCmd, PeerMessageTuple},
+        peer_handler::models::{PeerManagementCmd, PeerMessageTuple, SharedPeerDB},
     },
     messages::{Message, MessagesSerializer},
     wrap_network::ActiveConnectionsTrait,
@@ -28,19 +28,22 @@ use massa_consensus_exports::ConsensusController;
 use massa_logging::massa_trace;
 use massa_metrics::MassaMetrics;
 use massa_models::{
+    ban::{BanCause, BanEvidence, BanOrigin, BanReason},
     block::{Block, BlockSerializer},
     block_header::SecuredHeader,
     block_id::BlockId,
     endorsement::EndorsementId,
     operation::{
-        compute_operations_hash, OperationId, OperationIdSerializer, SecureShareOperation,
+        compute_operations_hash, OperationId, OperationIdSerializer, OperationPrefixId,
+        SecureShareOperation,
     },
     prehash::{PreHashMap, PreHashSet},
     secure_share::SecureShare,
+    slot::Slot,
     timeslots::get_block_slot_timestamp,
 };
 use massa_pool_exports::PoolController;
-use massa_pos_exports::SelectorController;
+use massa_pos_exports::{Selection, SelectorController};
 use massa_protocol_exports::PeerId;
 use massa_protocol_exports::{ProtocolConfig, ProtocolError};
 use massa_serialization::{DeserializeError, Deserializer, Serializer};
@@ -73,6 +76,9 @@ pub(crate) struct BlockInfo {
     /// Operations and endorsements contained in the block,
     /// if we've received them already, and none otherwise.
     pub(crate) storage: Storage,
+    /// True if the operation list could not be rebuilt from a compact list,
+    /// the full list of operation ids is then asked instead.
+    pub(crate) compact_failed: bool,
 }
 
 impl BlockInfo {
@@ -81,6 +87,7 @@ impl BlockInfo {
             header,
             operation_ids: None,
             storage,
+            compact_failed: false,
         }
     }
 }
@@ -96,6 +103,8 @@ pub struct RetrievalThread {
     block_message_serializer: MessagesSerializer,
     block_wishlist: PreHashMap<BlockId, BlockInfo>,
     asked_blocks: HashMap<PeerId, PreHashMap<BlockId, Instant>>,
+    /// peers we asked slot draws to, and the first slot asked
+    asked_draws: HashMap<PeerId, Slot>,
     peer_cmd_sender: MassaSender<PeerManagementCmd>,
     sender_propagation_ops: MassaSender<OperationHandlerPropagationCommand>,
     sender_propagation_endorsements: MassaSender<EndorsementHandlerPropagationCommand>,
@@ -108,6 +117,7 @@ pub struct RetrievalThread {
     mip_store: MipStore,
     massa_metrics: MassaMetrics,
     operation_id_serializer: OperationIdSerializer,
+    peer_db: SharedPeerDB,
 }
 
 impl RetrievalThread {
@@ -125,6 +135,7 @@ impl RetrievalThread {
                 max_op_datastore_value_length: self.config.max_op_datastore_value_length,
                 max_denunciations_in_block_header: self.config.max_denunciations_in_block_header,
                 last_start_period: Some(self.config.last_start_period),
+                max_draws_per_message: self.config.max_draws_per_message,
             });
 
         let tick_update_metrics = tick(self.massa_metrics.tick_delay);
@@ -158,6 +169,12 @@ impl RetrievalThread {
                                     self.on_block_header_received(peer_id, header);
                                     self.update_block_retrieval();
                                 }
+                                BlockMessage::DrawsRequest { from_slot } => {
+                                    self.on_ask_for_draws_received(peer_id, from_slot);
+                                }
+                                BlockMessage::DrawsResponse { draws } => {
+                                    self.on_draws_received(peer_id, draws);
+                                }
                             }
                         },
                         Err(_) => {
@@ -190,6 +207,15 @@ impl RetrievalThread {
                                     // update block asking process
                                     self.update_block_retrieval();
                                 },
+                                BlockHandlerRetrievalCommand::GetWishlistInfo { responder } => {
+                                    let asked_blocks = self.asked_blocks.iter().map(|(peer_id, asked)| (*peer_id, asked.len())).collect();
+                                    if let Err(err) = responder.try_send((self.block_wishlist.len(), asked_blocks)) {
+                                        warn!("error while sending wishlist info: {:?}", err);
+                                    }
+                                },
+                                BlockHandlerRetrievalCommand::AskForDraws { from_slot, peer_ids } => {
+                                    self.ask_for_draws(from_slot, peer_ids);
+                                },
                                 BlockHandlerRetrievalCommand::Stop => {
                                     info!("Stop block retrieval thread from command receiver (Stop)");
                                     return;
@@ -237,7 +263,8 @@ impl RetrievalThread {
 
     /// A remote node asked the local node for block data
     ///
-    /// We send the block's operation ids if the foreign node asked for `AskForBlockInfo::Info`
+    /// We send the block's operation ids if the foreign node asked for `AskForBlockInfo::Info`,
+    /// their prefixes if it asked for `AskForBlockInfo::CompactOperationIds`
     /// or a subset of the full operations of the block if it asked for `AskForBlockInfo::Operations`.
     fn on_ask_for_block_info_received(
         &mut self,
@@ -296,6 +323,17 @@ impl RetrievalThread {
 
                 BlockInfoReply::OperationIds(block_op_ids)
             }
+            (Some((_, block_op_ids)), AskForBlockInfo::CompactOperationIds) => {
+                // the peer asked for the operation ID prefixes of the block
+
+                // once sent, the peer will know about those operations,
+                // no need to announce their IDs to that peer anymore
+                operation_knowledge_updates.extend(block_op_ids.iter().cloned());
+
+                BlockInfoReply::CompactOperationIds(
+                    block_op_ids.iter().map(|op_id| op_id.prefix()).collect(),
+                )
+            }
             (Some((_, block_op_ids)), AskForBlockInfo::Operations(mut asked_ops)) => {
                 // the peer asked for a list of full operations from the block
 
@@ -401,6 +439,15 @@ impl RetrievalThread {
                 // the block_header.
                 self.on_block_operation_list_received(from_peer_id, block_id, operation_list);
             }
+            BlockInfoReply::CompactOperationIds(operation_prefix_list) => {
+                // Rebuild the operation list from the operations we know and handle it
+                // like a full list, or ask for the full list if it can't be rebuilt.
+                self.on_block_compact_operation_list_received(
+                    from_peer_id,
+                    block_id,
+                    operation_prefix_list,
+                );
+            }
             BlockInfoReply::Operations(operations) => {
                 // Send operations to pool,
                 // before performing the below checks,
@@ -430,7 +477,11 @@ impl RetrievalThread {
                     "peer {} sent us critically incorrect header: {}",
                     &from_peer_id, err
                 );
-                if let Err(err) = self.ban_peers(&[from_peer_id]) {
+                if let Err(err) = self.ban_peers(
+                    &[from_peer_id],
+                    BanReason::InvalidHeader,
+                    Some(BanEvidence::Block(block_id)),
+                ) {
                     warn!("Error while banning peer {} err: {:?}", &from_peer_id, err);
                 }
                 return;
@@ -450,7 +501,14 @@ impl RetrievalThread {
                 // This is done so that update_block_retrieval can prioritize asking the rest of the block data
                 // to that same peer that just gave us the header, and not exclude the peer
                 // because we still believe we are actively asking it for stuff.
-                self.remove_asked_blocks(&[block_id].into_iter().collect())
+                self.remove_asked_blocks(&[block_id].into_iter().collect());
+
+                if self.config.headers_only {
+                    // light mode: only the header is wished, it goes to consensus right away
+                    self.block_wishlist.remove(&block_id);
+                    self.consensus_controller
+                        .register_block_header(block_id, header);
+                }
             }
         } else if is_new {
             // if not in wishlist, and if the header is new, we send it to consensus
@@ -459,6 +517,84 @@ impl RetrievalThread {
         }
     }
 
+    /// A remote node asked the local node for the slot draws starting at `from_slot`
+    fn on_ask_for_draws_received(&mut self, from_peer_id: PeerId, from_slot: Slot) {
+        debug!(
+            "peer {} asked for draws from slot {}",
+            from_peer_id, from_slot
+        );
+        // the range covers at most `max_draws_per_message` slots
+        let thread_count = self.config.thread_count as u64;
+        let Some(to_index) = from_slot
+            .period
+            .checked_mul(thread_count)
+            .and_then(|index| index.checked_add(from_slot.thread as u64))
+            .and_then(|index| {
+                index.checked_add(self.config.max_draws_per_message.saturating_sub(1) as u64)
+            })
+        else {
+            debug!("peer {} asked for draws out of range", from_peer_id);
+            return;
+        };
+        let to_slot = Slot::new(to_index / thread_count, (to_index % thread_count) as u8);
+        let draws = match self
+            .selector_controller
+            .get_available_selections_in_range(from_slot..=to_slot, None)
+        {
+            Ok(draws) => draws.into_iter().collect(),
+            Err(err) => {
+                debug!("could not get the draws asked by {}: {}", from_peer_id, err);
+                return;
+            }
+        };
+        if let Err(err) = self.active_connections.send_to_peer(
+            &from_peer_id,
+            &self.block_message_serializer,
+            BlockMessage::DrawsResponse { draws }.into(),
+            false,
+        ) {
+            warn!("Error while sending draws to {}: {:?}", from_peer_id, err);
+        }
+    }
+
+    /// Ask peers for the slot draws starting at `from_slot`
+    fn ask_for_draws(&mut self, from_slot: Slot, peer_ids: Vec<PeerId>) {
+        for peer_id in peer_ids {
+            debug!("asking draws from slot {} to {}", from_slot, peer_id);
+            if let Err(err) = self.active_connections.send_to_peer(
+                &peer_id,
+                &self.block_message_serializer,
+                BlockMessage::DrawsRequest { from_slot }.into(),
+                false,
+            ) {
+                warn!("Error while asking draws to {}: {:?}", peer_id, err);
+                continue;
+            }
+            self.asked_draws.insert(peer_id, from_slot);
+        }
+    }
+
+    /// A peer sent us slot draws.
+    ///
+    /// Draws can't be verified without the rolls, so they are only accepted
+    /// from the peers we asked them to.
+    fn on_draws_received(&mut self, from_peer_id: PeerId, draws: Vec<(Slot, Selection)>) {
+        let Some(from_slot) = self.asked_draws.remove(&from_peer_id) else {
+            debug!("ignoring draws that were not asked to {}", from_peer_id);
+            return;
+        };
+        let draws: BTreeMap<Slot, Selection> = draws
+            .into_iter()
+            .filter(|(slot, _)| *slot >= from_slot)
+            .collect();
+        if draws.is_empty() {
+            return;
+        }
+        if let Err(err) = self.selector_controller.feed_selections(draws) {
+            warn!("could not feed the draws sent by {}: {}", from_peer_id, err);
+        }
+    }
+
     /// Check if the incoming header network version is compatible with the current node
     fn check_network_version_compatibility(
         &self,
@@ -654,9 +790,21 @@ impl RetrievalThread {
     }
 
     /// send a ban peer command to the peer handler
-    fn ban_peers(&mut self, peer_ids: &[PeerId]) -> Result<(), ProtocolError> {
+    fn ban_peers(
+        &mut self,
+        peer_ids: &[PeerId],
+        reason: BanReason,
+        evidence: Option<BanEvidence>,
+    ) -> Result<(), ProtocolError> {
         self.peer_cmd_sender
-            .try_send(PeerManagementCmd::Ban(peer_ids.to_vec()))
+            .try_send(PeerManagementCmd::Ban {
+                peer_ids: peer_ids.to_vec(),
+                cause: BanCause {
+                    reason,
+                    origin: BanOrigin::BlockRetrieval,
+                    evidence,
+                },
+            })
             .map_err(|err| ProtocolError::SendError(err.to_string()))
     }
 
@@ -691,7 +839,11 @@ impl RetrievalThread {
             }
         }
         if !peers_to_ban.is_empty() {
-            if let Err(err) = self.ban_peers(&peers_to_ban) {
+            if let Err(err) = self.ban_peers(
+                &peers_to_ban,
+                BanReason::InvalidBlock,
+                Some(BanEvidence::Block(*block_id)),
+            ) {
                 warn!(
                     "Error while banning peers {:?} err: {:?}",
                     peers_to_ban, err
@@ -762,7 +914,11 @@ impl RetrievalThread {
             != computed_operations_hash
         {
             warn!("Peer id {} sent us a operation list for block id {} but the hash in the header doesn't match.", from_peer_id, block_id);
-            if let Err(err) = self.ban_peers(&[from_peer_id]) {
+            if let Err(err) = self.ban_peers(
+                &[from_peer_id],
+                BanReason::OperationListHashMismatch,
+                Some(BanEvidence::Block(block_id)),
+            ) {
                 warn!("Error while banning peer {} err: {:?}", from_peer_id, err);
             }
             return;
@@ -780,6 +936,88 @@ impl RetrievalThread {
         self.remove_asked_blocks(&[block_id].into_iter().collect());
     }
 
+    /// We received a compact list of operations for a block.
+    ///
+    /// Each prefix is resolved with the operations of our storage, which contains the operations
+    /// of the pool and of the recent blocks. If a prefix is unknown or matches several operations,
+    /// or if the rebuilt list doesn't match the operations hash of the header,
+    /// the full list of operation IDs will be asked instead.
+    /// The sender is not banned for that as a collision is not a proof of misbehavior.
+    ///
+    /// # Parameters:
+    /// - `from_peer_id`: Node which sent us the information.
+    /// - `BlockId`: ID of the related operations we received.
+    /// - `operation_prefix_ids`: prefixes of the IDs of the operations contained by the block, ordered.
+    fn on_block_compact_operation_list_received(
+        &mut self,
+        from_peer_id: PeerId,
+        block_id: BlockId,
+        operation_prefix_ids: Vec<OperationPrefixId>,
+    ) {
+        debug!(
+            "received compact operation list for block {} from {}",
+            block_id, &from_peer_id
+        );
+
+        // mark the sender node as knowing those ops
+        self.operation_cache
+            .write()
+            .insert_peer_known_ops(&from_peer_id, &operation_prefix_ids);
+
+        let operations_hash = match self.block_wishlist.get(&block_id).and_then(|info| {
+            info.header
+                .as_ref()
+                .filter(|_| info.operation_ids.is_none())
+        }) {
+            Some(header) => header.content.operation_merkle_root,
+            None => {
+                // we were not actively looking for that data, but mark the remote node as knowing the block
+                debug!("peer {} sent us a compact list of operation IDs for block id {} but we were not looking for it", from_peer_id, block_id);
+                self.cache
+                    .write()
+                    .insert_peer_known_block(&from_peer_id, &[block_id], true);
+                return;
+            }
+        };
+
+        let operation_ids =
+            rebuild_operation_ids(&self.storage, &operation_prefix_ids).filter(|operation_ids| {
+                compute_operations_hash(operation_ids, &self.operation_id_serializer)
+                    == operations_hash
+            });
+        match operation_ids {
+            Some(operation_ids) => {
+                self.on_block_operation_list_received(from_peer_id, block_id, operation_ids)
+            }
+            None => {
+                debug!(
+                    "could not rebuild the operation list of block {} from the compact list of {}, asking the full list",
+                    block_id, from_peer_id
+                );
+                self.massa_metrics.inc_protocol_compact_block_fallbacks();
+                if let Some(info) = self.block_wishlist.get_mut(&block_id) {
+                    info.compact_failed = true;
+                }
+                // the sender knows the block, it can be asked for the full list
+                self.cache
+                    .write()
+                    .insert_peer_known_block(&from_peer_id, &[block_id], true);
+                self.remove_asked_blocks(&[block_id].into_iter().collect());
+            }
+        }
+    }
+
+    /// Returns true if the operation lists can be asked to the peer as compact lists
+    fn peer_supports_compact_relay(&self, peer_id: &PeerId) -> bool {
+        self.config.compact_block_relay
+            && self
+                .peer_db
+                .read()
+                .get_peer_version(peer_id)
+                .map(|version| version.is_at_least(&self.config.compact_block_relay_min_version))
+                .unwrap_or(false)
+    }
+
     /// Return the sum of all operation's serialized sizes in the id list
     fn get_total_operations_size(storage: &Storage, operation_ids: &[OperationId]) -> usize {
         let op_read_lock = storage.read_operations();
@@ -887,7 +1125,11 @@ impl RetrievalThread {
                 "Peer id {} sent us operations for block id {} but they failed validity checks: {}",
                 from_peer_id, block_id, err
             );
-            if let Err(err) = self.ban_peers(&[from_peer_id]) {
+            if let Err(err) = self.ban_peers(
+                &[from_peer_id],
+                BanReason::InvalidBlockOperations,
+                Some(BanEvidence::Block(block_id)),
+            ) {
                 warn!("Error while banning peer {} err: {:?}", from_peer_id, err);
             }
             return;
@@ -1057,6 +1299,7 @@ impl RetrievalThread {
                 .block_wishlist
                 .get_mut(&block_id)
                 .expect("block presence in wishlist should have been checked above");
+            let compact_allowed = !wishlist_info.compact_failed;
             let request = match (
                 wishlist_info.header.is_some(),
                 wishlist_info.operation_ids.is_some(),
@@ -1078,6 +1321,15 @@ impl RetrievalThread {
 
             // try to ask peers from best to worst
             for (_, _, _, _, peer_id) in peer_scores {
+                // the operation list is asked in its compact form to the peers supporting it
+                let request = match &request {
+                    AskForBlockInfo::OperationIds
+                        if compact_allowed && self.peer_supports_compact_relay(&peer_id) =>
+                    {
+                        AskForBlockInfo::CompactOperationIds
+                    }
+                    _ => request.clone(),
+                };
                 debug!(
                     "Sending ask for block {} data to {}: {:?}",
                     block_id, peer_id, &request
@@ -1087,7 +1339,7 @@ impl RetrievalThread {
                     &self.block_message_serializer,
                     Message::Block(Box::new(BlockMessage::DataRequest {
                         block_id,
-                        block_info: request.clone(),
+                        block_info: request,
                     })),
                     true,
                 ) {
@@ -1251,6 +1503,25 @@ impl RetrievalThread {
     }
 }
 
+/// Rebuild an ordered list of operation IDs from their prefixes, using the operations of `storage`.
+/// Returns None if a prefix is unknown or matches several operations.
+fn rebuild_operation_ids(
+    storage: &Storage,
+    operation_prefix_ids: &[OperationPrefixId],
+) -> Option<Vec<OperationId>> {
+    let operations = storage.read_operations();
+    operation_prefix_ids
+        .iter()
+        .map(|prefix| {
+            let mut matching = operations.get_operations_by_prefix(prefix)?.iter();
+            match (matching.next(), matching.next()) {
+                (Some(operation_id), None) => Some(*operation_id),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn start_retrieval_thread(
     active_connections: Box<dyn ActiveConnectionsTrait>,
@@ -1270,6 +1541,7 @@ pub fn start_retrieval_thread(
     storage: Storage,
     mip_store: MipStore,
     massa_metrics: MassaMetrics,
+    peer_db: SharedPeerDB,
 ) -> JoinHandle<()> {
     let block_message_serializer =
         MessagesSerializer::new().with_block_message_serializer(BlockMessageSerializer::new());
@@ -1284,6 +1556,7 @@ pub fn start_retrieval_thread(
                 next_timer_ask_block: Instant::now() + config.ask_block_timeout.to_duration(),
                 block_wishlist: PreHashMap::default(),
                 asked_blocks: HashMap::default(),
+                asked_draws: HashMap::default(),
                 peer_cmd_sender,
                 sender_propagation_ops,
                 sender_propagation_endorsements,
@@ -1299,6 +1572,7 @@ pub fn start_retrieval_thread(
                 mip_store,
                 massa_metrics,
                 operation_id_serializer: OperationIdSerializer::new(),
+                peer_db,
             };
             retrieval_thread.run();
         })
@@ -5,6 +5,7 @@ use massa_channel::{receiver::MassaReceiver, sender::MassaSender};
 use massa_logging::massa_trace;
 use massa_metrics::MassaMetrics;
 use massa_models::{
+    ban::{BanCause, BanEvidence, BanOrigin, BanReason},
     endorsement::SecureShareEndorsement,
     prehash::{CapacityAllocator, PreHashMap, PreHashSet},
     timeslots::get_block_slot_timestamp,
@@ -15,15 +16,15 @@ use massa_protocol_exports::PeerId;
 use massa_protocol_exports::{ProtocolConfig, ProtocolError};
 use massa_serialization::{DeserializeError, Deserializer};
 use massa_storage::Storage;
-use massa_time::MassaTime;
 use tracing::{debug, info, warn};
 
 use crate::{
     handlers::{
         endorsement_handler::messages::EndorsementMessage,
         peer_handler::models::{PeerManagementCmd, PeerMessageTuple},
+        InvalidContent,
     },
-    sig_verifier::verify_sigs_batch,
+    sig_verifier::{find_invalid_sig, verify_sigs_batch},
 };
 
 use super::{
@@ -134,7 +135,7 @@ impl RetrievalThread {
                         loss of sync between us and the remote node. Err = {}",
                         peer_id, err
                     );
-                    if let Err(err) = self.ban_peer(&peer_id) {
+                    if let Err(err) = self.ban_peer(&peer_id, err.evidence) {
                         warn!("Error while banning peer {} err: {:?}", peer_id, err);
                     }
                 }
@@ -143,10 +144,22 @@ impl RetrievalThread {
     }
 
     /// send a ban peer command to the peer handler
-    fn ban_peer(&mut self, peer_id: &PeerId) -> Result<(), ProtocolError> {
+    fn ban_peer(
+        &mut self,
+        peer_id: &PeerId,
+        evidence: Option<BanEvidence>,
+    ) -> Result<(), ProtocolError> {
         massa_trace!("ban node from retrieval thread", { "peer_id": peer_id.to_string() });
+        let mut cause = BanCause::new(
+            BanReason::InvalidEndorsements,
+            BanOrigin::EndorsementRetrieval,
+        );
+        cause.evidence = evidence;
         self.peer_cmd_sender
-            .try_send(PeerManagementCmd::Ban(vec![*peer_id]))
+            .try_send(PeerManagementCmd::Ban {
+                peer_ids: vec![*peer_id],
+                cause,
+            })
             .map_err(|err| ProtocolError::SendError(err.to_string()))
     }
 }
@@ -170,7 +183,7 @@ pub(crate) fn note_endorsements_from_peer(
     config: &ProtocolConfig,
     endorsement_propagation_sender: &MassaSender<EndorsementHandlerPropagationCommand>,
     pool_controller: &mut dyn PoolController,
-) -> Result<(), ProtocolError> {
+) -> Result<(), InvalidContent> {
     let mut new_endorsements = PreHashMap::with_capacity(endorsements.len());
     let mut all_endorsement_ids = PreHashSet::with_capacity(endorsements.len());
 
@@ -193,35 +206,46 @@ pub(crate) fn note_endorsements_from_peer(
     }
 
     // Batch signature verification
-    verify_sigs_batch(
-        &new_endorsements
-            .values()
-            .map(|endorsement| {
-                (
-                    endorsement.compute_signed_hash(),
-                    endorsement.signature,
-                    endorsement.content_creator_pub_key,
-                )
-            })
-            .collect::<Vec<_>>(),
-    )?;
+    let signatures = new_endorsements
+        .values()
+        .map(|endorsement| {
+            (
+                endorsement.compute_signed_hash(),
+                endorsement.signature,
+                endorsement.content_creator_pub_key,
+            )
+        })
+        .collect::<Vec<_>>();
+    verify_sigs_batch(&signatures).map_err(|error| InvalidContent {
+        error,
+        evidence: find_invalid_sig(&signatures)
+            .and_then(|index| new_endorsements.keys().nth(index))
+            .map(|endorsement_id| BanEvidence::Endorsement(*endorsement_id)),
+    })?;
 
     // Check PoS draws
     for endorsement in new_endorsements.values() {
         let selection = selector_controller
-            .get_selection(endorsement.content.slot)?
+            .get_selection(endorsement.content.slot)
+            .map_err(ProtocolError::from)?
             .endorsements;
         let Some(address) = selection.get(endorsement.content.index as usize) else {
-            return Err(ProtocolError::GeneralProtocolError(format!(
-                "No selection on slot {} for index {}",
-                endorsement.content.slot, endorsement.content.index
-            )));
+            return Err(InvalidContent {
+                error: ProtocolError::GeneralProtocolError(format!(
+                    "No selection on slot {} for index {}",
+                    endorsement.content.slot, endorsement.content.index
+                )),
+                evidence: Some(BanEvidence::Endorsement(endorsement.id)),
+            });
         };
         if address != &endorsement.content_creator_address {
-            return Err(ProtocolError::GeneralProtocolError(format!(
-                "Invalid endorsement producer selection: expected address {}, got {}",
-                address, endorsement.content_creator_address
-            )));
+            return Err(InvalidContent {
+                error: ProtocolError::GeneralProtocolError(format!(
+                    "Invalid endorsement producer selection: expected address {}, got {}",
+                    address, endorsement.content_creator_address
+                )),
+                evidence: Some(BanEvidence::Endorsement(endorsement.id)),
+            });
         }
     }
 
@@ -243,7 +267,7 @@ pub(crate) fn note_endorsements_from_peer(
     // From there we note new endorsements and propagate them
 
     // Filter out endorsements if they are too old (max age of the inclusion slot: `max_endorsements_propagation_time`)
-    let now = MassaTime::now();
+    let now = config.clock.now();
     new_endorsements.retain(|_id, endorsement| {
         match get_block_slot_timestamp(
             config.thread_count,
@@ -1,4 +1,33 @@
+use massa_models::ban::BanEvidence;
+use massa_protocol_exports::ProtocolError;
+
 pub mod block_handler;
 pub mod endorsement_handler;
 pub mod operation_handler;
 pub mod peer_handler;
+
+/// Error raised by the checks of the content sent by a peer,
+/// with the object that failed them when it is known
+#[derive(Debug)]
+pub(crate) struct InvalidContent {
+    pub error: ProtocolError,
+    pub evidence: Option<BanEvidence>,
+}
+
+impl From<ProtocolError> for InvalidContent {
+    fn from(error: ProtocolError) -> Self {
+        InvalidContent {
+            error,
+            evidence: None,
+        }
+    }
+}
+
+impl std::fmt::Display for InvalidContent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.evidence {
+            Some(evidence) => write!(f, "{} ({})", self.error, evidence),
+            None => write!(f, "{}", self.error),
+        }
+    }
+}
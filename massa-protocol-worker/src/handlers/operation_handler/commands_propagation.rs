@@ -1,8 +1,16 @@
+use massa_protocol_exports::PeerId;
 use massa_storage::Storage;
 
+use super::reconciliation::OperationsBloomFilter;
+
 #[derive(Clone)]
 pub enum OperationHandlerPropagationCommand {
     Stop,
     /// operations ids
     PropagateOperations(Storage),
+    /// announce to a peer the operations that are not in its filter
+    ReconcileWithPeer {
+        peer_id: PeerId,
+        filter: OperationsBloomFilter,
+    },
 }
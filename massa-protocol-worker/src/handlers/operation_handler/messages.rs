@@ -12,6 +12,10 @@ use nom::{
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use std::ops::Bound::Included;
 
+use super::reconciliation::{
+    OperationsBloomFilter, OperationsBloomFilterDeserializer, OperationsBloomFilterSerializer,
+};
+
 #[derive(Debug)]
 pub enum OperationMessage {
     /// Batch of operation ids
@@ -20,6 +24,9 @@ pub enum OperationMessage {
     AskForOperations(OperationPrefixIds),
     /// A list of operations
     Operations(Vec<SecureShareOperation>),
+    /// Filter of the operations recently received by the sender,
+    /// asking for the announcement of the ones that are not in it
+    OperationsFilter(OperationsBloomFilter),
 }
 
 #[derive(IntoPrimitive, Debug, Eq, PartialEq, TryFromPrimitive)]
@@ -28,6 +35,7 @@ pub enum MessageTypeId {
     OperationsAnnouncement = 0,
     AskForOperations = 1,
     Operations = 2,
+    OperationsFilter = 3,
 }
 
 impl From<&OperationMessage> for MessageTypeId {
@@ -36,6 +44,7 @@ impl From<&OperationMessage> for MessageTypeId {
             OperationMessage::OperationsAnnouncement(_) => MessageTypeId::OperationsAnnouncement,
             OperationMessage::AskForOperations(_) => MessageTypeId::AskForOperations,
             OperationMessage::Operations(_) => MessageTypeId::Operations,
+            OperationMessage::OperationsFilter(_) => MessageTypeId::OperationsFilter,
         }
     }
 }
@@ -45,6 +54,7 @@ pub struct OperationMessageSerializer {
     id_serializer: U64VarIntSerializer,
     operation_prefix_ids_serializer: OperationPrefixIdsSerializer,
     operations_serializer: OperationsSerializer,
+    operations_filter_serializer: OperationsBloomFilterSerializer,
 }
 
 impl OperationMessageSerializer {
@@ -53,6 +63,7 @@ impl OperationMessageSerializer {
             id_serializer: U64VarIntSerializer::new(),
             operation_prefix_ids_serializer: OperationPrefixIdsSerializer::new(),
             operations_serializer: OperationsSerializer::new(),
+            operations_filter_serializer: OperationsBloomFilterSerializer::new(),
         }
     }
 }
@@ -81,6 +92,10 @@ impl Serializer<OperationMessage> for OperationMessageSerializer {
             OperationMessage::Operations(operations) => {
                 self.operations_serializer.serialize(operations, buffer)?;
             }
+            OperationMessage::OperationsFilter(filter) => {
+                self.operations_filter_serializer
+                    .serialize(filter, buffer)?;
+            }
         }
         Ok(())
     }
@@ -90,6 +105,7 @@ pub struct OperationMessageDeserializer {
     id_deserializer: U64VarIntDeserializer,
     operation_prefix_ids_deserializer: OperationPrefixIdsDeserializer,
     operations_deserializer: OperationsDeserializer,
+    operations_filter_deserializer: OperationsBloomFilterDeserializer,
 }
 
 /// Limits used in the deserialization of `OperationMessage`
@@ -129,6 +145,9 @@ impl OperationMessageDeserializer {
                 args.max_op_datastore_key_length,
                 args.max_op_datastore_value_length,
             ),
+            operations_filter_deserializer: OperationsBloomFilterDeserializer::new(
+                args.max_operations_prefix_ids,
+            ),
         }
     }
 }
@@ -168,6 +187,13 @@ impl Deserializer<OperationMessage> for OperationMessageDeserializer {
                     .map(OperationMessage::Operations)
                     .parse(buffer)
                 }
+                MessageTypeId::OperationsFilter => {
+                    context("Failed OperationsFilter deserialization", |input| {
+                        self.operations_filter_deserializer.deserialize(input)
+                    })
+                    .map(OperationMessage::OperationsFilter)
+                    .parse(buffer)
+                }
             }
         })
         .parse(buffer)
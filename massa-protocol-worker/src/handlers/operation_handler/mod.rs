@@ -19,12 +19,16 @@ pub mod commands_propagation;
 pub mod commands_retrieval;
 mod messages;
 mod propagation;
+mod reconciliation;
 mod retrieval;
 
 pub(crate) use messages::{OperationMessage, OperationMessageSerializer};
 pub(crate) use retrieval::note_operations_from_peer;
 
-use super::peer_handler::models::{PeerManagementCmd, PeerMessageTuple};
+#[cfg(test)]
+pub use reconciliation::OperationsBloomFilter;
+
+use super::peer_handler::models::{PeerManagementCmd, PeerMessageTuple, SharedPeerDB};
 
 pub struct OperationHandler {
     pub operation_retrieval_thread: Option<(
@@ -52,6 +56,7 @@ impl OperationHandler {
         local_receiver: MassaReceiver<OperationHandlerPropagationCommand>,
         peer_cmd_sender: MassaSender<PeerManagementCmd>,
         massa_metrics: MassaMetrics,
+        peer_db: SharedPeerDB,
     ) -> Self {
         let operation_retrieval_thread = start_retrieval_thread(
             receiver_network,
@@ -73,6 +78,7 @@ impl OperationHandler {
             cache,
             storage.clone_without_refs(),
             massa_metrics,
+            peer_db,
         );
         Self {
             operation_retrieval_thread: Some((sender_retrieval_ext, operation_retrieval_thread)),
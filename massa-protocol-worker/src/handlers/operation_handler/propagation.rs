@@ -1,27 +1,31 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::time::Instant;
 use std::{mem, thread::JoinHandle};
 
 use crossbeam::channel::RecvTimeoutError;
 use massa_channel::receiver::MassaReceiver;
 use massa_logging::massa_trace;
 use massa_metrics::MassaMetrics;
-use massa_models::operation::OperationId;
+use massa_models::operation::{OperationId, OperationPrefixId};
 use massa_models::prehash::CapacityAllocator;
 use massa_models::prehash::PreHashSet;
 use massa_protocol_exports::PeerId;
 use massa_protocol_exports::ProtocolConfig;
 use massa_protocol_exports::ProtocolError;
 use massa_storage::Storage;
+use rand::Rng;
+use schnellru::{ByLength, LruMap};
 use tracing::{debug, info, log::warn};
 
 use crate::{
-    handlers::operation_handler::OperationMessage, messages::MessagesSerializer,
+    handlers::{operation_handler::OperationMessage, peer_handler::models::SharedPeerDB},
+    messages::MessagesSerializer,
     wrap_network::ActiveConnectionsTrait,
 };
 
 use super::{
     cache::SharedOperationCache, commands_propagation::OperationHandlerPropagationCommand,
-    OperationMessageSerializer,
+    reconciliation::OperationsBloomFilter, OperationMessageSerializer,
 };
 
 struct PropagationThread {
@@ -35,6 +39,10 @@ struct PropagationThread {
     cache: SharedOperationCache,
     operation_message_serializer: MessagesSerializer,
     _massa_metrics: MassaMetrics,
+    peer_db: SharedPeerDB,
+    /// peers that sent us a filter recently, with the time of their last filter.
+    /// Operations are not announced to them, they get the missing ones in reply to their next filter.
+    reconciliation_peers: HashMap<PeerId, Instant>,
 }
 
 impl PropagationThread {
@@ -42,8 +50,14 @@ impl PropagationThread {
         let mut batch_deadline = std::time::Instant::now()
             .checked_add(self.config.operation_announcement_interval.to_duration())
             .expect("Can't init interval op propagation");
+        let mut reconciliation_deadline = std::time::Instant::now()
+            .checked_add(self.config.operation_reconciliation_interval.to_duration())
+            .expect("Can't init interval op reconciliation");
         loop {
-            match self.internal_receiver.recv_deadline(batch_deadline) {
+            match self
+                .internal_receiver
+                .recv_deadline(batch_deadline.min(reconciliation_deadline))
+            {
                 Ok(internal_message) => {
                     match internal_message {
                         OperationHandlerPropagationCommand::PropagateOperations(operations) => {
@@ -78,6 +92,12 @@ impl PropagationThread {
                                 }
                             }
                         }
+                        OperationHandlerPropagationCommand::ReconcileWithPeer {
+                            peer_id,
+                            filter,
+                        } => {
+                            self.reconcile_with_peer(peer_id, filter);
+                        }
                         OperationHandlerPropagationCommand::Stop => {
                             info!("Stop operation propagation thread");
                             return;
@@ -85,10 +105,21 @@ impl PropagationThread {
                     }
                 }
                 Err(RecvTimeoutError::Timeout) => {
-                    self.announce_ops();
-                    batch_deadline = std::time::Instant::now()
-                        .checked_add(self.config.operation_announcement_interval.to_duration())
-                        .expect("Can't init interval op propagation");
+                    let now = std::time::Instant::now();
+                    if now >= batch_deadline {
+                        self.announce_ops();
+                        batch_deadline = now
+                            .checked_add(self.config.operation_announcement_interval.to_duration())
+                            .expect("Can't init interval op propagation");
+                    }
+                    if now >= reconciliation_deadline {
+                        self.send_filters();
+                        reconciliation_deadline = now
+                            .checked_add(
+                                self.config.operation_reconciliation_interval.to_duration(),
+                            )
+                            .expect("Can't init interval op reconciliation");
+                    }
                 }
                 Err(RecvTimeoutError::Disconnected) => {
                     return;
@@ -151,8 +182,23 @@ impl PropagationThread {
             let peers_connected = self.active_connections.get_peer_ids_connected();
             cache_write.update_cache(&peers_connected);
 
+            // Peers that stopped sending filters get announcements again
+            let reconciliation_timeout = self
+                .config
+                .operation_reconciliation_interval
+                .to_duration()
+                .saturating_mul(3);
+            self.reconciliation_peers.retain(|peer_id, last_filter| {
+                peers_connected.contains(peer_id) && last_filter.elapsed() < reconciliation_timeout
+            });
+
             // Propagate to peers
-            let all_keys: Vec<PeerId> = cache_write.ops_known_by_peer.keys().cloned().collect();
+            let all_keys: Vec<PeerId> = cache_write
+                .ops_known_by_peer
+                .keys()
+                .filter(|peer_id| !self.reconciliation_peers.contains_key(peer_id))
+                .cloned()
+                .collect();
             for peer_id in all_keys {
                 let ops = cache_write.ops_known_by_peer.get_mut(&peer_id).unwrap();
                 let new_ops: Vec<OperationId> = operation_ids
@@ -195,8 +241,127 @@ impl PropagationThread {
             }
         }
     }
+
+    /// Announce to `peer_id` the operations kept for propagation that are not in its filter
+    /// and that it doesn't already know.
+    ///
+    /// The operations of the filter are marked as known by the peer. A false positive is thus
+    /// never announced to this peer by us, but the filters of the other peers use other seeds.
+    fn reconcile_with_peer(&mut self, peer_id: PeerId, filter: OperationsBloomFilter) {
+        if !self
+            .active_connections
+            .get_peer_ids_connected()
+            .contains(&peer_id)
+        {
+            return;
+        }
+        self.reconciliation_peers.insert(peer_id, Instant::now());
+
+        let missing_ops: Vec<OperationPrefixId> = {
+            let mut cache_write = self.cache.write();
+            let max_known_ops_by_peer = cache_write.max_known_ops_by_peer;
+            let known_ops = cache_write
+                .ops_known_by_peer
+                .entry(peer_id)
+                .or_insert_with(|| LruMap::new(ByLength::new(max_known_ops_by_peer)));
+            let mut missing_ops = Vec::new();
+            for prefix in self
+                .stored_for_propagation
+                .iter()
+                .flat_map(|(_, op_ids)| op_ids.iter().map(|op_id| op_id.prefix()))
+            {
+                if known_ops.peek(&prefix).is_some() {
+                    continue;
+                }
+                if !filter.contains(&prefix) {
+                    missing_ops.push(prefix);
+                }
+                known_ops.insert(prefix, ());
+            }
+            missing_ops
+        };
+        if missing_ops.is_empty() {
+            return;
+        }
+        debug!(
+            "Send operations announcement of len {} to {} in reply to its filter",
+            missing_ops.len(),
+            peer_id
+        );
+        for sub_list in missing_ops.chunks(self.config.max_operations_per_message as usize) {
+            if let Err(err) = self.active_connections.send_to_peer(
+                &peer_id,
+                &self.operation_message_serializer,
+                OperationMessage::OperationsAnnouncement(sub_list.iter().copied().collect()).into(),
+                false,
+            ) {
+                warn!(
+                    "Failed to send OperationsAnnouncement message to peer: {}",
+                    err
+                );
+                if let ProtocolError::PeerDisconnected(_) = err {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Send to the peers supporting it a filter of the operations we received recently,
+    /// so that they only announce us the ones we miss.
+    fn send_filters(&mut self) {
+        if !self.config.operation_reconciliation {
+            return;
+        }
+        // the filter covers two intervals so that no operation falls between two rounds
+        let window = self
+            .config
+            .operation_reconciliation_interval
+            .to_duration()
+            .saturating_mul(2);
+        let recent_ops: Vec<OperationPrefixId> = self
+            .stored_for_propagation
+            .iter()
+            .rev()
+            .take_while(|(t, _)| t.elapsed() <= window)
+            .flat_map(|(_, op_ids)| op_ids.iter().map(|op_id| op_id.prefix()))
+            .take(self.config.max_operations_per_message as usize)
+            .collect();
+
+        let peers: Vec<PeerId> = {
+            let peer_db_read = self.peer_db.read();
+            self.active_connections
+                .get_peer_ids_connected()
+                .into_iter()
+                .filter(|peer_id| {
+                    peer_db_read
+                        .get_peer_version(peer_id)
+                        .map(|version| {
+                            version.is_at_least(&self.config.operation_reconciliation_min_version)
+                        })
+                        .unwrap_or(false)
+                })
+                .collect()
+        };
+        let mut rng = rand::thread_rng();
+        for peer_id in peers {
+            // each peer gets a different seed so that the false positives differ
+            let mut filter = OperationsBloomFilter::new(recent_ops.len(), rng.gen());
+            for prefix in &recent_ops {
+                filter.insert(prefix);
+            }
+            if let Err(err) = self.active_connections.send_to_peer(
+                &peer_id,
+                &self.operation_message_serializer,
+                OperationMessage::OperationsFilter(filter).into(),
+                false,
+            ) {
+                warn!("Failed to send OperationsFilter message to peer: {}", err);
+            }
+        }
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn start_propagation_thread(
     internal_receiver: MassaReceiver<OperationHandlerPropagationCommand>,
     active_connections: Box<dyn ActiveConnectionsTrait>,
@@ -204,6 +369,7 @@ pub fn start_propagation_thread(
     cache: SharedOperationCache,
     op_storage: Storage,
     massa_metrics: MassaMetrics,
+    peer_db: SharedPeerDB,
 ) -> JoinHandle<()> {
     std::thread::Builder::new()
         .name("protocol-operation-handler-propagation".to_string())
@@ -225,6 +391,8 @@ pub fn start_propagation_thread(
                 _massa_metrics: massa_metrics,
                 operation_message_serializer: MessagesSerializer::new()
                     .with_operation_message_serializer(OperationMessageSerializer::new()),
+                peer_db,
+                reconciliation_peers: HashMap::new(),
             };
             propagation_thread.run();
         })
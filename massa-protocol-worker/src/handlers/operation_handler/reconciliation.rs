@@ -0,0 +1,231 @@
+//! Bloom filter based reconciliation of the recently propagated operations.
+//!
+//! Instead of receiving an announcement of every new operation from each of its peers,
+//! a node can periodically send them a Bloom filter of the operations it received recently.
+//! Each peer then only announces the operations that are not in the filter.
+//! The seed of the hash functions is random for each filter, so that a false positive
+//! for a peer is not a false positive for the others nor for the next round.
+
+use std::ops::Bound::Included;
+
+use massa_hash::Hash;
+use massa_models::operation::OperationPrefixId;
+use massa_serialization::{
+    Deserializer, SerializeError, Serializer, U32VarIntDeserializer, U32VarIntSerializer,
+    U64VarIntDeserializer, U64VarIntSerializer,
+};
+use nom::{
+    bytes::complete::take,
+    error::{context, ContextError, ParseError},
+    sequence::tuple,
+    IResult, Parser,
+};
+
+/// Number of bits of the filter per inserted operation (about 1% of false positives)
+pub(crate) const BLOOM_FILTER_BITS_PER_ITEM: usize = 10;
+/// Number of hash functions of the filter
+pub(crate) const BLOOM_FILTER_HASH_COUNT: u32 = 7;
+/// Maximum number of hash functions accepted in a received filter
+const BLOOM_FILTER_MAX_HASH_COUNT: u32 = 32;
+
+/// Bloom filter of operation ID prefixes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationsBloomFilter {
+    seed: u64,
+    hash_count: u32,
+    bits: Vec<u8>,
+}
+
+impl OperationsBloomFilter {
+    /// Create an empty filter sized for `item_count` operations
+    pub fn new(item_count: usize, seed: u64) -> Self {
+        let bytes_count = item_count
+            .saturating_mul(BLOOM_FILTER_BITS_PER_ITEM)
+            .div_ceil(8)
+            .max(1);
+        OperationsBloomFilter {
+            seed,
+            hash_count: BLOOM_FILTER_HASH_COUNT,
+            bits: vec![0; bytes_count],
+        }
+    }
+
+    /// Maximum size in bytes of a filter sized for `item_count` operations
+    pub fn max_size_bytes(item_count: u32) -> u32 {
+        (item_count as usize)
+            .saturating_mul(BLOOM_FILTER_BITS_PER_ITEM)
+            .div_ceil(8)
+            .max(1)
+            .try_into()
+            .unwrap_or(u32::MAX)
+    }
+
+    fn bit_indices(&self, prefix: &OperationPrefixId) -> impl Iterator<Item = usize> {
+        // double hashing: the i-th index is h1 + i * h2
+        let hash = Hash::compute_from_tuple(&[
+            &self.seed.to_le_bytes(),
+            Vec::<u8>::from(prefix).as_slice(),
+        ]);
+        let bytes = hash.to_bytes();
+        let h1 = u64::from_le_bytes(bytes[0..8].try_into().expect("hash is 32 bytes long"));
+        let h2 = u64::from_le_bytes(bytes[8..16].try_into().expect("hash is 32 bytes long"));
+        let bits_count = (self.bits.len() as u64).saturating_mul(8);
+        (0..self.hash_count as u64)
+            .map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % bits_count) as usize)
+    }
+
+    /// Add an operation to the filter
+    pub fn insert(&mut self, prefix: &OperationPrefixId) {
+        let indices: Vec<usize> = self.bit_indices(prefix).collect();
+        for index in indices {
+            self.bits[index / 8] |= 1 << (index % 8);
+        }
+    }
+
+    /// Returns true if the operation may be in the filter, false if it is surely not
+    pub fn contains(&self, prefix: &OperationPrefixId) -> bool {
+        self.bit_indices(prefix)
+            .all(|index| self.bits[index / 8] & (1 << (index % 8)) != 0)
+    }
+}
+
+/// Serializer for `OperationsBloomFilter`
+#[derive(Default, Clone)]
+pub struct OperationsBloomFilterSerializer {
+    u64_serializer: U64VarIntSerializer,
+    u32_serializer: U32VarIntSerializer,
+}
+
+impl OperationsBloomFilterSerializer {
+    pub fn new() -> Self {
+        Self {
+            u64_serializer: U64VarIntSerializer::new(),
+            u32_serializer: U32VarIntSerializer::new(),
+        }
+    }
+}
+
+impl Serializer<OperationsBloomFilter> for OperationsBloomFilterSerializer {
+    fn serialize(
+        &self,
+        value: &OperationsBloomFilter,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        self.u64_serializer.serialize(&value.seed, buffer)?;
+        self.u32_serializer.serialize(&value.hash_count, buffer)?;
+        let bytes_count: u32 = value.bits.len().try_into().map_err(|_| {
+            SerializeError::NumberTooBig("could not encode bloom filter length as u32".into())
+        })?;
+        self.u32_serializer.serialize(&bytes_count, buffer)?;
+        buffer.extend(&value.bits);
+        Ok(())
+    }
+}
+
+/// Deserializer for `OperationsBloomFilter`
+pub struct OperationsBloomFilterDeserializer {
+    seed_deserializer: U64VarIntDeserializer,
+    hash_count_deserializer: U32VarIntDeserializer,
+    length_deserializer: U32VarIntDeserializer,
+}
+
+impl OperationsBloomFilterDeserializer {
+    /// Creates a deserializer accepting filters sized for at most `max_items` operations
+    pub fn new(max_items: u32) -> Self {
+        Self {
+            seed_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+            hash_count_deserializer: U32VarIntDeserializer::new(
+                Included(1),
+                Included(BLOOM_FILTER_MAX_HASH_COUNT),
+            ),
+            length_deserializer: U32VarIntDeserializer::new(
+                Included(1),
+                Included(OperationsBloomFilter::max_size_bytes(max_items)),
+            ),
+        }
+    }
+}
+
+impl Deserializer<OperationsBloomFilter> for OperationsBloomFilterDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], OperationsBloomFilter, E> {
+        context(
+            "Failed OperationsBloomFilter deserialization",
+            tuple((
+                context("Failed seed deserialization", |input| {
+                    self.seed_deserializer.deserialize(input)
+                }),
+                context("Failed hash count deserialization", |input| {
+                    self.hash_count_deserializer.deserialize(input)
+                }),
+                context("Failed bits deserialization", |input| {
+                    let (rest, length) = self.length_deserializer.deserialize(input)?;
+                    take(length)(rest)
+                }),
+            )),
+        )
+        .map(
+            |(seed, hash_count, bits): (u64, u32, &[u8])| OperationsBloomFilter {
+                seed,
+                hash_count,
+                bits: bits.to_vec(),
+            },
+        )
+        .parse(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use massa_models::operation::{OperationPrefixId, OPERATION_ID_PREFIX_SIZE_BYTES};
+    use massa_serialization::DeserializeError;
+
+    use super::*;
+
+    fn prefix(index: u32) -> OperationPrefixId {
+        let hash = Hash::compute_from(&index.to_le_bytes());
+        let bytes: [u8; OPERATION_ID_PREFIX_SIZE_BYTES] = hash.to_bytes()
+            [..OPERATION_ID_PREFIX_SIZE_BYTES]
+            .try_into()
+            .unwrap();
+        OperationPrefixId::from(&bytes)
+    }
+
+    #[test]
+    fn test_bloom_filter_membership() {
+        let mut filter = OperationsBloomFilter::new(1000, 42);
+        for index in 0..1000 {
+            filter.insert(&prefix(index));
+        }
+        // no false negatives
+        assert!((0..1000).all(|index| filter.contains(&prefix(index))));
+        // about 1% of false positives
+        let false_positives = (1000..11000)
+            .filter(|index| filter.contains(&prefix(*index)))
+            .count();
+        assert!(false_positives < 300, "{} false positives", false_positives);
+    }
+
+    #[test]
+    fn test_bloom_filter_serialization() {
+        let mut filter = OperationsBloomFilter::new(10, 7);
+        filter.insert(&prefix(1));
+        let mut buffer = Vec::new();
+        OperationsBloomFilterSerializer::new()
+            .serialize(&filter, &mut buffer)
+            .unwrap();
+        let (rest, deserialized) = OperationsBloomFilterDeserializer::new(10)
+            .deserialize::<DeserializeError>(&buffer)
+            .unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(deserialized, filter);
+        assert!(deserialized.contains(&prefix(1)));
+
+        // filters bigger than the limit are refused
+        OperationsBloomFilterDeserializer::new(5)
+            .deserialize::<DeserializeError>(&buffer)
+            .expect_err("filter sized for 10 operations should be refused");
+    }
+}
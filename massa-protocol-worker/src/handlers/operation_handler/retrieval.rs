@@ -5,6 +5,7 @@ use massa_channel::{receiver::MassaReceiver, sender::MassaSender};
 use massa_logging::massa_trace;
 use massa_metrics::MassaMetrics;
 use massa_models::{
+    ban::{BanCause, BanEvidence, BanOrigin, BanReason},
     operation::{OperationPrefixId, OperationPrefixIds, SecureShareOperation},
     prehash::{CapacityAllocator, PreHashMap, PreHashSet},
     secure_share::Id,
@@ -16,13 +17,16 @@ use massa_protocol_exports::PeerId;
 use massa_protocol_exports::{ProtocolConfig, ProtocolError};
 use massa_serialization::{DeserializeError, Deserializer};
 use massa_storage::Storage;
-use massa_time::{MassaTime, TimeError};
+use massa_time::TimeError;
 use schnellru::{ByLength, LruMap};
 
 use crate::{
-    handlers::peer_handler::models::{PeerManagementCmd, PeerMessageTuple},
+    handlers::{
+        peer_handler::models::{PeerManagementCmd, PeerMessageTuple},
+        InvalidContent,
+    },
     messages::MessagesSerializer,
-    sig_verifier::verify_sigs_batch,
+    sig_verifier::{find_invalid_sig, verify_sigs_batch},
     wrap_network::ActiveConnectionsTrait,
 };
 use tracing::{debug, info, warn};
@@ -110,7 +114,7 @@ impl RetrievalThread {
                                     ) {
                                         warn!("peer {} sent us critically incorrect operation, which may be an attack attempt by the remote peer or a loss of sync between us and the remote peer. Err = {}", peer_id, err);
 
-                                        if let Err(e) = self.ban_node(&peer_id) {
+                                        if let Err(e) = self.ban_node(&peer_id, err.evidence) {
                                             warn!("Error when banning node: {}", e);
                                         }
                                     }
@@ -129,6 +133,15 @@ impl RetrievalThread {
                                         warn!("error when processing asked operations received from peer {}: Err = {}", peer_id, err);
                                     }
                                 }
+                                OperationMessage::OperationsFilter(filter) => {
+                                    debug!("Received operation message: OperationsFilter from {}", peer_id);
+                                    // the propagation thread knows the operations to reconcile
+                                    if let Err(err) = self.internal_sender.try_send(
+                                        OperationHandlerPropagationCommand::ReconcileWithPeer { peer_id, filter },
+                                    ) {
+                                        warn!("error when forwarding operations filter received from peer {}: Err = {}", peer_id, err);
+                                    }
+                                }
                             }
                         }
                         Err(_) => {
@@ -351,10 +364,19 @@ impl RetrievalThread {
     }
 
     /// send a ban peer command to the peer handler
-    fn ban_node(&mut self, peer_id: &PeerId) -> Result<(), ProtocolError> {
+    fn ban_node(
+        &mut self,
+        peer_id: &PeerId,
+        evidence: Option<BanEvidence>,
+    ) -> Result<(), ProtocolError> {
         massa_trace!("ban node from retrieval thread", { "peer_id": peer_id.to_string() });
+        let mut cause = BanCause::new(BanReason::InvalidOperations, BanOrigin::OperationRetrieval);
+        cause.evidence = evidence;
         self.peer_cmd_sender
-            .try_send(PeerManagementCmd::Ban(vec![*peer_id]))
+            .try_send(PeerManagementCmd::Ban {
+                peer_ids: vec![*peer_id],
+                cause,
+            })
             .map_err(|err| ProtocolError::SendError(err.to_string()))
     }
 }
@@ -367,9 +389,9 @@ pub(crate) fn note_operations_from_peer(
     source_peer_id: &PeerId,
     ops_propagation_sender: &mut MassaSender<OperationHandlerPropagationCommand>,
     pool_controller: &mut Box<dyn PoolController>,
-) -> Result<(), ProtocolError> {
+) -> Result<(), InvalidContent> {
     massa_trace!("protocol.protocol_worker.note_operations_from_peer", { "peer": source_peer_id, "operations": operations });
-    let now = MassaTime::now();
+    let now = config.clock.now();
 
     let mut new_operations = PreHashMap::with_capacity(operations.len());
     for operation in operations {
@@ -396,12 +418,15 @@ pub(crate) fn note_operations_from_peer(
 
         // quit if op is too big
         if operation.serialized_size() > config.max_serialized_operations_size_per_block {
-            return Err(ProtocolError::InvalidOperationError(format!(
-                "Operation {} exceeds max block size,  maximum authorized {} bytes but found {} bytes",
-                operation.id,
-                operation.serialized_size(),
-                config.max_serialized_operations_size_per_block
-            )));
+            return Err(InvalidContent {
+                error: ProtocolError::InvalidOperationError(format!(
+                    "Operation {} exceeds max block size,  maximum authorized {} bytes but found {} bytes",
+                    operation.id,
+                    operation.serialized_size(),
+                    config.max_serialized_operations_size_per_block
+                )),
+                evidence: Some(BanEvidence::Operation(operation.id)),
+            });
         };
 
         // add to new operations
@@ -418,12 +443,16 @@ pub(crate) fn note_operations_from_peer(
     }
 
     // optimized signature verification
-    verify_sigs_batch(
-        &new_operations
-            .iter()
-            .map(|(op_id, op)| (*op_id.get_hash(), op.signature, op.content_creator_pub_key))
-            .collect::<Vec<_>>(),
-    )?;
+    let signatures = new_operations
+        .iter()
+        .map(|(op_id, op)| (*op_id.get_hash(), op.signature, op.content_creator_pub_key))
+        .collect::<Vec<_>>();
+    verify_sigs_batch(&signatures).map_err(|error| InvalidContent {
+        error,
+        evidence: find_invalid_sig(&signatures)
+            .and_then(|index| new_operations.keys().nth(index))
+            .map(|op_id| BanEvidence::Operation(*op_id)),
+    })?;
 
     {
         // add to checked operations
@@ -114,6 +114,11 @@ impl PeerManagementHandler {
                 loop {
                     select! {
                         recv(ticker) -> _ => {
+                            let expired_bans = peer_db.write().remove_expired_bans(config.clock.now());
+                            for peer_id in expired_bans {
+                                debug!("Ban of peer {} expired", peer_id);
+                            }
+
                             let peers_to_send = peer_db.read().get_rand_peers_to_send(100);
                             if peers_to_send.is_empty() {
                                 continue;
@@ -132,13 +137,18 @@ impl PeerManagementHandler {
                             receiver_cmd.update_metrics();
                             // internal command
                            match cmd {
-                             Ok(PeerManagementCmd::Ban(peer_ids)) => {
+                             Ok(PeerManagementCmd::Ban { peer_ids, cause }) => {
                                 // remove running handshake ?
                                 for peer_id in peer_ids {
                                     active_connections.shutdown_connection(&peer_id);
 
                                     // update peer_db
-                                    peer_db.write().ban_peer(&peer_id);
+                                    peer_db.write().ban_peer(
+                                        &peer_id,
+                                        cause.clone(),
+                                        config.ban_duration,
+                                        config.max_ban_duration,
+                                    );
                                 }
                             },
                              Ok(PeerManagementCmd::Unban(peer_ids)) => {
@@ -159,6 +169,12 @@ impl PeerManagementHandler {
                                     warn!("error sending bootstrap peers: {:?}", err);
                                 }
                              },
+                             Ok(PeerManagementCmd::GetBans { responder }) => {
+                                let bans = peer_db.read().get_bans().clone();
+                                if let Err(err) = responder.try_send(bans) {
+                                    warn!("error sending bans: {:?}", err);
+                                }
+                             },
                              Ok(PeerManagementCmd::Stop) => {
                                 while let Ok(_msg) = test_receiver.try_recv() {
                                     // nothing to do just clean the channel
@@ -295,6 +311,14 @@ impl MassaHandshake {
     }
 }
 
+/// Transport of a connection endpoint
+fn endpoint_transport(endpoint: &Endpoint) -> TransportType {
+    match endpoint {
+        Endpoint::Quic(_) => TransportType::Quic,
+        _ => TransportType::Tcp,
+    }
+}
+
 impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake {
     fn perform_handshake(
         &mut self,
@@ -304,6 +328,7 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
         messages_handler: MessagesHandler,
     ) -> PeerNetResult<PeerId> {
         let addr = *endpoint.get_target_addr();
+        let transport = endpoint_transport(endpoint);
         let mut bytes = vec![];
         self.peer_id_serializer
             .serialize(&context.get_peer_id(), &mut bytes)
@@ -367,6 +392,7 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
             }
         }
 
+        let mut peer_version = None;
         let res = {
             {
                 let mut peer_db_write = self.peer_db.write();
@@ -393,6 +419,7 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
                     Some(format!("Received version incompatible: {}", version)),
                 ));
             }
+            peer_version = Some(version);
             let id = received.first().ok_or(
                 PeerNetError::HandshakeError
                     .error("Massa Handshake", Some("Failed to get id".to_string())),
@@ -519,6 +546,10 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
                             last_announce: Some(announcement.clone()),
                             state: PeerState::Trusted,
                         });
+                    if let Some(version) = peer_version {
+                        peer_db_write.set_peer_version(peer_id, version);
+                    }
+                    peer_db_write.set_peer_connected(peer_id, transport);
                 }
                 Ok((_peer_id, None)) => {
                     peer_db_write
@@ -617,16 +648,27 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::HashMap, ops::Deref, sync::Arc};
+    use std::{
+        collections::{HashMap, HashSet},
+        ops::Deref,
+        sync::Arc,
+    };
 
     use massa_channel::MassaChannel;
     use massa_protocol_exports::ProtocolConfig;
     use massa_serialization::U64VarIntDeserializer;
     use massa_signature::KeyPair;
+    use massa_time::MassaTime;
     use parking_lot::RwLock;
-    use peernet::{peer::InitConnectionHandler, transports::endpoint::Endpoint};
+    use peernet::{
+        peer::InitConnectionHandler,
+        transports::{endpoint::Endpoint, TransportType},
+    };
 
-    use crate::{context::Context, messages::MessagesHandler};
+    use crate::{
+        context::Context, messages::MessagesHandler, traffic::TrafficAccounting,
+        wrap_peer_db::PeerDBTrait,
+    };
 
     use super::models::PeerDB;
 
@@ -637,7 +679,8 @@ mod tests {
         let (sender_operations, _) = MassaChannel::new(String::from("test_operations"), None);
         let (sender_peers, _) = MassaChannel::new(String::from("test_peers"), None);
         let shared_peer_db = Arc::new(RwLock::new(PeerDB::default()));
-        let mut handshake = super::MassaHandshake::new(shared_peer_db, ProtocolConfig::default());
+        let mut handshake =
+            super::MassaHandshake::new(shared_peer_db.clone(), ProtocolConfig::default());
         let our_keypair = KeyPair::generate(0).unwrap();
         let messages_handlers = MessagesHandler {
             id_deserializer: U64VarIntDeserializer::new(
@@ -648,6 +691,7 @@ mod tests {
             sender_endorsements,
             sender_operations,
             sender_peers,
+            traffic: TrafficAccounting::new_shared(&ProtocolConfig::default()),
         };
         let (local_sender, remote_receiver) =
             MassaChannel::new(String::from("Test_transport_local_to_remote"), None);
@@ -687,8 +731,29 @@ mod tests {
             &HashMap::default(),
             messages_handlers,
         );
-        assert!(res.is_ok());
         thread.join().unwrap();
+
+        // the version negotiated during the handshake enables the compact block relay
+        let peer_id = res.unwrap();
+        let config = ProtocolConfig::default();
+        let peer_version = shared_peer_db.read().get_peer_version(&peer_id).unwrap();
+        assert_eq!(peer_version, config.version);
+        assert!(peer_version.is_at_least(&config.compact_block_relay_min_version));
+
+        // the handshake records the connection
+        let connection = shared_peer_db.read().get_peer_connection(&peer_id).unwrap();
+        assert_eq!(connection.transport, TransportType::Tcp);
+        assert!(connection.connected_since <= MassaTime::now());
+
+        // the version and the connection are forgotten once the peer is disconnected
+        shared_peer_db
+            .write()
+            .retain_connected_peers(&HashSet::default());
+        assert!(shared_peer_db.read().get_peer_version(&peer_id).is_none());
+        assert!(shared_peer_db
+            .read()
+            .get_peer_connection(&peer_id)
+            .is_none());
     }
 
     #[test]
@@ -709,6 +774,7 @@ mod tests {
             sender_endorsements,
             sender_operations,
             sender_peers,
+            traffic: TrafficAccounting::new_shared(&ProtocolConfig::default()),
         };
         let (local_sender, _) =
             MassaChannel::new(String::from("Test_transport_local_to_remote"), None);
@@ -754,6 +820,7 @@ mod tests {
             sender_endorsements,
             sender_operations,
             sender_peers,
+            traffic: TrafficAccounting::new_shared(&ProtocolConfig::default()),
         };
         let (local_sender, _) =
             MassaChannel::new(String::from("Test_transport_local_to_remote"), None);
@@ -1,6 +1,8 @@
 use massa_channel::sender::MassaSender;
+use massa_models::ban::{BanCause, BanInfo, BanReason};
+use massa_models::version::Version;
 use massa_protocol_exports::{BootstrapPeers, PeerId};
-use massa_time::MassaTime;
+use massa_time::{MassaTime, SharedClock};
 use parking_lot::RwLock;
 use peernet::transports::TransportType;
 use rand::seq::SliceRandom;
@@ -125,24 +127,24 @@ impl ConnectionMetadata {
             _ => unreachable!("connection metadata data_type not recognized: {data_type}"),
         }
     }
-    pub fn failure(&mut self) {
-        self.last_failure = Some(MassaTime::now());
+    pub fn failure(&mut self, now: MassaTime) {
+        self.last_failure = Some(now);
     }
 
-    pub fn test_failure(&mut self) {
-        self.last_test_failure = Some(MassaTime::now());
+    pub fn test_failure(&mut self, now: MassaTime) {
+        self.last_test_failure = Some(now);
     }
 
-    pub fn test_success(&mut self) {
-        self.last_test_success = Some(MassaTime::now());
+    pub fn test_success(&mut self, now: MassaTime) {
+        self.last_test_success = Some(now);
     }
 
-    pub fn success(&mut self) {
-        self.last_success = Some(MassaTime::now());
+    pub fn success(&mut self, now: MassaTime) {
+        self.last_success = Some(now);
     }
 
-    pub fn try_connect(&mut self) {
-        self.last_try_connect = Some(MassaTime::now());
+    pub fn try_connect(&mut self, now: MassaTime) {
+        self.last_try_connect = Some(now);
     }
 }
 
@@ -155,10 +157,38 @@ pub struct PeerDB {
     pub try_connect_history: HashMap<SocketAddr, ConnectionMetadata>,
     /// peers currently tested
     pub peers_in_test: HashSet<SocketAddr>,
+    /// active bans with their cause and expiry
+    pub bans: HashMap<PeerId, BanInfo>,
+    /// offenses of each peer, used to escalate repeated bans
+    pub ban_offenses: HashMap<PeerId, BanOffenses>,
+    /// version announced by each peer during its last successful handshake
+    pub peer_versions: HashMap<PeerId, Version>,
+    /// connection established by the last successful handshake of each peer
+    pub peer_connections: HashMap<PeerId, PeerConnection>,
+    /// clock dating the bans, tests and connection attempts
+    pub clock: SharedClock,
+}
+
+/// Offenses of a peer, kept after its ban is lifted so that repeated bans escalate
+#[derive(Clone, Debug, Default)]
+pub struct BanOffenses {
+    /// number of times the peer has been banned
+    pub count: u64,
+    /// time at which the offenses are forgotten, `None` while the peer is banned manually
+    pub forgotten_at: Option<MassaTime>,
 }
 
 pub type SharedPeerDB = Arc<RwLock<dyn PeerDBTrait>>;
 
+/// Connection established with a peer by a successful handshake
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PeerConnection {
+    /// time at which the handshake succeeded
+    pub connected_since: MassaTime,
+    /// transport of the connection
+    pub transport: TransportType,
+}
+
 pub type PeerMessageTuple = (PeerId, Vec<u8>);
 
 #[derive(Clone, Debug)]
@@ -178,11 +208,17 @@ pub enum PeerState {
 
 #[derive(Clone)]
 pub enum PeerManagementCmd {
-    Ban(Vec<PeerId>),
+    Ban {
+        peer_ids: Vec<PeerId>,
+        cause: BanCause,
+    },
     Unban(Vec<PeerId>),
     GetBootstrapPeers {
         responder: MassaSender<BootstrapPeers>,
     },
+    GetBans {
+        responder: MassaSender<HashMap<PeerId, BanInfo>>,
+    },
     Stop,
 }
 
@@ -191,17 +227,64 @@ pub struct PeerManagementChannel {
     pub command_sender: MassaSender<PeerManagementCmd>,
 }
 
+/// Compute the duration of a ban given the number of offenses of the peer.
+/// The duration doubles at each offense, up to `max_ban_duration`.
+pub fn compute_ban_duration(
+    offense_count: u64,
+    ban_duration: MassaTime,
+    max_ban_duration: MassaTime,
+) -> MassaTime {
+    let exponent = u32::try_from(offense_count.saturating_sub(1)).unwrap_or(u32::MAX);
+    let factor = 2u64.checked_pow(exponent).unwrap_or(u64::MAX);
+    std::cmp::min(ban_duration.saturating_mul(factor), max_ban_duration)
+}
+
 impl PeerDBTrait for PeerDB {
-    fn ban_peer(&mut self, peer_id: &PeerId) {
+    fn ban_peer(
+        &mut self,
+        peer_id: &PeerId,
+        cause: BanCause,
+        ban_duration: MassaTime,
+        max_ban_duration: MassaTime,
+    ) {
         if let Some(peer) = self.peers.get_mut(peer_id) {
             peer.state = PeerState::Banned;
-            info!("Banned peer: {:?}", peer_id);
+            let offenses = self.ban_offenses.entry(*peer_id).or_default();
+            offenses.count = offenses.count.saturating_add(1);
+            let banned_at = self.clock.now();
+            // manual bans are only lifted manually
+            let expires_at = match cause.reason {
+                BanReason::Manual => None,
+                _ => Some(banned_at.saturating_add(compute_ban_duration(
+                    offenses.count,
+                    ban_duration,
+                    max_ban_duration,
+                ))),
+            };
+            // the offenses are forgotten if the peer behaves for `max_ban_duration` after its ban
+            offenses.forgotten_at =
+                expires_at.map(|expires_at| expires_at.saturating_add(max_ban_duration));
+            info!("Banned peer: {:?}, cause: {}", peer_id, cause);
+            self.bans.insert(
+                *peer_id,
+                BanInfo {
+                    cause,
+                    banned_at,
+                    expires_at,
+                    offense_count: offenses.count,
+                },
+            );
         } else {
             info!("Tried to ban unknown peer: {:?}", peer_id);
         };
     }
 
     fn unban_peer(&mut self, peer_id: &PeerId) {
+        self.bans.remove(peer_id);
+        // lifting a manual ban forgives the peer
+        if let Some(offenses) = self.ban_offenses.get_mut(peer_id) {
+            offenses.forgotten_at.get_or_insert(self.clock.now());
+        }
         if let Some(peer) = self.peers.get_mut(peer_id) {
             // We set the state to HandshakeFailed to force the peer to be tested again
             peer.state = PeerState::HandshakeFailed;
@@ -211,6 +294,34 @@ impl PeerDBTrait for PeerDB {
         };
     }
 
+    fn get_bans(&self) -> &HashMap<PeerId, BanInfo> {
+        &self.bans
+    }
+
+    /// Lift the bans that expired before `now` and return the unbanned peers.
+    /// The offenses of the peers that behaved since their last ban are forgotten.
+    fn remove_expired_bans(&mut self, now: MassaTime) -> Vec<PeerId> {
+        let expired: Vec<PeerId> = self
+            .bans
+            .iter()
+            .filter_map(|(peer_id, ban)| match ban.expires_at {
+                Some(expires_at) if expires_at <= now => Some(*peer_id),
+                _ => None,
+            })
+            .collect();
+        for peer_id in &expired {
+            self.unban_peer(peer_id);
+        }
+        let bans = &self.bans;
+        self.ban_offenses.retain(|peer_id, offenses| {
+            bans.contains_key(peer_id)
+                || offenses
+                    .forgotten_at
+                    .map_or(true, |forgotten_at| forgotten_at > now)
+        });
+        expired
+    }
+
     /// Retrieve the peer with the oldest test date.
     fn get_oldest_peer(
         &self,
@@ -224,7 +335,7 @@ impl PeerDBTrait for PeerDB {
         {
             Some((addr, timestamp)) => {
                 if !in_test.contains(addr) {
-                    if timestamp.estimate_instant().ok()?.elapsed() > cooldown {
+                    if self.clock.now().saturating_sub(*timestamp).to_duration() > cooldown {
                         Some(*addr)
                     } else {
                         None
@@ -244,7 +355,7 @@ impl PeerDBTrait for PeerDB {
         nb_peers: usize,
     ) -> Vec<(PeerId, HashMap<SocketAddr, TransportType>)> {
         //TODO: Add ourself
-        let now = MassaTime::now().as_millis();
+        let now = self.clock.now().as_millis();
 
         let min_time = now - THREE_DAYS_MS;
 
@@ -311,25 +422,29 @@ impl PeerDBTrait for PeerDB {
         self.try_connect_history
             .entry(*addr)
             .or_default()
-            .try_connect();
+            .try_connect(self.clock.now());
     }
 
     fn set_try_connect_failure_or_insert(&mut self, addr: &SocketAddr) {
-        self.try_connect_history.entry(*addr).or_default().failure();
+        let now = self.clock.now();
+        self.try_connect_history
+            .entry(*addr)
+            .or_default()
+            .failure(now);
     }
 
     fn set_try_connect_test_success_or_insert(&mut self, addr: &SocketAddr) {
         self.try_connect_history
             .entry(*addr)
             .or_default()
-            .test_success();
+            .test_success(self.clock.now());
     }
 
     fn set_try_connect_test_failure_or_insert(&mut self, addr: &SocketAddr) {
         self.try_connect_history
             .entry(*addr)
             .or_default()
-            .test_failure();
+            .test_failure(self.clock.now());
     }
 
     fn get_peers_in_test(&self) -> &HashSet<SocketAddr> {
@@ -351,4 +466,33 @@ impl PeerDBTrait for PeerDB {
     fn get_tested_addresses(&self) -> &HashMap<SocketAddr, MassaTime> {
         &self.tested_addresses
     }
+
+    fn set_peer_version(&mut self, peer_id: &PeerId, version: Version) {
+        self.peer_versions.insert(*peer_id, version);
+    }
+
+    fn get_peer_version(&self, peer_id: &PeerId) -> Option<Version> {
+        self.peer_versions.get(peer_id).copied()
+    }
+
+    fn set_peer_connected(&mut self, peer_id: &PeerId, transport: TransportType) {
+        self.peer_connections.insert(
+            *peer_id,
+            PeerConnection {
+                connected_since: self.clock.now(),
+                transport,
+            },
+        );
+    }
+
+    fn get_peer_connection(&self, peer_id: &PeerId) -> Option<PeerConnection> {
+        self.peer_connections.get(peer_id).cloned()
+    }
+
+    fn retain_connected_peers(&mut self, connected: &HashSet<PeerId>) {
+        self.peer_versions
+            .retain(|peer_id, _| connected.contains(peer_id));
+        self.peer_connections
+            .retain(|peer_id, _| connected.contains(peer_id));
+    }
 }
@@ -12,7 +12,6 @@ use massa_metrics::MassaMetrics;
 use massa_models::version::VersionDeserializer;
 use massa_protocol_exports::{PeerConnectionType, PeerId, PeerIdDeserializer, ProtocolConfig};
 use massa_serialization::{DeserializeError, Deserializer};
-use massa_time::MassaTime;
 use peernet::{
     error::{PeerNetError, PeerNetResult},
     messages::MessagesHandler as PeerNetMessagesHandler,
@@ -327,7 +326,7 @@ impl Tester {
                                     true
                                 }).count());
                                 {
-                                    let now = MassaTime::now();
+                                    let now = protocol_config.clock.now();
                                     let db = db.clone();
                                     // receive new listener to test
                                     for (addr, _) in listener.1.iter() {
@@ -421,7 +420,7 @@ impl Tester {
                                 db_write.get_peers_in_test(),
                             ) {
                                 db_write.insert_peer_in_test(&listener);
-                                db_write.insert_tested_address(&listener, MassaTime::now());
+                                db_write.insert_tested_address(&listener, protocol_config.clock.now());
                                 listener
                             } else {
                                 continue;
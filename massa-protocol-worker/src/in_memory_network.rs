@@ -0,0 +1,683 @@
+//! In-memory network standing in for the peernet transports, to run several protocol workers
+//! in the same process.
+//!
+//! Every node registers its message handler in a shared `InMemoryNetwork`.
+//! Sending a message to a connected peer serializes it and queues it for delivery to the
+//! handler of the peer, after the delay of the link, unless the link drops it.
+//! Connections are opened by the owner of the network (or by the connectivity thread through
+//! `try_connect`) without handshake, and can be cut by partitions.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    net::SocketAddr,
+    sync::Arc,
+    time::Duration,
+};
+
+use massa_channel::MassaChannel;
+use massa_consensus_exports::ConsensusController;
+use massa_metrics::MassaMetrics;
+use massa_models::node::NodeId;
+use massa_pool_exports::PoolController;
+use massa_pos_exports::SelectorController;
+use massa_protocol_exports::{PeerId, ProtocolConfig, ProtocolError, ProtocolManager};
+use massa_serialization::U64VarIntDeserializer;
+use massa_signature::KeyPair;
+use massa_storage::Storage;
+use massa_time::{MassaTime, SharedClock};
+use massa_versioning::versioning::MipStore;
+use parking_lot::{Condvar, Mutex, RwLock};
+use peernet::{
+    messages::{MessagesHandler as _, MessagesSerializer as _},
+    peer::PeerConnectionType,
+    transports::TransportType,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::ops::Bound::Included;
+use tracing::{debug, warn};
+
+use crate::{
+    connectivity::start_connectivity_thread,
+    handlers::peer_handler::models::PeerDB,
+    manager::ProtocolManagerImpl,
+    messages::{Message, MessageTypeId, MessagesHandler, MessagesSerializer},
+    traffic::{SharedTrafficAccounting, TrafficAccounting},
+    worker::ProtocolChannels,
+    wrap_network::{ActiveConnectionsTrait, NetworkController},
+};
+
+/// Faults injected on the messages sent over a link
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LinkFault {
+    /// delay before the delivery of each message
+    pub delay: MassaTime,
+    /// probability, between 0 and 1, that a message is dropped
+    pub drop_rate: f64,
+}
+
+/// Counters of the messages sent over the network
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InMemoryNetworkStats {
+    /// messages delivered to their recipient
+    pub delivered: u64,
+    /// messages dropped by a link fault
+    pub dropped: u64,
+    /// messages discarded because the connection was closed before their delivery
+    pub discarded: u64,
+}
+
+/// A node of the network
+struct Node {
+    listener: Option<SocketAddr>,
+    handler: MessagesHandler,
+    bytes_sent: u64,
+    bytes_received: u64,
+}
+
+/// A message waiting for its delivery
+struct PendingMessage {
+    deliver_at: MassaTime,
+    sequence: u64,
+    from: PeerId,
+    to: PeerId,
+    data: Vec<u8>,
+}
+
+impl PartialEq for PendingMessage {
+    fn eq(&self, other: &Self) -> bool {
+        (self.deliver_at, self.sequence) == (other.deliver_at, other.sequence)
+    }
+}
+
+impl Eq for PendingMessage {}
+
+impl PartialOrd for PendingMessage {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PendingMessage {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.deliver_at, self.sequence).cmp(&(other.deliver_at, other.sequence))
+    }
+}
+
+struct NetworkState {
+    nodes: HashMap<PeerId, Node>,
+    /// connections of each node, with their direction from the point of view of the node
+    connections: HashMap<PeerId, HashMap<PeerId, PeerConnectionType>>,
+    /// groups of nodes that can only reach the nodes of their own group
+    partition: Option<Vec<HashSet<PeerId>>>,
+    default_fault: LinkFault,
+    link_faults: HashMap<(PeerId, PeerId), LinkFault>,
+    queue: BinaryHeap<Reverse<PendingMessage>>,
+    sequence: u64,
+    rng: StdRng,
+    stats: InMemoryNetworkStats,
+    stopped: bool,
+}
+
+impl NetworkState {
+    fn can_reach(&self, a: &PeerId, b: &PeerId) -> bool {
+        match &self.partition {
+            Some(groups) => groups
+                .iter()
+                .any(|group| group.contains(a) && group.contains(b)),
+            None => true,
+        }
+    }
+
+    fn is_connected(&self, a: &PeerId, b: &PeerId) -> bool {
+        self.connections
+            .get(a)
+            .map_or(false, |peers| peers.contains_key(b))
+    }
+
+    fn remove_connection(&mut self, a: &PeerId, b: &PeerId) {
+        if let Some(peers) = self.connections.get_mut(a) {
+            peers.remove(b);
+        }
+        if let Some(peers) = self.connections.get_mut(b) {
+            peers.remove(a);
+        }
+    }
+}
+
+struct NetworkInner {
+    state: Mutex<NetworkState>,
+    wakeup: Condvar,
+    /// clock dating the delivery of the delayed messages
+    clock: SharedClock,
+}
+
+/// Network shared by the protocol workers of the same process
+#[derive(Clone)]
+pub struct InMemoryNetwork {
+    inner: Arc<NetworkInner>,
+}
+
+impl InMemoryNetwork {
+    /// Creates a network and starts its delivery thread.
+    /// The seed makes the dropped messages reproducible,
+    /// and the link delays are measured on `clock`.
+    pub fn new(seed: u64, clock: SharedClock) -> Self {
+        let inner = Arc::new(NetworkInner {
+            state: Mutex::new(NetworkState {
+                nodes: HashMap::new(),
+                connections: HashMap::new(),
+                partition: None,
+                default_fault: LinkFault::default(),
+                link_faults: HashMap::new(),
+                queue: BinaryHeap::new(),
+                sequence: 0,
+                rng: StdRng::seed_from_u64(seed),
+                stats: InMemoryNetworkStats::default(),
+                stopped: false,
+            }),
+            wakeup: Condvar::new(),
+            clock,
+        });
+        std::thread::Builder::new()
+            .name("in-memory-network".to_string())
+            .spawn({
+                let inner = inner.clone();
+                move || delivery_loop(inner)
+            })
+            .expect("OS failed to start in-memory network thread");
+        Self { inner }
+    }
+
+    /// Stops the delivery thread, the pending messages being discarded
+    pub fn stop(&self) {
+        self.inner.state.lock().stopped = true;
+        self.inner.wakeup.notify_all();
+    }
+
+    fn register(&self, peer_id: PeerId, handler: MessagesHandler) {
+        let mut state = self.inner.state.lock();
+        state.nodes.insert(
+            peer_id,
+            Node {
+                listener: None,
+                handler,
+                bytes_sent: 0,
+                bytes_received: 0,
+            },
+        );
+        state.connections.entry(peer_id).or_default();
+    }
+
+    /// Removes a node and closes its connections
+    pub fn unregister(&self, peer_id: &PeerId) {
+        let mut state = self.inner.state.lock();
+        state.nodes.remove(peer_id);
+        if let Some(peers) = state.connections.remove(peer_id) {
+            for peer in peers.keys() {
+                if let Some(peer_connections) = state.connections.get_mut(peer) {
+                    peer_connections.remove(peer_id);
+                }
+            }
+        }
+    }
+
+    /// Opens a connection from `from` to `to`
+    pub fn connect(&self, from: &PeerId, to: &PeerId) -> Result<(), ProtocolError> {
+        let mut state = self.inner.state.lock();
+        if from == to || !state.nodes.contains_key(from) || !state.nodes.contains_key(to) {
+            return Err(ProtocolError::GeneralProtocolError(format!(
+                "cannot connect {} to {}",
+                from, to
+            )));
+        }
+        if !state.can_reach(from, to) {
+            return Err(ProtocolError::GeneralProtocolError(format!(
+                "{} and {} are partitioned",
+                from, to
+            )));
+        }
+        if state.is_connected(from, to) {
+            return Ok(());
+        }
+        state
+            .connections
+            .entry(*from)
+            .or_default()
+            .insert(*to, PeerConnectionType::OUT);
+        state
+            .connections
+            .entry(*to)
+            .or_default()
+            .insert(*from, PeerConnectionType::IN);
+        Ok(())
+    }
+
+    /// Closes the connection between two nodes
+    pub fn disconnect(&self, a: &PeerId, b: &PeerId) {
+        self.inner.state.lock().remove_connection(a, b);
+    }
+
+    /// Connects every pair of nodes that can reach each other
+    pub fn connect_all(&self) {
+        let peer_ids: Vec<PeerId> = self.inner.state.lock().nodes.keys().copied().collect();
+        for (index, from) in peer_ids.iter().enumerate() {
+            for to in &peer_ids[index + 1..] {
+                // nodes separated by a partition stay disconnected
+                let _ = self.connect(from, to);
+            }
+        }
+    }
+
+    /// Splits the network into groups of nodes that can only reach their own group,
+    /// closing the connections between groups.
+    /// The nodes absent from every group are isolated.
+    pub fn partition(&self, groups: Vec<Vec<PeerId>>) {
+        let mut state = self.inner.state.lock();
+        state.partition = Some(
+            groups
+                .into_iter()
+                .map(|group| group.into_iter().collect())
+                .collect(),
+        );
+        let cut: Vec<(PeerId, PeerId)> = state
+            .connections
+            .iter()
+            .flat_map(|(a, peers)| peers.keys().map(move |b| (*a, *b)))
+            .filter(|(a, b)| !state.can_reach(a, b))
+            .collect();
+        for (a, b) in cut {
+            state.remove_connection(&a, &b);
+        }
+    }
+
+    /// Removes the partition and reconnects every pair of nodes
+    pub fn heal(&self) {
+        self.inner.state.lock().partition = None;
+        self.connect_all();
+    }
+
+    /// Sets the faults of the links without specific faults
+    pub fn set_default_fault(&self, fault: LinkFault) {
+        self.inner.state.lock().default_fault = fault;
+    }
+
+    /// Sets the faults of the messages sent from `from` to `to`
+    pub fn set_link_fault(&self, from: &PeerId, to: &PeerId, fault: LinkFault) {
+        self.inner
+            .state
+            .lock()
+            .link_faults
+            .insert((*from, *to), fault);
+    }
+
+    /// Removes all the link faults
+    pub fn clear_faults(&self) {
+        let mut state = self.inner.state.lock();
+        state.default_fault = LinkFault::default();
+        state.link_faults.clear();
+    }
+
+    /// Returns the peers connected to a node
+    pub fn get_connected_peers(&self, peer_id: &PeerId) -> HashSet<PeerId> {
+        self.inner
+            .state
+            .lock()
+            .connections
+            .get(peer_id)
+            .map(|peers| peers.keys().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the message counters
+    pub fn get_stats(&self) -> InMemoryNetworkStats {
+        self.inner.state.lock().stats
+    }
+
+    fn send(&self, from: &PeerId, to: &PeerId, data: Vec<u8>) -> Result<(), ProtocolError> {
+        let mut state = self.inner.state.lock();
+        if !state.is_connected(from, to) {
+            return Err(ProtocolError::PeerDisconnected(to.to_string()));
+        }
+        if let Some(node) = state.nodes.get_mut(from) {
+            node.bytes_sent = node.bytes_sent.saturating_add(data.len() as u64);
+        }
+        let fault = state
+            .link_faults
+            .get(&(*from, *to))
+            .copied()
+            .unwrap_or(state.default_fault);
+        if fault.drop_rate > 0.0 && state.rng.gen_bool(fault.drop_rate.min(1.0)) {
+            state.stats.dropped = state.stats.dropped.saturating_add(1);
+            return Ok(());
+        }
+        let sequence = state.sequence;
+        state.sequence = sequence.saturating_add(1);
+        state.queue.push(Reverse(PendingMessage {
+            deliver_at: self.inner.clock.now().saturating_add(fault.delay),
+            sequence,
+            from: *from,
+            to: *to,
+            data,
+        }));
+        drop(state);
+        self.inner.wakeup.notify_all();
+        Ok(())
+    }
+}
+
+/// Delivers the queued messages when their delivery time is reached
+fn delivery_loop(inner: Arc<NetworkInner>) {
+    let mut state = inner.state.lock();
+    loop {
+        if state.stopped {
+            return;
+        }
+        let now = inner.clock.now();
+        let due = matches!(state.queue.peek(), Some(Reverse(msg)) if msg.deliver_at <= now);
+        if !due {
+            // the clock may be moved by hand: poll regularly
+            let wait = match state.queue.peek() {
+                Some(Reverse(msg)) => msg
+                    .deliver_at
+                    .saturating_sub(now)
+                    .to_duration()
+                    .min(Duration::from_millis(10)),
+                None => Duration::from_millis(100),
+            };
+            inner.wakeup.wait_for(&mut state, wait);
+            continue;
+        }
+        let Some(Reverse(msg)) = state.queue.pop() else {
+            continue;
+        };
+        // messages in flight are lost when the connection closes
+        if !state.is_connected(&msg.from, &msg.to) {
+            state.stats.discarded = state.stats.discarded.saturating_add(1);
+            continue;
+        }
+        let Some(node) = state.nodes.get_mut(&msg.to) else {
+            continue;
+        };
+        node.bytes_received = node.bytes_received.saturating_add(msg.data.len() as u64);
+        let handler = node.handler.clone();
+        state.stats.delivered = state.stats.delivered.saturating_add(1);
+        // the handler only forwards to the handler channels, but may block on a full channel
+        drop(state);
+        if let Err(err) = handler.handle(&msg.data, &msg.from) {
+            warn!("in-memory network: could not handle message: {}", err);
+        }
+        state = inner.state.lock();
+    }
+}
+
+/// Network controller of a node of an `InMemoryNetwork`
+struct InMemoryNetworkController {
+    network: InMemoryNetwork,
+    peer_id: PeerId,
+    traffic: SharedTrafficAccounting,
+}
+
+impl NetworkController for InMemoryNetworkController {
+    fn get_active_connections(&self) -> Box<dyn ActiveConnectionsTrait> {
+        Box::new(InMemoryConnections {
+            network: self.network.clone(),
+            peer_id: self.peer_id,
+            traffic: self.traffic.clone(),
+        })
+    }
+
+    fn start_listener(
+        &mut self,
+        _transport_type: TransportType,
+        addr: SocketAddr,
+    ) -> Result<(), ProtocolError> {
+        let mut state = self.network.inner.state.lock();
+        if state.nodes.values().any(|node| node.listener == Some(addr)) {
+            return Err(ProtocolError::ListenerError(format!(
+                "address {} already used",
+                addr
+            )));
+        }
+        if let Some(node) = state.nodes.get_mut(&self.peer_id) {
+            node.listener = Some(addr);
+        }
+        Ok(())
+    }
+
+    fn stop_listener(
+        &mut self,
+        _transport_type: TransportType,
+        addr: SocketAddr,
+    ) -> Result<(), ProtocolError> {
+        let mut state = self.network.inner.state.lock();
+        if let Some(node) = state.nodes.get_mut(&self.peer_id) {
+            if node.listener == Some(addr) {
+                node.listener = None;
+            }
+        }
+        Ok(())
+    }
+
+    fn try_connect(&mut self, addr: SocketAddr, _timeout: Duration) -> Result<(), ProtocolError> {
+        let target = self
+            .network
+            .inner
+            .state
+            .lock()
+            .nodes
+            .iter()
+            .find(|(_, node)| node.listener == Some(addr))
+            .map(|(peer_id, _)| *peer_id)
+            .ok_or_else(|| {
+                ProtocolError::GeneralProtocolError(format!("no node listening on {}", addr))
+            })?;
+        self.network.connect(&self.peer_id, &target)
+    }
+
+    fn get_total_bytes_received(&self) -> u64 {
+        self.network
+            .inner
+            .state
+            .lock()
+            .nodes
+            .get(&self.peer_id)
+            .map_or(0, |node| node.bytes_received)
+    }
+
+    fn get_total_bytes_sent(&self) -> u64 {
+        self.network
+            .inner
+            .state
+            .lock()
+            .nodes
+            .get(&self.peer_id)
+            .map_or(0, |node| node.bytes_sent)
+    }
+}
+
+impl Drop for InMemoryNetworkController {
+    fn drop(&mut self) {
+        self.network.unregister(&self.peer_id);
+    }
+}
+
+/// Connections of a node of an `InMemoryNetwork`
+#[derive(Clone)]
+struct InMemoryConnections {
+    network: InMemoryNetwork,
+    peer_id: PeerId,
+    traffic: SharedTrafficAccounting,
+}
+
+impl ActiveConnectionsTrait for InMemoryConnections {
+    fn send_to_peer(
+        &self,
+        peer_id: &PeerId,
+        message_serializer: &MessagesSerializer,
+        message: Message,
+        _high_priority: bool,
+    ) -> Result<(), ProtocolError> {
+        let mut data = Vec::new();
+        message_serializer
+            .serialize(&message, &mut data)
+            .map_err(|err| ProtocolError::GeneralProtocolError(err.to_string()))?;
+        let (id, size) = (MessageTypeId::from(&message), data.len());
+        self.network.send(&self.peer_id, peer_id, data)?;
+        self.traffic.record_outbound(peer_id, &id, size);
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActiveConnectionsTrait> {
+        Box::new(self.clone())
+    }
+
+    fn get_peer_ids_connected(&self) -> HashSet<PeerId> {
+        self.network.get_connected_peers(&self.peer_id)
+    }
+
+    fn get_peers_connected(
+        &self,
+    ) -> HashMap<PeerId, (SocketAddr, PeerConnectionType, Option<String>)> {
+        let state = self.network.inner.state.lock();
+        let Some(peers) = state.connections.get(&self.peer_id) else {
+            return HashMap::new();
+        };
+        peers
+            .iter()
+            .map(|(peer_id, connection_type)| {
+                let addr = state
+                    .nodes
+                    .get(peer_id)
+                    .and_then(|node| node.listener)
+                    .unwrap_or_else(|| SocketAddr::from(([0, 0, 0, 0], 0)));
+                (*peer_id, (addr, *connection_type, None))
+            })
+            .collect()
+    }
+
+    fn get_peer_ids_out_connection_queue(&self) -> HashSet<SocketAddr> {
+        HashSet::new()
+    }
+
+    fn get_nb_out_connections(&self) -> usize {
+        self.count_connections(PeerConnectionType::OUT)
+    }
+
+    fn get_nb_in_connections(&self) -> usize {
+        self.count_connections(PeerConnectionType::IN)
+    }
+
+    fn shutdown_connection(&mut self, peer_id: &PeerId) {
+        self.network.disconnect(&self.peer_id, peer_id);
+    }
+
+    fn get_peers_connections_bandwidth(&self) -> HashMap<String, (u64, u64)> {
+        HashMap::new()
+    }
+}
+
+impl InMemoryConnections {
+    fn count_connections(&self, connection_type: PeerConnectionType) -> usize {
+        self.network
+            .inner
+            .state
+            .lock()
+            .connections
+            .get(&self.peer_id)
+            .map_or(0, |peers| {
+                peers
+                    .values()
+                    .filter(|peer_type| **peer_type == connection_type)
+                    .count()
+            })
+    }
+}
+
+/// start a new `ProtocolController` connected to an `InMemoryNetwork` instead of peernet
+///
+/// # Arguments
+/// * `config`: protocol settings, its listeners being registered in the in-memory network
+/// * `network`: in-memory network shared by the nodes
+/// * `keypair`: node keypair, from which its peer id is derived
+/// * `protocol_channels`: channels created by `create_protocol_controller`
+#[allow(clippy::too_many_arguments)]
+pub fn start_protocol_controller_in_memory(
+    config: ProtocolConfig,
+    network: &InMemoryNetwork,
+    keypair: &KeyPair,
+    selector_controller: Box<dyn SelectorController>,
+    consensus_controller: Box<dyn ConsensusController>,
+    pool_controller: Box<dyn PoolController>,
+    storage: Storage,
+    protocol_channels: ProtocolChannels,
+    mip_store: MipStore,
+    massa_metrics: MassaMetrics,
+) -> Result<(Box<dyn ProtocolManager>, NodeId), ProtocolError> {
+    debug!("starting protocol controller with in-memory network");
+    let peer_id = PeerId::from_public_key(keypair.get_public_key());
+
+    let (sender_operations, receiver_operations) = MassaChannel::new(
+        "sender_operations".to_string(),
+        Some(config.max_size_channel_network_to_operation_handler),
+    );
+    let (sender_endorsements, receiver_endorsements) = MassaChannel::new(
+        "sender_endorsements".to_string(),
+        Some(config.max_size_channel_network_to_endorsement_handler),
+    );
+    let (sender_blocks, receiver_blocks) = MassaChannel::new(
+        "sender_blocks".to_string(),
+        Some(config.max_size_channel_network_to_block_handler),
+    );
+    let (sender_peers, receiver_peers) = MassaChannel::new(
+        "sender_peers".to_string(),
+        Some(config.max_size_channel_network_to_peer_handler),
+    );
+
+    let traffic = TrafficAccounting::new_shared(&config);
+
+    // Register channels for handlers
+    let message_handlers: MessagesHandler = MessagesHandler {
+        sender_blocks: sender_blocks.clone(),
+        sender_endorsements: sender_endorsements.clone(),
+        sender_operations: sender_operations.clone(),
+        sender_peers: sender_peers.clone(),
+        id_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+        traffic: traffic.clone(),
+    };
+    network.register(peer_id, message_handlers.clone());
+
+    let network_controller = Box::new(InMemoryNetworkController {
+        network: network.clone(),
+        peer_id,
+        traffic,
+    });
+
+    let connectivity_thread_handle = start_connectivity_thread(
+        peer_id,
+        selector_controller,
+        network_controller,
+        consensus_controller,
+        pool_controller,
+        (sender_blocks, receiver_blocks),
+        (sender_endorsements, receiver_endorsements),
+        (sender_operations, receiver_operations),
+        (sender_peers, receiver_peers),
+        HashMap::default(),
+        Arc::new(RwLock::new(PeerDB {
+            clock: config.clock.clone(),
+            ..Default::default()
+        })),
+        storage,
+        protocol_channels,
+        message_handlers,
+        HashMap::default(),
+        config.default_category_info,
+        config,
+        mip_store,
+        massa_metrics,
+    )?;
+
+    let manager = ProtocolManagerImpl::new(connectivity_thread_handle);
+    Ok((Box::new(manager), NodeId::new(keypair.get_public_key())))
+}
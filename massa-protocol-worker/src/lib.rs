@@ -2,15 +2,23 @@ mod connectivity;
 mod context;
 mod controller;
 mod handlers;
+#[cfg(feature = "test-exports")]
+mod in_memory_network;
 mod ip;
 mod manager;
 mod messages;
 mod sig_verifier;
+mod traffic;
 mod worker;
 mod wrap_network;
 mod wrap_peer_db;
 
 pub use worker::{create_protocol_controller, start_protocol_controller};
 
+#[cfg(feature = "test-exports")]
+pub use in_memory_network::{
+    start_protocol_controller_in_memory, InMemoryNetwork, InMemoryNetworkStats, LinkFault,
+};
+
 #[cfg(test)]
 mod tests;
@@ -12,13 +12,16 @@ use peernet::{
 };
 use tracing::debug;
 
-use crate::handlers::{
-    block_handler::{BlockMessage, BlockMessageSerializer},
-    endorsement_handler::{EndorsementMessage, EndorsementMessageSerializer},
-    operation_handler::{OperationMessage, OperationMessageSerializer},
-    peer_handler::{
-        models::PeerMessageTuple, PeerManagementMessage, PeerManagementMessageSerializer,
+use crate::{
+    handlers::{
+        block_handler::{BlockMessage, BlockMessageSerializer},
+        endorsement_handler::{EndorsementMessage, EndorsementMessageSerializer},
+        operation_handler::{OperationMessage, OperationMessageSerializer},
+        peer_handler::{
+            models::PeerMessageTuple, PeerManagementMessage, PeerManagementMessageSerializer,
+        },
     },
+    traffic::{Admission, SharedTrafficAccounting},
 };
 
 #[derive(Debug)]
@@ -224,6 +227,7 @@ pub struct MessagesHandler {
     pub sender_endorsements: MassaSender<PeerMessageTuple>,
     pub sender_operations: MassaSender<PeerMessageTuple>,
     pub sender_peers: MassaSender<PeerMessageTuple>,
+    pub traffic: SharedTrafficAccounting,
 }
 
 impl PeerNetMessagesHandler<PeerId> for MessagesHandler {
@@ -243,6 +247,25 @@ impl PeerNetMessagesHandler<PeerId> for MessagesHandler {
                 Some(String::from("Invalid message type id")),
             )
         })?;
+        match self.traffic.record_inbound(peer_id, &id, data.len()) {
+            Admission::Accepted => {}
+            // Blocks over the rate limit are not dropped: we stop reading from the peer
+            // until its budget is refilled, which slows it down.
+            Admission::Delayed(until) => {
+                debug!(
+                    "Rate limit exceeded for {:?} message from peer {}, delaying it",
+                    id, peer_id
+                );
+                self.traffic.wait_until(until);
+            }
+            Admission::Dropped => {
+                debug!(
+                    "Rate limit exceeded for {:?} message from peer {}, dropping it",
+                    id, peer_id
+                );
+                return Ok(());
+            }
+        }
         match id {
             // Blocks are high-priority: we block if the channel is full.
             // This means that the sender will be blocked until the message is sent.
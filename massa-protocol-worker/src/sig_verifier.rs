@@ -28,3 +28,11 @@ pub fn verify_sigs_batch(ops: &[(Hash, Signature, PublicKey)]) -> Result<(), Pro
         .try_for_each(verify_signature_batch)
         .map_err(|_err| ProtocolError::WrongSignature)
 }
+
+/// Index of the first signature of the batch that fails to verify.
+/// Only meant to be called once `verify_sigs_batch` failed, to find the culprit.
+pub fn find_invalid_sig(ops: &[(Hash, Signature, PublicKey)]) -> Option<usize> {
+    ops.iter().position(|(hash, signature, public_key)| {
+        public_key.verify_signature(hash, signature).is_err()
+    })
+}
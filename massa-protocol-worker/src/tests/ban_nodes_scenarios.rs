@@ -4,7 +4,12 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 
-use massa_models::{block_id::BlockId, prehash::PreHashSet, slot::Slot};
+use massa_models::{
+    ban::{BanCause, BanEvidence, BanOrigin, BanReason},
+    block_id::BlockId,
+    prehash::PreHashSet,
+    slot::Slot,
+};
 use massa_protocol_exports::PeerId;
 use massa_protocol_exports::{test_exports::tools, ProtocolConfig};
 use massa_signature::KeyPair;
@@ -13,9 +18,9 @@ use massa_time::MassaTime;
 use mockall::predicate;
 use parking_lot::{RwLock, RwLockWriteGuard};
 
-use crate::handlers::peer_handler::models::{PeerInfo, PeerState};
+use crate::handlers::peer_handler::models::{PeerDB, PeerInfo, PeerState};
 use crate::wrap_network::{MockActiveConnectionsTrait, MockActiveConnectionsTraitWrapper};
-use crate::wrap_peer_db::MockPeerDBTrait;
+use crate::wrap_peer_db::{MockPeerDBTrait, PeerDBTrait};
 use crate::{
     handlers::{
         block_handler::{BlockInfoReply, BlockMessage},
@@ -34,13 +39,18 @@ fn peer_db_boilerplate(mock_peer_db: &mut RwLockWriteGuard<MockPeerDBTrait>) {
     mock_peer_db
         .expect_get_rand_peers_to_send()
         .return_const(vec![]);
+    mock_peer_db
+        .expect_remove_expired_bans()
+        .return_const(vec![]);
+    mock_peer_db
+        .expect_retain_connected_peers()
+        .return_const(());
 }
 
 #[test]
 fn test_protocol_bans_node_sending_block_header_with_invalid_signature() {
     let protocol_config = ProtocolConfig {
         thread_count: 2,
-        unban_everyone_timer: MassaTime::from_millis(1000),
         ..Default::default()
     };
 
@@ -57,8 +67,6 @@ fn test_protocol_bans_node_sending_block_header_with_invalid_signature() {
 
     let ban_waitpoint = WaitPoint::new();
     let ban_waitpoint_trigger_handle = ban_waitpoint.get_trigger_handle();
-    let unban_waitpoint = WaitPoint::new();
-    let unban_waitpoint_trigger_handle = unban_waitpoint.get_trigger_handle();
 
     foreign_controllers
         .peer_db
@@ -80,19 +88,18 @@ fn test_protocol_bans_node_sending_block_header_with_invalid_signature() {
         .peer_db
         .write()
         .expect_ban_peer()
-        .returning(move |peer_id| {
+        .returning(move |peer_id, cause, _, _| {
             assert_eq!(peer_id, &node_a_peer_id);
+            assert_eq!(cause.reason, BanReason::InvalidHeader);
             ban_waitpoint_trigger_handle.trigger();
         });
     peer_db_boilerplate(&mut foreign_controllers.peer_db.write());
+    // bans are only lifted once expired, the protocol never unbans peers on its own
     foreign_controllers
         .peer_db
         .write()
         .expect_unban_peer()
-        .returning(move |peer_id| {
-            assert_eq!(peer_id, &node_a_peer_id);
-            unban_waitpoint_trigger_handle.trigger();
-        });
+        .never();
     let mut peers = HashMap::new();
     peers.insert(
         node_a_peer_id,
@@ -142,9 +149,6 @@ fn test_protocol_bans_node_sending_block_header_with_invalid_signature() {
         ))),
     );
     ban_waitpoint.wait();
-
-    // After `unban_everyone_timer` the node should be unbanned
-    unban_waitpoint.wait();
 }
 
 #[test]
@@ -159,6 +163,7 @@ fn test_protocol_bans_node_sending_operation_with_invalid_signature() {
     let op_creator = KeyPair::generate(0).unwrap();
     let mut operation = tools::create_operation_with_expire_period(&op_creator, 1);
     operation.content_creator_pub_key = KeyPair::generate(0).unwrap().get_public_key();
+    let operation_id = operation.id;
     let node_a_keypair = KeyPair::generate(0).unwrap();
     let node_a_peer_id = PeerId::from_public_key(node_a_keypair.get_public_key());
 
@@ -185,8 +190,10 @@ fn test_protocol_bans_node_sending_operation_with_invalid_signature() {
         .peer_db
         .write()
         .expect_ban_peer()
-        .returning(move |peer_id| {
+        .returning(move |peer_id, cause, _, _| {
             assert_eq!(peer_id, &node_a_peer_id);
+            assert_eq!(cause.reason, BanReason::InvalidOperations);
+            assert_eq!(cause.evidence, Some(BanEvidence::Operation(operation_id)));
             ban_waitpoint_trigger_handle.trigger();
         });
     peer_db_boilerplate(&mut foreign_controllers.peer_db.write());
@@ -267,8 +274,9 @@ fn test_protocol_bans_node_sending_header_with_invalid_signature() {
         .peer_db
         .write()
         .expect_ban_peer()
-        .returning(move |peer_id| {
+        .returning(move |peer_id, cause, _, _| {
             assert_eq!(peer_id, &node_a_peer_id);
+            assert_eq!(cause.reason, BanReason::InvalidHeader);
             ban_waitpoint_trigger_handle.trigger();
         });
     peer_db_boilerplate(&mut foreign_controllers.peer_db.write());
@@ -395,8 +403,9 @@ fn test_protocol_does_not_asks_for_block_from_banned_node_who_propagated_header(
         .write()
         .expect_ban_peer()
         .times(1)
-        .returning(move |peer_id| {
+        .returning(move |peer_id, cause, _, _| {
             assert_eq!(peer_id, &node_a_peer_id);
+            assert_eq!(cause.reason, BanReason::InvalidHeader);
             ban_waitpoint_trigger_handle.trigger();
         });
     peer_db_boilerplate(&mut foreign_controllers.peer_db.write());
@@ -532,9 +541,15 @@ fn test_protocol_bans_all_nodes_propagating_an_attack_attempt() {
         .peer_db
         .write()
         .expect_ban_peer()
-        .with(predicate::eq(node_a_peer_id))
+        .with(
+            predicate::eq(node_a_peer_id),
+            predicate::always(),
+            predicate::always(),
+            predicate::always(),
+        )
         .times(1)
-        .returning(move |_| {
+        .returning(move |_, cause, _, _| {
+            assert_eq!(cause.reason, BanReason::AttackBlock);
             let mut counter = counter.write();
             *counter += 1;
             if *counter == 2 {
@@ -545,9 +560,15 @@ fn test_protocol_bans_all_nodes_propagating_an_attack_attempt() {
         .peer_db
         .write()
         .expect_ban_peer()
-        .with(predicate::eq(node_b_peer_id))
+        .with(
+            predicate::eq(node_b_peer_id),
+            predicate::always(),
+            predicate::always(),
+            predicate::always(),
+        )
         .times(1)
-        .returning(move |_| {
+        .returning(move |_, cause, _, _| {
+            assert_eq!(cause.reason, BanReason::AttackBlock);
             let mut counter = counter_clone.write();
             *counter += 1;
             if *counter == 2 {
@@ -617,3 +638,66 @@ fn test_protocol_bans_all_nodes_propagating_an_attack_attempt() {
 
     ban_waitpoint.wait();
 }
+
+#[test]
+fn test_peer_db_bans_escalate_and_expire() {
+    let mut peer_db = PeerDB::default();
+    let peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+    peer_db.peers.insert(
+        peer_id,
+        PeerInfo {
+            last_announce: None,
+            state: PeerState::Trusted,
+        },
+    );
+    let ban_duration = MassaTime::from_millis(1000);
+    let max_ban_duration = MassaTime::from_millis(3000);
+    let cause = BanCause::new(BanReason::InvalidHeader, BanOrigin::BlockRetrieval);
+
+    // each new offense doubles the ban duration, up to the maximum
+    let mut expected_durations = vec![1000, 2000, 3000, 3000].into_iter();
+    let mut last_expiry = MassaTime::from_millis(0);
+    for offense in 1..=4 {
+        peer_db.ban_peer(&peer_id, cause.clone(), ban_duration, max_ban_duration);
+        let ban = peer_db.get_bans().get(&peer_id).unwrap().clone();
+        assert_eq!(ban.offense_count, offense);
+        assert_eq!(ban.cause, cause);
+        assert_eq!(
+            ban.expires_at.unwrap().saturating_sub(ban.banned_at),
+            MassaTime::from_millis(expected_durations.next().unwrap())
+        );
+        assert_eq!(peer_db.peers[&peer_id].state, PeerState::Banned);
+
+        // the ban is lifted once expired
+        assert!(peer_db.remove_expired_bans(ban.banned_at).is_empty());
+        assert_eq!(
+            peer_db.remove_expired_bans(ban.expires_at.unwrap()),
+            vec![peer_id]
+        );
+        assert!(peer_db.get_bans().is_empty());
+        assert_eq!(peer_db.peers[&peer_id].state, PeerState::HandshakeFailed);
+        last_expiry = ban.expires_at.unwrap();
+    }
+
+    // the offenses are forgotten once the peer behaved for the maximum ban duration
+    assert!(peer_db
+        .remove_expired_bans(last_expiry.saturating_add(MassaTime::from_millis(2999)))
+        .is_empty());
+    assert_eq!(peer_db.ban_offenses[&peer_id].count, 4);
+    peer_db.remove_expired_bans(last_expiry.saturating_add(max_ban_duration));
+    assert!(peer_db.ban_offenses.is_empty());
+    peer_db.ban_peer(&peer_id, cause.clone(), ban_duration, max_ban_duration);
+    assert_eq!(peer_db.get_bans()[&peer_id].offense_count, 1);
+    peer_db.unban_peer(&peer_id);
+
+    // manual bans never expire
+    peer_db.ban_peer(
+        &peer_id,
+        BanCause::new(BanReason::Manual, BanOrigin::Api),
+        ban_duration,
+        max_ban_duration,
+    );
+    assert_eq!(peer_db.get_bans()[&peer_id].expires_at, None);
+    assert!(peer_db.remove_expired_bans(MassaTime::max()).is_empty());
+    assert_eq!(peer_db.get_banned_peer_count(), 1);
+}
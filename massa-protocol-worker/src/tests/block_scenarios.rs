@@ -11,6 +11,7 @@ use super::universe::{ProtocolForeignControllers, ProtocolTestUniverse};
 use massa_models::block_header::SecuredHeader;
 use massa_models::operation::{OperationId, OperationPrefixId};
 use massa_models::prehash::PreHashSet;
+use massa_models::version::Version;
 use massa_models::{block_id::BlockId, slot::Slot};
 use massa_protocol_exports::PeerId;
 use massa_protocol_exports::ProtocolConfig;
@@ -390,6 +391,215 @@ fn test_empty_block() {
     waitpoint.wait();
 }
 
+#[test]
+fn test_compact_operation_list_fallback() {
+    let protocol_config = ProtocolConfig {
+        thread_count: 2,
+        ask_block_timeout: MassaTime::from_millis(1000),
+        compact_block_relay: true,
+        ..Default::default()
+    };
+    let peer_version = protocol_config.version;
+
+    let block_creator = KeyPair::generate(0).unwrap();
+    let op_1 = ProtocolTestUniverse::create_operation(&block_creator, 5);
+    let op_thread = op_1
+        .content_creator_address
+        .get_thread(protocol_config.thread_count);
+    let block = ProtocolTestUniverse::create_block(
+        &block_creator,
+        Slot::new(1, op_thread),
+        vec![op_1.clone()],
+        vec![],
+        vec![],
+    );
+    let node_a_keypair = KeyPair::generate(0).unwrap();
+    let node_a_peer_id = PeerId::from_public_key(node_a_keypair.get_public_key());
+
+    let waitpoint = WaitPoint::new();
+    let mut foreign_controllers = ProtocolForeignControllers::new_with_mocks();
+    {
+        let mut peer_db = foreign_controllers.peer_db.write();
+        ProtocolTestUniverse::peer_db_boilerplate(&mut peer_db);
+        peer_db
+            .expect_get_peer_version()
+            .return_const(Some(peer_version));
+    }
+    foreign_controllers
+        .consensus_controller
+        .expect_register_block_header()
+        .return_once(move |block_id, header| {
+            assert_eq!(block_id, block.id);
+            assert_eq!(header.id, block.content.header.id);
+        });
+    block_retrieval_mock(
+        vec![
+            TestsStepMatch::AskData((
+                PeerIdMatchers::PeerId(node_a_peer_id),
+                block.id,
+                AskForBlockInfo::CompactOperationIds,
+            )),
+            TestsStepMatch::AskData((
+                PeerIdMatchers::PeerId(node_a_peer_id),
+                block.id,
+                AskForBlockInfo::OperationIds,
+            )),
+            TestsStepMatch::AskData((
+                PeerIdMatchers::PeerId(node_a_peer_id),
+                block.id,
+                AskForBlockInfo::Operations(vec![op_1.id]),
+            )),
+            TestsStepMatch::BlockManaged((block.id, true)),
+        ],
+        &mut foreign_controllers,
+        waitpoint.get_trigger_handle(),
+    );
+
+    let universe = ProtocolTestUniverse::new(foreign_controllers, protocol_config);
+
+    universe.mock_message_receive(
+        &node_a_peer_id,
+        Message::Block(Box::new(BlockMessage::Header(block.content.header.clone()))),
+    );
+
+    universe
+        .module_controller
+        .send_wishlist_delta(
+            vec![(block.id, Some(block.content.header.clone()))]
+                .into_iter()
+                .collect(),
+            PreHashSet::<BlockId>::default(),
+        )
+        .unwrap();
+    waitpoint.wait();
+
+    // the operation is unknown so the list can't be rebuilt: the full list is asked to the same peer
+    universe.mock_message_receive(
+        &node_a_peer_id,
+        Message::Block(Box::new(BlockMessage::DataResponse {
+            block_id: block.id,
+            block_info: BlockInfoReply::CompactOperationIds(vec![op_1.id.prefix()]),
+        })),
+    );
+    waitpoint.wait();
+
+    universe.mock_message_receive(
+        &node_a_peer_id,
+        Message::Block(Box::new(BlockMessage::DataResponse {
+            block_id: block.id,
+            block_info: BlockInfoReply::OperationIds(vec![op_1.id]),
+        })),
+    );
+    waitpoint.wait();
+
+    universe.mock_message_receive(
+        &node_a_peer_id,
+        Message::Block(Box::new(BlockMessage::DataResponse {
+            block_id: block.id,
+            block_info: BlockInfoReply::Operations(vec![op_1]),
+        })),
+    );
+    waitpoint.wait();
+}
+
+#[test]
+fn test_compact_operation_list_older_peer() {
+    let protocol_config = ProtocolConfig {
+        thread_count: 2,
+        ask_block_timeout: MassaTime::from_millis(1000),
+        compact_block_relay: true,
+        ..Default::default()
+    };
+    // a peer older than the compact block relay is asked the full operation list
+    let peer_version: Version = "TEST.23.1".parse().unwrap();
+    assert!(!peer_version.is_at_least(&protocol_config.compact_block_relay_min_version));
+
+    let block_creator = KeyPair::generate(0).unwrap();
+    let op_1 = ProtocolTestUniverse::create_operation(&block_creator, 5);
+    let op_thread = op_1
+        .content_creator_address
+        .get_thread(protocol_config.thread_count);
+    let block = ProtocolTestUniverse::create_block(
+        &block_creator,
+        Slot::new(1, op_thread),
+        vec![op_1.clone()],
+        vec![],
+        vec![],
+    );
+    let node_a_keypair = KeyPair::generate(0).unwrap();
+    let node_a_peer_id = PeerId::from_public_key(node_a_keypair.get_public_key());
+
+    let waitpoint = WaitPoint::new();
+    let mut foreign_controllers = ProtocolForeignControllers::new_with_mocks();
+    {
+        let mut peer_db = foreign_controllers.peer_db.write();
+        ProtocolTestUniverse::peer_db_boilerplate(&mut peer_db);
+        peer_db
+            .expect_get_peer_version()
+            .return_const(Some(peer_version));
+    }
+    foreign_controllers
+        .consensus_controller
+        .expect_register_block_header()
+        .return_once(move |block_id, header| {
+            assert_eq!(block_id, block.id);
+            assert_eq!(header.id, block.content.header.id);
+        });
+    block_retrieval_mock(
+        vec![
+            TestsStepMatch::AskData((
+                PeerIdMatchers::PeerId(node_a_peer_id),
+                block.id,
+                AskForBlockInfo::OperationIds,
+            )),
+            TestsStepMatch::AskData((
+                PeerIdMatchers::PeerId(node_a_peer_id),
+                block.id,
+                AskForBlockInfo::Operations(vec![op_1.id]),
+            )),
+            TestsStepMatch::BlockManaged((block.id, true)),
+        ],
+        &mut foreign_controllers,
+        waitpoint.get_trigger_handle(),
+    );
+
+    let universe = ProtocolTestUniverse::new(foreign_controllers, protocol_config);
+
+    universe.mock_message_receive(
+        &node_a_peer_id,
+        Message::Block(Box::new(BlockMessage::Header(block.content.header.clone()))),
+    );
+
+    universe
+        .module_controller
+        .send_wishlist_delta(
+            vec![(block.id, Some(block.content.header.clone()))]
+                .into_iter()
+                .collect(),
+            PreHashSet::<BlockId>::default(),
+        )
+        .unwrap();
+    waitpoint.wait();
+
+    universe.mock_message_receive(
+        &node_a_peer_id,
+        Message::Block(Box::new(BlockMessage::DataResponse {
+            block_id: block.id,
+            block_info: BlockInfoReply::OperationIds(vec![op_1.id]),
+        })),
+    );
+    waitpoint.wait();
+
+    universe.mock_message_receive(
+        &node_a_peer_id,
+        Message::Block(Box::new(BlockMessage::DataResponse {
+            block_id: block.id,
+            block_info: BlockInfoReply::Operations(vec![op_1]),
+        })),
+    );
+    waitpoint.wait();
+}
+
 #[test]
 fn test_dont_want_it_anymore() {
     let protocol_config = ProtocolConfig {
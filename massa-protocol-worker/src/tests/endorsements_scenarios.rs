@@ -1,3 +1,4 @@
+use massa_models::ban::{BanEvidence, BanReason};
 use massa_models::slot::Slot;
 use massa_pos_exports::Selection;
 use massa_protocol_exports::PeerId;
@@ -86,6 +87,7 @@ fn test_protocol_does_not_send_invalid_endorsements_it_receives_to_pool() {
     let mut endorsement =
         ProtocolTestUniverse::create_endorsement(&endorsement_creator, Slot::new(1, 1));
     endorsement.content_creator_pub_key = node_a_keypair.get_public_key();
+    let endorsement_id = endorsement.id;
 
     let waitpoint = WaitPoint::new();
     let waitpoint_trigger_handle = waitpoint.get_trigger_handle();
@@ -105,8 +107,13 @@ fn test_protocol_does_not_send_invalid_endorsements_it_receives_to_pool() {
         .peer_db
         .write()
         .expect_ban_peer()
-        .returning(move |peer_id| {
+        .returning(move |peer_id, cause, _, _| {
             assert_eq!(peer_id, &node_a_peer_id);
+            assert_eq!(cause.reason, BanReason::InvalidEndorsements);
+            assert_eq!(
+                cause.evidence,
+                Some(BanEvidence::Endorsement(endorsement_id))
+            );
             waitpoint_trigger_handle.trigger();
         });
     foreign_controllers
@@ -1,8 +1,11 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
+use massa_models::ban::BanReason;
 use massa_models::operation::{OperationPrefixId, SecureShareOperation};
 use massa_models::{block_id::BlockId, prehash::PreHashSet, slot::Slot};
 use massa_protocol_exports::PeerId;
@@ -11,8 +14,11 @@ use massa_signature::KeyPair;
 use massa_test_framework::{TestUniverse, WaitPoint};
 use massa_time::MassaTime;
 use mockall::{predicate, Sequence};
+use peernet::messages::MessagesSerializer as _;
 
 use crate::handlers::block_handler::AskForBlockInfo;
+use crate::handlers::operation_handler::{OperationMessageSerializer, OperationsBloomFilter};
+use crate::messages::MessagesSerializer;
 use crate::wrap_network::MockActiveConnectionsTraitWrapper;
 use crate::{
     handlers::{
@@ -239,8 +245,9 @@ fn test_protocol_does_not_send_invalid_operations_it_receives_to_pool() {
         .write()
         .expect_ban_peer()
         .times(1)
-        .returning(move |peer_id| {
+        .returning(move |peer_id, cause, _, _| {
             assert_eq!(*peer_id, node_a_peer_id);
+            assert_eq!(cause.reason, BanReason::InvalidOperations);
             waitpoint_trigger_handle2.trigger();
         });
     operation_workflow_mock(vec![], &mut foreign_controllers, waitpoint_trigger_handle);
@@ -660,3 +667,190 @@ fn test_protocol_on_ask_operations() {
     );
     waitpoint.wait();
 }
+
+/// Expect exactly the given announcements to be sent, in any order, and sum the size of all the
+/// messages sent in `sent_bytes`.
+fn reconciliation_mock(
+    connected_peers: HashSet<PeerId>,
+    announcements: Vec<(PeerId, Vec<OperationPrefixId>)>,
+    sent_bytes: Arc<AtomicUsize>,
+    foreign_controllers: &mut ProtocolForeignControllers,
+    waitpoint_trigger_handle: WaitPoint,
+) {
+    let mut shared_active_connections = MockActiveConnectionsTraitWrapper::new();
+    for (node_peer_id, announced_operations) in announcements {
+        let waitpoint_trigger_handle = waitpoint_trigger_handle.get_trigger_handle();
+        let sent_bytes = sent_bytes.clone();
+        shared_active_connections.set_expectations(|active_connections| {
+            active_connections
+                .expect_send_to_peer()
+                .times(1)
+                .with(
+                    predicate::eq(node_peer_id),
+                    predicate::always(),
+                    predicate::always(),
+                    predicate::always(),
+                )
+                .returning(move |_, message_serializer, message, _| {
+                    let mut buffer = Vec::new();
+                    message_serializer.serialize(&message, &mut buffer).unwrap();
+                    sent_bytes.fetch_add(buffer.len(), Ordering::SeqCst);
+                    match message {
+                        Message::Operation(OperationMessage::OperationsAnnouncement(
+                            operations,
+                        )) => {
+                            assert_eq!(operations.len(), announced_operations.len());
+                            for op in announced_operations.iter() {
+                                assert!(operations.contains(op));
+                            }
+                            waitpoint_trigger_handle.trigger();
+                        }
+                        _ => panic!("Unexpected message type."),
+                    }
+                    Ok(())
+                });
+        });
+    }
+    ProtocolTestUniverse::active_connections_boilerplate(
+        &mut shared_active_connections,
+        connected_peers,
+    );
+    foreign_controllers
+        .network_controller
+        .expect_get_active_connections()
+        .returning(move || Box::new(shared_active_connections.clone()));
+    foreign_controllers
+        .pool_controller
+        .set_expectations(|pool_controller| {
+            pool_controller
+                .expect_add_operations()
+                .times(1)
+                .returning(move |_| waitpoint_trigger_handle.trigger());
+        });
+}
+
+/// Compare the bandwidth used to propagate operations received from A to B, C and D
+/// with push announcements and with the filters sent by B, C and D.
+#[test]
+fn test_protocol_operation_reconciliation_saves_bandwidth() {
+    let protocol_config = ProtocolConfig {
+        thread_count: 2,
+        ..Default::default()
+    };
+    let block_creator = KeyPair::generate(0).unwrap();
+    let operations: Vec<SecureShareOperation> = (0..50)
+        .map(|_| ProtocolTestUniverse::create_operation(&block_creator, 1))
+        .collect();
+    let prefixes: Vec<OperationPrefixId> = operations.iter().map(|op| op.id.prefix()).collect();
+    let peer_ids: Vec<PeerId> = (0..4)
+        .map(|_| PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key()))
+        .collect();
+    let (node_a_peer_id, other_peer_ids) = (peer_ids[0], &peer_ids[1..]);
+    let message_serializer = MessagesSerializer::new()
+        .with_operation_message_serializer(OperationMessageSerializer::new());
+    let message_size = |message: &Message| {
+        let mut buffer = Vec::new();
+        message_serializer.serialize(message, &mut buffer).unwrap();
+        buffer.len()
+    };
+    let announcement_size = |prefixes: &[OperationPrefixId]| {
+        message_size(&Message::Operation(
+            OperationMessage::OperationsAnnouncement(prefixes.iter().copied().collect()),
+        ))
+    };
+
+    // Push: every operation is announced to each peer that doesn't know it
+    let push_bytes = {
+        let sent_bytes = Arc::new(AtomicUsize::new(0));
+        let waitpoint = WaitPoint::new();
+        let mut foreign_controllers = ProtocolForeignControllers::new_with_mocks();
+        ProtocolTestUniverse::peer_db_boilerplate(&mut foreign_controllers.peer_db.write());
+        reconciliation_mock(
+            peer_ids.iter().copied().collect(),
+            other_peer_ids
+                .iter()
+                .map(|peer_id| (*peer_id, prefixes.clone()))
+                .collect(),
+            sent_bytes.clone(),
+            &mut foreign_controllers,
+            waitpoint.get_trigger_handle(),
+        );
+        let universe = ProtocolTestUniverse::new(foreign_controllers, protocol_config.clone());
+
+        universe.mock_message_receive(
+            &node_a_peer_id,
+            Message::Operation(OperationMessage::Operations(operations.clone())),
+        );
+        // pool and the three announcements
+        for _ in 0..4 {
+            waitpoint.wait();
+        }
+        sent_bytes.load(Ordering::SeqCst)
+    };
+
+    // Reconciliation: B and C already received all the operations from elsewhere, D misses a few
+    let missing_count = 5;
+    let mut full_filter = OperationsBloomFilter::new(prefixes.len(), 1);
+    let mut partial_filter = OperationsBloomFilter::new(prefixes.len(), 2);
+    for (index, prefix) in prefixes.iter().enumerate() {
+        full_filter.insert(prefix);
+        if index >= missing_count {
+            partial_filter.insert(prefix);
+        }
+    }
+    // false positives of the filter of D are not announced to it
+    let missing_prefixes: Vec<OperationPrefixId> = prefixes[..missing_count]
+        .iter()
+        .filter(|prefix| !partial_filter.contains(prefix))
+        .copied()
+        .collect();
+    let node_d_peer_id = other_peer_ids[2];
+    let (announcements_bytes, filters_bytes) = {
+        let sent_bytes = Arc::new(AtomicUsize::new(0));
+        let waitpoint = WaitPoint::new();
+        let mut foreign_controllers = ProtocolForeignControllers::new_with_mocks();
+        ProtocolTestUniverse::peer_db_boilerplate(&mut foreign_controllers.peer_db.write());
+        reconciliation_mock(
+            peer_ids.iter().copied().collect(),
+            vec![(node_d_peer_id, missing_prefixes.clone())],
+            sent_bytes.clone(),
+            &mut foreign_controllers,
+            waitpoint.get_trigger_handle(),
+        );
+        let universe = ProtocolTestUniverse::new(foreign_controllers, protocol_config);
+
+        let mut filters_bytes = 0;
+        let mut receive_filter = |peer_id: &PeerId, filter: OperationsBloomFilter| {
+            let message = Message::Operation(OperationMessage::OperationsFilter(filter));
+            filters_bytes += message_size(&message);
+            universe.mock_message_receive(peer_id, message);
+        };
+        // first round: the peers don't know any operation yet
+        for peer_id in other_peer_ids {
+            receive_filter(peer_id, OperationsBloomFilter::new(0, 0));
+        }
+        universe.mock_message_receive(
+            &node_a_peer_id,
+            Message::Operation(OperationMessage::Operations(operations.clone())),
+        );
+        waitpoint.wait();
+        // second round: only D gets an announcement
+        receive_filter(&other_peer_ids[0], full_filter.clone());
+        receive_filter(&other_peer_ids[1], full_filter);
+        receive_filter(&node_d_peer_id, partial_filter);
+        waitpoint.wait();
+        (sent_bytes.load(Ordering::SeqCst), filters_bytes)
+    };
+
+    // each peer received one announcement of all the operations
+    assert_eq!(push_bytes, 3 * announcement_size(&prefixes));
+    // only the operations missed by D were announced, the filters were the only other traffic
+    assert_eq!(announcements_bytes, announcement_size(&missing_prefixes));
+    let reconciliation_bytes = announcements_bytes + filters_bytes;
+    assert!(
+        reconciliation_bytes < push_bytes,
+        "operation propagation to 3 peers: {} bytes with announcements, {} bytes with reconciliation",
+        push_bytes,
+        reconciliation_bytes
+    );
+}
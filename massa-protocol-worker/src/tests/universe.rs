@@ -30,6 +30,7 @@ use crate::{
     },
     manager::ProtocolManagerImpl,
     messages::{Message, MessagesHandler, MessagesSerializer},
+    traffic::TrafficAccounting,
     wrap_network::{MockActiveConnectionsTraitWrapper, MockNetworkController, NetworkController},
     wrap_peer_db::MockPeerDBTrait,
 };
@@ -129,6 +130,12 @@ impl ProtocolTestUniverse {
         mock_peer_db
             .expect_get_rand_peers_to_send()
             .return_const(vec![]);
+        mock_peer_db
+            .expect_remove_expired_bans()
+            .return_const(vec![]);
+        mock_peer_db
+            .expect_retain_connected_peers()
+            .return_const(());
     }
 
     pub fn active_connections_boilerplate(
@@ -222,6 +229,7 @@ pub fn start_protocol_controller_with_mock_network(
         sender_operations: sender_operations.clone(),
         sender_peers: sender_peers.clone(),
         id_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+        traffic: TrafficAccounting::new_shared(&config),
     };
 
     let (controller, channels) = create_protocol_controller(config.clone());
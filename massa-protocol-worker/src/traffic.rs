@@ -0,0 +1,449 @@
+//! Per peer accounting of the traffic and rate limiting of the inbound traffic.
+//!
+//! Every message received by `MessagesHandler` or sent through the active connections is
+//! accounted here, per peer and per message type.
+//! Endorsement and operation messages are also checked against a token bucket per peer
+//! so that a single peer cannot make us process (and answer) an unbounded amount of data.
+//! Block messages are never dropped: reading from a peer over its block budget waits for
+//! the bucket to refill, which slows the peer down through its connection.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use massa_models::stats::{MessageTrafficStats, PeerTrafficStats};
+use massa_protocol_exports::{PeerId, ProtocolConfig};
+use massa_time::{MassaTime, SharedClock};
+use parking_lot::{Mutex, RwLock};
+
+use crate::messages::MessageTypeId;
+
+pub(crate) type SharedTrafficAccounting = Arc<TrafficAccounting>;
+
+/// Token bucket refilled continuously at `rate` tokens per second, up to `capacity` tokens
+#[derive(Debug, Clone)]
+pub(crate) struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    last_refill: MassaTime,
+}
+
+impl TokenBucket {
+    /// Create a full bucket. The capacity is twice the rate to absorb small bursts,
+    /// like the bucket of the peernet data stream limiter.
+    pub fn new(rate: u64, now: MassaTime) -> Self {
+        let capacity = rate.saturating_mul(2) as f64;
+        TokenBucket {
+            rate: rate as f64,
+            capacity,
+            tokens: capacity,
+            last_refill: now,
+        }
+    }
+
+    /// Try to take `amount` tokens from the bucket. Returns false if there is not enough tokens.
+    ///
+    /// A message bigger than the capacity of the bucket is accepted only if the bucket is full,
+    /// so that a limit set too low cannot block a peer forever.
+    pub fn try_consume(&mut self, amount: u64, now: MassaTime) -> bool {
+        self.refill(now);
+        let amount = (amount as f64).min(self.capacity);
+        if self.tokens >= amount {
+            self.tokens -= amount;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Take `amount` tokens from the bucket, borrowing up to its capacity from the future.
+    /// Returns the time at which the bucket is not in debt anymore.
+    pub fn consume_until(&mut self, amount: u64, now: MassaTime) -> MassaTime {
+        self.refill(now);
+        let amount = (amount as f64).min(self.capacity);
+        self.tokens = (self.tokens - amount).max(-self.capacity);
+        if self.tokens >= 0.0 {
+            return now;
+        }
+        now.saturating_add(MassaTime::from_millis(
+            (-self.tokens * 1000.0 / self.rate).ceil() as u64,
+        ))
+    }
+
+    fn refill(&mut self, now: MassaTime) {
+        let elapsed = now
+            .saturating_sub(self.last_refill)
+            .to_duration()
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last_refill = now;
+    }
+}
+
+/// Whether a received message can be processed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Admission {
+    Accepted,
+    /// the message has to wait until the given time
+    Delayed(MassaTime),
+    Dropped,
+}
+
+#[derive(Debug, Default)]
+struct PeerTraffic {
+    stats: PeerTrafficStats,
+    block_bucket: Option<TokenBucket>,
+    endorsement_bucket: Option<TokenBucket>,
+    operation_bucket: Option<TokenBucket>,
+}
+
+impl PeerTraffic {
+    /// Rate limiter of a message type, `None` for the types that are not rate limited
+    fn bucket_mut(&mut self, id: &MessageTypeId) -> Option<&mut Option<TokenBucket>> {
+        match id {
+            MessageTypeId::Block => Some(&mut self.block_bucket),
+            MessageTypeId::Endorsement => Some(&mut self.endorsement_bucket),
+            MessageTypeId::Operation => Some(&mut self.operation_bucket),
+            MessageTypeId::PeerManagement => None,
+        }
+    }
+}
+
+/// Counters of one message type, updated without lock
+#[derive(Debug, Default)]
+struct AtomicMessageTrafficStats {
+    messages_received: AtomicU64,
+    bytes_received: AtomicU64,
+    messages_rate_limited: AtomicU64,
+    messages_sent: AtomicU64,
+    bytes_sent: AtomicU64,
+}
+
+impl AtomicMessageTrafficStats {
+    fn load(&self) -> MessageTrafficStats {
+        MessageTrafficStats {
+            messages_received: self.messages_received.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            messages_rate_limited: self.messages_rate_limited.load(Ordering::Relaxed),
+            messages_sent: self.messages_sent.load(Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Counters of the whole node, per message type
+#[derive(Debug, Default)]
+struct AtomicTrafficStats {
+    block: AtomicMessageTrafficStats,
+    endorsement: AtomicMessageTrafficStats,
+    operation: AtomicMessageTrafficStats,
+    peer_management: AtomicMessageTrafficStats,
+}
+
+impl AtomicTrafficStats {
+    fn get(&self, id: &MessageTypeId) -> &AtomicMessageTrafficStats {
+        match id {
+            MessageTypeId::Block => &self.block,
+            MessageTypeId::Endorsement => &self.endorsement,
+            MessageTypeId::Operation => &self.operation,
+            MessageTypeId::PeerManagement => &self.peer_management,
+        }
+    }
+
+    fn load(&self) -> PeerTrafficStats {
+        PeerTrafficStats {
+            block: self.block.load(),
+            endorsement: self.endorsement.load(),
+            operation: self.operation.load(),
+            peer_management: self.peer_management.load(),
+        }
+    }
+}
+
+/// Traffic counters and rate limiters of all the connected peers.
+///
+/// Each peer has its own lock, so that the handlers of different peers do not contend:
+/// the map of the peers is only locked for writing when a peer is added or removed.
+#[derive(Debug)]
+pub(crate) struct TrafficAccounting {
+    rate_limit_blocks: u64,
+    rate_limit_endorsements: u64,
+    rate_limit_operations: u64,
+    peers: RwLock<HashMap<PeerId, Arc<Mutex<PeerTraffic>>>>,
+    /// counters of the whole node since startup, kept when peers disconnect
+    total: AtomicTrafficStats,
+    /// clock refilling the buckets of the peers
+    clock: SharedClock,
+}
+
+fn message_stats_mut<'a>(
+    stats: &'a mut PeerTrafficStats,
+    id: &MessageTypeId,
+) -> &'a mut MessageTrafficStats {
+    match id {
+        MessageTypeId::Block => &mut stats.block,
+        MessageTypeId::Endorsement => &mut stats.endorsement,
+        MessageTypeId::Operation => &mut stats.operation,
+        MessageTypeId::PeerManagement => &mut stats.peer_management,
+    }
+}
+
+impl TrafficAccounting {
+    pub fn new(config: &ProtocolConfig) -> Self {
+        TrafficAccounting {
+            rate_limit_blocks: config.rate_limit_blocks,
+            rate_limit_endorsements: config.rate_limit_endorsements,
+            rate_limit_operations: config.rate_limit_operations,
+            peers: RwLock::new(HashMap::new()),
+            total: AtomicTrafficStats::default(),
+            clock: config.clock.clone(),
+        }
+    }
+
+    pub fn new_shared(config: &ProtocolConfig) -> SharedTrafficAccounting {
+        Arc::new(TrafficAccounting::new(config))
+    }
+
+    /// Traffic of a peer, added if it is not known yet
+    fn peer(&self, peer_id: &PeerId) -> Arc<Mutex<PeerTraffic>> {
+        if let Some(peer) = self.peers.read().get(peer_id) {
+            return peer.clone();
+        }
+        self.peers.write().entry(*peer_id).or_default().clone()
+    }
+
+    /// Account a message received from `peer_id`, and check it against the rate limit of the peer
+    pub fn record_inbound(&self, peer_id: &PeerId, id: &MessageTypeId, size: usize) -> Admission {
+        let size = size as u64;
+        let now = self.clock.now();
+        let peer = self.peer(peer_id);
+        let mut peer = peer.lock();
+        let rate = match id {
+            MessageTypeId::Block => self.rate_limit_blocks,
+            MessageTypeId::Endorsement => self.rate_limit_endorsements,
+            MessageTypeId::Operation => self.rate_limit_operations,
+            MessageTypeId::PeerManagement => 0,
+        };
+        let admission = match peer.bucket_mut(id) {
+            Some(bucket) if rate > 0 => {
+                let bucket = bucket.get_or_insert_with(|| TokenBucket::new(rate, now));
+                match id {
+                    MessageTypeId::Block => match bucket.consume_until(size, now) {
+                        until if until > now => Admission::Delayed(until),
+                        _ => Admission::Accepted,
+                    },
+                    _ if bucket.try_consume(size, now) => Admission::Accepted,
+                    _ => Admission::Dropped,
+                }
+            }
+            _ => Admission::Accepted,
+        };
+        let rate_limited = u64::from(admission != Admission::Accepted);
+
+        let stats = message_stats_mut(&mut peer.stats, id);
+        stats.messages_received = stats.messages_received.saturating_add(1);
+        stats.bytes_received = stats.bytes_received.saturating_add(size);
+        stats.messages_rate_limited = stats.messages_rate_limited.saturating_add(rate_limited);
+        let total = self.total.get(id);
+        total.messages_received.fetch_add(1, Ordering::Relaxed);
+        total.bytes_received.fetch_add(size, Ordering::Relaxed);
+        total
+            .messages_rate_limited
+            .fetch_add(rate_limited, Ordering::Relaxed);
+        admission
+    }
+
+    /// Account a message sent to `peer_id`
+    pub fn record_outbound(&self, peer_id: &PeerId, id: &MessageTypeId, size: usize) {
+        let size = size as u64;
+        let peer = self.peer(peer_id);
+        let mut peer = peer.lock();
+        let stats = message_stats_mut(&mut peer.stats, id);
+        stats.messages_sent = stats.messages_sent.saturating_add(1);
+        stats.bytes_sent = stats.bytes_sent.saturating_add(size);
+        let total = self.total.get(id);
+        total.messages_sent.fetch_add(1, Ordering::Relaxed);
+        total.bytes_sent.fetch_add(size, Ordering::Relaxed);
+    }
+
+    /// Blocks until the clock of the accounting reads `time`
+    pub fn wait_until(&self, time: MassaTime) {
+        self.clock.sleep_until(time);
+    }
+
+    /// Forget the peers that are not connected anymore
+    pub fn retain_peers(&self, connected: &HashSet<PeerId>) {
+        self.peers
+            .write()
+            .retain(|peer_id, _| connected.contains(peer_id));
+    }
+
+    /// Counters of each connected peer
+    pub fn get_peers_stats(&self) -> HashMap<PeerId, PeerTrafficStats> {
+        self.peers
+            .read()
+            .iter()
+            .map(|(peer_id, traffic)| (*peer_id, traffic.lock().stats))
+            .collect()
+    }
+
+    /// Counters of a connected peer
+    pub fn get_peer_stats(&self, peer_id: &PeerId) -> Option<PeerTrafficStats> {
+        self.peers
+            .read()
+            .get(peer_id)
+            .map(|traffic| traffic.lock().stats)
+    }
+
+    /// Counters of the whole node since startup
+    pub fn get_total_stats(&self) -> PeerTrafficStats {
+        self.total.load()
+    }
+
+    /// Peers that had at least `threshold` messages limited by the rate limiter.
+    /// Their counters are removed so that they are reported only once.
+    pub fn take_peers_over_threshold(&self, threshold: u64) -> Vec<PeerId> {
+        if threshold == 0 {
+            return Vec::new();
+        }
+        let mut peers = self.peers.write();
+        let peer_ids: Vec<PeerId> = peers
+            .iter()
+            .filter(|(_, traffic)| traffic.lock().stats.total().messages_rate_limited >= threshold)
+            .map(|(peer_id, _)| *peer_id)
+            .collect();
+        for peer_id in &peer_ids {
+            peers.remove(peer_id);
+        }
+        peer_ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use massa_signature::KeyPair;
+    use massa_time::ManualClock;
+
+    use super::*;
+
+    #[test]
+    fn test_token_bucket_refill() {
+        let start = MassaTime::from_millis(1_000_000);
+        let mut bucket = TokenBucket::new(100, start);
+        // burst of twice the rate is accepted
+        assert!(bucket.try_consume(200, start));
+        assert!(!bucket.try_consume(1, start));
+        // half a second later 50 tokens are available
+        let later = start.saturating_add(MassaTime::from_millis(500));
+        assert!(!bucket.try_consume(60, later));
+        assert!(bucket.try_consume(50, later));
+        // the bucket never holds more than its capacity
+        let much_later = later.saturating_add(MassaTime::from_millis(60_000));
+        assert!(bucket.try_consume(200, much_later));
+        assert!(!bucket.try_consume(1, much_later));
+        // oversized messages are accepted once the bucket is full again
+        let refilled = much_later.saturating_add(MassaTime::from_millis(2_000));
+        assert!(bucket.try_consume(10_000, refilled));
+    }
+
+    #[test]
+    fn test_traffic_accounting_rate_limit() {
+        let clock = Arc::new(ManualClock::new(MassaTime::from_millis(1_000_000)));
+        let config = ProtocolConfig {
+            rate_limit_operations: 1000,
+            rate_limit_endorsements: 0,
+            clock: SharedClock::new(clock.clone()),
+            ..Default::default()
+        };
+        let traffic = TrafficAccounting::new(&config);
+        let peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        let other_peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+        let accepted = |peer_id: &PeerId, id: MessageTypeId, size: usize| {
+            traffic.record_inbound(peer_id, &id, size) == Admission::Accepted
+        };
+
+        assert!(accepted(&peer_id, MessageTypeId::Operation, 1500));
+        assert!(!accepted(&peer_id, MessageTypeId::Operation, 1000));
+        assert!(!accepted(&peer_id, MessageTypeId::Operation, 1000));
+        // limits are per peer and per message type
+        assert!(accepted(&other_peer_id, MessageTypeId::Operation, 1500));
+        assert!(accepted(&peer_id, MessageTypeId::Endorsement, 100_000));
+
+        let stats = traffic.get_peers_stats();
+        let peer_stats = stats.get(&peer_id).unwrap();
+        assert_eq!(peer_stats.operation.messages_received, 3);
+        assert_eq!(peer_stats.operation.bytes_received, 3500);
+        assert_eq!(peer_stats.operation.messages_rate_limited, 2);
+        assert_eq!(peer_stats.endorsement.messages_rate_limited, 0);
+        assert_eq!(traffic.get_total_stats().operation.messages_received, 4);
+
+        assert_eq!(traffic.take_peers_over_threshold(2), vec![peer_id]);
+        assert!(traffic.take_peers_over_threshold(2).is_empty());
+
+        // the buckets refill as the clock moves
+        assert!(!accepted(&other_peer_id, MessageTypeId::Operation, 1000));
+        clock.advance(MassaTime::from_millis(1_000));
+        assert!(accepted(&other_peer_id, MessageTypeId::Operation, 1000));
+
+        traffic.retain_peers(&HashSet::new());
+        assert!(traffic.get_peers_stats().is_empty());
+        assert_eq!(traffic.get_total_stats().operation.messages_received, 6);
+    }
+
+    #[test]
+    fn test_traffic_accounting_delays_blocks() {
+        let start = MassaTime::from_millis(1_000_000);
+        let clock = Arc::new(ManualClock::new(start));
+        let config = ProtocolConfig {
+            rate_limit_blocks: 1000,
+            clock: SharedClock::new(clock.clone()),
+            ..Default::default()
+        };
+        let traffic = TrafficAccounting::new(&config);
+        let peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+
+        // the burst is accepted, then blocks wait for the bucket to refill instead of being dropped
+        assert_eq!(
+            traffic.record_inbound(&peer_id, &MessageTypeId::Block, 2000),
+            Admission::Accepted
+        );
+        assert_eq!(
+            traffic.record_inbound(&peer_id, &MessageTypeId::Block, 500),
+            Admission::Delayed(start.saturating_add(MassaTime::from_millis(500)))
+        );
+        // the debt is bounded by the capacity of the bucket
+        assert_eq!(
+            traffic.record_inbound(&peer_id, &MessageTypeId::Block, 5000),
+            Admission::Delayed(start.saturating_add(MassaTime::from_millis(2000)))
+        );
+        clock.advance(MassaTime::from_millis(4000));
+        assert_eq!(
+            traffic.record_inbound(&peer_id, &MessageTypeId::Block, 500),
+            Admission::Accepted
+        );
+        let stats = traffic.get_peer_stats(&peer_id).unwrap();
+        assert_eq!(stats.block.messages_received, 4);
+        assert_eq!(stats.block.messages_rate_limited, 2);
+    }
+
+    #[test]
+    fn test_traffic_accounting_outbound() {
+        let traffic = TrafficAccounting::new(&ProtocolConfig::default());
+        let peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+
+        traffic.record_outbound(&peer_id, &MessageTypeId::Block, 300);
+        traffic.record_outbound(&peer_id, &MessageTypeId::Block, 200);
+        traffic.record_inbound(&peer_id, &MessageTypeId::Block, 100);
+
+        let stats = traffic.get_peer_stats(&peer_id).unwrap();
+        assert_eq!(stats.block.messages_sent, 2);
+        assert_eq!(stats.block.bytes_sent, 500);
+        assert_eq!(stats.block.bytes_received, 100);
+        assert_eq!(traffic.get_total_stats().block.bytes_sent, 500);
+    }
+}
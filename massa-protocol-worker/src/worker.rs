@@ -11,7 +11,6 @@ use massa_protocol_exports::{
 use massa_serialization::U64VarIntDeserializer;
 use massa_signature::KeyPair;
 use massa_storage::Storage;
-use massa_time::MassaTime;
 use massa_versioning::{
     keypair_factory::KeyPairFactory,
     versioning::MipStore,
@@ -50,6 +49,7 @@ use crate::{
     ip::to_canonical,
     manager::ProtocolManagerImpl,
     messages::MessagesHandler,
+    traffic::TrafficAccounting,
     wrap_network::NetworkControllerImpl,
 };
 
@@ -188,7 +188,10 @@ pub fn start_protocol_controller(
     massa_metrics: MassaMetrics,
 ) -> Result<(Box<dyn ProtocolManager>, KeyPair, NodeId), ProtocolError> {
     debug!("starting protocol controller");
-    let peer_db = Arc::new(RwLock::new(PeerDB::default()));
+    let peer_db = Arc::new(RwLock::new(PeerDB {
+        clock: config.clock.clone(),
+        ..Default::default()
+    }));
 
     let (sender_operations, receiver_operations) = MassaChannel::new(
         "sender_operations".to_string(),
@@ -207,6 +210,8 @@ pub fn start_protocol_controller(
         Some(config.max_size_channel_network_to_peer_handler),
     );
 
+    let traffic = TrafficAccounting::new_shared(&config);
+
     // Register channels for handlers
     let message_handlers: MessagesHandler = MessagesHandler {
         sender_blocks: sender_blocks.clone(),
@@ -214,6 +219,7 @@ pub fn start_protocol_controller(
         sender_operations: sender_operations.clone(),
         sender_peers: sender_peers.clone(),
         id_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+        traffic: traffic.clone(),
     };
 
     // try to read node keypair from file, otherwise generate it & write to file. Then derive nodeId
@@ -229,7 +235,7 @@ pub fn start_protocol_controller(
         let keypair_factory = KeyPairFactory {
             mip_store: mip_store.clone(),
         };
-        let now = MassaTime::now();
+        let now = config.clock.now();
         let keypair = keypair_factory.create(&(), FactoryStrategy::At(now))?;
         if let Err(e) = std::fs::write(&config.keypair_file, serde_json::to_string(&keypair)?) {
             warn!("could not generate node key file: {}", e);
@@ -314,9 +320,10 @@ pub fn start_protocol_controller(
     };
     peernet_config.max_in_connections = config.max_in_connections;
 
-    let network_controller = Box::new(NetworkControllerImpl::new(PeerNetManager::new(
-        peernet_config,
-    )));
+    let network_controller = Box::new(NetworkControllerImpl::new(
+        PeerNetManager::new(peernet_config),
+        traffic,
+    ));
 
     let connectivity_thread_handle = start_connectivity_thread(
         PeerId::from_public_key(keypair.get_public_key()),
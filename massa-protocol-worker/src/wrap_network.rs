@@ -1,10 +1,13 @@
 use std::{
     collections::{HashMap, HashSet},
     net::SocketAddr,
+    sync::atomic::{AtomicUsize, Ordering},
 };
 
 use massa_protocol_exports::{PeerId, ProtocolError};
 use peernet::{
+    error::PeerNetResult,
+    messages::MessagesSerializer as PeerNetMessagesSerializer,
     network_manager::{PeerNetManager, SharedActiveConnections},
     peer::PeerConnectionType,
     transports::TransportType,
@@ -13,7 +16,8 @@ use peernet::{
 use crate::{
     context::Context,
     handlers::peer_handler::MassaHandshake,
-    messages::{Message, MessagesHandler, MessagesSerializer},
+    messages::{Message, MessageTypeId, MessagesHandler, MessagesSerializer},
+    traffic::SharedTrafficAccounting,
 };
 
 #[cfg(test)]
@@ -46,7 +50,30 @@ impl Clone for Box<dyn ActiveConnectionsTrait> {
     }
 }
 
-impl ActiveConnectionsTrait for SharedActiveConnections<PeerId> {
+/// Serializer recording the size of the message it serializes, to account the outbound traffic
+struct MeasuringSerializer<'a> {
+    serializer: &'a MessagesSerializer,
+    size: AtomicUsize,
+}
+
+impl PeerNetMessagesSerializer<Message> for MeasuringSerializer<'_> {
+    fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> PeerNetResult<()> {
+        let start = buffer.len();
+        self.serializer.serialize(message, buffer)?;
+        self.size
+            .fetch_add(buffer.len().saturating_sub(start), Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// Active connections of peernet, accounting the messages sent to each peer
+#[derive(Clone)]
+pub struct PeerNetConnections {
+    connections: SharedActiveConnections<PeerId>,
+    traffic: SharedTrafficAccounting,
+}
+
+impl ActiveConnectionsTrait for PeerNetConnections {
     fn send_to_peer(
         &self,
         peer_id: &PeerId,
@@ -54,14 +81,22 @@ impl ActiveConnectionsTrait for SharedActiveConnections<PeerId> {
         message: Message,
         high_priority: bool,
     ) -> Result<(), ProtocolError> {
-        if let Some(connection) = self.read().connections.get(peer_id) {
+        let id = MessageTypeId::from(&message);
+        let serializer = MeasuringSerializer {
+            serializer: message_serializer,
+            size: AtomicUsize::new(0),
+        };
+        if let Some(connection) = self.connections.read().connections.get(peer_id) {
             connection
                 .send_channels
-                .try_send(message_serializer, message, high_priority)
-                .map_err(|err| ProtocolError::SendError(err.to_string()))
+                .try_send(&serializer, message, high_priority)
+                .map_err(|err| ProtocolError::SendError(err.to_string()))?;
         } else {
-            Err(ProtocolError::PeerDisconnected(peer_id.to_string()))
+            return Err(ProtocolError::PeerDisconnected(peer_id.to_string()));
         }
+        self.traffic
+            .record_outbound(peer_id, &id, serializer.size.into_inner());
+        Ok(())
     }
 
     fn clone_box(&self) -> Box<dyn ActiveConnectionsTrait> {
@@ -69,13 +104,19 @@ impl ActiveConnectionsTrait for SharedActiveConnections<PeerId> {
     }
 
     fn get_peer_ids_connected(&self) -> HashSet<PeerId> {
-        self.read().connections.keys().cloned().collect()
+        self.connections
+            .read()
+            .connections
+            .keys()
+            .cloned()
+            .collect()
     }
 
     fn get_peers_connected(
         &self,
     ) -> HashMap<PeerId, (SocketAddr, PeerConnectionType, Option<String>)> {
-        self.read()
+        self.connections
+            .read()
             .connections
             .iter()
             .map(|(peer_id, connection)| {
@@ -92,29 +133,29 @@ impl ActiveConnectionsTrait for SharedActiveConnections<PeerId> {
     }
 
     fn get_nb_out_connections(&self) -> usize {
-        self.read().nb_out_connections
+        self.connections.read().nb_out_connections
     }
 
     fn get_nb_in_connections(&self) -> usize {
-        self.read().nb_in_connections
+        self.connections.read().nb_in_connections
     }
 
     fn shutdown_connection(&mut self, peer_id: &PeerId) {
-        if let Some(connection) = self.write().connections.get_mut(peer_id) {
+        if let Some(connection) = self.connections.write().connections.get_mut(peer_id) {
             connection.shutdown();
         }
     }
 
     fn get_peers_connections_bandwidth(&self) -> HashMap<String, (u64, u64)> {
         let mut map = HashMap::new();
-        for (peerid, conn) in self.read().connections.iter() {
+        for (peerid, conn) in self.connections.read().connections.iter() {
             map.insert(peerid.to_string(), conn.endpoint.get_bandwidth());
         }
         map
     }
 
     fn get_peer_ids_out_connection_queue(&self) -> HashSet<SocketAddr> {
-        self.read().out_connection_queue.clone()
+        self.connections.read().out_connection_queue.clone()
     }
 }
 
@@ -142,19 +183,27 @@ pub trait NetworkController: Send + Sync {
 
 pub struct NetworkControllerImpl {
     peernet_manager: PeerNetManager<PeerId, Context, MassaHandshake, MessagesHandler>,
+    traffic: SharedTrafficAccounting,
 }
 
 impl NetworkControllerImpl {
     pub fn new(
         peernet_manager: PeerNetManager<PeerId, Context, MassaHandshake, MessagesHandler>,
+        traffic: SharedTrafficAccounting,
     ) -> Self {
-        Self { peernet_manager }
+        Self {
+            peernet_manager,
+            traffic,
+        }
     }
 }
 
 impl NetworkController for NetworkControllerImpl {
     fn get_active_connections(&self) -> Box<dyn ActiveConnectionsTrait> {
-        Box::new(self.peernet_manager.active_connections.clone())
+        Box::new(PeerNetConnections {
+            connections: self.peernet_manager.active_connections.clone(),
+            traffic: self.traffic.clone(),
+        })
     }
 
     fn start_listener(
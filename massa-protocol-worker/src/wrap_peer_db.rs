@@ -1,16 +1,29 @@
-use crate::handlers::peer_handler::models::{ConnectionMetadata, PeerInfo};
+use crate::handlers::peer_handler::models::{ConnectionMetadata, PeerConnection, PeerInfo};
 use std::{
     collections::{HashMap, HashSet},
     net::SocketAddr,
     time::Duration,
 };
 
+use massa_models::{
+    ban::{BanCause, BanInfo},
+    version::Version,
+};
 use massa_protocol_exports::{PeerId, TransportType};
+use massa_time::MassaTime;
 
 #[cfg_attr(test, mockall::automock)]
 pub trait PeerDBTrait: Send + Sync {
-    fn ban_peer(&mut self, peer_id: &PeerId);
+    fn ban_peer(
+        &mut self,
+        peer_id: &PeerId,
+        cause: BanCause,
+        ban_duration: MassaTime,
+        max_ban_duration: MassaTime,
+    );
     fn unban_peer(&mut self, peer_id: &PeerId);
+    fn get_bans(&self) -> &HashMap<PeerId, BanInfo>;
+    fn remove_expired_bans(&mut self, now: MassaTime) -> Vec<PeerId>;
     fn clone_box(&self) -> Box<dyn PeerDBTrait>;
     fn get_oldest_peer(
         &self,
@@ -33,8 +46,13 @@ pub trait PeerDBTrait: Send + Sync {
     fn insert_peer_in_test(&mut self, addr: &SocketAddr) -> bool;
     fn remove_peer_in_test(&mut self, addr: &SocketAddr) -> bool;
     fn get_peers_in_test(&self) -> &HashSet<SocketAddr>;
-    fn insert_tested_address(&mut self, addr: &SocketAddr, time: massa_time::MassaTime);
-    fn get_tested_addresses(&self) -> &HashMap<SocketAddr, massa_time::MassaTime>;
+    fn insert_tested_address(&mut self, addr: &SocketAddr, time: MassaTime);
+    fn get_tested_addresses(&self) -> &HashMap<SocketAddr, MassaTime>;
+    fn set_peer_version(&mut self, peer_id: &PeerId, version: Version);
+    fn get_peer_version(&self, peer_id: &PeerId) -> Option<Version>;
+    fn set_peer_connected(&mut self, peer_id: &PeerId, transport: TransportType);
+    fn get_peer_connection(&self, peer_id: &PeerId) -> Option<PeerConnection>;
+    fn retain_connected_peers(&mut self, connected: &HashSet<PeerId>);
 }
 
 impl Clone for Box<dyn PeerDBTrait> {
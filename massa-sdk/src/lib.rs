@@ -17,15 +17,23 @@ use jsonrpsee_ws_client as _;
 use massa_api_exports::page::PagedVecV2;
 use massa_api_exports::ApiRequest;
 use massa_api_exports::{
-    address::AddressInfo,
+    address::{AddressInfo, AddressStakingHistory},
+    async_pool::{AsyncMessagesRequest, AsyncPoolMessage},
     block::{BlockInfo, BlockSummary},
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
+    draws::{DrawsLookahead, DrawsLookaheadRequest, NewCycleDraws},
     endorsement::EndorsementInfo,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
-    node::NodeStatus,
+    execution::{
+        ExecuteReadOnlyResponse, ExecutionProfileInfo, ModuleCacheEntry, ModuleCacheInfo,
+        ReadOnlyBundle, ReadOnlyBytecodeExecution, ReadOnlyCall,
+    },
+    ledger::{LedgerAnalytics, LedgerAnalyticsRequest, LedgerTreeItems, LedgerTreePathInput},
+    node::{NodeBanInfo, NodeStatus},
     operation::{OperationInfo, OperationInput},
     TimeInterval,
 };
+use massa_async_pool::AsyncMessageEvent;
+use massa_hash::Hash;
 use massa_models::secure_share::SecureShare;
 use massa_models::{
     address::Address,
@@ -34,12 +42,14 @@ use massa_models::{
     block_id::BlockId,
     clique::Clique,
     composite::PubkeySig,
+    connection::ConnectionInfo,
     endorsement::EndorsementId,
     execution::EventFilter,
     node::NodeId,
     operation::{Operation, OperationId},
     output_event::SCOutputEvent,
     prehash::{PreHashMap, PreHashSet},
+    slot::Slot,
     version::Version,
 };
 use massa_proto_rs::massa::api::v1::private_service_client::PrivateServiceClient;
@@ -221,6 +231,89 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Returns the currently banned node ids with the cause and expiry of their ban
+    pub async fn node_bans(&self) -> RpcResult<Vec<NodeBanInfo>> {
+        self.http_client
+            .request("node_bans", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the module cache statistics
+    pub async fn node_module_cache_stats(&self) -> RpcResult<ModuleCacheInfo> {
+        self.http_client
+            .request("node_module_cache_stats", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the module cache status of the given bytecode hashes
+    pub async fn node_module_cache_entries(
+        &self,
+        hashes: Vec<Hash>,
+    ) -> RpcResult<Vec<ModuleCacheEntry>> {
+        self.http_client
+            .request("node_module_cache_entries", rpc_params![hashes])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Removes the given bytecode hashes from the module cache
+    pub async fn node_evict_modules(&self, hashes: Vec<Hash>) -> RpcResult<()> {
+        self.http_client
+            .request("node_evict_modules", rpc_params![hashes])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Pins the given bytecode hashes in the module cache
+    pub async fn node_pin_modules(&self, hashes: Vec<Hash>) -> RpcResult<()> {
+        self.http_client
+            .request("node_pin_modules", rpc_params![hashes])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Unpins the given bytecode hashes in the module cache
+    pub async fn node_unpin_modules(&self, hashes: Vec<Hash>) -> RpcResult<()> {
+        self.http_client
+            .request("node_unpin_modules", rpc_params![hashes])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the execution profile of the node, with the `top_n` most expensive contracts
+    pub async fn node_execution_profile(&self, top_n: usize) -> RpcResult<ExecutionProfileInfo> {
+        self.http_client
+            .request("node_execution_profile", rpc_params![top_n])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Devnet only: produces the block of the next slot immediately
+    pub async fn node_devnet_next_slot(&self) -> RpcResult<Slot> {
+        self.http_client
+            .request("node_devnet_next_slot", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Devnet only: fast-forwards the given number of periods
+    pub async fn node_devnet_fast_forward(&self, periods: u64) -> RpcResult<Slot> {
+        self.http_client
+            .request("node_devnet_fast_forward", rpc_params![periods])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Returns the details of every active peer connection
+    pub async fn get_peers(&self) -> RpcResult<Vec<ConnectionInfo>> {
+        self.http_client
+            .request("get_peers", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Returns node peers whitelist IP address(es).
     pub async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
         self.http_client
@@ -394,6 +487,63 @@ impl RpcClient {
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
+    /// Get the staking history of addresses, per cycle
+    pub async fn get_staking_history(
+        &self,
+        addresses: Vec<Address>,
+    ) -> RpcResult<Vec<AddressStakingHistory>> {
+        self.http_client
+            .request("get_staking_history", rpc_params![addresses])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Get aggregated views over the final ledger: supply breakdown, richest addresses
+    /// and datastore accounting of addresses
+    pub async fn get_ledger_analytics(
+        &self,
+        request: LedgerAnalyticsRequest,
+    ) -> RpcResult<LedgerAnalytics> {
+        self.http_client
+            .request("get_ledger_analytics", rpc_params![request])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Get the final values of ledger items with their paths in the node local ledger Merkle tree
+    pub async fn get_ledger_tree_paths(
+        &self,
+        inputs: Vec<LedgerTreePathInput>,
+    ) -> RpcResult<LedgerTreeItems> {
+        self.http_client
+            .request("get_ledger_tree_paths", rpc_params![inputs])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Get the next draws of addresses through the cycles available in the selector,
+    /// with their draw statistics
+    pub async fn get_draws_lookahead(
+        &self,
+        request: DrawsLookaheadRequest,
+    ) -> RpcResult<DrawsLookahead> {
+        self.http_client
+            .request("get_draws_lookahead", rpc_params![request])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Get the asynchronous messages of the candidate pool sent by or to an address
+    pub async fn get_async_messages(
+        &self,
+        request: AsyncMessagesRequest,
+    ) -> RpcResult<Vec<AsyncPoolMessage>> {
+        self.http_client
+            .request("get_async_messages", rpc_params![request])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
     /// Get datastore entries
     pub async fn get_datastore_entries(
         &self,
@@ -453,6 +603,17 @@ impl RpcClient {
                 to_error_obj("missing return value on execute_read_only_call".to_owned())
             })
     }
+
+    /// execute an ordered bundle of read only executions on top of state overrides
+    pub async fn execute_read_only_bundle(
+        &self,
+        bundle: ReadOnlyBundle,
+    ) -> RpcResult<Vec<ExecuteReadOnlyResponse>> {
+        self.http_client
+            .request("execute_read_only_bundle", rpc_params![bundle])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
 }
 
 /// Client V2
@@ -626,6 +787,40 @@ impl RpcClientV2 {
             Err(to_error_obj("no WebSocket client instance found".to_owned()).into())
         }
     }
+
+    /// New cycles whose selector draws are available.
+    pub async fn subscribe_new_cycle_draws(
+        &self,
+    ) -> Result<Subscription<NewCycleDraws>, jsonrpsee::core::Error> {
+        if let Some(client) = self.ws_client.as_ref() {
+            client
+                .subscribe(
+                    "subscribe_new_cycle_draws",
+                    rpc_params![],
+                    "unsubscribe_new_cycle_draws",
+                )
+                .await
+        } else {
+            Err(to_error_obj("no WebSocket client instance found".to_owned()).into())
+        }
+    }
+
+    /// Asynchronous messages executed, expired or evicted from the final pool.
+    pub async fn subscribe_async_message_events(
+        &self,
+    ) -> Result<Subscription<AsyncMessageEvent>, jsonrpsee::core::Error> {
+        if let Some(client) = self.ws_client.as_ref() {
+            client
+                .subscribe(
+                    "subscribe_async_message_events",
+                    rpc_params![],
+                    "unsubscribe_async_message_events",
+                )
+                .await
+        } else {
+            Err(to_error_obj("no WebSocket client instance found".to_owned()).into())
+        }
+    }
 }
 
 fn http_client_from_url(url: &str, http_config: &HttpConfig) -> HttpClient<HttpBackend> {
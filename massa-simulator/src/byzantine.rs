@@ -0,0 +1,154 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Misbehaviors of the staker of a simulated node, to check that the honest nodes denounce them
+
+use massa_hash::Hash;
+use massa_models::{
+    address::Address, amount::Amount, block_id::BlockId, config::THREAD_COUNT,
+    denunciation::DenunciationIndex, slot::Slot,
+};
+use massa_signature::KeyPair;
+use massa_test_framework::{BlockBuilder, EndorsementBuilder, OperationBuilder};
+
+use crate::node::SimulatedNode;
+
+/// Returns the first slot after `after` for which the staker of `node` is the block producer
+pub fn next_produced_slot(node: &SimulatedNode, after: Slot) -> Slot {
+    let staker_address = node.staker_address();
+    let mut slot = after;
+    loop {
+        slot = slot
+            .get_next_slot(THREAD_COUNT)
+            .expect("could not get next slot");
+        let producer = node
+            .selector_controller
+            .get_producer(slot)
+            .expect("could not get slot producer");
+        if producer == staker_address {
+            return slot;
+        }
+    }
+}
+
+/// Returns the first slot after `after` for which the staker of `node` is drawn
+/// for an endorsement, with the index of the endorsement
+pub fn next_endorsed_slot(node: &SimulatedNode, after: Slot) -> (Slot, u32) {
+    let staker_address = node.staker_address();
+    let mut slot = after;
+    loop {
+        slot = slot
+            .get_next_slot(THREAD_COUNT)
+            .expect("could not get next slot");
+        let selection = node
+            .selector_controller
+            .get_selection(slot)
+            .expect("could not get slot selection");
+        if let Some(index) = selection
+            .endorsements
+            .iter()
+            .position(|address| address == &staker_address)
+        {
+            return (slot, index as u32);
+        }
+    }
+}
+
+/// Makes the staker of `node` produce a second block at `slot`, on top of the best parents
+/// of its consensus, and propagates it.
+/// The staker must be the producer of the slot, so that the block conflicts with the one
+/// of its factory.
+///
+/// # Returns
+/// The id of the conflicting block and the index of the denunciation it deserves
+pub fn double_produce_block(node: &SimulatedNode, slot: Slot) -> (BlockId, DenunciationIndex) {
+    let producer = node
+        .selector_controller
+        .get_producer(slot)
+        .expect("could not get slot producer");
+    assert_eq!(
+        producer,
+        node.staker_address(),
+        "the staker of node {} is not the producer of slot {}",
+        node.index,
+        slot
+    );
+
+    // an operation of the block thread makes the content differ from the factory block
+    let sender = loop {
+        let keypair = KeyPair::generate(0).unwrap();
+        if Address::from_public_key(&keypair.get_public_key()).get_thread(THREAD_COUNT)
+            == slot.thread
+        {
+            break keypair;
+        }
+    };
+    let operation = OperationBuilder::new(&sender)
+        .expire_period(slot.period.saturating_add(10))
+        .transaction(node.staker_address(), Amount::zero())
+        .build();
+
+    let parents = node
+        .consensus_controller
+        .get_best_parents()
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    let block = BlockBuilder::new(&node.staker, slot)
+        .thread_count(THREAD_COUNT)
+        .parents(parents)
+        .operations(vec![operation.clone()])
+        .build();
+    let block_id = block.id;
+
+    let mut storage = node.storage.clone_without_refs();
+    storage.store_operations(vec![operation]);
+    storage.store_block(block);
+    node.consensus_controller
+        .register_block(block_id, slot, storage, true);
+    (block_id, DenunciationIndex::BlockHeader { slot })
+}
+
+/// Makes the staker of `node` sign two endorsements of index `index` at `slot`
+/// endorsing different blocks, and sends them to its pool and to its peers.
+/// The staker must be drawn for this endorsement.
+///
+/// # Returns
+/// The index of the denunciation the endorsements deserve
+pub fn double_endorse(node: &SimulatedNode, slot: Slot, index: u32) -> DenunciationIndex {
+    let selection = node
+        .selector_controller
+        .get_selection(slot)
+        .expect("could not get slot selection");
+    assert_eq!(
+        selection.endorsements.get(index as usize),
+        Some(&node.staker_address()),
+        "the staker of node {} is not drawn for endorsement {} of slot {}",
+        node.index,
+        index,
+        slot
+    );
+
+    let best_parent = node.consensus_controller.get_best_parents()[slot.thread as usize].0;
+    let endorsements = vec![
+        EndorsementBuilder::new(&node.staker, slot)
+            .index(index)
+            .endorsed_block(best_parent)
+            .build(),
+        EndorsementBuilder::new(&node.staker, slot)
+            .index(index)
+            .endorsed_block(BlockId::generate_from_hash(Hash::compute_from(
+                format!("conflicting endorsement of {}", best_parent).as_bytes(),
+            )))
+            .build(),
+    ];
+
+    let mut storage = node.storage.clone_without_refs();
+    storage.store_endorsements(endorsements);
+    node.pool_controller
+        .clone()
+        .add_endorsements(storage.clone());
+    node.protocol_controller
+        .propagate_endorsements(storage)
+        .expect("could not propagate endorsements");
+    DenunciationIndex::Endorsement { slot, index }
+}
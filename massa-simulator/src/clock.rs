@@ -0,0 +1,86 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Virtual clock shared by the simulated nodes
+
+use std::sync::Arc;
+
+use massa_models::{
+    config::THREAD_COUNT,
+    slot::Slot,
+    timeslots::{get_block_slot_timestamp, get_latest_block_slot_at_timestamp},
+};
+use massa_time::{Clock, ManualClock, MassaTime, SharedClock};
+
+/// Clock of a simulated network.
+///
+/// The workers of every node and the in-memory network read the time through the
+/// `SharedClock` of the simulation, which only moves when the simulation advances it.
+#[derive(Debug, Clone)]
+pub struct VirtualClock {
+    t0: MassaTime,
+    genesis_timestamp: MassaTime,
+    clock: Arc<ManualClock>,
+}
+
+impl VirtualClock {
+    pub(crate) fn new(t0: MassaTime, start: MassaTime, genesis_timestamp: MassaTime) -> Self {
+        Self {
+            t0,
+            genesis_timestamp,
+            clock: Arc::new(ManualClock::new(start)),
+        }
+    }
+
+    /// Clock to give to the workers of the nodes
+    pub fn shared(&self) -> SharedClock {
+        SharedClock::new(self.clock.clone())
+    }
+
+    /// Current time of the simulation
+    pub fn now(&self) -> MassaTime {
+        self.clock.now()
+    }
+
+    /// Moves the time of the simulation forward, without waiting
+    pub fn advance(&self, duration: MassaTime) {
+        self.clock.advance(duration);
+    }
+
+    /// Moves the time of the simulation forward by a number of slots
+    pub fn advance_slots(&self, slot_count: u64) {
+        self.advance(self.slot_duration().saturating_mul(slot_count));
+    }
+
+    /// Moves the time of the simulation to the timestamp of `slot`, if not reached yet
+    pub fn advance_to_slot(&self, slot: Slot) {
+        let timestamp =
+            get_block_slot_timestamp(THREAD_COUNT, self.t0, self.genesis_timestamp, slot)
+                .expect("could not compute slot timestamp");
+        if timestamp > self.now() {
+            self.clock.set(timestamp);
+        }
+    }
+
+    /// Latest slot reached by the simulation, if the genesis is reached
+    pub fn current_slot(&self) -> Option<Slot> {
+        get_latest_block_slot_at_timestamp(
+            THREAD_COUNT,
+            self.t0,
+            self.genesis_timestamp,
+            self.now(),
+        )
+        .expect("could not compute current slot")
+    }
+
+    /// Genesis timestamp of the simulated network
+    pub fn genesis_timestamp(&self) -> MassaTime {
+        self.genesis_timestamp
+    }
+
+    /// Duration of a slot on the clock of the simulation
+    pub fn slot_duration(&self) -> MassaTime {
+        self.t0
+            .checked_div_u64(THREAD_COUNT as u64)
+            .expect("t0 is not zero")
+    }
+}
@@ -0,0 +1,37 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Configuration of a simulated network
+
+use massa_models::{amount::Amount, config::THREAD_COUNT};
+use massa_time::MassaTime;
+
+/// Configuration of a simulated network
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    /// number of nodes started with the network, each one staking with its own key
+    pub node_count: usize,
+    /// time between the periods in the same thread
+    pub t0: MassaTime,
+    /// delay between the start of the simulation and the genesis
+    pub genesis_delay: MassaTime,
+    /// rolls of the staker of each node
+    pub staker_rolls: u64,
+    /// initial balance of the staker of each node
+    pub staker_balance: Amount,
+    /// seed of the randomness of the in-memory network (dropped messages)
+    pub seed: u64,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            node_count: 3,
+            // 50 ms slots
+            t0: MassaTime::from_millis(50 * THREAD_COUNT as u64),
+            genesis_delay: MassaTime::from_millis(2000),
+            staker_rolls: 100,
+            staker_balance: Amount::const_init(1_000_000, 0),
+            seed: 0,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! # General description
+//!
+//! The simulator runs a network of nodes in a single process, to test the behavior of the
+//! whole protocol (block propagation, finality, denunciations, bootstrap) deterministically
+//! and without opening real connections between the nodes.
+//!
+//! Every node runs the real selector, execution, pool, consensus, protocol and factory workers,
+//! wired like in `massa-node`. Their protocol workers exchange messages through an
+//! `InMemoryNetwork` which can delay or drop messages per link and partition the nodes.
+//! Only the bootstrap goes through loopback TCP, the bootstrap client and server being bound
+//! to TCP streams.
+//!
+//! ```ignore
+//! let simulation = Simulation::start(SimulationConfig::default());
+//! simulation.partition(&[&[0, 1], &[2]]);
+//! simulation.run_until(200, |s| s.latest_final_periods(0)[0] > 5);
+//! simulation.heal();
+//! ```
+//!
+//! The workers of the nodes and the in-memory network read the time from the `VirtualClock`
+//! of the simulation, a manual clock that the simulation moves slot by slot, waiting for the
+//! nodes to settle each slot: independent simulations can run concurrently.
+
+#![warn(missing_docs)]
+#![warn(unused_crate_dependencies)]
+
+mod byzantine;
+mod clock;
+mod config;
+mod node;
+mod simulation;
+
+pub use byzantine::{double_endorse, double_produce_block, next_endorsed_slot, next_produced_slot};
+pub use clock::VirtualClock;
+pub use config::SimulationConfig;
+pub use massa_protocol_worker::{InMemoryNetworkStats, LinkFault};
+pub use node::SimulatedNode;
+pub use simulation::Simulation;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,581 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! A node of the simulated network, wired like `massa-node` but without API,
+//! its protocol worker being connected to the in-memory network
+
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::{Arc, Condvar, Mutex},
+    time::Duration,
+};
+
+use massa_async_pool::AsyncPoolConfig;
+use massa_bootstrap::{
+    get_state, start_bootstrap_server, BootstrapConfig, BootstrapManager, BootstrapTcpListener,
+    DefaultConnector, IpType,
+};
+use massa_channel::{receiver::MassaReceiver, MassaChannel};
+use massa_consensus_exports::{
+    events::ConsensusEvent, ConsensusBroadcasts, ConsensusChannels, ConsensusConfig,
+    ConsensusController, ConsensusManager,
+};
+use massa_consensus_worker::start_consensus_worker;
+use massa_db_exports::{MassaDBConfig, MassaDBController};
+use massa_db_worker::MassaDB;
+use massa_executed_ops::{ExecutedDenunciationsConfig, ExecutedOpsConfig};
+use massa_execution_exports::{
+    ExecutionChannels, ExecutionConfig, ExecutionController, ExecutionManager,
+};
+use massa_execution_worker::start_execution_worker;
+use massa_factory_exports::{FactoryChannels, FactoryConfig, FactoryManager};
+use massa_factory_worker::start_factory;
+use massa_final_state::{FinalState, FinalStateConfig, FinalStateController};
+use massa_ledger_exports::LedgerConfig;
+use massa_ledger_worker::FinalLedger;
+use massa_metrics::MassaMetrics;
+use massa_models::{
+    address::Address,
+    config::{
+        BOOTSTRAP_RANDOMNESS_SIZE_BYTES, CHANNEL_SIZE, CONSENSUS_BOOTSTRAP_PART_SIZE,
+        DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT, GENESIS_KEY, INITIAL_DRAW_SEED,
+        KEEP_EXECUTED_HISTORY_EXTRA_PERIODS, MAX_ADVERTISE_LENGTH, MAX_ASYNC_POOL_LENGTH,
+        MAX_BOOTSTRAP_BLOCKS, MAX_BOOTSTRAP_ERROR_LENGTH, MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE,
+        MAX_BOOTSTRAP_VERSIONING_ELEMENTS_SIZE, MAX_CONSENSUS_BLOCKS_IDS,
+        MAX_DATASTORE_ENTRY_COUNT, MAX_DATASTORE_KEY_LENGTH, MAX_DATASTORE_VALUE_LENGTH,
+        MAX_DEFERRED_CREDITS_LENGTH, MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+        MAX_DENUNCIATION_CHANGES_LENGTH, MAX_EXECUTED_OPS_CHANGES_LENGTH, MAX_EXECUTED_OPS_LENGTH,
+        MAX_FUNCTION_NAME_LENGTH, MAX_LEDGER_CHANGES_COUNT, MAX_LISTENERS_PER_PEER,
+        MAX_OPERATIONS_PER_BLOCK, MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+        MAX_OPERATION_DATASTORE_KEY_LENGTH, MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        MAX_PARAMETERS_SIZE, MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH,
+        MIP_STORE_STATS_BLOCK_CONSIDERED, PERIODS_PER_CYCLE, POS_SAVED_CYCLES,
+        SELECTOR_DRAW_CACHE_SIZE, THREAD_COUNT, VERSION,
+    },
+    node::NodeId,
+    prehash::PreHashMap,
+};
+use massa_pool_exports::{PoolBroadcasts, PoolChannels, PoolConfig, PoolController, PoolManager};
+use massa_pool_worker::start_pool_controller;
+use massa_pos_exports::{
+    PoSConfig, SelectorChannels, SelectorConfig, SelectorController, SelectorManager,
+};
+use massa_pos_worker::start_selector_worker;
+use massa_protocol_exports::{
+    PeerId, ProtocolConfig, ProtocolController, ProtocolManager, TransportType,
+};
+use massa_protocol_worker::{
+    create_protocol_controller, start_protocol_controller_in_memory, InMemoryNetwork,
+};
+use massa_signature::KeyPair;
+use massa_storage::Storage;
+use massa_time::{MassaTime, SharedClock};
+use massa_versioning::versioning::{MipStatsConfig, MipStore};
+use massa_wallet::test_exports::create_test_wallet;
+use num::rational::Ratio;
+use parking_lot::RwLock;
+use tempfile::TempDir;
+use tokio::sync::broadcast;
+use tracing::info;
+
+/// Number of final slots kept in the history of the final state of each node
+const FINAL_HISTORY_LENGTH: usize = 100;
+
+/// Parameters shared by all the nodes of a simulation
+pub(crate) struct NodeSetup<'a> {
+    pub(crate) index: usize,
+    pub(crate) t0: MassaTime,
+    pub(crate) genesis_timestamp: MassaTime,
+    /// clock of the simulation, given to the workers
+    pub(crate) clock: SharedClock,
+    /// folder containing `initial_ledger.json` and `initial_rolls.json`
+    pub(crate) genesis_path: &'a Path,
+    pub(crate) network: &'a InMemoryNetwork,
+    /// bootstrap server to get the state from, instead of starting from the initial files
+    pub(crate) bootstrap_from: Option<(SocketAddr, NodeId)>,
+}
+
+/// A running node of the simulated network
+pub struct SimulatedNode {
+    /// index of the node in the simulation
+    pub index: usize,
+    /// keypair of the node, from which its peer id is derived
+    pub keypair: KeyPair,
+    /// peer id of the node in the in-memory network
+    pub peer_id: PeerId,
+    /// node id of the node
+    pub node_id: NodeId,
+    /// keypair staking on the node
+    pub staker: KeyPair,
+    /// storage shared by the workers of the node
+    pub storage: Storage,
+    /// final state of the node
+    pub final_state: Arc<RwLock<dyn FinalStateController>>,
+    /// consensus controller of the node
+    pub consensus_controller: Box<dyn ConsensusController>,
+    /// execution controller of the node
+    pub execution_controller: Box<dyn ExecutionController>,
+    /// pool controller of the node
+    pub pool_controller: Box<dyn PoolController>,
+    /// protocol controller of the node
+    pub protocol_controller: Box<dyn ProtocolController>,
+    /// selector controller of the node
+    pub selector_controller: Box<dyn SelectorController>,
+    bootstrap_config: BootstrapConfig,
+    massa_metrics: MassaMetrics,
+    managers: Option<NodeManagers>,
+    _consensus_event_receiver: MassaReceiver<ConsensusEvent>,
+    _dir: TempDir,
+}
+
+/// Handles stopping the workers of a node
+struct NodeManagers {
+    bootstrap: Option<BootstrapManager>,
+    factory: Box<dyn FactoryManager>,
+    protocol: Box<dyn ProtocolManager>,
+    consensus: Box<dyn ConsensusManager>,
+    pool: Box<dyn PoolManager>,
+    execution: Box<dyn ExecutionManager>,
+    selector: Box<dyn SelectorManager>,
+}
+
+impl SimulatedNode {
+    /// Starts the workers of a node staking with `staker`, and registers it in the network
+    pub(crate) fn start(setup: NodeSetup, keypair: KeyPair, staker: KeyPair) -> Self {
+        let dir = TempDir::new().expect("could not create node folder");
+        let storage = Storage::create_root();
+        let (massa_metrics, _) = MassaMetrics::new(
+            false,
+            "0.0.0.0:0".parse().unwrap(),
+            THREAD_COUNT,
+            Duration::ZERO,
+        );
+
+        // final state, from the initial files of the simulation
+        let ledger_config = LedgerConfig {
+            thread_count: THREAD_COUNT,
+            initial_ledger_path: setup.genesis_path.join("initial_ledger.json"),
+            max_key_length: MAX_DATASTORE_KEY_LENGTH,
+            max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+        };
+        let final_state_config = FinalStateConfig {
+            ledger_config: ledger_config.clone(),
+            async_pool_config: AsyncPoolConfig {
+                max_length: MAX_ASYNC_POOL_LENGTH,
+                thread_count: THREAD_COUNT,
+                max_function_length: MAX_FUNCTION_NAME_LENGTH,
+                max_function_params_length: MAX_PARAMETERS_SIZE as u64,
+                max_key_length: MAX_DATASTORE_KEY_LENGTH as u32,
+            },
+            pos_config: PoSConfig {
+                periods_per_cycle: PERIODS_PER_CYCLE,
+                thread_count: THREAD_COUNT,
+                cycle_history_length: POS_SAVED_CYCLES,
+                max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
+                max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
+                max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
+                initial_deferred_credits_path: None,
+            },
+            executed_ops_config: ExecutedOpsConfig {
+                thread_count: THREAD_COUNT,
+                keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
+            },
+            executed_denunciations_config: ExecutedDenunciationsConfig {
+                denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
+                thread_count: THREAD_COUNT,
+                endorsement_count: ENDORSEMENT_COUNT,
+                keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
+            },
+            final_history_length: FINAL_HISTORY_LENGTH,
+            thread_count: THREAD_COUNT,
+            periods_per_cycle: PERIODS_PER_CYCLE,
+            initial_seed_string: INITIAL_DRAW_SEED.into(),
+            initial_rolls_path: setup.genesis_path.join("initial_rolls.json"),
+            endorsement_count: ENDORSEMENT_COUNT,
+            max_executed_denunciations_length: MAX_DENUNCIATION_CHANGES_LENGTH,
+            max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+            t0: setup.t0,
+            genesis_timestamp: setup.genesis_timestamp,
+        };
+        let db_config = MassaDBConfig {
+            path: dir.path().join("ledger"),
+            max_history_length: FINAL_HISTORY_LENGTH,
+            max_final_state_elements_size: MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE.try_into().unwrap(),
+            max_versioning_elements_size: MAX_BOOTSTRAP_VERSIONING_ELEMENTS_SIZE
+                .try_into()
+                .unwrap(),
+            thread_count: THREAD_COUNT,
+        };
+        let db = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+        ));
+        let ledger = FinalLedger::new(ledger_config, db.clone());
+
+        let selector_config = SelectorConfig {
+            max_draw_cache: SELECTOR_DRAW_CACHE_SIZE,
+            channel_size: CHANNEL_SIZE,
+            thread_count: THREAD_COUNT,
+            endorsement_count: ENDORSEMENT_COUNT,
+            periods_per_cycle: PERIODS_PER_CYCLE,
+            genesis_address: Address::from_public_key(&GENESIS_KEY.get_public_key()),
+            broadcast_cycle_draws_channel_capacity: 128,
+        };
+        let selector_channels = SelectorChannels {
+            cycle_draws_sender: broadcast::channel(
+                selector_config.broadcast_cycle_draws_channel_capacity,
+            )
+            .0,
+        };
+        let (selector_manager, selector_controller) =
+            start_selector_worker(selector_config, selector_channels)
+                .expect("could not start selector worker");
+
+        let mip_store = MipStore::try_from((
+            [],
+            MipStatsConfig {
+                block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+                warn_announced_version_ratio: Ratio::new(30, 100),
+            },
+        ))
+        .expect("mip store creation failed");
+        let final_state: Arc<RwLock<dyn FinalStateController>> = Arc::new(RwLock::new(
+            FinalState::new(
+                db,
+                final_state_config,
+                Box::new(ledger),
+                selector_controller.clone(),
+                mip_store,
+                true,
+            )
+            .expect("could not init final state"),
+        ));
+
+        let bootstrap_config = bootstrap_config(setup.bootstrap_from.into_iter().collect());
+        // the bootstrap client compares the genesis with the real time:
+        // shift it by the advance of the simulation clock
+        let bootstrap_state = get_state(
+            &bootstrap_config,
+            final_state.clone(),
+            DefaultConnector,
+            *VERSION,
+            setup
+                .genesis_timestamp
+                .saturating_sub(setup.clock.now().saturating_sub(MassaTime::now())),
+            None,
+            None,
+            Arc::new((Mutex::new(false), Condvar::new())),
+            massa_metrics.clone(),
+        )
+        .expect("could not get the initial state of the node");
+        if setup.bootstrap_from.is_none() {
+            final_state.write().recompute_caches();
+            final_state
+                .write()
+                .compute_initial_draws()
+                .expect("could not compute initial draws");
+        }
+        let last_start_period = final_state.read().get_last_start_period();
+        let mip_store = final_state.read().get_mip_store().clone();
+
+        let staker_address = Address::from_public_key(&staker.get_public_key());
+        let wallet = Arc::new(RwLock::new(create_test_wallet(Some(
+            PreHashMap::from_iter([(staker_address, staker.clone())]),
+        ))));
+
+        let execution_config = ExecutionConfig {
+            t0: setup.t0,
+            genesis_timestamp: setup.genesis_timestamp,
+            last_start_period,
+            hd_cache_path: dir.path().join("hd_cache"),
+            module_call_stats_path: dir.path().join("module_call_stats"),
+            clock: setup.clock.clone(),
+            ..Default::default()
+        };
+        let execution_channels = ExecutionChannels {
+            slot_execution_output_sender: broadcast::channel(
+                execution_config.broadcast_slot_execution_output_channel_capacity,
+            )
+            .0,
+            async_message_event_sender: broadcast::channel(
+                execution_config.broadcast_async_message_event_channel_capacity,
+            )
+            .0,
+        };
+        let (execution_manager, execution_controller) = start_execution_worker(
+            execution_config,
+            final_state.clone(),
+            selector_controller.clone(),
+            mip_store.clone(),
+            execution_channels,
+            wallet.clone(),
+            massa_metrics.clone(),
+        );
+
+        let pool_config = PoolConfig {
+            t0: setup.t0,
+            genesis_timestamp: setup.genesis_timestamp,
+            last_start_period,
+            clock: setup.clock.clone(),
+            ..Default::default()
+        };
+        let pool_channels = PoolChannels {
+            broadcasts: PoolBroadcasts {
+                endorsement_sender: broadcast::channel(
+                    pool_config.broadcast_endorsements_channel_capacity,
+                )
+                .0,
+                operation_sender: broadcast::channel(
+                    pool_config.broadcast_operations_channel_capacity,
+                )
+                .0,
+            },
+            selector: selector_controller.clone(),
+            execution_controller: execution_controller.clone(),
+        };
+        let (pool_manager, pool_controller) =
+            start_pool_controller(pool_config, &storage, pool_channels, wallet.clone());
+
+        let mut protocol_config = ProtocolConfig {
+            t0: setup.t0,
+            genesis_timestamp: setup.genesis_timestamp,
+            last_start_period,
+            version: *VERSION,
+            clock: setup.clock.clone(),
+            ..Default::default()
+        };
+        protocol_config
+            .listeners
+            .insert(listener_address(setup.index), TransportType::Tcp);
+        let (protocol_controller, protocol_channels) =
+            create_protocol_controller(protocol_config.clone());
+
+        let consensus_config = ConsensusConfig {
+            t0: setup.t0,
+            genesis_timestamp: setup.genesis_timestamp,
+            last_start_period,
+            clock: setup.clock.clone(),
+            ..Default::default()
+        };
+        let (consensus_event_sender, consensus_event_receiver) =
+            MassaChannel::new("consensus_event".to_string(), Some(CHANNEL_SIZE));
+        let consensus_channels = ConsensusChannels {
+            execution_controller: execution_controller.clone(),
+            selector_controller: selector_controller.clone(),
+            pool_controller: pool_controller.clone(),
+            controller_event_tx: consensus_event_sender,
+            protocol_controller: protocol_controller.clone(),
+            broadcasts: ConsensusBroadcasts {
+                block_header_sender: broadcast::channel(
+                    consensus_config.broadcast_blocks_headers_channel_capacity,
+                )
+                .0,
+                block_sender: broadcast::channel(
+                    consensus_config.broadcast_blocks_channel_capacity,
+                )
+                .0,
+                filled_block_sender: broadcast::channel(
+                    consensus_config.broadcast_filled_blocks_channel_capacity,
+                )
+                .0,
+            },
+        };
+        let (consensus_controller, consensus_manager) = start_consensus_worker(
+            consensus_config,
+            consensus_channels,
+            bootstrap_state.graph,
+            storage.clone(),
+            massa_metrics.clone(),
+        );
+
+        let (protocol_manager, node_id) = start_protocol_controller_in_memory(
+            protocol_config,
+            setup.network,
+            &keypair,
+            selector_controller.clone(),
+            consensus_controller.clone(),
+            pool_controller.clone(),
+            storage.clone(),
+            protocol_channels,
+            mip_store.clone(),
+            massa_metrics.clone(),
+        )
+        .expect("could not start protocol controller");
+
+        let factory_config = FactoryConfig {
+            t0: setup.t0,
+            genesis_timestamp: setup.genesis_timestamp,
+            last_start_period,
+            clock: setup.clock.clone(),
+            ..Default::default()
+        };
+        let factory_channels = FactoryChannels {
+            selector: selector_controller.clone(),
+            consensus: consensus_controller.clone(),
+            pool: pool_controller.clone(),
+            protocol: protocol_controller.clone(),
+            storage: storage.clone(),
+        };
+        let (factory_manager, _factory_controller) =
+            start_factory(factory_config, wallet, factory_channels, mip_store);
+
+        info!(
+            "simulated node {} started with peer id {}",
+            setup.index,
+            PeerId::from_public_key(keypair.get_public_key())
+        );
+        SimulatedNode {
+            index: setup.index,
+            peer_id: PeerId::from_public_key(keypair.get_public_key()),
+            node_id,
+            keypair,
+            staker,
+            storage,
+            final_state,
+            consensus_controller,
+            execution_controller,
+            pool_controller,
+            protocol_controller,
+            selector_controller,
+            bootstrap_config,
+            massa_metrics,
+            managers: Some(NodeManagers {
+                bootstrap: None,
+                factory: factory_manager,
+                protocol: protocol_manager,
+                consensus: consensus_manager,
+                pool: pool_manager,
+                execution: execution_manager,
+                selector: selector_manager,
+            }),
+            _consensus_event_receiver: consensus_event_receiver,
+            _dir: dir,
+        }
+    }
+
+    /// Address of the staker of the node
+    pub fn staker_address(&self) -> Address {
+        Address::from_public_key(&self.staker.get_public_key())
+    }
+
+    /// Starts a bootstrap server listening on a loopback address, if not started yet,
+    /// so that other nodes can join the network from the state of this one.
+    ///
+    /// # Returns
+    /// The address of the server and the node id to expect from it
+    pub fn serve_bootstrap(&mut self) -> (SocketAddr, NodeId) {
+        let addr = bootstrap_address(self.index);
+        let managers = self.managers.as_mut().expect("node is stopped");
+        if managers.bootstrap.is_none() {
+            let (listener_stopper, listener) = BootstrapTcpListener::create(&addr)
+                .unwrap_or_else(|_| panic!("could not bind bootstrap server to {}", addr));
+            let mut config = self.bootstrap_config.clone();
+            config.listen_addr = Some(addr);
+            managers.bootstrap = Some(
+                start_bootstrap_server(
+                    listener,
+                    listener_stopper,
+                    self.consensus_controller.clone(),
+                    self.protocol_controller.clone(),
+                    self.final_state.clone(),
+                    config,
+                    self.keypair.clone(),
+                    *VERSION,
+                    self.massa_metrics.clone(),
+                )
+                .expect("could not start bootstrap server"),
+            );
+        }
+        (addr, self.node_id)
+    }
+
+    /// Stops the workers of the node, in the order of `massa-node`
+    pub fn stop(&mut self) {
+        let Some(mut managers) = self.managers.take() else {
+            return;
+        };
+        if let Some(bootstrap) = managers.bootstrap {
+            bootstrap.stop().expect("bootstrap server shutdown failed");
+        }
+        managers.factory.stop();
+        managers.protocol.stop();
+        managers.consensus.stop();
+        managers.pool.stop();
+        managers.execution.stop();
+        managers.selector.stop();
+        info!("simulated node {} stopped", self.index);
+    }
+}
+
+impl Drop for SimulatedNode {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Address of the in-memory listener of a node, never bound on the host
+fn listener_address(index: usize) -> SocketAddr {
+    SocketAddr::from(([127, 0, 0, 1], 31244u16.saturating_add(index as u16)))
+}
+
+/// Loopback address of the bootstrap server of a node
+fn bootstrap_address(index: usize) -> SocketAddr {
+    SocketAddr::from(([127, 0, 0, 1], 41245u16.saturating_add(index as u16)))
+}
+
+/// Bootstrap settings of a node: the clients of a simulation retry quickly
+/// and the servers accept any number of bootstraps from the loopback address.
+fn bootstrap_config(bootstrap_list: Vec<(SocketAddr, NodeId)>) -> BootstrapConfig {
+    BootstrapConfig {
+        bootstrap_list,
+        bootstrap_protocol: IpType::IPv4,
+        bootstrap_whitelist_path: PathBuf::new(),
+        bootstrap_blacklist_path: PathBuf::new(),
+        listen_addr: None,
+        connect_timeout: MassaTime::from_millis(1000),
+        bootstrap_timeout: MassaTime::from_millis(60000),
+        read_timeout: MassaTime::from_millis(10000),
+        write_timeout: MassaTime::from_millis(10000),
+        read_error_timeout: MassaTime::from_millis(200),
+        write_error_timeout: MassaTime::from_millis(200),
+        retry_delay: MassaTime::from_millis(200),
+        max_ping: MassaTime::from_millis(1000),
+        max_clock_delta: MassaTime::from_millis(1000),
+        cache_duration: MassaTime::from_millis(10000),
+        keep_ledger: false,
+        max_listeners_per_peer: MAX_LISTENERS_PER_PEER as u32,
+        max_simultaneous_bootstraps: 8,
+        per_ip_min_interval: MassaTime::from_millis(0),
+        ip_list_max_size: 10,
+        rate_limit: u64::MAX,
+        max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
+        randomness_size_bytes: BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
+        thread_count: THREAD_COUNT,
+        periods_per_cycle: PERIODS_PER_CYCLE,
+        endorsement_count: ENDORSEMENT_COUNT,
+        max_advertise_length: MAX_ADVERTISE_LENGTH,
+        max_bootstrap_blocks_length: MAX_BOOTSTRAP_BLOCKS,
+        max_bootstrap_error_length: MAX_BOOTSTRAP_ERROR_LENGTH,
+        max_final_state_elements_size: MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE,
+        max_versioning_elements_size: MAX_BOOTSTRAP_VERSIONING_ELEMENTS_SIZE,
+        max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
+        max_datastore_entry_count: MAX_DATASTORE_ENTRY_COUNT,
+        max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+        max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
+        max_ledger_changes_count: MAX_LEDGER_CHANGES_COUNT,
+        max_parameters_size: MAX_PARAMETERS_SIZE,
+        max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+        max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
+        max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        max_changes_slot_count: FINAL_HISTORY_LENGTH as u64,
+        max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
+        max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
+        max_credits_length: MAX_DEFERRED_CREDITS_LENGTH,
+        max_executed_ops_length: MAX_EXECUTED_OPS_LENGTH,
+        max_ops_changes_length: MAX_EXECUTED_OPS_CHANGES_LENGTH,
+        consensus_bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
+        max_consensus_block_ids: MAX_CONSENSUS_BLOCKS_IDS,
+        mip_store_stats_block_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+        max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+        max_denunciation_changes_length: MAX_DENUNCIATION_CHANGES_LENGTH,
+    }
+}
@@ -0,0 +1,271 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Simulation of a network of nodes running in the same process
+
+use std::time::{Duration, Instant};
+
+use massa_models::{address::Address, config::THREAD_COUNT, slot::Slot};
+use massa_protocol_exports::PeerId;
+use massa_protocol_worker::{InMemoryNetwork, InMemoryNetworkStats, LinkFault};
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+use serde_json::{json, Map, Value};
+use tempfile::TempDir;
+
+use crate::{
+    clock::VirtualClock,
+    config::SimulationConfig,
+    node::{NodeSetup, SimulatedNode},
+};
+
+/// Real time during which the network must stay idle for a slot to be settled
+const SETTLE_IDLE_TIME: Duration = Duration::from_millis(20);
+
+/// Longest real time given to the nodes to settle a slot
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A network of nodes connected through an in-memory transport.
+///
+/// Each node stakes with its own key, registered with the same rolls in the initial files.
+/// The time of the nodes is the `VirtualClock` of the simulation, moved slot by slot.
+/// The nodes are stopped when the simulation is dropped.
+pub struct Simulation {
+    config: SimulationConfig,
+    clock: VirtualClock,
+    network: InMemoryNetwork,
+    nodes: Vec<SimulatedNode>,
+    genesis_dir: TempDir,
+}
+
+impl Simulation {
+    /// Writes the initial files of the network, then starts and connects its nodes
+    pub fn start(config: SimulationConfig) -> Self {
+        assert!(
+            config.t0.as_millis() > 0 && config.t0.as_millis() % THREAD_COUNT as u64 == 0,
+            "t0 must be a non-zero multiple of the thread count"
+        );
+        let genesis_dir = TempDir::new().expect("could not create simulation folder");
+        let stakers: Vec<KeyPair> = (0..config.node_count)
+            .map(|_| KeyPair::generate(0).unwrap())
+            .collect();
+
+        let mut ledger = Map::new();
+        let mut rolls = Map::new();
+        for staker in &stakers {
+            let address = Address::from_public_key(&staker.get_public_key()).to_string();
+            ledger.insert(
+                address.clone(),
+                json!({
+                    "balance": config.staker_balance.to_string(),
+                    "datastore": [],
+                    "bytecode": [],
+                }),
+            );
+            rolls.insert(address, json!(config.staker_rolls));
+        }
+        write_json(
+            genesis_dir.path().join("initial_ledger.json"),
+            Value::Object(ledger),
+        );
+        write_json(
+            genesis_dir.path().join("initial_rolls.json"),
+            Value::Object(rolls),
+        );
+
+        // the clock starts at the real time so that the initial nodes start before the genesis
+        let start = MassaTime::now();
+        let clock = VirtualClock::new(config.t0, start, start.saturating_add(config.genesis_delay));
+        let mut simulation = Simulation {
+            network: InMemoryNetwork::new(config.seed, clock.shared()),
+            clock,
+            nodes: Vec::with_capacity(config.node_count),
+            config,
+            genesis_dir,
+        };
+        for staker in stakers {
+            simulation.start_node(staker, None);
+        }
+        simulation.network.connect_all();
+        simulation
+    }
+
+    fn start_node(&mut self, staker: KeyPair, bootstrap_from: Option<usize>) -> usize {
+        let index = self.nodes.len();
+        let bootstrap_from = bootstrap_from.map(|source| self.nodes[source].serve_bootstrap());
+        let node = SimulatedNode::start(
+            NodeSetup {
+                index,
+                t0: self.config.t0,
+                genesis_timestamp: self.clock.genesis_timestamp(),
+                clock: self.clock.shared(),
+                genesis_path: self.genesis_dir.path(),
+                network: &self.network,
+                bootstrap_from,
+            },
+            KeyPair::generate(0).unwrap(),
+            staker,
+        );
+        self.nodes.push(node);
+        index
+    }
+
+    /// Starts a node getting its state from the bootstrap server of node `source`,
+    /// and connects it to the nodes it can reach.
+    /// Its staker has no rolls.
+    ///
+    /// # Returns
+    /// The index of the new node
+    pub fn add_node_bootstrapped_from(&mut self, source: usize) -> usize {
+        let index = self.start_node(KeyPair::generate(0).unwrap(), Some(source));
+        self.network.connect_all();
+        index
+    }
+
+    /// Returns the node of index `index`
+    pub fn node(&self, index: usize) -> &SimulatedNode {
+        &self.nodes[index]
+    }
+
+    /// Returns the nodes of the simulation
+    pub fn nodes(&self) -> &[SimulatedNode] {
+        &self.nodes
+    }
+
+    /// Returns the in-memory network connecting the nodes
+    pub fn network(&self) -> &InMemoryNetwork {
+        &self.network
+    }
+
+    /// Returns the clock of the simulation
+    pub fn clock(&self) -> &VirtualClock {
+        &self.clock
+    }
+
+    /// Returns the configuration of the simulation
+    pub fn config(&self) -> &SimulationConfig {
+        &self.config
+    }
+
+    fn peer_id(&self, index: usize) -> PeerId {
+        self.nodes[index].peer_id
+    }
+
+    /// Splits the nodes into groups that can only communicate inside their group
+    pub fn partition(&self, groups: &[&[usize]]) {
+        self.network.partition(
+            groups
+                .iter()
+                .map(|group| group.iter().map(|index| self.peer_id(*index)).collect())
+                .collect(),
+        );
+    }
+
+    /// Removes the partition and reconnects all the nodes
+    pub fn heal(&self) {
+        self.network.heal();
+    }
+
+    /// Sets the faults of the messages sent from node `from` to node `to`
+    pub fn set_link_fault(&self, from: usize, to: usize, fault: LinkFault) {
+        self.network
+            .set_link_fault(&self.peer_id(from), &self.peer_id(to), fault);
+    }
+
+    /// Returns the message counters of the network
+    pub fn network_stats(&self) -> InMemoryNetworkStats {
+        self.network.get_stats()
+    }
+
+    /// Latest final period of each thread, as seen by the consensus of a node
+    pub fn latest_final_periods(&self, index: usize) -> Vec<u64> {
+        self.nodes[index]
+            .consensus_controller
+            .get_block_graph_status(None, None)
+            .expect("could not get block graph status")
+            .latest_final_blocks_periods
+            .into_iter()
+            .map(|(_, period)| period)
+            .collect()
+    }
+
+    /// Moves the clock to the next slot, then lets the nodes process it
+    ///
+    /// # Returns
+    /// The slot reached
+    pub fn step(&self) -> Slot {
+        let slot = match self.clock.current_slot() {
+            Some(slot) => slot
+                .get_next_slot(THREAD_COUNT)
+                .expect("could not get next slot"),
+            None => Slot::new(0, 0),
+        };
+        self.clock.advance_to_slot(slot);
+        self.settle(slot);
+        slot
+    }
+
+    /// Waits in real time until the producer of `slot` has created its block
+    /// and no message has gone through the network for `SETTLE_IDLE_TIME`
+    fn settle(&self, slot: Slot) {
+        let producer = self.nodes.iter().find(|node| {
+            node.selector_controller.get_producer(slot).ok() == Some(node.staker_address())
+        });
+        let deadline = Instant::now() + SETTLE_TIMEOUT;
+        let mut stats = self.network_stats();
+        let mut idle_since = Instant::now();
+        loop {
+            std::thread::sleep(Duration::from_millis(2));
+            let now = Instant::now();
+            let new_stats = self.network_stats();
+            if new_stats != stats {
+                stats = new_stats;
+                idle_since = now;
+            }
+            let produced = producer.map_or(true, |node| {
+                node.storage
+                    .read_blocks()
+                    .get_blocks_by_slot(&slot)
+                    .map_or(false, |ids| !ids.is_empty())
+            });
+            if (produced && now.duration_since(idle_since) >= SETTLE_IDLE_TIME) || now >= deadline {
+                return;
+            }
+        }
+    }
+
+    /// Steps the simulation until `predicate` holds, for at most `max_slots` slots
+    ///
+    /// # Returns
+    /// Whether the predicate held within these slots
+    pub fn run_until(&self, max_slots: u64, mut predicate: impl FnMut(&Self) -> bool) -> bool {
+        for _ in 0..max_slots {
+            if predicate(self) {
+                return true;
+            }
+            self.step();
+        }
+        predicate(self)
+    }
+
+    /// Stops all the nodes and the network
+    pub fn stop(&mut self) {
+        for node in self.nodes.iter_mut() {
+            node.stop();
+        }
+        self.network.stop();
+    }
+}
+
+impl Drop for Simulation {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn write_json(path: std::path::PathBuf, value: Value) {
+    std::fs::write(
+        &path,
+        serde_json::to_string_pretty(&value).expect("could not serialize initial file"),
+    )
+    .unwrap_or_else(|err| panic!("could not write {}: {}", path.display(), err));
+}
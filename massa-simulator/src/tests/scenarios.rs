@@ -0,0 +1,189 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::{config::THREAD_COUNT, slot::Slot};
+use massa_time::MassaTime;
+
+use crate::{
+    double_endorse, double_produce_block, next_endorsed_slot, next_produced_slot, LinkFault,
+    Simulation, SimulationConfig,
+};
+
+/// Slots given to the network to reach a state
+const MAX_SLOTS: u64 = 20 * THREAD_COUNT as u64;
+
+/// Runs the simulation until every node has finalized `period` in every thread
+fn wait_for_final_period(simulation: &Simulation, period: u64) -> bool {
+    simulation.run_until(MAX_SLOTS, |simulation| {
+        (0..simulation.nodes().len()).all(|index| {
+            simulation
+                .latest_final_periods(index)
+                .iter()
+                .all(|final_period| *final_period >= period)
+        })
+    })
+}
+
+/// Checks that all the nodes have the same block at each slot of the final periods up to `period`
+fn assert_same_final_blocks(simulation: &Simulation, period: u64) {
+    for period in 1..=period {
+        for thread in 0..THREAD_COUNT {
+            let slot = Slot::new(period, thread);
+            let expected = simulation
+                .node(0)
+                .consensus_controller
+                .get_blockclique_block_at_slot(slot);
+            for node in simulation.nodes() {
+                assert_eq!(
+                    node.consensus_controller
+                        .get_blockclique_block_at_slot(slot),
+                    expected,
+                    "node {} disagrees on the block of slot {}",
+                    node.index,
+                    slot
+                );
+            }
+        }
+    }
+}
+
+/// Current slot of the simulation, or the last slot of the genesis period
+fn current_slot(simulation: &Simulation) -> Slot {
+    simulation
+        .clock()
+        .current_slot()
+        .unwrap_or_else(|| Slot::new(0, THREAD_COUNT - 1))
+}
+
+/// Runs the simulation until its clock reaches `slot`
+fn run_to_slot(simulation: &Simulation, slot: Slot) {
+    assert!(simulation.run_until(MAX_SLOTS, |simulation| current_slot(simulation) >= slot));
+}
+
+#[test]
+fn test_nodes_agree_on_final_blocks() {
+    let simulation = Simulation::start(SimulationConfig::default());
+
+    assert!(
+        wait_for_final_period(&simulation, 3),
+        "the network did not finalize period 3"
+    );
+    assert_same_final_blocks(&simulation, 3);
+    assert!(simulation.network_stats().delivered > 0);
+    assert_eq!(simulation.network_stats().dropped, 0);
+}
+
+#[test]
+fn test_network_converges_after_partition() {
+    let simulation = Simulation::start(SimulationConfig::default());
+    assert!(wait_for_final_period(&simulation, 1));
+
+    // a short partition, the isolated node falling behind the majority
+    simulation.partition(&[&[0, 1], &[2]]);
+    assert!(simulation
+        .network()
+        .get_connected_peers(&simulation.node(2).peer_id)
+        .is_empty());
+    let partition_end = current_slot(&simulation).period.saturating_add(2);
+    run_to_slot(&simulation, Slot::new(partition_end, 0));
+    simulation.heal();
+    assert_eq!(
+        simulation
+            .network()
+            .get_connected_peers(&simulation.node(2).peer_id)
+            .len(),
+        2
+    );
+
+    let target = partition_end.saturating_add(3);
+    assert!(
+        wait_for_final_period(&simulation, target),
+        "the network did not finalize period {} after healing",
+        target
+    );
+    assert_same_final_blocks(&simulation, target);
+}
+
+#[test]
+fn test_link_faults_drop_messages() {
+    let simulation = Simulation::start(SimulationConfig::default());
+    simulation.set_link_fault(
+        0,
+        1,
+        LinkFault {
+            delay: MassaTime::from_millis(10),
+            drop_rate: 1.0,
+        },
+    );
+
+    assert!(simulation.run_until(MAX_SLOTS, |simulation| {
+        simulation.network_stats().dropped > 0
+    }));
+    // node 1 still learns the blocks of node 0 through node 2
+    assert!(wait_for_final_period(&simulation, 2));
+    assert_same_final_blocks(&simulation, 2);
+}
+
+#[test]
+fn test_double_block_production_is_denounced() {
+    let simulation = Simulation::start(SimulationConfig::default());
+
+    let slot = next_produced_slot(simulation.node(0), current_slot(&simulation));
+    run_to_slot(&simulation, slot);
+    let (_, denunciation_index) = double_produce_block(simulation.node(0), slot);
+
+    // the denunciation is included in a block and executed by every node once final
+    assert!(
+        simulation.run_until(MAX_SLOTS, |simulation| simulation.nodes().iter().all(
+            |node| {
+                node.final_state
+                    .read()
+                    .get_executed_denunciations()
+                    .contains(&denunciation_index)
+            }
+        )),
+        "the double block production at slot {} was not denounced",
+        slot
+    );
+}
+
+#[test]
+fn test_double_endorsement_is_denounced() {
+    let simulation = Simulation::start(SimulationConfig::default());
+
+    let (slot, index) = next_endorsed_slot(simulation.node(1), current_slot(&simulation));
+    let denunciation_index = double_endorse(simulation.node(1), slot, index);
+
+    assert!(
+        simulation.run_until(MAX_SLOTS, |simulation| simulation.nodes().iter().all(
+            |node| {
+                node.final_state
+                    .read()
+                    .get_executed_denunciations()
+                    .contains(&denunciation_index)
+            }
+        )),
+        "the double endorsement {} at slot {} was not denounced",
+        index,
+        slot
+    );
+}
+
+#[test]
+fn test_node_bootstraps_from_running_node() {
+    let mut simulation = Simulation::start(SimulationConfig::default());
+    assert!(wait_for_final_period(&simulation, 2));
+
+    let index = simulation.add_node_bootstrapped_from(0);
+    assert_eq!(index, 3);
+    assert!(!simulation
+        .network()
+        .get_connected_peers(&simulation.node(index).peer_id)
+        .is_empty());
+
+    let target = current_slot(&simulation).period.saturating_add(2);
+    assert!(
+        wait_for_final_period(&simulation, target),
+        "the bootstrapped node did not follow the network"
+    );
+    assert_same_final_blocks(&simulation, target);
+}
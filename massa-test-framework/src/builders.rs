@@ -0,0 +1,428 @@
+//! Builders of signed blocks, headers, endorsements, operations and denunciations for tests.
+//!
+//! Every builder starts from defaults that pass the structural checks of the node
+//! (parents in every thread, endorsed block matching the parent of the slot thread,
+//! merkle root computed from the operations) and can be overridden field by field.
+
+use massa_hash::Hash;
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    block::{Block, BlockSerializer, SecureShareBlock},
+    block_header::{BlockHeader, BlockHeaderSerializer, SecuredHeader},
+    block_id::BlockId,
+    denunciation::Denunciation,
+    endorsement::{Endorsement, EndorsementSerializer, SecureShareEndorsement},
+    operation::{
+        compute_operations_hash, Operation, OperationIdSerializer, OperationSerializer,
+        OperationType, SecureShareOperation,
+    },
+    secure_share::SecureShareContent,
+    slot::Slot,
+};
+use massa_signature::KeyPair;
+
+/// Thread count used by the builders when none is given, matching most test configurations
+pub const DEFAULT_THREAD_COUNT: u8 = 2;
+
+/// Returns the genesis blocks of a network, identical to the ones created by consensus
+/// from the same genesis key and last start period.
+pub fn genesis_blocks(
+    genesis_key: &KeyPair,
+    thread_count: u8,
+    last_start_period: u64,
+) -> Vec<SecureShareBlock> {
+    (0..thread_count)
+        .map(|thread| {
+            BlockBuilder::new(genesis_key, Slot::new(last_start_period, thread))
+                .parents(Vec::new())
+                .operation_merkle_root(Hash::compute_from(&Vec::new()))
+                .build()
+        })
+        .collect()
+}
+
+/// Returns a placeholder genesis block id, for tests in which the parents are never resolved
+pub fn placeholder_genesis_id(thread: u8) -> BlockId {
+    BlockId::generate_from_hash(Hash::compute_from(format!("Genesis {}", thread).as_bytes()))
+}
+
+/// Returns the placeholder genesis block ids of all threads
+pub fn placeholder_genesis_ids(thread_count: u8) -> Vec<BlockId> {
+    (0..thread_count).map(placeholder_genesis_id).collect()
+}
+
+/// Builder of signed blocks and block headers
+#[derive(Clone)]
+pub struct BlockBuilder {
+    creator: KeyPair,
+    slot: Slot,
+    thread_count: u8,
+    parents: Option<Vec<BlockId>>,
+    operations: Vec<SecureShareOperation>,
+    endorsements: Vec<SecureShareEndorsement>,
+    denunciations: Vec<Denunciation>,
+    operation_merkle_root: Option<Hash>,
+    current_version: u32,
+    announced_version: Option<u32>,
+}
+
+impl BlockBuilder {
+    /// Starts a block produced by `creator` at `slot`, with placeholder genesis parents
+    /// and no content.
+    pub fn new(creator: &KeyPair, slot: Slot) -> Self {
+        Self {
+            creator: creator.clone(),
+            slot,
+            thread_count: DEFAULT_THREAD_COUNT,
+            parents: None,
+            operations: Vec::new(),
+            endorsements: Vec::new(),
+            denunciations: Vec::new(),
+            operation_merkle_root: None,
+            current_version: 0,
+            announced_version: None,
+        }
+    }
+
+    /// Sets the thread count, used for the default parents
+    pub fn thread_count(mut self, thread_count: u8) -> Self {
+        self.thread_count = thread_count;
+        self
+    }
+
+    /// Sets the parents, one per thread
+    pub fn parents(mut self, parents: Vec<BlockId>) -> Self {
+        self.parents = Some(parents);
+        self
+    }
+
+    /// Uses the real genesis blocks created from `genesis_key` as parents
+    pub fn genesis_parents(mut self, genesis_key: &KeyPair) -> Self {
+        self.parents = Some(
+            genesis_blocks(genesis_key, self.thread_count, 0)
+                .into_iter()
+                .map(|block| block.id)
+                .collect(),
+        );
+        self
+    }
+
+    /// Sets the operations, the merkle root being computed from their ids
+    pub fn operations(mut self, operations: Vec<SecureShareOperation>) -> Self {
+        self.operations = operations;
+        self
+    }
+
+    /// Sets the endorsements included in the header
+    pub fn endorsements(mut self, endorsements: Vec<SecureShareEndorsement>) -> Self {
+        self.endorsements = endorsements;
+        self
+    }
+
+    /// Sets the denunciations included in the header
+    pub fn denunciations(mut self, denunciations: Vec<Denunciation>) -> Self {
+        self.denunciations = denunciations;
+        self
+    }
+
+    /// Overrides the operation merkle root instead of computing it from the operations
+    pub fn operation_merkle_root(mut self, operation_merkle_root: Hash) -> Self {
+        self.operation_merkle_root = Some(operation_merkle_root);
+        self
+    }
+
+    /// Sets the current and announced versions of the header
+    pub fn versions(mut self, current_version: u32, announced_version: Option<u32>) -> Self {
+        self.current_version = current_version;
+        self.announced_version = announced_version;
+        self
+    }
+
+    /// Returns the parents the block will be built with
+    pub fn get_parents(&self) -> Vec<BlockId> {
+        self.parents
+            .clone()
+            .unwrap_or_else(|| placeholder_genesis_ids(self.thread_count))
+    }
+
+    /// Builds the signed header
+    pub fn build_header(&self) -> SecuredHeader {
+        let op_ids = self.operations.iter().map(|op| op.id).collect::<Vec<_>>();
+        let operation_merkle_root = self
+            .operation_merkle_root
+            .unwrap_or_else(|| compute_operations_hash(&op_ids, &OperationIdSerializer::new()));
+        BlockHeader::new_verifiable(
+            BlockHeader {
+                current_version: self.current_version,
+                announced_version: self.announced_version,
+                slot: self.slot,
+                parents: self.get_parents(),
+                operation_merkle_root,
+                endorsements: self.endorsements.clone(),
+                denunciations: self.denunciations.clone(),
+            },
+            BlockHeaderSerializer::new(),
+            &self.creator,
+        )
+        .unwrap()
+    }
+
+    /// Builds the signed block
+    pub fn build(&self) -> SecureShareBlock {
+        Block::new_verifiable(
+            Block {
+                header: self.build_header(),
+                operations: self.operations.iter().map(|op| op.id).collect(),
+            },
+            BlockSerializer::new(),
+            &self.creator,
+        )
+        .unwrap()
+    }
+}
+
+/// Builder of signed endorsements
+#[derive(Clone)]
+pub struct EndorsementBuilder {
+    creator: KeyPair,
+    slot: Slot,
+    index: u32,
+    endorsed_block: Option<BlockId>,
+}
+
+impl EndorsementBuilder {
+    /// Starts an endorsement of index 0 created by `creator` at `slot`,
+    /// endorsing the placeholder genesis block of the slot thread.
+    pub fn new(creator: &KeyPair, slot: Slot) -> Self {
+        Self {
+            creator: creator.clone(),
+            slot,
+            index: 0,
+            endorsed_block: None,
+        }
+    }
+
+    /// Sets the endorsement index
+    pub fn index(mut self, index: u32) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Sets the endorsed block, which must be the parent of the block in the slot thread
+    pub fn endorsed_block(mut self, endorsed_block: BlockId) -> Self {
+        self.endorsed_block = Some(endorsed_block);
+        self
+    }
+
+    /// Endorses the parent in the slot thread of the block being built by `block`
+    pub fn endorsing_parent_of(self, block: &BlockBuilder) -> Self {
+        let parent = block.get_parents()[self.slot.thread as usize];
+        self.endorsed_block(parent)
+    }
+
+    /// Builds the signed endorsement
+    pub fn build(&self) -> SecureShareEndorsement {
+        let endorsed_block = self
+            .endorsed_block
+            .unwrap_or_else(|| placeholder_genesis_id(self.slot.thread));
+        Endorsement::new_verifiable(
+            Endorsement {
+                slot: self.slot,
+                index: self.index,
+                endorsed_block,
+            },
+            EndorsementSerializer::new(),
+            &self.creator,
+        )
+        .unwrap()
+    }
+}
+
+/// Builder of signed operations
+#[derive(Clone)]
+pub struct OperationBuilder {
+    creator: KeyPair,
+    fee: Amount,
+    expire_period: u64,
+    op: Option<OperationType>,
+}
+
+impl OperationBuilder {
+    /// Starts an operation of `creator` without fee, expiring at period 10,
+    /// transferring nothing to a fresh address.
+    pub fn new(creator: &KeyPair) -> Self {
+        Self {
+            creator: creator.clone(),
+            fee: Amount::default(),
+            expire_period: 10,
+            op: None,
+        }
+    }
+
+    /// Sets the fee
+    pub fn fee(mut self, fee: Amount) -> Self {
+        self.fee = fee;
+        self
+    }
+
+    /// Sets the expire period
+    pub fn expire_period(mut self, expire_period: u64) -> Self {
+        self.expire_period = expire_period;
+        self
+    }
+
+    /// Sets the operation type
+    pub fn op(mut self, op: OperationType) -> Self {
+        self.op = Some(op);
+        self
+    }
+
+    /// Makes the operation a transaction
+    pub fn transaction(self, recipient_address: Address, amount: Amount) -> Self {
+        self.op(OperationType::Transaction {
+            recipient_address,
+            amount,
+        })
+    }
+
+    /// Makes the operation a roll buy
+    pub fn roll_buy(self, roll_count: u64) -> Self {
+        self.op(OperationType::RollBuy { roll_count })
+    }
+
+    /// Makes the operation a roll sell
+    pub fn roll_sell(self, roll_count: u64) -> Self {
+        self.op(OperationType::RollSell { roll_count })
+    }
+
+    /// Builds the signed operation
+    pub fn build(&self) -> SecureShareOperation {
+        let op = self.op.clone().unwrap_or_else(|| {
+            let recv_keypair = KeyPair::generate(0).unwrap();
+            OperationType::Transaction {
+                recipient_address: Address::from_public_key(&recv_keypair.get_public_key()),
+                amount: Amount::default(),
+            }
+        });
+        Operation::new_verifiable(
+            Operation {
+                fee: self.fee,
+                op,
+                expire_period: self.expire_period,
+            },
+            OperationSerializer::new(),
+            &self.creator,
+        )
+        .unwrap()
+    }
+}
+
+/// Builder of denunciations, signing the two conflicting items they prove
+#[derive(Clone)]
+pub struct DenunciationBuilder {
+    creator: KeyPair,
+    slot: Slot,
+    endorsement_index: Option<u32>,
+}
+
+impl DenunciationBuilder {
+    /// Starts a denunciation of `creator` for producing two block headers at `slot`
+    pub fn new(creator: &KeyPair, slot: Slot) -> Self {
+        Self {
+            creator: creator.clone(),
+            slot,
+            endorsement_index: None,
+        }
+    }
+
+    /// Denounces two endorsements of the given index instead of two block headers
+    pub fn endorsements(mut self, index: u32) -> Self {
+        self.endorsement_index = Some(index);
+        self
+    }
+
+    /// Builds the denunciation from two conflicting items signed by the creator
+    pub fn build(&self) -> Denunciation {
+        match self.endorsement_index {
+            Some(index) => {
+                let endorsements: Vec<_> = ["Genesis A", "Genesis B"]
+                    .iter()
+                    .map(|seed| {
+                        EndorsementBuilder::new(&self.creator, self.slot)
+                            .index(index)
+                            .endorsed_block(BlockId::generate_from_hash(Hash::compute_from(
+                                seed.as_bytes(),
+                            )))
+                            .build()
+                    })
+                    .collect();
+                Denunciation::try_from((&endorsements[0], &endorsements[1])).unwrap()
+            }
+            None => {
+                let headers: Vec<_> = ["content A", "content B"]
+                    .iter()
+                    .map(|seed| {
+                        BlockBuilder::new(&self.creator, self.slot)
+                            .operation_merkle_root(Hash::compute_from(seed.as_bytes()))
+                            .build_header()
+                    })
+                    .collect();
+                Denunciation::try_from((&headers[0], &headers[1])).unwrap()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_built_items_verify() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let slot = Slot::new(1, 1);
+
+        let operation = OperationBuilder::new(&keypair).roll_buy(1).build();
+        operation.verify_signature().unwrap();
+
+        let block_builder = BlockBuilder::new(&keypair, slot).operations(vec![operation.clone()]);
+        let endorsement = EndorsementBuilder::new(&keypair, slot)
+            .endorsing_parent_of(&block_builder)
+            .build();
+        endorsement.verify_signature().unwrap();
+        assert_eq!(
+            endorsement.content.endorsed_block,
+            placeholder_genesis_id(1)
+        );
+
+        let block = block_builder
+            .endorsements(vec![endorsement])
+            .denunciations(vec![
+                DenunciationBuilder::new(&keypair, Slot::new(0, 1)).build()
+            ])
+            .build();
+        block.verify_signature().unwrap();
+        block.content.header.verify_signature().unwrap();
+        assert_eq!(block.content.operations, vec![operation.id]);
+        assert_eq!(
+            block.content.header.content.operation_merkle_root,
+            compute_operations_hash(&[operation.id], &OperationIdSerializer::new())
+        );
+        assert_eq!(
+            block.content.header.content.parents,
+            placeholder_genesis_ids(DEFAULT_THREAD_COUNT)
+        );
+
+        for denunciation in [
+            DenunciationBuilder::new(&keypair, slot).build(),
+            DenunciationBuilder::new(&keypair, slot)
+                .endorsements(3)
+                .build(),
+        ] {
+            assert!(denunciation.is_valid());
+            assert_eq!(denunciation.get_slot(), &slot);
+            assert_eq!(denunciation.get_public_key(), &keypair.get_public_key());
+        }
+    }
+}
@@ -1,20 +1,17 @@
 use std::sync::{Arc, Condvar, Mutex};
 
-use massa_hash::Hash;
+mod builders;
+mod scenario;
+
+pub use builders::{
+    genesis_blocks, placeholder_genesis_id, placeholder_genesis_ids, BlockBuilder,
+    DenunciationBuilder, EndorsementBuilder, OperationBuilder, DEFAULT_THREAD_COUNT,
+};
+pub use scenario::BlockGraph;
+
 use massa_models::{
-    address::Address,
-    amount::Amount,
-    block::{Block, BlockSerializer, SecureShareBlock},
-    block_header::{BlockHeader, BlockHeaderSerializer},
-    block_id::BlockId,
-    denunciation::Denunciation,
-    endorsement::{Endorsement, EndorsementSerializer, SecureShareEndorsement},
-    operation::{
-        compute_operations_hash, Operation, OperationIdSerializer, OperationSerializer,
-        OperationType, SecureShareOperation,
-    },
-    secure_share::SecureShareContent,
-    slot::Slot,
+    block::SecureShareBlock, denunciation::Denunciation, endorsement::SecureShareEndorsement,
+    operation::SecureShareOperation, slot::Slot,
 };
 use massa_signature::KeyPair;
 
@@ -39,7 +36,6 @@ pub trait TestUniverse {
             .try_init();
     }
 
-    // TODO: Create a block builder
     fn create_block(
         keypair: &KeyPair,
         slot: Slot,
@@ -47,59 +43,21 @@ pub trait TestUniverse {
         endorsements: Vec<SecureShareEndorsement>,
         denunciations: Vec<Denunciation>,
     ) -> SecureShareBlock {
-        let op_ids = operations.iter().map(|op| op.id).collect::<Vec<_>>();
-        let operation_merkle_root = compute_operations_hash(&op_ids, &OperationIdSerializer::new());
-        let header = BlockHeader::new_verifiable(
-            BlockHeader {
-                current_version: 0,
-                announced_version: None,
-                slot,
-                parents: vec![
-                    BlockId::generate_from_hash(Hash::compute_from("Genesis 0".as_bytes())),
-                    BlockId::generate_from_hash(Hash::compute_from("Genesis 1".as_bytes())),
-                ],
-                operation_merkle_root,
-                endorsements,
-                denunciations,
-            },
-            BlockHeaderSerializer::new(),
-            keypair,
-        )
-        .unwrap();
-
-        Block::new_verifiable(
-            Block {
-                header,
-                operations: op_ids,
-            },
-            BlockSerializer::new(),
-            keypair,
-        )
-        .unwrap()
+        BlockBuilder::new(keypair, slot)
+            .operations(operations)
+            .endorsements(endorsements)
+            .denunciations(denunciations)
+            .build()
     }
 
     fn create_operation(keypair: &KeyPair, expire_period: u64) -> SecureShareOperation {
-        let recv_keypair = KeyPair::generate(0).unwrap();
-
-        let op = OperationType::Transaction {
-            recipient_address: Address::from_public_key(&recv_keypair.get_public_key()),
-            amount: Amount::default(),
-        };
-        let content = Operation {
-            fee: Amount::default(),
-            op,
-            expire_period,
-        };
-        Operation::new_verifiable(content, OperationSerializer::new(), keypair).unwrap()
+        OperationBuilder::new(keypair)
+            .expire_period(expire_period)
+            .build()
     }
 
     fn create_endorsement(creator: &KeyPair, slot: Slot) -> SecureShareEndorsement {
-        let content = Endorsement {
-            slot,
-            index: 0,
-            endorsed_block: BlockId::generate_from_hash(Hash::compute_from("Genesis 1".as_bytes())),
-        };
-        Endorsement::new_verifiable(content, EndorsementSerializer::new(), creator).unwrap()
+        EndorsementBuilder::new(creator, slot).build()
     }
 }
 
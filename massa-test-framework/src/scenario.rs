@@ -0,0 +1,215 @@
+//! Small DSL describing a graph of named blocks across threads.
+//!
+//! The genesis blocks are the real ones of the genesis key, named `g0`, `g1`, ...
+//! Every block is built and signed when it is declared, so that it can be used as a parent
+//! by the following declarations:
+//! ```ignore
+//! let graph = BlockGraph::new(&genesis_key, 2)
+//!     .block("a", Slot::new(1, 0), ["g0", "g1"])
+//!     .block("b", Slot::new(1, 1), ["g0", "g1"])
+//!     .block_with("c", Slot::new(2, 0), ["a", "b"], |b| b.operations(vec![op]))
+//!     .fill_periods(3, 5);
+//! for block in graph.blocks() {
+//!     // feed the consensus, protocol or execution universe
+//! }
+//! ```
+
+use std::collections::HashMap;
+
+use massa_models::{block::SecureShareBlock, block_id::BlockId, slot::Slot};
+use massa_signature::KeyPair;
+
+use crate::builders::{genesis_blocks, BlockBuilder};
+
+/// Graph of named blocks, in declaration order
+pub struct BlockGraph {
+    thread_count: u8,
+    creator: KeyPair,
+    genesis: Vec<SecureShareBlock>,
+    blocks: Vec<(String, SecureShareBlock)>,
+    index: HashMap<String, BlockId>,
+}
+
+impl BlockGraph {
+    /// Starts a graph from the genesis blocks of `genesis_key` at period 0.
+    /// The blocks are produced by the genesis key unless another creator is set.
+    pub fn new(genesis_key: &KeyPair, thread_count: u8) -> Self {
+        let genesis = genesis_blocks(genesis_key, thread_count, 0);
+        let index = genesis
+            .iter()
+            .enumerate()
+            .map(|(thread, block)| (format!("g{}", thread), block.id))
+            .collect();
+        Self {
+            thread_count,
+            creator: genesis_key.clone(),
+            genesis,
+            blocks: Vec::new(),
+            index,
+        }
+    }
+
+    /// Sets the creator of the blocks declared afterwards
+    pub fn creator(mut self, creator: &KeyPair) -> Self {
+        self.creator = creator.clone();
+        self
+    }
+
+    /// Declares a block at `slot` with the named parents, one per thread
+    pub fn block<'a>(
+        self,
+        name: &str,
+        slot: Slot,
+        parents: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        self.block_with(name, slot, parents, |builder| builder)
+    }
+
+    /// Declares a block at `slot` with the named parents, customized by `customize`
+    /// (operations, endorsements, denunciations, creator...)
+    pub fn block_with<'a>(
+        mut self,
+        name: &str,
+        slot: Slot,
+        parents: impl IntoIterator<Item = &'a str>,
+        customize: impl FnOnce(BlockBuilder) -> BlockBuilder,
+    ) -> Self {
+        assert!(
+            !self.index.contains_key(name),
+            "block {} declared twice",
+            name
+        );
+        let parents: Vec<BlockId> = parents.into_iter().map(|parent| self.id(parent)).collect();
+        assert_eq!(
+            parents.len(),
+            self.thread_count as usize,
+            "block {} must have one parent per thread",
+            name
+        );
+        let block = customize(
+            BlockBuilder::new(&self.creator, slot)
+                .thread_count(self.thread_count)
+                .parents(parents),
+        )
+        .build();
+        self.index.insert(name.to_string(), block.id);
+        self.blocks.push((name.to_string(), block));
+        self
+    }
+
+    /// Declares a complete chain from period `from` to period `to` included:
+    /// the block of each slot has for parents the latest blocks of every thread.
+    /// The blocks are named after their slot, see `slot_name`.
+    pub fn fill_periods(mut self, from: u64, to: u64) -> Self {
+        let mut latest: Vec<String> = (0..self.thread_count)
+            .map(|thread| self.latest_name(thread))
+            .collect();
+        for period in from..=to {
+            for thread in 0..self.thread_count {
+                let slot = Slot::new(period, thread);
+                let name = Self::slot_name(&slot);
+                let parents = latest.clone();
+                self = self.block(&name, slot, parents.iter().map(String::as_str));
+                latest[thread as usize] = name;
+            }
+        }
+        self
+    }
+
+    /// Name of the block of a slot declared by `fill_periods`: `t{thread}p{period}`
+    pub fn slot_name(slot: &Slot) -> String {
+        format!("t{}p{}", slot.thread, slot.period)
+    }
+
+    /// Name of the latest block declared in a thread, or of its genesis block
+    fn latest_name(&self, thread: u8) -> String {
+        self.blocks
+            .iter()
+            .rev()
+            .find(|(_, block)| block.content.header.content.slot.thread == thread)
+            .map(|(name, _)| name.clone())
+            .unwrap_or_else(|| format!("g{}", thread))
+    }
+
+    /// Returns the id of a named block
+    pub fn id(&self, name: &str) -> BlockId {
+        *self
+            .index
+            .get(name)
+            .unwrap_or_else(|| panic!("unknown block {}", name))
+    }
+
+    /// Returns a named block, genesis blocks included
+    pub fn get(&self, name: &str) -> &SecureShareBlock {
+        let id = self.id(name);
+        self.genesis
+            .iter()
+            .chain(self.blocks.iter().map(|(_, block)| block))
+            .find(|block| block.id == id)
+            .expect("indexed block not found")
+    }
+
+    /// Returns the ids of the genesis blocks
+    pub fn genesis_ids(&self) -> Vec<BlockId> {
+        self.genesis.iter().map(|block| block.id).collect()
+    }
+
+    /// Returns the declared blocks (genesis excluded) in declaration order
+    pub fn blocks(&self) -> impl Iterator<Item = &SecureShareBlock> {
+        self.blocks.iter().map(|(_, block)| block)
+    }
+
+    /// Returns the declared blocks with the given names, in the given order
+    pub fn select<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> Vec<SecureShareBlock> {
+        names
+            .into_iter()
+            .map(|name| self.get(name).clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_periods() {
+        let genesis_key = KeyPair::generate(0).unwrap();
+        let graph = BlockGraph::new(&genesis_key, 2)
+            .block("a", Slot::new(1, 0), ["g0", "g1"])
+            .fill_periods(2, 3);
+        assert_eq!(graph.blocks().count(), 5);
+        assert_eq!(
+            graph.get("t0p2").content.header.content.parents,
+            vec![graph.id("a"), graph.id("g1")]
+        );
+        assert_eq!(
+            graph.get("t1p2").content.header.content.parents,
+            vec![graph.id("t0p2"), graph.id("g1")]
+        );
+        assert_eq!(
+            graph.get("t0p3").content.header.content.parents,
+            vec![graph.id("t0p2"), graph.id("t1p2")]
+        );
+        assert_eq!(BlockGraph::slot_name(&Slot::new(3, 1)), "t1p3");
+        for block in graph.blocks() {
+            block.verify_signature().unwrap();
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "block b must have one parent per thread")]
+    fn test_wrong_parent_count() {
+        let genesis_key = KeyPair::generate(0).unwrap();
+        BlockGraph::new(&genesis_key, 2).block("b", Slot::new(1, 0), ["g0"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "block a declared twice")]
+    fn test_duplicate_name() {
+        let genesis_key = KeyPair::generate(0).unwrap();
+        BlockGraph::new(&genesis_key, 2)
+            .block("a", Slot::new(1, 0), ["g0", "g1"])
+            .block("a", Slot::new(1, 1), ["g0", "g1"]);
+    }
+}
@@ -0,0 +1,325 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Sources of the current time, injected into the workers so that their
+//! time-dependent logic can be driven by tests
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{at, bounded, never, Receiver, Sender};
+
+use crate::MassaTime;
+
+/// Source of the current time
+pub trait Clock: Send + Sync {
+    /// Current time
+    fn now(&self) -> MassaTime;
+
+    /// Returns a receiver getting a message once the clock reads `deadline` or later.
+    /// Workers select on it instead of computing an `Instant` from a timestamp,
+    /// so that they follow the clock they are given.
+    fn after(&self, deadline: MassaTime) -> Receiver<Instant>;
+
+    /// Moves the clock forward, if it can be moved.
+    ///
+    /// # Returns
+    /// Whether the clock moved: the system clock never does
+    fn fast_forward(&self, _duration: MassaTime) -> bool {
+        false
+    }
+}
+
+/// Clock following the system time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> MassaTime {
+        MassaTime::now()
+    }
+
+    fn after(&self, deadline: MassaTime) -> Receiver<Instant> {
+        match deadline.estimate_instant() {
+            Ok(instant) => at(instant),
+            Err(_) => never(),
+        }
+    }
+}
+
+/// Clock that only moves when it is told to.
+/// Moving it wakes up the waiters whose deadline is reached.
+///
+/// ```
+/// # use massa_time::*;
+/// let clock = ManualClock::new(MassaTime::from_millis(1000));
+/// let timer = clock.after(MassaTime::from_millis(1200));
+/// clock.advance(MassaTime::from_millis(100));
+/// assert!(timer.try_recv().is_err());
+/// clock.advance(MassaTime::from_millis(400));
+/// assert!(timer.try_recv().is_ok());
+/// assert_eq!(clock.now(), MassaTime::from_millis(1500));
+/// clock.set(MassaTime::from_millis(42));
+/// assert_eq!(clock.now(), MassaTime::from_millis(42));
+/// ```
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now: AtomicU64,
+    /// waiters that have not reached their deadline yet
+    timers: Mutex<Vec<(MassaTime, Sender<Instant>)>>,
+}
+
+impl ManualClock {
+    /// Creates a clock stopped at `start`
+    pub fn new(start: MassaTime) -> Self {
+        Self {
+            now: AtomicU64::new(start.as_millis()),
+            timers: Default::default(),
+        }
+    }
+
+    /// Moves the clock forward
+    pub fn advance(&self, duration: MassaTime) {
+        self.now.fetch_add(duration.as_millis(), Ordering::SeqCst);
+        self.fire_timers();
+    }
+
+    /// Sets the time of the clock
+    pub fn set(&self, now: MassaTime) {
+        self.now.store(now.as_millis(), Ordering::SeqCst);
+        self.fire_timers();
+    }
+
+    fn fire_timers(&self) {
+        let now = self.now();
+        fire_reached(
+            &mut self.timers.lock().expect("manual clock timers poisoned"),
+            now,
+        );
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> MassaTime {
+        MassaTime::from_millis(self.now.load(Ordering::SeqCst))
+    }
+
+    fn after(&self, deadline: MassaTime) -> Receiver<Instant> {
+        let (sender, receiver) = bounded(1);
+        // the time is read under the lock so that a concurrent move cannot be missed
+        let mut timers = self.timers.lock().expect("manual clock timers poisoned");
+        if self.now() >= deadline {
+            let _ = sender.try_send(Instant::now());
+        } else {
+            timers.push((deadline, sender));
+        }
+        receiver
+    }
+
+    fn fast_forward(&self, duration: MassaTime) -> bool {
+        self.advance(duration);
+        true
+    }
+}
+
+/// Wakes up and removes the waiters whose deadline is reached
+fn fire_reached(timers: &mut Vec<(MassaTime, Sender<Instant>)>, now: MassaTime) {
+    timers.retain(|(deadline, sender)| {
+        if *deadline <= now {
+            let _ = sender.try_send(Instant::now());
+            false
+        } else {
+            true
+        }
+    });
+}
+
+/// Clock following the system time shifted by an offset that can only grow.
+/// It lets a local development network skip the time between its slots:
+/// a node using it is out of sync with any other network.
+///
+/// ```
+/// # use massa_time::*;
+/// let clock = OffsetClock::new();
+/// let before = clock.now();
+/// let timer = clock.after(before.saturating_add(MassaTime::from_millis(60000)));
+/// assert!(clock.fast_forward(MassaTime::from_millis(60000)));
+/// assert!(clock.now() >= before.saturating_add(MassaTime::from_millis(60000)));
+/// assert!(timer.try_recv().is_ok());
+/// ```
+#[derive(Debug)]
+pub struct OffsetClock {
+    state: Arc<OffsetClockState>,
+}
+
+#[derive(Debug, Default)]
+struct OffsetClockState {
+    /// milliseconds added to the system time
+    offset: AtomicU64,
+    /// waiters that have not reached their deadline yet
+    timers: Mutex<Vec<(MassaTime, Sender<Instant>)>>,
+    /// notified when the timers or the offset change
+    changed: Condvar,
+    stopped: AtomicBool,
+}
+
+impl OffsetClockState {
+    fn now(&self) -> MassaTime {
+        MassaTime::now().saturating_add(MassaTime::from_millis(self.offset.load(Ordering::SeqCst)))
+    }
+}
+
+impl OffsetClock {
+    /// Creates a clock following the system time, and the thread waking up its waiters
+    /// when the system time reaches their deadline
+    pub fn new() -> Self {
+        let state = Arc::new(OffsetClockState::default());
+        std::thread::Builder::new()
+            .name("offset-clock".to_string())
+            .spawn({
+                let state = state.clone();
+                move || {
+                    let mut timers = state.timers.lock().expect("offset clock timers poisoned");
+                    while !state.stopped.load(Ordering::SeqCst) {
+                        fire_reached(&mut timers, state.now());
+                        let wait = timers
+                            .iter()
+                            .map(|(deadline, _)| *deadline)
+                            .min()
+                            .map_or(Duration::from_secs(60), |deadline| {
+                                deadline.saturating_sub(state.now()).to_duration()
+                            });
+                        timers = state
+                            .changed
+                            .wait_timeout(timers, wait)
+                            .expect("offset clock timers poisoned")
+                            .0;
+                    }
+                }
+            })
+            .expect("OS failed to start offset clock thread");
+        Self { state }
+    }
+}
+
+impl Default for OffsetClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for OffsetClock {
+    fn drop(&mut self) {
+        self.state.stopped.store(true, Ordering::SeqCst);
+        // taken so that the thread cannot miss the notification
+        let _timers = self.state.timers.lock();
+        self.state.changed.notify_all();
+    }
+}
+
+impl Clock for OffsetClock {
+    fn now(&self) -> MassaTime {
+        self.state.now()
+    }
+
+    fn after(&self, deadline: MassaTime) -> Receiver<Instant> {
+        let (sender, receiver) = bounded(1);
+        let mut timers = self
+            .state
+            .timers
+            .lock()
+            .expect("offset clock timers poisoned");
+        if self.now() >= deadline {
+            let _ = sender.try_send(Instant::now());
+        } else {
+            timers.push((deadline, sender));
+            // the thread waits for the earliest deadline
+            self.state.changed.notify_all();
+        }
+        receiver
+    }
+
+    fn fast_forward(&self, duration: MassaTime) -> bool {
+        let mut timers = self
+            .state
+            .timers
+            .lock()
+            .expect("offset clock timers poisoned");
+        self.state
+            .offset
+            .fetch_add(duration.as_millis(), Ordering::SeqCst);
+        fire_reached(&mut timers, self.now());
+        self.state.changed.notify_all();
+        true
+    }
+}
+
+/// Clock shared by the components of a node, the system clock by default.
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use massa_time::*;
+/// let manual = Arc::new(ManualClock::new(MassaTime::from_millis(1000)));
+/// let clock = SharedClock::new(manual.clone());
+/// let worker_clock = clock.clone();
+/// manual.advance(MassaTime::from_millis(1000));
+/// assert_eq!(worker_clock.now(), MassaTime::from_millis(2000));
+/// ```
+#[derive(Clone)]
+pub struct SharedClock(Arc<dyn Clock>);
+
+impl SharedClock {
+    /// Shares a clock
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self(clock)
+    }
+
+    /// Shares the system clock
+    pub fn system() -> Self {
+        Self(Arc::new(SystemClock))
+    }
+
+    /// Current time of the shared clock
+    pub fn now(&self) -> MassaTime {
+        self.0.now()
+    }
+
+    /// Returns a receiver getting a message once the shared clock reads `deadline` or later
+    pub fn after(&self, deadline: MassaTime) -> Receiver<Instant> {
+        self.0.after(deadline)
+    }
+
+    /// Moves the shared clock forward, if it can be moved.
+    ///
+    /// # Returns
+    /// Whether the clock moved
+    pub fn fast_forward(&self, duration: MassaTime) -> bool {
+        self.0.fast_forward(duration)
+    }
+
+    /// Blocks until the shared clock reads `deadline` or later
+    pub fn sleep_until(&self, deadline: MassaTime) {
+        let _ = self.after(deadline).recv();
+    }
+
+    /// Real instant at which the shared clock would read `time` if it followed the real time.
+    /// Only for waits that cannot select on `SharedClock::after`: the caller must check the
+    /// clock again when woken up.
+    pub fn estimate_instant(&self, time: MassaTime) -> Instant {
+        let now = Instant::now();
+        now.checked_add(time.saturating_sub(self.now()).to_duration())
+            .unwrap_or(now)
+    }
+}
+
+impl Default for SharedClock {
+    fn default() -> Self {
+        Self::system()
+    }
+}
+
+impl fmt::Debug for SharedClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedClock").field(&self.now()).finish()
+    }
+}
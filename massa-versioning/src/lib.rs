@@ -63,6 +63,13 @@
 //!
 //! Unit tests in versioning_factory.rs shows a basic but realistic implementation of a AddressFactory (impl the Factory trait)
 //!
+//! # MIP list file and simulator
+//!
+//! Instead of the compiled-in `get_mip_list`, a node can load its MIP list from a JSON file
+//! (see `mips::load_mip_list`), to rehearse upgrades on a test network.
+//! `mips_simulator` replays a deployment offline (`cargo xtask simulate_mips <file>`) from a MIP list
+//! and the percentage of blocks announcing each version over time, and lists the state transitions.
+//!
 //! # MipStore and Final state hash
 //!
 //! MipStore is written on disk after each block finalization. Writes are done in two separate column:
@@ -76,6 +83,7 @@ pub mod address_factory;
 pub mod grpc_mapping;
 pub mod keypair_factory;
 pub mod mips;
+pub mod mips_simulator;
 pub mod versioning;
 pub mod versioning_factory;
 pub mod versioning_ser_der;
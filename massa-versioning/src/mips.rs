@@ -1,10 +1,10 @@
-#[allow(unused_imports)]
 use std::collections::BTreeMap;
+use std::path::Path;
 
-#[allow(unused_imports)]
 use massa_time::MassaTime;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-#[allow(unused_imports)]
 use crate::versioning::{MipComponent, MipInfo, MipState};
 
 pub fn get_mip_list() -> [(MipInfo, MipState); 0] {
@@ -30,3 +30,116 @@ pub fn get_mip_list() -> [(MipInfo, MipState); 0] {
     #[allow(clippy::let_and_return)]
     mip_list
 }
+
+/// MIP definition as written in a MIP list file
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MipDefinition {
+    /// MIP name or descriptive name
+    pub name: String,
+    /// Network version to announce in block headers
+    pub version: u32,
+    /// Components concerned by this MIP, and the associated component version
+    pub components: BTreeMap<MipComponent, u32>,
+    /// timestamp (in milliseconds) at which the version can be announced
+    pub start: MassaTime,
+    /// timestamp (in milliseconds) at which the deployment is considered failed
+    pub timeout: MassaTime,
+    /// duration (in milliseconds) between LockedIn and Active
+    pub activation_delay: MassaTime,
+    /// timestamp (in milliseconds) of the initial Defined state, 0 if not set
+    #[serde(default)]
+    pub defined: Option<MassaTime>,
+}
+
+impl From<MipDefinition> for (MipInfo, MipState) {
+    fn from(value: MipDefinition) -> Self {
+        (
+            MipInfo {
+                name: value.name,
+                version: value.version,
+                components: value.components,
+                start: value.start,
+                timeout: value.timeout,
+                activation_delay: value.activation_delay,
+            },
+            MipState::new(value.defined.unwrap_or_else(|| MassaTime::from_millis(0))),
+        )
+    }
+}
+
+/// Error returned by `load_mip_list`
+#[derive(Error, Debug)]
+pub enum MipListLoadError {
+    #[error("error while reading MIP list file {0}: {1}")]
+    Read(String, std::io::Error),
+    #[error("error while parsing MIP list file {0}: {1}")]
+    Parse(String, serde_json::Error),
+}
+
+/// Parse a JSON list of MIP definitions
+pub fn parse_mip_list(content: &str) -> Result<Vec<(MipInfo, MipState)>, serde_json::Error> {
+    let definitions: Vec<MipDefinition> = serde_json::from_str(content)?;
+    Ok(definitions.into_iter().map(Into::into).collect())
+}
+
+/// Load a JSON list of MIP definitions from a file, to use instead of `get_mip_list`.
+///
+/// The list is not checked here: the resulting `MipStore` creation fails if the MIPs overlap
+/// or are not consistent.
+pub fn load_mip_list(path: &Path) -> Result<Vec<(MipInfo, MipState)>, MipListLoadError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|err| MipListLoadError::Read(path.display().to_string(), err))?;
+    parse_mip_list(&content).map_err(|err| MipListLoadError::Parse(path.display().to_string(), err))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use num::rational::Ratio;
+
+    use crate::versioning::{MipStatsConfig, MipStore};
+
+    #[test]
+    fn test_parse_mip_list() {
+        let content = r#"[
+            {
+                "name": "MIP-0001",
+                "version": 1,
+                "components": {"Address": 1, "KeyPair": 1},
+                "start": 2000,
+                "timeout": 5000,
+                "activation_delay": 100
+            },
+            {
+                "name": "MIP-0002",
+                "version": 2,
+                "components": {"Block": 1},
+                "start": 6000,
+                "timeout": 9000,
+                "activation_delay": 100,
+                "defined": 1000
+            }
+        ]"#;
+        let mip_list = parse_mip_list(content).unwrap();
+        assert_eq!(mip_list.len(), 2);
+        assert_eq!(mip_list[0].0.name, "MIP-0001");
+        assert_eq!(
+            mip_list[0].0.components,
+            BTreeMap::from([(MipComponent::Address, 1), (MipComponent::KeyPair, 1)])
+        );
+        assert_eq!(mip_list[1].1, MipState::new(MassaTime::from_millis(1000)));
+
+        let mip_stats_config = MipStatsConfig {
+            block_count_considered: 10,
+            warn_announced_version_ratio: Ratio::new(30, 100),
+        };
+        let mip_store = MipStore::try_from((mip_list, mip_stats_config)).unwrap();
+        assert_eq!(mip_store.get_mip_status().len(), 2);
+
+        // unknown components are refused
+        let content = r#"[{"name": "MIP-0003", "version": 3, "components": {"Unknown": 1},
+            "start": 2000, "timeout": 5000, "activation_delay": 100}]"#;
+        assert!(parse_mip_list(content).is_err());
+    }
+}
@@ -0,0 +1,312 @@
+//! Offline simulation of MIP deployments.
+//!
+//! Given a MIP list and, for successive ranges of periods, the percentage of blocks announcing
+//! each network version, the simulator feeds a `MipStore` slot by slot (like the execution module
+//! does on block finalization) and reports every state transition with its slot and timestamp.
+//! As it drives a real `MipStore`, its results follow the live state machine rules.
+//!
+//! Example of simulation file:
+//! ```json
+//! {
+//!     "genesis_timestamp": 0,
+//!     "t0": 16000,
+//!     "thread_count": 32,
+//!     "block_count_considered": 1000,
+//!     "mips": [
+//!         {
+//!             "name": "MIP-0001",
+//!             "version": 1,
+//!             "components": {"Address": 1},
+//!             "start": 160000,
+//!             "timeout": 1600000,
+//!             "activation_delay": 160000
+//!         }
+//!     ],
+//!     "announcements": [
+//!         {"periods": 20, "versions": {}},
+//!         {"periods": 40, "versions": {"1": 80}}
+//!     ]
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use massa_models::config::{MIP_STORE_STATS_BLOCK_CONSIDERED, T0, THREAD_COUNT};
+use massa_models::error::ModelsError;
+use massa_models::slot::Slot;
+use massa_models::timeslots::get_block_slot_timestamp;
+use massa_time::MassaTime;
+use num::rational::Ratio;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::mips::MipDefinition;
+use crate::versioning::{ComponentStateTypeId, MipInfo, MipStatsConfig, MipStore, UpdateWithError};
+
+fn default_t0() -> MassaTime {
+    T0
+}
+
+fn default_thread_count() -> u8 {
+    THREAD_COUNT
+}
+
+fn default_block_count_considered() -> usize {
+    MIP_STORE_STATS_BLOCK_CONSIDERED
+}
+
+/// Percentage of blocks announcing each network version during a range of periods
+#[derive(Clone, Debug, Deserialize)]
+pub struct AnnouncementStep {
+    /// Number of periods of this step
+    pub periods: u64,
+    /// Percentage (0 to 100) of the blocks announcing each network version.
+    /// The other blocks announce nothing.
+    pub versions: BTreeMap<u32, u64>,
+}
+
+/// Input of a MIP deployment simulation
+#[derive(Clone, Debug, Deserialize)]
+pub struct MipSimulationConfig {
+    /// Genesis timestamp of the simulated network
+    pub genesis_timestamp: MassaTime,
+    /// Time between two periods of the same thread
+    #[serde(default = "default_t0")]
+    pub t0: MassaTime,
+    /// Number of threads
+    #[serde(default = "default_thread_count")]
+    pub thread_count: u8,
+    /// Number of blocks considered to compute the vote ratio of a version
+    #[serde(default = "default_block_count_considered")]
+    pub block_count_considered: usize,
+    /// MIPs to deploy
+    pub mips: Vec<MipDefinition>,
+    /// Successive announcement steps, the first one starting at period 1
+    pub announcements: Vec<AnnouncementStep>,
+}
+
+/// A state transition of a MIP
+#[derive(Clone, Debug, PartialEq)]
+pub struct MipTransition {
+    /// MIP that changed state
+    pub mip_info: MipInfo,
+    /// Slot whose finalization triggered the transition
+    pub slot: Slot,
+    /// Timestamp of the slot
+    pub timestamp: MassaTime,
+    /// Previous state
+    pub from: ComponentStateTypeId,
+    /// New state
+    pub to: ComponentStateTypeId,
+}
+
+impl fmt::Display for MipTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}: {} (version {}) {:?} -> {:?}",
+            self.timestamp.format_instant(),
+            self.slot,
+            self.mip_info.name,
+            self.mip_info.version,
+            self.from,
+            self.to
+        )
+    }
+}
+
+/// Result of a MIP deployment simulation
+#[derive(Debug)]
+pub struct MipSimulation {
+    /// State transitions, in chronological order
+    pub transitions: Vec<MipTransition>,
+    /// MIP store at the end of the simulation
+    pub store: MipStore,
+}
+
+/// Error returned by `simulate_mips`
+#[derive(Error, Debug)]
+pub enum MipSimulationError {
+    #[error("invalid MIP list: {0}")]
+    MipList(#[from] UpdateWithError),
+    #[error("announcement step {0}: the sum of the percentages is {1}, it should be at most 100")]
+    InvalidPercentages(usize, u64),
+    #[error("slot timestamp error: {0}")]
+    Timestamp(#[from] ModelsError),
+}
+
+/// Run a MIP deployment simulation
+pub fn simulate_mips(config: &MipSimulationConfig) -> Result<MipSimulation, MipSimulationError> {
+    let mip_list: Vec<_> = config.mips.iter().cloned().map(Into::into).collect();
+    let mut store = MipStore::try_from((
+        mip_list,
+        MipStatsConfig {
+            block_count_considered: config.block_count_considered,
+            // never warn about unknown versions
+            warn_announced_version_ratio: Ratio::new(1, 1),
+        },
+    ))?;
+
+    let mut transitions = Vec::new();
+    let mut states = store.get_mip_status();
+    let mut period = 1;
+    for (step_index, step) in config.announcements.iter().enumerate() {
+        let total: u64 = step.versions.values().sum();
+        if total > 100 {
+            return Err(MipSimulationError::InvalidPercentages(step_index, total));
+        }
+        // Each block adds the percentage of each version to its credit, and announces the
+        // version with the highest credit once it reaches 100. This spreads the announcements
+        // evenly and deterministically.
+        let mut credits: BTreeMap<u32, u64> = BTreeMap::new();
+        for _ in 0..step.periods {
+            for thread in 0..config.thread_count {
+                for (version, percentage) in step.versions.iter() {
+                    *credits.entry(*version).or_default() += percentage;
+                }
+                let announced = credits
+                    .iter()
+                    .filter(|(_, credit)| **credit >= 100)
+                    .max_by_key(|(_, credit)| **credit)
+                    .map(|(version, _)| *version);
+                if let Some(version) = announced {
+                    credits.entry(version).and_modify(|credit| *credit -= 100);
+                }
+
+                let slot = Slot::new(period, thread);
+                let timestamp = get_block_slot_timestamp(
+                    config.thread_count,
+                    config.t0,
+                    config.genesis_timestamp,
+                    slot,
+                )?;
+                let current = store.get_network_version_current();
+                store.update_network_version_stats(timestamp, Some((current, announced)));
+
+                let new_states = store.get_mip_status();
+                for (mip_info, to) in new_states.iter() {
+                    match states.get(mip_info) {
+                        Some(from) if from != to => transitions.push(MipTransition {
+                            mip_info: mip_info.clone(),
+                            slot,
+                            timestamp,
+                            from: from.clone(),
+                            to: to.clone(),
+                        }),
+                        _ => {}
+                    }
+                }
+                states = new_states;
+            }
+            period += 1;
+        }
+    }
+
+    Ok(MipSimulation { transitions, store })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::versioning::ComponentStateTypeId::*;
+
+    fn simulation_config(activation_delay: u64, announcements: &str) -> MipSimulationConfig {
+        serde_json::from_str(&format!(
+            r#"{{
+                "genesis_timestamp": 0,
+                "t0": 1000,
+                "thread_count": 4,
+                "block_count_considered": 40,
+                "mips": [
+                    {{
+                        "name": "MIP-0001",
+                        "version": 1,
+                        "components": {{"Address": 1}},
+                        "start": 10000,
+                        "timeout": 60000,
+                        "activation_delay": {}
+                    }}
+                ],
+                "announcements": {}
+            }}"#,
+            activation_delay, announcements
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_simulate_mips_activation() {
+        let config = simulation_config(
+            5000,
+            r#"[{"periods": 20, "versions": {"1": 20}}, {"periods": 40, "versions": {"1": 90}}]"#,
+        );
+        let simulation = simulate_mips(&config).unwrap();
+        let states: Vec<(ComponentStateTypeId, ComponentStateTypeId)> = simulation
+            .transitions
+            .iter()
+            .map(|transition| (transition.from.clone(), transition.to.clone()))
+            .collect();
+        assert_eq!(
+            states,
+            vec![(Defined, Started), (Started, LockedIn), (LockedIn, Active)]
+        );
+        // started at the first slot after the start timestamp
+        assert_eq!(simulation.transitions[0].slot, Slot::new(10, 0));
+        // locked in during the second step, activated after the activation delay
+        let locked_in_at = simulation.transitions[1].timestamp;
+        let active_at = simulation.transitions[2].timestamp;
+        assert!(locked_in_at > MassaTime::from_millis(20000));
+        assert!(active_at > locked_in_at.saturating_add(MassaTime::from_millis(5000)));
+        assert!(active_at <= locked_in_at.saturating_add(MassaTime::from_millis(5250)));
+
+        // the transitions agree with the state queries of the resulting store
+        let guard = simulation.store.0.read();
+        for transition in simulation.transitions.iter() {
+            let mip_info = &transition.mip_info;
+            let state = guard.store.get(mip_info).unwrap();
+            assert_eq!(
+                state
+                    .state_at(
+                        transition.timestamp,
+                        mip_info.start,
+                        mip_info.timeout,
+                        mip_info.activation_delay
+                    )
+                    .unwrap(),
+                transition.to
+            );
+        }
+        drop(guard);
+        assert_eq!(simulation.store.get_network_version_active_at(active_at), 1);
+        assert_eq!(
+            simulation
+                .store
+                .get_network_version_active_at(active_at.saturating_sub(MassaTime::from_millis(1))),
+            0
+        );
+    }
+
+    #[test]
+    fn test_simulate_mips_failure() {
+        let config = simulation_config(5000, r#"[{"periods": 70, "versions": {"1": 50}}]"#);
+        let simulation = simulate_mips(&config).unwrap();
+        let states: Vec<(ComponentStateTypeId, ComponentStateTypeId)> = simulation
+            .transitions
+            .iter()
+            .map(|transition| (transition.from.clone(), transition.to.clone()))
+            .collect();
+        assert_eq!(states, vec![(Defined, Started), (Started, Failed)]);
+        assert_eq!(
+            simulation.transitions[1].timestamp,
+            MassaTime::from_millis(60000)
+        );
+
+        let config = simulation_config(5000, r#"[{"periods": 1, "versions": {"1": 60, "2": 60}}]"#);
+        assert!(matches!(
+            simulate_mips(&config),
+            Err(MipSimulationError::InvalidPercentages(0, 120))
+        ));
+    }
+}
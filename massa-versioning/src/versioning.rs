@@ -9,6 +9,7 @@ use machine::{machine, transitions};
 use num::{rational::Ratio, Zero};
 use num_enum::{FromPrimitive, IntoPrimitive, TryFromPrimitive};
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tracing::{debug, warn};
 
@@ -35,7 +36,18 @@ use crate::versioning_ser_der::{
 /// Versioning component enum
 #[allow(missing_docs)]
 #[derive(
-    Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, FromPrimitive, IntoPrimitive, VariantCount,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    FromPrimitive,
+    IntoPrimitive,
+    VariantCount,
+    Serialize,
+    Deserialize,
 )]
 #[repr(u32)]
 pub enum MipComponent {
@@ -45,8 +57,11 @@ pub enum MipComponent {
     Block,
     VM,
     FinalStateHashKind,
+    AsyncMessageTrigger,
+    AsyncMessageCancellation,
     #[doc(hidden)]
     #[num_enum(default)]
+    #[serde(skip)]
     __Nonexhaustive,
 }
 
@@ -601,17 +616,7 @@ impl MipStore {
     /// return 0 is there is nothing to announce
     pub fn get_network_version_to_announce(&self) -> Option<u32> {
         let lock = self.0.read();
-        let store = lock.deref();
-        // Announce the latest versioning info in Started / LockedIn state
-        // Defined == Not yet ready to announce
-        // Active == current version
-        store.store.iter().rev().find_map(|(k, v)| {
-            matches!(
-                &v.state,
-                &ComponentState::Started(_) | &ComponentState::LockedIn(_)
-            )
-            .then_some(k.version)
-        })
+        lock.get_network_version_to_announce()
     }
 
     pub fn update_network_version_stats(
@@ -673,6 +678,20 @@ impl MipStore {
             .collect()
     }
 
+    /// Retrieve the vote status of each MIP: announcements in the stats window versus the
+    /// lock-in threshold, and the lock-in / activation timestamps.
+    ///
+    /// `slot_duration` is the time between two slots, used to project the lock-in timestamp
+    /// of the MIPs not locked in yet.
+    pub fn get_mip_vote_status(
+        &self,
+        now: MassaTime,
+        slot_duration: MassaTime,
+    ) -> Vec<MipVoteStatus> {
+        let guard = self.0.read();
+        guard.get_mip_vote_status(now, slot_duration)
+    }
+
     // Network restart
     pub fn is_consistent_with_shutdown_period(
         &self,
@@ -770,6 +789,42 @@ impl<const N: usize> TryFrom<([(MipInfo, MipState); N], MipStatsConfig)> for Mip
     }
 }
 
+impl TryFrom<(Vec<(MipInfo, MipState)>, MipStatsConfig)> for MipStore {
+    type Error = UpdateWithError;
+
+    fn try_from(
+        (value, cfg): (Vec<(MipInfo, MipState)>, MipStatsConfig),
+    ) -> Result<Self, Self::Error> {
+        MipStoreRaw::try_from((value, cfg)).map(|store_raw| Self(Arc::new(RwLock::new(store_raw))))
+    }
+}
+
+/// Vote status of a MIP, returned by `MipStore::get_mip_vote_status`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MipVoteStatus {
+    /// MIP info
+    pub mip_info: MipInfo,
+    /// Current state
+    pub state: ComponentStateTypeId,
+    /// Number of blocks announcing the MIP version in the stats window
+    pub announced_count: u64,
+    /// Number of announcements required in the stats window to lock in
+    pub required_count: u64,
+    /// Number of blocks currently in the stats window
+    pub window_length: usize,
+    /// Size of the stats window once full
+    pub block_count_considered: usize,
+    /// True if our node announces the MIP version in the blocks it produces
+    pub announced_by_node: bool,
+    /// Lock-in timestamp. For a MIP not locked in yet, this is a projection assuming that
+    /// all the next blocks announce the version, from its start time on for a Defined MIP
+    /// (None if that would be after the timeout).
+    pub lock_in_at: Option<MassaTime>,
+    /// Activation timestamp (the MIP becomes active at the first slot after it).
+    /// For a MIP not locked in yet, this is a projection based on `lock_in_at`.
+    pub activation_at: Option<MassaTime>,
+}
+
 /// Statistics in MipStoreRaw
 #[derive(Debug, Clone, PartialEq)]
 pub struct MipStatsConfig {
@@ -1115,6 +1170,107 @@ impl MipStoreRaw {
 
     // Query
 
+    /// Network version to announce: the latest MIP in Started / LockedIn state
+    fn get_network_version_to_announce(&self) -> Option<u32> {
+        // Defined == Not yet ready to announce
+        // Active == current version
+        self.store.iter().rev().find_map(|(k, v)| {
+            matches!(
+                &v.state,
+                &ComponentState::Started(_) | &ComponentState::LockedIn(_)
+            )
+            .then_some(k.version)
+        })
+    }
+
+    /// Number of announcements required in a full stats window to lock in
+    fn required_announcement_count(&self) -> u64 {
+        (VERSIONING_THRESHOLD_TRANSITION_ACCEPTED
+            * Ratio::from_integer(self.stats.config.block_count_considered as u64))
+        .ceil()
+        .to_integer()
+    }
+
+    /// Minimum number of new blocks, all announcing `version`, needed to reach `required_count`
+    /// announcements in the stats window
+    fn blocks_to_lock_in(&self, version: u32, required_count: u64) -> u64 {
+        let mut count = *self
+            .stats
+            .network_version_counters
+            .get(&version)
+            .unwrap_or(&0);
+        let mut window_length = self.stats.latest_announcements.len();
+        let mut oldest = self.stats.latest_announcements.iter();
+        let mut blocks = 0;
+        while count < required_count && blocks < self.stats.config.block_count_considered as u64 {
+            // once the window is full, each new block pushes the oldest announcement out
+            if window_length >= self.stats.config.block_count_considered {
+                if oldest.next() == Some(&version) {
+                    count = count.saturating_sub(1);
+                }
+            } else {
+                window_length += 1;
+            }
+            count += 1;
+            blocks += 1;
+        }
+        blocks
+    }
+
+    fn get_mip_vote_status(&self, now: MassaTime, slot_duration: MassaTime) -> Vec<MipVoteStatus> {
+        let required_count = self.required_announcement_count();
+        let to_announce = self.get_network_version_to_announce();
+        self.store
+            .iter()
+            .map(|(mi, ms)| {
+                let (lock_in_at, activation_at) = match ms.state {
+                    ComponentState::Defined(_) | ComponentState::Started(_) => {
+                        let lock_in_at = if matches!(ms.state, ComponentState::Defined(_)) {
+                            // the version is only announced from the start time on: the window
+                            // holds none of its announcements, they all come from new blocks
+                            now.max(mi.start)
+                                .saturating_add(slot_duration.saturating_mul(required_count))
+                        } else {
+                            let blocks = self.blocks_to_lock_in(mi.version, required_count);
+                            now.saturating_add(slot_duration.saturating_mul(blocks))
+                        };
+                        if lock_in_at < mi.timeout {
+                            (
+                                Some(lock_in_at),
+                                Some(lock_in_at.saturating_add(mi.activation_delay)),
+                            )
+                        } else {
+                            (None, None)
+                        }
+                    }
+                    ComponentState::LockedIn(LockedIn { at }) => (Some(at), ms.activation_at(mi)),
+                    ComponentState::Active(Active { at }) => (
+                        ms.history.iter().find_map(|(advance, state_id)| {
+                            (*state_id == ComponentStateTypeId::LockedIn).then_some(advance.now)
+                        }),
+                        Some(at),
+                    ),
+                    ComponentState::Failed(_) | ComponentState::Error => (None, None),
+                };
+                MipVoteStatus {
+                    mip_info: mi.clone(),
+                    state: ComponentStateTypeId::from(&ms.state),
+                    announced_count: *self
+                        .stats
+                        .network_version_counters
+                        .get(&mi.version)
+                        .unwrap_or(&0),
+                    required_count,
+                    window_length: self.stats.latest_announcements.len(),
+                    block_count_considered: self.stats.config.block_count_considered,
+                    announced_by_node: to_announce == Some(mi.version),
+                    lock_in_at,
+                    activation_at,
+                }
+            })
+            .collect()
+    }
+
     /// Get latest version at given timestamp (e.g. slot)
     fn get_latest_component_version_at(&self, component: &MipComponent, ts: MassaTime) -> u32 {
         let version = self
@@ -1555,6 +1711,16 @@ impl<const N: usize> TryFrom<([(MipInfo, MipState); N], MipStatsConfig)> for Mip
 
     fn try_from(
         (value, cfg): ([(MipInfo, MipState); N], MipStatsConfig),
+    ) -> Result<Self, Self::Error> {
+        MipStoreRaw::try_from((Vec::from(value), cfg))
+    }
+}
+
+impl TryFrom<(Vec<(MipInfo, MipState)>, MipStatsConfig)> for MipStoreRaw {
+    type Error = UpdateWithError;
+
+    fn try_from(
+        (value, cfg): (Vec<(MipInfo, MipState)>, MipStatsConfig),
     ) -> Result<Self, Self::Error> {
         // Build an empty store
         let mut store = Self {
@@ -1564,7 +1730,7 @@ impl<const N: usize> TryFrom<([(MipInfo, MipState); N], MipStatsConfig)> for Mip
 
         // Build another one with given value
         let other_store = Self {
-            store: BTreeMap::from(value),
+            store: BTreeMap::from_iter(value),
             stats: MipStoreStats::new(cfg),
         };
 
@@ -2734,4 +2900,117 @@ mod test {
         assert_eq!(mip_store.stats.network_version_counters.get(&1), Some(&1));
         assert_eq!(mip_store.stats.network_version_counters.get(&2), Some(&1));
     }
+
+    #[test]
+    fn test_mip_vote_status() {
+        let genesis_timestamp = MassaTime::from_millis(0);
+        let get_slot_ts =
+            |slot| get_block_slot_timestamp(THREAD_COUNT, T0, genesis_timestamp, slot).unwrap();
+        let slot_duration = T0.checked_div_u64(THREAD_COUNT as u64).unwrap();
+
+        let mip_stats_config = MipStatsConfig {
+            block_count_considered: 4,
+            warn_announced_version_ratio: Ratio::new_raw(30, 100),
+        };
+        let activation_delay = MassaTime::from_millis(100);
+        let mi_1 = MipInfo {
+            name: "MIP-0001".to_string(),
+            version: 1,
+            components: BTreeMap::from([(MipComponent::Address, 1)]),
+            start: MassaTime::from_millis(2),
+            timeout: MassaTime::from_millis(10_000_000),
+            activation_delay,
+        };
+        let ms_1 = advance_state_until(ComponentState::started(Ratio::zero()), &mi_1);
+        let mut mip_store = MipStore::try_from(([(mi_1.clone(), ms_1)], mip_stats_config)).unwrap();
+
+        // Empty window: 3 announcements out of 4 are needed, all from new blocks
+        let now = MassaTime::from_millis(1000);
+        let status = mip_store.get_mip_vote_status(now, slot_duration);
+        assert_eq!(status.len(), 1);
+        assert_eq!(status[0].state, ComponentStateTypeId::Started);
+        assert_eq!(status[0].announced_count, 0);
+        assert_eq!(status[0].required_count, 3);
+        assert_eq!(status[0].window_length, 0);
+        assert!(status[0].announced_by_node);
+        let lock_in_at = now.saturating_add(slot_duration.saturating_mul(3));
+        assert_eq!(status[0].lock_in_at, Some(lock_in_at));
+        assert_eq!(
+            status[0].activation_at,
+            Some(lock_in_at.saturating_add(activation_delay))
+        );
+
+        // Window [0, 1, 1, 0]: the next block announcing 1 pushes a 0 out and locks in
+        for (period, announced) in [None, Some(1), Some(1), None].into_iter().enumerate() {
+            mip_store.update_network_version_stats(
+                get_slot_ts(Slot::new(period as u64 + 1, 0)),
+                Some((0, announced)),
+            );
+        }
+        let status = mip_store.get_mip_vote_status(now, slot_duration);
+        assert_eq!(status[0].state, ComponentStateTypeId::Started);
+        assert_eq!(status[0].announced_count, 2);
+        assert_eq!(status[0].window_length, 4);
+        assert_eq!(
+            status[0].lock_in_at,
+            Some(now.saturating_add(slot_duration))
+        );
+
+        let locked_in_at = get_slot_ts(Slot::new(5, 0));
+        mip_store.update_network_version_stats(locked_in_at, Some((0, Some(1))));
+        let status = mip_store.get_mip_vote_status(now, slot_duration);
+        assert_eq!(status[0].state, ComponentStateTypeId::LockedIn);
+        assert_eq!(status[0].announced_count, 3);
+        assert_eq!(status[0].lock_in_at, Some(locked_in_at));
+        assert_eq!(
+            status[0].activation_at,
+            Some(locked_in_at.saturating_add(activation_delay))
+        );
+    }
+
+    #[test]
+    fn test_mip_vote_status_defined() {
+        let slot_duration = T0.checked_div_u64(THREAD_COUNT as u64).unwrap();
+        let mip_stats_config = MipStatsConfig {
+            block_count_considered: 4,
+            warn_announced_version_ratio: Ratio::new_raw(30, 100),
+        };
+        let activation_delay = MassaTime::from_millis(100);
+        let start = MassaTime::from_millis(10_000);
+        let mi_1 = MipInfo {
+            name: "MIP-0001".to_string(),
+            version: 1,
+            components: BTreeMap::from([(MipComponent::Address, 1)]),
+            start,
+            timeout: MassaTime::from_millis(10_000_000),
+            activation_delay,
+        };
+        let ms_1 = MipState::new(MassaTime::from_millis(0));
+        let mip_store = MipStore::try_from(([(mi_1.clone(), ms_1)], mip_stats_config)).unwrap();
+
+        // Before the start time: the 3 required announcements start at the start time
+        let status = mip_store.get_mip_vote_status(MassaTime::from_millis(1000), slot_duration);
+        assert_eq!(status[0].state, ComponentStateTypeId::Defined);
+        assert!(!status[0].announced_by_node);
+        let lock_in_at = start.saturating_add(slot_duration.saturating_mul(3));
+        assert_eq!(status[0].lock_in_at, Some(lock_in_at));
+        assert_eq!(
+            status[0].activation_at,
+            Some(lock_in_at.saturating_add(activation_delay))
+        );
+
+        // Start time passed but the state did not advance yet: the announcements start now
+        let now = start.saturating_add(MassaTime::from_millis(500));
+        let status = mip_store.get_mip_vote_status(now, slot_duration);
+        assert_eq!(
+            status[0].lock_in_at,
+            Some(now.saturating_add(slot_duration.saturating_mul(3)))
+        );
+
+        // The projected lock-in is after the timeout
+        let now = MassaTime::from_millis(10_000_000);
+        let status = mip_store.get_mip_vote_status(now, slot_duration);
+        assert_eq!(status[0].lock_in_at, None);
+        assert_eq!(status[0].activation_at, None);
+    }
 }
@@ -1,15 +1,23 @@
+mod simulate_mips;
 mod update_package_versions;
+use crate::simulate_mips::simulate_mips;
 use crate::update_package_versions::update_package_versions;
 use std::env;
 
 /// to use it task: cargo xtask <task_name>
 /// example: cargo xtask update_package_versions to update package versions
+/// example: cargo xtask simulate_mips simulation.json to simulate a MIP deployment
 fn main() {
     let task = env::args().nth(1);
 
     match task.as_deref() {
         // We can add more tasks here
         Some("update_package_versions") => update_package_versions(),
+        Some("simulate_mips") => simulate_mips(
+            &env::args()
+                .nth(2)
+                .expect("Missing simulation file path argument"),
+        ),
         _ => panic!("Unknown task"),
     }
 }
@@ -0,0 +1,22 @@
+use massa_versioning::mips_simulator::{simulate_mips as run_simulation, MipSimulationConfig};
+use std::fs::read_to_string;
+
+/// Replay a MIP deployment described in a JSON file and print the state transitions
+pub(crate) fn simulate_mips(path: &str) {
+    let content = read_to_string(path).expect("Failed to read simulation file");
+    let config: MipSimulationConfig =
+        serde_json::from_str(&content).expect("Failed to parse simulation file");
+    let simulation = run_simulation(&config).expect("Simulation failed");
+    if simulation.transitions.is_empty() {
+        println!("No state transition");
+    }
+    for transition in simulation.transitions.iter() {
+        println!("{}", transition);
+    }
+    for (mip_info, state) in simulation.store.get_mip_status() {
+        println!(
+            "Final state of {} (version {}): {:?}",
+            mip_info.name, mip_info.version, state
+        );
+    }
+}